@@ -0,0 +1,398 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Generates the source code which declares the exported functions and data
+// of a module, so that the host programs (which embed the VM) call the
+// exported functions by the declared indices instead of the magic numbers.
+//
+// The generated code is either a C header (macros) or a Rust module (constants).
+//
+// Only the exported (i.e., public and package-private) items of the
+// "function name" and "data name" sections are declared, with their public indices:
+//
+// - function public index = the amount of imported functions + internal index
+// - data public index = the amount of imported data + the amount of the data items
+//   in the preceding data sections (in the order of read-only, read-write and
+//   uninitialized) + internal index in section
+//
+// Note: the public indices are the ones within the module, the indices in
+// an application image are the same for the main module.
+
+use anc_isa::{DataSectionType, MemoryDataType, OperandDataType};
+
+use crate::{
+    entry::{ImageCommonEntry, TypeEntry},
+    entry_dump::{format_data_section_type, format_memory_data_type, format_operand_data_types},
+    public_index::PublicIndexSpace,
+};
+
+struct PublicFunction<'a> {
+    full_name: &'a str,
+    public_index: usize,
+    type_entry: &'a TypeEntry,
+}
+
+struct PublicData<'a> {
+    full_name: &'a str,
+    public_index: usize,
+    section_type: DataSectionType,
+    memory_data_type: MemoryDataType,
+}
+
+/// Generates a C header which declares the indices of the exported functions and data
+/// as macros, the signatures are written as comments.
+pub fn generate_c_header(image_common_entry: &ImageCommonEntry) -> String {
+    let guard_name = format!(
+        "ANC_MODULE_{}_H",
+        to_identifier(&image_common_entry.name).to_uppercase()
+    );
+
+    let mut lines: Vec<String> = vec![
+        format!(
+            "// Generated from module \"{}\" version {}.{}.{}, do not edit.",
+            image_common_entry.name,
+            image_common_entry.version.major,
+            image_common_entry.version.minor,
+            image_common_entry.version.patch
+        ),
+        format!("#ifndef {}", guard_name),
+        format!("#define {}", guard_name),
+    ];
+
+    let public_functions = collect_public_functions(image_common_entry);
+    if !public_functions.is_empty() {
+        lines.push("".to_owned());
+        lines.push("// Function public indices".to_owned());
+    }
+
+    for public_function in public_functions {
+        lines.push(format!(
+            "// {}: {} -> {}",
+            public_function.full_name,
+            format_operand_data_types(&public_function.type_entry.params),
+            format_operand_data_types(&public_function.type_entry.results),
+        ));
+        lines.push(format!(
+            "// {}",
+            format_c_signature(
+                &to_identifier(public_function.full_name),
+                public_function.type_entry
+            )
+        ));
+        lines.push(format!(
+            "#define {}_FUNCTION_INDEX {}",
+            to_identifier(public_function.full_name).to_uppercase(),
+            public_function.public_index
+        ));
+    }
+
+    let public_data = collect_public_data(image_common_entry);
+    if !public_data.is_empty() {
+        lines.push("".to_owned());
+        lines.push("// Data public indices".to_owned());
+    }
+
+    for public_data_item in public_data {
+        lines.push(format!(
+            "// {}: {}, {}",
+            public_data_item.full_name,
+            format_data_section_type(public_data_item.section_type),
+            format_memory_data_type(public_data_item.memory_data_type)
+        ));
+        lines.push(format!(
+            "#define {}_DATA_INDEX {}",
+            to_identifier(public_data_item.full_name).to_uppercase(),
+            public_data_item.public_index
+        ));
+    }
+
+    lines.push("".to_owned());
+    lines.push(format!("#endif // {}", guard_name));
+    lines.push("".to_owned());
+
+    lines.join("\n")
+}
+
+/// Generates a Rust module which declares the indices of the exported functions and data
+/// as constants, and the signatures of the functions as `OperandDataType` slices.
+pub fn generate_rust_bindings(image_common_entry: &ImageCommonEntry) -> String {
+    let mut lines: Vec<String> = vec![format!(
+        "// Generated from module \"{}\" version {}.{}.{}, do not edit.",
+        image_common_entry.name,
+        image_common_entry.version.major,
+        image_common_entry.version.minor,
+        image_common_entry.version.patch
+    )];
+
+    let public_functions = collect_public_functions(image_common_entry);
+    if !public_functions.is_empty() {
+        lines.push("".to_owned());
+        lines.push("use anc_isa::OperandDataType;".to_owned());
+    }
+
+    for public_function in public_functions {
+        let constant_name = to_identifier(public_function.full_name).to_uppercase();
+        lines.push("".to_owned());
+        lines.push(format!("// {}", public_function.full_name));
+        lines.push(format!(
+            "pub const {}_FUNCTION_INDEX: usize = {};",
+            constant_name, public_function.public_index
+        ));
+        lines.push(format!(
+            "pub const {}_PARAMS: &[OperandDataType] = {};",
+            constant_name,
+            format_rust_operand_data_types(&public_function.type_entry.params)
+        ));
+        lines.push(format!(
+            "pub const {}_RESULTS: &[OperandDataType] = {};",
+            constant_name,
+            format_rust_operand_data_types(&public_function.type_entry.results)
+        ));
+    }
+
+    for public_data_item in collect_public_data(image_common_entry) {
+        lines.push("".to_owned());
+        lines.push(format!(
+            "// {}: {}, {}",
+            public_data_item.full_name,
+            format_data_section_type(public_data_item.section_type),
+            format_memory_data_type(public_data_item.memory_data_type)
+        ));
+        lines.push(format!(
+            "pub const {}_DATA_INDEX: usize = {};",
+            to_identifier(public_data_item.full_name).to_uppercase(),
+            public_data_item.public_index
+        ));
+    }
+
+    lines.push("".to_owned());
+    lines.join("\n")
+}
+
+fn format_rust_operand_data_types(operand_data_types: &[OperandDataType]) -> String {
+    let names = operand_data_types
+        .iter()
+        .map(|operand_data_type| {
+            let name = match operand_data_type {
+                OperandDataType::I32 => "I32",
+                OperandDataType::I64 => "I64",
+                OperandDataType::F32 => "F32",
+                OperandDataType::F64 => "F64",
+            };
+            format!("OperandDataType::{}", name)
+        })
+        .collect::<Vec<_>>();
+
+    format!("&[{}]", names.join(", "))
+}
+
+fn format_c_signature(name: &str, type_entry: &TypeEntry) -> String {
+    let params = if type_entry.params.is_empty() {
+        "void".to_owned()
+    } else {
+        type_entry
+            .params
+            .iter()
+            .map(|operand_data_type| get_c_type_name(*operand_data_type))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    // C functions return at most one value.
+    let result = match type_entry.results.as_slice() {
+        [] => "void".to_owned(),
+        [operand_data_type] => get_c_type_name(*operand_data_type).to_owned(),
+        _ => format!("/* {} values */ void", type_entry.results.len()),
+    };
+
+    format!("{} {}({});", result, name, params)
+}
+
+fn get_c_type_name(operand_data_type: OperandDataType) -> &'static str {
+    match operand_data_type {
+        OperandDataType::I32 => "int32_t",
+        OperandDataType::I64 => "int64_t",
+        OperandDataType::F32 => "float",
+        OperandDataType::F64 => "double",
+    }
+}
+
+// Returns the exported functions in the order of their public indices.
+fn collect_public_functions(image_common_entry: &ImageCommonEntry) -> Vec<PublicFunction<'_>> {
+    let public_index_space = PublicIndexSpace::from_common_entry(image_common_entry);
+
+    let mut public_functions = image_common_entry
+        .function_name_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .map(|entry| {
+            let function_entry = &image_common_entry.function_entries[entry.internal_index];
+            PublicFunction {
+                full_name: &entry.full_name,
+                public_index: public_index_space.function_public_index(entry.internal_index),
+                type_entry: &image_common_entry.type_entries[function_entry.type_index],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    public_functions.sort_by_key(|public_function| public_function.public_index);
+    public_functions
+}
+
+// Returns the exported data in the order of their public indices.
+fn collect_public_data(image_common_entry: &ImageCommonEntry) -> Vec<PublicData<'_>> {
+    let public_index_space = PublicIndexSpace::from_common_entry(image_common_entry);
+
+    let mut public_data = image_common_entry
+        .data_data_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .map(|entry| {
+            let index = entry.internal_index_in_section;
+            let memory_data_type = match entry.section_type {
+                DataSectionType::ReadOnly => {
+                    image_common_entry.read_only_data_entries[index].memory_data_type
+                }
+                DataSectionType::ReadWrite => {
+                    image_common_entry.read_write_data_entries[index].memory_data_type
+                }
+                DataSectionType::Uninit => {
+                    image_common_entry.uninit_data_entries[index].memory_data_type
+                }
+            };
+
+            PublicData {
+                full_name: &entry.full_name,
+                public_index: public_index_space.data_public_index(entry.section_type, index),
+                section_type: entry.section_type,
+                memory_data_type,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    public_data.sort_by_key(|public_data_item| public_data_item.public_index);
+    public_data
+}
+
+// Converts the full name into an identifier, e.g. "foo::math::add" -> "foo_math_add".
+fn to_identifier(full_name: &str) -> String {
+    full_name
+        .replace("::", "_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{DataSectionType, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        binding_generator::{generate_c_header, generate_rust_bindings},
+        entry::{
+            DataNameEntry, FunctionNameEntry, ImageCommonEntry, ReadWriteDataEntry, TypeEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::build_shared_module_scaffold,
+        module_image::Visibility,
+    };
+
+    fn build_image_common_entry() -> ImageCommonEntry {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                (
+                    "add",
+                    TypeEntry::new(
+                        vec![OperandDataType::I32, OperandDataType::I32],
+                        vec![OperandDataType::I32],
+                    ),
+                ),
+                ("math::reset", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry
+            .read_write_data_entries
+            .push(ReadWriteDataEntry::from_i64(0));
+        // the package-private items are declared as well
+        image_common_entry
+            .data_data_entries
+            .push(DataNameEntry::new(
+                "foo::count".to_owned(),
+                Visibility::Package,
+                DataSectionType::ReadWrite,
+                0,
+            ));
+        image_common_entry
+            .function_name_entries
+            .push(FunctionNameEntry::new(
+                "foo::hidden".to_owned(),
+                Visibility::Private,
+                0,
+            ));
+
+        image_common_entry
+    }
+
+    #[test]
+    fn test_generate_c_header() {
+        let image_common_entry = build_image_common_entry();
+
+        assert_eq!(
+            generate_c_header(&image_common_entry),
+            "\
+// Generated from module \"foo\" version 1.0.0, do not edit.
+#ifndef ANC_MODULE_FOO_H
+#define ANC_MODULE_FOO_H
+
+// Function public indices
+// foo::add: (i32, i32) -> (i32)
+// int32_t foo_add(int32_t, int32_t);
+#define FOO_ADD_FUNCTION_INDEX 0
+// foo::math::reset: () -> ()
+// void foo_math_reset(void);
+#define FOO_MATH_RESET_FUNCTION_INDEX 1
+
+// Data public indices
+// foo::count: read_write, i64
+#define FOO_COUNT_DATA_INDEX 0
+
+#endif // ANC_MODULE_FOO_H
+"
+        );
+    }
+
+    #[test]
+    fn test_generate_rust_bindings() {
+        let image_common_entry = build_image_common_entry();
+
+        assert_eq!(
+            generate_rust_bindings(&image_common_entry),
+            "\
+// Generated from module \"foo\" version 1.0.0, do not edit.
+
+use anc_isa::OperandDataType;
+
+// foo::add
+pub const FOO_ADD_FUNCTION_INDEX: usize = 0;
+pub const FOO_ADD_PARAMS: &[OperandDataType] = &[OperandDataType::I32, OperandDataType::I32];
+pub const FOO_ADD_RESULTS: &[OperandDataType] = &[OperandDataType::I32];
+
+// foo::math::reset
+pub const FOO_MATH_RESET_FUNCTION_INDEX: usize = 1;
+pub const FOO_MATH_RESET_PARAMS: &[OperandDataType] = &[];
+pub const FOO_MATH_RESET_RESULTS: &[OperandDataType] = &[];
+
+// foo::count: read_write, i64
+pub const FOO_COUNT_DATA_INDEX: usize = 0;
+"
+        );
+    }
+}
@@ -0,0 +1,682 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The fluent API for assembling a module image, e.g.,
+//
+// ```text
+// ModuleImageBuilder::new("hello")
+//     .add_function("main", Visibility::Public, type_entry, &[], code)
+//     .set_entry_point("_start", "main")
+//     .build_application()
+// ```
+//
+// The builder collects the entries (see `ImageCommonEntry`) and writes them by
+// `entry_writer`, so the sections are emitted in the canonical order.
+//
+// - The identical types and local variable lists are merged (interned).
+// - The names of the functions and data do not include the module name,
+//   the full names are `{module_name}::{name}`.
+// - The relocate list of each function is derived from its bytecode,
+//   see `relocate_coverage::get_expected_relocate_entries`.
+//
+// Note: the public indices of the internal functions and data follow the imported
+// ones (see `public_index`), so the imports should be added before the bytecode
+// which refers to the internal functions and data is generated.
+//
+// The errors of the chain calling (e.g., importing from a module which is not added)
+// are recorded and returned by the `build_*` methods, so the chain is not broken.
+
+use std::fmt::Display;
+
+use anc_isa::{DataSectionType, EffectiveVersion, MemoryDataType, OperandDataType};
+
+use crate::{
+    entry::{
+        DataIndexEntry, DataIndexListEntry, DataNameEntry, EntryPointEntry, ExternalFunctionEntry,
+        ExternalLibraryEntry, FunctionEntry, FunctionIndexEntry, FunctionIndexListEntry,
+        FunctionNameEntry, ImageCommonEntry, ImageLinkingEntry, ImportDataEntry,
+        ImportFunctionEntry, ImportModuleEntry, LinkingModuleEntry, LocalVariableListEntry,
+        ModuleLocation, ReadOnlyDataEntry, ReadWriteDataEntry, RelocateListEntry, TypeEntry,
+        UninitDataEntry,
+    },
+    entry_writer::{find_or_append, write_image_file, write_object_file},
+    module_image::{ImageType, Visibility},
+    relocate_coverage::get_expected_relocate_entries,
+    unified_external::build_unified_external_entries,
+    ImageError, ImageErrorType,
+};
+
+const NAME_PATH_SEPARATOR: &str = "::";
+
+// The reasons why the builder can not build the image.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BuildError {
+    // The application with imports must be built by the linker.
+    ApplicationWithImports,
+
+    MissingEntryPointFunction {
+        unit_name: String,
+        full_name: String,
+    },
+
+    MissingImportModule(String),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::ApplicationWithImports => write!(
+                f,
+                "The application with imports must be built by the linker."
+            ),
+            BuildError::MissingEntryPointFunction {
+                unit_name,
+                full_name,
+            } => write!(
+                f,
+                "Cannot find the function \"{}\" of the entry point \"{}\".",
+                full_name, unit_name
+            ),
+            BuildError::MissingImportModule(module_name) => {
+                write!(f, "Cannot find the import module \"{}\".", module_name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ModuleImageBuilder {
+    image_common_entry: ImageCommonEntry,
+
+    // `(unit_name, function_name)`, the function name does not include the module name.
+    entry_points: Vec<(String, String)>,
+
+    // The first error of the chain calling, it is returned
+    // by the `build_*` methods.
+    error: Option<BuildError>,
+}
+
+/// Chain calling style for adding the items.
+impl ModuleImageBuilder {
+    /// Creates a builder of the module with the version `1.0.0`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            image_common_entry: ImageCommonEntry {
+                name: name.to_owned(),
+                version: EffectiveVersion::new(1, 0, 0),
+                image_type: ImageType::ObjectFile,
+                type_entries: vec![],
+                local_variable_list_entries: vec![],
+                function_entries: vec![],
+                read_only_data_entries: vec![],
+                read_write_data_entries: vec![],
+                uninit_data_entries: vec![],
+                import_module_entries: vec![],
+                import_function_entries: vec![],
+                import_data_entries: vec![],
+                function_name_entries: vec![],
+                data_data_entries: vec![],
+                relocate_list_entries: vec![],
+                external_library_entries: vec![],
+                external_function_entries: vec![],
+                initializer_entries: vec![],
+            },
+            entry_points: vec![],
+            error: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: EffectiveVersion) -> Self {
+        self.image_common_entry.version = version;
+        self
+    }
+
+    pub fn add_import_module(mut self, import_module_entry: ImportModuleEntry) -> Self {
+        self.image_common_entry
+            .import_module_entries
+            .push(import_module_entry);
+        self
+    }
+
+    /// Imports a function from the module which is added by `add_import_module`.
+    ///
+    /// If the module is not found, the import is ignored and
+    /// the error is returned by the `build_*` methods.
+    pub fn add_import_function(
+        mut self,
+        module_name: &str,
+        full_name: &str,
+        type_entry: TypeEntry,
+    ) -> Self {
+        let Some(import_module_index) = self.get_import_module_index(module_name) else {
+            return self.with_unknown_import_module(module_name);
+        };
+        let type_index = find_or_append(&mut self.image_common_entry.type_entries, type_entry);
+        self.image_common_entry
+            .import_function_entries
+            .push(ImportFunctionEntry::new(
+                full_name.to_owned(),
+                import_module_index,
+                type_index,
+            ));
+        self
+    }
+
+    /// Imports a data item from the module which is added by `add_import_module`.
+    ///
+    /// If the module is not found, the import is ignored and
+    /// the error is returned by the `build_*` methods.
+    pub fn add_import_data(
+        mut self,
+        module_name: &str,
+        full_name: &str,
+        data_section_type: DataSectionType,
+        memory_data_type: MemoryDataType,
+    ) -> Self {
+        let Some(import_module_index) = self.get_import_module_index(module_name) else {
+            return self.with_unknown_import_module(module_name);
+        };
+        self.image_common_entry
+            .import_data_entries
+            .push(ImportDataEntry::new(
+                full_name.to_owned(),
+                import_module_index,
+                data_section_type,
+                memory_data_type,
+            ));
+        self
+    }
+
+    /// Adds a function, the local variables do not include the arguments.
+    pub fn add_function(
+        mut self,
+        name: &str,
+        visibility: Visibility,
+        type_entry: TypeEntry,
+        local_variable_types_without_args: &[OperandDataType],
+        code: Vec<u8>,
+    ) -> Self {
+        // The local variable list of a function starts with the arguments.
+        let mut local_variable_types = type_entry.params.clone();
+        local_variable_types.extend_from_slice(local_variable_types_without_args);
+
+        let type_index = find_or_append(&mut self.image_common_entry.type_entries, type_entry);
+        let local_variable_list_index = find_or_append(
+            &mut self.image_common_entry.local_variable_list_entries,
+            LocalVariableListEntry::new(local_variable_types),
+        );
+
+        let function_internal_index = self.image_common_entry.function_entries.len();
+        let full_name = self.get_full_name(name);
+
+        self.image_common_entry
+            .relocate_list_entries
+            .push(RelocateListEntry::new(get_expected_relocate_entries(&code)));
+        self.image_common_entry
+            .function_entries
+            .push(FunctionEntry::new(
+                type_index,
+                local_variable_list_index,
+                code,
+            ));
+        self.image_common_entry
+            .function_name_entries
+            .push(FunctionNameEntry::new(
+                full_name,
+                visibility,
+                function_internal_index,
+            ));
+        self
+    }
+
+    pub fn add_read_only_data(
+        mut self,
+        name: &str,
+        visibility: Visibility,
+        read_only_data_entry: ReadOnlyDataEntry,
+    ) -> Self {
+        let internal_index_in_section = self.image_common_entry.read_only_data_entries.len();
+        self.image_common_entry
+            .read_only_data_entries
+            .push(read_only_data_entry);
+        self.add_data_name(
+            name,
+            visibility,
+            DataSectionType::ReadOnly,
+            internal_index_in_section,
+        )
+    }
+
+    pub fn add_read_write_data(
+        mut self,
+        name: &str,
+        visibility: Visibility,
+        read_write_data_entry: ReadWriteDataEntry,
+    ) -> Self {
+        let internal_index_in_section = self.image_common_entry.read_write_data_entries.len();
+        self.image_common_entry
+            .read_write_data_entries
+            .push(read_write_data_entry);
+        self.add_data_name(
+            name,
+            visibility,
+            DataSectionType::ReadWrite,
+            internal_index_in_section,
+        )
+    }
+
+    pub fn add_uninit_data(
+        mut self,
+        name: &str,
+        visibility: Visibility,
+        uninit_data_entry: UninitDataEntry,
+    ) -> Self {
+        let internal_index_in_section = self.image_common_entry.uninit_data_entries.len();
+        self.image_common_entry
+            .uninit_data_entries
+            .push(uninit_data_entry);
+        self.add_data_name(
+            name,
+            visibility,
+            DataSectionType::Uninit,
+            internal_index_in_section,
+        )
+    }
+
+    /// Adds an external function, the library is added if it does not exist.
+    pub fn add_external_function(
+        mut self,
+        external_library_entry: ExternalLibraryEntry,
+        name: &str,
+        type_entry: TypeEntry,
+    ) -> Self {
+        let external_library_index = find_or_append(
+            &mut self.image_common_entry.external_library_entries,
+            external_library_entry,
+        );
+        let type_index = find_or_append(&mut self.image_common_entry.type_entries, type_entry);
+        self.image_common_entry
+            .external_function_entries
+            .push(ExternalFunctionEntry::new(
+                name.to_owned(),
+                external_library_index,
+                type_index,
+            ));
+        self
+    }
+
+    /// Sets the entry point of the application, the existing entry point with
+    /// the same unit name is replaced. It only affects `build_application`.
+    ///
+    /// The function name does not include the module name, e.g. "main".
+    pub fn set_entry_point(mut self, unit_name: &str, function_name: &str) -> Self {
+        self.entry_points.retain(|(name, _)| name != unit_name);
+        self.entry_points
+            .push((unit_name.to_owned(), function_name.to_owned()));
+        self
+    }
+
+    pub fn get_image_common_entry(&self) -> &ImageCommonEntry {
+        &self.image_common_entry
+    }
+
+    pub fn build_object_file(&self) -> Result<Vec<u8>, ImageError> {
+        self.check_error()?;
+
+        let mut image_binary: Vec<u8> = vec![];
+
+        write_object_file(&self.image_common_entry, false, &mut image_binary)?;
+        Ok(image_binary)
+    }
+
+    pub fn build_shared_module(&self) -> Result<Vec<u8>, ImageError> {
+        self.check_error()?;
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_object_file(&self.image_common_entry, true, &mut image_binary)?;
+        Ok(image_binary)
+    }
+
+    /// Builds a standalone application, i.e., the application which consists of
+    /// this module only, the index sections are generated.
+    ///
+    /// The application with imports must be built by the linker, since
+    /// the dependent modules are required.
+    pub fn build_application(&self) -> Result<Vec<u8>, ImageError> {
+        self.check_error()?;
+
+        let image_common_entry = &self.image_common_entry;
+
+        if !image_common_entry.import_function_entries.is_empty()
+            || !image_common_entry.import_data_entries.is_empty()
+        {
+            return Err(ImageError::new(ImageErrorType::Build(
+                BuildError::ApplicationWithImports,
+            )));
+        }
+
+        let entry_point_entries = self
+            .entry_points
+            .iter()
+            .map(|(unit_name, function_name)| {
+                let full_name = self.get_full_name(function_name);
+                image_common_entry
+                    .function_name_entries
+                    .iter()
+                    .find(|entry| entry.full_name == full_name)
+                    .map(|entry| EntryPointEntry::new(unit_name.to_owned(), entry.internal_index))
+                    .ok_or_else(|| {
+                        ImageError::new(ImageErrorType::Build(
+                            BuildError::MissingEntryPointFunction {
+                                unit_name: unit_name.to_owned(),
+                                full_name: full_name.clone(),
+                            },
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The public indices are the same as the internal indices,
+        // since there are no imports.
+        let function_index_entries = (0..image_common_entry.function_entries.len())
+            .map(|function_internal_index| FunctionIndexEntry::new(0, function_internal_index))
+            .collect::<Vec<_>>();
+
+        let data_index_entries = [
+            (
+                DataSectionType::ReadOnly,
+                image_common_entry.read_only_data_entries.len(),
+            ),
+            (
+                DataSectionType::ReadWrite,
+                image_common_entry.read_write_data_entries.len(),
+            ),
+            (
+                DataSectionType::Uninit,
+                image_common_entry.uninit_data_entries.len(),
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(data_section_type, count)| {
+            (0..count).map(move |data_internal_index_in_section| {
+                DataIndexEntry::new(0, data_section_type, data_internal_index_in_section)
+            })
+        })
+        .collect::<Vec<_>>();
+
+        let unified_external_entries =
+            build_unified_external_entries(std::slice::from_ref(image_common_entry));
+
+        let image_linking_entry = ImageLinkingEntry {
+            function_index_list_entries: vec![FunctionIndexListEntry::new(function_index_entries)],
+            data_index_list_entries: vec![DataIndexListEntry::new(data_index_entries)],
+            initialization_dependency_entries: vec![],
+            external_function_index_entries: unified_external_entries
+                .external_function_index_list_entries,
+            unified_external_library_entries: unified_external_entries
+                .unified_external_library_entries,
+            unified_external_type_entries: unified_external_entries.unified_external_type_entries,
+            unified_external_function_entries: unified_external_entries
+                .unified_external_function_entries,
+            lazy_binding_entries: vec![],
+            linking_module_entries: vec![LinkingModuleEntry::new(
+                image_common_entry.name.clone(),
+                Box::new(ModuleLocation::Embed),
+            )],
+            entry_point_entries,
+        };
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_image_file(image_common_entry, &image_linking_entry, &mut image_binary)?;
+        Ok(image_binary)
+    }
+
+    fn add_data_name(
+        mut self,
+        name: &str,
+        visibility: Visibility,
+        data_section_type: DataSectionType,
+        internal_index_in_section: usize,
+    ) -> Self {
+        let full_name = self.get_full_name(name);
+        self.image_common_entry
+            .data_data_entries
+            .push(DataNameEntry::new(
+                full_name,
+                visibility,
+                data_section_type,
+                internal_index_in_section,
+            ));
+        self
+    }
+
+    fn get_import_module_index(&self, module_name: &str) -> Option<usize> {
+        self.image_common_entry
+            .import_module_entries
+            .iter()
+            .position(|entry| entry.name == module_name)
+    }
+
+    // Records the error of importing from an unknown module, only the first error is kept.
+    fn with_unknown_import_module(mut self, module_name: &str) -> Self {
+        if self.error.is_none() {
+            self.error = Some(BuildError::MissingImportModule(module_name.to_owned()));
+        }
+        self
+    }
+
+    fn check_error(&self) -> Result<(), ImageError> {
+        match &self.error {
+            Some(error) => Err(ImageError::new(ImageErrorType::Build(error.clone()))),
+            None => Ok(()),
+        }
+    }
+
+    fn get_full_name(&self, name: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.image_common_entry.name, NAME_PATH_SEPARATOR, name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{
+        opcode::Opcode, DataSectionType, DependencyCondition, DependencyLocal, MemoryDataType,
+        ModuleDependency, OperandDataType,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        builder::ModuleImageBuilder,
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            ImportModuleEntry, ReadOnlyDataEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+            UninitDataEntry,
+        },
+        entry_reader::read_object_file,
+        module_image::{ImageType, ModuleImage, Visibility},
+        validator::validate_module_image,
+    };
+
+    #[test]
+    fn test_build_object_file() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let builder = ModuleImageBuilder::new("foo")
+            .add_import_module(ImportModuleEntry::new(
+                "bar".to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: "bar".to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            ))
+            .add_import_function("bar", "bar::get_number", TypeEntry::new(vec![], vec![]))
+            .add_import_data(
+                "bar",
+                "bar::count",
+                DataSectionType::ReadWrite,
+                MemoryDataType::I32,
+            )
+            .add_function(
+                "main",
+                Visibility::Public,
+                TypeEntry::new(vec![OperandDataType::I32], vec![]),
+                &[OperandDataType::I64],
+                code.clone(),
+            )
+            .add_function(
+                "helper",
+                Visibility::Private,
+                TypeEntry::new(vec![], vec![]),
+                &[],
+                code,
+            )
+            .add_read_only_data(
+                "message",
+                Visibility::Public,
+                ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+            )
+            .add_uninit_data("buffer", Visibility::Private, UninitDataEntry::from_i64());
+
+        let image_binary = builder.build_object_file().unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        assert_eq!(module_image.image_type, ImageType::ObjectFile);
+
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+
+        // the type `() -> ()` is interned
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![
+                TypeEntry::new(vec![], vec![]),
+                TypeEntry::new(vec![OperandDataType::I32], vec![]),
+            ]
+        );
+        assert_eq!(image_common_entry.local_variable_list_entries.len(), 2);
+        assert_eq!(image_common_entry.function_entries[0].type_index, 1);
+        assert_eq!(image_common_entry.function_entries[1].type_index, 0);
+
+        assert_eq!(
+            image_common_entry
+                .function_name_entries
+                .iter()
+                .map(|entry| entry.full_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo::main", "foo::helper"]
+        );
+        assert_eq!(
+            image_common_entry
+                .data_data_entries
+                .iter()
+                .map(|entry| (entry.full_name.as_str(), entry.section_type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo::message", DataSectionType::ReadOnly),
+                ("foo::buffer", DataSectionType::Uninit)
+            ]
+        );
+        assert_eq!(image_common_entry.import_function_entries.len(), 1);
+        assert_eq!(image_common_entry.import_data_entries.len(), 1);
+
+        // the relocate lists are derived from the bytecode
+        assert_eq!(
+            image_common_entry.relocate_list_entries[0],
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(0)])
+        );
+
+        // the imports require linking
+        assert_eq!(
+            builder.build_application().unwrap_err().to_string(),
+            "Build error: The application with imports must be built by the linker."
+        );
+    }
+
+    #[test]
+    fn test_build_application() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 0)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let builder = ModuleImageBuilder::new("hello")
+            .add_function(
+                "main",
+                Visibility::Public,
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+                &[],
+                code,
+            )
+            .add_read_only_data(
+                "number",
+                Visibility::Private,
+                ReadOnlyDataEntry::from_i32(11),
+            )
+            .set_entry_point("_start", "main");
+
+        let image_binary = builder.build_application().unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(module_image.image_type, ImageType::Application);
+        assert_eq!(validate_module_image(&module_image), Ok(()));
+        assert_eq!(
+            module_image
+                .get_entry_point_section()
+                .get_function_public_index("_start"),
+            Some(0)
+        );
+
+        // the entry point function is not found
+        assert!(builder
+            .set_entry_point("_start", "start")
+            .build_application()
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_with_unknown_import_module() {
+        let builder = ModuleImageBuilder::new("foo")
+            .add_import_function("bar", "bar::get_number", TypeEntry::new(vec![], vec![]))
+            .add_import_data(
+                "baz",
+                "baz::count",
+                DataSectionType::ReadWrite,
+                MemoryDataType::I32,
+            );
+
+        // only the first error is kept
+        let expected_message = "Build error: Cannot find the import module \"bar\".";
+        assert_eq!(
+            builder.build_object_file().unwrap_err().to_string(),
+            expected_message
+        );
+        assert_eq!(
+            builder.build_shared_module().unwrap_err().to_string(),
+            expected_message
+        );
+        assert_eq!(
+            builder.build_application().unwrap_err().to_string(),
+            expected_message
+        );
+
+        // the imports are ignored
+        assert!(builder
+            .get_image_common_entry()
+            .import_function_entries
+            .is_empty());
+        assert!(builder
+            .get_image_common_entry()
+            .import_data_entries
+            .is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Compares the bytecode of two functions at the instruction level.
+//
+// The instructions are decoded first and then aligned using the
+// "longest common subsequence" (LCS) algorithm, two instructions are
+// considered equal if they have the same opcode and parameters.
+//
+// The alignment `nop`s (inserted by the `BytecodeWriter` for 4-byte
+// alignment of instructions with 'i32' parameters) are ignored, because
+// they are added or removed whenever the address of an instruction
+// changes, and they do not reflect the actual changes of the code.
+//
+// The LCS is found by the Myers' diff algorithm in linear space, so that
+// large functions can be compared, see the section "About the diff algorithm".
+
+use std::{fmt::Display, ops::Range};
+
+use anc_isa::opcode::Opcode;
+
+use crate::bytecode_reader::{InstructionIterator, InstructionRecord};
+
+/// A run of changed instructions.
+///
+/// Instructions in `removed` only exist in the old bytecode, and instructions
+/// in `added` only exist in the new bytecode. Either of them may be empty.
+#[derive(Debug, PartialEq)]
+pub struct DiffHunk<'a> {
+    pub removed: Vec<InstructionRecord<'a>>,
+    pub added: Vec<InstructionRecord<'a>>,
+}
+
+impl Display for DiffHunk<'_> {
+    // Formats the hunk as the following text:
+    //
+    // - 0x0004  imm_i32           0x00000013
+    // + 0x0004  imm_i32           0x00000017
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .removed
+            .iter()
+            .map(|record| format_record_line('-', record))
+            .chain(
+                self.added
+                    .iter()
+                    .map(|record| format_record_line('+', record)),
+            )
+            .collect::<Vec<String>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+fn format_record_line(sign: char, record: &InstructionRecord) -> String {
+    let params_text = record.params.to_string();
+    if params_text.is_empty() {
+        format!(
+            "{} 0x{:04x}  {}",
+            sign,
+            record.offset,
+            record.opcode.get_name()
+        )
+    } else {
+        format!(
+            "{} 0x{:04x}  {:16}  {}",
+            sign,
+            record.offset,
+            record.opcode.get_name(),
+            params_text
+        )
+    }
+}
+
+/// Compares two functions bytecode and returns the changed instructions.
+///
+/// An empty list is returned if the two functions are equivalent.
+pub fn diff_bytecode<'a>(a: &'a [u8], b: &'a [u8]) -> Vec<DiffHunk<'a>> {
+    let records_a = decode_without_padding(a);
+    let records_b = decode_without_padding(b);
+
+    let count_a = records_a.len();
+    let count_b = records_b.len();
+
+    let is_equal = |i: usize, j: usize| {
+        records_a[i].opcode == records_b[j].opcode && records_a[i].params == records_b[j].params
+    };
+
+    let max_d = (count_a + count_b).div_ceil(2);
+    let mut forward = Frontier::new(max_d);
+    let mut backward = Frontier::new(max_d);
+    let mut matches: Vec<(usize, usize)> = vec![];
+    collect_matches(
+        &is_equal,
+        0..count_a,
+        0..count_b,
+        &mut forward,
+        &mut backward,
+        &mut matches,
+    );
+
+    let mut hunks: Vec<DiffHunk> = vec![];
+
+    let mut i = 0;
+    let mut j = 0;
+
+    for (match_i, match_j) in matches
+        .into_iter()
+        .chain(std::iter::once((count_a, count_b)))
+    {
+        if i < match_i || j < match_j {
+            hunks.push(DiffHunk {
+                removed: records_a[i..match_i].to_vec(),
+                added: records_b[j..match_j].to_vec(),
+            });
+        }
+        i = match_i + 1;
+        j = match_j + 1;
+    }
+
+    hunks
+}
+
+// About the diff algorithm
+// ------------------------
+//
+// The matched instructions are found by the linear space variant of the
+// Myers' diff algorithm, i.e., the "middle snake" of the edit graph is found
+// by searching forward from the start and backward from the end at the same
+// time, and then the two halves are solved recursively.
+//
+// The time complexity is `O((N + M) * D)` and the space complexity is
+// `O(N + M)`, where `D` is the number of the removed and added instructions.
+//
+// Reference: Eugene W. Myers, "An O(ND) Difference Algorithm and Its Variations".
+
+// The furthest reaching `x` of each diagonal `k = x - y`, for the forward
+// search `x` is counted from the start, and for the backward search `x`
+// is counted from the end.
+struct Frontier {
+    values: Vec<usize>,
+    offset: isize,
+}
+
+impl Frontier {
+    fn new(max_d: usize) -> Self {
+        // The diagonals `-max_d - 1 ..= max_d + 1` are accessed.
+        Self {
+            values: vec![0; 2 * max_d + 3],
+            offset: max_d as isize + 1,
+        }
+    }
+
+    fn get(&self, k: isize) -> usize {
+        self.values[(k + self.offset) as usize]
+    }
+
+    fn set(&mut self, k: isize, x: usize) {
+        self.values[(k + self.offset) as usize] = x;
+    }
+}
+
+// Appends the matched `(index_a, index_b)` pairs of the specified ranges to
+// `matches`, in ascending order.
+fn collect_matches<F: Fn(usize, usize) -> bool>(
+    is_equal: &F,
+    mut range_a: Range<usize>,
+    mut range_b: Range<usize>,
+    forward: &mut Frontier,
+    backward: &mut Frontier,
+    matches: &mut Vec<(usize, usize)>,
+) {
+    let prefix_len = common_prefix_len(is_equal, range_a.clone(), range_b.clone());
+    matches.extend((0..prefix_len).map(|offset| (range_a.start + offset, range_b.start + offset)));
+    range_a.start += prefix_len;
+    range_b.start += prefix_len;
+
+    let suffix_len = common_suffix_len(is_equal, range_a.clone(), range_b.clone());
+    range_a.end -= suffix_len;
+    range_b.end -= suffix_len;
+
+    if !range_a.is_empty() && !range_b.is_empty() {
+        if let Some((split_a, split_b)) = find_middle_snake(
+            is_equal,
+            range_a.clone(),
+            range_b.clone(),
+            forward,
+            backward,
+        ) {
+            collect_matches(
+                is_equal,
+                range_a.start..split_a,
+                range_b.start..split_b,
+                forward,
+                backward,
+                matches,
+            );
+            collect_matches(
+                is_equal,
+                split_a..range_a.end,
+                split_b..range_b.end,
+                forward,
+                backward,
+                matches,
+            );
+        }
+    }
+
+    matches.extend((0..suffix_len).map(|offset| (range_a.end + offset, range_b.end + offset)));
+}
+
+// Returns the start point of the middle snake, which splits the edit graph of
+// the specified ranges into two smaller ones.
+//
+// The ranges must not be empty and have no common prefix or suffix.
+fn find_middle_snake<F: Fn(usize, usize) -> bool>(
+    is_equal: &F,
+    range_a: Range<usize>,
+    range_b: Range<usize>,
+    forward: &mut Frontier,
+    backward: &mut Frontier,
+) -> Option<(usize, usize)> {
+    let n = range_a.len();
+    let m = range_b.len();
+    let delta = n as isize - m as isize;
+    let is_odd = delta & 1 == 1;
+    let max_d = (n + m).div_ceil(2) as isize;
+
+    forward.set(1, 0);
+    backward.set(1, 0);
+
+    for d in 0..=max_d {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && forward.get(k - 1) < forward.get(k + 1)) {
+                forward.get(k + 1)
+            } else {
+                forward.get(k - 1) + 1
+            };
+            let y = (x as isize - k) as usize;
+            let (x0, y0) = (x, y);
+
+            if x < n && y < m {
+                x += common_prefix_len(
+                    is_equal,
+                    range_a.start + x..range_a.end,
+                    range_b.start + y..range_b.end,
+                );
+            }
+            forward.set(k, x);
+
+            if is_odd && (k - delta).abs() < d && forward.get(k) + backward.get(delta - k) >= n {
+                return Some((range_a.start + x0, range_b.start + y0));
+            }
+        }
+
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && backward.get(k - 1) < backward.get(k + 1)) {
+                backward.get(k + 1)
+            } else {
+                backward.get(k - 1) + 1
+            };
+            let mut y = (x as isize - k) as usize;
+
+            if x < n && y < m {
+                let advance = common_suffix_len(
+                    is_equal,
+                    range_a.start..range_a.end - x,
+                    range_b.start..range_b.end - y,
+                );
+                x += advance;
+                y += advance;
+            }
+            backward.set(k, x);
+
+            if !is_odd && (k - delta).abs() <= d && backward.get(k) + forward.get(delta - k) >= n {
+                return Some((range_a.end - x, range_b.end - y));
+            }
+        }
+    }
+
+    None
+}
+
+fn common_prefix_len<F: Fn(usize, usize) -> bool>(
+    is_equal: &F,
+    range_a: Range<usize>,
+    range_b: Range<usize>,
+) -> usize {
+    range_a
+        .zip(range_b)
+        .take_while(|(i, j)| is_equal(*i, *j))
+        .count()
+}
+
+fn common_suffix_len<F: Fn(usize, usize) -> bool>(
+    is_equal: &F,
+    range_a: Range<usize>,
+    range_b: Range<usize>,
+) -> usize {
+    range_a
+        .rev()
+        .zip(range_b.rev())
+        .take_while(|(i, j)| is_equal(*i, *j))
+        .count()
+}
+
+// Decodes the bytecode and removes the alignment `nop`s.
+//
+// A `nop` is considered as padding when it is located at an address which
+// is not 4-byte aligned and the following instruction has 'i32' parameters
+// (i.e., the length of the instruction is at least 8 bytes).
+fn decode_without_padding(codes: &[u8]) -> Vec<InstructionRecord> {
+    let records = InstructionIterator::new(codes).collect::<Vec<InstructionRecord>>();
+
+    records
+        .iter()
+        .enumerate()
+        .filter(|(idx, record)| {
+            let is_padding = record.opcode == Opcode::nop
+                && record.offset % 4 != 0
+                && records
+                    .get(idx + 1)
+                    .is_some_and(|next_record| next_record.data.len() >= 8);
+            !is_padding
+        })
+        .map(|(_, record)| record.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_diff::diff_bytecode,
+        bytecode_reader::{InstructionParams, InstructionRecord},
+        bytecode_writer::BytecodeWriterHelper,
+    };
+
+    #[test]
+    fn test_diff_bytecode_equivalent() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 0x11)
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        assert!(diff_bytecode(&code0, &code0).is_empty());
+    }
+
+    #[test]
+    fn test_diff_bytecode_changed() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 0x11)
+            .append_opcode_i32(Opcode::imm_i32, 0x13)
+            .append_opcode(Opcode::add_i32)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // 0x0000 imm_i32 0x11
+        // 0x0008 imm_i32 0x13
+        // 0x0010 add_i32
+        // 0x0012 end
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::nop)
+            .append_opcode_i32(Opcode::imm_i32, 0x11)
+            .append_opcode_i16(Opcode::add_imm_i32, 0x13)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // 0x0000 nop
+        // 0x0002 nop (padding)
+        // 0x0004 imm_i32 0x11
+        // 0x000c add_imm_i32 0x13
+        // 0x0010 end
+
+        let hunks = diff_bytecode(&code0, &code1);
+
+        assert_eq!(hunks.len(), 2);
+
+        // the first `nop` is not padding since it is 4-byte aligned
+        assert_eq!(hunks[0].removed, vec![]);
+        assert_eq!(
+            hunks[0].added,
+            vec![InstructionRecord {
+                offset: 0,
+                opcode: Opcode::nop,
+                params: InstructionParams::None,
+                data: &code1[0..2]
+            }]
+        );
+
+        assert_eq!(
+            hunks[1].to_string(),
+            "\
+- 0x0008  imm_i32           0x00000013
+- 0x0010  add_i32
++ 0x000c  add_imm_i32       19"
+        );
+    }
+
+    #[test]
+    fn test_diff_bytecode_ignore_padding() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i32(Opcode::imm_i32, 0x11)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // 0x0000 eqz_i32
+        // 0x0002 nop (padding)
+        // 0x0004 imm_i32 0x11
+        // 0x000c end
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i32(Opcode::imm_i32, 0x11)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // 0x0000 eqz_i32
+        // 0x0002 eqz_i32
+        // 0x0004 imm_i32 0x11
+        // 0x000c end
+
+        let hunks = diff_bytecode(&code0, &code1);
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].to_string(), "+ 0x0002  eqz_i32");
+    }
+
+    #[test]
+    fn test_diff_bytecode_large_functions() {
+        // the functions which are too large for the `O(N * M)` table
+        let build_code = |changed_index: usize| {
+            let mut helper = BytecodeWriterHelper::new();
+            for idx in 0..100_000 {
+                let value = if idx == changed_index { 0x17 } else { 0x11 };
+                helper = helper.append_opcode_i16(Opcode::add_imm_i32, value);
+            }
+            helper.append_opcode(Opcode::end).to_bytes()
+        };
+
+        let code0 = build_code(50_000);
+        let code1 = build_code(70_000);
+
+        let hunks = diff_bytecode(&code0, &code1);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].to_string(), "- 0x30d40  add_imm_i32       23");
+        assert_eq!(hunks[1].to_string(), "+ 0x445c0  add_imm_i32       23");
+    }
+}
@@ -4,8 +4,38 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
+use std::num::NonZeroUsize;
+
 use anc_isa::opcode::Opcode;
 
+use crate::opcode_info::{find_opcode_by_value, get_opcode_info, OperandKind};
+
+/// Options for `format_bytecode_as_binary_with_options`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BinaryFormatOptions {
+    // The address of the first byte, e.g., the offset of the function
+    // code within the "Function Section", so that the addresses in
+    // the output match the VM trace logs.
+    pub base_address: usize,
+
+    // The number of bytes per row.
+    pub bytes_per_row: NonZeroUsize,
+
+    // The number of bytes per group, the groups are separated by two spaces.
+    // `0` means no grouping.
+    pub bytes_per_group: usize,
+}
+
+impl Default for BinaryFormatOptions {
+    fn default() -> Self {
+        Self {
+            base_address: 0,
+            bytes_per_row: NonZeroUsize::new(8).unwrap(),
+            bytes_per_group: 4,
+        }
+    }
+}
+
 /// Formats the bytecode as binary with fixed-length hexadecimal representation.
 ///
 /// Example output:
@@ -14,20 +44,34 @@ use anc_isa::opcode::Opcode;
 /// 0x0008  88 99 aa bb  cc dd ee ff
 /// ```
 pub fn format_bytecode_as_binary(codes: &[u8]) -> String {
+    format_bytecode_as_binary_with_options(codes, &BinaryFormatOptions::default())
+}
+
+/// Formats the bytecode as binary with the specified base address, bytes per row and grouping.
+///
+/// Example output (base address 0x100, 6 bytes per row, 2 bytes per group):
+/// ```text
+/// 0x0100  00 11  22 33  44 55
+/// 0x0106  66 77  88 99  aa bb
+/// ```
+pub fn format_bytecode_as_binary_with_options(
+    codes: &[u8],
+    options: &BinaryFormatOptions,
+) -> String {
     codes
-        .chunks(8)
+        .chunks(options.bytes_per_row.get())
         .enumerate()
-        .map(|(chunk_addr, chunk)| {
+        .map(|(chunk_idx, chunk)| {
             let binary = chunk
                 .iter()
                 .enumerate()
                 .map(|(idx, byte)| {
                     // Formats bytes as:
                     // 00 11 22 33  44 55 66 77
-                    if idx == 4 {
-                        format!("  {:02x}", byte)
-                    } else if idx == 0 {
+                    if idx == 0 {
                         format!("{:02x}", byte)
+                    } else if options.bytes_per_group != 0 && idx % options.bytes_per_group == 0 {
+                        format!("  {:02x}", byte)
                     } else {
                         format!(" {:02x}", byte)
                     }
@@ -35,516 +79,418 @@ pub fn format_bytecode_as_binary(codes: &[u8]) -> String {
                 .collect::<Vec<String>>()
                 .join("");
 
-            format!("0x{:04x}  {}", chunk_addr * 8, binary)
+            format!(
+                "0x{:04x}  {}",
+                options.base_address + chunk_idx * options.bytes_per_row.get(),
+                binary
+            )
         })
         .collect::<Vec<String>>()
         .join("\n")
 }
 
-/// Formats the bytecode as text with instruction hex and corresponding instruction names.
+/// The decoded parameters of an instruction.
 ///
-/// Example output:
-/// ```text
-/// 0x0000  01 00                       instruction_name
-/// 0x0002  02 00 11 00                 instruction_name parameter
-/// 0x0006  03 00 13 00 17 00 00 00     instruction_name parameter_0 parameter_1
-/// ```
-pub fn format_bytecode_as_text(codes: &[u8]) -> String {
-    let mut lines: Vec<String> = vec![];
-
-    let code_length = codes.len(); // Total bytecode length
-    let mut offset = 0; // Current offset in the bytecode
-
-    while offset < code_length {
-        let (offset_param, opcode) = read_opcode(codes, offset);
+/// Note that the "padding" bytes of an instruction are not included.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InstructionParams {
+    None,
+
+    // imm_i32, imm_f32
+    ImmI32(u32),
+
+    // imm_i64, imm_f64
+    ImmI64 {
+        low: u32,
+        high: u32,
+    },
+
+    // local_load_*, local_store_*
+    Local {
+        layers: u16,
+        index: u32,
+    },
+
+    // data_load_*, data_store_*, host_addr_data
+    Data {
+        offset: u16,
+        index: u32,
+    },
+
+    // data_*_extend_*, call, envcall, extcall, get_function, get_data,
+    // host_addr_function, host_addr_data_extend
+    Index(u32),
+
+    // add_imm_*, sub_imm_*
+    Amount(u16),
+
+    // block
+    Block {
+        type_index: u32,
+        local_variable_list_index: u32,
+    },
+
+    // break, recur
+    Break {
+        layers: u16,
+        offset: u32,
+    },
+
+    // block_alt
+    BlockAlt {
+        type_index: u32,
+        local_variable_list_index: u32,
+        offset: u32,
+    },
+
+    // break_alt
+    BreakAlt {
+        offset: u32,
+    },
+
+    // block_nez
+    BlockNez {
+        local_variable_list_index: u32,
+        offset: u32,
+    },
+
+    // terminate
+    Code(u32),
+}
 
-        let (offset_next, param_text) = match opcode {
-            // Category: Fundamental
-            Opcode::nop => (offset_param, String::new()),
-            Opcode::imm_i32 | Opcode::imm_f32 => {
-                let (offset_next, v) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("0x{:08x}", v))
-            }
-            Opcode::imm_i64 | Opcode::imm_f64 => {
-                let (offset_next, v_low, v_high) = continue_read_param_i32_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("low:0x{:08x}  high:0x{:08x}", v_low, v_high),
-                )
-            }
-            // Category: Local Variables
-            Opcode::local_load_i64
-            | Opcode::local_load_i32_s
-            | Opcode::local_load_i32_u
-            | Opcode::local_load_i16_s
-            | Opcode::local_load_i16_u
-            | Opcode::local_load_i8_s
-            | Opcode::local_load_i8_u
-            | Opcode::local_load_f64
-            | Opcode::local_load_f32
-            | Opcode::local_store_i64
-            | Opcode::local_store_i32
-            | Opcode::local_store_i16
-            | Opcode::local_store_i8
-            | Opcode::local_store_f64
-            | Opcode::local_store_f32 => {
-                let (offset_next, layers, index) = continue_read_param_i16_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("layers:{:<2}  index:{}", layers, index,),
-                )
-            }
-            // Category: Data
-            Opcode::data_load_i64
-            | Opcode::data_load_i32_s
-            | Opcode::data_load_i32_u
-            | Opcode::data_load_i16_s
-            | Opcode::data_load_i16_u
-            | Opcode::data_load_i8_s
-            | Opcode::data_load_i8_u
-            | Opcode::data_load_f64
-            | Opcode::data_load_f32
-            | Opcode::data_store_i64
-            | Opcode::data_store_i32
-            | Opcode::data_store_i16
-            | Opcode::data_store_i8
-            | Opcode::data_store_f64
-            | Opcode::data_store_f32 => {
-                let (offset_next, offset, index) = continue_read_param_i16_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("offset:0x{:02x}  index:{}", offset, index),
-                )
-            }
-            Opcode::data_load_extend_i64
-            | Opcode::data_load_extend_i32_s
-            | Opcode::data_load_extend_i32_u
-            | Opcode::data_load_extend_i16_s
-            | Opcode::data_load_extend_i16_u
-            | Opcode::data_load_extend_i8_s
-            | Opcode::data_load_extend_i8_u
-            | Opcode::data_load_extend_f64
-            | Opcode::data_load_extend_f32
-            | Opcode::data_store_extend_i64
-            | Opcode::data_store_extend_i32
-            | Opcode::data_store_extend_i16
-            | Opcode::data_store_extend_i8
-            | Opcode::data_store_extend_f64
-            | Opcode::data_store_extend_f32 => {
-                let (offset_next, index) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("index:{}", index))
-            }
-            Opcode::data_load_dynamic_i64
-            | Opcode::data_load_dynamic_i32_s
-            | Opcode::data_load_dynamic_i32_u
-            | Opcode::data_load_dynamic_i16_s
-            | Opcode::data_load_dynamic_i16_u
-            | Opcode::data_load_dynamic_i8_s
-            | Opcode::data_load_dynamic_i8_u
-            | Opcode::data_load_dynamic_f64
-            | Opcode::data_load_dynamic_f32
-            | Opcode::data_store_dynamic_i64
-            | Opcode::data_store_dynamic_i32
-            | Opcode::data_store_dynamic_i16
-            | Opcode::data_store_dynamic_i8
-            | Opcode::data_store_dynamic_f64
-            | Opcode::data_store_dynamic_f32 => (offset_param, String::new()),
-            // Category: Arithmetic
-            Opcode::add_i32
-            | Opcode::sub_i32
-            | Opcode::mul_i32
-            | Opcode::div_i32_s
-            | Opcode::div_i32_u
-            | Opcode::rem_i32_s
-            | Opcode::rem_i32_u => (offset_param, String::new()),
-            Opcode::add_imm_i32 | Opcode::sub_imm_i32 => {
-                let (offset_next, amount) = continue_read_param_i16(codes, offset_param);
-                (offset_next, format!("{}", amount))
-            }
-            Opcode::add_i64
-            | Opcode::sub_i64
-            | Opcode::mul_i64
-            | Opcode::div_i64_s
-            | Opcode::div_i64_u
-            | Opcode::rem_i64_s
-            | Opcode::rem_i64_u => (offset_param, String::new()),
-            Opcode::add_imm_i64 | Opcode::sub_imm_i64 => {
-                let (offset_next, amount) = continue_read_param_i16(codes, offset_param);
-                (offset_next, format!("{}", amount))
-            }
-            Opcode::add_f32
-            | Opcode::sub_f32
-            | Opcode::mul_f32
-            | Opcode::div_f32
-            | Opcode::add_f64
-            | Opcode::sub_f64
-            | Opcode::mul_f64
-            | Opcode::div_f64 => (offset_param, String::new()),
-            // Category: Bitwise
-            Opcode::and
-            | Opcode::or
-            | Opcode::xor
-            | Opcode::not
-            | Opcode::count_leading_zeros_i32
-            | Opcode::count_leading_ones_i32
-            | Opcode::count_trailing_zeros_i32
-            | Opcode::count_ones_i32
-            | Opcode::shift_left_i32
-            | Opcode::shift_right_i32_s
-            | Opcode::shift_right_i32_u
-            | Opcode::rotate_left_i32
-            | Opcode::rotate_right_i32
-            | Opcode::count_leading_zeros_i64
-            | Opcode::count_leading_ones_i64
-            | Opcode::count_trailing_zeros_i64
-            | Opcode::count_ones_i64
-            | Opcode::shift_left_i64
-            | Opcode::shift_right_i64_s
-            | Opcode::shift_right_i64_u
-            | Opcode::rotate_left_i64
-            | Opcode::rotate_right_i64 => (offset_param, String::new()),
-            // Category: Math
-            Opcode::abs_i32
-            | Opcode::neg_i32
-            | Opcode::abs_i64
-            | Opcode::neg_i64
-            | Opcode::abs_f32
-            | Opcode::neg_f32
-            | Opcode::copysign_f32
-            | Opcode::sqrt_f32
-            | Opcode::min_f32
-            | Opcode::max_f32
-            | Opcode::ceil_f32
-            | Opcode::floor_f32
-            | Opcode::round_half_away_from_zero_f32
-            | Opcode::round_half_to_even_f32
-            | Opcode::trunc_f32
-            | Opcode::fract_f32
-            | Opcode::cbrt_f32
-            | Opcode::exp_f32
-            | Opcode::exp2_f32
-            | Opcode::ln_f32
-            | Opcode::log2_f32
-            | Opcode::log10_f32
-            | Opcode::sin_f32
-            | Opcode::cos_f32
-            | Opcode::tan_f32
-            | Opcode::asin_f32
-            | Opcode::acos_f32
-            | Opcode::atan_f32
-            | Opcode::pow_f32
-            | Opcode::log_f32
-            | Opcode::abs_f64
-            | Opcode::neg_f64
-            | Opcode::copysign_f64
-            | Opcode::sqrt_f64
-            | Opcode::min_f64
-            | Opcode::max_f64
-            | Opcode::ceil_f64
-            | Opcode::floor_f64
-            | Opcode::round_half_away_from_zero_f64
-            | Opcode::round_half_to_even_f64
-            | Opcode::trunc_f64
-            | Opcode::fract_f64
-            | Opcode::cbrt_f64
-            | Opcode::exp_f64
-            | Opcode::exp2_f64
-            | Opcode::ln_f64
-            | Opcode::log2_f64
-            | Opcode::log10_f64
-            | Opcode::sin_f64
-            | Opcode::cos_f64
-            | Opcode::tan_f64
-            | Opcode::asin_f64
-            | Opcode::acos_f64
-            | Opcode::atan_f64
-            | Opcode::pow_f64
-            | Opcode::log_f64 => (offset_param, String::new()),
-            // Category: Conversion
-            Opcode::truncate_i64_to_i32
-            | Opcode::extend_i32_s_to_i64
-            | Opcode::extend_i32_u_to_i64
-            | Opcode::demote_f64_to_f32
-            | Opcode::promote_f32_to_f64
-            | Opcode::convert_f32_to_i32_s
-            | Opcode::convert_f32_to_i32_u
-            | Opcode::convert_f64_to_i32_s
-            | Opcode::convert_f64_to_i32_u
-            | Opcode::convert_f32_to_i64_s
-            | Opcode::convert_f32_to_i64_u
-            | Opcode::convert_f64_to_i64_s
-            | Opcode::convert_f64_to_i64_u
-            | Opcode::convert_i32_s_to_f32
-            | Opcode::convert_i32_u_to_f32
-            | Opcode::convert_i64_s_to_f32
-            | Opcode::convert_i64_u_to_f32
-            | Opcode::convert_i32_s_to_f64
-            | Opcode::convert_i32_u_to_f64
-            | Opcode::convert_i64_s_to_f64
-            | Opcode::convert_i64_u_to_f64 => (offset_param, String::new()),
-            // Category: Comparison
-            Opcode::eqz_i32
-            | Opcode::nez_i32
-            | Opcode::eq_i32
-            | Opcode::ne_i32
-            | Opcode::lt_i32_s
-            | Opcode::lt_i32_u
-            | Opcode::gt_i32_s
-            | Opcode::gt_i32_u
-            | Opcode::le_i32_s
-            | Opcode::le_i32_u
-            | Opcode::ge_i32_s
-            | Opcode::ge_i32_u
-            | Opcode::eqz_i64
-            | Opcode::nez_i64
-            | Opcode::eq_i64
-            | Opcode::ne_i64
-            | Opcode::lt_i64_s
-            | Opcode::lt_i64_u
-            | Opcode::gt_i64_s
-            | Opcode::gt_i64_u
-            | Opcode::le_i64_s
-            | Opcode::le_i64_u
-            | Opcode::ge_i64_s
-            | Opcode::ge_i64_u
-            | Opcode::eq_f32
-            | Opcode::ne_f32
-            | Opcode::lt_f32
-            | Opcode::gt_f32
-            | Opcode::le_f32
-            | Opcode::ge_f32
-            | Opcode::eq_f64
-            | Opcode::ne_f64
-            | Opcode::lt_f64
-            | Opcode::gt_f64
-            | Opcode::le_f64
-            | Opcode::ge_f64 => (offset_param, String::new()),
-            // Category: Control flow
-            Opcode::end => (offset_param, String::new()),
-            Opcode::block => {
-                let (offset_next, type_idx, local_variable_list_index) =
-                    continue_read_param_i32_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("type:{:<2}  local:{}", type_idx, local_variable_list_index),
-                )
-            }
-            Opcode::break_ | Opcode::recur => {
-                let (offset_next, layers, offset) =
-                    continue_read_param_i16_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("layers:{:<2}  offset:0x{:02x}", layers, offset),
-                )
-            }
-            Opcode::block_alt => {
-                let (offset_next, type_idx, local_variable_list_index, offset) =
-                    continue_read_param_i32_i32_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!(
-                        "type:{:<2}  local:{:<2}  offset:0x{:02x}",
-                        type_idx, local_variable_list_index, offset
-                    ),
-                )
-            }
-            Opcode::break_alt => {
-                let (offset_next, offset) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("offset:0x{:02x}", offset))
-            }
-            Opcode::block_nez => {
-                let (offset_next, local_variable_list_index, offset) =
-                    continue_read_param_i32_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!(
-                        "local:{:<2}  offset:0x{:02x}",
-                        local_variable_list_index, offset
-                    ),
-                )
-            }
-            Opcode::call | Opcode::envcall | Opcode::extcall => {
-                let (offset_next, idx) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("index:{}", idx))
-            }
-            Opcode::call_dynamic | Opcode::syscall => (offset_param, String::new()),
-            // Category: Memory
-            Opcode::memory_allocate
-            | Opcode::memory_reallocate
-            | Opcode::memory_free
-            | Opcode::memory_fill
-            | Opcode::memory_copy => (offset_param, String::new()),
-            // Category: Machine
-            Opcode::terminate => {
-                let (offset_next, code) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("code:{}", code))
-            }
-            Opcode::get_function | Opcode::get_data => {
-                let (offset_next, idx) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("index:{}", idx))
+impl std::fmt::Display for InstructionParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstructionParams::None => Ok(()),
+            InstructionParams::ImmI32(v) => write!(f, "0x{:08x}", v),
+            InstructionParams::ImmI64 { low, high } => {
+                write!(f, "low:0x{:08x}  high:0x{:08x}", low, high)
             }
-            Opcode::host_addr_function => {
-                let (offset_next, idx) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("index:{}", idx))
+            InstructionParams::Local { layers, index } => {
+                write!(f, "layers:{:<2}  index:{}", layers, index)
             }
-            Opcode::host_addr_function_dynamic => (offset_param, String::new()),
-            Opcode::host_addr_data => {
-                let (offset_next, offset, idx) = continue_read_param_i16_i32(codes, offset_param);
-                (
-                    offset_next,
-                    format!("offset:0x{:02x}  index:{}", offset, idx),
-                )
+            InstructionParams::Data { offset, index } => {
+                write!(f, "offset:0x{:02x}  index:{}", offset, index)
             }
-            Opcode::host_addr_data_extend => {
-                let (offset_next, idx) = continue_read_param_i32(codes, offset_param);
-                (offset_next, format!("index:{}", idx))
+            InstructionParams::Index(index) => write!(f, "index:{}", index),
+            InstructionParams::Amount(amount) => write!(f, "{}", amount),
+            InstructionParams::Block {
+                type_index,
+                local_variable_list_index,
+            } => write!(
+                f,
+                "type:{:<2}  local:{}",
+                type_index, local_variable_list_index
+            ),
+            InstructionParams::Break { layers, offset } => {
+                write!(f, "layers:{:<2}  offset:0x{:02x}", layers, offset)
             }
-            Opcode::host_addr_data_dynamic => (offset_param, String::new()),
-        };
+            InstructionParams::BlockAlt {
+                type_index,
+                local_variable_list_index,
+                offset,
+            } => write!(
+                f,
+                "type:{:<2}  local:{:<2}  offset:0x{:02x}",
+                type_index, local_variable_list_index, offset
+            ),
+            InstructionParams::BreakAlt { offset } => write!(f, "offset:0x{:02x}", offset),
+            InstructionParams::BlockNez {
+                local_variable_list_index,
+                offset,
+            } => write!(
+                f,
+                "local:{:<2}  offset:0x{:02x}",
+                local_variable_list_index, offset
+            ),
+            InstructionParams::Code(code) => write!(f, "code:{}", code),
+        }
+    }
+}
 
-        // format!(...)
-        // https://doc.rust-lang.org/std/fmt/
+/// A decoded instruction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InstructionRecord<'a> {
+    pub offset: usize, // The offset of the instruction in the bytecode
+    pub opcode: Opcode,
+    pub params: InstructionParams,
+    pub data: &'a [u8], // The raw bytes of the instruction (including the opcode and padding)
+}
 
-        let mut line = format!("0x{:04x}  ", offset);
-        let addr_width = line.len();
+impl InstructionRecord<'_> {
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction::new(self.opcode, self.params)
+    }
+}
 
-        let inst_data = &codes[offset..offset_next];
-        let mut chunks = inst_data.chunks(8);
+/// An instruction without location, it can be written by
+/// `BytecodeWriter::write_instruction`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub params: InstructionParams,
+}
 
-        // format the bytes as the following text:
-        //
-        // 0x0006  08 04 03 00
-        // 0x000a  00 02 05 00  07 00 11 00
-        let print_binary = |data: &[u8]| {
-            data.iter()
-                .enumerate()
-                .map(|(idx, byte)| {
-                    if idx == 4 {
-                        format!("  {:02x}", byte)
-                    } else if idx == 0 {
-                        format!("{:02x}", byte)
-                    } else {
-                        format!(" {:02x}", byte)
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join("")
-        };
-
-        if param_text.is_empty() {
-            line.push_str(&format!(
-                "{:28}{}",
-                print_binary(chunks.next().unwrap()),
-                opcode.get_name()
-            ));
-        } else {
-            line.push_str(&format!(
-                "{:28}{:16}  {}",
-                print_binary(chunks.next().unwrap()),
-                opcode.get_name(),
-                param_text
-            ));
-        }
+impl Instruction {
+    pub fn new(opcode: Opcode, params: InstructionParams) -> Self {
+        Self { opcode, params }
+    }
+}
+
+/// Decodes the instruction at the specified offset, the layout of
+/// the operands is determined by the opcode metadata, see `opcode_info`.
+///
+/// Returns `(next_instruction_offset, opcode, params)`.
+///
+/// Panics if the opcode is unknown or the instruction is truncated, the code
+/// from untrusted images should be checked by `bytecode_verifier::verify_bytecode` first.
+pub fn decode_instruction(codes: &[u8], offset: usize) -> (usize, Opcode, InstructionParams) {
+    let opcode = read_opcode(codes, offset);
+    let info = get_opcode_info(opcode);
+
+    // an instruction has at most 3 operands.
+    let mut values = [0u32; 3];
+    for (idx, operand) in info.operands.iter().enumerate() {
+        values[idx] = read_operand(codes, offset + info.operand_offset(idx), operand.size());
+    }
 
-        lines.push(line);
+    let params = build_instruction_params(opcode, &values);
+    (offset + info.length(), opcode, params)
+}
 
-        let indent_text = " ".repeat(addr_width);
-        for chunk in chunks {
-            lines.push(format!("{}{}", indent_text, print_binary(chunk)));
+/// Builds the parameters of the instruction from the operand values, the values
+/// are in the order of the operands of the opcode (see `opcode_info`).
+///
+/// Panics if there are fewer values than the operands.
+pub fn build_instruction_params(opcode: Opcode, values: &[u32]) -> InstructionParams {
+    match get_opcode_info(opcode).operands {
+        [] => InstructionParams::None,
+        [OperandKind::Immediate] => InstructionParams::ImmI32(values[0]),
+        [OperandKind::Immediate, OperandKind::Immediate] => InstructionParams::ImmI64 {
+            low: values[0],
+            high: values[1],
+        },
+        [OperandKind::Layers, OperandKind::LocalVariableIndex] => InstructionParams::Local {
+            layers: values[0] as u16,
+            index: values[1],
+        },
+        [OperandKind::DataOffset, OperandKind::DataPublicIndex] => InstructionParams::Data {
+            offset: values[0] as u16,
+            index: values[1],
+        },
+        [OperandKind::DataPublicIndex
+        | OperandKind::FunctionPublicIndex
+        | OperandKind::ExternalFunctionIndex
+        | OperandKind::EnvCallNumber] => InstructionParams::Index(values[0]),
+        [OperandKind::Amount] => InstructionParams::Amount(values[0] as u16),
+        [OperandKind::TypeIndex, OperandKind::LocalVariableListIndex] => InstructionParams::Block {
+            type_index: values[0],
+            local_variable_list_index: values[1],
+        },
+        [OperandKind::Layers, OperandKind::JumpOffset] => InstructionParams::Break {
+            layers: values[0] as u16,
+            offset: values[1],
+        },
+        [OperandKind::TypeIndex, OperandKind::LocalVariableListIndex, OperandKind::JumpOffset] => {
+            InstructionParams::BlockAlt {
+                type_index: values[0],
+                local_variable_list_index: values[1],
+                offset: values[2],
+            }
         }
+        [OperandKind::JumpOffset] => InstructionParams::BreakAlt { offset: values[0] },
+        [OperandKind::LocalVariableListIndex, OperandKind::JumpOffset] => {
+            InstructionParams::BlockNez {
+                local_variable_list_index: values[0],
+                offset: values[1],
+            }
+        }
+        [OperandKind::TerminateCode] => InstructionParams::Code(values[0]),
+        _ => unreachable!(
+            "Unexpected operands of the instruction \"{}\".",
+            opcode.get_name()
+        ),
+    }
+}
+
+/// An iterator that decodes the bytecode one instruction at a time.
+pub struct InstructionIterator<'a> {
+    codes: &'a [u8],
+    offset: usize,
+}
 
-        // move on
-        offset = offset_next;
+impl<'a> InstructionIterator<'a> {
+    pub fn new(codes: &'a [u8]) -> Self {
+        Self { codes, offset: 0 }
     }
+}
+
+impl<'a> Iterator for InstructionIterator<'a> {
+    type Item = InstructionRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.codes.len() {
+            return None;
+        }
+
+        let offset = self.offset;
+        let (offset_next, opcode, params) = decode_instruction(self.codes, offset);
+        self.offset = offset_next;
 
-    lines.join("\n")
+        Some(InstructionRecord {
+            offset,
+            opcode,
+            params,
+            data: &self.codes[offset..offset_next],
+        })
+    }
 }
 
-// opcode, or
-// 16 bits instruction
-// [opcode]
-fn read_opcode(codes: &[u8], offset: usize) -> (usize, Opcode) {
-    let opcode_data = &codes[offset..offset + 2];
-    let opcode_u16 = u16::from_le_bytes(opcode_data.try_into().unwrap());
+/// An iterator that formats the bytecode one text line at a time,
+/// the lines are the same as the result of `format_bytecode_as_text`.
+///
+/// It is suitable for rendering large function listings lazily.
+pub struct TextLineIterator<'a> {
+    instructions: InstructionIterator<'a>,
+    pending_lines: std::vec::IntoIter<String>,
+}
 
-    (offset + 2, unsafe {
-        std::mem::transmute::<u16, Opcode>(opcode_u16)
-    })
+impl<'a> TextLineIterator<'a> {
+    pub fn new(codes: &'a [u8]) -> Self {
+        Self {
+            instructions: InstructionIterator::new(codes),
+            pending_lines: Vec::new().into_iter(),
+        }
+    }
 }
 
-// 32 bits instruction parameters
-// [opcode + i16]
-fn continue_read_param_i16(codes: &[u8], offset: usize) -> (usize, u16) {
-    let param_data0 = &codes[offset..offset + 2];
-    (
-        offset + 2,
-        u16::from_le_bytes(param_data0.try_into().unwrap()),
-    )
+impl Iterator for TextLineIterator<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(line) = self.pending_lines.next() {
+            return Some(line);
+        }
+
+        let record = self.instructions.next()?;
+        self.pending_lines = format_instruction_record_as_text(&record).into_iter();
+        self.pending_lines.next()
+    }
 }
 
-// 64 bits instruction parameters
-// [opcode + padding + i32]
-//
-// note that 'i32' in function name means a 32-bit integer, which is equivalent to
-// the 'uint32_t' in C or 'u32' in Rust. do not confuse it with 'i32' in Rust.
-// the same applies to the i8, i16 and i64.
-fn continue_read_param_i32(codes: &[u8], offset: usize) -> (usize, u32) {
-    let param_data0 = &codes[offset + 2..offset + 6];
-
-    (
-        offset + 6,
-        u32::from_le_bytes(param_data0.try_into().unwrap()),
-    )
+/// Formats the bytecode as text with instruction hex and corresponding instruction names.
+///
+/// Example output:
+/// ```text
+/// 0x0000  01 00                       instruction_name
+/// 0x0002  02 00 11 00                 instruction_name parameter
+/// 0x0006  03 00 13 00 17 00 00 00     instruction_name parameter_0 parameter_1
+/// ```
+pub fn format_bytecode_as_text(codes: &[u8]) -> String {
+    TextLineIterator::new(codes)
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-// 64 bits instruction parameters
-// [opcode + i16 + i32]
-fn continue_read_param_i16_i32(codes: &[u8], offset: usize) -> (usize, u16, u32) {
-    let param_data0 = &codes[offset..offset + 2];
-    let param_data1 = &codes[offset + 2..offset + 6];
-
-    (
-        offset + 6,
-        u16::from_le_bytes(param_data0.try_into().unwrap()),
-        u32::from_le_bytes(param_data1.try_into().unwrap()),
-    )
+// An instruction longer than 8 bytes is formatted as multiple lines.
+fn format_instruction_record_as_text(record: &InstructionRecord) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    // format!(...)
+    // https://doc.rust-lang.org/std/fmt/
+
+    let mut line = format!("0x{:04x}  ", record.offset);
+    let addr_width = line.len();
+
+    let mut chunks = record.data.chunks(8);
+
+    // format the bytes as the following text:
+    //
+    // 0x0006  08 04 03 00
+    // 0x000a  00 02 05 00  07 00 11 00
+    let print_binary = |data: &[u8]| {
+        data.iter()
+            .enumerate()
+            .map(|(idx, byte)| {
+                if idx == 4 {
+                    format!("  {:02x}", byte)
+                } else if idx == 0 {
+                    format!("{:02x}", byte)
+                } else {
+                    format!(" {:02x}", byte)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    };
+
+    if get_opcode_info(record.opcode).operands.is_empty() {
+        line.push_str(&format!(
+            "{:28}{}",
+            print_binary(chunks.next().unwrap()),
+            record.opcode.get_name()
+        ));
+    } else {
+        line.push_str(&format!(
+            "{:28}{:16}  {}",
+            print_binary(chunks.next().unwrap()),
+            record.opcode.get_name(),
+            record.params
+        ));
+    }
+
+    lines.push(line);
+
+    let indent_text = " ".repeat(addr_width);
+    for chunk in chunks {
+        lines.push(format!("{}{}", indent_text, print_binary(chunk)));
+    }
+
+    lines
 }
 
-// 96 bits instruction parameters
-// [opcode + padding + i32 + i32]
-fn continue_read_param_i32_i32(codes: &[u8], offset: usize) -> (usize, u32, u32) {
-    let param_data0 = &codes[offset + 2..offset + 6];
-    let param_data1 = &codes[offset + 6..offset + 10];
-
-    (
-        offset + 10,
-        u32::from_le_bytes(param_data0.try_into().unwrap()),
-        u32::from_le_bytes(param_data1.try_into().unwrap()),
-    )
+// 16 bits opcode
+//
+// Panics if the value is not a known opcode, the code from untrusted images
+// should be checked by `bytecode_verifier::verify_bytecode` first.
+fn read_opcode(codes: &[u8], offset: usize) -> Opcode {
+    let opcode_data = &codes[offset..offset + 2];
+    let opcode_u16 = u16::from_le_bytes(opcode_data.try_into().unwrap());
+
+    find_opcode_by_value(opcode_u16).unwrap_or_else(|| {
+        panic!(
+            "Unknown opcode 0x{:04x} at offset 0x{:04x}.",
+            opcode_u16, offset
+        )
+    })
 }
 
-// 128 bits instruction parameters
-// [opcode + padding + i32 + i32 + i32]
-fn continue_read_param_i32_i32_i32(codes: &[u8], offset: usize) -> (usize, u32, u32, u32) {
-    let param_data0 = &codes[offset + 2..offset + 6];
-    let param_data1 = &codes[offset + 6..offset + 10];
-    let param_data2 = &codes[offset + 10..offset + 14];
-
-    (
-        offset + 14,
-        u32::from_le_bytes(param_data0.try_into().unwrap()),
-        u32::from_le_bytes(param_data1.try_into().unwrap()),
-        u32::from_le_bytes(param_data2.try_into().unwrap()),
-    )
+// 16 bits or 32 bits operand
+//
+// note that the operands are unsigned integers, i.e., the 'i32' operand is
+// equivalent to the 'uint32_t' in C or 'u32' in Rust.
+fn read_operand(codes: &[u8], offset: usize, size: usize) -> u32 {
+    let operand_data = &codes[offset..offset + size];
+    if size == 2 {
+        u16::from_le_bytes(operand_data.try_into().unwrap()) as u32
+    } else {
+        u32::from_le_bytes(operand_data.try_into().unwrap())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroUsize;
+
     use anc_isa::opcode::Opcode;
     use pretty_assertions::assert_eq;
 
     use crate::{
-        bytecode_reader::{format_bytecode_as_binary, format_bytecode_as_text},
+        bytecode_reader::{
+            format_bytecode_as_binary, format_bytecode_as_binary_with_options,
+            format_bytecode_as_text, BinaryFormatOptions, InstructionIterator, InstructionParams,
+            InstructionRecord, TextLineIterator,
+        },
         bytecode_writer::BytecodeWriterHelper,
     };
 
@@ -603,6 +549,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_print_bytecodes_as_binary_with_options() {
+        let data = (0u8..14).collect::<Vec<u8>>();
+
+        let text = format_bytecode_as_binary_with_options(
+            &data,
+            &BinaryFormatOptions {
+                base_address: 0x100,
+                bytes_per_row: NonZeroUsize::new(6).unwrap(),
+                bytes_per_group: 2,
+            },
+        );
+
+        assert_eq!(
+            text,
+            "\
+0x0100  00 01  02 03  04 05
+0x0106  06 07  08 09  0a 0b
+0x010c  0c 0d"
+        );
+
+        let text = format_bytecode_as_binary_with_options(
+            &data,
+            &BinaryFormatOptions {
+                base_address: 0x20,
+                bytes_per_row: NonZeroUsize::new(16).unwrap(),
+                bytes_per_group: 0,
+            },
+        );
+
+        assert_eq!(text, "0x0020  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d");
+    }
+
     #[test]
     fn test_print_bytecodes_as_text() {
         let data = BytecodeWriterHelper::new()
@@ -668,4 +647,92 @@ mod tests {
 0x0088  02 02 43 00  47 00 00 00    local_load_i32_u  layers:67  index:71"
         )
     }
+
+    #[test]
+    fn test_instruction_iterator() {
+        let data = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i32(Opcode::imm_i32, 0x13)
+            .append_opcode_i16(Opcode::add_imm_i32, 0x2)
+            .append_opcode_i32_i32(Opcode::block, 0x23, 0x29)
+            .to_bytes();
+
+        let records = InstructionIterator::new(&data).collect::<Vec<InstructionRecord>>();
+
+        assert_eq!(
+            records,
+            vec![
+                InstructionRecord {
+                    offset: 0,
+                    opcode: Opcode::eqz_i32,
+                    params: InstructionParams::None,
+                    data: &data[0..2],
+                },
+                InstructionRecord {
+                    offset: 2,
+                    opcode: Opcode::nop,
+                    params: InstructionParams::None,
+                    data: &data[2..4],
+                },
+                InstructionRecord {
+                    offset: 4,
+                    opcode: Opcode::imm_i32,
+                    params: InstructionParams::ImmI32(0x13),
+                    data: &data[4..12],
+                },
+                InstructionRecord {
+                    offset: 12,
+                    opcode: Opcode::add_imm_i32,
+                    params: InstructionParams::Amount(0x2),
+                    data: &data[12..16],
+                },
+                InstructionRecord {
+                    offset: 16,
+                    opcode: Opcode::block,
+                    params: InstructionParams::Block {
+                        type_index: 0x23,
+                        local_variable_list_index: 0x29
+                    },
+                    data: &data[16..28],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_line_iterator() {
+        let data = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i32_i32_i32(Opcode::block_alt, 0x31, 0x37, 0x41)
+            .append_opcode_i16(Opcode::add_imm_i32, 0x2)
+            .to_bytes();
+
+        let mut lines = TextLineIterator::new(&data);
+
+        assert_eq!(
+            lines.next().unwrap(),
+            "0x0000  00 08                       eqz_i32"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "0x0002  00 01                       nop"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "0x0004  04 09 00 00  31 00 00 00    block_alt         type:49  local:55  offset:0x41"
+        );
+        assert_eq!(lines.next().unwrap(), "        37 00 00 00  41 00 00 00");
+        assert_eq!(
+            lines.next().unwrap(),
+            "0x0014  02 04 02 00                 add_imm_i32       2"
+        );
+        assert_eq!(lines.next(), None);
+
+        assert_eq!(
+            TextLineIterator::new(&data)
+                .collect::<Vec<String>>()
+                .join("\n"),
+            format_bytecode_as_text(&data)
+        );
+    }
 }
@@ -0,0 +1,304 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Searches the references of functions and data in the bytecode of all functions.
+//
+// The "Relocate Section" lists the location of every `function_public_index`
+// and `data_public_index` parameter in the bytecode, so it is used
+// when present. Otherwise the bytecode is decoded instruction by instruction.
+//
+// Each reference site is represented by a pair
+// `(function_internal_index, instruction_offset)`, where the
+// `instruction_offset` is the offset of the referencing instruction
+// within the function code (not the offset of the parameter).
+//
+// A relocate section which is malformed or does not match the function
+// section is ignored (i.e., the bytecode is decoded instead), and the
+// relocate entries whose offsets are out of the function code are skipped.
+
+use anc_isa::opcode::Opcode;
+
+use crate::{
+    bytecode_reader::{InstructionIterator, InstructionParams},
+    module_image::{ModuleImage, RelocateType},
+    ImageError,
+};
+
+// All instructions that contain a relocatable `i32` parameter place it
+// at the offset `instruction_address + 4`, see `RelocateEntry::from_*`.
+const RELOCATABLE_PARAM_OFFSET_IN_INSTRUCTION: usize = 4;
+
+/// Finds all instructions that reference the specified function,
+/// i.e., `call`, `get_function` and `host_addr_function`.
+///
+/// Returns a list of `(function_internal_index, instruction_offset)`, or an error
+/// if the function section is missing or corrupted.
+pub fn find_callers(
+    image: &ModuleImage,
+    function_public_index: usize,
+) -> Result<Vec<(usize, usize)>, ImageError> {
+    find_references(
+        image,
+        RelocateType::FunctionPublicIndex,
+        function_public_index,
+        |opcode, params| match (opcode, params) {
+            (
+                Opcode::call | Opcode::get_function | Opcode::host_addr_function,
+                InstructionParams::Index(index),
+            ) => Some(index),
+            _ => None,
+        },
+    )
+}
+
+/// Finds all instructions that reference the specified data,
+/// i.e., `data_load_*`, `data_store_*`, `data_load_extend_*`, `data_store_extend_*`,
+/// `get_data`, `host_addr_data` and `host_addr_data_extend`.
+///
+/// Returns a list of `(function_internal_index, instruction_offset)`, or an error
+/// if the function section is missing or corrupted.
+pub fn find_data_references(
+    image: &ModuleImage,
+    data_public_index: usize,
+) -> Result<Vec<(usize, usize)>, ImageError> {
+    find_references(
+        image,
+        RelocateType::DataPublicIndex,
+        data_public_index,
+        |opcode, params| match (opcode, params) {
+            // data_load_*, data_store_*, host_addr_data
+            (_, InstructionParams::Data { index, .. }) => Some(index),
+            // data_load_extend_*, data_store_extend_*, get_data, host_addr_data_extend
+            (
+                Opcode::data_load_extend_i64
+                | Opcode::data_load_extend_i32_s
+                | Opcode::data_load_extend_i32_u
+                | Opcode::data_load_extend_i16_s
+                | Opcode::data_load_extend_i16_u
+                | Opcode::data_load_extend_i8_s
+                | Opcode::data_load_extend_i8_u
+                | Opcode::data_load_extend_f64
+                | Opcode::data_load_extend_f32
+                | Opcode::data_store_extend_i64
+                | Opcode::data_store_extend_i32
+                | Opcode::data_store_extend_i16
+                | Opcode::data_store_extend_i8
+                | Opcode::data_store_extend_f64
+                | Opcode::data_store_extend_f32
+                | Opcode::get_data
+                | Opcode::host_addr_data_extend,
+                InstructionParams::Index(index),
+            ) => Some(index),
+            _ => None,
+        },
+    )
+}
+
+fn find_references(
+    image: &ModuleImage,
+    relocate_type: RelocateType,
+    expected_index: usize,
+    get_index_from_instruction: impl Fn(Opcode, InstructionParams) -> Option<u32>,
+) -> Result<Vec<(usize, usize)>, ImageError> {
+    let function_section = image.try_get_function_section()?;
+    let opt_relocate_section = image
+        .try_get_optional_relocate_section()
+        .ok()
+        .flatten()
+        .filter(|section| section.lists.len() == function_section.items.len());
+
+    let mut sites: Vec<(usize, usize)> = vec![];
+
+    for (function_internal_index, item) in function_section.items.iter().enumerate() {
+        let code = &function_section.codes_data
+            [item.code_offset as usize..(item.code_offset + item.code_length) as usize];
+
+        match &opt_relocate_section {
+            Some(relocate_section) => {
+                for relocate_item in relocate_section.get_relocate_list(function_internal_index) {
+                    if relocate_item.relocate_type != relocate_type {
+                        continue;
+                    }
+
+                    let param_offset = relocate_item.offset_in_function as usize;
+                    let Some(param_data) = code.get(param_offset..param_offset + 4) else {
+                        continue;
+                    };
+                    let index = u32::from_le_bytes(param_data.try_into().unwrap());
+
+                    if index as usize == expected_index
+                        && param_offset >= RELOCATABLE_PARAM_OFFSET_IN_INSTRUCTION
+                    {
+                        sites.push((
+                            function_internal_index,
+                            param_offset - RELOCATABLE_PARAM_OFFSET_IN_INSTRUCTION,
+                        ));
+                    }
+                }
+            }
+            None => {
+                for record in InstructionIterator::new(code) {
+                    if get_index_from_instruction(record.opcode, record.params)
+                        .is_some_and(|index| index as usize == expected_index)
+                    {
+                        sites.push((function_internal_index, record.offset));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(sites)
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_search::{find_callers, find_data_references},
+        bytecode_writer::BytecodeWriterHelper,
+        common_sections::{function_section::FunctionSection, relocate_section::RelocateSection},
+        entry::{FunctionEntry, RelocateEntry, RelocateListEntry},
+        module_image::{ImageType, ModuleImage, ModuleSectionId, SectionEntry},
+        ImageErrorType,
+    };
+
+    fn build_function_entries() -> Vec<FunctionEntry> {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1) // 0x0000
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 3) // 0x0008
+            .append_opcode_i32(Opcode::get_data, 5) // 0x0010
+            .append_opcode(Opcode::end) // 0x0018
+            .to_bytes();
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::nop) // 0x0000
+            .append_opcode_i32(Opcode::call, 1) // 0x0004
+            .append_opcode_i32(Opcode::data_load_extend_i64, 3) // 0x000c
+            .append_opcode_i32(Opcode::imm_i32, 1) // 0x0014
+            .append_opcode(Opcode::end) // 0x001c
+            .to_bytes();
+
+        vec![
+            FunctionEntry::new(0, 0, code0),
+            FunctionEntry::new(0, 0, code1),
+        ]
+    }
+
+    #[test]
+    fn test_find_references_by_decoding() {
+        let (function_items, codes_data) =
+            FunctionSection::convert_from_entries(&build_function_entries());
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&function_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(find_callers(&image, 1).unwrap(), vec![(0, 0), (1, 4)]);
+        assert_eq!(find_callers(&image, 0).unwrap(), vec![]);
+        assert_eq!(
+            find_data_references(&image, 3).unwrap(),
+            vec![(0, 8), (1, 0xc)]
+        );
+        assert_eq!(find_data_references(&image, 5).unwrap(), vec![(0, 0x10)]);
+    }
+
+    #[test]
+    fn test_find_references_by_relocate_list() {
+        let (function_items, codes_data) =
+            FunctionSection::convert_from_entries(&build_function_entries());
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let relocate_list_entries = vec![
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0),
+                RelocateEntry::from_data_public_index(8),
+                RelocateEntry::from_data_public_index(0x10),
+            ]),
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(4),
+                RelocateEntry::from_data_public_index(0xc),
+            ]),
+        ];
+
+        let (relocate_lists, relocate_list_data) =
+            RelocateSection::convert_from_entries(&relocate_list_entries);
+        let relocate_section = RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&function_section, &relocate_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(find_callers(&image, 1).unwrap(), vec![(0, 0), (1, 4)]);
+        assert_eq!(
+            find_data_references(&image, 3).unwrap(),
+            vec![(0, 8), (1, 0xc)]
+        );
+        assert_eq!(find_data_references(&image, 5).unwrap(), vec![(0, 0x10)]);
+
+        // the `imm_i32` in function 1 is not listed in the relocate list
+        assert_eq!(find_data_references(&image, 1).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_find_references_with_invalid_sections() {
+        let (function_items, codes_data) =
+            FunctionSection::convert_from_entries(&build_function_entries());
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        // the offset of the stale relocate entry is out of the function code
+        let relocate_list_entries = vec![
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0),
+                RelocateEntry::from_function_public_index(0x100),
+            ]),
+            RelocateListEntry::new(vec![]),
+        ];
+
+        let (relocate_lists, relocate_list_data) =
+            RelocateSection::convert_from_entries(&relocate_list_entries);
+        let relocate_section = RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&function_section, &relocate_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(find_callers(&image, 1).unwrap(), vec![(0, 0)]);
+
+        // the function section is missing
+        let section_entries: Vec<&dyn SectionEntry> = vec![&relocate_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let error = find_callers(&image, 1).unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::MissingSection(ModuleSectionId::Function)
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Generates the bodies of the small standard functions (i.e., "stubs") from
+// templates, e.g., the lazy-binding thunks of the linker and the mock functions
+// of the testing tools.
+//
+// The templates are:
+//
+// - forwarding call: passes all arguments to another function (or an external
+//   function) and returns its results, e.g.,
+//
+//   ```text
+//   local_load_i32_u 0 0
+//   local_load_i64   0 1
+//   call             function_public_index
+//   end
+//   ```
+//
+// - returning constants: returns the specified values, the arguments are ignored.
+// - trap: terminates the program with the specified code.
+//
+// A stub has the same signature as the specified type, and its local variable
+// list contains only the arguments.
+
+use anc_isa::{opcode::Opcode, OperandDataType};
+
+use crate::{
+    bytecode_writer::BytecodeWriter,
+    entry::{LocalVariableListEntry, RelocateEntry, RelocateListEntry, TypeEntry},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum StubTemplate {
+    // Calls the function with the arguments and returns its results.
+    ForwardCall { function_public_index: usize },
+
+    // Calls the external function with the arguments and returns its results.
+    ForwardExternalCall { external_function_index: usize },
+
+    // Returns the values, they are the raw bits of the results in order,
+    // e.g., `f32` values are converted by `f32::to_bits`.
+    // The missing values are zero.
+    ReturnConstants(Vec<u64>),
+
+    // Terminates the program with the code.
+    Trap(u32),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StubFunction {
+    pub code: Vec<u8>,
+    pub local_variable_list_entry: LocalVariableListEntry,
+    pub relocate_list_entry: RelocateListEntry,
+}
+
+/// Generates the stub function with the signature `type_entry` from the template.
+pub fn build_stub_function(type_entry: &TypeEntry, template: &StubTemplate) -> StubFunction {
+    let mut writer = BytecodeWriter::new();
+    let mut relocate_entries = vec![];
+
+    match template {
+        StubTemplate::ForwardCall {
+            function_public_index,
+        } => {
+            write_load_arguments(&mut writer, &type_entry.params);
+            let inst_addr = writer.write_opcode_i32(Opcode::call, *function_public_index as u32);
+            relocate_entries.push(RelocateEntry::from_function_public_index(inst_addr));
+        }
+        StubTemplate::ForwardExternalCall {
+            external_function_index,
+        } => {
+            write_load_arguments(&mut writer, &type_entry.params);
+            let inst_addr =
+                writer.write_opcode_i32(Opcode::extcall, *external_function_index as u32);
+            relocate_entries.push(RelocateEntry::from_external_function_index(inst_addr));
+        }
+        StubTemplate::ReturnConstants(values) => {
+            for (idx, data_type) in type_entry.results.iter().enumerate() {
+                let value = values.get(idx).copied().unwrap_or(0);
+                match data_type {
+                    OperandDataType::I32 => writer.write_opcode_i32(Opcode::imm_i32, value as u32),
+                    OperandDataType::I64 => writer.write_opcode_i64(Opcode::imm_i64, value),
+                    OperandDataType::F32 => writer.write_opcode_i32(Opcode::imm_f32, value as u32),
+                    OperandDataType::F64 => writer.write_opcode_i64(Opcode::imm_f64, value),
+                };
+            }
+        }
+        StubTemplate::Trap(code) => {
+            writer.write_opcode_i32(Opcode::terminate, *code);
+        }
+    }
+
+    writer.write_opcode(Opcode::end);
+
+    StubFunction {
+        code: writer.to_bytes(),
+        local_variable_list_entry: LocalVariableListEntry::new(type_entry.params.clone()),
+        relocate_list_entry: RelocateListEntry::new(relocate_entries),
+    }
+}
+
+// Pushes all arguments onto the operand stack.
+fn write_load_arguments(writer: &mut BytecodeWriter, params: &[OperandDataType]) {
+    for (idx, data_type) in params.iter().enumerate() {
+        let opcode = match data_type {
+            OperandDataType::I32 => Opcode::local_load_i32_u,
+            OperandDataType::I64 => Opcode::local_load_i64,
+            OperandDataType::F32 => Opcode::local_load_f32,
+            OperandDataType::F64 => Opcode::local_load_f64,
+        };
+        writer.write_opcode_i16_i32(opcode, 0, idx as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_template::{build_stub_function, StubFunction, StubTemplate},
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{LocalVariableListEntry, RelocateEntry, RelocateListEntry, TypeEntry},
+    };
+
+    #[test]
+    fn test_build_stub_function() {
+        let type_entry = TypeEntry::new(
+            vec![OperandDataType::I32, OperandDataType::F64],
+            vec![OperandDataType::I64, OperandDataType::F32],
+        );
+        let local_variable_list_entry =
+            LocalVariableListEntry::new(vec![OperandDataType::I32, OperandDataType::F64]);
+
+        assert_eq!(
+            build_stub_function(
+                &type_entry,
+                &StubTemplate::ForwardCall {
+                    function_public_index: 7
+                }
+            ),
+            StubFunction {
+                code: BytecodeWriterHelper::new()
+                    .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0) // 0x0000
+                    .append_opcode_i16_i32(Opcode::local_load_f64, 0, 1) // 0x0008
+                    .append_opcode_i32(Opcode::call, 7) // 0x0010
+                    .append_opcode(Opcode::end) // 0x0018
+                    .to_bytes(),
+                local_variable_list_entry: local_variable_list_entry.clone(),
+                relocate_list_entry: RelocateListEntry::new(vec![
+                    RelocateEntry::from_function_public_index(0x10)
+                ]),
+            }
+        );
+
+        assert_eq!(
+            build_stub_function(
+                &TypeEntry::new(vec![], vec![]),
+                &StubTemplate::ForwardExternalCall {
+                    external_function_index: 3
+                }
+            ),
+            StubFunction {
+                code: BytecodeWriterHelper::new()
+                    .append_opcode_i32(Opcode::extcall, 3)
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+                local_variable_list_entry: LocalVariableListEntry::new(vec![]),
+                relocate_list_entry: RelocateListEntry::new(vec![
+                    RelocateEntry::from_external_function_index(0)
+                ]),
+            }
+        );
+
+        // the second value is missing
+        assert_eq!(
+            build_stub_function(&type_entry, &StubTemplate::ReturnConstants(vec![11])),
+            StubFunction {
+                code: BytecodeWriterHelper::new()
+                    .append_opcode_i64(Opcode::imm_i64, 11)
+                    .append_opcode_i32(Opcode::imm_f32, 0)
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+                local_variable_list_entry: local_variable_list_entry.clone(),
+                relocate_list_entry: RelocateListEntry::new(vec![]),
+            }
+        );
+
+        assert_eq!(
+            build_stub_function(&type_entry, &StubTemplate::Trap(0xdead)),
+            StubFunction {
+                code: BytecodeWriterHelper::new()
+                    .append_opcode_i32(Opcode::terminate, 0xdead)
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+                local_variable_list_entry,
+                relocate_list_entry: RelocateListEntry::new(vec![]),
+            }
+        );
+    }
+}
@@ -0,0 +1,836 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Transformations on the bytecode of a single function.
+//
+// The bytecode is always accompanied by its relocate list, because
+// the relocate list records the location of the indices in the
+// bytecode, and it must be kept in sync with the bytecode.
+
+use std::{collections::HashMap, fmt::Display};
+
+use anc_isa::opcode::Opcode;
+
+use crate::{
+    bytecode_reader::{Instruction, InstructionIterator, InstructionParams},
+    bytecode_writer::BytecodeWriter,
+    entry::{RelocateEntry, RelocateListEntry},
+    module_image::RelocateType,
+    ImageError, ImageErrorType,
+};
+
+// The `i32` parameter of `call` and `extcall` is located at the offset
+// `instruction_address + 4`. Note that it is not true for all relocatable
+// parameters, e.g., the second parameter `local_variable_list_index` of
+// `block` is located at the offset `instruction_address + 8`.
+const CALL_PARAM_OFFSET_IN_INSTRUCTION: usize = 4;
+
+// The reasons why the indices can not be retargeted by `retarget_indices`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RetargetError {
+    OffsetOutOfBounds {
+        param_offset: usize,
+    },
+
+    // Only the types `FunctionPublicIndex` and `ExternalFunctionIndex`
+    // can be changed to each other.
+    RelocateTypeUnchangeable {
+        param_offset: usize,
+        relocate_type: RelocateType,
+        new_relocate_type: RelocateType,
+    },
+
+    // The relocate offset does not point to the index of the expected
+    // `call` (or `extcall`) instruction.
+    UnexpectedInstruction {
+        param_offset: usize,
+        expected_opcode: Opcode,
+    },
+}
+
+impl Display for RetargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetargetError::OffsetOutOfBounds { param_offset } => write!(
+                f,
+                "The relocate offset 0x{:04x} is out of the bounds of the bytecode.",
+                param_offset
+            ),
+            RetargetError::RelocateTypeUnchangeable {
+                param_offset,
+                relocate_type,
+                new_relocate_type,
+            } => write!(
+                f,
+                "Cannot change the relocate type {:?} to {:?} at offset 0x{:04x}.",
+                relocate_type, new_relocate_type, param_offset
+            ),
+            RetargetError::UnexpectedInstruction {
+                param_offset,
+                expected_opcode,
+            } => write!(
+                f,
+                "The relocate offset 0x{:04x} is not the index of a \"{}\" instruction.",
+                param_offset,
+                expected_opcode.get_name()
+            ),
+        }
+    }
+}
+
+/// Rewrites the indices in the bytecode according to the map
+/// `(relocate_type, old_index) -> (relocate_type, new_index)`, and updates
+/// the relocate entries accordingly.
+///
+/// Only the locations listed in the relocate list are rewritten.
+///
+/// The relocate type can only be changed between `FunctionPublicIndex`
+/// and `ExternalFunctionIndex` for the `call` and `extcall` instructions,
+/// in this case the opcode is changed as well, e.g., a `call` is redirected
+/// to an `extcall`.
+///
+/// Returns the number of rewritten indices, or an error if a relocate offset
+/// is out of the bounds of the bytecode or a relocate type cannot be changed,
+/// in which case neither the bytecode nor the relocate list is modified.
+pub fn retarget_indices(
+    code: &mut [u8],
+    relocate_list: &mut RelocateListEntry,
+    map: &HashMap<(RelocateType, usize), (RelocateType, usize)>,
+) -> Result<usize, ImageError> {
+    // The changes are collected before modifying anything,
+    // `(relocate_entry_index, new_relocate_type, new_index, opt_new_opcode)`.
+    let mut changes: Vec<(usize, RelocateType, usize, Option<Opcode>)> = vec![];
+
+    for (idx, relocate_entry) in relocate_list.relocate_entries.iter().enumerate() {
+        let param_offset = relocate_entry.offset_in_function;
+        let Some(param_data) = code.get(param_offset..(param_offset.saturating_add(4))) else {
+            return Err(ImageError::new(ImageErrorType::Retarget(
+                RetargetError::OffsetOutOfBounds { param_offset },
+            )));
+        };
+
+        let old_index = u32::from_le_bytes(param_data.try_into().unwrap()) as usize;
+        let Some((new_relocate_type, new_index)) =
+            map.get(&(relocate_entry.relocate_type, old_index))
+        else {
+            continue;
+        };
+
+        let opt_new_opcode = if *new_relocate_type == relocate_entry.relocate_type {
+            None
+        } else {
+            Some(get_retargeted_call_opcode(
+                code,
+                param_offset,
+                relocate_entry.relocate_type,
+                *new_relocate_type,
+            )?)
+        };
+
+        changes.push((idx, *new_relocate_type, *new_index, opt_new_opcode));
+    }
+
+    for (idx, new_relocate_type, new_index, opt_new_opcode) in &changes {
+        let relocate_entry = &mut relocate_list.relocate_entries[*idx];
+        let param_offset = relocate_entry.offset_in_function;
+
+        if let Some(new_opcode) = opt_new_opcode {
+            let inst_addr = param_offset - CALL_PARAM_OFFSET_IN_INSTRUCTION;
+            code[inst_addr..inst_addr + 2].copy_from_slice(&(*new_opcode as u16).to_le_bytes());
+            relocate_entry.relocate_type = *new_relocate_type;
+        }
+
+        code[param_offset..param_offset + 4].copy_from_slice(&(*new_index as u32).to_le_bytes());
+    }
+
+    Ok(changes.len())
+}
+
+// Returns the new opcode for changing the relocate type of the `call` (or `extcall`)
+// instruction whose index is located at `param_offset`.
+//
+// The relocate types are checked before reading the opcode, since only
+// the index of `call` and `extcall` is located at `instruction_address + 4`.
+fn get_retargeted_call_opcode(
+    code: &[u8],
+    param_offset: usize,
+    relocate_type: RelocateType,
+    new_relocate_type: RelocateType,
+) -> Result<Opcode, ImageError> {
+    let (expected_opcode, new_opcode) = match (relocate_type, new_relocate_type) {
+        (RelocateType::FunctionPublicIndex, RelocateType::ExternalFunctionIndex) => {
+            (Opcode::call, Opcode::extcall)
+        }
+        (RelocateType::ExternalFunctionIndex, RelocateType::FunctionPublicIndex) => {
+            (Opcode::extcall, Opcode::call)
+        }
+        _ => {
+            return Err(ImageError::new(ImageErrorType::Retarget(
+                RetargetError::RelocateTypeUnchangeable {
+                    param_offset,
+                    relocate_type,
+                    new_relocate_type,
+                },
+            )))
+        }
+    };
+
+    // The opcode is read directly rather than decoding the instruction,
+    // because the relocate offset is not trusted.
+    let is_expected_opcode = param_offset
+        .checked_sub(CALL_PARAM_OFFSET_IN_INSTRUCTION)
+        .and_then(|inst_addr| code.get(inst_addr..inst_addr + 2))
+        .is_some_and(|data| u16::from_le_bytes(data.try_into().unwrap()) == expected_opcode as u16);
+
+    if is_expected_opcode {
+        Ok(new_opcode)
+    } else {
+        Err(ImageError::new(ImageErrorType::Retarget(
+            RetargetError::UnexpectedInstruction {
+                param_offset,
+                expected_opcode,
+            },
+        )))
+    }
+}
+
+/// Removes the unreachable instructions which follow an unconditional
+/// `break`, `recur` or `terminate`, until the `break_alt` or `end` of
+/// the current block (or the function).
+///
+/// The jump offsets and the relocate list are updated accordingly.
+///
+/// Returns the number of removed instructions, or an error if the remaining
+/// instructions can not be encoded, in which case the bytecode is unchanged.
+pub fn trim_unreachable_code(
+    code: &mut Vec<u8>,
+    relocate_list: &mut RelocateListEntry,
+) -> Result<usize, ImageError> {
+    let items = decode_function_instructions(code, relocate_list);
+    let item_count = items.len();
+
+    let mut items_new: Vec<InstructionItem> = Vec::with_capacity(item_count);
+
+    // The depth of the nested blocks within the unreachable region,
+    // `None` means the current instruction is reachable.
+    let mut opt_unreachable_depth: Option<usize> = None;
+
+    for item in items {
+        match opt_unreachable_depth {
+            None => {
+                if matches!(
+                    item.opcode,
+                    Opcode::break_ | Opcode::recur | Opcode::terminate
+                ) {
+                    opt_unreachable_depth = Some(0);
+                }
+                items_new.push(item);
+            }
+            Some(depth) => match item.opcode {
+                Opcode::block | Opcode::block_alt | Opcode::block_nez => {
+                    opt_unreachable_depth = Some(depth + 1);
+                }
+                Opcode::end if depth > 0 => {
+                    opt_unreachable_depth = Some(depth - 1);
+                }
+                Opcode::end | Opcode::break_alt if depth == 0 => {
+                    // The `end` of the current block is the target of `break`,
+                    // and the instruction following `break_alt` is the target of `block_alt`,
+                    // so they are reachable.
+                    opt_unreachable_depth = None;
+                    items_new.push(item);
+                }
+                _ => {
+                    // unreachable
+                }
+            },
+        }
+    }
+
+    let removed_count = item_count - items_new.len();
+    if removed_count > 0 {
+        let (code_new, relocate_list_new) = encode_function_instructions(&items_new)?;
+        *code = code_new;
+        *relocate_list = relocate_list_new;
+    }
+
+    Ok(removed_count)
+}
+
+/// Re-lays out the function bytecode with minimal alignment padding,
+/// the jump offsets and the relocate list are updated accordingly.
+///
+/// This pass is used to normalize the bytecode which is modified by other
+/// tools, where the alignment `nop`s may become redundant or missing.
+///
+/// Returns `true` if the bytecode is changed, or an error if the instructions
+/// can not be encoded, in which case the bytecode is unchanged.
+pub fn realign_function_code(
+    code: &mut Vec<u8>,
+    relocate_list: &mut RelocateListEntry,
+) -> Result<bool, ImageError> {
+    let items = decode_function_instructions(code, relocate_list);
+
+    // The `decode_function_instructions` only recognizes the padding
+    // at the unaligned address, when the bytecode is shifted by 2 bytes,
+    // the padding is located at the aligned address, and the following
+    // instruction is unaligned.
+    let items = items
+        .iter()
+        .enumerate()
+        .filter(|(idx, item)| {
+            let is_padding = item.opcode == Opcode::nop
+                && items.get(idx + 1).is_some_and(|next_item| {
+                    has_i32_params(&next_item.params)
+                        && next_item.origin_address.is_some_and(|addr| addr % 4 != 0)
+                });
+            !is_padding
+        })
+        .map(|(_, item)| item.clone())
+        .collect::<Vec<_>>();
+
+    let (code_new, relocate_list_new) = encode_function_instructions(&items)?;
+
+    if code_new == *code && relocate_list_new == *relocate_list {
+        return Ok(false);
+    }
+
+    *code = code_new;
+    *relocate_list = relocate_list_new;
+    Ok(true)
+}
+
+// Instructions with 'i32' parameters must be 4-byte aligned.
+fn has_i32_params(params: &InstructionParams) -> bool {
+    !matches!(
+        params,
+        InstructionParams::None | InstructionParams::Amount(_)
+    )
+}
+
+// About the instruction list
+// --------------------------
+//
+// Transformations that insert, remove or replace instructions change the
+// address of the following instructions, so the alignment padding, the
+// jump offsets (i.e., the "next_inst_offset" of `block_alt`, `block_nez`,
+// `break` and `break_alt`, and the "start_inst_offset" of `recur`) and
+// the relocate list must be regenerated.
+//
+// To do this, the bytecode is decoded into a list of `InstructionItem`
+// (the alignment `nop`s are dropped), and the jump offsets are converted
+// into the absolute addresses of the targets in the original bytecode.
+// After the transformation, the list is encoded again with minimal padding,
+// and the jump targets are mapped to the new addresses.
+//
+// If the target of a jump is removed, it is mapped to the next remaining
+// instruction (or the end of the function).
+//
+// Note that the jump offsets are relative to the address of the jump
+// instruction itself, a "break" or "recur" whose target layer is the
+// function has the offset `0` and it is kept as `0`.
+
+/// An instruction which can be re-encoded at a different address.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InstructionItem {
+    pub opcode: Opcode,
+    pub params: InstructionParams,
+
+    // The relocatable parameters, `(offset_in_instruction, relocate_type)`.
+    pub relocates: Vec<(usize, RelocateType)>,
+
+    // The address of the instruction in the original bytecode,
+    // it is `None` for the inserted instructions.
+    pub origin_address: Option<usize>,
+
+    // The address of the jump target in the original bytecode.
+    // Only available for `block_alt`, `block_nez`, `break`, `break_alt` and `recur`.
+    pub origin_jump_target: Option<usize>,
+}
+
+impl InstructionItem {
+    /// Creates a new instruction (which does not exist in the original bytecode).
+    pub fn new(opcode: Opcode, params: InstructionParams) -> Self {
+        Self {
+            opcode,
+            params,
+            relocates: vec![],
+            origin_address: None,
+            origin_jump_target: None,
+        }
+    }
+}
+
+/// Decodes the function bytecode into a list of `InstructionItem`,
+/// the alignment `nop`s are dropped.
+pub fn decode_function_instructions(
+    code: &[u8],
+    relocate_list: &RelocateListEntry,
+) -> Vec<InstructionItem> {
+    let records = InstructionIterator::new(code).collect::<Vec<_>>();
+    let mut items: Vec<InstructionItem> = Vec::with_capacity(records.len());
+
+    for (idx, record) in records.iter().enumerate() {
+        // A `nop` is considered as padding when it is located at an address which
+        // is not 4-byte aligned and the following instruction has 'i32' parameters.
+        let is_padding = record.opcode == Opcode::nop
+            && record.offset % 4 != 0
+            && records
+                .get(idx + 1)
+                .is_some_and(|next_record| next_record.data.len() >= 8);
+
+        if is_padding {
+            continue;
+        }
+
+        let inst_addr = record.offset;
+        let inst_end = record.offset + record.data.len();
+
+        let relocates = relocate_list
+            .relocate_entries
+            .iter()
+            .filter(|entry| {
+                entry.offset_in_function >= inst_addr && entry.offset_in_function < inst_end
+            })
+            .map(|entry| (entry.offset_in_function - inst_addr, entry.relocate_type))
+            .collect::<Vec<_>>();
+
+        let origin_jump_target = match (record.opcode, record.params) {
+            (Opcode::recur, InstructionParams::Break { offset, .. }) => {
+                Some(inst_addr - offset as usize)
+            }
+            (Opcode::break_, InstructionParams::Break { offset, .. })
+            | (Opcode::block_alt, InstructionParams::BlockAlt { offset, .. })
+            | (Opcode::break_alt, InstructionParams::BreakAlt { offset })
+            | (Opcode::block_nez, InstructionParams::BlockNez { offset, .. }) => {
+                Some(inst_addr + offset as usize)
+            }
+            _ => None,
+        };
+
+        items.push(InstructionItem {
+            opcode: record.opcode,
+            params: record.params,
+            relocates,
+            origin_address: Some(inst_addr),
+            origin_jump_target,
+        });
+    }
+
+    items
+}
+
+/// Encodes the list of `InstructionItem` into bytecode with minimal padding,
+/// the jump offsets are recalculated and the relocate list is regenerated.
+///
+/// Returns an error if the parameters of an item do not match its opcode,
+/// or if an operand is out of range, see `BytecodeWriter::write_instruction`.
+pub fn encode_function_instructions(
+    items: &[InstructionItem],
+) -> Result<(Vec<u8>, RelocateListEntry), ImageError> {
+    let mut writer = BytecodeWriter::new();
+
+    let addresses = items
+        .iter()
+        .map(|item| writer.write_instruction(&Instruction::new(item.opcode, item.params)))
+        .collect::<Result<Vec<usize>, ImageError>>()?;
+
+    let code_end = writer.get_addr();
+
+    // `(origin_address, new_address)`, sorted by the origin address.
+    let address_map = items
+        .iter()
+        .zip(addresses.iter())
+        .filter_map(|(item, addr)| item.origin_address.map(|origin| (origin, *addr)))
+        .collect::<Vec<(usize, usize)>>();
+
+    let map_address = |origin_target: usize| -> usize {
+        let pos = address_map.partition_point(|(origin, _)| *origin < origin_target);
+        address_map.get(pos).map_or(code_end, |(_, addr)| *addr)
+    };
+
+    for (item, addr) in items.iter().zip(addresses.iter()) {
+        let Some(origin_target) = item.origin_jump_target else {
+            continue;
+        };
+
+        let target = map_address(origin_target);
+
+        match item.opcode {
+            Opcode::recur => writer.fill_break_stub(*addr, (addr - target) as u32),
+            Opcode::break_ | Opcode::break_alt => {
+                writer.fill_break_stub(*addr, (target - addr) as u32)
+            }
+            Opcode::block_alt => writer.fill_block_alt_stub(*addr, (target - addr) as u32),
+            Opcode::block_nez => writer.fill_block_nez_stub(*addr, (target - addr) as u32),
+            _ => unreachable!(),
+        }
+    }
+
+    let relocate_entries = items
+        .iter()
+        .zip(addresses.iter())
+        .flat_map(|(item, addr)| {
+            item.relocates
+                .iter()
+                .map(|(offset, relocate_type)| RelocateEntry::new(addr + offset, *relocate_type))
+        })
+        .collect::<Vec<RelocateEntry>>();
+
+    Ok((writer.to_bytes(), RelocateListEntry::new(relocate_entries)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_reader::{InstructionIterator, InstructionParams},
+        bytecode_transform::{
+            decode_function_instructions, encode_function_instructions, realign_function_code,
+            retarget_indices, trim_unreachable_code, InstructionItem,
+        },
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{RelocateEntry, RelocateListEntry},
+        module_image::RelocateType,
+    };
+
+    #[test]
+    fn test_retarget_indices() {
+        let mut code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1) // 0x0000
+            .append_opcode_i32(Opcode::extcall, 2) // 0x0008
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 3) // 0x0010
+            .append_opcode_i32(Opcode::call, 11) // 0x0018
+            .append_opcode(Opcode::end) // 0x0020
+            .to_bytes();
+
+        let mut relocate_list = RelocateListEntry::new(vec![
+            RelocateEntry::from_function_public_index(0),
+            RelocateEntry::from_external_function_index(8),
+            RelocateEntry::from_data_public_index(0x10),
+            RelocateEntry::from_function_public_index(0x18),
+        ]);
+
+        let mut map = HashMap::new();
+        map.insert(
+            (RelocateType::FunctionPublicIndex, 1),
+            (RelocateType::FunctionPublicIndex, 7),
+        );
+        map.insert(
+            (RelocateType::ExternalFunctionIndex, 2),
+            (RelocateType::FunctionPublicIndex, 5),
+        );
+        map.insert(
+            (RelocateType::DataPublicIndex, 3),
+            (RelocateType::DataPublicIndex, 9),
+        );
+
+        // the index `11` is not in the map
+        let count = retarget_indices(&mut code, &mut relocate_list, &map).unwrap();
+        assert_eq!(count, 3);
+
+        let instructions = InstructionIterator::new(&code)
+            .map(|record| (record.opcode, record.params))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            instructions,
+            vec![
+                (Opcode::call, InstructionParams::Index(7)),
+                (Opcode::call, InstructionParams::Index(5)),
+                (
+                    Opcode::data_load_i32_u,
+                    InstructionParams::Data {
+                        offset: 0,
+                        index: 9
+                    }
+                ),
+                (Opcode::call, InstructionParams::Index(11)),
+                (Opcode::end, InstructionParams::None),
+            ]
+        );
+
+        assert_eq!(
+            relocate_list,
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0),
+                RelocateEntry::from_function_public_index(8),
+                RelocateEntry::from_data_public_index(0x10),
+                RelocateEntry::from_function_public_index(0x18),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_retarget_indices_errors() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::get_function, 1) // 0x0000
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 3) // 0x0008
+            .append_opcode(Opcode::end) // 0x0010
+            .to_bytes();
+
+        let relocate_list = RelocateListEntry::new(vec![
+            RelocateEntry::from_function_public_index(0),
+            RelocateEntry::from_data_public_index(8),
+        ]);
+
+        let mut map = HashMap::new();
+        map.insert(
+            (RelocateType::DataPublicIndex, 3),
+            (RelocateType::DataPublicIndex, 9),
+        );
+
+        // the relocate offset is out of bounds
+        let mut code0 = code.clone();
+        let mut relocate_list0 = relocate_list.clone();
+        relocate_list0
+            .relocate_entries
+            .push(RelocateEntry::from_data_public_index(0x10));
+        assert_eq!(
+            retarget_indices(&mut code0, &mut relocate_list0, &map)
+                .unwrap_err()
+                .to_string(),
+            "Retarget error: The relocate offset 0x0014 is out of the bounds of the bytecode."
+        );
+
+        // nothing is modified
+        assert_eq!(code0, code);
+
+        // the relocate type of a data index cannot be changed
+        let mut map1 = map.clone();
+        map1.insert(
+            (RelocateType::DataPublicIndex, 3),
+            (RelocateType::FunctionPublicIndex, 9),
+        );
+        let mut code1 = code.clone();
+        let mut relocate_list1 = relocate_list.clone();
+        assert_eq!(
+            retarget_indices(&mut code1, &mut relocate_list1, &map1)
+                .unwrap_err()
+                .to_string(),
+            "Retarget error: Cannot change the relocate type DataPublicIndex to \
+                FunctionPublicIndex at offset 0x000c."
+        );
+
+        // the function index of `get_function` cannot be changed to an external function index
+        let mut map2 = map.clone();
+        map2.insert(
+            (RelocateType::FunctionPublicIndex, 1),
+            (RelocateType::ExternalFunctionIndex, 5),
+        );
+        let mut code2 = code.clone();
+        let mut relocate_list2 = relocate_list.clone();
+        assert_eq!(
+            retarget_indices(&mut code2, &mut relocate_list2, &map2).unwrap_err().to_string(),
+            "Retarget error: The relocate offset 0x0004 is not the index of a \"call\" instruction."
+        );
+        assert_eq!(code2, code);
+        assert_eq!(relocate_list2, relocate_list);
+    }
+
+    #[test]
+    fn test_decode_and_encode_function_instructions() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 7) // 0x0000
+            .append_opcode_i32_i32(Opcode::block_nez, 3, 0x10) // 0x0008
+            .append_opcode(Opcode::eqz_i32) // 0x0014
+            .append_opcode(Opcode::end) // 0x0016
+            .append_opcode(Opcode::end) // 0x0018
+            .to_bytes();
+
+        let relocate_list = RelocateListEntry::new(vec![
+            RelocateEntry::from_function_public_index(0),
+            RelocateEntry::from_block_with_local_variables(8),
+        ]);
+
+        let mut items = decode_function_instructions(&code, &relocate_list);
+        assert_eq!(items.len(), 5);
+        assert_eq!(items[1].origin_address, Some(8));
+        assert_eq!(items[1].origin_jump_target, Some(0x18));
+
+        // encode without changes
+        assert_eq!(
+            encode_function_instructions(&items).unwrap(),
+            (code.clone(), relocate_list.clone())
+        );
+
+        items.insert(
+            0,
+            InstructionItem::new(Opcode::eqz_i32, InstructionParams::None),
+        );
+        items.insert(
+            3,
+            InstructionItem::new(Opcode::eqz_i32, InstructionParams::None),
+        );
+
+        let (code_new, relocate_list_new) = encode_function_instructions(&items).unwrap();
+
+        let code_expect = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32) // 0x0000
+            .append_opcode_i32(Opcode::call, 7) // 0x0004, a padding `nop` is inserted
+            .append_opcode_i32_i32(Opcode::block_nez, 3, 0x12) // 0x000c
+            .append_opcode(Opcode::eqz_i32) // 0x0018
+            .append_opcode(Opcode::eqz_i32) // 0x001a
+            .append_opcode(Opcode::end) // 0x001c
+            .append_opcode(Opcode::end) // 0x001e
+            .to_bytes();
+
+        assert_eq!(code_new, code_expect);
+        assert_eq!(
+            relocate_list_new,
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(4),
+                RelocateEntry::from_block_with_local_variables(0xc),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_trim_unreachable_code() {
+        let mut code = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0000
+            .append_opcode_i16_i32(Opcode::break_, 0, 0x1a) // 0x000c
+            .append_opcode_i32(Opcode::imm_i32, 2) // 0x0014, unreachable
+            .append_opcode_i32(Opcode::call, 5) // 0x001c, unreachable
+            .append_opcode(Opcode::end) // 0x0024
+            .append_opcode(Opcode::end) // 0x0026
+            .to_bytes();
+
+        let mut relocate_list = RelocateListEntry::new(
+            [
+                RelocateEntry::from_block_with_type_and_local_variables(0),
+                vec![RelocateEntry::from_function_public_index(0x1c)],
+            ]
+            .concat(),
+        );
+
+        assert_eq!(
+            trim_unreachable_code(&mut code, &mut relocate_list).unwrap(),
+            2
+        );
+
+        let code_expect = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0000
+            .append_opcode_i16_i32(Opcode::break_, 0, 0x0a) // 0x000c
+            .append_opcode(Opcode::end) // 0x0014
+            .append_opcode(Opcode::end) // 0x0016
+            .to_bytes();
+
+        assert_eq!(code, code_expect);
+        assert_eq!(
+            relocate_list,
+            RelocateListEntry::new(RelocateEntry::from_block_with_type_and_local_variables(0))
+        );
+
+        // nothing to trim
+        assert_eq!(
+            trim_unreachable_code(&mut code, &mut relocate_list).unwrap(),
+            0
+        );
+        assert_eq!(code, code_expect);
+    }
+
+    #[test]
+    fn test_trim_unreachable_code_keep_alternative_branch() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32_i32(Opcode::block_alt, 0, 1, 0x20) // 0x0000
+            .append_opcode_i32(Opcode::terminate, 1) // 0x0010
+            .append_opcode_i32(Opcode::break_alt, 0x0c) // 0x0018
+            .append_opcode(Opcode::eqz_i32) // 0x0020
+            .append_opcode(Opcode::end) // 0x0022
+            .append_opcode(Opcode::end) // 0x0024
+            .to_bytes();
+
+        let mut code_trimmed = code.clone();
+        let mut relocate_list = RelocateListEntry::new(vec![]);
+
+        assert_eq!(
+            trim_unreachable_code(&mut code_trimmed, &mut relocate_list).unwrap(),
+            0
+        );
+        assert_eq!(code_trimmed, code);
+    }
+
+    #[test]
+    fn test_realign_function_code() {
+        let code_aligned = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 7) // 0x0000
+            .append_opcode(Opcode::end) // 0x0008
+            .to_bytes();
+
+        let code_with_padding = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32) // 0x0000
+            .append_opcode_i32(Opcode::call, 7) // 0x0004, a padding `nop` is inserted
+            .append_opcode(Opcode::end) // 0x000c
+            .to_bytes();
+
+        // redundant padding:
+        // 0x0000 nop
+        // 0x0002 call 7
+        // 0x000a end
+        let mut code0 = code_with_padding[2..].to_vec();
+        let mut relocate_list0 =
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(2)]);
+
+        assert_eq!(
+            realign_function_code(&mut code0, &mut relocate_list0).unwrap(),
+            true
+        );
+        assert_eq!(code0, code_aligned);
+        assert_eq!(
+            relocate_list0,
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(0)])
+        );
+
+        // missing padding:
+        // 0x0000 eqz_i32
+        // 0x0002 call 7
+        // 0x000a end
+        let mut code1 = [&code_with_padding[0..2], &code_aligned[..]].concat();
+        let mut relocate_list1 =
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(2)]);
+
+        assert_eq!(
+            realign_function_code(&mut code1, &mut relocate_list1).unwrap(),
+            true
+        );
+        assert_eq!(code1, code_with_padding);
+        assert_eq!(
+            relocate_list1,
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(4)])
+        );
+
+        // already aligned
+        assert_eq!(
+            realign_function_code(&mut code1, &mut relocate_list1).unwrap(),
+            false
+        );
+        assert_eq!(code1, code_with_padding);
+    }
+
+    #[test]
+    fn test_encode_function_instructions_with_16_bit_operands() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16(Opcode::add_imm_i32, 40000) // 0x0000
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0x9000, 3) // 0x0004
+            .append_opcode(Opcode::end) // 0x000c
+            .to_bytes();
+
+        let relocate_list = RelocateListEntry::new(vec![RelocateEntry::from_data_public_index(4)]);
+
+        let items = decode_function_instructions(&code, &relocate_list);
+        assert_eq!(items[0].params, InstructionParams::Amount(40000));
+
+        assert_eq!(
+            encode_function_instructions(&items).unwrap(),
+            (code, relocate_list)
+        );
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Verifies the encoding of the function code before it is executed by the VM,
+// e.g., for the compilers which emit the bytecode through `BytecodeWriter`.
+//
+// The code is walked one instruction at a time (like `format_bytecode_as_text`),
+// and the following problems are rejected:
+//
+// - Unknown opcodes, i.e., the opcodes which are not in `opcode_info::ALL_OPCODES`.
+// - Truncated instructions, i.e., the code ends in the middle of an instruction.
+// - Misaligned instructions, the instructions with `i32` parameters must be
+//   4-byte aligned, see the "About the padding" section of `bytecode_writer`.
+// - Unresolved stubs, i.e., the "next_inst_offset" of `block_alt`, `block_nez`,
+//   `break` and `break_alt` is still `0`, see the "About the stubs" section
+//   of `bytecode_writer`.
+//
+// The `break` instruction whose target layer is the function is not a stub,
+// because the VM ignores its "next_inst_offset".
+//
+// The walking stops at the first error, since the boundaries of the
+// following instructions are unknown.
+//
+// Only the encoding is verified, the indices in the parameters are checked
+// by `validator`.
+
+use std::fmt::Display;
+
+use anc_isa::opcode::Opcode;
+
+use crate::{
+    bytecode_reader::{decode_instruction, InstructionParams},
+    module_image::ModuleImage,
+    opcode_info::{find_opcode_by_value, get_opcode_info, OPCODE_LENGTH},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct BytecodeVerificationError {
+    // `None` if the code is verified alone, see `verify_bytecode`.
+    pub function_internal_index: Option<usize>,
+
+    // The offset of the instruction within the function code.
+    pub instruction_offset: usize,
+
+    pub error_type: BytecodeVerificationErrorType,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BytecodeVerificationErrorType {
+    UnknownOpcode(u16),
+
+    // The code ends before the end of the instruction.
+    TruncatedInstruction { opcode: Opcode },
+
+    // The instruction has `i32` parameters but it is not 4-byte aligned.
+    MisalignedInstruction { opcode: Opcode },
+
+    // The "next_inst_offset" of the instruction is `0`.
+    UnresolvedStub { opcode: Opcode },
+}
+
+impl Display for BytecodeVerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(function_internal_index) = self.function_internal_index {
+            write!(f, "Function {}, ", function_internal_index)?;
+        }
+
+        write!(
+            f,
+            "instruction 0x{:04x}: {}",
+            self.instruction_offset, self.error_type
+        )
+    }
+}
+
+impl Display for BytecodeVerificationErrorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BytecodeVerificationErrorType::UnknownOpcode(value) => {
+                write!(f, "Unknown opcode 0x{:04x}.", value)
+            }
+            BytecodeVerificationErrorType::TruncatedInstruction { opcode } => {
+                write!(f, "The instruction \"{}\" is truncated.", opcode.get_name())
+            }
+            BytecodeVerificationErrorType::MisalignedInstruction { opcode } => write!(
+                f,
+                "The instruction \"{}\" is not 4-byte aligned.",
+                opcode.get_name()
+            ),
+            BytecodeVerificationErrorType::UnresolvedStub { opcode } => write!(
+                f,
+                "The \"next_inst_offset\" of the instruction \"{}\" is not filled.",
+                opcode.get_name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BytecodeVerificationError {}
+
+/// Verifies the encoding of the code of a function.
+pub fn verify_bytecode(code: &[u8]) -> Result<(), BytecodeVerificationError> {
+    let build_error = |instruction_offset: usize, error_type: BytecodeVerificationErrorType| {
+        BytecodeVerificationError {
+            function_internal_index: None,
+            instruction_offset,
+            error_type,
+        }
+    };
+
+    // The amount of the enclosing blocks, `0` means the function body.
+    let mut block_depth: usize = 0;
+    let mut offset: usize = 0;
+
+    while offset < code.len() {
+        // a single trailing byte is not a complete opcode.
+        if offset + OPCODE_LENGTH > code.len() {
+            return Err(build_error(
+                offset,
+                BytecodeVerificationErrorType::UnknownOpcode(code[offset] as u16),
+            ));
+        }
+
+        let value = u16::from_le_bytes([code[offset], code[offset + 1]]);
+        let Some(opcode) = find_opcode_by_value(value) else {
+            return Err(build_error(
+                offset,
+                BytecodeVerificationErrorType::UnknownOpcode(value),
+            ));
+        };
+
+        let info = get_opcode_info(opcode);
+        if info.operands.iter().any(|operand| operand.size() == 4) && offset % 4 != 0 {
+            return Err(build_error(
+                offset,
+                BytecodeVerificationErrorType::MisalignedInstruction { opcode },
+            ));
+        }
+
+        if offset + info.length() > code.len() {
+            return Err(build_error(
+                offset,
+                BytecodeVerificationErrorType::TruncatedInstruction { opcode },
+            ));
+        }
+
+        let (offset_next, _, params) = decode_instruction(code, offset);
+
+        let is_unresolved_stub = match params {
+            InstructionParams::BlockAlt {
+                offset: next_inst_offset,
+                ..
+            }
+            | InstructionParams::BlockNez {
+                offset: next_inst_offset,
+                ..
+            }
+            | InstructionParams::BreakAlt {
+                offset: next_inst_offset,
+            } => next_inst_offset == 0,
+            InstructionParams::Break {
+                layers,
+                offset: next_inst_offset,
+            } if opcode == Opcode::break_ => {
+                next_inst_offset == 0 && (layers as usize) < block_depth
+            }
+            _ => false,
+        };
+
+        if is_unresolved_stub {
+            return Err(build_error(
+                offset,
+                BytecodeVerificationErrorType::UnresolvedStub { opcode },
+            ));
+        }
+
+        match opcode {
+            Opcode::block | Opcode::block_alt | Opcode::block_nez => block_depth += 1,
+            Opcode::end => block_depth = block_depth.saturating_sub(1),
+            _ => {}
+        }
+
+        offset = offset_next;
+    }
+
+    Ok(())
+}
+
+/// Verifies the code of all functions of the image.
+pub fn verify_image_bytecode(image: &ModuleImage) -> Vec<BytecodeVerificationError> {
+    let function_section = image.get_function_section();
+
+    function_section
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(function_internal_index, item)| {
+            let code = &function_section.codes_data
+                [item.code_offset as usize..(item.code_offset + item.code_length) as usize];
+
+            verify_bytecode(code)
+                .err()
+                .map(|error| BytecodeVerificationError {
+                    function_internal_index: Some(function_internal_index),
+                    ..error
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_verifier::{
+            verify_bytecode, verify_image_bytecode, BytecodeVerificationError,
+            BytecodeVerificationErrorType,
+        },
+        bytecode_writer::BytecodeWriterHelper,
+        module_image::ModuleImage,
+        utils::helper_build_module_binary_with_single_function,
+    };
+
+    #[test]
+    fn test_verify_bytecode() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::nop) // 0x0000
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0004 (padding at 0x0002)
+            .append_opcode_i32_i32_i32(Opcode::block_alt, 0, 0, 0x18) // 0x000c
+            .append_opcode_i16_i32(Opcode::break_, 0, 0x0a) // 0x001c
+            .append_opcode(Opcode::end) // 0x0024
+            .append_opcode_i16_i32(Opcode::break_, 0, 0) // 0x0028 (padding at 0x0026)
+            .append_opcode(Opcode::end) // 0x0030
+            .to_bytes();
+        assert_eq!(verify_bytecode(&code), Ok(()));
+
+        let verify_error_type =
+            |code: &[u8]| verify_bytecode(code).map_err(|error| error.error_type);
+
+        // unknown opcode
+        assert_eq!(
+            verify_error_type(&[0xff, 0xff]),
+            Err(BytecodeVerificationErrorType::UnknownOpcode(0xffff))
+        );
+
+        // truncated
+        let mut truncated_code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11)
+            .to_bytes();
+        truncated_code.truncate(6);
+        assert_eq!(
+            verify_error_type(&truncated_code),
+            Err(BytecodeVerificationErrorType::TruncatedInstruction {
+                opcode: Opcode::imm_i32
+            })
+        );
+
+        // misaligned, i.e., without the padding `nop`
+        let mut misaligned_code = (Opcode::nop as u16).to_le_bytes().to_vec();
+        misaligned_code.extend_from_slice(&(Opcode::imm_i32 as u16).to_le_bytes());
+        misaligned_code.extend_from_slice(&[0, 0, 11, 0, 0, 0]);
+        assert_eq!(
+            verify_bytecode(&misaligned_code),
+            Err(BytecodeVerificationError {
+                function_internal_index: None,
+                instruction_offset: 2,
+                error_type: BytecodeVerificationErrorType::MisalignedInstruction {
+                    opcode: Opcode::imm_i32
+                }
+            })
+        );
+
+        // unresolved stubs
+        let stub_code = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block_nez, 0, 0)
+            .append_opcode(Opcode::end)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+        assert_eq!(
+            verify_error_type(&stub_code),
+            Err(BytecodeVerificationErrorType::UnresolvedStub {
+                opcode: Opcode::block_nez
+            })
+        );
+
+        let stub_code = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 0, 0)
+            .append_opcode_i16_i32(Opcode::break_, 0, 0)
+            .append_opcode(Opcode::end)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+        assert_eq!(
+            verify_error_type(&stub_code),
+            Err(BytecodeVerificationErrorType::UnresolvedStub {
+                opcode: Opcode::break_
+            })
+        );
+
+        // verify the image
+        let image_binary =
+            helper_build_module_binary_with_single_function(&[], &[], &[], stub_code.clone());
+        let image = ModuleImage::read(&image_binary).unwrap();
+        let errors = verify_image_bytecode(&image);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].function_internal_index, Some(0));
+        assert_eq!(
+            errors[0].to_string(),
+            format!(
+                "Function 0, instruction 0x000c: The \"next_inst_offset\" of the instruction \"{}\" is not filled.",
+                Opcode::break_.get_name()
+            )
+        );
+    }
+}
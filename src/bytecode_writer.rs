@@ -4,10 +4,68 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
-use std::io::Write;
+use std::{fmt::Display, io::Write};
 
 use anc_isa::opcode::Opcode;
 
+use crate::{
+    bytecode_reader::{Instruction, InstructionParams},
+    opcode_info::{get_opcode_info, OperandKind},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why an instruction can not be encoded by `BytecodeWriter::write_instruction`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InstructionError {
+    OperandOutOfRange {
+        opcode: Opcode,
+        operand: OperandKind,
+        value: u32,
+    },
+
+    // The jump target is an instruction, which is at least 2-byte aligned.
+    UnalignedJumpOffset {
+        opcode: Opcode,
+        offset: u32,
+    },
+
+    // The parameters do not match the operands of the opcode.
+    ParamsMismatch {
+        opcode: Opcode,
+        params: InstructionParams,
+    },
+}
+
+impl Display for InstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstructionError::OperandOutOfRange {
+                opcode,
+                operand,
+                value,
+            } => write!(
+                f,
+                "The operand {:?} {} of instruction \"{}\" is out of range.",
+                operand,
+                value,
+                opcode.get_name()
+            ),
+            InstructionError::UnalignedJumpOffset { opcode, offset } => write!(
+                f,
+                "The jump offset {} of instruction \"{}\" is not 2-byte aligned.",
+                offset,
+                opcode.get_name()
+            ),
+            InstructionError::ParamsMismatch { opcode, params } => write!(
+                f,
+                "The parameters \"{:?}\" do not match the operands of instruction \"{}\".",
+                params,
+                opcode.get_name()
+            ),
+        }
+    }
+}
+
 pub struct BytecodeWriter {
     buffer: Vec<u8>, // Implements the trait std::io::Write
 }
@@ -31,6 +89,7 @@ pub struct BytecodeWriter {
 // - write_opcode_i64
 // - write_opcode_f32
 // - write_opcode_f64
+// - write_instruction (when the opcode has 'i32' operands)
 
 // About the stubs
 // ---------------
@@ -217,6 +276,61 @@ impl BytecodeWriter {
         addr
     }
 
+    /// Writes a structured instruction (e.g. the one decoded by `bytecode_reader`)
+    /// and returns its address (the padding `nop` is not included).
+    ///
+    /// The layout of the instruction is determined by the opcode metadata (see
+    /// `opcode_info`), an error is returned if the parameters do not match
+    /// the operands of the opcode, or if an operand is out of range.
+    pub fn write_instruction(&mut self, instruction: &Instruction) -> Result<usize, ImageError> {
+        let info = get_opcode_info(instruction.opcode);
+        let values = get_operand_values(instruction)?;
+
+        for (operand, value) in info.operands.iter().zip(values.iter()) {
+            if *value > get_operand_max_value(*operand) {
+                return Err(ImageError::new(ImageErrorType::Instruction(
+                    InstructionError::OperandOutOfRange {
+                        opcode: instruction.opcode,
+                        operand: *operand,
+                        value: *value,
+                    },
+                )));
+            }
+
+            if *operand == OperandKind::JumpOffset && value % 2 != 0 {
+                return Err(ImageError::new(ImageErrorType::Instruction(
+                    InstructionError::UnalignedJumpOffset {
+                        opcode: instruction.opcode,
+                        offset: *value,
+                    },
+                )));
+            }
+        }
+
+        let addr = if info.operands.iter().any(|operand| operand.size() == 4) {
+            self.insert_padding_if_necessary()
+        } else {
+            self.get_addr()
+        };
+
+        self.put_opcode(instruction.opcode);
+
+        for (idx, (operand, value)) in info.operands.iter().zip(values.iter()).enumerate() {
+            // Adds padding before the first 'i32' operand.
+            while self.get_addr() - addr < info.operand_offset(idx) {
+                self.put_i16(0);
+            }
+
+            if operand.size() == 2 {
+                self.put_i16(*value as u16);
+            } else {
+                self.put_i32(*value);
+            }
+        }
+
+        Ok(addr)
+    }
+
     /// Converts the buffer into a byte vector.
     pub fn to_bytes(self) -> Vec<u8> {
         self.buffer
@@ -266,6 +380,90 @@ impl BytecodeWriter {
     }
 }
 
+// Returns the maximum value of the operand.
+//
+// The 16-bit operands (layers, data offset and amount) are encoded as `i16`,
+// which is interpreted as `u16` (the same as `write_opcode_i16` and the decoder).
+// The indices are encoded as `i32`, which is interpreted as `u32`.
+fn get_operand_max_value(operand: OperandKind) -> u32 {
+    if operand.size() == 2 {
+        u16::MAX as u32
+    } else {
+        u32::MAX
+    }
+}
+
+// Returns the values of the operands of the instruction, or an error if the
+// parameters do not match the operands of the opcode.
+fn get_operand_values(instruction: &Instruction) -> Result<Vec<u32>, ImageError> {
+    let info = get_opcode_info(instruction.opcode);
+
+    let values = match (info.operands, instruction.params) {
+        ([], InstructionParams::None) => vec![],
+        ([OperandKind::Immediate], InstructionParams::ImmI32(value)) => vec![value],
+        (
+            [OperandKind::Immediate, OperandKind::Immediate],
+            InstructionParams::ImmI64 { low, high },
+        ) => {
+            vec![low, high]
+        }
+        (
+            [OperandKind::Layers, OperandKind::LocalVariableIndex],
+            InstructionParams::Local { layers, index },
+        ) => vec![layers as u32, index],
+        (
+            [OperandKind::DataOffset, OperandKind::DataPublicIndex],
+            InstructionParams::Data { offset, index },
+        ) => vec![offset as u32, index],
+        (
+            [OperandKind::DataPublicIndex
+            | OperandKind::FunctionPublicIndex
+            | OperandKind::ExternalFunctionIndex
+            | OperandKind::EnvCallNumber],
+            InstructionParams::Index(index),
+        ) => vec![index],
+        ([OperandKind::Amount], InstructionParams::Amount(amount)) => vec![amount as u32],
+        (
+            [OperandKind::TypeIndex, OperandKind::LocalVariableListIndex],
+            InstructionParams::Block {
+                type_index,
+                local_variable_list_index,
+            },
+        ) => vec![type_index, local_variable_list_index],
+        (
+            [OperandKind::Layers, OperandKind::JumpOffset],
+            InstructionParams::Break { layers, offset },
+        ) => vec![layers as u32, offset],
+        (
+            [OperandKind::TypeIndex, OperandKind::LocalVariableListIndex, OperandKind::JumpOffset],
+            InstructionParams::BlockAlt {
+                type_index,
+                local_variable_list_index,
+                offset,
+            },
+        ) => vec![type_index, local_variable_list_index, offset],
+        ([OperandKind::JumpOffset], InstructionParams::BreakAlt { offset }) => vec![offset],
+        (
+            [OperandKind::LocalVariableListIndex, OperandKind::JumpOffset],
+            InstructionParams::BlockNez {
+                local_variable_list_index,
+                offset,
+            },
+        ) => vec![local_variable_list_index, offset],
+        ([OperandKind::TerminateCode], InstructionParams::Code(code)) => vec![code],
+        _ => {
+            return Err(ImageError::new(ImageErrorType::Instruction(
+                InstructionError::ParamsMismatch {
+                    opcode: instruction.opcode,
+                    params: instruction.params,
+                },
+            )))
+        }
+    };
+
+    Ok(values)
+}
+
 pub struct BytecodeWriterHelper {
     writer: BytecodeWriter,
 }
@@ -346,7 +544,13 @@ mod tests {
     use anc_isa::opcode::Opcode;
     use pretty_assertions::assert_eq;
 
-    use crate::{bytecode_reader::format_bytecode_as_text, bytecode_writer::BytecodeWriterHelper};
+    use crate::{
+        bytecode_reader::{
+            format_bytecode_as_text, Instruction, InstructionIterator, InstructionParams,
+        },
+        bytecode_writer::{BytecodeWriter, BytecodeWriterHelper, InstructionError},
+        ImageErrorType,
+    };
 
     #[test]
     fn test_bytecode_writer() {
@@ -600,4 +804,128 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_bytecode_writer_write_instruction() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i64(Opcode::imm_i64, 0x1300000011)
+            .append_opcode_i16(Opcode::add_imm_i32, 0x2)
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 1, 3)
+            .append_opcode(Opcode::eqz_i32)
+            .append_opcode_i32_i32_i32(Opcode::block_alt, 5, 7, 0x20)
+            .append_opcode_i32(Opcode::break_alt, 0x12)
+            .append_opcode_i16_i32(Opcode::break_, 0, 0x10)
+            .append_opcode(Opcode::end)
+            .append_opcode_i32(Opcode::call, 11)
+            .append_opcode_i32(Opcode::terminate, 13)
+            .to_bytes();
+
+        // writes the decoded instructions back, the padding `nop`s are
+        // written at the original (unaligned) addresses, so no extra
+        // padding is inserted.
+        let mut writer = BytecodeWriter::new();
+        for record in InstructionIterator::new(&code) {
+            let addr = writer.write_instruction(&record.to_instruction()).unwrap();
+            assert_eq!(addr, record.offset);
+        }
+        assert_eq!(writer.to_bytes(), code);
+
+        // auto padding
+        let mut writer = BytecodeWriter::new();
+        writer
+            .write_instruction(&Instruction::new(Opcode::eqz_i32, InstructionParams::None))
+            .unwrap();
+        let addr = writer
+            .write_instruction(&Instruction::new(
+                Opcode::data_load_i32_u,
+                InstructionParams::Data {
+                    offset: 0x17,
+                    index: 0x19,
+                },
+            ))
+            .unwrap();
+        assert_eq!(addr, 4);
+        assert_eq!(
+            format_bytecode_as_text(&writer.to_bytes()),
+            "\
+0x0000  00 08                       eqz_i32
+0x0002  00 01                       nop
+0x0004  02 03 17 00  19 00 00 00    data_load_i32_u   offset:0x17  index:25"
+        );
+
+        // parameters mismatch
+        let mut writer = BytecodeWriter::new();
+        assert!(writer
+            .write_instruction(&Instruction::new(Opcode::call, InstructionParams::None))
+            .is_err());
+        assert!(writer
+            .write_instruction(&Instruction::new(
+                Opcode::local_load_i32_u,
+                InstructionParams::Data {
+                    offset: 0,
+                    index: 1
+                }
+            ))
+            .is_err());
+
+        // jump offset out of alignment
+        assert!(matches!(
+            writer
+                .write_instruction(&Instruction::new(
+                    Opcode::break_alt,
+                    InstructionParams::BreakAlt { offset: 0x13 }
+                ))
+                .unwrap_err()
+                .error_type,
+            ImageErrorType::Instruction(InstructionError::UnalignedJumpOffset {
+                opcode: Opcode::break_alt,
+                offset: 0x13
+            })
+        ));
+
+        // the maximum values
+        let mut writer_max = BytecodeWriter::new();
+        writer_max
+            .write_instruction(&Instruction::new(
+                Opcode::local_load_i32_u,
+                InstructionParams::Local {
+                    layers: u16::MAX,
+                    index: u32::MAX,
+                },
+            ))
+            .unwrap();
+        writer_max
+            .write_instruction(&Instruction::new(
+                Opcode::block,
+                InstructionParams::Block {
+                    type_index: u32::MAX,
+                    local_variable_list_index: u32::MAX,
+                },
+            ))
+            .unwrap();
+
+        // nothing is written on error
+        assert_eq!(writer.get_addr(), 0);
+    }
+
+    #[test]
+    fn test_bytecode_writer_write_instruction_with_16_bit_operands() {
+        // the 16-bit operands are decoded as `u16`, so the values
+        // above `i16::MAX` can be written back.
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16(Opcode::add_imm_i32, 40000)
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0x8001, 3)
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0x9000, 5)
+            .append_opcode_i16_i32(Opcode::break_, u16::MAX, 0x10)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let mut writer = BytecodeWriter::new();
+        for record in InstructionIterator::new(&code) {
+            let addr = writer.write_instruction(&record.to_instruction()).unwrap();
+            assert_eq!(addr, record.offset);
+        }
+        assert_eq!(writer.to_bytes(), code);
+    }
 }
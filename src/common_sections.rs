@@ -5,14 +5,20 @@
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
 pub mod data_name_section;
-pub mod function_name_section;
+pub mod debug_link_section;
+pub mod export_hash_section;
 pub mod external_function_section;
 pub mod external_library_section;
+pub mod function_hash_section;
+pub mod function_name_section;
 pub mod function_section;
 pub mod import_data_section;
 pub mod import_function_section;
 pub mod import_module_section;
+pub mod initializer_section;
+pub mod license_section;
 pub mod local_variable_section;
+pub mod patch_slot_section;
 pub mod property_section;
 pub mod read_only_data_section;
 pub mod read_write_data_section;
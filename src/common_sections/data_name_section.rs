@@ -50,6 +50,12 @@
 // internal      | Internal uninitialized data |    |
 // index         |                             |    |
 //               \-----------------------------/ <--/
+//
+// The conversions are implemented by `public_index::PublicIndexSpace`,
+// `DataNameSection::get_public_index` applies them to the items of this section.
+// The amounts of the data are supplied by the caller (e.g., by
+// `PublicIndexSpace::from_module_image`) instead of being counted from the items,
+// since the names of the private data may be stripped (see `NameRetention`).
 
 // "Data Name Section" binary layout:
 //
@@ -76,9 +82,11 @@ use anc_isa::DataSectionType;
 use crate::entry::DataNameEntry;
 
 use crate::module_image::Visibility;
+use crate::public_index::PublicIndexSpace;
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     module_image::{ModuleSectionId, SectionEntry},
 };
@@ -147,6 +155,10 @@ impl<'a> SectionEntry<'a> for DataNameSection<'a> {
         write_section_with_table_and_data_area(self.items, self.full_names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.full_names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::DataName
     }
@@ -210,6 +222,50 @@ impl<'a> DataNameSection<'a> {
         })
     }
 
+    /// Get the data public index of the item, i.e., the imported data first,
+    /// followed by the internal read-only, read-write and uninitialized data.
+    ///
+    /// Returns `None` if the item index is out of range, or the item
+    /// refers to a data which is out of the public index space.
+    pub fn get_public_index(
+        &self,
+        item_idx: usize,
+        public_index_space: &PublicIndexSpace,
+    ) -> Option<usize> {
+        let item = self.items.get(item_idx)?;
+        let data_internal_index = item.internal_index_in_section as usize;
+        let data_count = match item.section_type {
+            DataSectionType::ReadOnly => public_index_space.read_only_data_count,
+            DataSectionType::ReadWrite => public_index_space.read_write_data_count,
+            DataSectionType::Uninit => public_index_space.uninit_data_count,
+        };
+
+        if data_internal_index < data_count {
+            Some(public_index_space.data_public_index(item.section_type, data_internal_index))
+        } else {
+            None
+        }
+    }
+
+    /// Get the index of the item by the data public index, it is the inverse
+    /// of `get_public_index`.
+    ///
+    /// Returns `None` if the public index refers to an imported data,
+    /// or is out of range.
+    pub fn get_item_index_by_public_index(
+        &self,
+        data_public_index: usize,
+        public_index_space: &PublicIndexSpace,
+    ) -> Option<usize> {
+        let (data_section_type, data_internal_index) = public_index_space
+            .data_section_type_and_internal_index_from_public_index(data_public_index)?;
+
+        self.items.iter().position(|item| {
+            item.section_type == data_section_type
+                && item.internal_index_in_section as usize == data_internal_index
+        })
+    }
+
     /// Converts the section into a vector of `ExportDataEntry`.
     pub fn convert_to_entries(&self) -> Vec<DataNameEntry> {
         let items = self.items;
@@ -264,6 +320,19 @@ impl<'a> DataNameSection<'a> {
 
         (items, full_names_data)
     }
+
+    /// Checks that the full names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(
+                self.full_names_data,
+                item.full_name_offset,
+                item.full_name_length,
+            )
+            .is_some()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +343,7 @@ mod tests {
         common_sections::data_name_section::{DataNameItem, DataNameSection},
         entry::DataNameEntry,
         module_image::{SectionEntry, Visibility},
+        public_index::PublicIndexSpace,
     };
 
     #[test]
@@ -405,4 +475,139 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries, entries_restore);
     }
+
+    #[test]
+    fn test_public_index() {
+        // the items are not in the order of the public indices
+        let entries: Vec<DataNameEntry> = vec![
+            DataNameEntry::new(
+                "buffer".to_string(),
+                Visibility::Private,
+                DataSectionType::Uninit,
+                0,
+            ),
+            DataNameEntry::new(
+                "message".to_string(),
+                Visibility::Public,
+                DataSectionType::ReadOnly,
+                0,
+            ),
+            DataNameEntry::new(
+                "count".to_string(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                0,
+            ),
+            DataNameEntry::new(
+                "title".to_string(),
+                Visibility::Private,
+                DataSectionType::ReadOnly,
+                1,
+            ),
+            DataNameEntry::new(
+                "total".to_string(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                1,
+            ),
+        ];
+
+        let (items, names_data) = DataNameSection::convert_from_entries(&entries);
+        let section = DataNameSection {
+            items: &items,
+            full_names_data: &names_data,
+        };
+
+        // 3 imported data, followed by 2 read-only data, 2 read-write data and 1 uninit data
+        let public_index_space = PublicIndexSpace {
+            import_data_count: 3,
+            read_only_data_count: 2,
+            read_write_data_count: 2,
+            uninit_data_count: 1,
+            ..PublicIndexSpace::default()
+        };
+        let public_indices = (0..items.len())
+            .map(|item_idx| {
+                section
+                    .get_public_index(item_idx, &public_index_space)
+                    .unwrap()
+            })
+            .collect::<Vec<usize>>();
+        assert_eq!(public_indices, vec![7, 3, 5, 4, 6]);
+
+        for (item_idx, public_index) in public_indices.iter().enumerate() {
+            assert_eq!(
+                section.get_item_index_by_public_index(*public_index, &public_index_space),
+                Some(item_idx)
+            );
+        }
+
+        // imported data
+        assert_eq!(
+            section.get_item_index_by_public_index(0, &public_index_space),
+            None
+        );
+        assert_eq!(
+            section.get_item_index_by_public_index(2, &public_index_space),
+            None
+        );
+
+        // out of range
+        assert_eq!(
+            section.get_item_index_by_public_index(8, &public_index_space),
+            None
+        );
+        assert_eq!(section.get_public_index(5, &public_index_space), None);
+
+        // the item refers to a data which is out of the public index space
+        let public_index_space_without_uninit_data = PublicIndexSpace {
+            uninit_data_count: 0,
+            ..public_index_space
+        };
+        assert_eq!(
+            section.get_public_index(0, &public_index_space_without_uninit_data),
+            None
+        );
+
+        // without imported data
+        let public_index_space_without_imports = PublicIndexSpace {
+            import_data_count: 0,
+            ..public_index_space
+        };
+        assert_eq!(
+            section.get_public_index(1, &public_index_space_without_imports),
+            Some(0)
+        );
+        assert_eq!(
+            section.get_item_index_by_public_index(4, &public_index_space_without_imports),
+            Some(0)
+        );
+
+        // only the names of the public data are retained
+        let public_entries = entries
+            .into_iter()
+            .filter(|entry| entry.visibility == Visibility::Public)
+            .collect::<Vec<DataNameEntry>>();
+        let (public_items, public_names_data) =
+            DataNameSection::convert_from_entries(&public_entries);
+        let public_section = DataNameSection {
+            items: &public_items,
+            full_names_data: &public_names_data,
+        };
+
+        let public_indices = (0..public_items.len())
+            .map(|item_idx| {
+                public_section
+                    .get_public_index(item_idx, &public_index_space)
+                    .unwrap()
+            })
+            .collect::<Vec<usize>>();
+        assert_eq!(public_indices, vec![3, 5, 6]);
+
+        // the private data has no name
+        assert_eq!(
+            public_section.get_item_index_by_public_index(4, &public_index_space),
+            None
+        );
+    }
 }
@@ -0,0 +1,72 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Debug Link Section" references the companion debug file (`*.ancd`)
+// of an image whose debug sections are split out, see `debug_info`.
+//
+// "Debug Link Section" binary layout:
+//
+// |----------------------------------------------------|
+// | debug file hash (8 bytes, little-endian u64)       |
+// |----------------------------------------------------|
+//
+// The hash is computed over the whole debug file,
+// see `export_surface::compute_content_hash`.
+
+use crate::module_image::{ModuleSectionId, SectionEntry};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DebugLinkSection {
+    pub debug_file_hash: u64,
+}
+
+impl DebugLinkSection {
+    pub fn new(debug_file_hash: u64) -> Self {
+        Self { debug_file_hash }
+    }
+}
+
+impl<'a> SectionEntry<'a> for DebugLinkSection {
+    fn read(section_data: &'a [u8]) -> Self {
+        let debug_file_hash = u64::from_le_bytes(section_data[0..8].try_into().unwrap());
+        Self { debug_file_hash }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writer.write_all(&self.debug_file_hash.to_le_bytes())
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        size_of::<u64>()
+    }
+
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::DebugLink
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::debug_link_section::DebugLinkSection, module_image::SectionEntry,
+    };
+
+    #[test]
+    fn test_write_section() {
+        let section = DebugLinkSection::new(0x1122_3344_5566_7788);
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11]
+        );
+        assert_eq!(DebugLinkSection::read(&section_data), section);
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Export Hash Section" stores the ABI hash of each public function and data,
+// the hash is computed from the full name and the signature (for functions) or
+// the section type and data type (for data), see `export_surface::compute_abi_hash`.
+//
+// At load time, the runtime compares the hashes recorded when linking with
+// the hashes of the shared module, which is much cheaper than comparing
+// the names and signatures of all imports.
+//
+// "Export Hash Section" binary layout:
+//
+//              |------------------------------------------------------------|
+//              | item count (u32) | extra header length (u32)               |
+//              |------------------------------------------------------------|
+//  item 0 -->  | export type 0 (u8) | section type 0 (u8) | pad 2 bytes     | <-- table
+//              | internal index 0 (u32)                                     |
+//              | hash 0 (8 bytes, little-endian u64)                        |
+//  item 1 -->  | export type 1      | section type 1      | pad 2 bytes     |
+//              | internal index 1                                           |
+//              | hash 1                                                     |
+//              | ...                                                        |
+//              |------------------------------------------------------------|
+//
+// The "section type" is meaningful only for the data, it is `0` for functions.
+// The "internal index" is the function internal index for functions, and the
+// internal index in the data section for data.
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
+    entry::ExportHashEntry,
+    module_image::{ExportType, ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct ExportHashSection<'a> {
+    pub items: &'a [ExportHashItem],
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct ExportHashItem {
+    pub export_type: ExportType,
+    pub section_type: DataSectionType,
+    _padding0: [u8; 2],
+    pub internal_index: u32,
+
+    // The hash is stored as bytes rather than `u64`, so that the
+    // alignment of the item is kept at 4 bytes.
+    pub hash: [u8; 8],
+}
+
+impl ExportHashItem {
+    pub fn new(
+        export_type: ExportType,
+        section_type: DataSectionType,
+        internal_index: u32,
+        hash: u64,
+    ) -> Self {
+        Self {
+            export_type,
+            section_type,
+            _padding0: [0, 0],
+            internal_index,
+            hash: hash.to_le_bytes(),
+        }
+    }
+
+    pub fn get_hash(&self) -> u64 {
+        u64::from_le_bytes(self.hash)
+    }
+}
+
+impl<'a> SectionEntry<'a> for ExportHashSection<'a> {
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::ExportHash
+    }
+
+    fn read(section_data: &'a [u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let items = read_section_with_one_table::<ExportHashItem>(section_data);
+        ExportHashSection { items }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_one_table(self.items, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
+}
+
+impl ExportHashSection<'_> {
+    /// Checks that the functions and data of all items are located in their sections,
+    /// `get_data_count` returns the number of the data items of the specified section.
+    pub fn is_well_formed(
+        &self,
+        function_count: usize,
+        get_data_count: impl Fn(DataSectionType) -> usize,
+    ) -> bool {
+        self.items.iter().all(|item| {
+            let item_count = match item.export_type {
+                ExportType::Function => function_count,
+                ExportType::Data => get_data_count(item.section_type),
+            };
+            (item.internal_index as usize) < item_count
+        })
+    }
+
+    pub fn get_function_hash(&self, function_internal_index: usize) -> Option<u64> {
+        self.items
+            .iter()
+            .find(|item| {
+                item.export_type == ExportType::Function
+                    && item.internal_index as usize == function_internal_index
+            })
+            .map(|item| item.get_hash())
+    }
+
+    pub fn get_data_hash(
+        &self,
+        section_type: DataSectionType,
+        internal_index_in_section: usize,
+    ) -> Option<u64> {
+        self.items
+            .iter()
+            .find(|item| {
+                item.export_type == ExportType::Data
+                    && item.section_type == section_type
+                    && item.internal_index as usize == internal_index_in_section
+            })
+            .map(|item| item.get_hash())
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<ExportHashEntry> {
+        self.items
+            .iter()
+            .map(|item| match item.export_type {
+                ExportType::Function => {
+                    ExportHashEntry::from_function(item.internal_index as usize, item.get_hash())
+                }
+                ExportType::Data => ExportHashEntry::from_data(
+                    item.section_type,
+                    item.internal_index as usize,
+                    item.get_hash(),
+                ),
+            })
+            .collect()
+    }
+
+    pub fn convert_from_entries(entries: &[ExportHashEntry]) -> Vec<ExportHashItem> {
+        entries
+            .iter()
+            .map(|entry| {
+                ExportHashItem::new(
+                    entry.export_type,
+                    entry.section_type.unwrap_or(DataSectionType::ReadOnly),
+                    entry.internal_index as u32,
+                    entry.hash,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::DataSectionType;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::export_hash_section::{ExportHashItem, ExportHashSection},
+        entry::ExportHashEntry,
+        module_image::{ExportType, SectionEntry},
+    };
+
+    #[test]
+    fn test_write_section() {
+        let items = vec![
+            ExportHashItem::new(
+                ExportType::Function,
+                DataSectionType::ReadOnly,
+                3,
+                0x1122_3344_5566_7788,
+            ),
+            ExportHashItem::new(ExportType::Data, DataSectionType::ReadWrite, 5, 0xff),
+        ];
+
+        let section = ExportHashSection { items: &items };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![
+                2u8, 0, 0, 0, // item count
+                0, 0, 0, 0, // extra section header length
+                //
+                0, // export type
+                0, // section type
+                0, 0, // padding
+                3, 0, 0, 0, // internal index
+                0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, // hash
+                //
+                1, // export type
+                1, // section type
+                0, 0, // padding
+                5, 0, 0, 0, // internal index
+                0xff, 0, 0, 0, 0, 0, 0, 0, // hash
+            ]
+        );
+
+        let section_restore = ExportHashSection::read(&section_data);
+        assert_eq!(section_restore.items, &items);
+        assert_eq!(
+            section_restore.get_function_hash(3),
+            Some(0x1122_3344_5566_7788)
+        );
+        assert_eq!(
+            section_restore.get_data_hash(DataSectionType::ReadWrite, 5),
+            Some(0xff)
+        );
+        assert_eq!(
+            section_restore.get_data_hash(DataSectionType::ReadOnly, 5),
+            None
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let entries = vec![
+            ExportHashEntry::from_function(0, 11),
+            ExportHashEntry::from_data(DataSectionType::Uninit, 1, 13),
+        ];
+
+        let items = ExportHashSection::convert_from_entries(&entries);
+        let section = ExportHashSection { items: &items };
+        assert_eq!(section.convert_to_entries(), entries);
+    }
+}
@@ -22,7 +22,8 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ExternalFunctionEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -70,12 +71,24 @@ impl<'a> SectionEntry<'a> for ExternalFunctionSection<'a> {
         write_section_with_table_and_data_area(self.items, self.names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ExternalFunction
     }
 }
 
 impl<'a> ExternalFunctionSection<'a> {
+    /// Checks that the names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(self.names_data, item.name_offset, item.name_length).is_some()
+        })
+    }
+
     /// Retrieves the function name, external library index, and type index for a given item index.
     pub fn get_item_name_and_external_library_index_and_type_index(
         &'a self,
@@ -26,7 +26,8 @@ use anc_isa::{ExternalLibraryDependency, ExternalLibraryDependencyType};
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ExternalLibraryEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -79,12 +80,28 @@ impl<'a> SectionEntry<'a> for ExternalLibrarySection<'a> {
         write_section_with_table_and_data_area(self.items, self.items_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ExternalLibrary
     }
 }
 
 impl<'a> ExternalLibrarySection<'a> {
+    /// Checks that the names and the values of all items are located in
+    /// the data area, and the values are valid dependency objects.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            let opt_value_text =
+                get_data_area_text(self.items_data, item.value_offset, item.value_length);
+            get_data_area_text(self.items_data, item.name_offset, item.name_length).is_some()
+                && opt_value_text
+                    .is_some_and(|text| ason::from_str::<ExternalLibraryDependency>(text).is_ok())
+        })
+    }
+
     pub fn get_item_name_and_external_library_dependent_type_and_value(
         &'a self,
         idx: usize,
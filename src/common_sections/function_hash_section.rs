@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Function Hash Section" stores the content hash of each function,
+// the hash is computed over the signature, the local variables and the
+// canonicalized bytecode of the function, see `function_hash::compute_function_hash`.
+//
+// The AOT/JIT compilers use the hashes as the keys of their code caches,
+// so that the compiled code of a function can be reused even if the other
+// functions of the module are changed.
+//
+// "Function Hash Section" binary layout:
+//
+//              |----------------------------------------------|
+//              | item count (u32) | extra header length (u32) |
+//              |----------------------------------------------|
+//  item 0 -->  | hash 0 (8 bytes, little-endian u64)          | <-- table
+//  item 1 -->  | hash 1                                       |
+//              | ...                                          |
+//              |----------------------------------------------|
+//
+// The items are in the order of the function internal indices.
+
+use crate::{
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
+    module_image::{ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct FunctionHashSection<'a> {
+    pub items: &'a [FunctionHashItem],
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct FunctionHashItem {
+    // The hash is stored as bytes rather than `u64`, so that the
+    // alignment of the item is kept at 4 bytes.
+    pub hash: [u8; 8],
+}
+
+impl FunctionHashItem {
+    pub fn new(hash: u64) -> Self {
+        Self {
+            hash: hash.to_le_bytes(),
+        }
+    }
+
+    pub fn get_hash(&self) -> u64 {
+        u64::from_le_bytes(self.hash)
+    }
+}
+
+impl<'a> SectionEntry<'a> for FunctionHashSection<'a> {
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::FunctionHash
+    }
+
+    fn read(section_data: &'a [u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let items = read_section_with_one_table::<FunctionHashItem>(section_data);
+        FunctionHashSection { items }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_one_table(self.items, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
+}
+
+impl FunctionHashSection<'_> {
+    /// Checks that the section is empty (i.e., the hashes are not emitted),
+    /// or contains exactly one hash for each function.
+    pub fn is_well_formed(&self, function_count: usize) -> bool {
+        self.items.is_empty() || self.items.len() == function_count
+    }
+
+    pub fn get_function_hash(&self, function_internal_index: usize) -> Option<u64> {
+        self.items
+            .get(function_internal_index)
+            .map(|item| item.get_hash())
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<u64> {
+        self.items.iter().map(|item| item.get_hash()).collect()
+    }
+
+    pub fn convert_from_entries(hashes: &[u64]) -> Vec<FunctionHashItem> {
+        hashes
+            .iter()
+            .map(|hash| FunctionHashItem::new(*hash))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::function_hash_section::{FunctionHashItem, FunctionHashSection},
+        module_image::SectionEntry,
+    };
+
+    #[test]
+    fn test_write_section() {
+        let items = FunctionHashSection::convert_from_entries(&[0x1122_3344_5566_7788, 0xff]);
+        let section = FunctionHashSection { items: &items };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![
+                2u8, 0, 0, 0, // item count
+                0, 0, 0, 0, // extra section header length
+                //
+                0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, // hash 0
+                0xff, 0, 0, 0, 0, 0, 0, 0, // hash 1
+            ]
+        );
+
+        let section_restore = FunctionHashSection::read(&section_data);
+        assert_eq!(
+            section_restore.items,
+            &[
+                FunctionHashItem::new(0x1122_3344_5566_7788),
+                FunctionHashItem::new(0xff)
+            ]
+        );
+        assert_eq!(section_restore.get_function_hash(1), Some(0xff));
+        assert_eq!(section_restore.get_function_hash(2), None);
+        assert_eq!(
+            section_restore.convert_to_entries(),
+            vec![0x1122_3344_5566_7788, 0xff]
+        );
+    }
+}
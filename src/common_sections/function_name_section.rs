@@ -46,11 +46,11 @@
 //              | item count (u32) | extra header length (u32)        |
 //              |-----------------------------------------------------|
 //  item 0 -->  | full name offset 0 (u32) | full name length 0 (u32) |
-//              | visibility 0 (u8) | pad 3 bytes                     | <-- table
+//              | visibility 0 (u8) | pad 1 byte | ordinal 0 (u16)     | <-- table
 //              | internal_index (u32)                                |
 //              |                                                     |
 //  item 1 -->  | full name offset 1       | full name length 1       |
-//              | visibility 1      | pad 3 bytes |                   |
+//              | visibility 1      | pad 1 byte | ordinal 1          |
 //              | internal_index (u32)                                |
 //              |                                                     |
 //              | ...                                                 |
@@ -60,9 +60,24 @@
 //              | ...                                                 |
 //              |-----------------------------------------------------|
 
+// Ordinals
+// --------
+// A public function can be assigned an ordinal by the toolchain, it is a number
+// which remains stable across rebuilds of the module (e.g., recorded in a
+// definition file), so the function can be looked up by the ordinal
+// instead of the full name, which is faster and robust against
+// the reordering of functions.
+//
+// The ordinals start from 1 and must be unique within a module,
+// the value `0` means that the function has no ordinal
+// (the ordinal field was a padding field in the earlier images).
+
+use std::num::NonZeroU16;
+
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::FunctionNameEntry,
     module_image::{ModuleSectionId, SectionEntry, Visibility},
@@ -92,7 +107,10 @@ pub struct FunctionNameItem {
     pub full_name_offset: u32,
     pub full_name_length: u32,
     pub visibility: Visibility,
-    _padding0: [u8; 3],
+    _padding0: u8,
+
+    // The ordinal of the function, `0` means no ordinal, see the "Ordinals" section above.
+    pub ordinal: u16,
 
     /// The function index in the function section.
     pub internal_index: u32,
@@ -109,10 +127,16 @@ impl FunctionNameItem {
             full_name_offset,
             full_name_length,
             visibility,
-            _padding0: [0, 0, 0],
+            _padding0: 0,
+            ordinal: 0,
             internal_index,
         }
     }
+
+    pub fn with_ordinal(mut self, ordinal: u16) -> Self {
+        self.ordinal = ordinal;
+        self
+    }
 }
 
 impl<'a> SectionEntry<'a> for FunctionNameSection<'a> {
@@ -129,6 +153,10 @@ impl<'a> SectionEntry<'a> for FunctionNameSection<'a> {
         write_section_with_table_and_data_area(self.items, self.full_names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.full_names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::FunctionName
     }
@@ -181,6 +209,26 @@ impl<'a> FunctionNameSection<'a> {
         })
     }
 
+    /// Retrieves `(visibility, function_internal_index)` by the ordinal.
+    ///
+    /// Returns `None` if the ordinal is `0` or not found.
+    pub fn get_item_visibility_and_function_internal_index_by_ordinal(
+        &'a self,
+        ordinal: u16,
+    ) -> Option<(
+        Visibility,
+        usize, // function_internal_index
+    )> {
+        if ordinal == 0 {
+            return None;
+        }
+
+        self.items
+            .iter()
+            .find(|item| item.ordinal == ordinal)
+            .map(|item| (item.visibility, item.internal_index as usize))
+    }
+
     /// Converts the section into a vector of `ExportFunctionEntry`.
     pub fn convert_to_entries(&self) -> Vec<FunctionNameEntry> {
         let items = self.items;
@@ -193,11 +241,16 @@ impl<'a> FunctionNameSection<'a> {
                     ..(item.full_name_offset + item.full_name_length) as usize];
 
                 let full_name = std::str::from_utf8(full_name_data).unwrap().to_owned();
-                FunctionNameEntry::new(
+                let entry = FunctionNameEntry::new(
                     full_name.to_owned(),
                     item.visibility,
                     item.internal_index as usize,
-                )
+                );
+
+                match NonZeroU16::new(item.ordinal) {
+                    Some(ordinal) => entry.with_ordinal(ordinal),
+                    None => entry,
+                }
             })
             .collect()
     }
@@ -225,6 +278,7 @@ impl<'a> FunctionNameSection<'a> {
                     entry.visibility,
                     entry.internal_index as u32,
                 )
+                .with_ordinal(entry.ordinal.map_or(0, NonZeroU16::get))
             })
             .collect::<Vec<FunctionNameItem>>();
 
@@ -235,10 +289,25 @@ impl<'a> FunctionNameSection<'a> {
 
         (items, full_names_data)
     }
+
+    /// Checks that the full names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(
+                self.full_names_data,
+                item.full_name_offset,
+                item.full_name_length,
+            )
+            .is_some()
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU16;
+
     use crate::{
         common_sections::function_name_section::{FunctionNameItem, FunctionNameSection},
         entry::FunctionNameEntry,
@@ -354,4 +423,36 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries, entries_restore);
     }
+
+    #[test]
+    fn test_ordinal() {
+        let entries = vec![
+            FunctionNameEntry::new("foo::add".to_owned(), Visibility::Public, 0)
+                .with_ordinal(NonZeroU16::new(7).unwrap()),
+            FunctionNameEntry::new("foo::sub".to_owned(), Visibility::Public, 1),
+        ];
+
+        let (items, full_names_data) = FunctionNameSection::convert_from_entries(&entries);
+        let section = FunctionNameSection {
+            items: &items,
+            full_names_data: &full_names_data,
+        };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        // the visibility, padding and ordinal of item 0
+        assert_eq!(&section_data[16..20], &[1, 0, 7, 0]);
+
+        let section_restore = FunctionNameSection::read(&section_data);
+        assert_eq!(
+            section_restore.get_item_visibility_and_function_internal_index_by_ordinal(7),
+            Some((Visibility::Public, 0))
+        );
+        assert_eq!(
+            section_restore.get_item_visibility_and_function_internal_index_by_ordinal(0),
+            None
+        );
+        assert_eq!(section_restore.convert_to_entries(), entries);
+    }
 }
@@ -22,7 +22,8 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        read_section_with_table_and_data_area, section_with_table_and_data_area_length,
+        write_section_with_table_and_data_area,
     },
     entry::FunctionEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -70,6 +71,10 @@ impl<'a> SectionEntry<'a> for FunctionSection<'a> {
         write_section_with_table_and_data_area(self.items, self.codes_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.codes_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::Function
     }
@@ -116,31 +121,51 @@ impl<'a> FunctionSection<'a> {
             .collect()
     }
 
+    /// Checks that the code of all functions is located in the data area.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            let start = item.code_offset as usize;
+            let end = start + item.code_length as usize;
+            end <= self.codes_data.len()
+        })
+    }
+
     /// Converts a vector of `FunctionEntry` objects into the section's internal representation.
     pub fn convert_from_entries(entries: &[FunctionEntry]) -> (Vec<FunctionItem>, Vec<u8>) {
-        let mut next_offset: u32 = 0;
+        let mut items: Vec<FunctionItem> = vec![];
+        let mut codes_data: Vec<u8> = vec![];
+        Self::convert_from_entries_into(entries, &mut items, &mut codes_data);
+        (items, codes_data)
+    }
 
-        let items = entries
-            .iter()
-            .map(|entry| {
-                let code_offset = next_offset;
-                let code_length = entry.code.len() as u32;
-                next_offset += code_length; // for next offset
-                FunctionItem::new(
-                    code_offset,
-                    code_length,
-                    entry.type_index as u32,
-                    entry.local_variable_list_index as u32,
-                )
-            })
-            .collect::<Vec<FunctionItem>>();
+    /// Same as `convert_from_entries`, but writes the result into the given buffers.
+    ///
+    /// The buffers are cleared first, their capacity is kept, so the caller
+    /// can reuse them across many modules without reallocating.
+    pub fn convert_from_entries_into(
+        entries: &[FunctionEntry],
+        items: &mut Vec<FunctionItem>,
+        codes_data: &mut Vec<u8>,
+    ) {
+        items.clear();
+        codes_data.clear();
+        items.reserve(entries.len());
 
-        let codes_data = entries
-            .iter()
-            .flat_map(|entry| entry.code.clone())
-            .collect::<Vec<u8>>();
+        let mut next_offset: u32 = 0;
 
-        (items, codes_data)
+        for entry in entries {
+            let code_offset = next_offset;
+            let code_length = entry.code.len() as u32;
+            next_offset += code_length; // for next offset
+
+            items.push(FunctionItem::new(
+                code_offset,
+                code_length,
+                entry.type_index as u32,
+                entry.local_variable_list_index as u32,
+            ));
+            codes_data.extend_from_slice(&entry.code);
+        }
     }
 }
 
@@ -248,4 +273,31 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries, entries_restore);
     }
+
+    #[test]
+    fn test_convert_into_reused_buffers() {
+        let entries0 = vec![FunctionEntry::new(7, 9, b"bar".to_vec())];
+        let entries1 = vec![
+            FunctionEntry::new(11, 13, b"world".to_vec()),
+            FunctionEntry::new(17, 19, b"hi".to_vec()),
+        ];
+
+        let mut items: Vec<FunctionItem> = vec![];
+        let mut codes_data: Vec<u8> = vec![];
+
+        FunctionSection::convert_from_entries_into(&entries0, &mut items, &mut codes_data);
+        assert_eq!(items, vec![FunctionItem::new(0, 3, 7, 9)]);
+        assert_eq!(codes_data, b"bar");
+
+        // the previous content should be discarded
+        FunctionSection::convert_from_entries_into(&entries1, &mut items, &mut codes_data);
+        assert_eq!(
+            items,
+            vec![
+                FunctionItem::new(0, 5, 11, 13),
+                FunctionItem::new(5, 2, 17, 19)
+            ]
+        );
+        assert_eq!(codes_data, b"worldhi");
+    }
 }
@@ -10,6 +10,10 @@
 // - "uninitialized data"
 // However, this order is not strictly enforced.
 // Note that the order is unrelated to the "data internal index" or the "imported module index".
+//
+// The "requires init" flag (`0` or `1`) declares that the imported data must be
+// initialized (i.e., the constructors of the module which owns the data have run)
+// before the constructors of the current module run, see `initialization_order`.
 
 // "Import Data Section" binary layout:
 //
@@ -18,10 +22,10 @@
 //              |-------------------------------------------------------|
 //  item 0 -->  | full name off 0 (u32) | full name length 0 (u32)      |
 //              | import module idx 0 (u32) | data section type 0 (u8)  |
-//              | memory data type 0 (u8) | pad 2 bytes                 | <-- table
+//              | memory data type 0 (u8) | requires init 0 (u8) | pad  | <-- table
 //  item 1 -->  | full name off 1       | full name length 1            |
 //              | import module idx 1       | data section type 1       |
-//              | memory data type 0 (u8) | pad 2 bytes                 |
+//              | memory data type 1      | requires init 1      | pad  |
 //              | ...                                                   |
 //              |-------------------------------------------------------|
 // offset 0 --> | full name string 0 (UTF-8)                            | <-- data
@@ -33,7 +37,8 @@ use anc_isa::{DataSectionType, MemoryDataType};
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ImportDataEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -63,7 +68,8 @@ pub struct ImportDataItem {
     pub import_module_index: u32, // Index of the import module
     pub data_section_type: DataSectionType, // Type of the data section
     pub memory_data_type: MemoryDataType, // Type of the memory data
-    _padding0: [u8; 2],        // Padding for alignment
+    pub requires_initialization: u8, // `1` if the data must be initialized before the constructors
+    _padding0: [u8; 1],        // Padding for alignment
 }
 
 impl ImportDataItem {
@@ -73,6 +79,7 @@ impl ImportDataItem {
         import_module_index: u32,
         data_section_type: DataSectionType,
         memory_data_type: MemoryDataType,
+        requires_initialization: bool,
     ) -> Self {
         Self {
             full_name_offset,
@@ -80,7 +87,8 @@ impl ImportDataItem {
             import_module_index,
             data_section_type,
             memory_data_type,
-            _padding0: [0; 2],
+            requires_initialization: requires_initialization as u8,
+            _padding0: [0; 1],
         }
     }
 }
@@ -99,12 +107,29 @@ impl<'a> SectionEntry<'a> for ImportDataSection<'a> {
         write_section_with_table_and_data_area(self.items, self.full_names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.full_names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ImportData
     }
 }
 
 impl<'a> ImportDataSection<'a> {
+    /// Checks that the full names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(
+                self.full_names_data,
+                item.full_name_offset,
+                item.full_name_length,
+            )
+            .is_some()
+        })
+    }
+
     /// Retrieves the full name, import module index, data section type, and memory data type of an item at the specified index.
     pub fn get_item_full_name_and_import_module_index_and_data_section_type_and_memory_data_type(
         &'a self,
@@ -142,6 +167,7 @@ impl<'a> ImportDataSection<'a> {
                     item.data_section_type,
                     item.memory_data_type,
                 )
+                .with_requires_initialization(item.requires_initialization != 0)
             })
             .collect()
     }
@@ -169,6 +195,7 @@ impl<'a> ImportDataSection<'a> {
                     entry.import_module_index as u32,
                     entry.data_section_type,
                     entry.memory_data_type,
+                    entry.requires_initialization,
                 )
             })
             .collect::<Vec<ImportDataItem>>();
@@ -203,14 +230,16 @@ mod tests {
             11, 0, 0, 0, // import module index
             0, // data section type
             0, // mem data type
-            0, 0, // padding
+            0, // requires initialization
+            0, // padding
             //
             3, 0, 0, 0, // name offset (item 1)
             5, 0, 0, 0, // name length
             13, 0, 0, 0, // import module index
             1, // data section type
             1, // mem data type
-            0, 0, // padding
+            1, // requires initialization
+            0, // padding
         ];
 
         section_data.extend_from_slice(b"foo");
@@ -221,11 +250,25 @@ mod tests {
         assert_eq!(section.items.len(), 2);
         assert_eq!(
             section.items[0],
-            ImportDataItem::new(0, 3, 11, DataSectionType::ReadOnly, MemoryDataType::I32,)
+            ImportDataItem::new(
+                0,
+                3,
+                11,
+                DataSectionType::ReadOnly,
+                MemoryDataType::I32,
+                false
+            )
         );
         assert_eq!(
             section.items[1],
-            ImportDataItem::new(3, 5, 13, DataSectionType::ReadWrite, MemoryDataType::I64,)
+            ImportDataItem::new(
+                3,
+                5,
+                13,
+                DataSectionType::ReadWrite,
+                MemoryDataType::I64,
+                true
+            )
         );
         assert_eq!(section.full_names_data, "foohello".as_bytes())
     }
@@ -233,8 +276,22 @@ mod tests {
     #[test]
     fn test_write_section() {
         let items = vec![
-            ImportDataItem::new(0, 3, 11, DataSectionType::ReadOnly, MemoryDataType::I32),
-            ImportDataItem::new(3, 5, 13, DataSectionType::ReadWrite, MemoryDataType::I64),
+            ImportDataItem::new(
+                0,
+                3,
+                11,
+                DataSectionType::ReadOnly,
+                MemoryDataType::I32,
+                false,
+            ),
+            ImportDataItem::new(
+                3,
+                5,
+                13,
+                DataSectionType::ReadWrite,
+                MemoryDataType::I64,
+                true,
+            ),
         ];
 
         let section = ImportDataSection {
@@ -254,14 +311,16 @@ mod tests {
             11, 0, 0, 0, // import module index
             0, // data section type
             0, // mem data type
-            0, 0, // padding
+            0, // requires initialization
+            0, // padding
             //
             3, 0, 0, 0, // name offset (item 1)
             5, 0, 0, 0, // name length
             13, 0, 0, 0, // import module index
             1, // data section type
             1, // mem data type
-            0, 0, // padding
+            1, // requires initialization
+            0, // padding
         ];
 
         expect_data.extend_from_slice(b"foo");
@@ -284,7 +343,8 @@ mod tests {
                 13,
                 DataSectionType::ReadWrite,
                 MemoryDataType::I64,
-            ),
+            )
+            .with_requires_initialization(true),
         ];
 
         let (items, names_data) = ImportDataSection::convert_from_entries(&entries);
@@ -25,7 +25,8 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ImportFunctionEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -86,12 +87,29 @@ impl<'a> SectionEntry<'a> for ImportFunctionSection<'a> {
         write_section_with_table_and_data_area(self.items, self.full_names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.full_names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ImportFunction
     }
 }
 
 impl<'a> ImportFunctionSection<'a> {
+    /// Checks that the full names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(
+                self.full_names_data,
+                item.full_name_offset,
+                item.full_name_length,
+            )
+            .is_some()
+        })
+    }
+
     /// Retrieves the full name, import module index, and type index of an item at the specified index.
     pub fn get_item_full_name_and_import_module_index_and_type_index(
         &'a self,
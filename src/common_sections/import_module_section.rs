@@ -11,20 +11,28 @@
 //              |---------------------------------------------------------|
 //  item 0 -->  | module name offset 0 (u32) | module name length 0 (u32) |
 //              | value offset (u32) | value length 0 (u32)               | <-- table
+//              | version range offset 0 (u32) | version range length 0   |
 //  item 1 -->  | module name offset 1       | module name length 1       |
 //              | value offset       | value offset 1                     |
+//              | version range offset 1 | version range length 1         |
 //              | ...                                                     |
 //              |---------------------------------------------------------|
 // offset 0 --> | name string 0 (UTF-8) | value string 0 (UTF-8)          | <-- data
+//              | version range string 0 (UTF-8)                          |
 // offset 1 --> | name string 1         | value string 1 (UTF-8)          |
+//              | version range string 1 (UTF-8)                          |
 //              | ...                                                     |
 //              |---------------------------------------------------------|
+//
+// The version range is optional, its length is `0` if the import does not
+// request a version range, see `version_range` for the syntax.
 
 use anc_isa::ModuleDependency;
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ImportModuleEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -39,19 +47,30 @@ pub struct ImportModuleSection<'a> {
 #[repr(C)]
 #[derive(Debug, PartialEq)]
 pub struct ImportModuleItem {
-    pub name_offset: u32,  // Offset of the name string in the data area
-    pub name_length: u32,  // Length (in bytes) of the name string in the data area
-    pub value_offset: u32, // Offset of the value string in the data area
-    pub value_length: u32, // Length (in bytes) of the value string in the data area
+    pub name_offset: u32,          // Offset of the name string in the data area
+    pub name_length: u32,          // Length (in bytes) of the name string in the data area
+    pub value_offset: u32,         // Offset of the value string in the data area
+    pub value_length: u32,         // Length (in bytes) of the value string in the data area
+    pub version_range_offset: u32, // Offset of the version range string in the data area
+    pub version_range_length: u32, // Length (in bytes) of the version range string
 }
 
 impl ImportModuleItem {
-    pub fn new(name_offset: u32, name_length: u32, value_offset: u32, value_length: u32) -> Self {
+    pub fn new(
+        name_offset: u32,
+        name_length: u32,
+        value_offset: u32,
+        value_length: u32,
+        version_range_offset: u32,
+        version_range_length: u32,
+    ) -> Self {
         Self {
             name_offset,
             name_length,
             value_offset,
             value_length,
+            version_range_offset,
+            version_range_length,
         }
     }
 }
@@ -70,12 +89,34 @@ impl<'a> SectionEntry<'a> for ImportModuleSection<'a> {
         write_section_with_table_and_data_area(self.items, self.items_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ImportModule
     }
 }
 
 impl<'a> ImportModuleSection<'a> {
+    /// Checks that the names, the values and the version ranges of all items are
+    /// located in the data area, and the values are valid dependency objects.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            let opt_value_text =
+                get_data_area_text(self.items_data, item.value_offset, item.value_length);
+            get_data_area_text(self.items_data, item.name_offset, item.name_length).is_some()
+                && opt_value_text
+                    .is_some_and(|text| ason::from_str::<ModuleDependency>(text).is_ok())
+                && get_data_area_text(
+                    self.items_data,
+                    item.version_range_offset,
+                    item.version_range_length,
+                )
+                .is_some()
+        })
+    }
+
     /// Retrieves the name and value of an item at the specified index.
     pub fn get_item_name_and_value(&'a self, idx: usize) -> (&'a str, &'a [u8]) {
         let items = self.items;
@@ -103,9 +144,19 @@ impl<'a> ImportModuleSection<'a> {
                 let value_data = &items_data
                     [item.value_offset as usize..(item.value_offset + item.value_length) as usize];
 
+                let version_range_data = &items_data[item.version_range_offset as usize
+                    ..(item.version_range_offset + item.version_range_length) as usize];
+
                 let name = std::str::from_utf8(name_data).unwrap().to_owned();
                 let module_dependency: ModuleDependency = ason::from_reader(value_data).unwrap();
-                ImportModuleEntry::new(name, Box::new(module_dependency))
+                let entry = ImportModuleEntry::new(name, Box::new(module_dependency));
+
+                if version_range_data.is_empty() {
+                    entry
+                } else {
+                    let version_range = std::str::from_utf8(version_range_data).unwrap();
+                    entry.with_version_range(version_range.to_owned())
+                }
             })
             .collect()
     }
@@ -126,26 +177,43 @@ impl<'a> ImportModuleSection<'a> {
             })
             .collect::<Vec<Vec<u8>>>();
 
+        let mut version_range_bytes = entries
+            .iter()
+            .map(|entry| match &entry.version_range {
+                Some(version_range) => version_range.as_bytes().to_vec(),
+                None => vec![],
+            })
+            .collect::<Vec<Vec<u8>>>();
+
         let mut next_offset: u32 = 0;
 
         let items = (0..entries.len())
             .map(|idx| {
                 let name_length = name_bytes[idx].len() as u32;
                 let value_length = value_bytes[idx].len() as u32;
+                let version_range_length = version_range_bytes[idx].len() as u32;
                 let name_offset = next_offset;
                 let value_offset = name_offset + name_length;
-                next_offset = value_offset + value_length; // for next offset
-
-                ImportModuleItem::new(name_offset, name_length, value_offset, value_length)
+                let version_range_offset = value_offset + value_length;
+                next_offset = version_range_offset + version_range_length; // for next offset
+
+                ImportModuleItem::new(
+                    name_offset,
+                    name_length,
+                    value_offset,
+                    value_length,
+                    version_range_offset,
+                    version_range_length,
+                )
             })
             .collect::<Vec<ImportModuleItem>>();
 
-        let items_data = name_bytes
-            .iter_mut()
-            .zip(value_bytes.iter_mut())
-            .flat_map(|(name_bytes, value_bytes)| {
-                name_bytes.append(value_bytes);
-                name_bytes.to_owned()
+        let items_data = (0..entries.len())
+            .flat_map(|idx| {
+                let mut item_bytes = std::mem::take(&mut name_bytes[idx]);
+                item_bytes.append(&mut value_bytes[idx]);
+                item_bytes.append(&mut version_range_bytes[idx]);
+                item_bytes
             })
             .collect::<Vec<u8>>();
 
@@ -175,36 +243,41 @@ mod tests {
             3, 0, 0, 0, // name length
             3, 0, 0, 0, // value offset
             5, 0, 0, 0, // value length
+            8, 0, 0, 0, // version range offset
+            0, 0, 0, 0, // version range length
             //
             8, 0, 0, 0, // name offset (item 1)
             4, 0, 0, 0, // name length
             12, 0, 0, 0, // value offset
             6, 0, 0, 0, // value length
+            18, 0, 0, 0, // version range offset
+            2, 0, 0, 0, // version range length
         ];
 
         section_data.extend_from_slice(b"foo");
         section_data.extend_from_slice(b"hello");
         section_data.extend_from_slice(b".bar");
         section_data.extend_from_slice(b".world");
+        section_data.extend_from_slice(b"^1");
 
         let section = ImportModuleSection::read(&section_data);
 
         assert_eq!(section.items.len(), 2);
-        assert_eq!(section.items[0], ImportModuleItem::new(0, 3, 3, 5));
-        assert_eq!(section.items[1], ImportModuleItem::new(8, 4, 12, 6));
-        assert_eq!(section.items_data, "foohello.bar.world".as_bytes())
+        assert_eq!(section.items[0], ImportModuleItem::new(0, 3, 3, 5, 8, 0));
+        assert_eq!(section.items[1], ImportModuleItem::new(8, 4, 12, 6, 18, 2));
+        assert_eq!(section.items_data, "foohello.bar.world^1".as_bytes())
     }
 
     #[test]
     fn test_write_section() {
         let items = vec![
-            ImportModuleItem::new(0, 3, 3, 5),
-            ImportModuleItem::new(8, 4, 12, 6),
+            ImportModuleItem::new(0, 3, 3, 5, 8, 0),
+            ImportModuleItem::new(8, 4, 12, 6, 18, 2),
         ];
 
         let section = ImportModuleSection {
             items: &items,
-            items_data: b"foohello.bar.world",
+            items_data: b"foohello.bar.world^1",
         };
 
         let mut section_data: Vec<u8> = vec![];
@@ -218,19 +291,22 @@ mod tests {
             3, 0, 0, 0, // name length
             3, 0, 0, 0, // value offset
             5, 0, 0, 0, // value length
+            8, 0, 0, 0, // version range offset
+            0, 0, 0, 0, // version range length
             //
             8, 0, 0, 0, // name offset (item 1)
             4, 0, 0, 0, // name length
             12, 0, 0, 0, // value offset
             6, 0, 0, 0, // value length
+            18, 0, 0, 0, // version range offset
+            2, 0, 0, 0, // version range length
         ];
 
         expect_data.extend_from_slice(b"foo");
         expect_data.extend_from_slice(b"hello");
         expect_data.extend_from_slice(b".bar");
         expect_data.extend_from_slice(b".world");
-
-        expect_data.extend_from_slice(&[0, 0]); // padding for 4-byte align
+        expect_data.extend_from_slice(b"^1");
 
         assert_eq!(section_data, expect_data);
     }
@@ -255,7 +331,8 @@ mod tests {
                     condition: DependencyCondition::True,
                     parameters: HashMap::default(),
                 }))),
-            ),
+            )
+            .with_version_range(">=1.0, <1.5".to_owned()),
         ];
 
         let (items, items_data) = ImportModuleSection::convert_from_entries(&entries);
@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Initializer Section" declares the functions of the module which
+// must be run by the runtime outside of the normal control flow:
+//
+// - Constructors: run before the entry point of the application,
+//   e.g., the static initializers of the module.
+// - Finalizers: run at the shutdown of the application (after the entry point
+//   returns), e.g., flushing buffers.
+//
+// The functions of each type are run in the order they are declared in this section.
+// The constructors of a module are run after the constructors of its dependencies,
+// and the finalizers are run in the reverse order of modules. A module can also
+// require the imported data to be initialized first, see `initialization_order`.
+//
+// The initializer functions must be public internal functions of the module
+// with the signature `() -> ()`, see `validator::validate_initializers`.
+//
+// "Initializer Section" binary layout:
+//
+//              |--------------------------------------------------|
+//              | item count (u32) | extra header length (u32)     |
+//              |--------------------------------------------------|
+//  item 0 -->  | function internal index 0 (u32)                  | <-- table
+//              | initializer type 0 (u8) | pad 3 bytes            |
+//  item 1 -->  | function internal index 1                        |
+//              | initializer type 1      | pad 3 bytes            |
+//              | ...                                              |
+//              |--------------------------------------------------|
+
+use crate::{
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
+    entry::InitializerEntry,
+    module_image::{InitializerType, ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct InitializerSection<'a> {
+    pub items: &'a [InitializerItem],
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct InitializerItem {
+    pub function_internal_index: u32,
+    pub initializer_type: InitializerType,
+    _padding0: [u8; 3],
+}
+
+impl InitializerItem {
+    pub fn new(function_internal_index: u32, initializer_type: InitializerType) -> Self {
+        Self {
+            function_internal_index,
+            initializer_type,
+            _padding0: [0; 3],
+        }
+    }
+}
+
+impl<'a> SectionEntry<'a> for InitializerSection<'a> {
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::Initializer
+    }
+
+    fn read(section_data: &'a [u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let items = read_section_with_one_table::<InitializerItem>(section_data);
+        InitializerSection { items }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_one_table(self.items, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
+}
+
+impl InitializerSection<'_> {
+    /// Checks that the functions of all items are located in the function section,
+    /// i.e., the function internal indices are less than `function_count`.
+    pub fn is_well_formed(&self, function_count: usize) -> bool {
+        self.items
+            .iter()
+            .all(|item| (item.function_internal_index as usize) < function_count)
+    }
+
+    /// Returns the function internal indices of the specified type, in the declared order.
+    pub fn get_function_internal_indices(&self, initializer_type: InitializerType) -> Vec<usize> {
+        self.items
+            .iter()
+            .filter(|item| item.initializer_type == initializer_type)
+            .map(|item| item.function_internal_index as usize)
+            .collect()
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<InitializerEntry> {
+        self.items
+            .iter()
+            .map(|item| {
+                InitializerEntry::new(item.initializer_type, item.function_internal_index as usize)
+            })
+            .collect()
+    }
+
+    pub fn convert_from_entries(entries: &[InitializerEntry]) -> Vec<InitializerItem> {
+        entries
+            .iter()
+            .map(|entry| {
+                InitializerItem::new(entry.function_internal_index as u32, entry.initializer_type)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::initializer_section::{InitializerItem, InitializerSection},
+        entry::InitializerEntry,
+        module_image::{InitializerType, SectionEntry},
+    };
+
+    #[test]
+    fn test_write_section() {
+        let items = vec![
+            InitializerItem::new(3, InitializerType::Constructor),
+            InitializerItem::new(5, InitializerType::Finalizer),
+        ];
+
+        let section = InitializerSection { items: &items };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![
+                2u8, 0, 0, 0, // item count
+                0, 0, 0, 0, // extra section header length
+                //
+                3, 0, 0, 0, // function internal index
+                0, // initializer type
+                0, 0, 0, // padding
+                //
+                5, 0, 0, 0, // function internal index
+                1, // initializer type
+                0, 0, 0, // padding
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_section() {
+        let section_data = vec![
+            2u8, 0, 0, 0, // item count
+            0, 0, 0, 0, // extra section header length
+            //
+            3, 0, 0, 0, // function internal index
+            0, // initializer type
+            0, 0, 0, // padding
+            //
+            5, 0, 0, 0, // function internal index
+            1, // initializer type
+            0, 0, 0, // padding
+        ];
+
+        let section = InitializerSection::read(&section_data);
+        assert_eq!(
+            section.items,
+            &[
+                InitializerItem::new(3, InitializerType::Constructor),
+                InitializerItem::new(5, InitializerType::Finalizer),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let entries = vec![
+            InitializerEntry::new(InitializerType::Constructor, 2),
+            InitializerEntry::new(InitializerType::Finalizer, 0),
+            InitializerEntry::new(InitializerType::Constructor, 1),
+        ];
+
+        let items = InitializerSection::convert_from_entries(&entries);
+        let section = InitializerSection { items: &items };
+
+        assert_eq!(
+            section.get_function_internal_indices(InitializerType::Constructor),
+            vec![2, 1]
+        );
+        assert_eq!(
+            section.get_function_internal_indices(InitializerType::Finalizer),
+            vec![0]
+        );
+        assert_eq!(section.convert_to_entries(), entries);
+    }
+}
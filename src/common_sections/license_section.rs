@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "License Section" records the license identifiers (SPDX license
+// expressions, e.g. "MIT OR Apache-2.0") of the module itself and of
+// the external libraries which the module depends on, see `license_scan`.
+//
+// "License Section" binary layout:
+//
+//              |--------------------------------------------------------|
+//              | item count (u32) | extra header length (u32)           |
+//              |--------------------------------------------------------|
+//  item 0 -->  | license offset 0 (u32) | license length 0 (u32)        | <-- table
+//              | external library index 0 (u32)                         |
+//              | target type 0 (u8) | padding (3 bytes)                 |
+//  item 1 -->  | license offset 1       | license length 1              |
+//              | external library index 1 (u32)                         |
+//              | target type 1 (u8) | padding (3 bytes)                 |
+//              | ...                                                    |
+//              |--------------------------------------------------------|
+// offset 0 --> | license string 0 (UTF-8)                               | <-- data
+// offset 1 --> | license string 1                                       |
+//              | ...                                                    |
+//              |--------------------------------------------------------|
+//
+// The external library index is the index of the item in the
+// "external library" section of the same module, it is `0` and
+// is ignored when the target type is `LicenseTargetType::Module`.
+
+use crate::{
+    datatableaccess::{
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
+    },
+    entry::LicenseEntry,
+    module_image::{LicenseTargetType, ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct LicenseSection<'a> {
+    pub items: &'a [LicenseItem],
+    pub items_data: &'a [u8], // UTF-8 encoded license expressions
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct LicenseItem {
+    pub license_offset: u32, // Offset of the license string in the data area
+    pub license_length: u32, // Length (in bytes) of the license string in the data area
+    pub external_library_index: u32, // Index of the external library, for libraries only
+    pub target_type: LicenseTargetType, // The module itself or an external library
+    _padding0: [u8; 3],      // Padding for alignment
+}
+
+impl LicenseItem {
+    pub fn new(
+        license_offset: u32,
+        license_length: u32,
+        external_library_index: u32,
+        target_type: LicenseTargetType,
+    ) -> Self {
+        Self {
+            license_offset,
+            license_length,
+            external_library_index,
+            target_type,
+            _padding0: [0; 3],
+        }
+    }
+}
+
+impl<'a> SectionEntry<'a> for LicenseSection<'a> {
+    fn read(section_data: &'a [u8]) -> Self {
+        let (items, items_data) =
+            read_section_with_table_and_data_area::<LicenseItem>(section_data);
+        LicenseSection { items, items_data }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_table_and_data_area(self.items, self.items_data, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::License
+    }
+}
+
+impl<'a> LicenseSection<'a> {
+    /// Checks that the licenses of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(self.items_data, item.license_offset, item.license_length).is_some()
+        })
+    }
+
+    pub fn get_item_license(&'a self, idx: usize) -> &'a str {
+        let item = &self.items[idx];
+        let license_data = &self.items_data
+            [item.license_offset as usize..(item.license_offset + item.license_length) as usize];
+        std::str::from_utf8(license_data).unwrap()
+    }
+
+    /// Returns the license of the module itself, or `None` if it is not recorded.
+    pub fn get_module_license(&'a self) -> Option<&'a str> {
+        self.items
+            .iter()
+            .position(|item| item.target_type == LicenseTargetType::Module)
+            .map(|idx| self.get_item_license(idx))
+    }
+
+    /// Returns the license of the specified external library,
+    /// or `None` if it is not recorded.
+    pub fn get_external_library_license(
+        &'a self,
+        external_library_index: usize,
+    ) -> Option<&'a str> {
+        self.items
+            .iter()
+            .position(|item| {
+                item.target_type == LicenseTargetType::ExternalLibrary
+                    && item.external_library_index as usize == external_library_index
+            })
+            .map(|idx| self.get_item_license(idx))
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<LicenseEntry> {
+        (0..self.items.len())
+            .map(|idx| {
+                let item = &self.items[idx];
+                LicenseEntry::new(
+                    item.target_type,
+                    item.external_library_index as usize,
+                    self.get_item_license(idx).to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn convert_from_entries(entries: &[LicenseEntry]) -> (Vec<LicenseItem>, Vec<u8>) {
+        let mut next_offset: u32 = 0;
+
+        let items = entries
+            .iter()
+            .map(|entry| {
+                let license_offset = next_offset;
+                let license_length = entry.license.len() as u32;
+                next_offset += license_length; // for next offset
+
+                LicenseItem::new(
+                    license_offset,
+                    license_length,
+                    entry.external_library_index as u32,
+                    entry.target_type,
+                )
+            })
+            .collect::<Vec<LicenseItem>>();
+
+        let items_data = entries
+            .iter()
+            .flat_map(|entry| entry.license.as_bytes().to_vec())
+            .collect::<Vec<u8>>();
+
+        (items, items_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::license_section::{LicenseItem, LicenseSection},
+        entry::LicenseEntry,
+        module_image::{LicenseTargetType, SectionEntry},
+    };
+
+    #[test]
+    fn test_write_section() {
+        let entries = vec![
+            LicenseEntry::for_module("MIT".to_owned()),
+            LicenseEntry::for_external_library(1, "Zlib".to_owned()),
+        ];
+
+        let (items, items_data) = LicenseSection::convert_from_entries(&entries);
+        let section = LicenseSection {
+            items: &items,
+            items_data: &items_data,
+        };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        let mut expect_data = vec![
+            2u8, 0, 0, 0, // item count
+            0, 0, 0, 0, // extra section header len (i32)
+            //
+            0, 0, 0, 0, // license offset (item 0)
+            3, 0, 0, 0, // license length
+            0, 0, 0, 0, // external library index
+            0, // target type
+            0, 0, 0, // padding
+            //
+            3, 0, 0, 0, // license offset (item 1)
+            4, 0, 0, 0, // license length
+            1, 0, 0, 0, // external library index
+            1, // target type
+            0, 0, 0, // padding
+        ];
+
+        expect_data.extend_from_slice(b"MIT");
+        expect_data.extend_from_slice(b"Zlib");
+        expect_data.extend_from_slice(&[0]); // padding for 4-byte align
+
+        assert_eq!(section_data, expect_data);
+
+        let section_restore = LicenseSection::read(&section_data);
+        assert_eq!(
+            section_restore.items,
+            &[
+                LicenseItem::new(0, 3, 0, LicenseTargetType::Module),
+                LicenseItem::new(3, 4, 1, LicenseTargetType::ExternalLibrary),
+            ]
+        );
+        assert_eq!(section_restore.get_module_license(), Some("MIT"));
+        assert_eq!(
+            section_restore.get_external_library_license(1),
+            Some("Zlib")
+        );
+        assert_eq!(section_restore.get_external_library_license(0), None);
+        assert_eq!(section_restore.convert_to_entries(), entries);
+    }
+}
@@ -56,8 +56,10 @@ use std::mem::size_of;
 use anc_isa::{OperandDataType, OPERAND_SIZE_IN_BYTES};
 
 use crate::{
+    common_sections::type_section::is_valid_operand_data_type,
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        read_section_with_table_and_data_area, section_with_table_and_data_area_length,
+        write_section_with_table_and_data_area,
     },
     entry::LocalVariableListEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -149,6 +151,10 @@ impl<'a> SectionEntry<'a> for LocalVariableSection<'a> {
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         write_section_with_table_and_data_area(self.lists, self.list_data, writer)
     }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.lists, self.list_data)
+    }
 }
 
 impl<'a> LocalVariableSection<'a> {
@@ -198,109 +204,115 @@ impl<'a> LocalVariableSection<'a> {
             .collect()
     }
 
+    /// Checks that all lists are located in the data area and all variables
+    /// have a valid operand data type.
+    pub fn is_well_formed(&self) -> bool {
+        const LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES: usize = size_of::<LocalVariableItem>();
+
+        // the offset of the field `operand_data_type` in the item
+        const OPERAND_DATA_TYPE_OFFSET_IN_ITEM: usize = 8;
+
+        self.lists.iter().all(|list| {
+            let start = list.list_offset as usize;
+            (list.list_item_count as usize)
+                .checked_mul(LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES)
+                .and_then(|length| self.list_data.get(start..start + length))
+                .is_some_and(|items_data| {
+                    start % align_of::<LocalVariableItem>() == 0
+                        && items_data
+                            .chunks_exact(LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES)
+                            .all(|item_data| {
+                                is_valid_operand_data_type(
+                                    item_data[OPERAND_DATA_TYPE_OFFSET_IN_ITEM],
+                                )
+                            })
+                })
+        })
+    }
+
     /// Converts a vector of `LocalVariableListEntry` objects into the section's internal representation.
     pub fn convert_from_entries(
         entries: &[LocalVariableListEntry],
     ) -> (Vec<LocalVariableList>, Vec<u8>) {
-        const LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES: usize = size_of::<LocalVariableItem>();
-
-        // Generate a list of (list, variables_allocated_bytes)
-        let items_list_with_variables_allocated_bytes = entries
-            .iter()
-            .map(|list_entry| {
-                // The offset in the list
-                let mut variable_offset_next: u32 = 0;
+        let mut lists: Vec<LocalVariableList> = vec![];
+        let mut list_data: Vec<u8> = vec![];
+        Self::convert_from_entries_into(entries, &mut lists, &mut list_data);
+        (lists, list_data)
+    }
 
-                let items = list_entry
-                    .local_variable_types
-                    .iter()
-                    .map(|operand_data_type| {
-                        let item = match operand_data_type {
-                            OperandDataType::I32 => LocalVariableItem::new(
-                                variable_offset_next,
-                                4,
-                                OperandDataType::I32,
-                            ),
-                            OperandDataType::I64 => LocalVariableItem::new(
-                                variable_offset_next,
-                                8,
-                                OperandDataType::I64,
-                            ),
-                            OperandDataType::F32 => LocalVariableItem::new(
-                                variable_offset_next,
-                                4,
-                                OperandDataType::F32,
-                            ),
-                            OperandDataType::F64 => LocalVariableItem::new(
-                                variable_offset_next,
-                                8,
-                                OperandDataType::F64,
-                            ),
-                        };
-
-                        // Pad the length of variable/data to the multiple of 8
-                        let padding = {
-                            let remainder =
-                                item.variable_actual_size_in_bytes % OPERAND_SIZE_IN_BYTES as u32; // Remainder
-                            if remainder != 0 {
-                                OPERAND_SIZE_IN_BYTES as u32 - remainder
-                            } else {
-                                0
-                            }
-                        };
-
-                        let variables_allocated_bytes =
-                            item.variable_actual_size_in_bytes + padding;
-                        variable_offset_next += variables_allocated_bytes;
-                        item
-                    })
-                    .collect::<Vec<LocalVariableItem>>();
+    /// Same as `convert_from_entries`, but writes the result into the given buffers.
+    ///
+    /// The buffers are cleared first, their capacity is kept, so the caller
+    /// can reuse them across many modules without reallocating.
+    pub fn convert_from_entries_into(
+        entries: &[LocalVariableListEntry],
+        lists: &mut Vec<LocalVariableList>,
+        list_data: &mut Vec<u8>,
+    ) {
+        const LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES: usize = size_of::<LocalVariableItem>();
 
-                // Now `var_offset_next` is the `variables_allocated_bytes * N`
-                (items, variable_offset_next)
-            })
-            .collect::<Vec<(Vec<LocalVariableItem>, u32)>>();
+        lists.clear();
+        list_data.clear();
+        lists.reserve(entries.len());
 
-        // Make lists
         let mut list_offset_next: u32 = 0;
-        let lists = items_list_with_variables_allocated_bytes
-            .iter()
-            .map(|(list, variables_allocated_bytes)| {
-                let list_offset = list_offset_next;
-                let list_item_count = list.len() as u32;
-                list_offset_next +=
-                    list_item_count * LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES as u32;
-
-                LocalVariableList {
-                    list_offset,
-                    list_item_count,
-                    allocated_bytes: *variables_allocated_bytes,
-                }
-            })
-            .collect::<Vec<LocalVariableList>>();
 
-        // Make data
-        let list_data = items_list_with_variables_allocated_bytes
-            .iter()
-            .flat_map(|(list, _)| {
-                let list_item_count = list.len();
-                let total_length_in_bytes =
-                    list_item_count * LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES;
-
-                let mut buf: Vec<u8> = Vec::with_capacity(total_length_in_bytes);
-                let dst = buf.as_mut_ptr();
-                let src = list.as_ptr() as *const u8;
-
-                unsafe {
-                    std::ptr::copy(src, dst, total_length_in_bytes);
-                    buf.set_len(total_length_in_bytes);
-                }
+        for list_entry in entries {
+            // The offset in the list
+            let mut variable_offset_next: u32 = 0;
+
+            let list_offset = list_offset_next;
+            let list_item_count = list_entry.local_variable_types.len() as u32;
+
+            for operand_data_type in &list_entry.local_variable_types {
+                let item = match operand_data_type {
+                    OperandDataType::I32 => {
+                        LocalVariableItem::new(variable_offset_next, 4, OperandDataType::I32)
+                    }
+                    OperandDataType::I64 => {
+                        LocalVariableItem::new(variable_offset_next, 8, OperandDataType::I64)
+                    }
+                    OperandDataType::F32 => {
+                        LocalVariableItem::new(variable_offset_next, 4, OperandDataType::F32)
+                    }
+                    OperandDataType::F64 => {
+                        LocalVariableItem::new(variable_offset_next, 8, OperandDataType::F64)
+                    }
+                };
+
+                // Pad the length of variable/data to the multiple of 8
+                let padding = {
+                    let remainder =
+                        item.variable_actual_size_in_bytes % OPERAND_SIZE_IN_BYTES as u32; // Remainder
+                    if remainder != 0 {
+                        OPERAND_SIZE_IN_BYTES as u32 - remainder
+                    } else {
+                        0
+                    }
+                };
+
+                let variables_allocated_bytes = item.variable_actual_size_in_bytes + padding;
+                variable_offset_next += variables_allocated_bytes;
+
+                // Make data
+                let src = &item as *const LocalVariableItem as *const u8;
+                let item_bytes = std::ptr::slice_from_raw_parts(
+                    src,
+                    LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES,
+                );
+                list_data.extend_from_slice(unsafe { &*item_bytes });
+            }
 
-                buf
-            })
-            .collect::<Vec<u8>>();
+            list_offset_next +=
+                list_item_count * LOCAL_VARIABLE_ITEM_LENGTH_IN_RECORD_IN_BYTES as u32;
 
-        (lists, list_data)
+            // Now `variable_offset_next` is the `variables_allocated_bytes * N`
+            lists.push(LocalVariableList {
+                list_offset,
+                list_item_count,
+                allocated_bytes: variable_offset_next,
+            });
+        }
     }
 }
 
@@ -0,0 +1,223 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Patch Slot Section" reserves the fixed-size placeholder regions (i.e., the
+// "slots") which are filled after the image is written, e.g., the signature,
+// the fingerprint and the timestamp, see `patch_slot`.
+//
+// "Patch Slot Section" binary layout:
+//
+//              |--------------------------------------------------------|
+//              | item count (u32) | extra header length (u32)           |
+//              |--------------------------------------------------------|
+//  item 0 -->  | slot offset 0 (u32) | slot length 0 (u32)              | <-- table
+//              | name offset 0 (u32) | name length 0 (u32)              |
+//  item 1 -->  | slot offset 1       | slot length 1                    |
+//              | name offset 1       | name length 1                    |
+//              | ...                                                    |
+//              |--------------------------------------------------------|
+// offset 0 --> | slot 0 (zero-filled when it is reserved)               | <-- data
+// offset 1 --> | slot 1                                                 |
+//              | ...                                                    |
+//              | name 0 (UTF-8)                                         |
+//              | name 1                                                 |
+//              | ...                                                    |
+//              |--------------------------------------------------------|
+//
+// The slots are 4-byte aligned within the data area.
+
+use crate::{
+    datatableaccess::{
+        get_data_area_bytes, get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
+    },
+    entry::PatchSlotEntry,
+    module_image::{ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct PatchSlotSection<'a> {
+    pub items: &'a [PatchSlotItem],
+    pub items_data: &'a [u8], // the slots followed by the UTF-8 encoded names
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct PatchSlotItem {
+    pub slot_offset: u32, // Offset of the slot in the data area
+    pub slot_length: u32, // Length (in bytes) of the slot
+    pub name_offset: u32, // Offset of the name string in the data area
+    pub name_length: u32, // Length (in bytes) of the name string
+}
+
+impl PatchSlotItem {
+    pub fn new(slot_offset: u32, slot_length: u32, name_offset: u32, name_length: u32) -> Self {
+        Self {
+            slot_offset,
+            slot_length,
+            name_offset,
+            name_length,
+        }
+    }
+}
+
+impl<'a> SectionEntry<'a> for PatchSlotSection<'a> {
+    fn read(section_data: &'a [u8]) -> Self {
+        let (items, items_data) =
+            read_section_with_table_and_data_area::<PatchSlotItem>(section_data);
+        PatchSlotSection { items, items_data }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_table_and_data_area(self.items, self.items_data, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::PatchSlot
+    }
+}
+
+impl<'a> PatchSlotSection<'a> {
+    /// Checks that the slots and the names of all items are located in the data area,
+    /// and the names are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_bytes(self.items_data, item.slot_offset, item.slot_length).is_some()
+                && get_data_area_text(self.items_data, item.name_offset, item.name_length).is_some()
+        })
+    }
+
+    pub fn get_item_name(&'a self, idx: usize) -> &'a str {
+        let item = &self.items[idx];
+        let name_data = &self.items_data
+            [item.name_offset as usize..(item.name_offset + item.name_length) as usize];
+        std::str::from_utf8(name_data).unwrap()
+    }
+
+    pub fn get_item_slot(&'a self, idx: usize) -> &'a [u8] {
+        let item = &self.items[idx];
+        &self.items_data[item.slot_offset as usize..(item.slot_offset + item.slot_length) as usize]
+    }
+
+    /// Returns the index of the slot with the specified name.
+    pub fn get_item_index(&'a self, expected_name: &str) -> Option<usize> {
+        (0..self.items.len()).find(|idx| self.get_item_name(*idx) == expected_name)
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<PatchSlotEntry> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                PatchSlotEntry::new(
+                    self.get_item_name(idx).to_owned(),
+                    item.slot_length as usize,
+                )
+            })
+            .collect()
+    }
+
+    /// Converts the entries into the items and the data area,
+    /// the slots are zero-filled.
+    pub fn convert_from_entries(entries: &[PatchSlotEntry]) -> (Vec<PatchSlotItem>, Vec<u8>) {
+        let mut items_data: Vec<u8> = vec![];
+
+        let slot_offsets = entries
+            .iter()
+            .map(|entry| {
+                let slot_offset = items_data.len() as u32;
+                items_data.resize(slot_offset as usize + entry.length.next_multiple_of(4), 0);
+                slot_offset
+            })
+            .collect::<Vec<u32>>();
+
+        let items = entries
+            .iter()
+            .zip(slot_offsets)
+            .map(|(entry, slot_offset)| {
+                let name_offset = items_data.len() as u32;
+                items_data.extend_from_slice(entry.name.as_bytes());
+
+                PatchSlotItem::new(
+                    slot_offset,
+                    entry.length as u32,
+                    name_offset,
+                    entry.name.len() as u32,
+                )
+            })
+            .collect::<Vec<PatchSlotItem>>();
+
+        (items, items_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::patch_slot_section::{PatchSlotItem, PatchSlotSection},
+        entry::PatchSlotEntry,
+        module_image::SectionEntry,
+    };
+
+    #[test]
+    fn test_write_section() {
+        let entries = vec![
+            PatchSlotEntry::new("hash".to_owned(), 6),
+            PatchSlotEntry::new("time".to_owned(), 4),
+        ];
+
+        let (items, items_data) = PatchSlotSection::convert_from_entries(&entries);
+        let section = PatchSlotSection {
+            items: &items,
+            items_data: &items_data,
+        };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        let mut expect_data = vec![
+            2u8, 0, 0, 0, // item count
+            0, 0, 0, 0, // extra section header len (i32)
+            //
+            0, 0, 0, 0, // slot offset (item 0)
+            6, 0, 0, 0, // slot length
+            12, 0, 0, 0, // name offset
+            4, 0, 0, 0, // name length
+            //
+            8, 0, 0, 0, // slot offset (item 1)
+            4, 0, 0, 0, // slot length
+            16, 0, 0, 0, // name offset
+            4, 0, 0, 0, // name length
+        ];
+
+        expect_data.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // slot 0
+        expect_data.extend_from_slice(&[0, 0]); // padding for 4-byte align
+        expect_data.extend_from_slice(&[0, 0, 0, 0]); // slot 1
+        expect_data.extend_from_slice(b"hash");
+        expect_data.extend_from_slice(b"time");
+
+        assert_eq!(section_data, expect_data);
+
+        let section_restore = PatchSlotSection::read(&section_data);
+        assert_eq!(
+            section_restore.items,
+            &[
+                PatchSlotItem::new(0, 6, 12, 4),
+                PatchSlotItem::new(8, 4, 16, 4),
+            ]
+        );
+        assert_eq!(section_restore.get_item_index("time"), Some(1));
+        assert_eq!(section_restore.get_item_index("signature"), None);
+        assert_eq!(section_restore.get_item_slot(0), &[0, 0, 0, 0, 0, 0]);
+        assert_eq!(section_restore.convert_to_entries(), entries);
+    }
+}
@@ -4,10 +4,24 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
+use anc_isa::RUNTIME_EDITION;
+
 use crate::module_image::{ModuleSectionId, SectionEntry};
 
 pub const MODULE_NAME_BUFFER_LENGTH: usize = 256;
 
+// The compatibility of the edition of a module with the runtime.
+//
+// The edition is a zero-padded ASCII string (e.g., "2025"), editions
+// are numbered by year, an older edition is still supported by the
+// runtime, while a newer (or an unrecognized) edition is not.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EditionCompatibility {
+    Same,
+    OlderCompatible,
+    Incompatible,
+}
+
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct PropertySection {
@@ -60,10 +74,55 @@ impl PropertySection {
         }
     }
 
+    /// Checks that the module name is located in the name buffer
+    /// and is a valid UTF-8 string.
+    pub fn is_well_formed(&self) -> bool {
+        self.module_name_buffer
+            .get(..(self.module_name_length as usize))
+            .is_some_and(|data| std::str::from_utf8(data).is_ok())
+    }
+
     pub fn get_module_name(&self) -> &str {
         // Extract the module name as a UTF-8 string.
         std::str::from_utf8(&self.module_name_buffer[..(self.module_name_length as usize)]).unwrap()
     }
+
+    /// Returns the edition without the trailing zero padding,
+    /// or `None` if it is not a valid UTF-8 string.
+    pub fn get_edition(&self) -> Option<&str> {
+        edition_to_str(&self.edition)
+    }
+
+    /// Compares the edition of the module with the edition of the current runtime.
+    pub fn check_runtime_edition(&self) -> EditionCompatibility {
+        self.check_edition(RUNTIME_EDITION)
+    }
+
+    /// Compares the edition of the module with the specified runtime edition.
+    pub fn check_edition(&self, runtime_edition: &[u8; 8]) -> EditionCompatibility {
+        if &self.edition == runtime_edition {
+            return EditionCompatibility::Same;
+        }
+
+        let parse_edition = |edition: &[u8; 8]| {
+            edition_to_str(edition).and_then(|edition| edition.parse::<u32>().ok())
+        };
+
+        match (parse_edition(&self.edition), parse_edition(runtime_edition)) {
+            (Some(module_edition), Some(runtime_edition)) if module_edition < runtime_edition => {
+                EditionCompatibility::OlderCompatible
+            }
+            _ => EditionCompatibility::Incompatible,
+        }
+    }
+}
+
+fn edition_to_str(edition: &[u8; 8]) -> Option<&str> {
+    let length = edition
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |pos| pos + 1);
+    std::str::from_utf8(&edition[..length]).ok()
 }
 
 impl<'a> SectionEntry<'a> for PropertySection {
@@ -86,6 +145,10 @@ impl<'a> SectionEntry<'a> for PropertySection {
         writer.write_all(&section_data)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        std::mem::size_of::<PropertySection>()
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::Property
     }
@@ -94,10 +157,11 @@ impl<'a> SectionEntry<'a> for PropertySection {
 #[cfg(test)]
 mod tests {
     use anc_isa::RUNTIME_EDITION;
+    use pretty_assertions::assert_eq;
 
     use crate::module_image::SectionEntry;
 
-    use super::PropertySection;
+    use super::{EditionCompatibility, PropertySection};
 
     #[test]
     fn test_write_section() {
@@ -153,4 +217,36 @@ mod tests {
 
         assert_eq!(section.get_module_name(), "bar");
     }
+
+    #[test]
+    fn test_check_edition() {
+        let section = PropertySection::new("bar", *b"2025\0\0\0\0", 0, 0, 1);
+        assert_eq!(section.get_edition(), Some("2025"));
+
+        assert_eq!(
+            section.check_edition(b"2025\0\0\0\0"),
+            EditionCompatibility::Same
+        );
+        assert_eq!(
+            section.check_edition(b"2026\0\0\0\0"),
+            EditionCompatibility::OlderCompatible
+        );
+        assert_eq!(
+            section.check_edition(b"2024\0\0\0\0"),
+            EditionCompatibility::Incompatible
+        );
+
+        let unknown_section = PropertySection::new("bar", *b"draft\0\0\0", 0, 0, 1);
+        assert_eq!(unknown_section.get_edition(), Some("draft"));
+        assert_eq!(
+            unknown_section.check_edition(b"2025\0\0\0\0"),
+            EditionCompatibility::Incompatible
+        );
+
+        let current_section = PropertySection::new("bar", *RUNTIME_EDITION, 0, 0, 1);
+        assert_eq!(
+            current_section.check_runtime_edition(),
+            EditionCompatibility::Same
+        );
+    }
 }
@@ -22,10 +22,13 @@
 use anc_isa::MemoryDataType;
 
 use crate::{
+    bytecode_reader::{format_bytecode_as_binary_with_options, BinaryFormatOptions},
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_bytes, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ReadOnlyDataEntry,
+    entry_dump::format_memory_data_type,
     module_image::{ModuleSectionId, SectionEntry, DATA_ITEM_ALIGN_BYTES},
 };
 
@@ -106,9 +109,37 @@ impl<'a> SectionEntry<'a> for ReadOnlyDataSection<'a> {
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         write_section_with_table_and_data_area(self.items, self.datas_data, writer)
     }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.datas_data)
+    }
 }
 
 impl ReadOnlyDataSection<'_> {
+    /// Checks that the data of all items is located in the data area.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_bytes(self.datas_data, item.data_offset, item.data_length).is_some()
+        })
+    }
+
+    /// Returns the annotated hexdump of the specified data item,
+    /// or `None` if the index is out of range.
+    ///
+    /// See `format_data_item` for the format.
+    pub fn dump_item(&self, idx: usize) -> Option<String> {
+        let item = self.items.get(idx)?;
+        let data = &self.datas_data
+            [item.data_offset as usize..(item.data_offset + item.data_length) as usize];
+        Some(format_data_item(
+            idx,
+            item.data_offset,
+            item.memory_data_type,
+            item.data_align,
+            data,
+        ))
+    }
+
     pub fn convert_to_entries(&self) -> Vec<ReadOnlyDataEntry> {
         let items = self.items;
         let datas_data = self.datas_data;
@@ -129,58 +160,120 @@ impl ReadOnlyDataSection<'_> {
     }
 
     pub fn convert_from_entries(entries: &[ReadOnlyDataEntry]) -> (Vec<DataItem>, Vec<u8>) {
+        let mut items: Vec<DataItem> = vec![];
+        let mut datas_data: Vec<u8> = vec![];
+        Self::convert_from_entries_into(entries, &mut items, &mut datas_data);
+        (items, datas_data)
+    }
+
+    /// Same as `convert_from_entries`, but writes the result into the given buffers.
+    ///
+    /// The buffers are cleared first, their capacity is kept, so the caller
+    /// can reuse them across many modules without reallocating.
+    pub fn convert_from_entries_into(
+        entries: &[ReadOnlyDataEntry],
+        items: &mut Vec<DataItem>,
+        datas_data: &mut Vec<u8>,
+    ) {
+        items.clear();
+        datas_data.clear();
+        items.reserve(entries.len());
+
         let mut next_offset: u32 = 0;
 
-        // Calculate the position `(padding, data_offset, data_length)` for each entry
-        let positions = entries
-            .iter()
-            .map(|entry| {
-                // The alignment of the record should be a multiple of `DATA_ITEM_ALIGN_BYTES` (8 bytes)
-                let entry_align = entry.align as u32;
-                let head_align = DATA_ITEM_ALIGN_BYTES as u32;
-                let actual_align = (entry_align / head_align
-                    + if entry_align % head_align != 0 { 1 } else { 0 })
-                    * head_align;
-
-                let remainder = next_offset % actual_align; // Remainder
-                let head_padding = if remainder != 0 {
-                    actual_align - remainder
-                } else {
-                    0
-                };
-
-                let data_offset = next_offset + head_padding; // Data offset after aligning
-                let data_length = entry.length;
-                next_offset = data_offset + data_length;
-                (head_padding, data_offset, data_length)
-            })
-            .collect::<Vec<(u32, u32, u32)>>();
+        for entry in entries {
+            // The alignment of the record should be a multiple of `DATA_ITEM_ALIGN_BYTES` (8 bytes)
+            let entry_align = entry.align as u32;
+            let head_align = DATA_ITEM_ALIGN_BYTES as u32;
+            let actual_align = (entry_align / head_align
+                + if entry_align % head_align != 0 { 1 } else { 0 })
+                * head_align;
+
+            let remainder = next_offset % actual_align; // Remainder
+            let head_padding = if remainder != 0 {
+                actual_align - remainder
+            } else {
+                0
+            };
+
+            let data_offset = next_offset + head_padding; // Data offset after aligning
+            let data_length = entry.length;
+            next_offset = data_offset + data_length;
+
+            items.push(DataItem::new(
+                data_offset,
+                data_length,
+                entry.memory_data_type,
+                entry.align,
+            ));
+
+            datas_data.resize(datas_data.len() + head_padding as usize, 0);
+            datas_data.extend_from_slice(&entry.data);
+        }
+    }
+}
 
-        let items = entries
-            .iter()
-            .zip(&positions)
-            .map(|(entry, (_padding, data_offset, data_length))| {
-                DataItem::new(
-                    *data_offset,
-                    *data_length,
-                    entry.memory_data_type,
-                    entry.align,
-                )
-            })
-            .collect::<Vec<DataItem>>();
+/// Formats a data item as the annotated hexdump, the addresses are
+/// the offsets in the "data area", e.g.:
+///
+/// ```text
+/// #1: i64, length 8, align 8
+/// 0x0008  0d 00 00 00  00 00 00 00
+/// value: 13
+/// ```
+///
+/// The "value" line is present only for the numeric data types (i32, i64, f32 and f64)
+/// whose length matches the type.
+pub fn format_data_item(
+    idx: usize,
+    data_offset: u32,
+    memory_data_type: MemoryDataType,
+    data_align: u16,
+    data: &[u8],
+) -> String {
+    let mut lines = vec![format!(
+        "#{}: {}, length {}, align {}",
+        idx,
+        format_memory_data_type(memory_data_type),
+        data.len(),
+        data_align
+    )];
+
+    if !data.is_empty() {
+        lines.push(format_bytecode_as_binary_with_options(
+            data,
+            &BinaryFormatOptions {
+                base_address: data_offset as usize,
+                ..BinaryFormatOptions::default()
+            },
+        ));
+    }
 
-        let datas_data = entries
-            .iter()
-            .zip(&positions)
-            .flat_map(|(entry, (padding, _data_offset, _data_length))| {
-                let mut data = vec![0u8; *padding as usize];
-                data.extend(entry.data.iter());
-                data
-            })
-            .collect::<Vec<u8>>();
+    let value = match memory_data_type {
+        MemoryDataType::I32 => data
+            .try_into()
+            .ok()
+            .map(|bytes| i32::from_le_bytes(bytes).to_string()),
+        MemoryDataType::I64 => data
+            .try_into()
+            .ok()
+            .map(|bytes| i64::from_le_bytes(bytes).to_string()),
+        MemoryDataType::F32 => data
+            .try_into()
+            .ok()
+            .map(|bytes| f32::from_le_bytes(bytes).to_string()),
+        MemoryDataType::F64 => data
+            .try_into()
+            .ok()
+            .map(|bytes| f64::from_le_bytes(bytes).to_string()),
+        MemoryDataType::Bytes => None,
+    };
 
-        (items, datas_data)
+    if let Some(value) = value {
+        lines.push(format!("value: {}", value));
     }
+
+    lines.join("\n")
 }
 
 #[cfg(test)]
@@ -189,7 +282,7 @@ mod tests {
 
     use crate::{
         common_sections::read_only_data_section::{DataItem, ReadOnlyDataSection},
-        entry::ReadOnlyDataEntry,
+        entry::{ReadOnlyDataEntry, StringEncoding},
         module_image::SectionEntry,
     };
 
@@ -455,4 +548,102 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries_restore, entries);
     }
+
+    #[test]
+    fn test_convert_into_reused_buffers() {
+        let entries0 = vec![
+            ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+            ReadOnlyDataEntry::from_i64(13),
+        ];
+        let entries1 = vec![ReadOnlyDataEntry::from_i32(11)];
+
+        let mut items: Vec<DataItem> = vec![];
+        let mut datas: Vec<u8> = vec![];
+
+        ReadOnlyDataSection::convert_from_entries_into(&entries0, &mut items, &mut datas);
+        assert_eq!(
+            items,
+            vec![
+                DataItem::new(0, 5, MemoryDataType::Bytes, 1),
+                DataItem::new(8, 8, MemoryDataType::I64, 8),
+            ]
+        );
+        assert_eq!(datas.len(), 16);
+
+        // the previous content should be discarded
+        ReadOnlyDataSection::convert_from_entries_into(&entries1, &mut items, &mut datas);
+        assert_eq!(items, vec![DataItem::new(0, 4, MemoryDataType::I32, 4)]);
+        assert_eq!(datas, vec![11, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_convert_strings() {
+        let entries = vec![
+            ReadOnlyDataEntry::from_str("abc", StringEncoding::Utf8),
+            ReadOnlyDataEntry::from_str("abc", StringEncoding::Utf8NulTerminated),
+            ReadOnlyDataEntry::from_str("a中", StringEncoding::Utf16Le),
+        ];
+
+        assert_eq!(entries[0].data, b"abc".to_vec());
+        assert_eq!(entries[1].data, b"abc\0".to_vec());
+        assert_eq!(entries[2].data, vec![0x61, 0x00, 0x2d, 0x4e]);
+
+        let (items, datas) = ReadOnlyDataSection::convert_from_entries(&entries);
+
+        assert_eq!(
+            items,
+            vec![
+                DataItem::new(0, 3, MemoryDataType::Bytes, 1),
+                DataItem::new(8, 4, MemoryDataType::Bytes, 1),
+                DataItem::new(16, 4, MemoryDataType::Bytes, 2),
+            ]
+        );
+
+        let section = ReadOnlyDataSection {
+            items: &items,
+            datas_data: &datas,
+        };
+        assert_eq!(section.convert_to_entries(), entries);
+    }
+
+    #[test]
+    fn test_dump_item() {
+        let entries = vec![
+            ReadOnlyDataEntry::from_i32(11),
+            ReadOnlyDataEntry::from_i64(13),
+            ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+            ReadOnlyDataEntry::from_f32(1.5),
+        ];
+
+        let (items, datas) = ReadOnlyDataSection::convert_from_entries(&entries);
+        let section = ReadOnlyDataSection {
+            items: &items,
+            datas_data: &datas,
+        };
+
+        assert_eq!(
+            section.dump_item(1).unwrap(),
+            "\
+#1: i64, length 8, align 8
+0x0008  0d 00 00 00  00 00 00 00
+value: 13"
+        );
+
+        assert_eq!(
+            section.dump_item(2).unwrap(),
+            "\
+#2: bytes, length 5, align 1
+0x0010  68 65 6c 6c  6f"
+        );
+
+        assert_eq!(
+            section.dump_item(3).unwrap(),
+            "\
+#3: f32, length 4, align 4
+0x0018  00 00 c0 3f
+value: 1.5"
+        );
+
+        assert!(section.dump_item(4).is_none());
+    }
 }
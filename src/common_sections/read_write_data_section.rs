@@ -22,8 +22,10 @@
 use anc_isa::MemoryDataType;
 
 use crate::{
+    common_sections::read_only_data_section::format_data_item,
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_bytes, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ReadWriteDataEntry,
     module_image::{ModuleSectionId, SectionEntry, DATA_ITEM_ALIGN_BYTES},
@@ -107,9 +109,37 @@ impl<'a> SectionEntry<'a> for ReadWriteDataSection<'a> {
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         write_section_with_table_and_data_area(self.items, self.datas_data, writer)
     }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.datas_data)
+    }
 }
 
 impl ReadWriteDataSection<'_> {
+    /// Checks that the data of all items is located in the data area.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_bytes(self.datas_data, item.data_offset, item.data_length).is_some()
+        })
+    }
+
+    /// Returns the annotated hexdump of the specified data item,
+    /// or `None` if the index is out of range.
+    ///
+    /// See `read_only_data_section::format_data_item` for the format.
+    pub fn dump_item(&self, idx: usize) -> Option<String> {
+        let item = self.items.get(idx)?;
+        let data = &self.datas_data
+            [item.data_offset as usize..(item.data_offset + item.data_length) as usize];
+        Some(format_data_item(
+            idx,
+            item.data_offset,
+            item.memory_data_type,
+            item.data_align,
+            data,
+        ))
+    }
+
     pub fn convert_to_entries(&self) -> Vec<ReadWriteDataEntry> {
         let items = self.items;
         let datas_data = self.datas_data;
@@ -130,57 +160,56 @@ impl ReadWriteDataSection<'_> {
     }
 
     pub fn convert_from_entries(entries: &[ReadWriteDataEntry]) -> (Vec<DataItem>, Vec<u8>) {
-        let mut next_offset: u32 = 0;
-
-        // Calculate the position `(padding, data_offset, data_length)` for each entry
-        let positions = entries
-            .iter()
-            .map(|entry| {
-                // The alignment of the record should be a multiple of `DATA_ITEM_ALIGN_BYTES` (8 bytes)
-                let entry_align = entry.align as u32;
-                let head_align = DATA_ITEM_ALIGN_BYTES as u32;
-                let actual_align = (entry_align / head_align
-                    + if entry_align % head_align != 0 { 1 } else { 0 })
-                    * head_align;
-
-                let remainder = next_offset % actual_align; // Remainder
-                let head_padding = if remainder != 0 {
-                    actual_align - remainder
-                } else {
-                    0
-                };
-
-                let data_offset = next_offset + head_padding; // Data offset after aligning
-                let data_length = entry.length;
-                next_offset = data_offset + data_length;
-                (head_padding, data_offset, data_length)
-            })
-            .collect::<Vec<(u32, u32, u32)>>();
+        let mut items: Vec<DataItem> = vec![];
+        let mut datas_data: Vec<u8> = vec![];
+        Self::convert_from_entries_into(entries, &mut items, &mut datas_data);
+        (items, datas_data)
+    }
 
-        let items = entries
-            .iter()
-            .zip(&positions)
-            .map(|(entry, (_padding, data_offset, data_length))| {
-                DataItem::new(
-                    *data_offset,
-                    *data_length,
-                    entry.memory_data_type,
-                    entry.align,
-                )
-            })
-            .collect::<Vec<DataItem>>();
+    /// Same as `convert_from_entries`, but writes the result into the given buffers.
+    ///
+    /// The buffers are cleared first, their capacity is kept, so the caller
+    /// can reuse them across many modules without reallocating.
+    pub fn convert_from_entries_into(
+        entries: &[ReadWriteDataEntry],
+        items: &mut Vec<DataItem>,
+        datas_data: &mut Vec<u8>,
+    ) {
+        items.clear();
+        datas_data.clear();
+        items.reserve(entries.len());
 
-        let datas_data = entries
-            .iter()
-            .zip(&positions)
-            .flat_map(|(entry, (padding, _data_offset, _data_length))| {
-                let mut data = vec![0u8; *padding as usize];
-                data.extend(entry.data.iter());
-                data
-            })
-            .collect::<Vec<u8>>();
+        let mut next_offset: u32 = 0;
 
-        (items, datas_data)
+        for entry in entries {
+            // The alignment of the record should be a multiple of `DATA_ITEM_ALIGN_BYTES` (8 bytes)
+            let entry_align = entry.align as u32;
+            let head_align = DATA_ITEM_ALIGN_BYTES as u32;
+            let actual_align = (entry_align / head_align
+                + if entry_align % head_align != 0 { 1 } else { 0 })
+                * head_align;
+
+            let remainder = next_offset % actual_align; // Remainder
+            let head_padding = if remainder != 0 {
+                actual_align - remainder
+            } else {
+                0
+            };
+
+            let data_offset = next_offset + head_padding; // Data offset after aligning
+            let data_length = entry.length;
+            next_offset = data_offset + data_length;
+
+            items.push(DataItem::new(
+                data_offset,
+                data_length,
+                entry.memory_data_type,
+                entry.align,
+            ));
+
+            datas_data.resize(datas_data.len() + head_padding as usize, 0);
+            datas_data.extend_from_slice(&entry.data);
+        }
     }
 }
 
@@ -457,4 +486,37 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries_restore, entries);
     }
+
+    #[test]
+    fn test_dump_item() {
+        let entries = vec![
+            ReadWriteDataEntry::from_bytes(b"foo".to_vec(), 8),
+            ReadWriteDataEntry::from_f64(-2.25),
+            ReadWriteDataEntry::from_i32(0xffff_ffff),
+        ];
+
+        let (items, datas) = ReadWriteDataSection::convert_from_entries(&entries);
+        let section = ReadWriteDataSection {
+            items: &items,
+            datas_data: &datas,
+        };
+
+        assert_eq!(
+            section.dump_item(1).unwrap(),
+            "\
+#1: f64, length 8, align 8
+0x0008  00 00 00 00  00 00 02 c0
+value: -2.25"
+        );
+
+        assert_eq!(
+            section.dump_item(2).unwrap(),
+            "\
+#2: i32, length 4, align 4
+0x0010  ff ff ff ff
+value: -1"
+        );
+
+        assert!(section.dump_item(3).is_none());
+    }
 }
@@ -41,7 +41,8 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        read_section_with_table_and_data_area, section_with_table_and_data_area_length,
+        write_section_with_table_and_data_area,
     },
     entry::{RelocateEntry, RelocateListEntry},
     module_image::{ModuleSectionId, RelocateType, SectionEntry},
@@ -112,6 +113,10 @@ impl<'a> SectionEntry<'a> for RelocateSection<'a> {
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         write_section_with_table_and_data_area(self.lists, self.list_data, writer)
     }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.lists, self.list_data)
+    }
 }
 
 impl<'a> RelocateSection<'a> {
@@ -218,6 +223,19 @@ impl<'a> RelocateSection<'a> {
 
         (lists, list_data)
     }
+
+    /// Checks that all relocate lists are located in the data area,
+    /// and are aligned for reading the items by casting the pointer.
+    pub fn is_well_formed(&self) -> bool {
+        self.lists.iter().all(|list| {
+            let start = list.list_offset as usize;
+            start % align_of::<RelocateItem>() == 0
+                && (list.list_item_count as usize)
+                    .checked_mul(size_of::<RelocateItem>())
+                    .and_then(|length| start.checked_add(length))
+                    .is_some_and(|end| end <= self.list_data.len())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -589,4 +607,42 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries_restore, entries);
     }
+
+    #[test]
+    fn test_is_well_formed() {
+        let entries = vec![RelocateListEntry::new(vec![
+            RelocateEntry::new(11, RelocateType::TypeIndex),
+            RelocateEntry::new(13, RelocateType::FunctionPublicIndex),
+        ])];
+
+        let (lists, list_data) = RelocateSection::convert_from_entries(&entries);
+
+        let section = RelocateSection {
+            lists: &lists,
+            list_data: &list_data,
+        };
+        assert!(section.is_well_formed());
+
+        // the list exceeds the data area
+        let lists_out_of_bounds = vec![RelocateList {
+            list_offset: 8,
+            list_item_count: 2,
+        }];
+        let section = RelocateSection {
+            lists: &lists_out_of_bounds,
+            list_data: &list_data,
+        };
+        assert!(!section.is_well_formed());
+
+        // the list is not aligned
+        let lists_misaligned = vec![RelocateList {
+            list_offset: 2,
+            list_item_count: 1,
+        }];
+        let section = RelocateSection {
+            lists: &lists_misaligned,
+            list_data: &list_data,
+        };
+        assert!(!section.is_well_formed());
+    }
 }
@@ -28,7 +28,8 @@ use anc_isa::OperandDataType;
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        read_section_with_table_and_data_area, section_with_table_and_data_area_length,
+        write_section_with_table_and_data_area,
     },
     entry::TypeEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -84,6 +85,10 @@ impl<'a> SectionEntry<'a> for TypeSection<'a> {
         write_section_with_table_and_data_area(self.items, self.types_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.types_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::Type
     }
@@ -157,49 +162,86 @@ impl<'a> TypeSection<'a> {
             .collect()
     }
 
+    /// Checks that the parameter and result type lists of all items are located
+    /// in the data area and contain only valid operand data types.
+    pub fn is_well_formed(&self) -> bool {
+        let is_valid_list = |offset: u32, count: u16| {
+            let start = offset as usize;
+            let end = start + count as usize;
+            self.types_data
+                .get(start..end)
+                .is_some_and(|data| data.iter().all(|byte| is_valid_operand_data_type(*byte)))
+        };
+
+        self.items.iter().all(|item| {
+            is_valid_list(item.params_offset, item.params_count)
+                && is_valid_list(item.results_offset, item.results_count)
+        })
+    }
+
     // Converts a vector of `TypeEntry` objects back into the binary layout of the section.
     pub fn convert_from_entries(entries: &[TypeEntry]) -> (Vec<TypeItem>, Vec<u8>) {
-        let mut next_offset: u32 = 0;
+        let mut items: Vec<TypeItem> = vec![];
+        let mut types_data: Vec<u8> = vec![];
+        Self::convert_from_entries_into(entries, &mut items, &mut types_data);
+        (items, types_data)
+    }
 
-        let items = entries
-            .iter()
-            .map(|entry| {
-                let params_count = entry.params.len() as u16;
-                let params_offset = next_offset;
-                let results_count = entry.results.len() as u16;
-                let results_offset = params_offset + params_count as u32;
-
-                // the size of 'data type' is 1 byte, so the 'result_count' is
-                // also the length (in bytes) of the list.
-                next_offset = results_offset + results_count as u32; // for next offset
-
-                TypeItem {
-                    params_count,
-                    results_count,
-                    params_offset,
-                    results_offset,
-                }
-            })
-            .collect::<Vec<TypeItem>>();
+    // Same as `convert_from_entries`, but writes the result into the given buffers.
+    //
+    // The buffers are cleared first and their capacity is kept, so that build tools
+    // can reuse them across many modules.
+    pub fn convert_from_entries_into(
+        entries: &[TypeEntry],
+        items: &mut Vec<TypeItem>,
+        types_data: &mut Vec<u8>,
+    ) {
+        items.clear();
+        types_data.clear();
+        items.reserve(entries.len());
 
-        let types_data = entries
-            .iter()
-            .flat_map(|entry| {
-                let mut bytes: Vec<u8> = vec![];
-                let params_bytes =
-                    slice_from_raw_parts(entry.params.as_ptr() as *const u8, entry.params.len());
-                let results_bytes =
-                    slice_from_raw_parts(entry.results.as_ptr() as *const u8, entry.results.len());
-                bytes.extend_from_slice(unsafe { &*params_bytes });
-                bytes.extend_from_slice(unsafe { &*results_bytes });
-                bytes
-            })
-            .collect::<Vec<u8>>();
+        let mut next_offset: u32 = 0;
 
-        (items, types_data)
+        for entry in entries {
+            let params_count = entry.params.len() as u16;
+            let params_offset = next_offset;
+            let results_count = entry.results.len() as u16;
+            let results_offset = params_offset + params_count as u32;
+
+            // the size of 'data type' is 1 byte, so the 'result_count' is
+            // also the length (in bytes) of the list.
+            next_offset = results_offset + results_count as u32; // for next offset
+
+            items.push(TypeItem {
+                params_count,
+                results_count,
+                params_offset,
+                results_offset,
+            });
+
+            let params_bytes =
+                slice_from_raw_parts(entry.params.as_ptr() as *const u8, entry.params.len());
+            let results_bytes =
+                slice_from_raw_parts(entry.results.as_ptr() as *const u8, entry.results.len());
+            types_data.extend_from_slice(unsafe { &*params_bytes });
+            types_data.extend_from_slice(unsafe { &*results_bytes });
+        }
     }
 }
 
+// Checks whether the byte is the value of an `OperandDataType`, the type lists
+// are read by casting the bytes, so the value must be checked first.
+pub(crate) fn is_valid_operand_data_type(value: u8) -> bool {
+    [
+        OperandDataType::I32,
+        OperandDataType::I64,
+        OperandDataType::F32,
+        OperandDataType::F64,
+    ]
+    .iter()
+    .any(|operand_data_type| *operand_data_type as u8 == value)
+}
+
 #[cfg(test)]
 mod tests {
     use anc_isa::OperandDataType;
@@ -10,20 +10,30 @@
 //              | item count (u32) | extra header length (u32)                        |
 //              |---------------------------------------------------------------------|
 //  item 0 -->  | data offset 0 (u32) | data length 0 (u32) | memory data type 0 (u8) |
-//              | pad (1 byte) | data align 0 (u16)                                   | <-- table
+//              | flags 0 (u8) | data align 0 (u16)                                   | <-- table
 //  item 1 -->  | data offset 1       | data length 1       | memory data type 1      |
-//              | pad          | data align 1                                         |
+//              | flags 1      | data align 1                                         |
 //              | ...                                                                 |
 //              |---------------------------------------------------------------------|
+//
+// The "flags" byte was a padding byte (always `0`) in the earlier images,
+// so the `0` value of each flag keeps the original behavior.
 
 use anc_isa::MemoryDataType;
 
 use crate::{
-    datatableaccess::{read_section_with_one_table, write_section_with_one_table},
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
     entry::UninitDataEntry,
     module_image::{ModuleSectionId, SectionEntry, DATA_ITEM_ALIGN_BYTES},
 };
 
+// The flag indicates that the content of the data item may be left undefined
+// when the module is loaded, i.e., the runtime is not required to zero it.
+// Without this flag, the data item must be zeroed.
+pub const UNINIT_DATA_FLAG_MAY_BE_UNDEFINED: u8 = 0b0000_0001;
+
 #[derive(Debug, PartialEq, Default)]
 pub struct UninitDataSection<'a> {
     pub items: &'a [DataItem], // Array of data items in the section
@@ -38,7 +48,8 @@ pub struct DataItem {
     // The data type field is not required at runtime but is useful for debugging.
     pub memory_data_type: MemoryDataType,
 
-    _padding0: u8, // Padding for alignment
+    // The bit flags of the data item, see `UNINIT_DATA_FLAG_*`.
+    pub flags: u8,
 
     // Alignment of the data item itself.
     //
@@ -75,10 +86,20 @@ impl DataItem {
             data_offset,
             data_length,
             memory_data_type: data_type,
-            _padding0: 0,
+            flags: 0,
             data_align,
         }
     }
+
+    pub fn with_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns `true` if the runtime may skip zeroing the data item.
+    pub fn may_be_undefined(&self) -> bool {
+        self.flags & UNINIT_DATA_FLAG_MAY_BE_UNDEFINED != 0
+    }
 }
 
 impl<'a> SectionEntry<'a> for UninitDataSection<'a> {
@@ -97,6 +118,10 @@ impl<'a> SectionEntry<'a> for UninitDataSection<'a> {
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         write_section_with_one_table(self.items, writer)
     }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
 }
 
 impl UninitDataSection<'_> {
@@ -107,6 +132,7 @@ impl UninitDataSection<'_> {
                 memory_data_type: item.memory_data_type,
                 length: item.data_length,
                 align: item.data_align,
+                may_be_undefined: item.may_be_undefined(),
             })
             .collect()
     }
@@ -143,12 +169,19 @@ impl UninitDataSection<'_> {
             .iter()
             .zip(&positions)
             .map(|(entry, (_padding, data_offset, data_length))| {
+                let flags = if entry.may_be_undefined {
+                    UNINIT_DATA_FLAG_MAY_BE_UNDEFINED
+                } else {
+                    0
+                };
+
                 DataItem::new(
                     *data_offset,
                     *data_length,
                     entry.memory_data_type,
                     entry.align,
                 )
+                .with_flags(flags)
             })
             .collect::<Vec<DataItem>>();
 
@@ -161,7 +194,9 @@ mod tests {
     use anc_isa::MemoryDataType;
 
     use crate::{
-        common_sections::uninit_data_section::{DataItem, UninitDataSection},
+        common_sections::uninit_data_section::{
+            DataItem, UninitDataSection, UNINIT_DATA_FLAG_MAY_BE_UNDEFINED,
+        },
         entry::UninitDataEntry,
         module_image::SectionEntry,
     };
@@ -349,4 +384,34 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries_restore, entries);
     }
+
+    #[test]
+    fn test_convert_undefined_flag() {
+        let entries = vec![
+            UninitDataEntry::from_i32(),
+            UninitDataEntry::from_bytes(4096, 8).with_undefined(),
+        ];
+
+        let items = UninitDataSection::convert_from_entries(&entries);
+        assert_eq!(
+            items,
+            vec![
+                DataItem::new(0, 4, MemoryDataType::I32, 4),
+                DataItem::new(8, 4096, MemoryDataType::Bytes, 8)
+                    .with_flags(UNINIT_DATA_FLAG_MAY_BE_UNDEFINED),
+            ]
+        );
+        assert!(!items[0].may_be_undefined());
+        assert!(items[1].may_be_undefined());
+
+        let section = UninitDataSection { items: &items };
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        // the flags byte of item 1
+        assert_eq!(section_data[8 + 12 + 9], UNINIT_DATA_FLAG_MAY_BE_UNDEFINED);
+
+        let section_restore = UninitDataSection::read(&section_data);
+        assert_eq!(section_restore.convert_to_entries(), entries);
+    }
 }
@@ -0,0 +1,173 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Checks whether the version bump of a module matches the changes of its ABI,
+// e.g., for package managers to verify a new version before publishing.
+//
+// The changes of the export surface (see `export_surface`) are classified as:
+//
+// - Major: any public function or data is removed, or its signature
+//   (or data type) is changed, the applications linked against the old
+//   version can no longer run with the new version.
+// - Minor: public functions or data are added only.
+// - None: the export surface is unchanged, any version bump is acceptable.
+//
+// Following the convention of semantic versioning, when the major version is `0`,
+// the minor version plays the role of the major version, i.e., "0.1.0" to "0.2.0"
+// is a major bump, and "0.1.0" to "0.1.1" is a minor bump.
+
+use crate::{
+    export_surface::{compare_export_surfaces, ExportSurfaceDiff},
+    module_image::ModuleImage,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum VersionBump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CompatReport {
+    pub diff: ExportSurfaceDiff,
+
+    // The minimum version bump required by the changes of the export surface.
+    pub required_bump: VersionBump,
+
+    // The version bump declared by the property sections of the two modules.
+    // It is `None` if the new version is not greater than the old version.
+    pub declared_bump: VersionBump,
+}
+
+impl CompatReport {
+    /// Returns `true` if the declared version bump covers the changes of the ABI.
+    pub fn is_version_bump_valid(&self) -> bool {
+        self.declared_bump >= self.required_bump
+    }
+}
+
+/// Compares the export surfaces and the versions of two versions of a module.
+pub fn check_compatibility(old: &ModuleImage, new: &ModuleImage) -> CompatReport {
+    let diff = compare_export_surfaces(old, new);
+
+    let required_bump = if !diff.removed.is_empty() || !diff.changed.is_empty() {
+        VersionBump::Major
+    } else if !diff.added.is_empty() {
+        VersionBump::Minor
+    } else {
+        VersionBump::None
+    };
+
+    let get_version = |image: &ModuleImage| {
+        let property_section = image.get_property_section();
+        (
+            property_section.version_major,
+            property_section.version_minor,
+            property_section.version_patch,
+        )
+    };
+
+    CompatReport {
+        diff,
+        required_bump,
+        declared_bump: get_version_bump(get_version(old), get_version(new)),
+    }
+}
+
+// The versions are `(major, minor, patch)`.
+fn get_version_bump(old: (u16, u16, u16), new: (u16, u16, u16)) -> VersionBump {
+    if new <= old {
+        VersionBump::None
+    } else if new.0 != old.0 {
+        VersionBump::Major
+    } else if new.1 != old.1 {
+        if new.0 == 0 {
+            VersionBump::Major
+        } else {
+            VersionBump::Minor
+        }
+    } else if new.0 == 0 {
+        VersionBump::Minor
+    } else {
+        VersionBump::Patch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{EffectiveVersion, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        compatibility::{check_compatibility, get_version_bump, VersionBump},
+        entry::TypeEntry,
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_object_file},
+        module_image::ModuleImage,
+    };
+
+    #[test]
+    fn test_get_version_bump() {
+        assert_eq!(get_version_bump((1, 2, 3), (1, 2, 3)), VersionBump::None);
+        assert_eq!(get_version_bump((1, 2, 3), (1, 2, 2)), VersionBump::None);
+        assert_eq!(get_version_bump((1, 2, 3), (1, 2, 4)), VersionBump::Patch);
+        assert_eq!(get_version_bump((1, 2, 3), (1, 3, 0)), VersionBump::Minor);
+        assert_eq!(get_version_bump((1, 2, 3), (2, 0, 0)), VersionBump::Major);
+
+        // the major version 0
+        assert_eq!(get_version_bump((0, 1, 0), (0, 1, 1)), VersionBump::Minor);
+        assert_eq!(get_version_bump((0, 1, 0), (0, 2, 0)), VersionBump::Major);
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let build_module = |stubs: &[(&str, TypeEntry)], version: EffectiveVersion| {
+            let image_binary = build_shared_module_scaffold("foo", stubs).unwrap();
+            let mut image_common_entry = read_object_file(&image_binary).unwrap();
+            image_common_entry.version = version;
+
+            let mut image_binary: Vec<u8> = vec![];
+            write_object_file(&image_common_entry, true, &mut image_binary).unwrap();
+            image_binary
+        };
+
+        let add_type = TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]);
+        let reset_type = TypeEntry::new(vec![], vec![]);
+
+        let binary_v1 = build_module(&[("add", add_type.clone())], EffectiveVersion::new(1, 0, 0));
+        let image_v1 = ModuleImage::read(&binary_v1).unwrap();
+
+        // adds a function with a minor bump
+        let binary_v2 = build_module(
+            &[("add", add_type.clone()), ("reset", reset_type.clone())],
+            EffectiveVersion::new(1, 1, 0),
+        );
+        let image_v2 = ModuleImage::read(&binary_v2).unwrap();
+
+        let report = check_compatibility(&image_v1, &image_v2);
+        assert_eq!(report.diff.added, vec!["foo::reset".to_owned()]);
+        assert_eq!(report.required_bump, VersionBump::Minor);
+        assert_eq!(report.declared_bump, VersionBump::Minor);
+        assert!(report.is_version_bump_valid());
+
+        // removes a function with a minor bump
+        let binary_v3 = build_module(&[("reset", reset_type)], EffectiveVersion::new(1, 2, 0));
+        let image_v3 = ModuleImage::read(&binary_v3).unwrap();
+
+        let report = check_compatibility(&image_v2, &image_v3);
+        assert_eq!(report.diff.removed, vec!["foo::add".to_owned()]);
+        assert_eq!(report.required_bump, VersionBump::Major);
+        assert_eq!(report.declared_bump, VersionBump::Minor);
+        assert!(!report.is_version_bump_valid());
+
+        // unchanged
+        let report = check_compatibility(&image_v1, &image_v1);
+        assert_eq!(report.required_bump, VersionBump::None);
+        assert!(report.is_version_bump_valid());
+    }
+}
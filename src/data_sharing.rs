@@ -0,0 +1,556 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Detects the identical read-only data items across modules, e.g.,
+// the same string literals or lookup tables which are compiled into
+// multiple modules of an application.
+//
+// Two read-only data items are identical when their contents, data types
+// and alignments are all the same.
+//
+// The duplicates can be merged at link time by `merge_shared_read_only_data`:
+// each group of identical items is kept (or appended) in the main module,
+// and the data index entries which point to the items are retargeted to it.
+// The original items are left in their modules, they are no longer
+// referenced by the data index, and can be stripped when the modules
+// are rewritten.
+//
+// The read-write data items which are never stored to can be migrated into
+// the read-only sections at link time by `migrate_unmodified_read_write_data`,
+// so that they are no longer cloned for each thread by the runtime.
+//
+// An item is considered modified if any instruction other than the `data_load_*`
+// and `data_load_extend_*` refers to it, i.e., `data_store_*`, `get_data` and
+// `host_addr_data*`, because the data handle and the address can be used for
+// storing indirectly (e.g., `data_store_dynamic_*` and the external functions).
+//
+// The instructions refer to the data by the data public index of their module,
+// which is resolved through the data index list, so the bytecode is unchanged,
+// only the data index entries and the data names are updated.
+
+use std::collections::{HashMap, HashSet};
+
+use anc_isa::{opcode::Opcode, DataSectionType};
+
+use crate::{
+    bytecode_reader::InstructionIterator,
+    entry::{DataIndexListEntry, ImageCommonEntry, ReadOnlyDataEntry},
+    module_image::ModuleImage,
+    opcode_info::{get_opcode_info, OperandKind},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SharedReadOnlyData {
+    pub entry: ReadOnlyDataEntry,
+
+    // The locations of the identical items, i.e., `(module_index, data_internal_index)`,
+    // in the order of the module indices and the internal indices.
+    pub occurrences: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DataSharingReport {
+    // The groups of the identical items, only the groups with
+    // more than one occurrence are listed.
+    pub shared_data: Vec<SharedReadOnlyData>,
+
+    // The total size of the duplicates, i.e., the bytes which are saved
+    // if each group is kept only once.
+    pub saved_bytes: usize,
+}
+
+/// Finds the identical read-only data items of the modules.
+///
+/// The first module is the main module of the application.
+pub fn analyze_read_only_data_sharing(module_images: &[ModuleImage]) -> DataSharingReport {
+    let mut groups: Vec<SharedReadOnlyData> = vec![];
+
+    // The groups indexed by the data content.
+    let mut group_indices_by_data: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+
+    for (module_index, module_image) in module_images.iter().enumerate() {
+        let Some(read_only_data_section) = module_image.get_optional_read_only_data_section()
+        else {
+            continue;
+        };
+
+        for (data_internal_index, entry) in read_only_data_section
+            .convert_to_entries()
+            .into_iter()
+            .enumerate()
+        {
+            let group_indices = group_indices_by_data.entry(entry.data.clone()).or_default();
+            match group_indices
+                .iter()
+                .find(|group_index| groups[**group_index].entry == entry)
+            {
+                Some(group_index) => groups[*group_index]
+                    .occurrences
+                    .push((module_index, data_internal_index)),
+                None => {
+                    group_indices.push(groups.len());
+                    groups.push(SharedReadOnlyData {
+                        entry,
+                        occurrences: vec![(module_index, data_internal_index)],
+                    });
+                }
+            }
+        }
+    }
+
+    let shared_data = groups
+        .into_iter()
+        .filter(|group| group.occurrences.len() > 1)
+        .collect::<Vec<_>>();
+
+    let saved_bytes = shared_data
+        .iter()
+        .map(|group| group.entry.data.len() * (group.occurrences.len() - 1))
+        .sum();
+
+    DataSharingReport {
+        shared_data,
+        saved_bytes,
+    }
+}
+
+/// Merges the identical read-only data items into the main module (i.e., the module `0`).
+///
+/// For each group, the item of the main module is kept if there is one,
+/// otherwise the item is appended to `main_read_only_data_entries`.
+/// Then the data index entries which point to the items of the group
+/// are retargeted to the item of the main module.
+pub fn merge_shared_read_only_data(
+    report: &DataSharingReport,
+    main_read_only_data_entries: &mut Vec<ReadOnlyDataEntry>,
+    data_index_list_entries: &mut [DataIndexListEntry],
+) {
+    for group in &report.shared_data {
+        let main_data_internal_index = match group
+            .occurrences
+            .iter()
+            .find(|(module_index, _)| *module_index == 0)
+        {
+            Some((_, data_internal_index)) => *data_internal_index,
+            None => {
+                main_read_only_data_entries.push(group.entry.clone());
+                main_read_only_data_entries.len() - 1
+            }
+        };
+
+        for data_index_entry in data_index_list_entries
+            .iter_mut()
+            .flat_map(|list_entry| list_entry.index_entries.iter_mut())
+        {
+            let is_occurrence = data_index_entry.target_data_section_type
+                == DataSectionType::ReadOnly
+                && group.occurrences.contains(&(
+                    data_index_entry.target_module_index,
+                    data_index_entry.data_internal_index_in_section,
+                ));
+
+            if is_occurrence {
+                data_index_entry.target_module_index = 0;
+                data_index_entry.data_internal_index_in_section = main_data_internal_index;
+            }
+        }
+    }
+}
+
+/// Moves the read-write data items which are never stored to into the read-only
+/// sections of their modules, and updates the data index entries and the data names.
+///
+/// `image_common_entries` and `data_index_list_entries` are the modules of the
+/// application and their data index lists, in the order of the module indices.
+/// Nothing is migrated if a data public index can not be resolved.
+///
+/// Returns the number of the migrated items.
+pub fn migrate_unmodified_read_write_data(
+    image_common_entries: &mut [ImageCommonEntry],
+    data_index_list_entries: &mut [DataIndexListEntry],
+) -> usize {
+    // The modified read-write data items, i.e., `(module_index, data_internal_index)`.
+    let mut modified_items: HashSet<(usize, usize)> = HashSet::new();
+
+    for (module_index, image_common_entry) in image_common_entries.iter().enumerate() {
+        for function_entry in &image_common_entry.function_entries {
+            for record in InstructionIterator::new(&function_entry.code) {
+                if !is_possible_data_store(record.opcode) {
+                    continue;
+                }
+
+                let opcode_info = get_opcode_info(record.opcode);
+                let Some(operand_index) = opcode_info
+                    .operands
+                    .iter()
+                    .position(|operand| *operand == OperandKind::DataPublicIndex)
+                else {
+                    continue;
+                };
+
+                let operand_offset = opcode_info.operand_offset(operand_index);
+                let data_public_index = u32::from_le_bytes(
+                    record.data[operand_offset..(operand_offset + 4)]
+                        .try_into()
+                        .unwrap(),
+                ) as usize;
+
+                let Some(data_index_entry) = data_index_list_entries
+                    .get(module_index)
+                    .and_then(|list_entry| list_entry.index_entries.get(data_public_index))
+                else {
+                    return 0;
+                };
+
+                if data_index_entry.target_data_section_type == DataSectionType::ReadWrite {
+                    modified_items.insert((
+                        data_index_entry.target_module_index,
+                        data_index_entry.data_internal_index_in_section,
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut migrated_count = 0;
+
+    for (module_index, image_common_entry) in image_common_entries.iter_mut().enumerate() {
+        // `old read-write internal index -> (new section type, new internal index)`
+        let mut index_map: HashMap<usize, (DataSectionType, usize)> = HashMap::new();
+        let mut read_write_data_entries = vec![];
+
+        for (data_internal_index, entry) in
+            std::mem::take(&mut image_common_entry.read_write_data_entries)
+                .into_iter()
+                .enumerate()
+        {
+            if modified_items.contains(&(module_index, data_internal_index)) {
+                index_map.insert(
+                    data_internal_index,
+                    (DataSectionType::ReadWrite, read_write_data_entries.len()),
+                );
+                read_write_data_entries.push(entry);
+            } else {
+                index_map.insert(
+                    data_internal_index,
+                    (
+                        DataSectionType::ReadOnly,
+                        image_common_entry.read_only_data_entries.len(),
+                    ),
+                );
+                image_common_entry
+                    .read_only_data_entries
+                    .push(ReadOnlyDataEntry {
+                        memory_data_type: entry.memory_data_type,
+                        data: entry.data,
+                        length: entry.length,
+                        align: entry.align,
+                    });
+                migrated_count += 1;
+            }
+        }
+
+        image_common_entry.read_write_data_entries = read_write_data_entries;
+
+        for data_name_entry in image_common_entry.data_data_entries.iter_mut() {
+            if data_name_entry.section_type != DataSectionType::ReadWrite {
+                continue;
+            }
+
+            if let Some((section_type, data_internal_index)) =
+                index_map.get(&data_name_entry.internal_index_in_section)
+            {
+                data_name_entry.section_type = *section_type;
+                data_name_entry.internal_index_in_section = *data_internal_index;
+            }
+        }
+
+        for data_index_entry in data_index_list_entries
+            .iter_mut()
+            .flat_map(|list_entry| list_entry.index_entries.iter_mut())
+        {
+            if data_index_entry.target_module_index != module_index
+                || data_index_entry.target_data_section_type != DataSectionType::ReadWrite
+            {
+                continue;
+            }
+
+            if let Some((section_type, data_internal_index)) =
+                index_map.get(&data_index_entry.data_internal_index_in_section)
+            {
+                data_index_entry.target_data_section_type = *section_type;
+                data_index_entry.data_internal_index_in_section = *data_internal_index;
+            }
+        }
+    }
+
+    migrated_count
+}
+
+// Returns `true` if the instruction may modify the data it refers to,
+// see the description at the top of this file.
+fn is_possible_data_store(opcode: Opcode) -> bool {
+    !matches!(
+        opcode,
+        Opcode::data_load_i64
+            | Opcode::data_load_i32_s
+            | Opcode::data_load_i32_u
+            | Opcode::data_load_i16_s
+            | Opcode::data_load_i16_u
+            | Opcode::data_load_i8_s
+            | Opcode::data_load_i8_u
+            | Opcode::data_load_f64
+            | Opcode::data_load_f32
+            | Opcode::data_load_extend_i64
+            | Opcode::data_load_extend_i32_s
+            | Opcode::data_load_extend_i32_u
+            | Opcode::data_load_extend_i16_s
+            | Opcode::data_load_extend_i16_u
+            | Opcode::data_load_extend_i8_s
+            | Opcode::data_load_extend_i8_u
+            | Opcode::data_load_extend_f64
+            | Opcode::data_load_extend_f32
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, DataSectionType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        data_sharing::{
+            analyze_read_only_data_sharing, merge_shared_read_only_data,
+            migrate_unmodified_read_write_data, SharedReadOnlyData,
+        },
+        entry::{
+            DataIndexEntry, DataIndexListEntry, DataNameEntry, ReadOnlyDataEntry,
+            ReadWriteDataEntry,
+        },
+        entry_reader::read_object_file,
+        module_image::{ModuleImage, Visibility},
+        utils::helper_build_module_binary_with_single_function_and_data,
+    };
+
+    #[test]
+    fn test_read_only_data_sharing() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let build_module = |read_only_data_entries: &[ReadOnlyDataEntry]| {
+            helper_build_module_binary_with_single_function_and_data(
+                &[],
+                &[],
+                &[],
+                code.clone(),
+                read_only_data_entries,
+                &[],
+                &[],
+            )
+        };
+
+        let hello = ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1);
+        let world = ReadOnlyDataEntry::from_bytes(b"world".to_vec(), 1);
+        let number = ReadOnlyDataEntry::from_i32(11);
+
+        let binaries = [
+            build_module(&[hello.clone()]),
+            build_module(&[number, hello.clone(), world.clone()]),
+            build_module(&[world.clone(), hello.clone()]),
+            // the same content with different alignment
+            build_module(&[ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 4)]),
+        ];
+        let module_images = binaries
+            .iter()
+            .map(|binary| ModuleImage::read(binary).unwrap())
+            .collect::<Vec<_>>();
+
+        let report = analyze_read_only_data_sharing(&module_images);
+        assert_eq!(
+            report.shared_data,
+            vec![
+                SharedReadOnlyData {
+                    entry: hello.clone(),
+                    occurrences: vec![(0, 0), (1, 1), (2, 1)],
+                },
+                SharedReadOnlyData {
+                    entry: world.clone(),
+                    occurrences: vec![(1, 2), (2, 0)],
+                },
+            ]
+        );
+        assert_eq!(report.saved_bytes, 5 * 2 + 5);
+
+        let mut main_read_only_data_entries = vec![hello.clone()];
+        let mut data_index_list_entries = vec![
+            DataIndexListEntry::new(vec![DataIndexEntry::new(0, DataSectionType::ReadOnly, 0)]),
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(1, DataSectionType::ReadOnly, 0),
+                DataIndexEntry::new(1, DataSectionType::ReadOnly, 1),
+                DataIndexEntry::new(1, DataSectionType::ReadOnly, 2),
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 1),
+            ]),
+        ];
+
+        merge_shared_read_only_data(
+            &report,
+            &mut main_read_only_data_entries,
+            &mut data_index_list_entries,
+        );
+
+        assert_eq!(main_read_only_data_entries, vec![hello, world]);
+        assert_eq!(
+            data_index_list_entries,
+            vec![
+                DataIndexListEntry::new(vec![DataIndexEntry::new(0, DataSectionType::ReadOnly, 0)]),
+                DataIndexListEntry::new(vec![
+                    DataIndexEntry::new(1, DataSectionType::ReadOnly, 0),
+                    DataIndexEntry::new(0, DataSectionType::ReadOnly, 0),
+                    DataIndexEntry::new(0, DataSectionType::ReadOnly, 1),
+                    DataIndexEntry::new(1, DataSectionType::ReadWrite, 1),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_unmodified_read_write_data() {
+        let build_module = |code: Vec<u8>, read_write_data_entries: &[ReadWriteDataEntry]| {
+            let binary = helper_build_module_binary_with_single_function_and_data(
+                &[],
+                &[],
+                &[],
+                code,
+                &[],
+                read_write_data_entries,
+                &[],
+            );
+            read_object_file(&binary).unwrap()
+        };
+
+        // module 0: loads the item 0, stores the item 1, and gets the address of the item 2.
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 0)
+            .append_opcode_i16_i32(Opcode::data_store_i32, 0, 1)
+            .append_opcode_i16_i32(Opcode::host_addr_data, 0, 2)
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 3)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // module 1: stores the item 3 of module 0, and loads its own item 0.
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::data_store_i32, 0, 0)
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let mut module0 = build_module(
+            code0,
+            &[
+                ReadWriteDataEntry::from_i32(11),
+                ReadWriteDataEntry::from_i32(13),
+                ReadWriteDataEntry::from_i32(17),
+                ReadWriteDataEntry::from_i32(19),
+            ],
+        );
+        module0.data_data_entries = vec![
+            DataNameEntry::new(
+                "foo::a".to_owned(),
+                Visibility::Private,
+                DataSectionType::ReadWrite,
+                0,
+            ),
+            DataNameEntry::new(
+                "foo::d".to_owned(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                3,
+            ),
+        ];
+
+        let module1 = build_module(code1, &[ReadWriteDataEntry::from_i32(23)]);
+        let mut image_common_entries = vec![module0, module1];
+
+        let mut data_index_list_entries = vec![
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(0, DataSectionType::ReadWrite, 0),
+                DataIndexEntry::new(0, DataSectionType::ReadWrite, 1),
+                DataIndexEntry::new(0, DataSectionType::ReadWrite, 2),
+                DataIndexEntry::new(0, DataSectionType::ReadWrite, 3),
+            ]),
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(0, DataSectionType::ReadWrite, 3),
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 0),
+            ]),
+        ];
+
+        assert_eq!(
+            migrate_unmodified_read_write_data(
+                &mut image_common_entries,
+                &mut data_index_list_entries
+            ),
+            2
+        );
+
+        assert_eq!(
+            image_common_entries[0].read_only_data_entries,
+            vec![ReadOnlyDataEntry::from_i32(11)]
+        );
+        assert_eq!(
+            image_common_entries[0].read_write_data_entries,
+            vec![
+                ReadWriteDataEntry::from_i32(13),
+                ReadWriteDataEntry::from_i32(17),
+                ReadWriteDataEntry::from_i32(19),
+            ]
+        );
+        assert_eq!(
+            image_common_entries[1].read_only_data_entries,
+            vec![ReadOnlyDataEntry::from_i32(23)]
+        );
+        assert!(image_common_entries[1].read_write_data_entries.is_empty());
+
+        assert_eq!(
+            image_common_entries[0]
+                .data_data_entries
+                .iter()
+                .map(|entry| (entry.section_type, entry.internal_index_in_section))
+                .collect::<Vec<_>>(),
+            vec![
+                (DataSectionType::ReadOnly, 0),
+                (DataSectionType::ReadWrite, 2)
+            ]
+        );
+
+        assert_eq!(
+            data_index_list_entries,
+            vec![
+                DataIndexListEntry::new(vec![
+                    DataIndexEntry::new(0, DataSectionType::ReadOnly, 0),
+                    DataIndexEntry::new(0, DataSectionType::ReadWrite, 0),
+                    DataIndexEntry::new(0, DataSectionType::ReadWrite, 1),
+                    DataIndexEntry::new(0, DataSectionType::ReadWrite, 2),
+                ]),
+                DataIndexListEntry::new(vec![
+                    DataIndexEntry::new(0, DataSectionType::ReadWrite, 2),
+                    DataIndexEntry::new(1, DataSectionType::ReadOnly, 0),
+                ]),
+            ]
+        );
+
+        // the unresolved data public index
+        let mut data_index_list_entries = vec![DataIndexListEntry::new(vec![])];
+        assert_eq!(
+            migrate_unmodified_read_write_data(
+                &mut image_common_entries[..1],
+                &mut data_index_list_entries
+            ),
+            0
+        );
+    }
+}
@@ -6,7 +6,54 @@
 
 use std::ptr::slice_from_raw_parts;
 
-use crate::module_image::{BASE_SECTION_HEADER_LENGTH, TABLE_RECORD_ALIGN_BYTES};
+use crate::module_image::{RangeItem, BASE_SECTION_HEADER_LENGTH, TABLE_RECORD_ALIGN_BYTES};
+
+/// Checks whether the section data is large enough to hold the header and
+/// the (first) table declared by the header, and whether the section data is
+/// aligned for reading the header and the table records.
+///
+/// The `read_section_*` functions trust the section data, this function should be
+/// called before reading a section from untrusted data.
+pub fn check_section_with_table<T>(section_data: &[u8]) -> bool {
+    if section_data.len() < BASE_SECTION_HEADER_LENGTH {
+        return false;
+    }
+
+    // the header and the records are read by casting the pointer
+    let align = TABLE_RECORD_ALIGN_BYTES.max(align_of::<T>());
+    if section_data.as_ptr().align_offset(align) != 0 {
+        return false;
+    }
+
+    let item_count = u32::from_le_bytes(section_data[0..4].try_into().unwrap()) as usize;
+    item_count
+        .checked_mul(size_of::<T>())
+        .and_then(|length| length.checked_add(BASE_SECTION_HEADER_LENGTH))
+        .is_some_and(|end| end <= section_data.len())
+}
+
+/// Returns the bytes at `offset..(offset + length)` of the data area,
+/// or `None` if the range exceeds the data area.
+///
+/// The `convert_to_entries` functions of the sections trust the offsets of
+/// the table items, the `is_well_formed` functions check them by this function.
+pub fn get_data_area_bytes(data_area: &[u8], offset: u32, length: u32) -> Option<&[u8]> {
+    let start = offset as usize;
+    data_area.get(start..(start + length as usize))
+}
+
+/// The same as `get_data_area_bytes`, but the bytes must be a valid UTF-8 string.
+pub fn get_data_area_text(data_area: &[u8], offset: u32, length: u32) -> Option<&str> {
+    get_data_area_bytes(data_area, offset, length).and_then(|data| std::str::from_utf8(data).ok())
+}
+
+/// Checks that the items of all ranges (i.e., `offset..(offset + count)`)
+/// are located in the table of the items, e.g., the function index section.
+pub fn check_ranges(ranges: &[RangeItem], item_count: usize) -> bool {
+    ranges
+        .iter()
+        .all(|range| range.offset as usize + range.count as usize <= item_count)
+}
 
 /// Reads a section containing two tables.
 ///
@@ -84,6 +131,11 @@ pub fn write_section_with_two_tables<T0, T1>(
     Ok(())
 }
 
+/// Returns the length (in bytes) of the section written by `write_section_with_two_tables`.
+pub fn section_with_two_tables_length<T0, T1>(items0: &[T0], items1: &[T1]) -> usize {
+    BASE_SECTION_HEADER_LENGTH + std::mem::size_of_val(items0) + std::mem::size_of_val(items1)
+}
+
 /// Reads a section containing a table and a variable-length data area.
 ///
 /// ```text
@@ -154,6 +206,16 @@ pub fn write_section_with_table_and_data_area<T>(
     Ok(())
 }
 
+/// Returns the length (in bytes) of the section written by
+/// `write_section_with_table_and_data_area`, the padding of the data area is included.
+pub fn section_with_table_and_data_area_length<T>(items: &[T], additional_data: &[u8]) -> usize {
+    BASE_SECTION_HEADER_LENGTH
+        + std::mem::size_of_val(items)
+        + additional_data
+            .len()
+            .next_multiple_of(TABLE_RECORD_ALIGN_BYTES)
+}
+
 /// Reads a section containing only one table.
 ///
 /// ```text
@@ -204,6 +266,11 @@ pub fn write_section_with_one_table<T>(
     Ok(())
 }
 
+/// Returns the length (in bytes) of the section written by `write_section_with_one_table`.
+pub fn section_with_one_table_length<T>(items: &[T]) -> usize {
+    BASE_SECTION_HEADER_LENGTH + std::mem::size_of_val(items)
+}
+
 /// Reads a table from the given data.
 ///
 /// Note: The record length must be a multiple of 4 bytes.
@@ -0,0 +1,229 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Splits the debug sections of an image into a companion debug file (`*.ancd`),
+// so that the shipped images are kept small, and attaches them back when
+// symbolizing, similar to the "split debug info" of the native toolchains.
+//
+// The debug sections are the "function name", "data name" and "relocate" sections.
+//
+// - The debug file is an image of the type `ImageType::DebugInfo`, it contains
+//   the property section of the original image (for identifying the module)
+//   and the debug sections.
+// - The main image contains the other sections and the "debug link" section,
+//   which records the hash of the debug file.
+//
+// Note: the function and data of the main image can no longer be imported
+// by name, and the image can no longer be linked, so the splitting is suitable
+// for the applications and the shared modules which are linked already.
+
+use std::fmt::Display;
+
+use crate::{
+    common_sections::debug_link_section::DebugLinkSection,
+    export_surface::compute_content_hash,
+    image_pipeline::ImageSections,
+    module_image::{ImageType, ModuleImage, ModuleSectionId, SectionEntry},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why the debug information can not be attached.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DebugInfoError {
+    MissingDebugLink,
+
+    // The hash of the debug file does not match the "debug link" section of the image.
+    DebugFileMismatch {
+        expected_hash: u64,
+        actual_hash: u64,
+    },
+
+    NotDebugFile(ImageType),
+}
+
+impl Display for DebugInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DebugInfoError::MissingDebugLink => write!(f, "The image has no debug link."),
+            DebugInfoError::DebugFileMismatch {
+                expected_hash,
+                actual_hash,
+            } => write!(
+                f,
+                "The debug file does not match the image, expect hash {:016x}, actual {:016x}.",
+                expected_hash, actual_hash
+            ),
+            DebugInfoError::NotDebugFile(image_type) => {
+                write!(f, "Not a debug file, found {:?}.", image_type)
+            }
+        }
+    }
+}
+
+pub const DEBUG_SECTION_IDS: [ModuleSectionId; 3] = [
+    ModuleSectionId::FunctionName,
+    ModuleSectionId::DataName,
+    ModuleSectionId::Relocate,
+];
+
+/// Writes the image without the debug sections to `main_writer`,
+/// and the debug file to `debug_writer`.
+pub fn split_debug_info(
+    image: &ModuleImage,
+    main_writer: &mut dyn std::io::Write,
+    debug_writer: &mut dyn std::io::Write,
+) -> Result<(), ImageError> {
+    let image_sections = ImageSections::from_module_image(image);
+
+    let mut main_sections = image_sections.clone();
+    let mut debug_sections = ImageSections {
+        image_type: ImageType::DebugInfo,
+        sections: vec![],
+        compression_info: None,
+    };
+
+    if let Some(property_data) = image_sections.get_section_data(ModuleSectionId::Property) {
+        debug_sections.set_section_data(ModuleSectionId::Property, property_data.to_vec());
+    }
+
+    for section_id in DEBUG_SECTION_IDS {
+        if let Some(section_data) = image_sections.get_section_data(section_id) {
+            debug_sections.set_section_data(section_id, section_data.to_vec());
+            main_sections.remove_section(section_id);
+        }
+    }
+
+    let mut debug_binary: Vec<u8> = vec![];
+    debug_sections.write(&mut debug_binary)?;
+
+    let debug_link_section = DebugLinkSection::new(compute_content_hash(&debug_binary));
+    let mut debug_link_data: Vec<u8> = vec![];
+    debug_link_section.write(&mut debug_link_data)?;
+    main_sections.set_section_data(ModuleSectionId::DebugLink, debug_link_data);
+
+    debug_writer.write_all(&debug_binary)?;
+    main_sections.write(main_writer)
+}
+
+/// Returns `true` if the debug file is the companion of the image,
+/// i.e., the hash of the debug file matches the "debug link" section of the image.
+pub fn is_debug_file_matched(image: &ModuleImage, debug_binary: &[u8]) -> bool {
+    image
+        .get_optional_debug_link_section()
+        .is_some_and(|section| section.debug_file_hash == compute_content_hash(debug_binary))
+}
+
+/// Writes the image with the debug sections of the debug file attached,
+/// and the "debug link" section removed.
+pub fn attach_debug_info(
+    image: &ModuleImage,
+    debug_binary: &[u8],
+    writer: &mut dyn std::io::Write,
+) -> Result<(), ImageError> {
+    let Some(debug_link_section) = image.get_optional_debug_link_section() else {
+        return Err(ImageError::new(ImageErrorType::DebugInfo(
+            DebugInfoError::MissingDebugLink,
+        )));
+    };
+
+    let debug_file_hash = compute_content_hash(debug_binary);
+    if debug_link_section.debug_file_hash != debug_file_hash {
+        return Err(ImageError::new(ImageErrorType::DebugInfo(
+            DebugInfoError::DebugFileMismatch {
+                expected_hash: debug_link_section.debug_file_hash,
+                actual_hash: debug_file_hash,
+            },
+        )));
+    }
+
+    let debug_image = ModuleImage::read(debug_binary)?;
+    if debug_image.image_type != ImageType::DebugInfo {
+        return Err(ImageError::new(ImageErrorType::DebugInfo(
+            DebugInfoError::NotDebugFile(debug_image.image_type),
+        )));
+    }
+
+    let mut image_sections = ImageSections::from_module_image(image);
+    image_sections.remove_section(ModuleSectionId::DebugLink);
+
+    let debug_sections = ImageSections::from_module_image(&debug_image);
+    for section_id in DEBUG_SECTION_IDS {
+        if let Some(section_data) = debug_sections.get_section_data(section_id) {
+            image_sections.set_section_data(section_id, section_data.to_vec());
+        }
+    }
+
+    image_sections.write(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::OperandDataType;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        debug_info::{attach_debug_info, is_debug_file_matched, split_debug_info, DebugInfoError},
+        entry::TypeEntry,
+        entry_writer::build_shared_module_scaffold,
+        image_pipeline::ImageSections,
+        module_image::{ImageType, ModuleImage},
+        ImageErrorType,
+    };
+
+    #[test]
+    fn test_split_and_attach_debug_info() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[(
+                "add",
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+            )],
+        )
+        .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut main_binary: Vec<u8> = vec![];
+        let mut debug_binary: Vec<u8> = vec![];
+        split_debug_info(&module_image, &mut main_binary, &mut debug_binary).unwrap();
+
+        let main_image = ModuleImage::read(&main_binary).unwrap();
+        assert!(main_image.get_optional_export_function_section().is_none());
+        assert!(main_image.get_optional_debug_link_section().is_some());
+        assert!(is_debug_file_matched(&main_image, &debug_binary));
+
+        let debug_image = ModuleImage::read(&debug_binary).unwrap();
+        assert_eq!(debug_image.image_type, ImageType::DebugInfo);
+        assert_eq!(debug_image.get_property_section().get_module_name(), "foo");
+        assert!(debug_image.get_optional_export_function_section().is_some());
+
+        // attaches the debug file back
+        let mut attached_binary: Vec<u8> = vec![];
+        attach_debug_info(&main_image, &debug_binary, &mut attached_binary).unwrap();
+        let attached_image = ModuleImage::read(&attached_binary).unwrap();
+        assert_eq!(
+            ImageSections::from_module_image(&attached_image),
+            ImageSections::from_module_image(&module_image)
+        );
+
+        // the mismatched debug file
+        let mut other_debug_binary = debug_binary.clone();
+        other_debug_binary.push(0);
+        assert!(matches!(
+            attach_debug_info(&main_image, &other_debug_binary, &mut Vec::<u8>::new())
+                .unwrap_err()
+                .error_type,
+            ImageErrorType::DebugInfo(DebugInfoError::DebugFileMismatch { .. })
+        ));
+
+        // the image without debug link
+        assert_eq!(
+            attach_debug_info(&module_image, &debug_binary, &mut Vec::<u8>::new())
+                .unwrap_err()
+                .to_string(),
+            "Debug info error: The image has no debug link."
+        );
+    }
+}
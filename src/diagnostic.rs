@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The diagnostics produced by the tools which check module images,
+// e.g., the validator and the lint, so that the toolchains can render
+// them uniformly.
+//
+// A diagnostic can carry a span, i.e., the section and the byte range
+// within the section data (the section header is included, i.e., the
+// offset 0 is the "item count" field), so that the editors can highlight
+// the exact bytes or items involved. The related spans point out the
+// other locations which help to understand the diagnostic, e.g., the
+// definition of a function.
+
+use std::{fmt::Display, ops::Range};
+
+use crate::module_image::{ModuleSectionId, BASE_SECTION_HEADER_LENGTH};
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    // The identifier of the check, e.g. "unused_import_function".
+    pub code: &'static str,
+
+    pub message: String,
+
+    // The span of the diagnostic, they are `None` if the diagnostic
+    // is not related to a specific section or item.
+    pub section_id: Option<ModuleSectionId>,
+    pub byte_range: Option<Range<usize>>,
+
+    pub related: Vec<RelatedSpan>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RelatedSpan {
+    pub section_id: ModuleSectionId,
+    pub byte_range: Range<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: String) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            section_id: None,
+            byte_range: None,
+            related: vec![],
+        }
+    }
+
+    /// Sets the span of the diagnostic.
+    pub fn with_span(mut self, section_id: ModuleSectionId, byte_range: Range<usize>) -> Self {
+        self.section_id = Some(section_id);
+        self.byte_range = Some(byte_range);
+        self
+    }
+
+    /// Appends a related span.
+    pub fn with_related(
+        mut self,
+        section_id: ModuleSectionId,
+        byte_range: Range<usize>,
+        message: String,
+    ) -> Self {
+        self.related.push(RelatedSpan {
+            section_id,
+            byte_range,
+            message,
+        });
+        self
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    // Formats the diagnostic as the following text:
+    //
+    // warning[unused_private_function]: The private function "foo::bar" is never referenced.
+    //   --> FunctionName 0x0008..0x0018
+    //   note: The function is defined here.
+    //   --> Function 0x0018..0x0028
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+
+        if let (Some(section_id), Some(byte_range)) = (&self.section_id, &self.byte_range) {
+            write!(f, "\n  --> {}", format_span(section_id, byte_range))?;
+        }
+
+        for related in &self.related {
+            write!(
+                f,
+                "\n  note: {}\n  --> {}",
+                related.message,
+                format_span(&related.section_id, &related.byte_range)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn format_span(section_id: &ModuleSectionId, byte_range: &Range<usize>) -> String {
+    format!(
+        "{:?} 0x{:04x}..0x{:04x}",
+        section_id, byte_range.start, byte_range.end
+    )
+}
+
+/// Returns the byte range of the specified item in the (first) table
+/// of a section.
+pub fn table_item_byte_range(item_index: usize, item_size: usize) -> Range<usize> {
+    let start = BASE_SECTION_HEADER_LENGTH + item_index * item_size;
+    start..(start + item_size)
+}
+
+/// Returns the byte range of the specified bytes in the data area of a section
+/// which consists of a table and a data area, e.g., the function section.
+pub fn data_area_byte_range(
+    table_item_count: usize,
+    table_item_size: usize,
+    offset_in_data_area: usize,
+    length: usize,
+) -> Range<usize> {
+    let start =
+        BASE_SECTION_HEADER_LENGTH + table_item_count * table_item_size + offset_in_data_area;
+    start..(start + length)
+}
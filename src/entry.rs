@@ -4,7 +4,7 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
-use std::fmt::Debug;
+use std::{fmt::Debug, num::NonZeroU16};
 
 use anc_isa::{
     DataSectionType, EffectiveVersion, ExternalLibraryDependency, MemoryDataType, ModuleDependency,
@@ -14,7 +14,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     bytecode_reader::format_bytecode_as_text,
-    module_image::{ImageType, RelocateType, Visibility},
+    module_image::{
+        ExportType, ImageType, InitializerType, LicenseTargetType, RelocateType, Visibility,
+    },
+    parse_dependency_hash, DependencyHash,
 };
 
 // Represents the type signature of a function or block, including parameters and results.
@@ -72,6 +75,22 @@ impl Debug for FunctionEntry {
     }
 }
 
+/// Infers the alignment of a "bytes" data item from its length,
+/// i.e., the largest of 8, 4, 2 and 1 which divides the length.
+///
+/// e.g., the alignment of a 12-byte item is 4, and the alignment of
+/// an empty item is 8.
+///
+/// The inferred alignment is always safe for loading and storing the
+/// primitive values at the aligned positions within the item, but it may be
+/// larger than required, specify the alignment explicitly if it is known.
+pub fn infer_data_align(length: usize) -> u16 {
+    [8, 4, 2]
+        .into_iter()
+        .find(|align| length % (*align as usize) == 0)
+        .unwrap_or(1)
+}
+
 // Represents initialized data, including its type, content, length, and alignment.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ReadOnlyDataEntry {
@@ -144,6 +163,60 @@ impl ReadOnlyDataEntry {
             align,
         }
     }
+
+    /// Creates a "bytes" data item with the alignment inferred from the length,
+    /// see `infer_data_align`.
+    pub fn from_bytes_auto_align(data: Vec<u8>) -> Self {
+        let align = infer_data_align(data.len());
+        Self::from_bytes(data, align)
+    }
+
+    /// Creates a string constant with the specified encoding.
+    ///
+    /// The data type is "bytes", the alignment is the size of the code unit,
+    /// i.e., 1 for UTF-8 and 2 for UTF-16.
+    pub fn from_str(s: &str, encoding: StringEncoding) -> Self {
+        Self::from_bytes(encoding.encode(s), encoding.code_unit_length())
+    }
+}
+
+// The encoding of the string constants, see `ReadOnlyDataEntry::from_str`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StringEncoding {
+    // The UTF-8 bytes without terminator, the length of the string
+    // is the length of the data item.
+    Utf8,
+
+    // The UTF-8 bytes followed by a `0x00` byte, i.e., the C string.
+    Utf8NulTerminated,
+
+    // The UTF-16 code units in little-endian, without terminator.
+    Utf16Le,
+}
+
+impl StringEncoding {
+    pub fn encode(&self, s: &str) -> Vec<u8> {
+        match self {
+            StringEncoding::Utf8 => s.as_bytes().to_vec(),
+            StringEncoding::Utf8NulTerminated => {
+                let mut data = Vec::with_capacity(s.len() + 1);
+                data.extend_from_slice(s.as_bytes());
+                data.push(0);
+                data
+            }
+            StringEncoding::Utf16Le => s
+                .encode_utf16()
+                .flat_map(|code_unit| code_unit.to_le_bytes())
+                .collect(),
+        }
+    }
+
+    pub fn code_unit_length(&self) -> u16 {
+        match self {
+            StringEncoding::Utf8 | StringEncoding::Utf8NulTerminated => 1,
+            StringEncoding::Utf16Le => 2,
+        }
+    }
 }
 
 // Represents initialized data, including its type, content, length, and alignment.
@@ -218,6 +291,13 @@ impl ReadWriteDataEntry {
             align,
         }
     }
+
+    /// Creates a "bytes" data item with the alignment inferred from the length,
+    /// see `infer_data_align`.
+    pub fn from_bytes_auto_align(data: Vec<u8>) -> Self {
+        let align = infer_data_align(data.len());
+        Self::from_bytes(data, align)
+    }
 }
 
 // Represents uninitialized data, including its type, length, and alignment.
@@ -226,6 +306,12 @@ pub struct UninitDataEntry {
     pub memory_data_type: MemoryDataType,
     pub length: u32, // Length of the data in bytes.
     pub align: u16,  // Alignment requirement in bytes.
+
+    // By default the uninitialized data is zeroed when the module is loaded,
+    // `true` indicates that the content may be left undefined, e.g., a huge
+    // scratch buffer which is always written before being read,
+    // so the runtime can skip zeroing it.
+    pub may_be_undefined: bool,
 }
 
 impl UninitDataEntry {
@@ -234,6 +320,7 @@ impl UninitDataEntry {
             memory_data_type: MemoryDataType::I32,
             length: 4,
             align: 4,
+            may_be_undefined: false,
         }
     }
 
@@ -242,6 +329,7 @@ impl UninitDataEntry {
             memory_data_type: MemoryDataType::I64,
             length: 8,
             align: 8,
+            may_be_undefined: false,
         }
     }
 
@@ -250,6 +338,7 @@ impl UninitDataEntry {
             memory_data_type: MemoryDataType::F32,
             length: 4,
             align: 4,
+            may_be_undefined: false,
         }
     }
 
@@ -258,6 +347,7 @@ impl UninitDataEntry {
             memory_data_type: MemoryDataType::F64,
             length: 8,
             align: 8,
+            may_be_undefined: false,
         }
     }
 
@@ -266,8 +356,15 @@ impl UninitDataEntry {
             memory_data_type: MemoryDataType::Bytes,
             length,
             align,
+            may_be_undefined: false,
         }
     }
+
+    /// Marks the data as "may be left undefined", see `may_be_undefined`.
+    pub fn with_undefined(mut self) -> Self {
+        self.may_be_undefined = true;
+        self
+    }
 }
 
 // Represents an external library dependency, including its name and dependency details.
@@ -310,6 +407,10 @@ pub struct ImportModuleEntry {
     // Only [a-zA-Z0-9_] and Unicode characters are allowed for module names.
     pub name: String,
     pub module_dependency: Box<ModuleDependency>,
+
+    // The optional version range the resolved module must satisfy,
+    // e.g. "^1.2" or ">=1.0, <2.0", see `version_range` for the syntax.
+    pub version_range: Option<String>,
 }
 
 impl ImportModuleEntry {
@@ -317,9 +418,16 @@ impl ImportModuleEntry {
         Self {
             name,
             module_dependency,
+            version_range: None,
         }
     }
 
+    /// Sets the version range the resolved module must satisfy.
+    pub fn with_version_range(mut self, version_range: String) -> Self {
+        self.version_range = Some(version_range);
+        self
+    }
+
     /// Creates a self-reference entry.
     /// It represents the current module and only presents
     /// in the "import module section" of **object files**.
@@ -327,6 +435,7 @@ impl ImportModuleEntry {
         Self {
             name: SELF_REFERENCE_MODULE_NAME.to_owned(),
             module_dependency: Box::new(ModuleDependency::Current),
+            version_range: None,
         }
     }
 }
@@ -367,6 +476,39 @@ pub enum ModuleLocation {
     Embed,
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ModuleLocationKind {
+    Local,
+    Remote,
+    Share,
+    Runtime,
+    Embed,
+}
+
+impl ModuleLocation {
+    pub fn kind(&self) -> ModuleLocationKind {
+        match self {
+            ModuleLocation::Local(_) => ModuleLocationKind::Local,
+            ModuleLocation::Remote(_) => ModuleLocationKind::Remote,
+            ModuleLocation::Share(_) => ModuleLocationKind::Share,
+            ModuleLocation::Runtime => ModuleLocationKind::Runtime,
+            ModuleLocation::Embed => ModuleLocationKind::Embed,
+        }
+    }
+
+    /// Returns the parsed hash of the module file, or `None` if the location
+    /// has no hash (i.e., `Runtime` and `Embed`) or the hash string is malformed.
+    pub fn hash(&self) -> Option<DependencyHash> {
+        let hash = match self {
+            ModuleLocation::Local(local) => &local.hash,
+            ModuleLocation::Remote(remote) => &remote.hash,
+            ModuleLocation::Share(share) => &share.hash,
+            ModuleLocation::Runtime | ModuleLocation::Embed => return None,
+        };
+        parse_dependency_hash(hash)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(rename = "local")]
 pub struct ModuleLocationLocal {
@@ -450,6 +592,10 @@ pub struct ImportDataEntry {
 
     // For validation during linking.
     pub memory_data_type: MemoryDataType,
+
+    // The data must be initialized before the constructors of the current module run,
+    // see `initialization_order`.
+    pub requires_initialization: bool,
 }
 
 impl ImportDataEntry {
@@ -464,8 +610,15 @@ impl ImportDataEntry {
             import_module_index,
             data_section_type,
             memory_data_type,
+            requires_initialization: false,
         }
     }
+
+    /// Sets whether the data must be initialized before the constructors of the current module run.
+    pub fn with_requires_initialization(mut self, requires_initialization: bool) -> Self {
+        self.requires_initialization = requires_initialization;
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -476,6 +629,11 @@ pub struct FunctionNameEntry {
     pub full_name: String,
     pub visibility: Visibility,
     pub internal_index: usize,
+
+    // The stable ordinal assigned by the toolchain, it starts from 1
+    // (the value `0` means "no ordinal" in the image, so it is not representable here),
+    // see the "Ordinals" section of `function_name_section`.
+    pub ordinal: Option<NonZeroU16>,
 }
 
 impl FunctionNameEntry {
@@ -484,8 +642,14 @@ impl FunctionNameEntry {
             full_name,
             visibility,
             internal_index,
+            ordinal: None,
         }
     }
+
+    pub fn with_ordinal(mut self, ordinal: NonZeroU16) -> Self {
+        self.ordinal = Some(ordinal);
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -732,6 +896,13 @@ impl ExternalFunctionIndexListEntry {
 ///   - Internal Name: `{submodule_name}::test_*`
 ///   - Executes Function: `{app_module_name}::tests::{submodule_name}::test_*`
 ///   - User CLI Unit Name: Name path prefix, e.g., `{submodule_name}`, `{submodule_name}::test_get_`
+///
+/// - **Applications** (of an application suite, e.g., a "busybox" style multi-tool):
+///   - Internal Name: `{application_name}`
+///   - Executes Function: `{app_module_name}::app::{application_name}::_start`
+///   - User CLI Unit Name: `:{application_name}`, or the name of the program
+///     (i.e., `argv[0]`) if the runtime is invoked via a link named after the application.
+///   - The entry carries `ApplicationInfo`.
 #[derive(Debug, PartialEq)]
 pub struct EntryPointEntry {
     /// Internal name of the entry point.
@@ -742,6 +913,14 @@ pub struct EntryPointEntry {
     /// Because the entry points always exist in the main module,
     /// the module index is omitted (the index of main module is always 0).
     pub function_public_index: usize,
+
+    /// The metadata of the top-level application,
+    /// `None` if the entry point is not an application.
+    pub application_info: Option<ApplicationInfo>,
+
+    /// The run configuration which the launchers present and validate
+    /// before running the entry point, it is empty by default.
+    pub run_configuration: RunConfiguration,
 }
 
 impl EntryPointEntry {
@@ -749,12 +928,183 @@ impl EntryPointEntry {
         Self {
             unit_name,
             function_public_index,
+            application_info: None,
+            run_configuration: RunConfiguration::default(),
+        }
+    }
+
+    /// Marks the entry point as a top-level application.
+    pub fn with_application_info(mut self, application_info: ApplicationInfo) -> Self {
+        self.application_info = Some(application_info);
+        self
+    }
+
+    pub fn with_run_configuration(mut self, run_configuration: RunConfiguration) -> Self {
+        self.run_configuration = run_configuration;
+        self
+    }
+}
+
+/// The metadata of a top-level application of an application image.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ApplicationInfo {
+    // The one-line description, it is shown in the list of applications.
+    pub description: String,
+}
+
+impl ApplicationInfo {
+    pub fn new(description: String) -> Self {
+        Self { description }
+    }
+}
+
+/// The run configuration of an entry point.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RunConfiguration {
+    // The names of the environment variables which must be defined.
+    pub required_environment_variables: Vec<String>,
+
+    // The working directory, `None` for the current directory of the launcher.
+    pub working_directory: Option<String>,
+
+    // The arguments which are passed to the entry point when
+    // no argument is given by the user.
+    pub default_arguments: Vec<String>,
+}
+
+impl RunConfiguration {
+    pub fn is_empty(&self) -> bool {
+        self == &RunConfiguration::default()
+    }
+
+    /// Returns the required environment variables which are not defined,
+    /// `is_defined` checks whether a variable is defined in the environment
+    /// of the launcher.
+    pub fn get_missing_environment_variables(
+        &self,
+        is_defined: impl Fn(&str) -> bool,
+    ) -> Vec<&str> {
+        self.required_environment_variables
+            .iter()
+            .map(|name| name.as_str())
+            .filter(|name| !is_defined(name))
+            .collect()
+    }
+}
+
+// Represents the ABI hash of a public function or data, see `export_hash_section`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExportHashEntry {
+    pub export_type: ExportType,
+
+    // `None` for functions.
+    pub section_type: Option<DataSectionType>,
+
+    // The function internal index, or the internal index in the data section.
+    pub internal_index: usize,
+
+    pub hash: u64,
+}
+
+impl ExportHashEntry {
+    pub fn from_function(function_internal_index: usize, hash: u64) -> Self {
+        Self {
+            export_type: ExportType::Function,
+            section_type: None,
+            internal_index: function_internal_index,
+            hash,
+        }
+    }
+
+    pub fn from_data(
+        section_type: DataSectionType,
+        internal_index_in_section: usize,
+        hash: u64,
+    ) -> Self {
+        Self {
+            export_type: ExportType::Data,
+            section_type: Some(section_type),
+            internal_index: internal_index_in_section,
+            hash,
+        }
+    }
+}
+
+// Represents a constructor or finalizer of the module, see `initializer_section`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InitializerEntry {
+    pub initializer_type: InitializerType,
+    pub function_internal_index: usize,
+}
+
+impl InitializerEntry {
+    pub fn new(initializer_type: InitializerType, function_internal_index: usize) -> Self {
+        Self {
+            initializer_type,
+            function_internal_index,
+        }
+    }
+}
+
+// Represents the license of the module itself or of one of its external libraries,
+// see `license_section`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LicenseEntry {
+    pub target_type: LicenseTargetType,
+
+    // The index of the external library in the "external library" section,
+    // it is `0` when the target is the module itself.
+    pub external_library_index: usize,
+
+    // The SPDX license expression, e.g. "MIT OR Apache-2.0".
+    pub license: String,
+}
+
+impl LicenseEntry {
+    pub fn new(
+        target_type: LicenseTargetType,
+        external_library_index: usize,
+        license: String,
+    ) -> Self {
+        Self {
+            target_type,
+            external_library_index,
+            license,
         }
     }
+
+    pub fn for_module(license: String) -> Self {
+        Self::new(LicenseTargetType::Module, 0, license)
+    }
+
+    pub fn for_external_library(external_library_index: usize, license: String) -> Self {
+        Self::new(
+            LicenseTargetType::ExternalLibrary,
+            external_library_index,
+            license,
+        )
+    }
+}
+
+// Represents a placeholder region of the image which is filled after writing,
+// e.g., the signature, see `patch_slot_section`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PatchSlotEntry {
+    // e.g. "signature".
+    pub name: String,
+
+    // The length of the region in bytes.
+    pub length: usize,
+}
+
+impl PatchSlotEntry {
+    pub fn new(name: String, length: usize) -> Self {
+        Self { name, length }
+    }
 }
 
 // Represents common properties of the module image, including its name, version, and type.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct ImageCommonEntry {
     // The name of the module (similar to a "package" in other languages).
     // It cannot be the name of a submodule.
@@ -795,17 +1145,75 @@ pub struct ImageCommonEntry {
 
     // The external function list.
     pub external_function_entries: Vec<ExternalFunctionEntry>,
+
+    // The constructors and finalizers, in the declared order.
+    pub initializer_entries: Vec<InitializerEntry>,
+}
+
+// Represents a dependency edge of the initialization order: the constructors of
+// the module `module_index` must run after the data item is initialized, i.e.,
+// after the constructors of the module `target_module_index` have run.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InitializationDependencyEntry {
+    pub module_index: usize,
+    pub target_module_index: usize,
+    pub target_data_section_type: DataSectionType,
+    pub data_internal_index_in_section: usize,
 }
 
-#[derive(Debug)]
+impl InitializationDependencyEntry {
+    pub fn new(
+        module_index: usize,
+        target_module_index: usize,
+        target_data_section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+    ) -> Self {
+        Self {
+            module_index,
+            target_module_index,
+            target_data_section_type,
+            data_internal_index_in_section,
+        }
+    }
+}
+
+// Represents a lazily bound external function: the thunk function forwards the
+// calls to the external function, and the runtime resolves the external symbol
+// when the thunk is called for the first time, rather than at load time.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LazyBindingEntry {
+    pub unified_external_function_index: usize,
+
+    // The location of the thunk function.
+    pub module_index: usize,
+    pub function_internal_index: usize,
+}
+
+impl LazyBindingEntry {
+    pub fn new(
+        unified_external_function_index: usize,
+        module_index: usize,
+        function_internal_index: usize,
+    ) -> Self {
+        Self {
+            unified_external_function_index,
+            module_index,
+            function_internal_index,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct ImageLinkingEntry {
     pub function_index_list_entries: Vec<FunctionIndexListEntry>,
     pub data_index_list_entries: Vec<DataIndexListEntry>,
+    pub initialization_dependency_entries: Vec<InitializationDependencyEntry>,
     //
     pub external_function_index_entries: Vec<ExternalFunctionIndexListEntry>,
     pub unified_external_library_entries: Vec<ExternalLibraryEntry>,
     pub unified_external_type_entries: Vec<TypeEntry>,
     pub unified_external_function_entries: Vec<ExternalFunctionEntry>,
+    pub lazy_binding_entries: Vec<LazyBindingEntry>,
     //
     pub linking_module_entries: Vec<LinkingModuleEntry>,
     pub entry_point_entries: Vec<EntryPointEntry>,
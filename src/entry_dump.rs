@@ -0,0 +1,489 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The stable text dump of `ImageCommonEntry`.
+//
+// The dump is designed for the snapshot tests (golden files) of the compilers,
+// the format is part of the public API and will not be changed between releases
+// except adding new groups at the end of the dump.
+//
+// Format
+// ------
+//
+// ```text
+// module: "foo"
+// version: 1.0.0
+// image_type: object_file
+//
+// types:
+//   #0: (i32, i32) -> (i32)
+//
+// local_variable_lists:
+//   #0: (i32, i32, i64)
+//
+// functions:
+//   #0: type #0, local_variable_list #0
+//     0x0000  00 01 00 00  0b 00 00 00
+//     0x0008  c0 03
+//
+// read_only_data:
+//   #0: i32, length 4, align 4
+//     0x0000  0b 00 00 00
+//
+// read_write_data:
+//
+// uninit_data:
+//   #0: i64, length 8, align 8
+//
+// import_modules:
+//   #0: "module" <dependency in ASON>
+//
+// import_functions:
+//   #0: "math::add", module #1, type #1
+//
+// import_data:
+//   #0: "math::pi", module #1, read_only, f64
+//
+// function_names:
+//   "foo::add": public, function #0
+//
+// data_names:
+//   "foo::count": private, read_write #0
+//
+// relocates:
+//   function #0:
+//     0x0004: function_public_index
+//
+// external_libraries:
+//   #0: "libc" <dependency in ASON>
+//
+// external_functions:
+//   #0: "getuid", library #0, type #1
+// ```
+//
+// Rules:
+//
+// - The groups are always written in the order above, even if they are empty.
+//   Each group is followed by an empty line except the last one.
+// - The items are written in the order of their indices, since the order
+//   is significant. The exceptions are the function names and data names,
+//   they are sorted by the full names.
+// - The bytes are written in the format of `format_bytecode_as_binary`, i.e.,
+//   8 bytes per row with the offset, and the rows are indented by 4 spaces.
+// - The dependencies are written in the ASON format as they are stored in the image.
+//   Note that the order of the `parameters` entries of a dependency follows the
+//   serializer, so it is recommended to use at most one parameter in the snapshot tests.
+
+use anc_isa::{DataSectionType, MemoryDataType, OperandDataType};
+
+use crate::{
+    bytecode_reader::format_bytecode_as_binary,
+    entry::ImageCommonEntry,
+    module_image::{ImageType, RelocateType, Visibility},
+};
+
+/// Dumps the `ImageCommonEntry` as text in a stable format.
+///
+/// See the beginning of this file for the format.
+pub fn stable_dump(image_common_entry: &ImageCommonEntry) -> String {
+    // The header group.
+    let mut groups: Vec<Vec<String>> = vec![vec![
+        format!("module: \"{}\"", image_common_entry.name),
+        format!(
+            "version: {}.{}.{}",
+            image_common_entry.version.major,
+            image_common_entry.version.minor,
+            image_common_entry.version.patch
+        ),
+        format!(
+            "image_type: {}",
+            format_image_type(image_common_entry.image_type)
+        ),
+    ]];
+
+    let mut lines = vec!["types:".to_owned()];
+    for (idx, entry) in image_common_entry.type_entries.iter().enumerate() {
+        lines.push(format!(
+            "  #{}: {} -> {}",
+            idx,
+            format_operand_data_types(&entry.params),
+            format_operand_data_types(&entry.results)
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["local_variable_lists:".to_owned()];
+    for (idx, entry) in image_common_entry
+        .local_variable_list_entries
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!(
+            "  #{}: {}",
+            idx,
+            format_operand_data_types(&entry.local_variable_types)
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["functions:".to_owned()];
+    for (idx, entry) in image_common_entry.function_entries.iter().enumerate() {
+        lines.push(format!(
+            "  #{}: type #{}, local_variable_list #{}",
+            idx, entry.type_index, entry.local_variable_list_index
+        ));
+        lines.extend(format_bytes(&entry.code));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["read_only_data:".to_owned()];
+    for (idx, entry) in image_common_entry.read_only_data_entries.iter().enumerate() {
+        lines.push(format!(
+            "  #{}: {}, length {}, align {}",
+            idx,
+            format_memory_data_type(entry.memory_data_type),
+            entry.length,
+            entry.align
+        ));
+        lines.extend(format_bytes(&entry.data));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["read_write_data:".to_owned()];
+    for (idx, entry) in image_common_entry
+        .read_write_data_entries
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!(
+            "  #{}: {}, length {}, align {}",
+            idx,
+            format_memory_data_type(entry.memory_data_type),
+            entry.length,
+            entry.align
+        ));
+        lines.extend(format_bytes(&entry.data));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["uninit_data:".to_owned()];
+    for (idx, entry) in image_common_entry.uninit_data_entries.iter().enumerate() {
+        // The "undefined" mark is appended only if it is set, so the dumps
+        // of the existing images are unchanged.
+        lines.push(format!(
+            "  #{}: {}, length {}, align {}{}",
+            idx,
+            format_memory_data_type(entry.memory_data_type),
+            entry.length,
+            entry.align,
+            if entry.may_be_undefined {
+                ", undefined"
+            } else {
+                ""
+            }
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["import_modules:".to_owned()];
+    for (idx, entry) in image_common_entry.import_module_entries.iter().enumerate() {
+        lines.push(format!(
+            "  #{}: \"{}\" {}{}",
+            idx,
+            entry.name,
+            ason::to_string(entry.module_dependency.as_ref()).unwrap(),
+            match &entry.version_range {
+                Some(version_range) => format!(" version \"{}\"", version_range),
+                None => "".to_owned(),
+            }
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["import_functions:".to_owned()];
+    for (idx, entry) in image_common_entry
+        .import_function_entries
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!(
+            "  #{}: \"{}\", module #{}, type #{}",
+            idx, entry.full_name, entry.import_module_index, entry.type_index
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["import_data:".to_owned()];
+    for (idx, entry) in image_common_entry.import_data_entries.iter().enumerate() {
+        lines.push(format!(
+            "  #{}: \"{}\", module #{}, {}, {}",
+            idx,
+            entry.full_name,
+            entry.import_module_index,
+            format_data_section_type(entry.data_section_type),
+            format_memory_data_type(entry.memory_data_type)
+        ));
+    }
+    groups.push(lines);
+
+    let mut function_name_entries = image_common_entry
+        .function_name_entries
+        .iter()
+        .collect::<Vec<_>>();
+    function_name_entries.sort_by(|left, right| left.full_name.cmp(&right.full_name));
+
+    let mut lines = vec!["function_names:".to_owned()];
+    for entry in function_name_entries {
+        lines.push(format!(
+            "  \"{}\": {}, function #{}",
+            entry.full_name,
+            format_visibility(entry.visibility),
+            entry.internal_index
+        ));
+    }
+    groups.push(lines);
+
+    let mut data_name_entries = image_common_entry
+        .data_data_entries
+        .iter()
+        .collect::<Vec<_>>();
+    data_name_entries.sort_by(|left, right| left.full_name.cmp(&right.full_name));
+
+    let mut lines = vec!["data_names:".to_owned()];
+    for entry in data_name_entries {
+        lines.push(format!(
+            "  \"{}\": {}, {} #{}",
+            entry.full_name,
+            format_visibility(entry.visibility),
+            format_data_section_type(entry.section_type),
+            entry.internal_index_in_section
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["relocates:".to_owned()];
+    for (idx, entry) in image_common_entry.relocate_list_entries.iter().enumerate() {
+        lines.push(format!("  function #{}:", idx));
+        for relocate_entry in &entry.relocate_entries {
+            lines.push(format!(
+                "    0x{:04x}: {}",
+                relocate_entry.offset_in_function,
+                format_relocate_type(relocate_entry.relocate_type)
+            ));
+        }
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["external_libraries:".to_owned()];
+    for (idx, entry) in image_common_entry
+        .external_library_entries
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!(
+            "  #{}: \"{}\" {}",
+            idx,
+            entry.name,
+            ason::to_string(entry.value.as_ref()).unwrap()
+        ));
+    }
+    groups.push(lines);
+
+    let mut lines = vec!["external_functions:".to_owned()];
+    for (idx, entry) in image_common_entry
+        .external_function_entries
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!(
+            "  #{}: \"{}\", library #{}, type #{}",
+            idx, entry.name, entry.external_library_index, entry.type_index
+        ));
+    }
+    groups.push(lines);
+
+    groups
+        .iter()
+        .map(|lines| lines.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn format_bytes(data: &[u8]) -> Vec<String> {
+    if data.is_empty() {
+        return vec![];
+    }
+
+    format_bytecode_as_binary(data)
+        .lines()
+        .map(|line| format!("    {}", line))
+        .collect()
+}
+
+pub fn format_operand_data_types(operand_data_types: &[OperandDataType]) -> String {
+    let names = operand_data_types
+        .iter()
+        .map(|operand_data_type| match operand_data_type {
+            OperandDataType::I32 => "i32",
+            OperandDataType::I64 => "i64",
+            OperandDataType::F32 => "f32",
+            OperandDataType::F64 => "f64",
+        })
+        .collect::<Vec<_>>();
+
+    format!("({})", names.join(", "))
+}
+
+pub fn format_memory_data_type(memory_data_type: MemoryDataType) -> &'static str {
+    match memory_data_type {
+        MemoryDataType::I32 => "i32",
+        MemoryDataType::I64 => "i64",
+        MemoryDataType::F32 => "f32",
+        MemoryDataType::F64 => "f64",
+        MemoryDataType::Bytes => "bytes",
+    }
+}
+
+pub fn format_data_section_type(data_section_type: DataSectionType) -> &'static str {
+    match data_section_type {
+        DataSectionType::ReadOnly => "read_only",
+        DataSectionType::ReadWrite => "read_write",
+        DataSectionType::Uninit => "uninit",
+    }
+}
+
+fn format_image_type(image_type: ImageType) -> &'static str {
+    match image_type {
+        ImageType::Application => "application",
+        ImageType::SharedModule => "shared_module",
+        ImageType::ObjectFile => "object_file",
+        ImageType::DebugInfo => "debug_info",
+    }
+}
+
+pub fn format_visibility(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Private => "private",
+        Visibility::Public => "public",
+        Visibility::Package => "package",
+    }
+}
+
+fn format_relocate_type(relocate_type: RelocateType) -> &'static str {
+    match relocate_type {
+        RelocateType::TypeIndex => "type_index",
+        RelocateType::LocalVariableListIndex => "local_variable_list_index",
+        RelocateType::FunctionPublicIndex => "function_public_index",
+        RelocateType::ExternalFunctionIndex => "external_function_index",
+        RelocateType::DataPublicIndex => "data_public_index",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{DataSectionType, EffectiveVersion, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            DataNameEntry, FunctionEntry, FunctionNameEntry, ImageCommonEntry,
+            LocalVariableListEntry, ReadOnlyDataEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+            UninitDataEntry,
+        },
+        entry_dump::stable_dump,
+        module_image::{ImageType, Visibility},
+    };
+
+    #[test]
+    fn test_stable_dump() {
+        let image_common_entry = ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 2, 3),
+            image_type: ImageType::ObjectFile,
+            type_entries: vec![TypeEntry::new(
+                vec![OperandDataType::I32, OperandDataType::I32],
+                vec![OperandDataType::I32],
+            )],
+            local_variable_list_entries: vec![LocalVariableListEntry::new(vec![
+                OperandDataType::I32,
+                OperandDataType::I32,
+                OperandDataType::I64,
+            ])],
+            function_entries: vec![FunctionEntry::new(0, 0, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9])],
+            read_only_data_entries: vec![ReadOnlyDataEntry::from_i32(11)],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![UninitDataEntry::from_i64()],
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            function_name_entries: vec![
+                FunctionNameEntry::new("foo::sub".to_owned(), Visibility::Private, 0),
+                FunctionNameEntry::new("foo::add".to_owned(), Visibility::Public, 1),
+            ],
+            data_data_entries: vec![DataNameEntry::new(
+                "foo::count".to_owned(),
+                Visibility::Private,
+                DataSectionType::Uninit,
+                0,
+            )],
+            relocate_list_entries: vec![RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0),
+            ])],
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+            initializer_entries: vec![],
+        };
+
+        assert_eq!(
+            stable_dump(&image_common_entry),
+            "\
+module: \"foo\"
+version: 1.2.3
+image_type: object_file
+
+types:
+  #0: (i32, i32) -> (i32)
+
+local_variable_lists:
+  #0: (i32, i32, i64)
+
+functions:
+  #0: type #0, local_variable_list #0
+    0x0000  00 01 02 03  04 05 06 07
+    0x0008  08 09
+
+read_only_data:
+  #0: i32, length 4, align 4
+    0x0000  0b 00 00 00
+
+read_write_data:
+
+uninit_data:
+  #0: i64, length 8, align 8
+
+import_modules:
+
+import_functions:
+
+import_data:
+
+function_names:
+  \"foo::add\": public, function #1
+  \"foo::sub\": private, function #0
+
+data_names:
+  \"foo::count\": private, uninit #0
+
+relocates:
+  function #0:
+    0x0004: function_public_index
+
+external_libraries:
+
+external_functions:"
+        );
+    }
+}
@@ -4,167 +4,324 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
+use std::{borrow::Cow, time::Instant};
+
 use anc_isa::EffectiveVersion;
 
 use crate::{
     entry::{ImageCommonEntry, ImageLinkingEntry},
-    module_image::ModuleImage,
+    image_compression::{decompress_image, is_compressed_image, LzCodec},
+    io_observer::ImageIoObserver,
+    module_image::{ModuleImage, ModuleSectionId},
     ImageError,
 };
 
 // Reads an object file and converts its binary content into an ImageCommonEntry.
 pub fn read_object_file(object_binary: &[u8]) -> Result<ImageCommonEntry, ImageError> {
-    let module_image = ModuleImage::read(object_binary)?;
-
-    // Extract and convert various sections of the module image into entries.
-    let type_entries = module_image.get_type_section().convert_to_entries();
-    let local_variable_list_entries = module_image
-        .get_local_variable_section()
-        .convert_to_entries();
-    let function_entries = module_image.get_function_section().convert_to_entries();
-    let read_only_data_entries = module_image
-        .get_optional_read_only_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let read_write_data_entries = module_image
-        .get_optional_read_write_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let uninit_data_entries = module_image
-        .get_optional_uninit_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let external_library_entries = module_image
-        .get_optional_external_library_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let external_function_entries = module_image
-        .get_optional_external_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_module_entries = module_image
-        .get_optional_import_module_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_function_entries = module_image
-        .get_optional_import_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_data_entries = module_image
-        .get_optional_import_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let export_function_entries = module_image
-        .get_optional_export_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let export_data_entries = module_image
-        .get_optional_export_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let relocate_list_entries = module_image
-        .get_optional_relocate_section()
-        .unwrap_or_default()
-        .convert_to_entries();
+    read_object_file_with_observer(object_binary, &mut ())
+}
 
-    // Retrieve the property section for metadata.
-    let property_section = module_image.get_property_section();
+/// The same as `read_object_file`, but notifies the observer after each section is read.
+pub fn read_object_file_with_observer(
+    object_binary: &[u8],
+    observer: &mut dyn ImageIoObserver,
+) -> Result<ImageCommonEntry, ImageError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("read_object_file", length = object_binary.len()).entered();
 
-    // Construct the ImageCommonEntry with all extracted and converted entries.
-    let image_common_entry = ImageCommonEntry {
-        name: property_section.get_module_name().to_owned(),
-        version: EffectiveVersion::new(
-            property_section.version_major,
-            property_section.version_minor,
-            property_section.version_patch,
-        ),
-        image_type: module_image.image_type,
-        //
-        type_entries,
-        local_variable_list_entries,
-        function_entries,
-        //
-        read_only_data_entries,
-        read_write_data_entries,
-        uninit_data_entries,
-        //
-        import_module_entries,
-        import_function_entries,
-        import_data_entries,
-        //
-        function_name_entries: export_function_entries,
-        data_data_entries: export_data_entries,
-        relocate_list_entries,
-        //
-        external_library_entries,
-        external_function_entries,
-    };
+    let object_binary = decompress_if_compressed(object_binary)?;
+    let module_image = ModuleImage::read(&object_binary)?;
+    read_image_common_entry(&module_image, observer)
+}
 
-    Ok(image_common_entry)
+// The compressed images (e.g., written with `WriteProfile::MinSize`) are
+// decompressed with the built-in codec `LzCodec` before reading.
+fn decompress_if_compressed(image_binary: &[u8]) -> Result<Cow<'_, [u8]>, ImageError> {
+    if is_compressed_image(image_binary) {
+        Ok(Cow::Owned(decompress_image(image_binary, &LzCodec)?))
+    } else {
+        Ok(Cow::Borrowed(image_binary))
+    }
 }
 
 // Reads an image file and converts its binary content into both ImageCommonEntry and ImageIndexEntry.
 pub fn read_image_file(
     image_binary: &[u8],
 ) -> Result<(ImageCommonEntry, ImageLinkingEntry), ImageError> {
-    let module_image = ModuleImage::read(image_binary)?;
+    read_image_file_with_observer(image_binary, &mut ())
+}
+
+/// The same as `read_image_file`, but notifies the observer after each section is read.
+pub fn read_image_file_with_observer(
+    image_binary: &[u8],
+    observer: &mut dyn ImageIoObserver,
+) -> Result<(ImageCommonEntry, ImageLinkingEntry), ImageError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("read_image_file", length = image_binary.len()).entered();
+
+    let image_binary = decompress_if_compressed(image_binary)?;
+    let module_image = ModuleImage::read(&image_binary)?;
+
+    let image_common_entry = read_image_common_entry(&module_image, observer)?;
+
+    // Extract and convert additional sections specific to the image index.
+    let function_index_list_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::FunctionIndex,
+        observer,
+        || {
+            module_image
+                .try_get_function_index_section()
+                .map(|section| section.convert_to_entries())
+        },
+    )?;
+    let data_index_list_entries =
+        observe_section_read(&module_image, ModuleSectionId::DataIndex, observer, || {
+            module_image
+                .get_optional_data_index_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        });
+    let initialization_dependency_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::InitializationDependency,
+        observer,
+        || {
+            module_image
+                .get_optional_initialization_dependency_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let external_function_index_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::ExternalFunctionIndex,
+        observer,
+        || {
+            module_image
+                .get_optional_external_function_index_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let unified_external_library_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::UnifiedExternalLibrary,
+        observer,
+        || {
+            module_image
+                .get_optional_unified_external_library_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let unified_external_type_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::UnifiedExternalType,
+        observer,
+        || {
+            module_image
+                .get_optional_unified_external_type_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let unified_external_function_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::UnifiedExternalFunction,
+        observer,
+        || {
+            module_image
+                .get_optional_unified_external_function_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let lazy_binding_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::LazyBinding,
+        observer,
+        || {
+            module_image
+                .get_optional_lazy_binding_section()
+                .unwrap_or_default()
+                .convert_to_entries()
+        },
+    );
+    let dynamic_link_module_entries = observe_section_read(
+        &module_image,
+        ModuleSectionId::LinkingModule,
+        observer,
+        || {
+            module_image
+                .try_get_dynamic_link_module_list_section()
+                .map(|section| section.convert_to_entries())
+        },
+    )?;
+    let entry_point_entries =
+        observe_section_read(&module_image, ModuleSectionId::EntryPoint, observer, || {
+            module_image
+                .try_get_entry_point_section()
+                .map(|section| section.convert_to_entries())
+        })?;
+
+    // Construct the ImageIndexEntry with all extracted and converted entries.
+    let image_index_entry = ImageLinkingEntry {
+        function_index_list_entries,
+        data_index_list_entries,
+        initialization_dependency_entries,
+        external_function_index_entries,
+        unified_external_library_entries,
+        unified_external_type_entries,
+        unified_external_function_entries,
+        lazy_binding_entries,
+        linking_module_entries: dynamic_link_module_entries,
+        entry_point_entries,
+    };
+
+    Ok((image_common_entry, image_index_entry))
+}
 
+// Reads the common sections, they are shared by object files and image files.
+//
+// All sections are read by the `try_get_*` (and `try_get_optional_*`) functions,
+// so a missing required section or a corrupted section results in an error
+// instead of a panic.
+fn read_image_common_entry(
+    module_image: &ModuleImage,
+    observer: &mut dyn ImageIoObserver,
+) -> Result<ImageCommonEntry, ImageError> {
     // Extract and convert various sections of the module image into entries.
-    let type_entries = module_image.get_type_section().convert_to_entries();
-    let local_variable_list_entries = module_image
-        .get_local_variable_section()
-        .convert_to_entries();
-    let function_entries = module_image.get_function_section().convert_to_entries();
-    let read_only_data_entries = module_image
-        .get_optional_read_only_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let read_write_data_entries = module_image
-        .get_optional_read_write_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let uninit_data_entries = module_image
-        .get_optional_uninit_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let external_library_entries = module_image
-        .get_optional_external_library_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let external_function_entries = module_image
-        .get_optional_external_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_module_entries = module_image
-        .get_optional_import_module_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_function_entries = module_image
-        .get_optional_import_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let import_data_entries = module_image
-        .get_optional_import_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let export_function_entries = module_image
-        .get_optional_export_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let export_data_entries = module_image
-        .get_optional_export_data_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let relocate_list_entries = module_image
-        .get_optional_relocate_section()
-        .unwrap_or_default()
-        .convert_to_entries();
+    let type_entries = observe_section_read(module_image, ModuleSectionId::Type, observer, || {
+        module_image
+            .try_get_type_section()
+            .map(|section| section.convert_to_entries())
+    })?;
+    let local_variable_list_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::LocalVariable,
+        observer,
+        || {
+            module_image
+                .try_get_local_variable_section()
+                .map(|section| section.convert_to_entries())
+        },
+    )?;
+    let function_entries =
+        observe_section_read(module_image, ModuleSectionId::Function, observer, || {
+            module_image
+                .try_get_function_section()
+                .map(|section| section.convert_to_entries())
+        })?;
+    let read_only_data_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ReadOnlyData,
+        observer,
+        || {
+            module_image
+                .try_get_optional_read_only_data_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let read_write_data_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ReadWriteData,
+        observer,
+        || {
+            module_image
+                .try_get_optional_read_write_data_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let uninit_data_entries =
+        observe_section_read(module_image, ModuleSectionId::UninitData, observer, || {
+            module_image
+                .try_get_optional_uninit_data_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        })?;
+    let external_library_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ExternalLibrary,
+        observer,
+        || {
+            module_image
+                .try_get_optional_external_library_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let external_function_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ExternalFunction,
+        observer,
+        || {
+            module_image
+                .try_get_optional_external_function_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let import_module_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ImportModule,
+        observer,
+        || {
+            module_image
+                .try_get_optional_import_module_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let import_function_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::ImportFunction,
+        observer,
+        || {
+            module_image
+                .try_get_optional_import_function_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let import_data_entries =
+        observe_section_read(module_image, ModuleSectionId::ImportData, observer, || {
+            module_image
+                .try_get_optional_import_data_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        })?;
+    let export_function_entries = observe_section_read(
+        module_image,
+        ModuleSectionId::FunctionName,
+        observer,
+        || {
+            module_image
+                .try_get_optional_export_function_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        },
+    )?;
+    let export_data_entries =
+        observe_section_read(module_image, ModuleSectionId::DataName, observer, || {
+            module_image
+                .try_get_optional_export_data_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        })?;
+    let relocate_list_entries =
+        observe_section_read(module_image, ModuleSectionId::Relocate, observer, || {
+            module_image
+                .try_get_optional_relocate_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        })?;
+    let initializer_entries =
+        observe_section_read(module_image, ModuleSectionId::Initializer, observer, || {
+            module_image
+                .try_get_optional_initializer_section()
+                .map(|section| section.unwrap_or_default().convert_to_entries())
+        })?;
 
     // Retrieve the property section for metadata.
-    let property_section = module_image.get_property_section();
+    let property_section =
+        observe_section_read(module_image, ModuleSectionId::Property, observer, || {
+            module_image.try_get_property_section()
+        })?;
 
     // Construct the ImageCommonEntry with all extracted and converted entries.
-    let image_common_entry = ImageCommonEntry {
+    Ok(ImageCommonEntry {
         name: property_section.get_module_name().to_owned(),
         version: EffectiveVersion::new(
             property_section.version_major,
@@ -191,48 +348,216 @@ pub fn read_image_file(
         //
         external_library_entries,
         external_function_entries,
-    };
+        //
+        initializer_entries,
+    })
+}
 
-    // Extract and convert additional sections specific to the image index.
-    let function_index_list_entries = module_image
-        .get_function_index_section()
-        .convert_to_entries();
-    let data_index_list_entries = module_image
-        .get_optional_data_index_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let external_function_index_entries = module_image
-        .get_optional_external_function_index_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let unified_external_library_entries = module_image
-        .get_optional_external_library_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let unified_external_type_entries = module_image
-        .get_optional_unified_external_type_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let unified_external_function_entries = module_image
-        .get_optional_external_function_section()
-        .unwrap_or_default()
-        .convert_to_entries();
-    let dynamic_link_module_entries = module_image
-        .get_dynamic_link_module_list_section()
-        .convert_to_entries();
-    let entry_point_entries = module_image.get_entry_point_section().convert_to_entries();
+// Runs the `read` function and notifies the observer if the section exists.
+fn observe_section_read<T>(
+    module_image: &ModuleImage,
+    section_id: ModuleSectionId,
+    observer: &mut dyn ImageIoObserver,
+    read: impl FnOnce() -> T,
+) -> T {
+    let start = Instant::now();
+    let result = read();
+    let elapsed = start.elapsed();
 
-    // Construct the ImageIndexEntry with all extracted and converted entries.
-    let image_index_entry = ImageLinkingEntry {
-        function_index_list_entries,
-        data_index_list_entries,
-        external_function_index_entries,
-        unified_external_library_entries,
-        unified_external_type_entries,
-        unified_external_function_entries,
-        linking_module_entries: dynamic_link_module_entries,
-        entry_point_entries,
+    if let Some(item) = module_image.items.iter().find(|item| item.id == section_id) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            section = ?section_id,
+            length = item.length,
+            elapsed_us = elapsed.as_micros() as u64,
+            "section read"
+        );
+
+        observer.on_section_read(section_id, item.length as usize, elapsed);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, mem::offset_of};
+
+    use anc_isa::{
+        opcode::Opcode, DataSectionType, DependencyCondition, DependencyLocal,
+        ExternalLibraryDependency, MemoryDataType, ModuleDependency, OperandDataType,
     };
+    use pretty_assertions::assert_eq;
 
-    Ok((image_common_entry, image_index_entry))
+    use crate::{
+        builder::ModuleImageBuilder,
+        bytecode_writer::BytecodeWriterHelper,
+        common_sections::read_only_data_section,
+        entry::{
+            ExternalFunctionEntry, ExternalLibraryEntry, ImportModuleEntry, ReadOnlyDataEntry,
+            ReadWriteDataEntry, TypeEntry, UninitDataEntry,
+        },
+        entry_reader::{read_image_file, read_image_file_with_observer, read_object_file},
+        entry_writer::write_image_file,
+        io_observer::ImageIoRecorder,
+        module_image::{filter_sections, ModuleImage, ModuleSectionId, Visibility},
+        utils::helper_build_application_fixture,
+        ImageErrorType,
+    };
+
+    #[test]
+    fn test_read_object_file() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode_i32(Opcode::extcall, 0)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // entries of all kinds, the relocations are derived from the code
+        let builder = ModuleImageBuilder::new("foo")
+            .add_import_module(ImportModuleEntry::new(
+                "bar".to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: "bar".to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            ))
+            .add_import_function("bar", "bar::get_number", TypeEntry::new(vec![], vec![]))
+            .add_import_data(
+                "bar",
+                "bar::count",
+                DataSectionType::ReadWrite,
+                MemoryDataType::I32,
+            )
+            .add_function(
+                "main",
+                Visibility::Public,
+                TypeEntry::new(vec![OperandDataType::I32], vec![]),
+                &[OperandDataType::I64],
+                code,
+            )
+            .add_read_only_data(
+                "message",
+                Visibility::Public,
+                ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+            )
+            .add_read_write_data(
+                "counter",
+                Visibility::Private,
+                ReadWriteDataEntry::from_i32(11),
+            )
+            .add_uninit_data("buffer", Visibility::Private, UninitDataEntry::from_i64())
+            .add_external_function(
+                ExternalLibraryEntry::new(
+                    "libc".to_owned(),
+                    Box::new(ExternalLibraryDependency::Local(Box::new(
+                        DependencyLocal {
+                            path: "libc.so.6".to_owned(),
+                            condition: DependencyCondition::True,
+                            parameters: HashMap::default(),
+                        },
+                    ))),
+                ),
+                "getpid",
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+            );
+
+        let image_binary = builder.build_object_file().unwrap();
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+        assert_eq!(&image_common_entry, builder.get_image_common_entry());
+
+        // not an image
+        let error = read_object_file(&[0u8; 16]).unwrap_err();
+        assert!(matches!(error.error_type, ImageErrorType::InvalidImage));
+    }
+
+    #[test]
+    fn test_read_object_file_without_type_section() {
+        let image_binary = ModuleImageBuilder::new("foo").build_object_file().unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        let image_binary_without_type_section = filter_sections(&module_image, |section_id| {
+            section_id != ModuleSectionId::Type
+        })
+        .unwrap();
+
+        let error = read_object_file(&image_binary_without_type_section).unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::MissingSection(ModuleSectionId::Type)
+        ));
+    }
+
+    #[test]
+    fn test_read_object_file_with_corrupted_optional_section() {
+        let image_binary = ModuleImageBuilder::new("foo")
+            .add_read_only_data(
+                "message",
+                Visibility::Public,
+                ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+            )
+            .build_object_file()
+            .unwrap();
+
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        let section = module_image
+            .try_get_optional_read_only_data_section()
+            .unwrap()
+            .unwrap();
+        let data_length_position = section.items.as_ptr() as usize - image_binary.as_ptr() as usize
+            + offset_of!(read_only_data_section::DataItem, data_length);
+
+        // the data length of the first item exceeds the data area
+        let mut image_binary_corrupted = image_binary.clone();
+        image_binary_corrupted[data_length_position..(data_length_position + 4)]
+            .copy_from_slice(&0x1000_u32.to_le_bytes());
+
+        let error = read_object_file(&image_binary_corrupted).unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::CorruptedSection(ModuleSectionId::ReadOnlyData)
+        ));
+    }
+
+    #[test]
+    fn test_read_image_file_unified_external_sections() {
+        let fixture = helper_build_application_fixture(1);
+        let (image_common_entry, mut image_linking_entry) =
+            read_image_file(&fixture.application_binary).unwrap();
+
+        // the unified sections are different from the external library
+        // and external function sections of the main module.
+        assert!(image_common_entry.external_library_entries.is_empty());
+        image_linking_entry.unified_external_library_entries = vec![ExternalLibraryEntry::new(
+            "libc".to_owned(),
+            Box::new(ExternalLibraryDependency::Runtime),
+        )];
+        image_linking_entry.unified_external_function_entries =
+            vec![ExternalFunctionEntry::new("getuid".to_owned(), 0, 0)];
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_image_file(&image_common_entry, &image_linking_entry, &mut image_binary).unwrap();
+
+        let mut recorder = ImageIoRecorder::new();
+        let (image_common_entry_restore, image_linking_entry_restore) =
+            read_image_file_with_observer(&image_binary, &mut recorder).unwrap();
+        assert_eq!(image_common_entry_restore, image_common_entry);
+        assert_eq!(image_linking_entry_restore, image_linking_entry);
+
+        // each section is read once
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        for item in module_image.items {
+            let count = recorder
+                .records
+                .iter()
+                .filter(|record| record.section_id == item.id)
+                .count();
+            assert!(
+                count <= 1,
+                "The section {:?} is read {} times.",
+                item.id,
+                count
+            );
+        }
+    }
 }
@@ -0,0 +1,482 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Validates the names of an `ImageCommonEntry` before it is written,
+// so that the bugs of the code generators are caught at build time
+// rather than by the linker or the runtime.
+//
+// The following rules are checked:
+//
+// - in range: the internal index of each function name refers to an existing
+//   function, and the internal index of each data name refers to an existing
+//   item of its data section.
+// - dense: each function (and each data item) has exactly one name, i.e.,
+//   there are no duplicate indices and no gaps.
+// - unique: the full names of the functions are unique, and so are the full
+//   names of the data. Note that a function and a data item can share the
+//   same full name, since they are imported separately.
+// - unique ordinals: the ordinals of the functions are unique, see the
+//   "Ordinals" section of `function_name_section`.
+//
+// Note: the names are complete in the entries, the private names are
+// stripped (if required) when the image is written, see `NameRetention`.
+
+use std::{collections::HashMap, fmt::Display, num::NonZeroU16};
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    entry::ImageCommonEntry,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EntryValidationError {
+    FunctionNameIndexOutOfRange {
+        full_name: String,
+        internal_index: usize,
+        function_count: usize,
+    },
+
+    // More than one name refers to the same function.
+    DuplicateFunctionNameIndex {
+        full_name: String,
+        other_full_name: String,
+        internal_index: usize,
+    },
+
+    MissingFunctionName {
+        internal_index: usize,
+    },
+
+    DuplicateFunctionFullName {
+        full_name: String,
+    },
+
+    // More than one function is assigned the same ordinal.
+    DuplicateFunctionOrdinal {
+        full_name: String,
+        other_full_name: String,
+        ordinal: NonZeroU16,
+    },
+
+    DataNameIndexOutOfRange {
+        full_name: String,
+        section_type: DataSectionType,
+        internal_index_in_section: usize,
+        data_count: usize,
+    },
+
+    // More than one name refers to the same data item.
+    DuplicateDataNameIndex {
+        full_name: String,
+        other_full_name: String,
+        section_type: DataSectionType,
+        internal_index_in_section: usize,
+    },
+
+    MissingDataName {
+        section_type: DataSectionType,
+        internal_index_in_section: usize,
+    },
+
+    DuplicateDataFullName {
+        full_name: String,
+    },
+}
+
+impl EntryValidationError {
+    /// Returns the identifier of the check, it is used as the code of the diagnostic.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EntryValidationError::FunctionNameIndexOutOfRange { .. } => {
+                "function_name_index_out_of_range"
+            }
+            EntryValidationError::DuplicateFunctionNameIndex { .. } => {
+                "duplicate_function_name_index"
+            }
+            EntryValidationError::MissingFunctionName { .. } => "missing_function_name",
+            EntryValidationError::DuplicateFunctionFullName { .. } => {
+                "duplicate_function_full_name"
+            }
+            EntryValidationError::DuplicateFunctionOrdinal { .. } => "duplicate_function_ordinal",
+            EntryValidationError::DataNameIndexOutOfRange { .. } => "data_name_index_out_of_range",
+            EntryValidationError::DuplicateDataNameIndex { .. } => "duplicate_data_name_index",
+            EntryValidationError::MissingDataName { .. } => "missing_data_name",
+            EntryValidationError::DuplicateDataFullName { .. } => "duplicate_data_full_name",
+        }
+    }
+
+    /// Converts the error into an error-level `Diagnostic`,
+    /// the span is not available since the image is not written yet.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(Severity::Error, self.code(), self.to_string())
+    }
+}
+
+impl Display for EntryValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryValidationError::FunctionNameIndexOutOfRange {
+                full_name,
+                internal_index,
+                function_count,
+            } => write!(
+                f,
+                "The internal index {} of function \"{}\" is out of range, the module has {} functions.",
+                internal_index, full_name, function_count
+            ),
+            EntryValidationError::DuplicateFunctionNameIndex {
+                full_name,
+                other_full_name,
+                internal_index,
+            } => write!(
+                f,
+                "The function names \"{}\" and \"{}\" refer to the same function {}.",
+                other_full_name, full_name, internal_index
+            ),
+            EntryValidationError::MissingFunctionName { internal_index } => {
+                write!(f, "The function {} has no name.", internal_index)
+            }
+            EntryValidationError::DuplicateFunctionFullName { full_name } => {
+                write!(f, "The function name \"{}\" is duplicated.", full_name)
+            }
+            EntryValidationError::DuplicateFunctionOrdinal {
+                full_name,
+                other_full_name,
+                ordinal,
+            } => write!(
+                f,
+                "The functions \"{}\" and \"{}\" have the same ordinal {}.",
+                other_full_name, full_name, ordinal
+            ),
+            EntryValidationError::DataNameIndexOutOfRange {
+                full_name,
+                section_type,
+                internal_index_in_section,
+                data_count,
+            } => write!(
+                f,
+                "The internal index {} of data \"{}\" is out of range, the {:?} data section has {} items.",
+                internal_index_in_section, full_name, section_type, data_count
+            ),
+            EntryValidationError::DuplicateDataNameIndex {
+                full_name,
+                other_full_name,
+                section_type,
+                internal_index_in_section,
+            } => write!(
+                f,
+                "The data names \"{}\" and \"{}\" refer to the same data {} of the {:?} data section.",
+                other_full_name, full_name, internal_index_in_section, section_type
+            ),
+            EntryValidationError::MissingDataName {
+                section_type,
+                internal_index_in_section,
+            } => write!(
+                f,
+                "The data {} of the {:?} data section has no name.",
+                internal_index_in_section, section_type
+            ),
+            EntryValidationError::DuplicateDataFullName { full_name } => {
+                write!(f, "The data name \"{}\" is duplicated.", full_name)
+            }
+        }
+    }
+}
+
+/// Checks the function names and the data names of the entry,
+/// an empty list means the entry passes the check.
+pub fn validate_entry_names(image_common_entry: &ImageCommonEntry) -> Vec<EntryValidationError> {
+    let mut errors = vec![];
+
+    // functions
+    let function_count = image_common_entry.function_entries.len();
+    let mut function_names: Vec<Option<&str>> = vec![None; function_count];
+    let mut function_full_names: HashMap<&str, usize> = HashMap::new();
+    let mut function_ordinals: HashMap<NonZeroU16, &str> = HashMap::new();
+
+    for entry in &image_common_entry.function_name_entries {
+        let full_name = entry.full_name.as_str();
+
+        *function_full_names.entry(full_name).or_default() += 1;
+        if function_full_names[full_name] == 2 {
+            errors.push(EntryValidationError::DuplicateFunctionFullName {
+                full_name: full_name.to_owned(),
+            });
+        }
+
+        if let Some(ordinal) = entry.ordinal {
+            if let Some(other_full_name) = function_ordinals.insert(ordinal, full_name) {
+                errors.push(EntryValidationError::DuplicateFunctionOrdinal {
+                    full_name: full_name.to_owned(),
+                    other_full_name: other_full_name.to_owned(),
+                    ordinal,
+                });
+            }
+        }
+
+        match function_names.get_mut(entry.internal_index) {
+            None => errors.push(EntryValidationError::FunctionNameIndexOutOfRange {
+                full_name: full_name.to_owned(),
+                internal_index: entry.internal_index,
+                function_count,
+            }),
+            Some(Some(other_full_name)) => {
+                errors.push(EntryValidationError::DuplicateFunctionNameIndex {
+                    full_name: full_name.to_owned(),
+                    other_full_name: other_full_name.to_string(),
+                    internal_index: entry.internal_index,
+                })
+            }
+            Some(name) => *name = Some(full_name),
+        }
+    }
+
+    for (internal_index, name) in function_names.iter().enumerate() {
+        if name.is_none() {
+            errors.push(EntryValidationError::MissingFunctionName { internal_index });
+        }
+    }
+
+    // data
+    let data_counts = [
+        (
+            DataSectionType::ReadOnly,
+            image_common_entry.read_only_data_entries.len(),
+        ),
+        (
+            DataSectionType::ReadWrite,
+            image_common_entry.read_write_data_entries.len(),
+        ),
+        (
+            DataSectionType::Uninit,
+            image_common_entry.uninit_data_entries.len(),
+        ),
+    ];
+
+    let mut data_names: Vec<Vec<Option<&str>>> = data_counts
+        .iter()
+        .map(|(_, data_count)| vec![None; *data_count])
+        .collect();
+    let mut data_full_names: HashMap<&str, usize> = HashMap::new();
+
+    for entry in &image_common_entry.data_data_entries {
+        let full_name = entry.full_name.as_str();
+
+        *data_full_names.entry(full_name).or_default() += 1;
+        if data_full_names[full_name] == 2 {
+            errors.push(EntryValidationError::DuplicateDataFullName {
+                full_name: full_name.to_owned(),
+            });
+        }
+
+        let section_index = data_counts
+            .iter()
+            .position(|(section_type, _)| *section_type == entry.section_type)
+            .unwrap();
+        let section_names = &mut data_names[section_index];
+        let data_count = section_names.len();
+        match section_names.get_mut(entry.internal_index_in_section) {
+            None => errors.push(EntryValidationError::DataNameIndexOutOfRange {
+                full_name: full_name.to_owned(),
+                section_type: entry.section_type,
+                internal_index_in_section: entry.internal_index_in_section,
+                data_count,
+            }),
+            Some(Some(other_full_name)) => {
+                errors.push(EntryValidationError::DuplicateDataNameIndex {
+                    full_name: full_name.to_owned(),
+                    other_full_name: other_full_name.to_string(),
+                    section_type: entry.section_type,
+                    internal_index_in_section: entry.internal_index_in_section,
+                })
+            }
+            Some(name) => *name = Some(full_name),
+        }
+    }
+
+    for ((section_type, _), section_names) in data_counts.iter().zip(&data_names) {
+        for (internal_index_in_section, name) in section_names.iter().enumerate() {
+            if name.is_none() {
+                errors.push(EntryValidationError::MissingDataName {
+                    section_type: *section_type,
+                    internal_index_in_section,
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use anc_isa::{DataSectionType, EffectiveVersion};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            DataNameEntry, FunctionEntry, FunctionNameEntry, ImageCommonEntry, ReadOnlyDataEntry,
+            UninitDataEntry,
+        },
+        entry_validator::{validate_entry_names, EntryValidationError},
+        module_image::{ImageType, Visibility},
+    };
+
+    fn build_entry(
+        function_name_entries: Vec<FunctionNameEntry>,
+        data_name_entries: Vec<DataNameEntry>,
+    ) -> ImageCommonEntry {
+        ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 0, 0),
+            image_type: ImageType::ObjectFile,
+            type_entries: vec![],
+            local_variable_list_entries: vec![],
+            function_entries: vec![
+                FunctionEntry::new(0, 0, vec![]),
+                FunctionEntry::new(0, 0, vec![]),
+            ],
+            read_only_data_entries: vec![ReadOnlyDataEntry::from_i32(11)],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![UninitDataEntry::from_i64()],
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            function_name_entries,
+            data_data_entries: data_name_entries,
+            relocate_list_entries: vec![],
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+            initializer_entries: vec![],
+        }
+    }
+
+    #[test]
+    fn test_validate_entry_names() {
+        let entry = build_entry(
+            vec![
+                FunctionNameEntry::new("foo::a".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::b".to_owned(), Visibility::Private, 1),
+            ],
+            vec![
+                DataNameEntry::new(
+                    "foo::a".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::ReadOnly,
+                    0,
+                ),
+                DataNameEntry::new(
+                    "foo::c".to_owned(),
+                    Visibility::Private,
+                    DataSectionType::Uninit,
+                    0,
+                ),
+            ],
+        );
+        assert!(validate_entry_names(&entry).is_empty());
+
+        let entry = build_entry(
+            vec![
+                FunctionNameEntry::new("foo::a".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::b".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::a".to_owned(), Visibility::Public, 2),
+            ],
+            vec![DataNameEntry::new(
+                "foo::d".to_owned(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                0,
+            )],
+        );
+
+        let errors = validate_entry_names(&entry);
+        assert_eq!(
+            errors,
+            vec![
+                EntryValidationError::DuplicateFunctionNameIndex {
+                    full_name: "foo::b".to_owned(),
+                    other_full_name: "foo::a".to_owned(),
+                    internal_index: 0
+                },
+                EntryValidationError::DuplicateFunctionFullName {
+                    full_name: "foo::a".to_owned()
+                },
+                EntryValidationError::FunctionNameIndexOutOfRange {
+                    full_name: "foo::a".to_owned(),
+                    internal_index: 2,
+                    function_count: 2
+                },
+                EntryValidationError::MissingFunctionName { internal_index: 1 },
+                EntryValidationError::DataNameIndexOutOfRange {
+                    full_name: "foo::d".to_owned(),
+                    section_type: DataSectionType::ReadWrite,
+                    internal_index_in_section: 0,
+                    data_count: 0
+                },
+                EntryValidationError::MissingDataName {
+                    section_type: DataSectionType::ReadOnly,
+                    internal_index_in_section: 0
+                },
+                EntryValidationError::MissingDataName {
+                    section_type: DataSectionType::Uninit,
+                    internal_index_in_section: 0
+                },
+            ]
+        );
+
+        assert_eq!(
+            errors[3].to_diagnostic().to_string(),
+            "error[missing_function_name]: The function 1 has no name."
+        );
+    }
+
+    #[test]
+    fn test_validate_function_ordinals() {
+        let mut entry = build_entry(
+            vec![
+                FunctionNameEntry::new("foo::a".to_owned(), Visibility::Public, 0)
+                    .with_ordinal(NonZeroU16::new(1).unwrap()),
+                FunctionNameEntry::new("foo::b".to_owned(), Visibility::Public, 1)
+                    .with_ordinal(NonZeroU16::new(2).unwrap()),
+            ],
+            vec![
+                DataNameEntry::new(
+                    "foo::c".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::ReadOnly,
+                    0,
+                ),
+                DataNameEntry::new(
+                    "foo::d".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::Uninit,
+                    0,
+                ),
+            ],
+        );
+        assert!(validate_entry_names(&entry).is_empty());
+
+        entry.function_name_entries[1].ordinal = NonZeroU16::new(1);
+
+        let errors = validate_entry_names(&entry);
+        assert_eq!(
+            errors,
+            vec![EntryValidationError::DuplicateFunctionOrdinal {
+                full_name: "foo::b".to_owned(),
+                other_full_name: "foo::a".to_owned(),
+                ordinal: NonZeroU16::new(1).unwrap(),
+            }]
+        );
+        assert_eq!(
+            errors[0].to_diagnostic().to_string(),
+            "error[duplicate_function_ordinal]: The functions \"foo::a\" and \"foo::b\" have the same ordinal 1."
+        );
+    }
+}
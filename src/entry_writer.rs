@@ -4,165 +4,274 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
-use std::io::Write;
+use std::{borrow::Cow, io::Write};
 
-use anc_isa::RUNTIME_EDITION;
+use anc_isa::{opcode::Opcode, EffectiveVersion, OperandDataType, RUNTIME_EDITION};
 
 use crate::{
+    bytecode_writer::BytecodeWriterHelper,
     common_sections::{
-        data_name_section::DataNameSection, external_function_section::ExternalFunctionSection,
-        external_library_section::ExternalLibrarySection,
-        function_name_section::FunctionNameSection, function_section::FunctionSection,
-        import_data_section::ImportDataSection, import_function_section::ImportFunctionSection,
-        import_module_section::ImportModuleSection, local_variable_section::LocalVariableSection,
-        property_section::PropertySection, read_only_data_section::ReadOnlyDataSection,
-        read_write_data_section::ReadWriteDataSection, relocate_section::RelocateSection,
-        type_section::TypeSection, uninit_data_section::UninitDataSection,
+        data_name_section::{DataNameItem, DataNameSection},
+        export_hash_section::{ExportHashItem, ExportHashSection},
+        external_function_section::{ExternalFunctionItem, ExternalFunctionSection},
+        external_library_section::{ExternalLibraryItem, ExternalLibrarySection},
+        function_hash_section::{FunctionHashItem, FunctionHashSection},
+        function_name_section::{FunctionNameItem, FunctionNameSection},
+        function_section::{FunctionItem, FunctionSection},
+        import_data_section::{ImportDataItem, ImportDataSection},
+        import_function_section::{ImportFunctionItem, ImportFunctionSection},
+        import_module_section::{ImportModuleItem, ImportModuleSection},
+        initializer_section::{InitializerItem, InitializerSection},
+        local_variable_section::{LocalVariableList, LocalVariableSection},
+        property_section::PropertySection,
+        read_only_data_section::{self, ReadOnlyDataSection},
+        read_write_data_section::{self, ReadWriteDataSection},
+        relocate_section::{RelocateList, RelocateSection},
+        type_section::{TypeItem, TypeSection},
+        uninit_data_section::{self, UninitDataSection},
     },
-    entry::{ImageCommonEntry, ImageLinkingEntry},
+    entry::{
+        FunctionEntry, FunctionNameEntry, ImageCommonEntry, ImageLinkingEntry,
+        LocalVariableListEntry, TypeEntry,
+    },
+    export_surface::{collect_export_signatures, convert_to_export_hash_entries},
+    function_hash::compute_function_hashes,
+    image_compression::{compress_sections, CompressionError, LzCodec},
+    image_pipeline::ImageSections,
+    io_observer::ImageIoObserver,
     linking_sections::{
         data_index_section::DataIndexSection, entry_point_section::EntryPointSection,
         external_function_index_section::ExternalFunctionIndexSection,
         function_index_section::FunctionIndexSection,
-        linking_module_section::LinkingModuleSection,
+        initialization_dependency_section::InitializationDependencySection,
+        lazy_binding_section::LazyBindingSection, linking_module_section::LinkingModuleSection,
         unified_external_function_section::UnifiedExternalFunctionSection,
         unified_external_library_section::UnifiedExternalLibrarySection,
         unified_external_type_section::UnifiedExternalTypeSection,
     },
-    module_image::{ImageType, ModuleImage, SectionEntry},
+    module_image::{
+        ImageType, ModuleImage, ModuleSectionId, SectionEntry, Visibility,
+        BASE_SECTION_HEADER_LENGTH,
+    },
+    ImageError, ImageErrorType,
 };
 
-// Writes an object file based on the provided ImageCommonEntry.
-// If `generate_shared_module` is true, the output will be a shared module; otherwise, it will be an object file.
-pub fn write_object_file(
-    image_common_entry: &ImageCommonEntry,
-    generate_shared_module: bool,
-    writer: &mut dyn Write,
-) -> std::io::Result<()> {
-    // Create the property section with metadata about the image.
-    let property_section = PropertySection::new(
-        &image_common_entry.name,
-        *RUNTIME_EDITION,
-        image_common_entry.version.patch,
-        image_common_entry.version.minor,
-        image_common_entry.version.major,
-        // image_common_entry.import_data_entries.len() as u32,
-        // image_common_entry.import_function_entries.len() as u32,
-    );
+// Determines whether the optional sections without items are written.
+//
+// An empty section only contains the section header (8 bytes), but it
+// takes a record in the section table, omitting them makes the image
+// smaller and cleaner. The readers treat a missing optional section as
+// an empty one, so both forms are equivalent.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum OptionalSectionPolicy {
+    // Always writes all optional sections, even if they are empty.
+    #[default]
+    EmitAll,
 
-    // Convert and prepare all sections from the ImageCommonEntry.
-    // Type section
-    let (type_items, types_data) =
-        TypeSection::convert_from_entries(&image_common_entry.type_entries);
-    let type_section = TypeSection {
-        items: &type_items,
-        types_data: &types_data,
-    };
+    // Skips the optional sections whose item count is zero.
+    OmitEmpty,
+}
 
-    // Local variable section
-    let (local_lists, local_list_data) =
-        LocalVariableSection::convert_from_entries(&image_common_entry.local_variable_list_entries);
-    let local_variable_section = LocalVariableSection {
-        lists: &local_lists,
-        list_data: &local_list_data,
-    };
+// Determines which entries of the name sections (i.e., the "function name"
+// and "data name" sections) are written.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum NameRetention {
+    // Writes all names.
+    #[default]
+    All,
 
-    // Function section
-    let (function_items, function_codes_data) =
-        FunctionSection::convert_from_entries(&image_common_entry.function_entries);
-    let function_section = FunctionSection {
-        items: &function_items,
-        codes_data: &function_codes_data,
-    };
+    // Writes only the names of the public (and package-private) functions
+    // and data, which are required for importing by other modules.
+    PublicOnly,
 
-    // Read-only data section
-    let (read_only_data_items, read_only_data) =
-        ReadOnlyDataSection::convert_from_entries(&image_common_entry.read_only_data_entries);
-    let read_only_data_section = ReadOnlyDataSection {
-        items: &read_only_data_items,
-        datas_data: &read_only_data,
-    };
+    // Omits the name sections.
+    //
+    // Note: the functions and data of the image can no longer be imported
+    // by name, it is suitable for the applications only.
+    None,
+}
 
-    // Read-write data section
-    let (read_write_data_items, read_write_data) =
-        ReadWriteDataSection::convert_from_entries(&image_common_entry.read_write_data_entries);
-    let read_write_data_section = ReadWriteDataSection {
-        items: &read_write_data_items,
-        datas_data: &read_write_data,
-    };
+// Determines which public (and package-private) functions and data
+// remain exported, so that a module can expose only a curated API surface.
+//
+// A pattern is either a full name (e.g. "foo::add"), or a namespace path
+// followed by "*" (e.g. "foo::math::*") which matches all items under it.
+//
+// The rejected items are demoted to private, i.e., they are still
+// accessible within the module, but can not be imported by other modules.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum ExportFilter {
+    // Keeps all exports.
+    #[default]
+    None,
 
-    // Uninitialized data section
-    let uninit_data_items =
-        UninitDataSection::convert_from_entries(&image_common_entry.uninit_data_entries);
-    let uninit_data_section = UninitDataSection {
-        items: &uninit_data_items,
-    };
+    // Keeps only the exports which match one of the patterns.
+    AllowList(Vec<String>),
 
-    // External library section
-    let (external_library_items, external_library_names_data) =
-        ExternalLibrarySection::convert_from_entries(&image_common_entry.external_library_entries);
-    let external_library_section = ExternalLibrarySection {
-        items: &external_library_items,
-        items_data: &external_library_names_data,
-    };
+    // Demotes the exports which match one of the patterns.
+    DenyList(Vec<String>),
+}
 
-    // External function section
-    let (external_function_items, external_function_names_data) =
-        ExternalFunctionSection::convert_from_entries(
-            &image_common_entry.external_function_entries,
-        );
-    let external_function_section = ExternalFunctionSection {
-        items: &external_function_items,
-        names_data: &external_function_names_data,
-    };
+impl ExportFilter {
+    /// Returns `true` if the function or data with the specified full name remains exported.
+    pub fn is_allowed(&self, full_name: &str) -> bool {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => full_name.starts_with(prefix),
+                    None => pattern == full_name,
+                })
+        };
 
-    // Import module section
-    let (import_module_items, import_module_data) =
-        ImportModuleSection::convert_from_entries(&image_common_entry.import_module_entries);
-    let import_module_section = ImportModuleSection {
-        items: &import_module_items,
-        items_data: &import_module_data,
-    };
+        match self {
+            ExportFilter::None => true,
+            ExportFilter::AllowList(patterns) => matches(patterns),
+            ExportFilter::DenyList(patterns) => !matches(patterns),
+        }
+    }
+}
 
-    // Import function section
-    let (import_function_items, import_function_data) =
-        ImportFunctionSection::convert_from_entries(&image_common_entry.import_function_entries);
-    let import_function_section = ImportFunctionSection {
-        items: &import_function_items,
-        full_names_data: &import_function_data,
-    };
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct WriteOptions {
+    pub optional_section_policy: OptionalSectionPolicy,
 
-    // Import data section
-    let (import_data_items, import_data) =
-        ImportDataSection::convert_from_entries(&image_common_entry.import_data_entries);
-    let import_data_section = ImportDataSection {
-        items: &import_data_items,
-        full_names_data: &import_data,
-    };
+    // Appends a trailer (total image length and checksum) to the image,
+    // so that the truncated images are detected when reading.
+    pub append_trailer: bool,
 
-    // Export function section
-    let (export_function_items, export_function_names_data) =
-        FunctionNameSection::convert_from_entries(&image_common_entry.function_name_entries);
-    let export_function_section = FunctionNameSection {
-        items: &export_function_items,
-        full_names_data: &export_function_names_data,
-    };
+    pub name_retention: NameRetention,
 
-    // Export data section
-    let (export_data_items, export_data_names_data) =
-        DataNameSection::convert_from_entries(&image_common_entry.data_data_entries);
-    let export_data_section = DataNameSection {
-        items: &export_data_items,
-        full_names_data: &export_data_names_data,
-    };
+    // Omits the relocate section.
+    //
+    // Note: the image can no longer be linked or transformed
+    // (e.g., by `image_transform`), since the relocate lists are
+    // the only reliable way to find out the indices within the bytecode.
+    pub strip_relocations: bool,
 
-    // Relocate section
-    let (relocate_lists, relocate_lists_data) =
-        RelocateSection::convert_from_entries(&image_common_entry.relocate_list_entries);
-    let relocate_section = RelocateSection {
-        lists: &relocate_lists,
-        list_data: &relocate_lists_data,
-    };
+    // The minimum alignment of the data items (read-only, read-write and
+    // uninitialized), the alignment of the items which are less than this value
+    // are raised to it, e.g., to make all data items 8-byte aligned for SIMD
+    // or atomic accesses. The value `0` (default) means no change, the other
+    // values must be a power of two, otherwise `ImageErrorType::InvalidAlign`
+    // is returned.
+    pub min_data_align: u16,
+
+    // Writes the "function hash" section, i.e., the content hash of each function
+    // for the AOT/JIT code caches, see `function_hash`.
+    pub emit_function_hashes: bool,
+
+    pub export_filter: ExportFilter,
+
+    // Omits the names of the functions and data which are demoted by
+    // `export_filter`, instead of keeping them as private.
+    pub drop_filtered_names: bool,
+
+    // Compresses the sections (except the property section) with the built-in
+    // codec `LzCodec`. The compressed image is decompressed automatically by
+    // `entry_reader`, while `ModuleImage::read` requires the image to be
+    // decompressed by `image_compression::decompress_image` first.
+    //
+    // Note: the compressed image can not carry the trailer, so this option
+    // can not be combined with `append_trailer`.
+    pub compress_sections: bool,
+}
+
+// The named presets of `WriteOptions`, so the build systems
+// do not need to assemble the options by hand.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WriteProfile {
+    // Keeps all names and relocations, and all optional sections are written.
+    Debug,
+
+    // Keeps the public names (for importing) and relocations,
+    // omits the private names and the empty optional sections,
+    // and appends the trailer for detecting truncated images.
+    Release,
+
+    // Omits all names, relocations and empty optional sections,
+    // and compresses the sections.
+    MinSize,
+}
+
+impl WriteOptions {
+    pub fn from_profile(profile: WriteProfile) -> Self {
+        match profile {
+            WriteProfile::Debug => WriteOptions::default(),
+            WriteProfile::Release => WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                append_trailer: true,
+                name_retention: NameRetention::PublicOnly,
+                strip_relocations: false,
+                min_data_align: 0,
+                emit_function_hashes: false,
+                export_filter: ExportFilter::None,
+                drop_filtered_names: false,
+                compress_sections: false,
+            },
+            WriteProfile::MinSize => WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                append_trailer: false,
+                name_retention: NameRetention::None,
+                strip_relocations: true,
+                min_data_align: 0,
+                emit_function_hashes: false,
+                export_filter: ExportFilter::None,
+                drop_filtered_names: false,
+                compress_sections: true,
+            },
+        }
+    }
+}
+
+// Writes an object file based on the provided ImageCommonEntry.
+// If `generate_shared_module` is true, the output will be a shared module; otherwise, it will be an object file.
+pub fn write_object_file(
+    image_common_entry: &ImageCommonEntry,
+    generate_shared_module: bool,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    write_object_file_with_options(
+        image_common_entry,
+        generate_shared_module,
+        &WriteOptions::default(),
+        writer,
+    )
+}
+
+/// The same as `write_object_file`, but with the specified options.
+pub fn write_object_file_with_options(
+    image_common_entry: &ImageCommonEntry,
+    generate_shared_module: bool,
+    options: &WriteOptions,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    write_object_file_with_observer(
+        image_common_entry,
+        generate_shared_module,
+        options,
+        &mut (),
+        writer,
+    )
+}
+
+/// The same as `write_object_file_with_options`, but notifies the observer
+/// after each section is written.
+pub fn write_object_file_with_observer(
+    image_common_entry: &ImageCommonEntry,
+    generate_shared_module: bool,
+    options: &WriteOptions,
+    observer: &mut dyn ImageIoObserver,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::debug_span!("write_object_file", name = %image_common_entry.name).entered();
+
+    check_write_options(options)?;
+
+    let common_section_data = CommonSectionData::new(image_common_entry, options);
+    let common_sections = common_section_data.sections();
 
     // Determine the image type based on the `generate_shared_module` flag.
     let image_type = if generate_shared_module {
@@ -171,41 +280,21 @@ pub fn write_object_file(
         ImageType::ObjectFile
     };
 
-    // Collect all section entries into a vector.
-    let section_entries: Vec<&dyn SectionEntry> = vec![
-        &property_section,
-        //
-        &type_section,
-        &local_variable_section,
-        &function_section,
-        //
-        &read_only_data_section,
-        &read_write_data_section,
-        &uninit_data_section,
-        //
-        &import_module_section,
-        &import_function_section,
-        &import_data_section,
-        //
-        &export_function_section,
-        &export_data_section,
-        &relocate_section,
-        //
-        &external_library_section,
-        &external_function_section,
-    ];
+    let section_entries = apply_optional_section_policy(common_sections.entries(), options);
 
     // Build the object file binary from the section entries.
-    let (section_items, sections_data) =
-        ModuleImage::convert_from_section_entries(&section_entries);
-    let module_image = ModuleImage {
-        image_type,
-        items: &section_items,
-        sections_data: &sections_data,
-    };
+    let mut section_items = vec![];
+    let mut sections_data = vec![];
+    ModuleImage::convert_from_section_entries_into_with_observer(
+        &section_entries,
+        &mut section_items,
+        &mut sections_data,
+        observer,
+    )?;
+    let module_image = ModuleImage::new(image_type, &section_items, &sections_data);
 
     // Write the binary data to the provided writer.
-    module_image.write(writer)
+    write_module_image(&module_image, options, writer)
 }
 
 // Writes an image file based on the provided ImageCommonEntry and ImageIndexEntry.
@@ -214,131 +303,47 @@ pub fn write_image_file(
     image_common_entry: &ImageCommonEntry,
     image_index_entry: &ImageLinkingEntry,
     writer: &mut dyn Write,
-) -> std::io::Result<()> {
-    // Create the property section with metadata about the image.
-    let property_section = PropertySection::new(
-        &image_common_entry.name,
-        *RUNTIME_EDITION,
-        image_common_entry.version.patch,
-        image_common_entry.version.minor,
-        image_common_entry.version.major,
-        // image_common_entry.import_data_entries.len() as u32,
-        // image_common_entry.import_function_entries.len() as u32,
-    );
-
-    // Convert and prepare all sections from the ImageCommonEntry.
-    // Type section
-    let (type_items, types_data) =
-        TypeSection::convert_from_entries(&image_common_entry.type_entries);
-    let type_section = TypeSection {
-        items: &type_items,
-        types_data: &types_data,
-    };
-
-    // Local variable section
-    let (local_lists, local_list_data) =
-        LocalVariableSection::convert_from_entries(&image_common_entry.local_variable_list_entries);
-    let local_variable_section = LocalVariableSection {
-        lists: &local_lists,
-        list_data: &local_list_data,
-    };
-
-    // Function section
-    let (function_items, function_codes_data) =
-        FunctionSection::convert_from_entries(&image_common_entry.function_entries);
-    let function_section = FunctionSection {
-        items: &function_items,
-        codes_data: &function_codes_data,
-    };
-
-    // Read-only data section
-    let (read_only_data_items, read_only_data) =
-        ReadOnlyDataSection::convert_from_entries(&image_common_entry.read_only_data_entries);
-    let read_only_data_section = ReadOnlyDataSection {
-        items: &read_only_data_items,
-        datas_data: &read_only_data,
-    };
-
-    // Read-write data section
-    let (read_write_data_items, read_write_data) =
-        ReadWriteDataSection::convert_from_entries(&image_common_entry.read_write_data_entries);
-    let read_write_data_section = ReadWriteDataSection {
-        items: &read_write_data_items,
-        datas_data: &read_write_data,
-    };
-
-    // Uninitialized data section
-    let uninit_data_items =
-        UninitDataSection::convert_from_entries(&image_common_entry.uninit_data_entries);
-    let uninit_data_section = UninitDataSection {
-        items: &uninit_data_items,
-    };
-
-    // External library section
-    let (external_library_items, external_library_names_data) =
-        ExternalLibrarySection::convert_from_entries(&image_common_entry.external_library_entries);
-    let external_library_section = ExternalLibrarySection {
-        items: &external_library_items,
-        items_data: &external_library_names_data,
-    };
-
-    // External function section
-    let (external_function_items, external_function_names_data) =
-        ExternalFunctionSection::convert_from_entries(
-            &image_common_entry.external_function_entries,
-        );
-    let external_function_section = ExternalFunctionSection {
-        items: &external_function_items,
-        names_data: &external_function_names_data,
-    };
-
-    // Import module section
-    let (import_module_items, import_module_data) =
-        ImportModuleSection::convert_from_entries(&image_common_entry.import_module_entries);
-    let import_module_section = ImportModuleSection {
-        items: &import_module_items,
-        items_data: &import_module_data,
-    };
-
-    // Import function section
-    let (import_function_items, import_function_data) =
-        ImportFunctionSection::convert_from_entries(&image_common_entry.import_function_entries);
-    let import_function_section = ImportFunctionSection {
-        items: &import_function_items,
-        full_names_data: &import_function_data,
-    };
+) -> Result<(), ImageError> {
+    write_image_file_with_options(
+        image_common_entry,
+        image_index_entry,
+        &WriteOptions::default(),
+        writer,
+    )
+}
 
-    // Import data section
-    let (import_data_items, import_data) =
-        ImportDataSection::convert_from_entries(&image_common_entry.import_data_entries);
-    let import_data_section = ImportDataSection {
-        items: &import_data_items,
-        full_names_data: &import_data,
-    };
+/// The same as `write_image_file`, but with the specified options.
+pub fn write_image_file_with_options(
+    image_common_entry: &ImageCommonEntry,
+    image_index_entry: &ImageLinkingEntry,
+    options: &WriteOptions,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    write_image_file_with_observer(
+        image_common_entry,
+        image_index_entry,
+        options,
+        &mut (),
+        writer,
+    )
+}
 
-    // Export function section
-    let (export_function_items, export_function_names_data) =
-        FunctionNameSection::convert_from_entries(&image_common_entry.function_name_entries);
-    let export_function_section = FunctionNameSection {
-        items: &export_function_items,
-        full_names_data: &export_function_names_data,
-    };
+/// The same as `write_image_file_with_options`, but notifies the observer
+/// after each section is written.
+pub fn write_image_file_with_observer(
+    image_common_entry: &ImageCommonEntry,
+    image_index_entry: &ImageLinkingEntry,
+    options: &WriteOptions,
+    observer: &mut dyn ImageIoObserver,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("write_image_file", name = %image_common_entry.name).entered();
 
-    // Export data section
-    let (export_data_items, export_data_names_data) =
-        DataNameSection::convert_from_entries(&image_common_entry.data_data_entries);
-    let export_data_section = DataNameSection {
-        items: &export_data_items,
-        full_names_data: &export_data_names_data,
-    };
+    check_write_options(options)?;
 
-    // Relocate section
-    let (relocate_lists, relocate_lists_data) =
-        RelocateSection::convert_from_entries(&image_common_entry.relocate_list_entries);
-    let relocate_section = RelocateSection {
-        lists: &relocate_lists,
-        list_data: &relocate_lists_data,
-    };
+    let common_section_data = CommonSectionData::new(image_common_entry, options);
+    let common_sections = common_section_data.sections();
 
     // Convert and prepare all index-specific sections from the ImageIndexEntry.
     // Function index section
@@ -357,6 +362,14 @@ pub fn write_image_file(
         items: &data_index_items,
     };
 
+    // Initialization dependency section
+    let initialization_dependency_items = InitializationDependencySection::convert_from_entries(
+        &image_index_entry.initialization_dependency_entries,
+    );
+    let initialization_dependency_section = InitializationDependencySection {
+        items: &initialization_dependency_items,
+    };
+
     // External function index section
     let (external_function_ranges, external_function_index_items) =
         ExternalFunctionIndexSection::convert_from_entries(
@@ -397,6 +410,13 @@ pub fn write_image_file(
         names_data: &unified_external_function_data,
     };
 
+    // Lazy binding section
+    let lazy_binding_items =
+        LazyBindingSection::convert_from_entries(&image_index_entry.lazy_binding_entries);
+    let lazy_binding_section = LazyBindingSection {
+        items: &lazy_binding_items,
+    };
+
     // Dynamic link module section
     let (dynamic_link_module_items, dynamic_link_module_data) =
         LinkingModuleSection::convert_from_entries(&image_index_entry.linking_module_entries);
@@ -414,54 +434,1139 @@ pub fn write_image_file(
     };
 
     // Collect all section entries, including both common and index-specific sections.
-    let section_entries: Vec<&dyn SectionEntry> = vec![
-        /*
-         * Common sections
-         */
-        &property_section,
-        //
-        &type_section,
-        &local_variable_section,
-        &function_section,
-        //
-        &read_only_data_section,
-        &read_write_data_section,
-        &uninit_data_section,
-        //
-        &import_module_section,
-        &import_function_section,
-        &import_data_section,
-        //
-        &export_function_section,
-        &export_data_section,
-        &relocate_section,
-        //
-        &external_library_section,
-        &external_function_section,
-        /*
-         * Index-specific sections
-         */
+    // Note: the sections are listed in ascending order of their ids, so that
+    // the section table is sorted and the readers can look up sections by binary search.
+    // The ids of the index-specific sections follow the ones of the common sections.
+    let index_section_entries: Vec<&dyn SectionEntry> = vec![
+        &entry_point_section,
         &function_index_section,
+        &dynamic_link_module_section,
+        //
         &data_index_section,
-        &external_function_index_section,
+        &initialization_dependency_section,
         //
-        &unified_external_library_section,
         &unified_external_type_section,
+        &unified_external_library_section,
         &unified_external_function_section,
-        //
-        &dynamic_link_module_section,
-        &entry_point_section,
+        &external_function_index_section,
+        &lazy_binding_section,
     ];
 
+    let mut section_entries = common_sections.entries();
+    section_entries.extend(index_section_entries);
+
+    let section_entries = apply_optional_section_policy(section_entries, options);
+
     // Build the application image binary from the section entries.
-    let (section_items, sections_data) =
-        ModuleImage::convert_from_section_entries(&section_entries);
-    let module_image = ModuleImage {
-        image_type: ImageType::Application,
-        items: &section_items,
-        sections_data: &sections_data,
-    };
+    let mut section_items = vec![];
+    let mut sections_data = vec![];
+    ModuleImage::convert_from_section_entries_into_with_observer(
+        &section_entries,
+        &mut section_items,
+        &mut sections_data,
+        observer,
+    )?;
+    let module_image = ModuleImage::new(ImageType::Application, &section_items, &sections_data);
 
     // Write the binary data to the provided writer.
-    module_image.write(writer)
+    write_module_image(&module_image, options, writer)
+}
+
+// The items and data of the common sections, i.e., the sections which are
+// written to both the object files (and shared modules) and the application images.
+//
+// The sections only borrow their items and data, so they are owned here,
+// and the sections are created by `CommonSectionData::sections`.
+struct CommonSectionData {
+    property_section: PropertySection,
+    type_items: Vec<TypeItem>,
+    types_data: Vec<u8>,
+    local_lists: Vec<LocalVariableList>,
+    local_list_data: Vec<u8>,
+    function_items: Vec<FunctionItem>,
+    function_codes_data: Vec<u8>,
+    read_only_data_items: Vec<read_only_data_section::DataItem>,
+    read_only_data: Vec<u8>,
+    read_write_data_items: Vec<read_write_data_section::DataItem>,
+    read_write_data: Vec<u8>,
+    uninit_data_items: Vec<uninit_data_section::DataItem>,
+    external_library_items: Vec<ExternalLibraryItem>,
+    external_library_names_data: Vec<u8>,
+    external_function_items: Vec<ExternalFunctionItem>,
+    external_function_names_data: Vec<u8>,
+    import_module_items: Vec<ImportModuleItem>,
+    import_module_data: Vec<u8>,
+    import_function_items: Vec<ImportFunctionItem>,
+    import_function_data: Vec<u8>,
+    import_data_items: Vec<ImportDataItem>,
+    import_data: Vec<u8>,
+    export_function_items: Vec<FunctionNameItem>,
+    export_function_names_data: Vec<u8>,
+    export_data_items: Vec<DataNameItem>,
+    export_data_names_data: Vec<u8>,
+    relocate_lists: Vec<RelocateList>,
+    relocate_lists_data: Vec<u8>,
+    export_hash_items: Vec<ExportHashItem>,
+    function_hash_items: Vec<FunctionHashItem>,
+    initializer_items: Vec<InitializerItem>,
+}
+
+struct CommonSections<'a> {
+    property_section: &'a PropertySection,
+    type_section: TypeSection<'a>,
+    local_variable_section: LocalVariableSection<'a>,
+    function_section: FunctionSection<'a>,
+    read_only_data_section: ReadOnlyDataSection<'a>,
+    read_write_data_section: ReadWriteDataSection<'a>,
+    uninit_data_section: UninitDataSection<'a>,
+    export_function_section: FunctionNameSection<'a>,
+    export_data_section: DataNameSection<'a>,
+    relocate_section: RelocateSection<'a>,
+    export_hash_section: ExportHashSection<'a>,
+    function_hash_section: FunctionHashSection<'a>,
+    import_module_section: ImportModuleSection<'a>,
+    import_function_section: ImportFunctionSection<'a>,
+    import_data_section: ImportDataSection<'a>,
+    external_library_section: ExternalLibrarySection<'a>,
+    external_function_section: ExternalFunctionSection<'a>,
+    initializer_section: InitializerSection<'a>,
+}
+
+impl CommonSectionData {
+    // Converts the entries of the ImageCommonEntry according to the options,
+    // e.g., the export filter, the name retention and the minimum data alignment.
+    fn new(image_common_entry: &ImageCommonEntry, options: &WriteOptions) -> Self {
+        // Create the property section with metadata about the image.
+        let property_section = PropertySection::new(
+            &image_common_entry.name,
+            *RUNTIME_EDITION,
+            image_common_entry.version.patch,
+            image_common_entry.version.minor,
+            image_common_entry.version.major,
+            // image_common_entry.import_data_entries.len() as u32,
+            // image_common_entry.import_function_entries.len() as u32,
+        );
+
+        let (type_items, types_data) =
+            TypeSection::convert_from_entries(&image_common_entry.type_entries);
+
+        let (local_lists, local_list_data) = LocalVariableSection::convert_from_entries(
+            &image_common_entry.local_variable_list_entries,
+        );
+
+        let (function_items, function_codes_data) =
+            FunctionSection::convert_from_entries(&image_common_entry.function_entries);
+
+        // Data sections
+        let read_only_data_entries = raise_data_align(
+            &image_common_entry.read_only_data_entries,
+            options.min_data_align,
+            |entry| &mut entry.align,
+        );
+        let (read_only_data_items, read_only_data) =
+            ReadOnlyDataSection::convert_from_entries(&read_only_data_entries);
+
+        let read_write_data_entries = raise_data_align(
+            &image_common_entry.read_write_data_entries,
+            options.min_data_align,
+            |entry| &mut entry.align,
+        );
+        let (read_write_data_items, read_write_data) =
+            ReadWriteDataSection::convert_from_entries(&read_write_data_entries);
+
+        let uninit_data_entries = raise_data_align(
+            &image_common_entry.uninit_data_entries,
+            options.min_data_align,
+            |entry| &mut entry.align,
+        );
+        let uninit_data_items = UninitDataSection::convert_from_entries(&uninit_data_entries);
+
+        // External sections
+        let (external_library_items, external_library_names_data) =
+            ExternalLibrarySection::convert_from_entries(
+                &image_common_entry.external_library_entries,
+            );
+
+        let (external_function_items, external_function_names_data) =
+            ExternalFunctionSection::convert_from_entries(
+                &image_common_entry.external_function_entries,
+            );
+
+        // Import sections
+        let (import_module_items, import_module_data) =
+            ImportModuleSection::convert_from_entries(&image_common_entry.import_module_entries);
+
+        let (import_function_items, import_function_data) =
+            ImportFunctionSection::convert_from_entries(
+                &image_common_entry.import_function_entries,
+            );
+
+        let (import_data_items, import_data) =
+            ImportDataSection::convert_from_entries(&image_common_entry.import_data_entries);
+
+        // Export function section
+        let function_name_entries = filter_export_entries(
+            &image_common_entry.function_name_entries,
+            options,
+            |entry| &entry.full_name,
+            |entry| &mut entry.visibility,
+        );
+        let function_name_entries =
+            retain_name_entries(&function_name_entries, options.name_retention, |entry| {
+                entry.visibility
+            });
+        let (export_function_items, export_function_names_data) =
+            FunctionNameSection::convert_from_entries(&function_name_entries);
+
+        // Export data section
+        let data_name_entries = filter_export_entries(
+            &image_common_entry.data_data_entries,
+            options,
+            |entry| &entry.full_name,
+            |entry| &mut entry.visibility,
+        );
+        let data_name_entries =
+            retain_name_entries(&data_name_entries, options.name_retention, |entry| {
+                entry.visibility
+            });
+        let (export_data_items, export_data_names_data) =
+            DataNameSection::convert_from_entries(&data_name_entries);
+
+        let (relocate_lists, relocate_lists_data) =
+            RelocateSection::convert_from_entries(&image_common_entry.relocate_list_entries);
+
+        // Export hash section
+        let export_signatures = collect_export_signatures(image_common_entry)
+            .into_iter()
+            .filter(|signature| options.export_filter.is_allowed(&signature.full_name))
+            .collect::<Vec<_>>();
+        let export_hash_entries = convert_to_export_hash_entries(&export_signatures);
+        let export_hash_items = ExportHashSection::convert_from_entries(&export_hash_entries);
+
+        // Function hash section
+        let function_hashes = if options.emit_function_hashes {
+            compute_function_hashes(image_common_entry)
+        } else {
+            vec![]
+        };
+        let function_hash_items = FunctionHashSection::convert_from_entries(&function_hashes);
+
+        let initializer_items =
+            InitializerSection::convert_from_entries(&image_common_entry.initializer_entries);
+
+        Self {
+            property_section,
+            type_items,
+            types_data,
+            local_lists,
+            local_list_data,
+            function_items,
+            function_codes_data,
+            read_only_data_items,
+            read_only_data,
+            read_write_data_items,
+            read_write_data,
+            uninit_data_items,
+            external_library_items,
+            external_library_names_data,
+            external_function_items,
+            external_function_names_data,
+            import_module_items,
+            import_module_data,
+            import_function_items,
+            import_function_data,
+            import_data_items,
+            import_data,
+            export_function_items,
+            export_function_names_data,
+            export_data_items,
+            export_data_names_data,
+            relocate_lists,
+            relocate_lists_data,
+            export_hash_items,
+            function_hash_items,
+            initializer_items,
+        }
+    }
+
+    fn sections(&self) -> CommonSections<'_> {
+        CommonSections {
+            property_section: &self.property_section,
+            type_section: TypeSection {
+                items: &self.type_items,
+                types_data: &self.types_data,
+            },
+            local_variable_section: LocalVariableSection {
+                lists: &self.local_lists,
+                list_data: &self.local_list_data,
+            },
+            function_section: FunctionSection {
+                items: &self.function_items,
+                codes_data: &self.function_codes_data,
+            },
+            read_only_data_section: ReadOnlyDataSection {
+                items: &self.read_only_data_items,
+                datas_data: &self.read_only_data,
+            },
+            read_write_data_section: ReadWriteDataSection {
+                items: &self.read_write_data_items,
+                datas_data: &self.read_write_data,
+            },
+            uninit_data_section: UninitDataSection {
+                items: &self.uninit_data_items,
+            },
+            export_function_section: FunctionNameSection {
+                items: &self.export_function_items,
+                full_names_data: &self.export_function_names_data,
+            },
+            export_data_section: DataNameSection {
+                items: &self.export_data_items,
+                full_names_data: &self.export_data_names_data,
+            },
+            relocate_section: RelocateSection {
+                lists: &self.relocate_lists,
+                list_data: &self.relocate_lists_data,
+            },
+            export_hash_section: ExportHashSection {
+                items: &self.export_hash_items,
+            },
+            function_hash_section: FunctionHashSection {
+                items: &self.function_hash_items,
+            },
+            import_module_section: ImportModuleSection {
+                items: &self.import_module_items,
+                items_data: &self.import_module_data,
+            },
+            import_function_section: ImportFunctionSection {
+                items: &self.import_function_items,
+                full_names_data: &self.import_function_data,
+            },
+            import_data_section: ImportDataSection {
+                items: &self.import_data_items,
+                full_names_data: &self.import_data,
+            },
+            external_library_section: ExternalLibrarySection {
+                items: &self.external_library_items,
+                items_data: &self.external_library_names_data,
+            },
+            external_function_section: ExternalFunctionSection {
+                items: &self.external_function_items,
+                names_data: &self.external_function_names_data,
+            },
+            initializer_section: InitializerSection {
+                items: &self.initializer_items,
+            },
+        }
+    }
+}
+
+impl<'a> CommonSections<'a> {
+    // Returns the section entries in ascending order of their ids.
+    fn entries(&'a self) -> Vec<&'a dyn SectionEntry<'a>> {
+        vec![
+            self.property_section,
+            //
+            &self.type_section,
+            &self.local_variable_section,
+            &self.function_section,
+            //
+            &self.read_only_data_section,
+            &self.read_write_data_section,
+            &self.uninit_data_section,
+            //
+            &self.export_function_section,
+            &self.export_data_section,
+            &self.relocate_section,
+            &self.export_hash_section,
+            &self.function_hash_section,
+            //
+            &self.import_module_section,
+            &self.import_function_section,
+            &self.import_data_section,
+            &self.external_library_section,
+            &self.external_function_section,
+            //
+            &self.initializer_section,
+        ]
+    }
+}
+
+// The function definition for `build_minimal_module`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MinimalFunctionEntry {
+    pub params: Vec<OperandDataType>,
+    pub results: Vec<OperandDataType>,
+
+    // The local variables, the function arguments are not included.
+    pub local_variable_types_without_args: Vec<OperandDataType>,
+    pub code: Vec<u8>,
+}
+
+/// Builds an object file which contains only the essential sections, i.e.,
+/// the property, type, local variable and function sections.
+///
+/// The identical types and local variable lists are merged, and the version
+/// of the module is "1.0.0".
+pub fn build_minimal_module(
+    name: &str,
+    functions: &[MinimalFunctionEntry],
+) -> Result<Vec<u8>, ImageError> {
+    let image_common_entry = build_minimal_image_common_entry(name, functions);
+    write_with_empty_sections_omitted(&image_common_entry, false)
+}
+
+// The terminate code of the stub functions generated by `build_shared_module_scaffold`.
+pub const SCAFFOLD_STUB_TERMINATE_CODE: u32 = 0xdead;
+
+/// Builds a shared module which exports the given functions, the body of
+/// each function is a `terminate` instruction, i.e., calling the stub
+/// terminates the program with the code `SCAFFOLD_STUB_TERMINATE_CODE`.
+///
+/// The stubs are specified as `(name, signature)`, the name does not include
+/// the module name, e.g. "add" or "math::add", and all stubs are public.
+///
+/// The scaffold is used to mock the dependencies, e.g., in the integration
+/// tests of the linker and the loader.
+pub fn build_shared_module_scaffold(
+    name: &str,
+    stubs: &[(&str, TypeEntry)],
+) -> Result<Vec<u8>, ImageError> {
+    let stub_code = BytecodeWriterHelper::new()
+        .append_opcode_i32(Opcode::terminate, SCAFFOLD_STUB_TERMINATE_CODE)
+        .append_opcode(Opcode::end)
+        .to_bytes();
+
+    let functions = stubs
+        .iter()
+        .map(|(_, type_entry)| MinimalFunctionEntry {
+            params: type_entry.params.clone(),
+            results: type_entry.results.clone(),
+            local_variable_types_without_args: vec![],
+            code: stub_code.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut image_common_entry = build_minimal_image_common_entry(name, &functions);
+    image_common_entry.image_type = ImageType::SharedModule;
+    image_common_entry.function_name_entries = stubs
+        .iter()
+        .enumerate()
+        .map(|(internal_index, (stub_name, _))| {
+            FunctionNameEntry::new(
+                format!("{}::{}", name, stub_name),
+                Visibility::Public,
+                internal_index,
+            )
+        })
+        .collect();
+
+    write_with_empty_sections_omitted(&image_common_entry, true)
+}
+
+fn build_minimal_image_common_entry(
+    name: &str,
+    functions: &[MinimalFunctionEntry],
+) -> ImageCommonEntry {
+    let mut type_entries: Vec<TypeEntry> = vec![];
+    let mut local_variable_list_entries: Vec<LocalVariableListEntry> = vec![];
+    let mut function_entries: Vec<FunctionEntry> = vec![];
+
+    for function in functions {
+        let type_entry = TypeEntry::new(function.params.clone(), function.results.clone());
+        let type_index = find_or_append(&mut type_entries, type_entry);
+
+        // The local variable list of a function starts with the arguments.
+        let mut local_variable_types = function.params.clone();
+        local_variable_types.extend_from_slice(&function.local_variable_types_without_args);
+        let local_variable_list_index = find_or_append(
+            &mut local_variable_list_entries,
+            LocalVariableListEntry::new(local_variable_types),
+        );
+
+        function_entries.push(FunctionEntry::new(
+            type_index,
+            local_variable_list_index,
+            function.code.clone(),
+        ));
+    }
+
+    ImageCommonEntry {
+        name: name.to_owned(),
+        version: EffectiveVersion::new(1, 0, 0),
+        image_type: ImageType::ObjectFile,
+        type_entries,
+        local_variable_list_entries,
+        function_entries,
+        read_only_data_entries: vec![],
+        read_write_data_entries: vec![],
+        uninit_data_entries: vec![],
+        import_module_entries: vec![],
+        import_function_entries: vec![],
+        import_data_entries: vec![],
+        function_name_entries: vec![],
+        data_data_entries: vec![],
+        relocate_list_entries: vec![],
+        external_library_entries: vec![],
+        external_function_entries: vec![],
+        initializer_entries: vec![],
+    }
+}
+
+fn write_with_empty_sections_omitted(
+    image_common_entry: &ImageCommonEntry,
+    generate_shared_module: bool,
+) -> Result<Vec<u8>, ImageError> {
+    let mut image_binary: Vec<u8> = vec![];
+
+    write_object_file_with_options(
+        image_common_entry,
+        generate_shared_module,
+        &WriteOptions {
+            optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+            ..Default::default()
+        },
+        &mut image_binary,
+    )?;
+
+    Ok(image_binary)
+}
+
+// Writes the image with the trailer appended or the sections compressed
+// according to the options.
+fn write_module_image<'a>(
+    module_image: &'a ModuleImage<'a>,
+    options: &WriteOptions,
+    writer: &mut dyn Write,
+) -> Result<(), ImageError> {
+    if options.compress_sections && options.append_trailer {
+        return Err(ImageError::new(ImageErrorType::Compression(
+            CompressionError::TrailerUnsupported,
+        )));
+    }
+
+    if options.compress_sections {
+        let mut image_sections = ImageSections::from_module_image(module_image);
+        let section_ids = image_sections
+            .sections
+            .iter()
+            .map(|(section_id, _)| *section_id)
+            .filter(|section_id| *section_id != ModuleSectionId::Property)
+            .collect::<Vec<_>>();
+
+        compress_sections(&mut image_sections, &section_ids, &LzCodec)?;
+        image_sections.write(writer)
+    } else if options.append_trailer {
+        module_image.write_with_trailer(writer)
+    } else {
+        module_image.write(writer)
+    }
+}
+
+/// Returns the index of the given entry, appends it if it does not exist.
+pub fn find_or_append<T: PartialEq>(entries: &mut Vec<T>, entry: T) -> usize {
+    match entries.iter().position(|item| item == &entry) {
+        Some(index) => index,
+        None => {
+            entries.push(entry);
+            entries.len() - 1
+        }
+    }
+}
+
+// Removes the empty optional sections if the policy is `OmitEmpty`,
+// and the name sections (along with the export hashes) and the relocate section
+// if they are stripped, and the function hash section if it is not enabled.
+fn apply_optional_section_policy<'a>(
+    section_entries: Vec<&'a dyn SectionEntry<'a>>,
+    options: &WriteOptions,
+) -> Vec<&'a dyn SectionEntry<'a>> {
+    section_entries
+        .into_iter()
+        .filter(|section_entry| {
+            let is_stripped = match section_entry.id() {
+                ModuleSectionId::FunctionName
+                | ModuleSectionId::DataName
+                | ModuleSectionId::ExportHash => options.name_retention == NameRetention::None,
+                ModuleSectionId::Relocate => options.strip_relocations,
+                ModuleSectionId::FunctionHash => !options.emit_function_hashes,
+                _ => false,
+            };
+
+            if is_stripped {
+                return false;
+            }
+
+            if options.optional_section_policy == OptionalSectionPolicy::EmitAll {
+                return true;
+            }
+
+            let is_essential = section_entry.id().is_essential();
+
+            // A section without items only contains the section header.
+            is_essential || section_entry.estimated_size() > BASE_SECTION_HEADER_LENGTH
+        })
+        .collect()
+}
+
+// Removes the private names if the retention is `PublicOnly`,
+// the package-private names are kept since they can be imported.
+fn retain_name_entries<T: Clone>(
+    entries: &[T],
+    name_retention: NameRetention,
+    get_visibility: impl Fn(&T) -> Visibility,
+) -> Cow<'_, [T]> {
+    match name_retention {
+        NameRetention::PublicOnly => Cow::Owned(
+            entries
+                .iter()
+                .filter(|entry| get_visibility(entry).is_exported())
+                .cloned()
+                .collect(),
+        ),
+        // The name sections are removed entirely by `apply_optional_section_policy`
+        // if the retention is `None`.
+        NameRetention::All | NameRetention::None => Cow::Borrowed(entries),
+    }
+}
+
+// Demotes the exports which are rejected by the export filter to private,
+// or removes them if `drop_filtered_names` is set.
+fn filter_export_entries<T: Clone>(
+    entries: &[T],
+    options: &WriteOptions,
+    get_full_name: impl Fn(&T) -> &str,
+    get_visibility_mut: impl Fn(&mut T) -> &mut Visibility,
+) -> Cow<'_, [T]> {
+    if options.export_filter == ExportFilter::None {
+        return Cow::Borrowed(entries);
+    }
+
+    Cow::Owned(
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let mut entry = entry.clone();
+                let visibility = *get_visibility_mut(&mut entry);
+                if visibility.is_exported()
+                    && !options.export_filter.is_allowed(get_full_name(&entry))
+                {
+                    if options.drop_filtered_names {
+                        return None;
+                    }
+                    *get_visibility_mut(&mut entry) = Visibility::Private;
+                }
+                Some(entry)
+            })
+            .collect(),
+    )
+}
+
+fn check_write_options(options: &WriteOptions) -> Result<(), ImageError> {
+    let min_data_align = options.min_data_align;
+    if min_data_align != 0 && !min_data_align.is_power_of_two() {
+        return Err(ImageError::new(ImageErrorType::InvalidAlign(
+            min_data_align,
+        )));
+    }
+
+    Ok(())
+}
+
+// Raises the alignment of the data items to `min_align`,
+// which is `0` or a power of two, see `check_write_options`.
+fn raise_data_align<T: Clone>(
+    entries: &[T],
+    min_align: u16,
+    get_align_mut: impl Fn(&mut T) -> &mut u16,
+) -> Cow<'_, [T]> {
+    if min_align <= 1 {
+        return Cow::Borrowed(entries);
+    }
+
+    Cow::Owned(
+        entries
+            .iter()
+            .cloned()
+            .map(|mut entry| {
+                let align = get_align_mut(&mut entry);
+                *align = (*align).max(min_align);
+                entry
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, EffectiveVersion, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            FunctionEntry, FunctionNameEntry, ImageCommonEntry, LocalVariableListEntry,
+            ReadOnlyDataEntry, TypeEntry, UninitDataEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::{
+            build_minimal_module, build_shared_module_scaffold, write_object_file,
+            write_object_file_with_options, ExportFilter, MinimalFunctionEntry,
+            OptionalSectionPolicy, WriteOptions, WriteProfile, SCAFFOLD_STUB_TERMINATE_CODE,
+        },
+        image_compression::{decompress_image, is_compressed_image, CompressionError, LzCodec},
+        module_image::{ImageType, ModuleImage, ModuleSectionId, Visibility},
+        ImageErrorType,
+    };
+
+    #[test]
+    fn test_write_object_file_omit_empty_sections() {
+        let image_common_entry = ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 0, 0),
+            image_type: ImageType::ObjectFile,
+            type_entries: vec![TypeEntry::new(vec![], vec![])],
+            local_variable_list_entries: vec![LocalVariableListEntry::new(vec![])],
+            function_entries: vec![FunctionEntry::new(
+                0,
+                0,
+                BytecodeWriterHelper::new()
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+            )],
+            read_only_data_entries: vec![],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![],
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            function_name_entries: vec![],
+            data_data_entries: vec![],
+            relocate_list_entries: vec![],
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+            initializer_entries: vec![],
+        };
+
+        let mut binary_all: Vec<u8> = vec![];
+        write_object_file(&image_common_entry, false, &mut binary_all).unwrap();
+        assert_eq!(ModuleImage::read(&binary_all).unwrap().items.len(), 17);
+
+        let mut binary_omit: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                ..Default::default()
+            },
+            &mut binary_omit,
+        )
+        .unwrap();
+
+        let module_image = ModuleImage::read(&binary_omit).unwrap();
+        assert_eq!(
+            module_image
+                .items
+                .iter()
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            vec![
+                ModuleSectionId::Property,
+                ModuleSectionId::Type,
+                ModuleSectionId::LocalVariable,
+                ModuleSectionId::Function,
+            ]
+        );
+
+        // both forms are read as the same entry
+        let entry_all = read_object_file(&binary_all).unwrap();
+        let entry_omit = read_object_file(&binary_omit).unwrap();
+        assert_eq!(entry_omit.function_entries, entry_all.function_entries);
+        assert_eq!(
+            entry_omit.read_only_data_entries,
+            entry_all.read_only_data_entries
+        );
+        assert_eq!(
+            entry_omit.relocate_list_entries,
+            entry_all.relocate_list_entries
+        );
+    }
+
+    #[test]
+    fn test_build_minimal_module() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let image_binary = build_minimal_module(
+            "foo",
+            &[
+                MinimalFunctionEntry {
+                    params: vec![OperandDataType::I32],
+                    results: vec![OperandDataType::I32],
+                    local_variable_types_without_args: vec![],
+                    code: code.clone(),
+                },
+                MinimalFunctionEntry {
+                    params: vec![OperandDataType::I32],
+                    results: vec![OperandDataType::I32],
+                    local_variable_types_without_args: vec![OperandDataType::I64],
+                    code: code.clone(),
+                },
+                MinimalFunctionEntry {
+                    params: vec![OperandDataType::I32],
+                    results: vec![OperandDataType::I32],
+                    local_variable_types_without_args: vec![],
+                    code: code.clone(),
+                },
+            ],
+        )
+        .unwrap();
+
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        assert_eq!(module_image.image_type, ImageType::ObjectFile);
+        assert_eq!(
+            module_image
+                .items
+                .iter()
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            vec![
+                ModuleSectionId::Property,
+                ModuleSectionId::Type,
+                ModuleSectionId::LocalVariable,
+                ModuleSectionId::Function,
+            ]
+        );
+
+        let property_section = module_image.get_property_section();
+        assert_eq!(property_section.get_module_name(), "foo");
+        assert_eq!(property_section.version_major, 1);
+        assert_eq!(property_section.version_minor, 0);
+        assert_eq!(property_section.version_patch, 0);
+
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![TypeEntry::new(
+                vec![OperandDataType::I32],
+                vec![OperandDataType::I32]
+            )]
+        );
+        assert_eq!(
+            image_common_entry.local_variable_list_entries,
+            vec![
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+                LocalVariableListEntry::new(vec![OperandDataType::I32, OperandDataType::I64]),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.function_entries,
+            vec![
+                FunctionEntry::new(0, 0, code.clone()),
+                FunctionEntry::new(0, 1, code.clone()),
+                FunctionEntry::new(0, 0, code),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_shared_module_scaffold() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                (
+                    "add",
+                    TypeEntry::new(
+                        vec![OperandDataType::I32, OperandDataType::I32],
+                        vec![OperandDataType::I32],
+                    ),
+                ),
+                ("math::reset", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        assert_eq!(module_image.image_type, ImageType::SharedModule);
+
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+        assert_eq!(image_common_entry.name, "foo");
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![
+                TypeEntry::new(
+                    vec![OperandDataType::I32, OperandDataType::I32],
+                    vec![OperandDataType::I32]
+                ),
+                TypeEntry::new(vec![], vec![])
+            ]
+        );
+        assert_eq!(
+            image_common_entry.function_name_entries,
+            vec![
+                FunctionNameEntry::new("foo::add".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::math::reset".to_owned(), Visibility::Public, 1),
+            ]
+        );
+
+        let stub_code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::terminate, SCAFFOLD_STUB_TERMINATE_CODE)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+        assert_eq!(
+            image_common_entry.function_entries,
+            vec![
+                FunctionEntry::new(0, 0, stub_code.clone()),
+                FunctionEntry::new(1, 1, stub_code),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_object_file_with_profiles() {
+        let image_binary =
+            build_shared_module_scaffold("foo", &[("bar", TypeEntry::new(vec![], vec![]))])
+                .unwrap();
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry
+            .function_name_entries
+            .push(FunctionNameEntry::new(
+                "foo::baz".to_owned(),
+                Visibility::Private,
+                0,
+            ));
+
+        let write_with_profile = |profile: WriteProfile| {
+            let mut binary: Vec<u8> = vec![];
+            write_object_file_with_options(
+                &image_common_entry,
+                true,
+                &WriteOptions::from_profile(profile),
+                &mut binary,
+            )
+            .unwrap();
+            binary
+        };
+
+        // debug
+        let binary_debug = write_with_profile(WriteProfile::Debug);
+        assert_eq!(
+            read_object_file(&binary_debug)
+                .unwrap()
+                .function_name_entries,
+            image_common_entry.function_name_entries
+        );
+
+        // release
+        let binary_release = write_with_profile(WriteProfile::Release);
+        assert_eq!(
+            read_object_file(&binary_release)
+                .unwrap()
+                .function_name_entries,
+            vec![FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Public,
+                0
+            )]
+        );
+
+        // min size
+        let binary_min_size = write_with_profile(WriteProfile::MinSize);
+        assert!(is_compressed_image(&binary_min_size));
+        assert!(binary_min_size.len() < binary_release.len());
+
+        // the compressed image is read back directly
+        let min_size_entry = read_object_file(&binary_min_size).unwrap();
+        assert_eq!(
+            min_size_entry.function_entries,
+            image_common_entry.function_entries
+        );
+        assert!(min_size_entry.function_name_entries.is_empty());
+        assert!(min_size_entry.relocate_list_entries.is_empty());
+
+        let decompressed_binary = decompress_image(&binary_min_size, &LzCodec).unwrap();
+        let module_image = ModuleImage::read(&decompressed_binary).unwrap();
+        assert!(module_image.items.iter().all(|item| !matches!(
+            item.id,
+            ModuleSectionId::FunctionName | ModuleSectionId::DataName | ModuleSectionId::Relocate
+        )));
+
+        // the trailer can not be combined with the compression
+        let mut binary: Vec<u8> = vec![];
+        let result = write_object_file_with_options(
+            &image_common_entry,
+            true,
+            &WriteOptions {
+                append_trailer: true,
+                ..WriteOptions::from_profile(WriteProfile::MinSize)
+            },
+            &mut binary,
+        );
+        assert!(matches!(
+            result.unwrap_err().error_type,
+            ImageErrorType::Compression(CompressionError::TrailerUnsupported)
+        ));
+    }
+
+    #[test]
+    fn test_write_object_file_with_min_data_align() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry.read_only_data_entries = vec![
+            ReadOnlyDataEntry::from_bytes_auto_align(b"abc".to_vec()),
+            ReadOnlyDataEntry::from_bytes_auto_align(b"abcd".to_vec()),
+            ReadOnlyDataEntry::from_i64(11),
+        ];
+        image_common_entry.uninit_data_entries = vec![UninitDataEntry::from_i32()];
+
+        assert_eq!(
+            image_common_entry
+                .read_only_data_entries
+                .iter()
+                .map(|entry| entry.align)
+                .collect::<Vec<_>>(),
+            vec![1, 4, 8]
+        );
+
+        let mut binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                min_data_align: 4,
+                ..Default::default()
+            },
+            &mut binary,
+        )
+        .unwrap();
+
+        let image_common_entry_restore = read_object_file(&binary).unwrap();
+        assert_eq!(
+            image_common_entry_restore
+                .read_only_data_entries
+                .iter()
+                .map(|entry| entry.align)
+                .collect::<Vec<_>>(),
+            vec![4, 4, 8]
+        );
+        assert_eq!(image_common_entry_restore.uninit_data_entries[0].align, 4);
+
+        // the alignment must be a power of two
+        let result = write_object_file_with_options(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                min_data_align: 6,
+                ..Default::default()
+            },
+            &mut binary,
+        );
+        assert!(matches!(
+            result.unwrap_err().error_type,
+            ImageErrorType::InvalidAlign(6)
+        ));
+    }
+
+    #[test]
+    fn test_write_object_file_with_export_filter() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("add", TypeEntry::new(vec![], vec![])),
+                ("math::sqrt", TypeEntry::new(vec![], vec![])),
+                ("math::internal::helper", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+
+        let get_names = |binary: &[u8]| {
+            read_object_file(binary)
+                .unwrap()
+                .function_name_entries
+                .iter()
+                .map(|entry| (entry.full_name.clone(), entry.visibility))
+                .collect::<Vec<_>>()
+        };
+
+        // allow-list
+        let mut binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            true,
+            &WriteOptions {
+                export_filter: ExportFilter::AllowList(vec![
+                    "foo::add".to_owned(),
+                    "foo::math::*".to_owned(),
+                ]),
+                ..Default::default()
+            },
+            &mut binary,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_names(&binary),
+            vec![
+                ("foo::add".to_owned(), Visibility::Public),
+                ("foo::math::sqrt".to_owned(), Visibility::Public),
+                ("foo::math::internal::helper".to_owned(), Visibility::Public),
+            ]
+        );
+
+        // deny-list
+        let mut binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            true,
+            &WriteOptions {
+                export_filter: ExportFilter::DenyList(vec!["foo::math::internal::*".to_owned()]),
+                ..Default::default()
+            },
+            &mut binary,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_names(&binary),
+            vec![
+                ("foo::add".to_owned(), Visibility::Public),
+                ("foo::math::sqrt".to_owned(), Visibility::Public),
+                (
+                    "foo::math::internal::helper".to_owned(),
+                    Visibility::Private
+                ),
+            ]
+        );
+
+        let module_image = ModuleImage::read(&binary).unwrap();
+        assert_eq!(
+            module_image
+                .get_optional_export_hash_section()
+                .unwrap()
+                .items
+                .len(),
+            2
+        );
+
+        // drops the names
+        let mut binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            true,
+            &WriteOptions {
+                export_filter: ExportFilter::AllowList(vec!["foo::add".to_owned()]),
+                drop_filtered_names: true,
+                ..Default::default()
+            },
+            &mut binary,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_names(&binary),
+            vec![("foo::add".to_owned(), Visibility::Public)]
+        );
+    }
 }
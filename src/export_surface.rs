@@ -0,0 +1,394 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "export surface" of a module is the set of its public (and package-private)
+// functions and data, along with their signatures (for functions) and data types
+// (for data).
+//
+// Each export is described by a descriptor string, e.g.:
+//
+// - `function foo::add (i32, i32) -> (i32)`
+// - `data foo::count read_write i64`
+//
+// and the ABI hash of an export is the 64-bit FNV-1a hash of its descriptor.
+// FNV-1a is used rather than the Rust default hasher because the hashes are
+// stored in the images, so they must be stable across Rust versions and platforms.
+//
+// The hashes are written into the "export hash section" by the image writers,
+// see `export_hash_section`.
+
+use anc_isa::{DataSectionType, MemoryDataType, OperandDataType};
+
+use crate::{
+    entry::{DataNameEntry, ExportHashEntry, FunctionNameEntry, ImageCommonEntry},
+    entry_dump::{format_data_section_type, format_memory_data_type, format_operand_data_types},
+    module_image::{ExportType, ModuleImage},
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExportSignature {
+    pub full_name: String,
+    pub export_type: ExportType,
+
+    // `None` for functions.
+    pub section_type: Option<DataSectionType>,
+
+    // The function internal index, or the internal index in the data section.
+    pub internal_index: usize,
+
+    pub descriptor: String,
+    pub hash: u64,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct ExportSurfaceDiff {
+    // The full names of the exports which exist only in the new module.
+    pub added: Vec<String>,
+
+    // The full names of the exports which exist only in the old module.
+    pub removed: Vec<String>,
+
+    // The full names of the exports whose signature or data type is changed.
+    pub changed: Vec<String>,
+}
+
+impl ExportSurfaceDiff {
+    /// Returns `true` if the applications linked against the old module
+    /// can run with the new module, i.e., no export is removed or changed.
+    pub fn is_compatible(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Computes the ABI hash of an export descriptor (64-bit FNV-1a).
+pub fn compute_abi_hash(descriptor: &str) -> u64 {
+    compute_content_hash(descriptor.as_bytes())
+}
+
+/// Computes the 64-bit FNV-1a hash of the data.
+pub fn compute_content_hash(data: &[u8]) -> u64 {
+    data.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Collects the public functions and data of the module entry.
+pub fn collect_export_signatures(image_common_entry: &ImageCommonEntry) -> Vec<ExportSignature> {
+    build_export_signatures(
+        &image_common_entry.function_name_entries,
+        &image_common_entry.data_data_entries,
+        |function_internal_index| {
+            let function_entry = image_common_entry
+                .function_entries
+                .get(function_internal_index)?;
+            let type_entry = image_common_entry
+                .type_entries
+                .get(function_entry.type_index)?;
+            Some((type_entry.params.clone(), type_entry.results.clone()))
+        },
+        |section_type, internal_index_in_section| match section_type {
+            DataSectionType::ReadOnly => image_common_entry
+                .read_only_data_entries
+                .get(internal_index_in_section)
+                .map(|entry| entry.memory_data_type),
+            DataSectionType::ReadWrite => image_common_entry
+                .read_write_data_entries
+                .get(internal_index_in_section)
+                .map(|entry| entry.memory_data_type),
+            DataSectionType::Uninit => image_common_entry
+                .uninit_data_entries
+                .get(internal_index_in_section)
+                .map(|entry| entry.memory_data_type),
+        },
+    )
+}
+
+/// Collects the public functions and data of the module image.
+///
+/// The signatures are computed from the name sections and the other sections,
+/// so the export hash section is not required.
+pub fn collect_export_signatures_from_image(image: &ModuleImage) -> Vec<ExportSignature> {
+    let function_name_entries = image
+        .get_optional_export_function_section()
+        .map(|section| section.convert_to_entries())
+        .unwrap_or_default();
+    let data_name_entries = image
+        .get_optional_export_data_section()
+        .map(|section| section.convert_to_entries())
+        .unwrap_or_default();
+
+    let type_section = image.get_type_section();
+    let function_section = image.get_function_section();
+    let read_only_data_section = image.get_optional_read_only_data_section();
+    let read_write_data_section = image.get_optional_read_write_data_section();
+    let uninit_data_section = image.get_optional_uninit_data_section();
+
+    build_export_signatures(
+        &function_name_entries,
+        &data_name_entries,
+        |function_internal_index| {
+            let function_item = function_section.items.get(function_internal_index)?;
+            let type_index = function_item.type_index as usize;
+            if type_index >= type_section.items.len() {
+                return None;
+            }
+            let (params, results) = type_section.get_item_params_and_results(type_index);
+            Some((params.to_vec(), results.to_vec()))
+        },
+        |section_type, internal_index_in_section| match section_type {
+            DataSectionType::ReadOnly => read_only_data_section
+                .as_ref()?
+                .items
+                .get(internal_index_in_section)
+                .map(|item| item.memory_data_type),
+            DataSectionType::ReadWrite => read_write_data_section
+                .as_ref()?
+                .items
+                .get(internal_index_in_section)
+                .map(|item| item.memory_data_type),
+            DataSectionType::Uninit => uninit_data_section
+                .as_ref()?
+                .items
+                .get(internal_index_in_section)
+                .map(|item| item.memory_data_type),
+        },
+    )
+}
+
+/// Converts the signatures to the entries of the export hash section.
+pub fn convert_to_export_hash_entries(signatures: &[ExportSignature]) -> Vec<ExportHashEntry> {
+    signatures
+        .iter()
+        .map(|signature| match signature.section_type {
+            None => ExportHashEntry::from_function(signature.internal_index, signature.hash),
+            Some(section_type) => {
+                ExportHashEntry::from_data(section_type, signature.internal_index, signature.hash)
+            }
+        })
+        .collect()
+}
+
+/// Returns the stored ABI hash of the specified public function or data.
+///
+/// Returns `None` if the name or the export hash section does not exist.
+pub fn get_export_hash(image: &ModuleImage, full_name: &str) -> Option<u64> {
+    let export_hash_section = image.get_optional_export_hash_section()?;
+
+    let function_internal_index = image
+        .get_optional_export_function_section()
+        .and_then(|section| section.get_item_visibility_and_function_internal_index(full_name));
+
+    if let Some((_, function_internal_index)) = function_internal_index {
+        return export_hash_section.get_function_hash(function_internal_index);
+    }
+
+    let (_, section_type, internal_index_in_section) = image
+        .get_optional_export_data_section()?
+        .get_item_visibility_and_section_type_and_data_internal_index_in_section(full_name)?;
+    export_hash_section.get_data_hash(section_type, internal_index_in_section)
+}
+
+/// Compares the export surfaces of two versions of a module.
+///
+/// The results are sorted by full name.
+pub fn compare_export_surfaces(old: &ModuleImage, new: &ModuleImage) -> ExportSurfaceDiff {
+    let old_signatures = collect_export_signatures_from_image(old);
+    let new_signatures = collect_export_signatures_from_image(new);
+
+    let find = |signatures: &[ExportSignature], full_name: &str| {
+        signatures
+            .iter()
+            .find(|signature| signature.full_name == full_name)
+            .map(|signature| signature.hash)
+    };
+
+    let mut diff = ExportSurfaceDiff::default();
+
+    for old_signature in &old_signatures {
+        match find(&new_signatures, &old_signature.full_name) {
+            None => diff.removed.push(old_signature.full_name.clone()),
+            Some(hash) if hash != old_signature.hash => {
+                diff.changed.push(old_signature.full_name.clone())
+            }
+            Some(_) => {}
+        }
+    }
+
+    for new_signature in &new_signatures {
+        if find(&old_signatures, &new_signature.full_name).is_none() {
+            diff.added.push(new_signature.full_name.clone());
+        }
+    }
+
+    diff.added.sort();
+    diff.removed.sort();
+    diff.changed.sort();
+    diff
+}
+
+// The exports whose function or data does not exist are skipped.
+fn build_export_signatures(
+    function_name_entries: &[FunctionNameEntry],
+    data_name_entries: &[DataNameEntry],
+    get_function_signature: impl Fn(usize) -> Option<(Vec<OperandDataType>, Vec<OperandDataType>)>,
+    get_memory_data_type: impl Fn(DataSectionType, usize) -> Option<MemoryDataType>,
+) -> Vec<ExportSignature> {
+    let function_signatures = function_name_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .filter_map(|entry| {
+            let (params, results) = get_function_signature(entry.internal_index)?;
+            let descriptor = format!(
+                "function {} {} -> {}",
+                entry.full_name,
+                format_operand_data_types(&params),
+                format_operand_data_types(&results)
+            );
+
+            Some(ExportSignature {
+                full_name: entry.full_name.clone(),
+                export_type: ExportType::Function,
+                section_type: None,
+                internal_index: entry.internal_index,
+                hash: compute_abi_hash(&descriptor),
+                descriptor,
+            })
+        });
+
+    let data_signatures = data_name_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .filter_map(|entry| {
+            let memory_data_type =
+                get_memory_data_type(entry.section_type, entry.internal_index_in_section)?;
+            let descriptor = format!(
+                "data {} {} {}",
+                entry.full_name,
+                format_data_section_type(entry.section_type),
+                format_memory_data_type(memory_data_type)
+            );
+
+            Some(ExportSignature {
+                full_name: entry.full_name.clone(),
+                export_type: ExportType::Data,
+                section_type: Some(entry.section_type),
+                internal_index: entry.internal_index_in_section,
+                hash: compute_abi_hash(&descriptor),
+                descriptor,
+            })
+        });
+
+    function_signatures.chain(data_signatures).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{DataSectionType, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{DataNameEntry, ReadWriteDataEntry, TypeEntry},
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_object_file},
+        export_surface::{
+            collect_export_signatures, compare_export_surfaces, compute_abi_hash, get_export_hash,
+            ExportSurfaceDiff,
+        },
+        module_image::{ModuleImage, Visibility},
+    };
+
+    #[test]
+    fn test_compute_abi_hash() {
+        // the test vectors of FNV-1a 64
+        assert_eq!(compute_abi_hash(""), 0xcbf29ce484222325);
+        assert_eq!(compute_abi_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(compute_abi_hash("foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_export_surface() {
+        let build_module = |add_results: Vec<OperandDataType>, with_reset: bool| {
+            let mut stubs = vec![(
+                "add",
+                TypeEntry::new(vec![OperandDataType::I32], add_results),
+            )];
+            if with_reset {
+                stubs.push(("reset", TypeEntry::new(vec![], vec![])));
+            }
+
+            let image_binary = build_shared_module_scaffold("foo", &stubs).unwrap();
+            let mut image_common_entry = read_object_file(&image_binary).unwrap();
+            image_common_entry
+                .read_write_data_entries
+                .push(ReadWriteDataEntry::from_i64(0));
+            image_common_entry
+                .data_data_entries
+                .push(DataNameEntry::new(
+                    "foo::count".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::ReadWrite,
+                    0,
+                ));
+
+            let signatures = collect_export_signatures(&image_common_entry);
+
+            let mut image_binary: Vec<u8> = vec![];
+            write_object_file(&image_common_entry, true, &mut image_binary).unwrap();
+            (image_binary, signatures)
+        };
+
+        let (binary_v1, signatures_v1) = build_module(vec![OperandDataType::I32], true);
+
+        assert_eq!(
+            signatures_v1
+                .iter()
+                .map(|signature| signature.descriptor.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "function foo::add (i32) -> (i32)",
+                "function foo::reset () -> ()",
+                "data foo::count read_write i64"
+            ]
+        );
+
+        let image_v1 = ModuleImage::read(&binary_v1).unwrap();
+        assert_eq!(
+            get_export_hash(&image_v1, "foo::add"),
+            Some(compute_abi_hash("function foo::add (i32) -> (i32)"))
+        );
+        assert_eq!(
+            get_export_hash(&image_v1, "foo::count"),
+            Some(compute_abi_hash("data foo::count read_write i64"))
+        );
+        assert_eq!(get_export_hash(&image_v1, "foo::bar"), None);
+
+        // the same surface
+        assert!(compare_export_surfaces(&image_v1, &image_v1).is_compatible());
+
+        // changes the result type of "add" and removes "reset"
+        let (binary_v2, _) = build_module(vec![OperandDataType::I64], false);
+        let image_v2 = ModuleImage::read(&binary_v2).unwrap();
+        let diff = compare_export_surfaces(&image_v1, &image_v2);
+
+        assert_eq!(
+            diff,
+            ExportSurfaceDiff {
+                added: vec![],
+                removed: vec!["foo::reset".to_owned()],
+                changed: vec!["foo::add".to_owned()],
+            }
+        );
+        assert!(!diff.is_compatible());
+
+        // adding exports is compatible
+        let diff = compare_export_surfaces(&image_v2, &image_v1);
+        assert_eq!(diff.added, vec!["foo::reset".to_owned()]);
+        assert!(!diff.is_compatible()); // "add" is changed
+    }
+}
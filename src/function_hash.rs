@@ -0,0 +1,172 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The content hashes of the functions, for the AOT/JIT code caches which
+// are keyed by function rather than by the whole module.
+//
+// The hash of a function is computed over:
+//
+// - the signature, e.g. "(i32, i32) -> (i32)".
+// - the types of the local variables (the arguments are included).
+// - the canonicalized bytecode, i.e., the bytecode re-laid out with
+//   minimal alignment padding (see `bytecode_transform::realign_function_code`),
+//   so that the redundant `nop`s left by other tools do not change the hash.
+//   The bytecode is used as it is if the relocate list is absent.
+//
+// The hashes are stored in the optional "function hash" section, which is
+// written when `WriteOptions::emit_function_hashes` is enabled.
+
+use crate::{
+    bytecode_transform::realign_function_code,
+    entry::{
+        FunctionEntry, ImageCommonEntry, LocalVariableListEntry, RelocateListEntry, TypeEntry,
+    },
+    entry_dump::format_operand_data_types,
+    export_surface::compute_content_hash,
+    module_image::ModuleImage,
+};
+
+pub fn compute_function_hash(
+    type_entry: &TypeEntry,
+    local_variable_list_entry: &LocalVariableListEntry,
+    function_entry: &FunctionEntry,
+    relocate_list_entry: Option<&RelocateListEntry>,
+) -> u64 {
+    let mut code = function_entry.code.clone();
+    if let Some(relocate_list_entry) = relocate_list_entry {
+        // The bytecode is hashed as is if it can not be re-encoded.
+        let _ = realign_function_code(&mut code, &mut relocate_list_entry.clone());
+    }
+
+    let descriptor = format!(
+        "{} -> {} {}\n",
+        format_operand_data_types(&type_entry.params),
+        format_operand_data_types(&type_entry.results),
+        format_operand_data_types(&local_variable_list_entry.local_variable_types)
+    );
+
+    let mut data = descriptor.into_bytes();
+    data.extend_from_slice(&code);
+    compute_content_hash(&data)
+}
+
+/// Computes the hashes of all functions of the module entry,
+/// in the order of the function internal indices.
+pub fn compute_function_hashes(image_common_entry: &ImageCommonEntry) -> Vec<u64> {
+    image_common_entry
+        .function_entries
+        .iter()
+        .enumerate()
+        .map(|(function_internal_index, function_entry)| {
+            compute_function_hash(
+                &image_common_entry.type_entries[function_entry.type_index],
+                &image_common_entry.local_variable_list_entries
+                    [function_entry.local_variable_list_index],
+                function_entry,
+                image_common_entry
+                    .relocate_list_entries
+                    .get(function_internal_index),
+            )
+        })
+        .collect()
+}
+
+/// Returns the hash of the function recorded in the "function hash" section,
+/// or `None` if the section is absent.
+pub fn get_function_hash(image: &ModuleImage, function_internal_index: usize) -> Option<u64> {
+    image
+        .get_optional_function_hash_section()?
+        .get_function_hash(function_internal_index)
+}
+
+/// Checks whether the compiled code cached with the key `cache_key`
+/// (i.e., the hash of the function when it was compiled) is still valid.
+///
+/// Returns `None` if the image does not record the hash of the function,
+/// in which case the cache can not be verified.
+pub fn matches_code_cache_key(
+    image: &ModuleImage,
+    function_internal_index: usize,
+    cache_key: u64,
+) -> Option<bool> {
+    get_function_hash(image, function_internal_index).map(|hash| hash == cache_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry_reader::read_object_file,
+        entry_writer::{
+            build_minimal_module, write_object_file_with_options, MinimalFunctionEntry,
+            WriteOptions,
+        },
+        function_hash::{compute_function_hashes, get_function_hash, matches_code_cache_key},
+        module_image::ModuleImage,
+    };
+
+    #[test]
+    fn test_function_hashes() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let build_function = |params: Vec<OperandDataType>| MinimalFunctionEntry {
+            params,
+            results: vec![OperandDataType::I32],
+            local_variable_types_without_args: vec![],
+            code: code.clone(),
+        };
+
+        let image_binary = build_minimal_module(
+            "foo",
+            &[
+                build_function(vec![OperandDataType::I32]),
+                build_function(vec![OperandDataType::I64]),
+                build_function(vec![OperandDataType::I32]),
+            ],
+        )
+        .unwrap();
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+
+        // the hashes depend on the signatures and the code, rather than the indices
+        let hashes = compute_function_hashes(&image_common_entry);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0], hashes[2]);
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                emit_function_hashes: true,
+                ..Default::default()
+            },
+            &mut image_binary,
+        )
+        .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(get_function_hash(&module_image, 1), Some(hashes[1]));
+        assert_eq!(
+            matches_code_cache_key(&module_image, 0, hashes[0]),
+            Some(true)
+        );
+        assert_eq!(
+            matches_code_cache_key(&module_image, 0, hashes[1]),
+            Some(false)
+        );
+
+        // the section is not written by default
+        let image_binary = build_minimal_module("foo", &[build_function(vec![])]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        assert_eq!(matches_code_cache_key(&module_image, 0, hashes[0]), None);
+    }
+}
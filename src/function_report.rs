@@ -0,0 +1,259 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The overview of the functions of a module, one row per function,
+// ready for rendering as a table, e.g.:
+//
+// | index | name     | signature           | code | locals | calls |
+// |-------|----------|---------------------|------|--------|-------|
+// | 1     | foo::add | (i32, i32) -> (i32) | 24   | 16     | 2     |
+//
+// The imported functions are not listed since they have no code.
+//
+// The frame size summary (see `frame_size_summary`) collects the sizes of the
+// "local variable area" of the functions, e.g., for the runtimes to tune the
+// stack sizes, and for the compilers to check the frame bloat.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    bytecode_search::find_callers, entry_dump::format_operand_data_types, module_image::ModuleImage,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FrameSizeSummary {
+    // The size of the "local variable area" (the arguments are included) in bytes,
+    // of each (internal) function, in the order of function public indices,
+    // i.e., `(function_public_index, allocated_bytes)`.
+    pub functions: Vec<(usize, usize)>,
+
+    // The maximum size of the functions, it is `0` if there are no functions.
+    pub max_bytes: usize,
+
+    // The amount of functions of each size, in the order of the sizes,
+    // i.e., `(allocated_bytes, function_count)`.
+    pub histogram: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionReportRow {
+    pub function_public_index: usize,
+
+    // The full name in the "function name" section, it is `None`
+    // if the section is absent or the function is not named.
+    pub name: Option<String>,
+
+    // e.g. "(i32, i32) -> (i32)"
+    pub signature: String,
+
+    // The length of the bytecode in bytes.
+    pub code_size: usize,
+
+    // The size of the "local variable area" (the arguments are included) in bytes.
+    pub local_variable_bytes: usize,
+
+    // The amount of instructions which reference this function within the module,
+    // i.e., `call`, `get_function` and `host_addr_function`,
+    // see `bytecode_search::find_callers`.
+    pub call_count: usize,
+}
+
+/// Returns one row per (internal) function, in the order of function public indices.
+pub fn function_table_report(image: &ModuleImage) -> Vec<FunctionReportRow> {
+    let type_section = image.get_type_section();
+    let local_variable_section = image.get_local_variable_section();
+    let function_section = image.get_function_section();
+
+    let import_function_count = image
+        .get_optional_import_function_section()
+        .map_or(0, |section| section.items.len());
+
+    let function_name_entries = image
+        .get_optional_export_function_section()
+        .map(|section| section.convert_to_entries())
+        .unwrap_or_default();
+
+    // The call counts indexed by the function public index.
+    let call_counts = (0..(import_function_count + function_section.items.len()))
+        .map(|function_public_index| {
+            find_callers(image, function_public_index).map_or(0, |sites| sites.len())
+        })
+        .collect::<Vec<_>>();
+
+    function_section
+        .items
+        .iter()
+        .enumerate()
+        .map(|(function_internal_index, function_item)| {
+            let function_public_index = import_function_count + function_internal_index;
+
+            let (params, results) =
+                type_section.get_item_params_and_results(function_item.type_index as usize);
+            let local_variable_list =
+                &local_variable_section.lists[function_item.local_variable_list_index as usize];
+
+            let name = function_name_entries
+                .iter()
+                .find(|entry| entry.internal_index == function_internal_index)
+                .map(|entry| entry.full_name.clone());
+
+            FunctionReportRow {
+                function_public_index,
+                name,
+                signature: format!(
+                    "{} -> {}",
+                    format_operand_data_types(params),
+                    format_operand_data_types(results)
+                ),
+                code_size: function_item.code_length as usize,
+                local_variable_bytes: local_variable_list.allocated_bytes as usize,
+                call_count: call_counts[function_public_index],
+            }
+        })
+        .collect()
+}
+
+/// Collects the sizes of the "local variable area" of the (internal) functions.
+///
+/// The local variable lists of the blocks are not included.
+pub fn frame_size_summary(image: &ModuleImage) -> FrameSizeSummary {
+    let local_variable_section = image.get_local_variable_section();
+    let function_section = image.get_function_section();
+
+    let import_function_count = image
+        .get_optional_import_function_section()
+        .map_or(0, |section| section.items.len());
+
+    let functions = function_section
+        .items
+        .iter()
+        .enumerate()
+        .map(|(function_internal_index, function_item)| {
+            let local_variable_list =
+                &local_variable_section.lists[function_item.local_variable_list_index as usize];
+            (
+                import_function_count + function_internal_index,
+                local_variable_list.allocated_bytes as usize,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    for (_, allocated_bytes) in &functions {
+        *histogram.entry(*allocated_bytes).or_default() += 1;
+    }
+
+    FrameSizeSummary {
+        max_bytes: histogram.last_key_value().map_or(0, |(bytes, _)| *bytes),
+        histogram: histogram.into_iter().collect(),
+        functions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry_writer::{build_minimal_module, MinimalFunctionEntry},
+        function_report::{
+            frame_size_summary, function_table_report, FrameSizeSummary, FunctionReportRow,
+        },
+        module_image::ModuleImage,
+    };
+
+    #[test]
+    fn test_function_table_report() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let image_binary = build_minimal_module(
+            "foo",
+            &[
+                MinimalFunctionEntry {
+                    params: vec![],
+                    results: vec![],
+                    local_variable_types_without_args: vec![],
+                    code: code0.clone(),
+                },
+                MinimalFunctionEntry {
+                    params: vec![OperandDataType::I32, OperandDataType::I32],
+                    results: vec![OperandDataType::I32],
+                    local_variable_types_without_args: vec![OperandDataType::F64],
+                    code: code1.clone(),
+                },
+            ],
+        )
+        .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(
+            function_table_report(&module_image),
+            vec![
+                FunctionReportRow {
+                    function_public_index: 0,
+                    name: None,
+                    signature: "() -> ()".to_owned(),
+                    code_size: code0.len(),
+                    local_variable_bytes: 0,
+                    call_count: 0,
+                },
+                FunctionReportRow {
+                    function_public_index: 1,
+                    name: None,
+                    signature: "(i32, i32) -> (i32)".to_owned(),
+                    code_size: code1.len(),
+                    local_variable_bytes: 24,
+                    call_count: 2,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frame_size_summary() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let build_function = |params: Vec<OperandDataType>| MinimalFunctionEntry {
+            params,
+            results: vec![],
+            local_variable_types_without_args: vec![],
+            code: code.clone(),
+        };
+
+        let image_binary = build_minimal_module(
+            "foo",
+            &[
+                build_function(vec![OperandDataType::I32]),
+                build_function(vec![]),
+                build_function(vec![OperandDataType::I64]),
+                build_function(vec![OperandDataType::I64, OperandDataType::F32]),
+            ],
+        )
+        .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(
+            frame_size_summary(&module_image),
+            FrameSizeSummary {
+                functions: vec![(0, 8), (1, 0), (2, 8), (3, 16)],
+                max_bytes: 16,
+                histogram: vec![(0, 1), (8, 2), (16, 1)],
+            }
+        );
+    }
+}
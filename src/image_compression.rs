@@ -0,0 +1,476 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The compression of the image sections, it is used for reducing the size
+// of the images for distribution.
+//
+// The selected sections are compressed by the `SectionCodec` supplied by the caller,
+// this crate provides a simple built-in codec `LzCodec` (so that it does not
+// depend on any compression library), and the other codecs (e.g., zstd) can be
+// supplied by implementing the trait.
+//
+// - The sections which are not smaller after compression are kept as they are.
+// - The compressed section data replaces the original section data in the section table.
+//
+// The compression metadata is stored in the extra header of the image:
+//
+// |----------------------------------------------------------------|
+// | Image Flags (u32)            | Reserved (u32)                  | offset=16
+// | Algorithm (u32)              | Compressed Section Count (u32)  |
+// | Section Id 0 (u32)           | Original Length 0 (u32)         |
+// | Section Id 1 (u32)           | Original Length 1 (u32)         |
+// | ...                                                            |
+// |----------------------------------------------------------------|
+//
+// Bit 2 of the image flags (`IMAGE_FLAG_COMPRESSED`) is set, and `ModuleImage::read`
+// rejects the compressed images with `ImageErrorType::CompressedImage`, the image
+// must be decompressed by `decompress_image` first. The functions of `entry_reader`
+// decompress the images with the built-in codec `LzCodec` automatically.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    image_pipeline::ImageSections,
+    module_image::{read_image_flags, ModuleSectionId, IMAGE_FLAG_COMPRESSED},
+    ImageError, ImageErrorType,
+};
+
+// The algorithm id of `LzCodec`.
+pub const LZ_CODEC_ALGORITHM: u32 = 1;
+
+// The length of the fixed part of the extra header, i.e., the image flags,
+// the reserved field, the algorithm and the section count.
+const COMPRESSION_HEADER_FIXED_LENGTH: usize = 16;
+
+// The reasons why the sections can not be compressed or decompressed.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CompressionError {
+    AlreadyCompressed,
+    NotCompressed,
+    UnsupportedAlgorithm(u32),
+
+    // The section is listed in the compression metadata but absent in the image.
+    MissingSection(ModuleSectionId),
+
+    DecompressionFailed(ModuleSectionId),
+
+    // The trailer can not be appended to the compressed image,
+    // see `entry_writer::WriteOptions`.
+    TrailerUnsupported,
+}
+
+impl Display for CompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompressionError::AlreadyCompressed => write!(f, "The image is already compressed."),
+            CompressionError::NotCompressed => write!(f, "Not a compressed image."),
+            CompressionError::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "Unsupported compression algorithm {}.", algorithm)
+            }
+            CompressionError::MissingSection(section_id) => write!(
+                f,
+                "The compressed section \"{}\" is missing.",
+                section_id.name()
+            ),
+            CompressionError::DecompressionFailed(section_id) => write!(
+                f,
+                "Failed to decompress the section \"{}\".",
+                section_id.name()
+            ),
+            CompressionError::TrailerUnsupported => write!(
+                f,
+                "The trailer can not be appended to the compressed image."
+            ),
+        }
+    }
+}
+
+/// Compresses and decompresses the section data.
+pub trait SectionCodec {
+    /// Returns the id of the algorithm, it is recorded in the image.
+    fn algorithm(&self) -> u32;
+
+    fn compress(&self, data: &[u8]) -> Vec<u8>;
+
+    /// Returns the original data, or `None` if the data is malformed
+    /// or its length does not match `original_length`.
+    fn decompress(&self, data: &[u8], original_length: usize) -> Option<Vec<u8>>;
+}
+
+// The compression metadata of a compressed image.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CompressionInfo {
+    pub algorithm: u32,
+
+    // `(section id, original length)` of the compressed sections,
+    // in the order of the section table.
+    pub sections: Vec<(ModuleSectionId, u32)>,
+}
+
+impl CompressionInfo {
+    pub(crate) fn to_extra_header(&self) -> Vec<u8> {
+        let mut extra_header: Vec<u8> = vec![];
+        extra_header.extend_from_slice(&IMAGE_FLAG_COMPRESSED.to_le_bytes());
+        extra_header.extend_from_slice(&0u32.to_le_bytes()); // reserved
+        extra_header.extend_from_slice(&self.algorithm.to_le_bytes());
+        extra_header.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
+        for (section_id, original_length) in &self.sections {
+            extra_header.extend_from_slice(&(*section_id as u32).to_le_bytes());
+            extra_header.extend_from_slice(&original_length.to_le_bytes());
+        }
+        extra_header
+    }
+
+    // Returns `None` if the extra header is malformed.
+    pub(crate) fn from_extra_header(extra_header: &[u8]) -> Option<Self> {
+        let read_u32 = |offset: usize| {
+            extra_header
+                .get(offset..offset + 4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        };
+
+        let algorithm = read_u32(8)?;
+        let section_count = read_u32(12)? as usize;
+
+        let sections = (0..section_count)
+            .map(|idx| {
+                let offset = COMPRESSION_HEADER_FIXED_LENGTH + idx * 8;
+                let section_id = ModuleSectionId::try_from(read_u32(offset)?).ok()?;
+                Some((section_id, read_u32(offset + 4)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            algorithm,
+            sections,
+        })
+    }
+}
+
+/// Compresses the selected sections, the absent sections and the sections
+/// which are not smaller after compression are ignored.
+pub fn compress_sections(
+    image_sections: &mut ImageSections,
+    section_ids: &[ModuleSectionId],
+    codec: &dyn SectionCodec,
+) -> Result<(), ImageError> {
+    if image_sections.compression_info.is_some() {
+        return Err(ImageError::new(ImageErrorType::Compression(
+            CompressionError::AlreadyCompressed,
+        )));
+    }
+
+    let mut compressed_sections = vec![];
+
+    for (section_id, section_data) in image_sections.sections.iter_mut() {
+        if !section_ids.contains(section_id) {
+            continue;
+        }
+
+        let compressed_data = codec.compress(section_data);
+        if compressed_data.len() >= section_data.len() {
+            continue;
+        }
+
+        compressed_sections.push((*section_id, section_data.len() as u32));
+        *section_data = compressed_data;
+    }
+
+    if !compressed_sections.is_empty() {
+        image_sections.compression_info = Some(CompressionInfo {
+            algorithm: codec.algorithm(),
+            sections: compressed_sections,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the image is compressed.
+pub fn is_compressed_image(image_binary: &[u8]) -> bool {
+    read_image_flags(image_binary).is_some_and(|flags| flags & IMAGE_FLAG_COMPRESSED != 0)
+}
+
+/// Decompresses the image, and returns the plain image which can be read by `ModuleImage::read`.
+pub fn decompress_image(
+    image_binary: &[u8],
+    codec: &dyn SectionCodec,
+) -> Result<Vec<u8>, ImageError> {
+    let mut image_sections = ImageSections::read(image_binary)?;
+
+    let Some(compression_info) = image_sections.compression_info.take() else {
+        return Err(ImageError::new(ImageErrorType::Compression(
+            CompressionError::NotCompressed,
+        )));
+    };
+
+    if compression_info.algorithm != codec.algorithm() {
+        return Err(ImageError::new(ImageErrorType::Compression(
+            CompressionError::UnsupportedAlgorithm(compression_info.algorithm),
+        )));
+    }
+
+    for (section_id, original_length) in &compression_info.sections {
+        let section_data = image_sections
+            .sections
+            .iter_mut()
+            .find(|(id, _)| id == section_id)
+            .map(|(_, data)| data)
+            .ok_or_else(|| {
+                ImageError::new(ImageErrorType::Compression(
+                    CompressionError::MissingSection(*section_id),
+                ))
+            })?;
+
+        *section_data = codec
+            .decompress(section_data, *original_length as usize)
+            .ok_or_else(|| {
+                ImageError::new(ImageErrorType::Compression(
+                    CompressionError::DecompressionFailed(*section_id),
+                ))
+            })?;
+    }
+
+    let mut plain_image_binary: Vec<u8> = vec![];
+    image_sections.write(&mut plain_image_binary)?;
+
+    Ok(plain_image_binary)
+}
+
+// The built-in codec, a byte-oriented LZ77 variant.
+//
+// The compressed data is a sequence of tokens:
+//
+// - `0x00..=0x7f`: a literal run, followed by `token + 1` bytes.
+// - `0x80..=0xff`: a match of `(token & 0x7f) + 3` bytes, followed by the distance
+//   (u16, little-endian) from the current position back to the start of the match.
+pub struct LzCodec;
+
+const LZ_MAX_LITERAL_LENGTH: usize = 128;
+const LZ_MIN_MATCH_LENGTH: usize = 3;
+const LZ_MAX_MATCH_LENGTH: usize = 130;
+const LZ_MAX_DISTANCE: usize = u16::MAX as usize;
+
+impl SectionCodec for LzCodec {
+    fn algorithm(&self) -> u32 {
+        LZ_CODEC_ALGORITHM
+    }
+
+    fn compress(&self, data: &[u8]) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+
+        // The last position of each 3-byte sequence.
+        let mut positions: HashMap<[u8; 3], usize> = HashMap::new();
+        let mut literal_start = 0;
+        let mut position = 0;
+
+        while position + LZ_MIN_MATCH_LENGTH <= data.len() {
+            let key = [data[position], data[position + 1], data[position + 2]];
+            let opt_match_start = positions
+                .insert(key, position)
+                .filter(|match_start| position - match_start <= LZ_MAX_DISTANCE);
+
+            let Some(match_start) = opt_match_start else {
+                position += 1;
+                continue;
+            };
+
+            // The first 3 bytes always match, and the match may overlap
+            // the current position, e.g., a run of the same byte.
+            let match_length = (0..(data.len() - position).min(LZ_MAX_MATCH_LENGTH))
+                .take_while(|idx| data[match_start + idx] == data[position + idx])
+                .count();
+
+            write_literals(&mut output, &data[literal_start..position]);
+            output.push(0x80 | (match_length - LZ_MIN_MATCH_LENGTH) as u8);
+            output.extend_from_slice(&((position - match_start) as u16).to_le_bytes());
+
+            position += match_length;
+            literal_start = position;
+        }
+
+        write_literals(&mut output, &data[literal_start..]);
+        output
+    }
+
+    fn decompress(&self, data: &[u8], original_length: usize) -> Option<Vec<u8>> {
+        let mut output: Vec<u8> = vec![];
+        let mut position = 0;
+
+        while position < data.len() {
+            let token = data[position] as usize;
+            position += 1;
+
+            if token < 0x80 {
+                let literals = data.get(position..(position + token + 1))?;
+                output.extend_from_slice(literals);
+                position += token + 1;
+            } else {
+                let distance = data
+                    .get(position..(position + 2))
+                    .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()) as usize)?;
+                position += 2;
+
+                if distance == 0 || distance > output.len() {
+                    return None;
+                }
+
+                let match_start = output.len() - distance;
+                for idx in 0..((token & 0x7f) + LZ_MIN_MATCH_LENGTH) {
+                    output.push(output[match_start + idx]);
+                }
+            }
+
+            if output.len() > original_length {
+                return None;
+            }
+        }
+
+        (output.len() == original_length).then_some(output)
+    }
+}
+
+fn write_literals(output: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(LZ_MAX_LITERAL_LENGTH) {
+        output.push((chunk.len() - 1) as u8);
+        output.extend_from_slice(chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::ReadOnlyDataEntry,
+        image_compression::{
+            compress_sections, decompress_image, is_compressed_image, LzCodec, SectionCodec,
+        },
+        image_pipeline::ImageSections,
+        module_image::{ModuleImage, ModuleSectionId},
+        utils::helper_build_module_binary_with_single_function_and_data,
+        ImageErrorType,
+    };
+
+    #[test]
+    fn test_lz_codec() {
+        let codec = LzCodec;
+
+        let data_list: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![1],
+            vec![1, 2],
+            vec![0; 1000],
+            b"abcabcabcabd, hello world, hello world!".to_vec(),
+            (0..=255).cycle().take(5000).collect(),
+            (0..3000u32).map(|value| (value * 7 % 251) as u8).collect(),
+        ];
+
+        for data in data_list {
+            let compressed_data = codec.compress(&data);
+            assert_eq!(codec.decompress(&compressed_data, data.len()), Some(data));
+        }
+
+        // a run of the same byte is compressed to the matches of the maximum length
+        assert!(codec.compress(&[0; 1000]).len() < 40);
+
+        // malformed data
+        let compressed_data = codec.compress(b"hello hello hello");
+        assert_eq!(codec.decompress(&compressed_data, 16), None);
+        assert_eq!(
+            codec.decompress(&compressed_data[..compressed_data.len() - 1], 17),
+            None
+        );
+
+        // the distance exceeds the output
+        assert_eq!(codec.decompress(&[0x00, 0x11, 0x80, 0x02, 0x00], 4), None);
+        assert_eq!(codec.decompress(&[0x80, 0x00, 0x00], 3), None);
+    }
+
+    #[test]
+    fn test_compress_and_decompress_image() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let image_binary = helper_build_module_binary_with_single_function_and_data(
+            &[],
+            &[OperandDataType::I32],
+            &[],
+            code,
+            &[ReadOnlyDataEntry::from_bytes(vec![0x11; 256], 1)],
+            &[],
+            &[],
+        );
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut image_sections = ImageSections::from_module_image(&module_image);
+        compress_sections(
+            &mut image_sections,
+            &[ModuleSectionId::Function, ModuleSectionId::ReadOnlyData],
+            &LzCodec,
+        )
+        .unwrap();
+
+        // the function section is too small to be compressed
+        assert_eq!(
+            image_sections
+                .compression_info
+                .as_ref()
+                .unwrap()
+                .sections
+                .iter()
+                .map(|(section_id, _)| *section_id)
+                .collect::<Vec<_>>(),
+            vec![ModuleSectionId::ReadOnlyData]
+        );
+
+        // cannot be compressed twice
+        assert!(
+            compress_sections(&mut image_sections, &[ModuleSectionId::Function], &LzCodec).is_err()
+        );
+
+        let mut compressed_binary: Vec<u8> = vec![];
+        image_sections.write(&mut compressed_binary).unwrap();
+
+        assert!(is_compressed_image(&compressed_binary));
+        assert!(!is_compressed_image(&image_binary));
+        assert!(compressed_binary.len() < image_binary.len());
+        assert!(matches!(
+            ModuleImage::read(&compressed_binary)
+                .unwrap_err()
+                .error_type,
+            ImageErrorType::CompressedImage
+        ));
+
+        let decompressed_binary = decompress_image(&compressed_binary, &LzCodec).unwrap();
+        let decompressed_image = ModuleImage::read(&decompressed_binary).unwrap();
+        assert_eq!(
+            decompressed_image.get_function_section(),
+            module_image.get_function_section()
+        );
+        assert_eq!(
+            decompressed_image.get_optional_read_only_data_section(),
+            module_image.get_optional_read_only_data_section()
+        );
+
+        // truncated image
+        let mut corrupted_binary = compressed_binary.clone();
+        let length = corrupted_binary.len();
+        corrupted_binary.truncate(length - 4);
+        assert!(decompress_image(&corrupted_binary, &LzCodec).is_err());
+
+        assert_eq!(
+            decompress_image(&image_binary, &LzCodec)
+                .unwrap_err()
+                .to_string(),
+            "Compression error: Not a compressed image."
+        );
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The incremental editor of the images, for the iterative tools which
+// modify an image and write it repeatedly, e.g., patch one data item and write.
+//
+// The editor keeps the image binary emitted last time, and tracks the
+// changes since then:
+//
+// - The dirty regions, i.e., the modified bytes of the sections.
+// - Whether the layout is dirty, i.e., sections are added, removed or resized.
+//
+// When emitting, if the layout is unchanged, only the dirty regions are
+// copied into the image binary, so the cost is proportional to the amount
+// of the changed bytes. Otherwise the whole image is composed again
+// (see `ImageSections::write`).
+//
+// The CRC-32 checksums of the sections are computed lazily and cached,
+// only the checksums of the dirty sections are recomputed.
+//
+// Note: the emitted image does not contain the trailer, because its checksum
+// covers the whole image, use `ModuleImage::write_with_trailer` for the final output.
+
+use std::{fmt::Display, ops::Range};
+
+use crate::{
+    image_pipeline::ImageSections,
+    module_image::{compute_crc32, ModuleImage, ModuleSectionId},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why a section can not be edited.
+#[derive(Debug, PartialEq, Clone)]
+pub enum EditError {
+    MissingSection(ModuleSectionId),
+    RegionOutOfBounds {
+        section_id: ModuleSectionId,
+        region: Range<usize>,
+        section_length: usize,
+    },
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::MissingSection(section_id) => {
+                write!(f, "Cannot find the section {:?}.", section_id)
+            }
+            EditError::RegionOutOfBounds {
+                section_id,
+                region,
+                section_length,
+            } => write!(
+                f,
+                "The region {}..{} is out of the section {:?} ({} bytes).",
+                region.start, region.end, section_id, section_length
+            ),
+        }
+    }
+}
+
+pub struct ImageEditor {
+    image_sections: ImageSections,
+
+    // The image binary emitted last time, it is empty before the first emitting.
+    image_binary: Vec<u8>,
+
+    // The offsets (in bytes) of the sections in `image_binary`,
+    // in the order of `image_sections.sections`.
+    section_offsets: Vec<usize>,
+
+    // The modified regions of the sections since the last emitting,
+    // the range is relative to the start of the section data.
+    dirty_regions: Vec<(ModuleSectionId, Range<usize>)>,
+
+    // Sections are added, removed or resized since the last emitting.
+    is_layout_dirty: bool,
+
+    // The cached CRC-32 checksums of the section data.
+    section_checksums: Vec<(ModuleSectionId, u32)>,
+}
+
+impl ImageEditor {
+    pub fn new(module_image: &ModuleImage) -> Self {
+        Self {
+            image_sections: ImageSections::from_module_image(module_image),
+            image_binary: vec![],
+            section_offsets: vec![],
+            dirty_regions: vec![],
+            is_layout_dirty: true,
+            section_checksums: vec![],
+        }
+    }
+
+    pub fn get_image_sections(&self) -> &ImageSections {
+        &self.image_sections
+    }
+
+    pub fn get_section_data(&self, section_id: ModuleSectionId) -> Option<&[u8]> {
+        self.image_sections.get_section_data(section_id)
+    }
+
+    /// Replaces the data of the existing section, or inserts a new section.
+    ///
+    /// The layout is unchanged if the length of the data is the same as the old one.
+    pub fn set_section_data(&mut self, section_id: ModuleSectionId, section_data: Vec<u8>) {
+        match self.image_sections.get_section_data(section_id) {
+            Some(data) if data.len() == section_data.len() => {
+                self.dirty_regions.push((section_id, 0..section_data.len()));
+            }
+            _ => {
+                self.is_layout_dirty = true;
+            }
+        }
+
+        self.invalidate_checksum(section_id);
+        self.image_sections
+            .set_section_data(section_id, section_data);
+    }
+
+    /// Overwrites the bytes of the section at the specified offset, e.g., a data item,
+    /// the layout is unchanged.
+    pub fn patch_section_data(
+        &mut self,
+        section_id: ModuleSectionId,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<(), ImageError> {
+        let Some((_, section_data)) = self
+            .image_sections
+            .sections
+            .iter_mut()
+            .find(|(id, _)| *id == section_id)
+        else {
+            return Err(ImageError::new(ImageErrorType::Edit(
+                EditError::MissingSection(section_id),
+            )));
+        };
+
+        let end = offset + data.len();
+        if end > section_data.len() {
+            return Err(ImageError::new(ImageErrorType::Edit(
+                EditError::RegionOutOfBounds {
+                    section_id,
+                    region: offset..end,
+                    section_length: section_data.len(),
+                },
+            )));
+        }
+
+        section_data[offset..end].copy_from_slice(data);
+        self.dirty_regions.push((section_id, offset..end));
+        self.invalidate_checksum(section_id);
+        Ok(())
+    }
+
+    pub fn remove_section(&mut self, section_id: ModuleSectionId) {
+        if self.image_sections.get_section_data(section_id).is_some() {
+            self.is_layout_dirty = true;
+            self.invalidate_checksum(section_id);
+            self.image_sections.remove_section(section_id);
+        }
+    }
+
+    /// Returns the ids of the sections which are modified since the last emitting,
+    /// in the order of modification.
+    pub fn get_dirty_section_ids(&self) -> Vec<ModuleSectionId> {
+        let mut section_ids: Vec<ModuleSectionId> = vec![];
+        for (section_id, _) in &self.dirty_regions {
+            if !section_ids.contains(section_id) {
+                section_ids.push(*section_id);
+            }
+        }
+        section_ids
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_layout_dirty || !self.dirty_regions.is_empty()
+    }
+
+    pub fn is_layout_dirty(&self) -> bool {
+        self.is_layout_dirty
+    }
+
+    /// Returns the CRC-32 checksum of the section data, it is computed
+    /// only if the section is modified since the last computing.
+    pub fn get_section_checksum(&mut self, section_id: ModuleSectionId) -> Option<u32> {
+        if let Some((_, checksum)) = self
+            .section_checksums
+            .iter()
+            .find(|(id, _)| *id == section_id)
+        {
+            return Some(*checksum);
+        }
+
+        let checksum = compute_crc32(self.image_sections.get_section_data(section_id)?);
+        self.section_checksums.push((section_id, checksum));
+        Some(checksum)
+    }
+
+    /// Returns the image binary with all modifications applied.
+    pub fn emit(&mut self) -> Result<&[u8], ImageError> {
+        if self.is_layout_dirty {
+            self.compose()?;
+        } else {
+            for (section_id, range) in &self.dirty_regions {
+                let section_index = self
+                    .image_sections
+                    .sections
+                    .iter()
+                    .position(|(id, _)| id == section_id)
+                    .unwrap();
+                let section_data = &self.image_sections.sections[section_index].1;
+                let start = self.section_offsets[section_index] + range.start;
+                let end = self.section_offsets[section_index] + range.end;
+
+                self.image_binary[start..end].copy_from_slice(&section_data[range.clone()]);
+            }
+        }
+
+        self.dirty_regions.clear();
+        self.is_layout_dirty = false;
+        Ok(&self.image_binary)
+    }
+
+    // Writes the whole image and records the offsets of the sections.
+    fn compose(&mut self) -> Result<(), ImageError> {
+        let mut image_binary: Vec<u8> = vec![];
+        self.image_sections.write(&mut image_binary)?;
+
+        let module_image = ModuleImage::read(&image_binary)?;
+
+        // The data area of the image is a sub-slice of the image binary.
+        let data_area_offset =
+            module_image.sections_data.as_ptr() as usize - image_binary.as_ptr() as usize;
+        let section_offsets = module_image
+            .items
+            .iter()
+            .map(|item| data_area_offset + item.offset as usize)
+            .collect();
+
+        self.section_offsets = section_offsets;
+        self.image_binary = image_binary;
+        Ok(())
+    }
+
+    fn invalidate_checksum(&mut self, section_id: ModuleSectionId) {
+        self.section_checksums.retain(|(id, _)| *id != section_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::TypeEntry,
+        entry_writer::build_shared_module_scaffold,
+        image_editor::ImageEditor,
+        module_image::{compute_crc32, ModuleImage, ModuleSectionId},
+    };
+
+    #[test]
+    fn test_image_editor() {
+        let image_binary =
+            build_shared_module_scaffold("foo", &[("bar", TypeEntry::new(vec![], vec![]))])
+                .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut editor = ImageEditor::new(&module_image);
+        assert!(editor.is_layout_dirty());
+
+        let emitted_binary = editor.emit().unwrap().to_vec();
+        assert!(!editor.is_dirty());
+        assert!(ModuleImage::read(&emitted_binary).is_ok());
+
+        // patch the bytes, the layout is unchanged
+        let type_checksum = editor.get_section_checksum(ModuleSectionId::Type).unwrap();
+        let function_data = editor
+            .get_section_data(ModuleSectionId::Function)
+            .unwrap()
+            .to_vec();
+        let last_offset = function_data.len() - 4;
+
+        editor
+            .patch_section_data(ModuleSectionId::Function, last_offset, &[1, 2, 3, 4])
+            .unwrap();
+        assert!(!editor.is_layout_dirty());
+        assert_eq!(
+            editor.get_dirty_section_ids(),
+            vec![ModuleSectionId::Function]
+        );
+
+        let patched_binary = editor.emit().unwrap().to_vec();
+        assert!(!editor.is_dirty());
+
+        let mut expected_binary: Vec<u8> = vec![];
+        editor
+            .get_image_sections()
+            .write(&mut expected_binary)
+            .unwrap();
+        assert_eq!(patched_binary, expected_binary);
+        assert_ne!(patched_binary, emitted_binary);
+
+        // the checksums
+        assert_eq!(
+            editor.get_section_checksum(ModuleSectionId::Type),
+            Some(type_checksum)
+        );
+        let mut expected_function_data = function_data.clone();
+        expected_function_data[last_offset..].copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(
+            editor.get_section_checksum(ModuleSectionId::Function),
+            Some(compute_crc32(&expected_function_data))
+        );
+
+        // changes the layout
+        editor.set_section_data(ModuleSectionId::Relocate, vec![0, 0, 0, 0, 0, 0, 0, 0]);
+        editor.remove_section(ModuleSectionId::FunctionName);
+        assert!(editor.is_layout_dirty());
+
+        let rewritten_binary = editor.emit().unwrap().to_vec();
+        let rewritten_image = ModuleImage::read(&rewritten_binary).unwrap();
+        assert!(rewritten_image.get_optional_relocate_section().is_some());
+        assert!(rewritten_image
+            .get_optional_export_function_section()
+            .is_none());
+
+        // errors
+        assert_eq!(
+            editor
+                .patch_section_data(ModuleSectionId::FunctionName, 0, &[0])
+                .unwrap_err()
+                .to_string(),
+            "Edit error: Cannot find the section FunctionName."
+        );
+        assert!(editor
+            .patch_section_data(ModuleSectionId::Relocate, 6, &[0, 0, 0])
+            .is_err());
+    }
+}
@@ -0,0 +1,728 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The encryption envelope of the images, it is used by the vendors
+// who ship the proprietary modules.
+//
+// The selected sections (by default the function section and the read-only data
+// section) are encrypted with an AEAD algorithm, the other sections are kept as they are,
+// so the tools can still list the sections and read the property of the module.
+//
+// The encryption is performed by the `KeyProvider` supplied by the caller, so that
+// the keys never leave it (e.g., a hardware security module), and this crate does
+// not depend on any cryptography library.
+//
+// - Each section is encrypted with its own nonce, which is the 8-byte base nonce
+//   followed by the section id (u32, little-endian), so the nonces of the sections
+//   never collide as long as the base nonce is unique for each image encrypted
+//   with the same key.
+// - The associated data is the section id (u32, little-endian), followed by
+//   the extra header and the section table (see `build_metadata`), so that
+//   the encrypted sections can not be swapped, and the metadata can not be
+//   altered undetected.
+// - The encrypted section data (i.e., the ciphertext and the tag) replaces
+//   the original section data in the section table.
+//
+// The encryption metadata is stored in the extra header of the image:
+//
+// |----------------------------------------------------------------|
+// | Image Flags (u32)            | Reserved (u32)                  | offset=16
+// | Algorithm (u32)              | Key Id (u32)                    |
+// | Nonce Length (u32)           | Encrypted Section Count (u32)   |
+// | Encrypted Section Id 0 (u32) | Encrypted Section Id 1 (u32)    |
+// | ...                                                            |
+// | Base Nonce (bytes, padded to 4 bytes)                          |
+// |----------------------------------------------------------------|
+//
+// Bit 1 of the image flags (`IMAGE_FLAG_ENCRYPTED`) is set, and `ModuleImage::read`
+// rejects the encrypted images with `ImageErrorType::EncryptedImage`, the image
+// must be decrypted by `decrypt_image` first.
+
+use std::fmt::Display;
+
+use crate::{
+    image_pipeline::ImageSections,
+    module_image::{
+        read_image_flags, ImageType, ModuleImage, ModuleSectionId, BASE_MODULE_HEADER_LENGTH,
+        BASE_SECTION_HEADER_LENGTH, IMAGE_FLAG_ENCRYPTED,
+    },
+    ImageError, ImageErrorType,
+};
+
+pub const DEFAULT_ENCRYPTED_SECTION_IDS: [ModuleSectionId; 2] =
+    [ModuleSectionId::Function, ModuleSectionId::ReadOnlyData];
+
+// The length of the fixed part of the extra header, i.e., the image flags,
+// the reserved field, the algorithm, the key id, the nonce length and the section count.
+const ENCRYPTION_HEADER_FIXED_LENGTH: usize = 24;
+
+// The reasons why the sections can not be encrypted or decrypted.
+#[derive(Debug, PartialEq, Clone)]
+pub enum EncryptionError {
+    NonceLengthMismatch {
+        expected_length: usize,
+        actual_length: usize,
+    },
+    EncryptionFailed(ModuleSectionId),
+    NotEncrypted,
+    MalformedHeader,
+    UnsupportedAlgorithm(u32),
+    MalformedSectionTable,
+
+    // The section is listed in the encryption metadata but absent in the image.
+    MissingSection(ModuleSectionId),
+
+    // The key is wrong, or the section (or the metadata) has been tampered with.
+    DecryptionFailed(ModuleSectionId),
+}
+
+impl Display for EncryptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncryptionError::NonceLengthMismatch {
+                expected_length,
+                actual_length,
+            } => write!(
+                f,
+                "The length of the nonce ({}) does not match the algorithm ({}).",
+                actual_length, expected_length
+            ),
+            EncryptionError::EncryptionFailed(section_id) => write!(
+                f,
+                "Failed to encrypt the section \"{}\".",
+                section_id.name()
+            ),
+            EncryptionError::NotEncrypted => write!(f, "Not an encrypted image."),
+            EncryptionError::MalformedHeader => write!(f, "The encryption header is malformed."),
+            EncryptionError::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "Unsupported encryption algorithm {}.", algorithm)
+            }
+            EncryptionError::MalformedSectionTable => write!(f, "The section table is malformed."),
+            EncryptionError::MissingSection(section_id) => write!(
+                f,
+                "The encrypted section \"{}\" is missing.",
+                section_id.name()
+            ),
+            EncryptionError::DecryptionFailed(section_id) => write!(
+                f,
+                "Failed to decrypt the section \"{}\".",
+                section_id.name()
+            ),
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EncryptionAlgorithm {
+    Aes256Gcm = 1,
+    ChaCha20Poly1305,
+}
+
+impl EncryptionAlgorithm {
+    pub fn nonce_length(&self) -> usize {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm | EncryptionAlgorithm::ChaCha20Poly1305 => 12,
+        }
+    }
+
+    /// Returns the length of the base nonce, the remaining 4 bytes
+    /// of the nonce are the section id.
+    pub fn base_nonce_length(&self) -> usize {
+        self.nonce_length() - 4
+    }
+
+    fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(EncryptionAlgorithm::Aes256Gcm),
+            2 => Some(EncryptionAlgorithm::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+}
+
+/// Performs the AEAD operations with the key identified by `key_id`.
+pub trait KeyProvider {
+    /// Returns the ciphertext followed by the tag,
+    /// or `None` if the key is not available.
+    fn seal(
+        &self,
+        algorithm: EncryptionAlgorithm,
+        key_id: u32,
+        nonce: &[u8],
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Option<Vec<u8>>;
+
+    /// Returns the plaintext, or `None` if the key is not available
+    /// or the authentication fails.
+    fn open(
+        &self,
+        algorithm: EncryptionAlgorithm,
+        key_id: u32,
+        nonce: &[u8],
+        associated_data: &[u8],
+        ciphertext: &[u8],
+    ) -> Option<Vec<u8>>;
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct EncryptionOptions {
+    pub algorithm: EncryptionAlgorithm,
+    pub key_id: u32,
+
+    // The base nonce, its length must be `algorithm.base_nonce_length()`,
+    // and it must be unique for each image encrypted with the same key.
+    pub nonce: Vec<u8>,
+
+    // The sections to be encrypted, the absent sections are ignored.
+    pub section_ids: Vec<ModuleSectionId>,
+}
+
+// The encryption metadata of an encrypted image.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EncryptionInfo {
+    pub algorithm: EncryptionAlgorithm,
+    pub key_id: u32,
+    pub nonce: Vec<u8>,
+
+    // The encrypted sections, in the order of the section table.
+    pub section_ids: Vec<ModuleSectionId>,
+}
+
+/// Writes the image with the selected sections encrypted.
+pub fn encrypt_image(
+    image: &ModuleImage,
+    options: &EncryptionOptions,
+    key_provider: &dyn KeyProvider,
+    writer: &mut dyn std::io::Write,
+) -> Result<(), ImageError> {
+    if options.nonce.len() != options.algorithm.base_nonce_length() {
+        return Err(ImageError::new(ImageErrorType::Encryption(
+            EncryptionError::NonceLengthMismatch {
+                expected_length: options.algorithm.base_nonce_length(),
+                actual_length: options.nonce.len(),
+            },
+        )));
+    }
+
+    let mut image_sections = ImageSections::from_module_image(image);
+    let encrypted_section_ids = image_sections
+        .sections
+        .iter()
+        .map(|(section_id, _)| *section_id)
+        .filter(|section_id| options.section_ids.contains(section_id))
+        .collect::<Vec<_>>();
+
+    let mut extra_header: Vec<u8> = vec![];
+    extra_header.extend_from_slice(&IMAGE_FLAG_ENCRYPTED.to_le_bytes());
+    extra_header.extend_from_slice(&0u32.to_le_bytes()); // reserved
+    extra_header.extend_from_slice(&(options.algorithm as u32).to_le_bytes());
+    extra_header.extend_from_slice(&options.key_id.to_le_bytes());
+    extra_header.extend_from_slice(&(options.nonce.len() as u32).to_le_bytes());
+    extra_header.extend_from_slice(&(encrypted_section_ids.len() as u32).to_le_bytes());
+    for section_id in &encrypted_section_ids {
+        extra_header.extend_from_slice(&(*section_id as u32).to_le_bytes());
+    }
+    extra_header.extend_from_slice(&options.nonce);
+    extra_header.resize(extra_header.len().next_multiple_of(4), 0);
+
+    let metadata = build_metadata(
+        &extra_header,
+        &image_sections.sections,
+        &encrypted_section_ids,
+    );
+
+    for (section_id, section_data) in image_sections.sections.iter_mut() {
+        if !encrypted_section_ids.contains(section_id) {
+            continue;
+        }
+
+        *section_data = key_provider
+            .seal(
+                options.algorithm,
+                options.key_id,
+                &derive_section_nonce(&options.nonce, *section_id),
+                &build_associated_data(*section_id, &metadata),
+                section_data,
+            )
+            .ok_or_else(|| {
+                ImageError::new(ImageErrorType::Encryption(
+                    EncryptionError::EncryptionFailed(*section_id),
+                ))
+            })?;
+    }
+
+    // The composed image has no extra header, the extra header is
+    // inserted after the base header, and the extra header length is updated.
+    let mut image_binary: Vec<u8> = vec![];
+    image_sections.write(&mut image_binary)?;
+
+    image_binary[10..12].copy_from_slice(&(extra_header.len() as u16).to_le_bytes());
+
+    writer
+        .write_all(&image_binary[..BASE_MODULE_HEADER_LENGTH])
+        .and_then(|_| writer.write_all(&extra_header))
+        .and_then(|_| writer.write_all(&image_binary[BASE_MODULE_HEADER_LENGTH..]))
+        .map_err(ImageError::from)
+}
+
+/// Returns `true` if the image is encrypted.
+pub fn is_encrypted_image(image_binary: &[u8]) -> bool {
+    read_image_flags(image_binary).is_some_and(|flags| flags & IMAGE_FLAG_ENCRYPTED != 0)
+}
+
+/// Reads the encryption metadata, e.g., for selecting the key.
+pub fn read_encryption_info(image_binary: &[u8]) -> Result<EncryptionInfo, ImageError> {
+    read_encryption_header(image_binary).map(|(encryption_info, _)| encryption_info)
+}
+
+/// Decrypts the image, and returns the plain image which can be read by `ModuleImage::read`.
+pub fn decrypt_image(
+    image_binary: &[u8],
+    key_provider: &dyn KeyProvider,
+) -> Result<Vec<u8>, ImageError> {
+    let (encryption_info, body_start) = read_encryption_header(image_binary)?;
+
+    let image_type =
+        ImageType::try_from(u16::from_le_bytes(image_binary[8..10].try_into().unwrap()))?;
+
+    let mut sections = read_sections(&image_binary[body_start..])?;
+
+    if let Some(section_id) = encryption_info
+        .section_ids
+        .iter()
+        .find(|section_id| !sections.iter().any(|(id, _)| id == *section_id))
+    {
+        return Err(ImageError::new(ImageErrorType::Encryption(
+            EncryptionError::MissingSection(*section_id),
+        )));
+    }
+
+    let metadata = build_metadata(
+        &image_binary[BASE_MODULE_HEADER_LENGTH..body_start],
+        &sections,
+        &encryption_info.section_ids,
+    );
+
+    for (section_id, section_data) in sections.iter_mut() {
+        if !encryption_info.section_ids.contains(section_id) {
+            continue;
+        }
+
+        *section_data = key_provider
+            .open(
+                encryption_info.algorithm,
+                encryption_info.key_id,
+                &derive_section_nonce(&encryption_info.nonce, *section_id),
+                &build_associated_data(*section_id, &metadata),
+                section_data,
+            )
+            .ok_or_else(|| {
+                ImageError::new(ImageErrorType::Encryption(
+                    EncryptionError::DecryptionFailed(*section_id),
+                ))
+            })?;
+    }
+
+    let mut plain_image_binary: Vec<u8> = vec![];
+    ImageSections {
+        image_type,
+        sections,
+        compression_info: None,
+    }
+    .write(&mut plain_image_binary)?;
+
+    Ok(plain_image_binary)
+}
+
+// The nonce of a section is the base nonce followed by the section id (u32, little-endian).
+fn derive_section_nonce(nonce: &[u8], section_id: ModuleSectionId) -> Vec<u8> {
+    let mut section_nonce = nonce.to_vec();
+    section_nonce.extend_from_slice(&(section_id as u32).to_le_bytes());
+    section_nonce
+}
+
+// Returns the metadata which is bound to each encrypted section, i.e., the extra
+// header (includes the encryption header) followed by the section table.
+//
+// Each item of the section table is `(section id, length)`, the offsets are omitted
+// since the sections are laid out in order, and the lengths of the encrypted sections
+// are zero since they are not known until the sections are encrypted.
+fn build_metadata(
+    extra_header: &[u8],
+    sections: &[(ModuleSectionId, Vec<u8>)],
+    encrypted_section_ids: &[ModuleSectionId],
+) -> Vec<u8> {
+    let mut metadata = extra_header.to_vec();
+    metadata.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+    for (section_id, section_data) in sections {
+        let length = if encrypted_section_ids.contains(section_id) {
+            0
+        } else {
+            section_data.len() as u32
+        };
+        metadata.extend_from_slice(&(*section_id as u32).to_le_bytes());
+        metadata.extend_from_slice(&length.to_le_bytes());
+    }
+    metadata
+}
+
+fn build_associated_data(section_id: ModuleSectionId, metadata: &[u8]) -> Vec<u8> {
+    let mut associated_data = (section_id as u32).to_le_bytes().to_vec();
+    associated_data.extend_from_slice(metadata);
+    associated_data
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+// Returns the encryption metadata and the start position of the image body.
+fn read_encryption_header(image_binary: &[u8]) -> Result<(EncryptionInfo, usize), ImageError> {
+    if !is_encrypted_image(image_binary) {
+        return Err(ImageError::new(ImageErrorType::Encryption(
+            EncryptionError::NotEncrypted,
+        )));
+    }
+
+    let malformed_error =
+        || ImageError::new(ImageErrorType::Encryption(EncryptionError::MalformedHeader));
+
+    let extra_header_length = u16::from_le_bytes(image_binary[10..12].try_into().unwrap()) as usize;
+    let body_start = BASE_MODULE_HEADER_LENGTH + extra_header_length;
+    let extra_header = image_binary
+        .get(BASE_MODULE_HEADER_LENGTH..body_start)
+        .filter(|data| data.len() >= ENCRYPTION_HEADER_FIXED_LENGTH)
+        .ok_or_else(malformed_error)?;
+
+    let algorithm_value = read_u32(extra_header, 8).unwrap();
+    let algorithm = EncryptionAlgorithm::from_u32(algorithm_value).ok_or(ImageError::new(
+        ImageErrorType::Encryption(EncryptionError::UnsupportedAlgorithm(algorithm_value)),
+    ))?;
+    let key_id = read_u32(extra_header, 12).unwrap();
+    let nonce_length = read_u32(extra_header, 16).unwrap() as usize;
+    let section_count = read_u32(extra_header, 20).unwrap() as usize;
+
+    if nonce_length != algorithm.base_nonce_length() {
+        return Err(malformed_error());
+    }
+
+    let section_ids = (0..section_count)
+        .map(|idx| {
+            read_u32(extra_header, ENCRYPTION_HEADER_FIXED_LENGTH + idx * 4)
+                .and_then(|value| ModuleSectionId::try_from(value).ok())
+                .ok_or_else(malformed_error)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let nonce_start = ENCRYPTION_HEADER_FIXED_LENGTH + section_count * 4;
+    let nonce = extra_header
+        .get(nonce_start..nonce_start + nonce_length)
+        .ok_or_else(malformed_error)?
+        .to_vec();
+
+    Ok((
+        EncryptionInfo {
+            algorithm,
+            key_id,
+            nonce,
+            section_ids,
+        },
+        body_start,
+    ))
+}
+
+// Reads the sections of the image body, the section data is not checked
+// since the encrypted sections can not be parsed.
+fn read_sections(image_body: &[u8]) -> Result<Vec<(ModuleSectionId, Vec<u8>)>, ImageError> {
+    let malformed_error = || {
+        ImageError::new(ImageErrorType::Encryption(
+            EncryptionError::MalformedSectionTable,
+        ))
+    };
+
+    // Each item of the section table is `(section id, offset, length)`.
+    let item_count = read_u32(image_body, 0).ok_or_else(malformed_error)? as usize;
+    let data_area_start = BASE_SECTION_HEADER_LENGTH + item_count * 12;
+    let data_area = image_body
+        .get(data_area_start..)
+        .ok_or_else(malformed_error)?;
+
+    (0..item_count)
+        .map(|idx| {
+            let item_offset = BASE_SECTION_HEADER_LENGTH + idx * 12;
+            let section_id = ModuleSectionId::try_from(read_u32(image_body, item_offset).unwrap())
+                .map_err(|_| malformed_error())?;
+            let offset = read_u32(image_body, item_offset + 4).unwrap() as usize;
+            let length = read_u32(image_body, item_offset + 8).unwrap() as usize;
+
+            let section_data = data_area
+                .get(offset..offset + length)
+                .ok_or_else(malformed_error)?;
+            Ok((section_id, section_data.to_vec()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::ReadOnlyDataEntry,
+        image_encryption::{
+            decrypt_image, derive_section_nonce, encrypt_image, is_encrypted_image,
+            read_encryption_info, EncryptionAlgorithm, EncryptionInfo, EncryptionOptions,
+            KeyProvider, DEFAULT_ENCRYPTED_SECTION_IDS,
+        },
+        module_image::{ModuleImage, ModuleSectionId},
+        utils::helper_build_module_binary_with_single_function_and_data,
+        ImageErrorType,
+    };
+
+    // A toy AEAD for testing: XOR with the key and the nonce,
+    // followed by a 4-byte checksum as the tag.
+    struct TestKeyProvider {
+        key_id: u32,
+        key: u8,
+    }
+
+    impl TestKeyProvider {
+        fn compute_tag(&self, nonce: &[u8], associated_data: &[u8], plaintext: &[u8]) -> [u8; 4] {
+            let sum = nonce
+                .iter()
+                .chain(associated_data)
+                .chain(plaintext)
+                .fold(self.key as u32, |acc, byte| {
+                    acc.wrapping_mul(31).wrapping_add(*byte as u32)
+                });
+            sum.to_le_bytes()
+        }
+
+        fn xor(&self, nonce: &[u8], data: &[u8]) -> Vec<u8> {
+            data.iter()
+                .enumerate()
+                .map(|(idx, byte)| byte ^ self.key ^ nonce[idx % nonce.len()])
+                .collect()
+        }
+    }
+
+    impl KeyProvider for TestKeyProvider {
+        fn seal(
+            &self,
+            _algorithm: EncryptionAlgorithm,
+            key_id: u32,
+            nonce: &[u8],
+            associated_data: &[u8],
+            plaintext: &[u8],
+        ) -> Option<Vec<u8>> {
+            if key_id != self.key_id {
+                return None;
+            }
+
+            let mut ciphertext = self.xor(nonce, plaintext);
+            ciphertext.extend_from_slice(&self.compute_tag(nonce, associated_data, plaintext));
+            Some(ciphertext)
+        }
+
+        fn open(
+            &self,
+            _algorithm: EncryptionAlgorithm,
+            key_id: u32,
+            nonce: &[u8],
+            associated_data: &[u8],
+            ciphertext: &[u8],
+        ) -> Option<Vec<u8>> {
+            if key_id != self.key_id || ciphertext.len() < 4 {
+                return None;
+            }
+
+            let (data, tag) = ciphertext.split_at(ciphertext.len() - 4);
+            let plaintext = self.xor(nonce, data);
+            (self.compute_tag(nonce, associated_data, &plaintext) == tag).then_some(plaintext)
+        }
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt_image() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let image_binary = helper_build_module_binary_with_single_function_and_data(
+            &[],
+            &[OperandDataType::I32],
+            &[],
+            code,
+            &[ReadOnlyDataEntry::from_bytes(b"secret".to_vec(), 1)],
+            &[],
+            &[],
+        );
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let key_provider = TestKeyProvider {
+            key_id: 7,
+            key: 0x5a,
+        };
+        let nonce = (1..=8).collect::<Vec<u8>>();
+        let options = EncryptionOptions {
+            algorithm: EncryptionAlgorithm::ChaCha20Poly1305,
+            key_id: 7,
+            nonce: nonce.clone(),
+            section_ids: DEFAULT_ENCRYPTED_SECTION_IDS.to_vec(),
+        };
+
+        let mut encrypted_binary: Vec<u8> = vec![];
+        encrypt_image(
+            &module_image,
+            &options,
+            &key_provider,
+            &mut encrypted_binary,
+        )
+        .unwrap();
+
+        assert!(is_encrypted_image(&encrypted_binary));
+        assert!(!is_encrypted_image(&image_binary));
+        assert!(!encrypted_binary
+            .windows(6)
+            .any(|window| window == b"secret"));
+        assert!(matches!(
+            ModuleImage::read(&encrypted_binary).unwrap_err().error_type,
+            ImageErrorType::EncryptedImage
+        ));
+
+        assert_eq!(
+            read_encryption_info(&encrypted_binary).unwrap(),
+            EncryptionInfo {
+                algorithm: EncryptionAlgorithm::ChaCha20Poly1305,
+                key_id: 7,
+                nonce,
+                section_ids: vec![ModuleSectionId::Function, ModuleSectionId::ReadOnlyData],
+            }
+        );
+
+        let decrypted_binary = decrypt_image(&encrypted_binary, &key_provider).unwrap();
+        let decrypted_image = ModuleImage::read(&decrypted_binary).unwrap();
+        assert_eq!(
+            decrypted_image.get_function_section(),
+            module_image.get_function_section()
+        );
+        assert_eq!(
+            decrypted_image.get_optional_read_only_data_section(),
+            module_image.get_optional_read_only_data_section()
+        );
+
+        // wrong key
+        assert!(decrypt_image(
+            &encrypted_binary,
+            &TestKeyProvider {
+                key_id: 7,
+                key: 0x11
+            }
+        )
+        .is_err());
+
+        // tampered nonce, it follows the fixed part and the 2 section ids of the extra header
+        let mut tampered_binary = encrypted_binary.clone();
+        tampered_binary[16 + 24 + 8] ^= 0xff;
+        assert!(decrypt_image(&tampered_binary, &key_provider).is_err());
+
+        // tampered algorithm and reserved field of the extra header,
+        // they are bound to the encrypted sections
+        let mut tampered_binary = encrypted_binary.clone();
+        tampered_binary[16 + 8] = EncryptionAlgorithm::Aes256Gcm as u8;
+        assert!(decrypt_image(&tampered_binary, &key_provider).is_err());
+
+        let mut tampered_binary = encrypted_binary.clone();
+        tampered_binary[16 + 4] = 1;
+        assert!(decrypt_image(&tampered_binary, &key_provider).is_err());
+
+        // removed encrypted section id, i.e., the section count is changed to 1
+        let mut tampered_binary = encrypted_binary.clone();
+        tampered_binary[16 + 20] = 1;
+        assert!(decrypt_image(&tampered_binary, &key_provider).is_err());
+
+        assert_eq!(
+            decrypt_image(&image_binary, &key_provider)
+                .unwrap_err()
+                .to_string(),
+            "Encryption error: Not an encrypted image."
+        );
+    }
+
+    #[test]
+    fn test_encrypt_image_with_invalid_nonce() {
+        let image_binary = helper_build_module_binary_with_single_function_and_data(
+            &[],
+            &[],
+            &[],
+            BytecodeWriterHelper::new()
+                .append_opcode(Opcode::end)
+                .to_bytes(),
+            &[],
+            &[],
+            &[],
+        );
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let key_provider = TestKeyProvider {
+            key_id: 7,
+            key: 0x5a,
+        };
+
+        // the base nonce must not contain the section id part
+        let options = EncryptionOptions {
+            algorithm: EncryptionAlgorithm::Aes256Gcm,
+            key_id: 7,
+            nonce: vec![0; 12],
+            section_ids: DEFAULT_ENCRYPTED_SECTION_IDS.to_vec(),
+        };
+
+        let mut encrypted_binary: Vec<u8> = vec![];
+        assert_eq!(
+            encrypt_image(
+                &module_image,
+                &options,
+                &key_provider,
+                &mut encrypted_binary
+            )
+            .unwrap_err()
+            .to_string(),
+            "Encryption error: The length of the nonce (12) does not match the algorithm (8)."
+        );
+    }
+
+    #[test]
+    fn test_derive_section_nonce() {
+        // the nonces of the adjacent base nonces never collide
+        let nonce0 = derive_section_nonce(&[0, 0, 0, 0, 0, 0, 0, 1], ModuleSectionId::Type);
+        let nonce1 = derive_section_nonce(&[0, 0, 0, 0, 0, 0, 0, 2], ModuleSectionId::Property);
+        assert_eq!(nonce0.len(), 12);
+        assert_ne!(nonce0, nonce1);
+        assert_eq!(
+            derive_section_nonce(&[1, 2, 3, 4, 5, 6, 7, 8], ModuleSectionId::Function),
+            vec![
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                ModuleSectionId::Function as u8,
+                0,
+                0,
+                0
+            ]
+        );
+    }
+}
@@ -0,0 +1,850 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Builds an object file from a declarative manifest (an ASON text), it is the
+// canonical "module description" for the tests and the small tools which
+// do not have a compiler, e.g.:
+//
+// ```ason
+// {
+//     name: "foo"
+//     version: "1.0.0"
+//     imports: [
+//         {
+//             name: "bar"
+//             dependency: ModuleDependency::Local({...})
+//             functions: [
+//                 { name: "add", params: ["i32", "i32"], results: ["i32"] }
+//             ]
+//             data: [
+//                 { name: "count", section_type: "read_write", memory_data_type: "i32" }
+//             ]
+//         }
+//     ]
+//     types: []
+//     local_variable_lists: []
+//     data: [
+//         {
+//             name: "number"
+//             visibility: "public"
+//             section_type: "read_only"
+//             value: DataValue::I32(11)
+//         }
+//     ]
+//     functions: [
+//         {
+//             name: "main"
+//             visibility: "public"
+//             params: []
+//             results: ["i32"]
+//             locals: []
+//             code: FunctionCode::Assembly([
+//                 "imm_i32 11"
+//                 "end"
+//             ])
+//         }
+//     ]
+// }
+// ```
+//
+// The names of the items do not include the module name, e.g. the full name of
+// the function "main" above is "foo::main", and the full name of the imported
+// function "add" is "bar::add".
+//
+// The code of a function is either a bytecode file (the path is relative to the
+// manifest file) or the text assembly, see `assemble_bytecode`. The indices in
+// the code are:
+//
+// - function public index: the imported functions (in the order of the manifest)
+//   followed by the functions of the manifest.
+// - data public index: the imported data followed by the read-only data,
+//   the read-write data and the uninitialized data of the manifest, see `public_index`.
+// - type index and local variable list index: the items of `types` and
+//   `local_variable_lists` of the manifest, followed by the ones of the functions.
+//
+// The relocate lists are generated from the bytecode, see `relocate_coverage`.
+
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anc_isa::{
+    DataSectionType, EffectiveVersion, MemoryDataType, ModuleDependency, OperandDataType,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bytecode_reader::{build_instruction_params, Instruction},
+    bytecode_writer::{BytecodeWriter, InstructionError},
+    entry::{
+        infer_data_align, DataNameEntry, FunctionEntry, FunctionNameEntry, ImageCommonEntry,
+        ImportDataEntry, ImportFunctionEntry, ImportModuleEntry, LocalVariableListEntry,
+        ReadOnlyDataEntry, ReadWriteDataEntry, RelocateListEntry, TypeEntry, UninitDataEntry,
+    },
+    entry_dump::format_visibility,
+    entry_writer::{find_or_append, write_object_file},
+    module_image::{ImageType, Visibility},
+    module_interface::{
+        parse_data_section_type_name, parse_memory_data_type_name, parse_operand_data_type_names,
+    },
+    opcode_info::{find_opcode_by_name, get_opcode_info},
+    relocate_coverage::get_expected_relocate_entries,
+    ImageError, ImageErrorType,
+};
+
+// The reasons why the manifest can not be converted into an image.
+#[derive(Debug)]
+pub enum ManifestError {
+    // The message of the ASON parser.
+    Malformed(String),
+
+    ManifestFileUnreadable {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    BytecodeFileUnreadable {
+        function_name: String,
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    // The text assembly of the function can not be assembled, `error` is
+    // one of the assembly errors below.
+    FunctionAssembly {
+        function_name: String,
+        error: Box<ManifestError>,
+    },
+
+    // The assembly errors, see `assemble_bytecode`.
+    UnknownInstruction {
+        name: String,
+        line_number: usize,
+    },
+    InvalidOperand {
+        token: String,
+        line_number: usize,
+    },
+    OperandCountMismatch {
+        name: String,
+        expected_count: usize,
+        actual_count: usize,
+        line_number: usize,
+    },
+    InvalidInstruction {
+        error: InstructionError,
+        line_number: usize,
+    },
+
+    InvalidVersion(String),
+    InvalidOperandDataTypes(Vec<String>),
+    InvalidDataSectionType(String),
+    InvalidMemoryDataType(String),
+    InvalidVisibility(String),
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::Malformed(message) => write!(f, "Malformed manifest: {}", message),
+            ManifestError::ManifestFileUnreadable { path, error } => write!(
+                f,
+                "Failed to read the manifest file \"{}\": {}",
+                path.display(),
+                error
+            ),
+            ManifestError::BytecodeFileUnreadable {
+                function_name,
+                path,
+                error,
+            } => write!(
+                f,
+                "Failed to read the bytecode file \"{}\" of function \"{}\": {}",
+                path.display(),
+                function_name,
+                error
+            ),
+            ManifestError::FunctionAssembly {
+                function_name,
+                error,
+            } => write!(
+                f,
+                "Failed to assemble function \"{}\": {}",
+                function_name, error
+            ),
+            ManifestError::UnknownInstruction { name, line_number } => write!(
+                f,
+                "Unknown instruction \"{}\" at line {}.",
+                name, line_number
+            ),
+            ManifestError::InvalidOperand { token, line_number } => {
+                write!(f, "Invalid operand \"{}\" at line {}.", token, line_number)
+            }
+            ManifestError::OperandCountMismatch {
+                name,
+                expected_count,
+                actual_count,
+                line_number,
+            } => write!(
+                f,
+                "Instruction \"{}\" requires {} operand(s) but {} are given at line {}.",
+                name, expected_count, actual_count, line_number
+            ),
+            ManifestError::InvalidInstruction { error, line_number } => {
+                write!(f, "{} (line {})", error, line_number)
+            }
+            ManifestError::InvalidVersion(text) => write!(f, "Invalid version \"{}\".", text),
+            ManifestError::InvalidOperandDataTypes(names) => {
+                write!(f, "Invalid operand data types \"{:?}\".", names)
+            }
+            ManifestError::InvalidDataSectionType(name) => {
+                write!(f, "Invalid data section type \"{}\".", name)
+            }
+            ManifestError::InvalidMemoryDataType(name) => {
+                write!(f, "Invalid memory data type \"{}\".", name)
+            }
+            ManifestError::InvalidVisibility(name) => {
+                write!(f, "Invalid visibility \"{}\".", name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImageManifest {
+    pub name: String,
+
+    // e.g. "1.0.0".
+    pub version: String,
+
+    pub imports: Vec<ManifestImportModule>,
+
+    // The additional types and local variable lists for the blocks.
+    pub types: Vec<ManifestType>,
+    pub local_variable_lists: Vec<Vec<String>>,
+
+    pub data: Vec<ManifestData>,
+    pub functions: Vec<ManifestFunction>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestImportModule {
+    pub name: String,
+    pub dependency: ModuleDependency,
+    pub functions: Vec<ManifestImportFunction>,
+    pub data: Vec<ManifestImportData>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestImportFunction {
+    pub name: String,
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestImportData {
+    pub name: String,
+    pub section_type: String,
+    pub memory_data_type: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestType {
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestData {
+    pub name: String,
+    pub visibility: String,
+    pub section_type: String,
+
+    // The content is ignored for the uninitialized data,
+    // only the type and the length are used.
+    pub value: DataValue,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum DataValue {
+    I32(u32),
+    I64(u64),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFunction {
+    pub name: String,
+    pub visibility: String,
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+
+    // The local variables, not including the arguments.
+    pub locals: Vec<String>,
+
+    pub code: FunctionCode,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum FunctionCode {
+    // The path of the bytecode file, relative to the manifest file.
+    File(String),
+
+    // The lines of the text assembly.
+    Assembly(Vec<String>),
+}
+
+impl ImageManifest {
+    /// Deserializes the manifest from an ASON text.
+    pub fn from_ason_str(text: &str) -> Result<Self, ImageError> {
+        ason::from_str(text).map_err(|error| {
+            ImageError::new(ImageErrorType::Manifest(ManifestError::Malformed(
+                error.to_string(),
+            )))
+        })
+    }
+}
+
+/// Reads the manifest file and builds the object file,
+/// the bytecode files are relative to the directory of the manifest file.
+pub fn build_object_file_from_manifest_file(path: &Path) -> Result<Vec<u8>, ImageError> {
+    let text = std::fs::read_to_string(path).map_err(|error| {
+        ImageError::new(ImageErrorType::Manifest(
+            ManifestError::ManifestFileUnreadable {
+                path: path.to_owned(),
+                error,
+            },
+        ))
+    })?;
+
+    let manifest = ImageManifest::from_ason_str(&text)?;
+    build_object_file_from_manifest(&manifest, path.parent().unwrap_or(Path::new("")))
+}
+
+/// Builds the object file from the manifest,
+/// the bytecode files are relative to `base_dir`.
+pub fn build_object_file_from_manifest(
+    manifest: &ImageManifest,
+    base_dir: &Path,
+) -> Result<Vec<u8>, ImageError> {
+    let image_common_entry = build_image_common_entry_from_manifest(manifest, base_dir)?;
+
+    let mut image_binary: Vec<u8> = vec![];
+    write_object_file(&image_common_entry, false, &mut image_binary)?;
+    Ok(image_binary)
+}
+
+/// Converts the manifest into the entries of an object file.
+pub fn build_image_common_entry_from_manifest(
+    manifest: &ImageManifest,
+    base_dir: &Path,
+) -> Result<ImageCommonEntry, ImageError> {
+    let version = parse_version(&manifest.version)?;
+
+    let mut type_entries = manifest
+        .types
+        .iter()
+        .map(|type_item| parse_type(&type_item.params, &type_item.results))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut local_variable_list_entries = manifest
+        .local_variable_lists
+        .iter()
+        .map(|names| parse_operand_data_types(names).map(LocalVariableListEntry::new))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // imports
+    let mut import_module_entries = vec![];
+    let mut import_function_entries = vec![];
+    let mut import_data_entries = vec![];
+
+    for (import_module_index, import_module) in manifest.imports.iter().enumerate() {
+        import_module_entries.push(ImportModuleEntry::new(
+            import_module.name.clone(),
+            Box::new(import_module.dependency.clone()),
+        ));
+
+        for function in &import_module.functions {
+            let type_index = find_or_append(
+                &mut type_entries,
+                parse_type(&function.params, &function.results)?,
+            );
+            import_function_entries.push(ImportFunctionEntry::new(
+                format!("{}::{}", import_module.name, function.name),
+                import_module_index,
+                type_index,
+            ));
+        }
+
+        for data_item in &import_module.data {
+            import_data_entries.push(ImportDataEntry::new(
+                format!("{}::{}", import_module.name, data_item.name),
+                import_module_index,
+                parse_section_type(&data_item.section_type)?,
+                parse_memory_data_type(&data_item.memory_data_type)?,
+            ));
+        }
+    }
+
+    // data
+    let mut read_only_data_entries = vec![];
+    let mut read_write_data_entries = vec![];
+    let mut uninit_data_entries = vec![];
+    let mut data_name_entries = vec![];
+
+    for data_item in &manifest.data {
+        let section_type = parse_section_type(&data_item.section_type)?;
+        let internal_index_in_section = match section_type {
+            DataSectionType::ReadOnly => {
+                read_only_data_entries.push(match &data_item.value {
+                    DataValue::I32(value) => ReadOnlyDataEntry::from_i32(*value),
+                    DataValue::I64(value) => ReadOnlyDataEntry::from_i64(*value),
+                    DataValue::F32(value) => ReadOnlyDataEntry::from_f32(*value),
+                    DataValue::F64(value) => ReadOnlyDataEntry::from_f64(*value),
+                    DataValue::Bytes(data) => {
+                        ReadOnlyDataEntry::from_bytes_auto_align(data.clone())
+                    }
+                });
+                read_only_data_entries.len() - 1
+            }
+            DataSectionType::ReadWrite => {
+                read_write_data_entries.push(match &data_item.value {
+                    DataValue::I32(value) => ReadWriteDataEntry::from_i32(*value),
+                    DataValue::I64(value) => ReadWriteDataEntry::from_i64(*value),
+                    DataValue::F32(value) => ReadWriteDataEntry::from_f32(*value),
+                    DataValue::F64(value) => ReadWriteDataEntry::from_f64(*value),
+                    DataValue::Bytes(data) => {
+                        ReadWriteDataEntry::from_bytes_auto_align(data.clone())
+                    }
+                });
+                read_write_data_entries.len() - 1
+            }
+            DataSectionType::Uninit => {
+                uninit_data_entries.push(match &data_item.value {
+                    DataValue::I32(_) => UninitDataEntry::from_i32(),
+                    DataValue::I64(_) => UninitDataEntry::from_i64(),
+                    DataValue::F32(_) => UninitDataEntry::from_f32(),
+                    DataValue::F64(_) => UninitDataEntry::from_f64(),
+                    DataValue::Bytes(data) => {
+                        UninitDataEntry::from_bytes(data.len() as u32, infer_data_align(data.len()))
+                    }
+                });
+                uninit_data_entries.len() - 1
+            }
+        };
+
+        data_name_entries.push(DataNameEntry::new(
+            format!("{}::{}", manifest.name, data_item.name),
+            parse_visibility(&data_item.visibility)?,
+            section_type,
+            internal_index_in_section,
+        ));
+    }
+
+    // functions
+    let mut function_entries = vec![];
+    let mut function_name_entries = vec![];
+    let mut relocate_list_entries = vec![];
+
+    for (function_internal_index, function) in manifest.functions.iter().enumerate() {
+        let type_index = find_or_append(
+            &mut type_entries,
+            parse_type(&function.params, &function.results)?,
+        );
+
+        // The local variable list of a function starts with the arguments.
+        let mut local_variable_types = parse_operand_data_types(&function.params)?;
+        local_variable_types.extend(parse_operand_data_types(&function.locals)?);
+        let local_variable_list_index = find_or_append(
+            &mut local_variable_list_entries,
+            LocalVariableListEntry::new(local_variable_types),
+        );
+
+        let code = match &function.code {
+            FunctionCode::File(file_path) => {
+                let path = base_dir.join(file_path);
+                std::fs::read(&path).map_err(|error| {
+                    ImageError::new(ImageErrorType::Manifest(
+                        ManifestError::BytecodeFileUnreadable {
+                            function_name: function.name.clone(),
+                            path: path.clone(),
+                            error,
+                        },
+                    ))
+                })?
+            }
+            FunctionCode::Assembly(lines) => {
+                assemble_bytecode(&lines.join("\n")).map_err(|error| match error.error_type {
+                    ImageErrorType::Manifest(manifest_error) => {
+                        ImageError::new(ImageErrorType::Manifest(ManifestError::FunctionAssembly {
+                            function_name: function.name.clone(),
+                            error: Box::new(manifest_error),
+                        }))
+                    }
+                    error_type => ImageError::new(error_type),
+                })?
+            }
+        };
+
+        relocate_list_entries.push(RelocateListEntry::new(get_expected_relocate_entries(&code)));
+        function_entries.push(FunctionEntry::new(
+            type_index,
+            local_variable_list_index,
+            code,
+        ));
+        function_name_entries.push(FunctionNameEntry::new(
+            format!("{}::{}", manifest.name, function.name),
+            parse_visibility(&function.visibility)?,
+            function_internal_index,
+        ));
+    }
+
+    Ok(ImageCommonEntry {
+        name: manifest.name.clone(),
+        version,
+        image_type: ImageType::ObjectFile,
+        type_entries,
+        local_variable_list_entries,
+        function_entries,
+        read_only_data_entries,
+        read_write_data_entries,
+        uninit_data_entries,
+        import_module_entries,
+        import_function_entries,
+        import_data_entries,
+        function_name_entries,
+        data_data_entries: data_name_entries,
+        relocate_list_entries,
+        external_library_entries: vec![],
+        external_function_entries: vec![],
+        initializer_entries: vec![],
+    })
+}
+
+/// Assembles the text assembly into bytecode.
+///
+/// Each line is an instruction, i.e., the opcode name followed by the operands,
+/// the operands are decimal or hexadecimal (with the prefix "0x") integers, e.g.:
+///
+/// ```text
+/// imm_i32 11
+/// local_load_i32_u 0 1  // layers and index
+/// imm_i64 0x1 0x0       // low and high 32 bits
+/// end
+/// ```
+///
+/// The operands are in the order of the operands of the opcode (see `opcode_info`),
+/// the padding `nop` instructions are inserted automatically. The empty lines
+/// and the comments (start with "//") are ignored.
+pub fn assemble_bytecode(text: &str) -> Result<Vec<u8>, ImageError> {
+    let mut writer = BytecodeWriter::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let content = match line.split_once("//") {
+            Some((content, _)) => content,
+            None => line,
+        };
+
+        let mut tokens = content.split_whitespace();
+        let Some(name) = tokens.next() else {
+            continue;
+        };
+
+        let opcode = find_opcode_by_name(name).ok_or_else(|| {
+            ImageError::new(ImageErrorType::Manifest(
+                ManifestError::UnknownInstruction {
+                    name: name.to_owned(),
+                    line_number,
+                },
+            ))
+        })?;
+
+        let values = tokens
+            .map(|token| {
+                parse_operand_value(token).ok_or_else(|| {
+                    ImageError::new(ImageErrorType::Manifest(ManifestError::InvalidOperand {
+                        token: token.to_owned(),
+                        line_number,
+                    }))
+                })
+            })
+            .collect::<Result<Vec<u32>, _>>()?;
+
+        let operand_count = get_opcode_info(opcode).operands.len();
+        if values.len() != operand_count {
+            return Err(ImageError::new(ImageErrorType::Manifest(
+                ManifestError::OperandCountMismatch {
+                    name: name.to_owned(),
+                    expected_count: operand_count,
+                    actual_count: values.len(),
+                    line_number,
+                },
+            )));
+        }
+
+        let instruction = Instruction::new(opcode, build_instruction_params(opcode, &values));
+        writer
+            .write_instruction(&instruction)
+            .map_err(|error| match error.error_type {
+                ImageErrorType::Instruction(instruction_error) => ImageError::new(
+                    ImageErrorType::Manifest(ManifestError::InvalidInstruction {
+                        error: instruction_error,
+                        line_number,
+                    }),
+                ),
+                error_type => ImageError::new(error_type),
+            })?;
+    }
+
+    Ok(writer.to_bytes())
+}
+
+fn parse_operand_value(token: &str) -> Option<u32> {
+    match token.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => token.parse::<u32>().ok(),
+    }
+}
+
+fn parse_version(text: &str) -> Result<EffectiveVersion, ImageError> {
+    let version_numbers = text
+        .split('.')
+        .map(|number| number.parse::<u16>().ok())
+        .collect::<Option<Vec<_>>>();
+
+    match version_numbers.as_deref() {
+        Some([major, minor, patch]) => Ok(EffectiveVersion::new(*major, *minor, *patch)),
+        _ => Err(ImageError::new(ImageErrorType::Manifest(
+            ManifestError::InvalidVersion(text.to_owned()),
+        ))),
+    }
+}
+
+fn parse_operand_data_types(names: &[String]) -> Result<Vec<OperandDataType>, ImageError> {
+    parse_operand_data_type_names(names).ok_or_else(|| {
+        ImageError::new(ImageErrorType::Manifest(
+            ManifestError::InvalidOperandDataTypes(names.to_vec()),
+        ))
+    })
+}
+
+fn parse_type(params: &[String], results: &[String]) -> Result<TypeEntry, ImageError> {
+    Ok(TypeEntry::new(
+        parse_operand_data_types(params)?,
+        parse_operand_data_types(results)?,
+    ))
+}
+
+fn parse_section_type(name: &str) -> Result<DataSectionType, ImageError> {
+    parse_data_section_type_name(name).ok_or_else(|| {
+        ImageError::new(ImageErrorType::Manifest(
+            ManifestError::InvalidDataSectionType(name.to_owned()),
+        ))
+    })
+}
+
+fn parse_memory_data_type(name: &str) -> Result<MemoryDataType, ImageError> {
+    parse_memory_data_type_name(name).ok_or_else(|| {
+        ImageError::new(ImageErrorType::Manifest(
+            ManifestError::InvalidMemoryDataType(name.to_owned()),
+        ))
+    })
+}
+
+fn parse_visibility(name: &str) -> Result<Visibility, ImageError> {
+    [Visibility::Private, Visibility::Public, Visibility::Package]
+        .into_iter()
+        .find(|visibility| format_visibility(*visibility) == name)
+        .ok_or_else(|| {
+            ImageError::new(ImageErrorType::Manifest(ManifestError::InvalidVisibility(
+                name.to_owned(),
+            )))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use anc_isa::{
+        opcode::Opcode, DataSectionType, DependencyCondition, DependencyLocal, ModuleDependency,
+        OperandDataType,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            ImportFunctionEntry, ImportModuleEntry, ReadOnlyDataEntry, RelocateEntry,
+            RelocateListEntry, TypeEntry,
+        },
+        entry_reader::read_object_file,
+        image_manifest::{
+            assemble_bytecode, build_object_file_from_manifest, DataValue, FunctionCode,
+            ImageManifest,
+        },
+        module_image::{RelocateType, Visibility},
+    };
+
+    #[test]
+    fn test_assemble_bytecode() {
+        let code = assemble_bytecode(
+            "\
+            // comment
+            imm_i32 11
+
+            local_load_i32_u 0 0x1  // layers and index
+            call 2
+            end",
+        )
+        .unwrap();
+
+        assert_eq!(
+            code,
+            BytecodeWriterHelper::new()
+                .append_opcode_i32(Opcode::imm_i32, 11)
+                .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 1)
+                .append_opcode_i32(Opcode::call, 2)
+                .append_opcode(Opcode::end)
+                .to_bytes()
+        );
+
+        assert!(assemble_bytecode("foo 1")
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown instruction \"foo\" at line 1"));
+        assert!(assemble_bytecode("end\nimm_i32")
+            .unwrap_err()
+            .to_string()
+            .contains("at line 2"));
+        assert!(matches!(
+            assemble_bytecode("imm_i32 abc").unwrap_err().error_type,
+            ImageErrorType::Manifest(ManifestError::InvalidOperand { token, line_number: 1 })
+                if token == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_build_object_file_from_manifest() {
+        let dependency = ModuleDependency::Local(Box::new(DependencyLocal {
+            path: "../bar".to_owned(),
+            condition: DependencyCondition::True,
+            parameters: HashMap::default(),
+        }));
+
+        let text = format!(
+            r#"{{
+                name: "foo"
+                version: "1.2.3"
+                imports: [
+                    {{
+                        name: "bar"
+                        dependency: {}
+                        functions: [
+                            {{ name: "add", params: ["i32", "i32"], results: ["i32"] }}
+                        ]
+                        data: []
+                    }}
+                ]
+                types: []
+                local_variable_lists: []
+                data: [
+                    {{
+                        name: "number"
+                        visibility: "public"
+                        section_type: "read_only"
+                        value: DataValue::I32(11)
+                    }}
+                ]
+                functions: [
+                    {{
+                        name: "main"
+                        visibility: "public"
+                        params: []
+                        results: ["i32"]
+                        locals: []
+                        code: FunctionCode::Assembly([
+                            "data_load_i32_u 0 0"
+                            "data_load_i32_u 0 0"
+                            "call 0"
+                            "end"
+                        ])
+                    }}
+                ]
+            }}"#,
+            ason::to_string(&dependency).unwrap()
+        );
+
+        let manifest = ImageManifest::from_ason_str(&text).unwrap();
+        assert_eq!(manifest.data[0].value, DataValue::I32(11));
+        assert!(matches!(
+            manifest.functions[0].code,
+            FunctionCode::Assembly(_)
+        ));
+
+        let image_binary = build_object_file_from_manifest(&manifest, Path::new("")).unwrap();
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+
+        assert_eq!(image_common_entry.name, "foo");
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![
+                TypeEntry::new(
+                    vec![OperandDataType::I32, OperandDataType::I32],
+                    vec![OperandDataType::I32]
+                ),
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.import_module_entries,
+            vec![ImportModuleEntry::new(
+                "bar".to_owned(),
+                Box::new(dependency)
+            )]
+        );
+        assert_eq!(
+            image_common_entry.import_function_entries,
+            vec![ImportFunctionEntry::new("bar::add".to_owned(), 0, 0)]
+        );
+        assert_eq!(
+            image_common_entry.read_only_data_entries,
+            vec![ReadOnlyDataEntry::from_i32(11)]
+        );
+        assert_eq!(
+            image_common_entry.data_data_entries[0].section_type,
+            DataSectionType::ReadOnly
+        );
+
+        let function_name_entry = &image_common_entry.function_name_entries[0];
+        assert_eq!(function_name_entry.full_name, "foo::main");
+        assert_eq!(function_name_entry.visibility, Visibility::Public);
+        assert_eq!(image_common_entry.function_entries[0].type_index, 1);
+        assert_eq!(
+            image_common_entry.relocate_list_entries,
+            vec![RelocateListEntry::new(vec![
+                RelocateEntry::new(4, RelocateType::DataPublicIndex),
+                RelocateEntry::new(12, RelocateType::DataPublicIndex),
+                RelocateEntry::new(20, RelocateType::FunctionPublicIndex),
+            ])]
+        );
+
+        // invalid manifest
+        let text = text.replace("\"public\"", "\"open\"");
+        let manifest = ImageManifest::from_ason_str(&text).unwrap();
+        assert_eq!(
+            build_object_file_from_manifest(&manifest, Path::new(""))
+                .unwrap_err()
+                .to_string(),
+            "Manifest error: Invalid visibility \"open\"."
+        );
+    }
+}
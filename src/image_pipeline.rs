@@ -0,0 +1,588 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The objcopy-style transformation pipeline.
+//
+// A pipeline consists of a sequence of transforms, the image is split into
+// sections (see `ImageSections`), each transform modifies the sections
+// in place, and the result is written once at the end.
+//
+// The transforms operate on the raw section data, so the sections which
+// are not touched by any transform are copied as they are.
+//
+// The following transforms are provided:
+//
+// - `StripSections`: removes the specified optional sections.
+// - `RenameModule`: changes the module name, the full names in the
+//   "function name" and "data name" sections (and the ABI hashes in the
+//   "export hash" section) are updated as well.
+// - `AddSection`: adds a section, or replaces the existing one.
+// - `CompressSections`: compresses the specified sections with the codec
+//   supplied by the caller, see `image_compression`.
+// - `SignImage`: signs the image with the signer supplied by the caller,
+//   see `image_signature`. It should be the last transform, since the signature
+//   covers all other sections.
+
+use std::fmt::Display;
+
+use crate::{
+    common_sections::{
+        data_name_section::DataNameSection,
+        export_hash_section::ExportHashSection,
+        function_name_section::FunctionNameSection,
+        property_section::{PropertySection, MODULE_NAME_BUFFER_LENGTH},
+    },
+    datatableaccess::read_section_with_table_and_data_area,
+    export_surface::{collect_export_signatures_from_image, convert_to_export_hash_entries},
+    image_compression::{compress_sections, CompressionInfo, SectionCodec},
+    image_signature::{sign_image_sections, SignatureProvider},
+    module_image::{
+        check_section_table, read_image_flags, verify_trailer, ImageType, ModuleImage,
+        ModuleSectionId, ModuleSectionItem, SectionEntry, BASE_MODULE_HEADER_LENGTH,
+        IMAGE_FILE_MAGIC_NUMBER, IMAGE_FLAG_COMPRESSED, IMAGE_FLAG_ENCRYPTED,
+        IMAGE_FLAG_HAS_TRAILER,
+    },
+    ImageError, ImageErrorType,
+};
+
+// The reasons why a transform of the pipeline fails.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TransformError {
+    EssentialSection(ModuleSectionId),
+    CompressedImage,
+    InvalidModuleName(String),
+    MissingSection(ModuleSectionId),
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::EssentialSection(section_id) => {
+                write!(f, "Cannot strip the essential section {:?}.", section_id)
+            }
+            TransformError::CompressedImage => {
+                write!(f, "Cannot transform a compressed image.")
+            }
+            TransformError::InvalidModuleName(name) => {
+                write!(f, "Invalid module name \"{}\".", name)
+            }
+            TransformError::MissingSection(section_id) => {
+                write!(f, "Cannot find the section {:?}.", section_id)
+            }
+        }
+    }
+}
+
+// The owned sections of an image, in the order of the section table.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImageSections {
+    pub image_type: ImageType,
+    pub sections: Vec<(ModuleSectionId, Vec<u8>)>,
+
+    // The compressed sections, see `image_compression`.
+    pub compression_info: Option<CompressionInfo>,
+}
+
+impl ImageSections {
+    pub fn from_module_image(module_image: &ModuleImage) -> Self {
+        let sections = module_image
+            .items
+            .iter()
+            .map(|item| {
+                let data = &module_image.sections_data
+                    [item.offset as usize..(item.offset + item.length) as usize];
+                (item.id, data.to_vec())
+            })
+            .collect();
+
+        Self {
+            image_type: module_image.image_type,
+            sections,
+            compression_info: None,
+        }
+    }
+
+    /// Reads the sections of the image without checking the section data,
+    /// so that the compressed images can be read as well.
+    ///
+    /// The encrypted images are rejected, see `image_encryption::decrypt_image`.
+    pub fn read(image_binary: &[u8]) -> Result<Self, ImageError> {
+        if image_binary.len() < BASE_MODULE_HEADER_LENGTH {
+            return Err(ImageError::new(ImageErrorType::TruncatedImage));
+        }
+
+        if &image_binary[0..8] != IMAGE_FILE_MAGIC_NUMBER {
+            return Err(ImageError::new(ImageErrorType::InvalidImage));
+        }
+
+        let image_type =
+            ImageType::try_from(u16::from_le_bytes(image_binary[8..10].try_into().unwrap()))?;
+
+        let extra_header_length =
+            u16::from_le_bytes(image_binary[10..12].try_into().unwrap()) as usize;
+        let body_start = BASE_MODULE_HEADER_LENGTH + extra_header_length;
+        let extra_header = image_binary
+            .get(BASE_MODULE_HEADER_LENGTH..body_start)
+            .ok_or(ImageError::new(ImageErrorType::TruncatedImage))?;
+
+        let image_flags = read_image_flags(image_binary).unwrap_or(0);
+        if image_flags & IMAGE_FLAG_ENCRYPTED != 0 {
+            return Err(ImageError::new(ImageErrorType::EncryptedImage));
+        }
+
+        let compression_info = if image_flags & IMAGE_FLAG_COMPRESSED != 0 {
+            Some(
+                CompressionInfo::from_extra_header(extra_header)
+                    .ok_or(ImageError::new(ImageErrorType::InvalidImage))?,
+            )
+        } else {
+            None
+        };
+
+        let body_end = if image_flags & IMAGE_FLAG_HAS_TRAILER != 0 {
+            verify_trailer(image_binary, body_start)?
+        } else {
+            image_binary.len()
+        };
+
+        let image_body = &image_binary[body_start..body_end];
+        check_section_table(image_body)?;
+
+        let (items, sections_data) =
+            read_section_with_table_and_data_area::<ModuleSectionItem>(image_body);
+        let sections = items
+            .iter()
+            .map(|item| {
+                let offset = item.offset as usize;
+                let data = &sections_data[offset..(offset + item.length as usize)];
+                (item.id, data.to_vec())
+            })
+            .collect();
+
+        Ok(Self {
+            image_type,
+            sections,
+            compression_info,
+        })
+    }
+
+    pub fn get_section_data(&self, section_id: ModuleSectionId) -> Option<&[u8]> {
+        self.sections
+            .iter()
+            .find(|(id, _)| *id == section_id)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    /// Replaces the data of the existing section, or inserts a new section
+    /// while keeping the ascending order of the section ids.
+    ///
+    /// The new data is not compressed.
+    pub fn set_section_data(&mut self, section_id: ModuleSectionId, section_data: Vec<u8>) {
+        self.remove_compressed_section(section_id);
+
+        if let Some((_, data)) = self.sections.iter_mut().find(|(id, _)| *id == section_id) {
+            *data = section_data;
+        } else {
+            let position = self
+                .sections
+                .iter()
+                .position(|(id, _)| (*id as u32) > (section_id as u32))
+                .unwrap_or(self.sections.len());
+            self.sections.insert(position, (section_id, section_data));
+        }
+    }
+
+    pub fn remove_section(&mut self, section_id: ModuleSectionId) {
+        self.remove_compressed_section(section_id);
+        self.sections.retain(|(id, _)| *id != section_id);
+    }
+
+    pub fn write(&self, writer: &mut dyn std::io::Write) -> Result<(), ImageError> {
+        let sections = self
+            .sections
+            .iter()
+            .map(|(section_id, data)| (*section_id, data.as_slice()))
+            .collect::<Vec<_>>();
+
+        let Some(compression_info) = &self.compression_info else {
+            return ModuleImage::compose(self.image_type, &sections, writer);
+        };
+
+        // The composed image has no extra header, the extra header is
+        // inserted after the base header, and the extra header length is updated.
+        let extra_header = compression_info.to_extra_header();
+        let mut image_binary: Vec<u8> = vec![];
+        ModuleImage::compose(self.image_type, &sections, &mut image_binary)?;
+        image_binary[10..12].copy_from_slice(&(extra_header.len() as u16).to_le_bytes());
+
+        writer.write_all(&image_binary[..BASE_MODULE_HEADER_LENGTH])?;
+        writer.write_all(&extra_header)?;
+        writer.write_all(&image_binary[BASE_MODULE_HEADER_LENGTH..])?;
+        Ok(())
+    }
+
+    // Removes the section from the compression metadata.
+    fn remove_compressed_section(&mut self, section_id: ModuleSectionId) {
+        if let Some(compression_info) = &mut self.compression_info {
+            compression_info
+                .sections
+                .retain(|(id, _)| *id != section_id);
+            if compression_info.sections.is_empty() {
+                self.compression_info = None;
+            }
+        }
+    }
+}
+
+pub trait Transform {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError>;
+}
+
+#[derive(Default)]
+pub struct Pipeline {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Applies all transforms in order and writes the result.
+    pub fn run(
+        &self,
+        module_image: &ModuleImage,
+        writer: &mut dyn std::io::Write,
+    ) -> Result<(), ImageError> {
+        let mut image_sections = ImageSections::from_module_image(module_image);
+
+        for transform in &self.transforms {
+            transform.apply(&mut image_sections)?;
+        }
+
+        image_sections.write(writer)
+    }
+}
+
+pub struct StripSections {
+    pub section_ids: Vec<ModuleSectionId>,
+}
+
+impl Transform for StripSections {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError> {
+        for section_id in &self.section_ids {
+            if section_id.is_essential() {
+                return Err(ImageError::new(ImageErrorType::Transform(
+                    TransformError::EssentialSection(*section_id),
+                )));
+            }
+
+            image_sections.remove_section(*section_id);
+        }
+
+        Ok(())
+    }
+}
+
+pub struct RenameModule {
+    pub new_name: String,
+}
+
+impl Transform for RenameModule {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError> {
+        if image_sections.compression_info.is_some() {
+            return Err(ImageError::new(ImageErrorType::Transform(
+                TransformError::CompressedImage,
+            )));
+        }
+
+        if self.new_name.is_empty() || self.new_name.len() > MODULE_NAME_BUFFER_LENGTH {
+            return Err(ImageError::new(ImageErrorType::Transform(
+                TransformError::InvalidModuleName(self.new_name.clone()),
+            )));
+        }
+
+        let Some(property_section_data) =
+            image_sections.get_section_data(ModuleSectionId::Property)
+        else {
+            return Err(ImageError::new(ImageErrorType::Transform(
+                TransformError::MissingSection(ModuleSectionId::Property),
+            )));
+        };
+
+        let property_section = PropertySection::read(property_section_data);
+        let old_prefix = format!("{}::", property_section.get_module_name());
+        let new_prefix = format!("{}::", self.new_name);
+
+        let rename = |full_name: &mut String| {
+            if let Some(name_path) = full_name.strip_prefix(&old_prefix) {
+                *full_name = format!("{}{}", new_prefix, name_path);
+            }
+        };
+
+        let new_property_section = PropertySection::new(
+            &self.new_name,
+            property_section.edition,
+            property_section.version_patch,
+            property_section.version_minor,
+            property_section.version_major,
+        );
+        let new_property_section_data = write_section_entry(&new_property_section);
+        image_sections.set_section_data(ModuleSectionId::Property, new_property_section_data);
+
+        if let Some(section_data) = image_sections.get_section_data(ModuleSectionId::FunctionName) {
+            let mut entries = FunctionNameSection::read(section_data).convert_to_entries();
+            entries
+                .iter_mut()
+                .for_each(|entry| rename(&mut entry.full_name));
+
+            let (items, full_names_data) = FunctionNameSection::convert_from_entries(&entries);
+            let section = FunctionNameSection {
+                items: &items,
+                full_names_data: &full_names_data,
+            };
+            let section_data = write_section_entry(&section);
+            image_sections.set_section_data(ModuleSectionId::FunctionName, section_data);
+        }
+
+        if let Some(section_data) = image_sections.get_section_data(ModuleSectionId::DataName) {
+            let mut entries = DataNameSection::read(section_data).convert_to_entries();
+            entries
+                .iter_mut()
+                .for_each(|entry| rename(&mut entry.full_name));
+
+            let (items, full_names_data) = DataNameSection::convert_from_entries(&entries);
+            let section = DataNameSection {
+                items: &items,
+                full_names_data: &full_names_data,
+            };
+            let section_data = write_section_entry(&section);
+            image_sections.set_section_data(ModuleSectionId::DataName, section_data);
+        }
+
+        // The ABI hashes are computed from the full names, so they are recomputed.
+        if image_sections
+            .get_section_data(ModuleSectionId::ExportHash)
+            .is_some()
+        {
+            let mut image_binary: Vec<u8> = vec![];
+            image_sections.write(&mut image_binary)?;
+            let module_image = ModuleImage::read(&image_binary)?;
+
+            let signatures = collect_export_signatures_from_image(&module_image);
+            let entries = convert_to_export_hash_entries(&signatures);
+            let items = ExportHashSection::convert_from_entries(&entries);
+            let section = ExportHashSection { items: &items };
+            let section_data = write_section_entry(&section);
+            image_sections.set_section_data(ModuleSectionId::ExportHash, section_data);
+        }
+
+        Ok(())
+    }
+}
+
+pub struct AddSection {
+    pub section_id: ModuleSectionId,
+    pub section_data: Vec<u8>,
+}
+
+impl Transform for AddSection {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError> {
+        image_sections.set_section_data(self.section_id, self.section_data.clone());
+        Ok(())
+    }
+}
+
+pub struct CompressSections {
+    pub section_ids: Vec<ModuleSectionId>,
+    pub codec: Box<dyn SectionCodec>,
+}
+
+impl Transform for CompressSections {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError> {
+        compress_sections(image_sections, &self.section_ids, self.codec.as_ref())
+    }
+}
+
+pub struct SignImage {
+    // The custom section which stores the signature.
+    pub section_id: ModuleSectionId,
+    pub signature_provider: Box<dyn SignatureProvider>,
+}
+
+impl Transform for SignImage {
+    fn apply(&self, image_sections: &mut ImageSections) -> Result<(), ImageError> {
+        sign_image_sections(
+            image_sections,
+            self.section_id,
+            self.signature_provider.as_ref(),
+        )
+    }
+}
+
+fn write_section_entry<'a>(section_entry: &'a dyn SectionEntry<'a>) -> Vec<u8> {
+    let mut section_data: Vec<u8> = vec![];
+
+    // Writing to a `Vec<u8>` never fails.
+    section_entry.write(&mut section_data).unwrap();
+    section_data
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{FunctionNameEntry, TypeEntry},
+        entry_reader::read_object_file,
+        entry_writer::build_shared_module_scaffold,
+        export_surface::{compute_abi_hash, get_export_hash},
+        image_compression::{decompress_image, is_compressed_image, LzCodec},
+        image_pipeline::{
+            AddSection, CompressSections, Pipeline, RenameModule, SignImage, StripSections,
+        },
+        image_signature::{verify_image_signature, SignatureProvider},
+        module_image::{ModuleImage, ModuleSectionId, Visibility},
+    };
+
+    struct TestSignatureProvider;
+
+    impl SignatureProvider for TestSignatureProvider {
+        fn sign(&self, data: &[u8]) -> Option<Vec<u8>> {
+            Some(vec![data.iter().fold(0u8, |acc, byte| acc ^ byte); 4])
+        }
+
+        fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+            self.sign(data)
+                .is_some_and(|expected| expected == signature)
+        }
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let image_binary =
+            build_shared_module_scaffold("foo", &[("bar", TypeEntry::new(vec![], vec![]))])
+                .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut output_binary: Vec<u8> = vec![];
+        Pipeline::new()
+            .append(RenameModule {
+                new_name: "hello".to_owned(),
+            })
+            .append(AddSection {
+                section_id: ModuleSectionId::Relocate,
+                section_data: vec![0, 0, 0, 0, 0, 0, 0, 0],
+            })
+            .run(&module_image, &mut output_binary)
+            .unwrap();
+
+        let output_module_image = ModuleImage::read(&output_binary).unwrap();
+        assert!(output_module_image
+            .get_optional_relocate_section()
+            .is_some());
+        assert_eq!(
+            get_export_hash(&output_module_image, "hello::bar"),
+            Some(compute_abi_hash("function hello::bar () -> ()"))
+        );
+
+        let image_common_entry = read_object_file(&output_binary).unwrap();
+        assert_eq!(image_common_entry.name, "hello");
+        assert_eq!(
+            image_common_entry.function_name_entries,
+            vec![FunctionNameEntry::new(
+                "hello::bar".to_owned(),
+                Visibility::Public,
+                0
+            )]
+        );
+
+        // strip
+        let mut stripped_binary: Vec<u8> = vec![];
+        Pipeline::new()
+            .append(StripSections {
+                section_ids: vec![ModuleSectionId::FunctionName],
+            })
+            .run(&output_module_image, &mut stripped_binary)
+            .unwrap();
+        assert!(ModuleImage::read(&stripped_binary)
+            .unwrap()
+            .get_optional_export_function_section()
+            .is_none());
+
+        // the essential sections cannot be stripped
+        assert_eq!(
+            Pipeline::new()
+                .append(StripSections {
+                    section_ids: vec![ModuleSectionId::Function],
+                })
+                .run(&module_image, &mut Vec::<u8>::new())
+                .unwrap_err()
+                .to_string(),
+            "Transform failed: Cannot strip the essential section Function."
+        );
+    }
+
+    #[test]
+    fn test_pipeline_compress_and_sign() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("bar", TypeEntry::new(vec![], vec![])),
+                ("baz", TypeEntry::new(vec![], vec![])),
+                ("qux", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut output_binary: Vec<u8> = vec![];
+        Pipeline::new()
+            .append(CompressSections {
+                section_ids: vec![ModuleSectionId::Function, ModuleSectionId::FunctionName],
+                codec: Box::new(LzCodec),
+            })
+            .append(SignImage {
+                section_id: ModuleSectionId::Custom0,
+                signature_provider: Box::new(TestSignatureProvider),
+            })
+            .run(&module_image, &mut output_binary)
+            .unwrap();
+
+        assert!(is_compressed_image(&output_binary));
+        assert!(verify_image_signature(
+            &output_binary,
+            ModuleSectionId::Custom0,
+            &TestSignatureProvider
+        )
+        .is_ok());
+
+        let decompressed_binary = decompress_image(&output_binary, &LzCodec).unwrap();
+        let image_common_entry = read_object_file(&decompressed_binary).unwrap();
+        assert_eq!(image_common_entry.function_name_entries.len(), 3);
+
+        // the compressed image cannot be renamed
+        assert_eq!(
+            Pipeline::new()
+                .append(CompressSections {
+                    section_ids: vec![ModuleSectionId::FunctionName],
+                    codec: Box::new(LzCodec),
+                })
+                .append(RenameModule {
+                    new_name: "hello".to_owned(),
+                })
+                .run(&module_image, &mut Vec::<u8>::new())
+                .unwrap_err()
+                .to_string(),
+            "Transform failed: Cannot transform a compressed image."
+        );
+    }
+}
@@ -0,0 +1,604 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Repairs the mechanical inconsistencies of an image, a last resort for
+// salvaging the images produced by buggy third-party generators.
+//
+// Only the problems which have exactly one correct answer are fixed:
+//
+// - Section lengths which overrun the next section (or the end of the image),
+//   the length is recomputed from the offset of the next section.
+//   Note that the new length may include the padding (up to 3 bytes) of the section.
+// - Section table which is not sorted by the section id.
+// - Trailer whose total length or checksum does not match the image.
+// - Function and data name entries whose internal index does not exist,
+//   i.e., the orphan name entries.
+//
+// Anything semantic (e.g., unknown section ids, overlapped sections, invalid
+// enum values or corrupted section tables) is refused with an `ImageErrorType::Repair` error
+// (see `RepairError`),
+// since guessing the intent of the generator may produce an image which
+// loads but behaves differently, use `validator` to diagnose such images.
+//
+// The repaired image is always re-written in the canonical layout,
+// i.e., the same as the output of `ImageSections::write`, with a new trailer
+// if the original image has one.
+
+use std::fmt::Display;
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    common_sections::{
+        data_name_section::{DataNameItem, DataNameSection},
+        function_name_section::{FunctionNameItem, FunctionNameSection},
+        function_section::FunctionItem,
+        read_only_data_section, read_write_data_section, uninit_data_section,
+    },
+    datatableaccess::check_section_with_table,
+    image_pipeline::ImageSections,
+    module_image::{
+        compute_crc32, ImageType, ModuleImage, ModuleSectionId, ModuleSectionItem, SectionEntry,
+        BASE_MODULE_HEADER_LENGTH, BASE_SECTION_HEADER_LENGTH, IMAGE_FILE_MAGIC_NUMBER,
+        IMAGE_FLAG_ENCRYPTED, IMAGE_FLAG_HAS_TRAILER, IMAGE_TRAILER_LENGTH,
+        IMAGE_TRAILER_MAGIC_NUMBER,
+    },
+    ImageError, ImageErrorType,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RepairAction {
+    // The declared length overruns the next section or the end of the image.
+    SectionLengthRecomputed {
+        section_id: ModuleSectionId,
+        old_length: u32,
+        new_length: u32,
+    },
+
+    // The section table is not sorted by the section id.
+    SectionTableSorted,
+
+    // The total length or the checksum of the trailer is wrong.
+    TrailerRegenerated,
+
+    FunctionNameDropped {
+        full_name: String,
+        function_internal_index: usize,
+    },
+
+    DataNameDropped {
+        full_name: String,
+        section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+    },
+}
+
+// The reasons why the image can not be repaired mechanically.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RepairError {
+    NotModuleImage,
+    TruncatedHeader,
+    EncryptedImage,
+
+    // The image declares a trailer, but the trailer is lost,
+    // i.e., the image may be truncated.
+    TrailerLost,
+
+    TruncatedSectionTable,
+    DuplicateSection(ModuleSectionId),
+
+    // `offset` is relative to the start of the sections data area.
+    SectionOffsetOutOfBounds {
+        section_id: ModuleSectionId,
+        offset: usize,
+    },
+
+    OverlappedSection {
+        section_id: ModuleSectionId,
+        other_section_id: ModuleSectionId,
+    },
+
+    CorruptedSection(ModuleSectionId),
+}
+
+impl Display for RepairError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepairError::NotModuleImage => write!(f, "Not a module image."),
+            RepairError::TruncatedHeader => write!(f, "The image header is truncated."),
+            RepairError::EncryptedImage => write!(f, "The image is encrypted."),
+            RepairError::TrailerLost => {
+                write!(f, "The trailer is lost, the image may be truncated.")
+            }
+            RepairError::TruncatedSectionTable => write!(f, "The section table is truncated."),
+            RepairError::DuplicateSection(section_id) => {
+                write!(f, "The section {:?} occurs more than once.", section_id)
+            }
+            RepairError::SectionOffsetOutOfBounds { section_id, offset } => write!(
+                f,
+                "The offset {} of the section {:?} is out of the image.",
+                offset, section_id
+            ),
+            RepairError::OverlappedSection {
+                section_id,
+                other_section_id,
+            } => write!(
+                f,
+                "The section {:?} overlaps the section {:?}.",
+                section_id, other_section_id
+            ),
+            RepairError::CorruptedSection(section_id) => {
+                write!(f, "The section {:?} is corrupted.", section_id)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RepairLog {
+    // The actions in the order of being applied,
+    // an empty list means the image is consistent.
+    pub actions: Vec<RepairAction>,
+}
+
+impl RepairLog {
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+/// Repairs the mechanical inconsistencies of the image,
+/// returns the repaired image binary and the applied actions.
+pub fn repair(image_binary: &[u8]) -> Result<(Vec<u8>, RepairLog), ImageError> {
+    let mut repair_log = RepairLog::default();
+
+    if image_binary.len() < BASE_MODULE_HEADER_LENGTH
+        || image_binary[0..8] != IMAGE_FILE_MAGIC_NUMBER[..]
+    {
+        return Err(ImageError::new(ImageErrorType::Repair(
+            RepairError::NotModuleImage,
+        )));
+    }
+
+    let image_type =
+        ImageType::try_from(u16::from_le_bytes(image_binary[8..10].try_into().unwrap()))?;
+
+    let extra_header_length = u16::from_le_bytes(image_binary[10..12].try_into().unwrap()) as usize;
+    let body_start = BASE_MODULE_HEADER_LENGTH + extra_header_length;
+    if body_start > image_binary.len() {
+        return Err(ImageError::new(ImageErrorType::Repair(
+            RepairError::TruncatedHeader,
+        )));
+    }
+
+    let image_flags = if extra_header_length >= 4 {
+        u32::from_le_bytes(
+            image_binary[BASE_MODULE_HEADER_LENGTH..(BASE_MODULE_HEADER_LENGTH + 4)]
+                .try_into()
+                .unwrap(),
+        )
+    } else {
+        0
+    };
+
+    if image_flags & IMAGE_FLAG_ENCRYPTED != 0 {
+        return Err(ImageError::new(ImageErrorType::Repair(
+            RepairError::EncryptedImage,
+        )));
+    }
+
+    let has_trailer = image_flags & IMAGE_FLAG_HAS_TRAILER != 0;
+    let body_end = if has_trailer {
+        let trailer_start = image_binary
+            .len()
+            .checked_sub(IMAGE_TRAILER_LENGTH)
+            .filter(|trailer_start| *trailer_start >= body_start)
+            .filter(|trailer_start| {
+                image_binary[*trailer_start..(*trailer_start + 8)] == IMAGE_TRAILER_MAGIC_NUMBER[..]
+            })
+            .ok_or(ImageError::new(ImageErrorType::Repair(
+                RepairError::TrailerLost,
+            )))?;
+
+        let trailer = &image_binary[trailer_start..];
+        let total_length = u32::from_le_bytes(trailer[8..12].try_into().unwrap()) as usize;
+        let checksum = u32::from_le_bytes(trailer[12..16].try_into().unwrap());
+        if total_length != image_binary.len()
+            || checksum != compute_crc32(&image_binary[..trailer_start])
+        {
+            repair_log.actions.push(RepairAction::TrailerRegenerated);
+        }
+
+        trailer_start
+    } else {
+        image_binary.len()
+    };
+
+    let image_body = &image_binary[body_start..body_end];
+    let sections = read_sections(image_body, &mut repair_log)?;
+
+    let mut image_sections = ImageSections {
+        image_type,
+        sections,
+        compression_info: None,
+    };
+
+    drop_orphan_names(&mut image_sections, &mut repair_log)?;
+
+    let mut repaired_binary: Vec<u8> = vec![];
+    image_sections.write(&mut repaired_binary)?;
+
+    // The repaired image must be readable, otherwise the problem is not mechanical.
+    let module_image = ModuleImage::read(&repaired_binary)?;
+
+    if has_trailer {
+        let mut image_binary_with_trailer: Vec<u8> = vec![];
+        module_image.write_with_trailer(&mut image_binary_with_trailer)?;
+        return Ok((image_binary_with_trailer, repair_log));
+    }
+
+    Ok((repaired_binary, repair_log))
+}
+
+// Reads the section table and the section data, the lengths are recomputed
+// and the sections are sorted by id.
+fn read_sections(
+    image_body: &[u8],
+    repair_log: &mut RepairLog,
+) -> Result<Vec<(ModuleSectionId, Vec<u8>)>, ImageError> {
+    if !check_section_with_table::<ModuleSectionItem>(image_body) {
+        return Err(ImageError::new(ImageErrorType::Repair(
+            RepairError::TruncatedSectionTable,
+        )));
+    }
+
+    let item_count = u32::from_le_bytes(image_body[0..4].try_into().unwrap()) as usize;
+    let table_end = BASE_SECTION_HEADER_LENGTH + item_count * size_of::<ModuleSectionItem>();
+    let sections_data = &image_body[table_end..];
+
+    // `(section_id, offset, length)`
+    let mut items: Vec<(ModuleSectionId, usize, usize)> = vec![];
+    for idx in 0..item_count {
+        let position = BASE_SECTION_HEADER_LENGTH + idx * size_of::<ModuleSectionItem>();
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(
+                image_body[(position + offset)..(position + offset + 4)]
+                    .try_into()
+                    .unwrap(),
+            )
+        };
+
+        let section_id = ModuleSectionId::try_from(read_u32(0))?;
+        if items.iter().any(|(id, _, _)| *id == section_id) {
+            return Err(ImageError::new(ImageErrorType::Repair(
+                RepairError::DuplicateSection(section_id),
+            )));
+        }
+
+        let offset = read_u32(4) as usize;
+        if offset > sections_data.len() {
+            return Err(ImageError::new(ImageErrorType::Repair(
+                RepairError::SectionOffsetOutOfBounds { section_id, offset },
+            )));
+        }
+
+        items.push((section_id, offset, read_u32(8) as usize));
+    }
+
+    let mut sections: Vec<(ModuleSectionId, Vec<u8>)> = vec![];
+    for (section_id, offset, length) in &items {
+        // The section ends before the next section, i.e., the nearest
+        // greater offset, or the end of the data area.
+        let available_length = items
+            .iter()
+            .map(|(_, other_offset, _)| *other_offset)
+            .filter(|other_offset| other_offset > offset)
+            .min()
+            .unwrap_or(sections_data.len())
+            - offset;
+
+        let opt_overlapped_item = items.iter().find(|(other_id, other_offset, other_length)| {
+            *length > 0 && other_id != section_id && other_offset == offset && *other_length > 0
+        });
+        if let Some((other_section_id, _, _)) = opt_overlapped_item {
+            return Err(ImageError::new(ImageErrorType::Repair(
+                RepairError::OverlappedSection {
+                    section_id: *section_id,
+                    other_section_id: *other_section_id,
+                },
+            )));
+        }
+
+        let new_length = if *length > available_length {
+            repair_log
+                .actions
+                .push(RepairAction::SectionLengthRecomputed {
+                    section_id: *section_id,
+                    old_length: *length as u32,
+                    new_length: available_length as u32,
+                });
+            available_length
+        } else {
+            *length
+        };
+
+        sections.push((
+            *section_id,
+            sections_data[*offset..(offset + new_length)].to_vec(),
+        ));
+    }
+
+    if !sections
+        .windows(2)
+        .all(|pair| (pair[0].0 as u32) < (pair[1].0 as u32))
+    {
+        sections.sort_by_key(|(section_id, _)| *section_id as u32);
+        repair_log.actions.push(RepairAction::SectionTableSorted);
+    }
+
+    Ok(sections)
+}
+
+// Drops the name entries whose internal index does not exist.
+fn drop_orphan_names(
+    image_sections: &mut ImageSections,
+    repair_log: &mut RepairLog,
+) -> Result<(), ImageError> {
+    let function_count =
+        get_section_item_count::<FunctionItem>(image_sections, ModuleSectionId::Function)?;
+
+    if let Some(section_data) = image_sections.get_section_data(ModuleSectionId::FunctionName) {
+        if !check_section_with_table::<FunctionNameItem>(section_data)
+            || !FunctionNameSection::read(section_data).is_well_formed()
+        {
+            return Err(ImageError::new(ImageErrorType::Repair(
+                RepairError::CorruptedSection(ModuleSectionId::FunctionName),
+            )));
+        }
+
+        let (entries, orphan_entries): (Vec<_>, Vec<_>) = FunctionNameSection::read(section_data)
+            .convert_to_entries()
+            .into_iter()
+            .partition(|entry| entry.internal_index < function_count);
+
+        if !orphan_entries.is_empty() {
+            repair_log
+                .actions
+                .extend(orphan_entries.into_iter().map(|entry| {
+                    RepairAction::FunctionNameDropped {
+                        full_name: entry.full_name,
+                        function_internal_index: entry.internal_index,
+                    }
+                }));
+
+            let (items, full_names_data) = FunctionNameSection::convert_from_entries(&entries);
+            let mut section_data: Vec<u8> = vec![];
+            FunctionNameSection {
+                items: &items,
+                full_names_data: &full_names_data,
+            }
+            .write(&mut section_data)
+            .unwrap();
+            image_sections.set_section_data(ModuleSectionId::FunctionName, section_data);
+        }
+    }
+
+    let data_counts = [
+        (
+            DataSectionType::ReadOnly,
+            get_section_item_count::<read_only_data_section::DataItem>(
+                image_sections,
+                ModuleSectionId::ReadOnlyData,
+            )?,
+        ),
+        (
+            DataSectionType::ReadWrite,
+            get_section_item_count::<read_write_data_section::DataItem>(
+                image_sections,
+                ModuleSectionId::ReadWriteData,
+            )?,
+        ),
+        (
+            DataSectionType::Uninit,
+            get_section_item_count::<uninit_data_section::DataItem>(
+                image_sections,
+                ModuleSectionId::UninitData,
+            )?,
+        ),
+    ];
+
+    if let Some(section_data) = image_sections.get_section_data(ModuleSectionId::DataName) {
+        if !check_section_with_table::<DataNameItem>(section_data)
+            || !DataNameSection::read(section_data).is_well_formed()
+        {
+            return Err(ImageError::new(ImageErrorType::Repair(
+                RepairError::CorruptedSection(ModuleSectionId::DataName),
+            )));
+        }
+
+        let (entries, orphan_entries): (Vec<_>, Vec<_>) = DataNameSection::read(section_data)
+            .convert_to_entries()
+            .into_iter()
+            .partition(|entry| {
+                data_counts.iter().any(|(section_type, count)| {
+                    *section_type == entry.section_type && entry.internal_index_in_section < *count
+                })
+            });
+
+        if !orphan_entries.is_empty() {
+            repair_log
+                .actions
+                .extend(
+                    orphan_entries
+                        .into_iter()
+                        .map(|entry| RepairAction::DataNameDropped {
+                            full_name: entry.full_name,
+                            section_type: entry.section_type,
+                            data_internal_index_in_section: entry.internal_index_in_section,
+                        }),
+                );
+
+            let (items, full_names_data) = DataNameSection::convert_from_entries(&entries);
+            let mut section_data: Vec<u8> = vec![];
+            DataNameSection {
+                items: &items,
+                full_names_data: &full_names_data,
+            }
+            .write(&mut section_data)
+            .unwrap();
+            image_sections.set_section_data(ModuleSectionId::DataName, section_data);
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the amount of the table items of the section, `0` if the section is absent.
+// `I` is the type of the table item of the section.
+fn get_section_item_count<I>(
+    image_sections: &ImageSections,
+    section_id: ModuleSectionId,
+) -> Result<usize, ImageError> {
+    match image_sections.get_section_data(section_id) {
+        None => Ok(0),
+        Some(section_data) if check_section_with_table::<I>(section_data) => {
+            Ok(u32::from_le_bytes(section_data[0..4].try_into().unwrap()) as usize)
+        }
+        Some(_) => Err(ImageError::new(ImageErrorType::Repair(
+            RepairError::CorruptedSection(section_id),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::function_name_section::FunctionNameSection,
+        entry::{FunctionNameEntry, TypeEntry},
+        entry_writer::build_shared_module_scaffold,
+        image_pipeline::ImageSections,
+        image_repair::{repair, RepairAction},
+        module_image::{
+            ModuleImage, ModuleSectionId, ModuleSectionItem, SectionEntry, Visibility,
+            BASE_MODULE_HEADER_LENGTH, BASE_SECTION_HEADER_LENGTH,
+        },
+    };
+
+    #[test]
+    fn test_repair() {
+        let image_binary =
+            build_shared_module_scaffold("foo", &[("bar", TypeEntry::new(vec![], vec![]))])
+                .unwrap();
+
+        // a consistent image needs no repair, and the canonical layout is stable
+        let (repaired_binary, repair_log) = repair(&image_binary).unwrap();
+        assert!(repair_log.is_empty());
+        let (rerepaired_binary, repair_log) = repair(&repaired_binary).unwrap();
+        assert!(repair_log.is_empty());
+        assert_eq!(rerepaired_binary, repaired_binary);
+
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        let expected_sections = ImageSections::from_module_image(&module_image);
+
+        // add an orphan function name
+        let mut image_sections = expected_sections.clone();
+        let mut function_name_entries = module_image
+            .get_optional_export_function_section()
+            .unwrap()
+            .convert_to_entries();
+        function_name_entries.push(FunctionNameEntry::new(
+            "foo::orphan".to_owned(),
+            Visibility::Public,
+            7,
+        ));
+        let (items, full_names_data) =
+            FunctionNameSection::convert_from_entries(&function_name_entries);
+        let mut section_data: Vec<u8> = vec![];
+        FunctionNameSection {
+            items: &items,
+            full_names_data: &full_names_data,
+        }
+        .write(&mut section_data)
+        .unwrap();
+        image_sections.set_section_data(ModuleSectionId::FunctionName, section_data);
+
+        let mut broken_binary: Vec<u8> = vec![];
+        image_sections.write(&mut broken_binary).unwrap();
+        let mut broken_with_trailer_binary: Vec<u8> = vec![];
+        ModuleImage::read(&broken_binary)
+            .unwrap()
+            .write_with_trailer(&mut broken_with_trailer_binary)
+            .unwrap();
+
+        // the image flags and the reserved field follow the base header
+        let table_start = BASE_MODULE_HEADER_LENGTH + 8;
+        let item_start = table_start + BASE_SECTION_HEADER_LENGTH;
+        let item_length = size_of::<ModuleSectionItem>();
+
+        // swap the first two items of the section table
+        let (first_item, second_item) =
+            broken_with_trailer_binary[item_start..].split_at_mut(item_length);
+        first_item.swap_with_slice(&mut second_item[..item_length]);
+
+        // overrun the length of the first item (originally the second item)
+        let length_position = item_start + 8;
+        let old_length = u32::from_le_bytes(
+            broken_with_trailer_binary[length_position..(length_position + 4)]
+                .try_into()
+                .unwrap(),
+        );
+        broken_with_trailer_binary[length_position..(length_position + 4)]
+            .copy_from_slice(&0xffffu32.to_le_bytes());
+
+        let (repaired_binary, repair_log) = repair(&broken_with_trailer_binary).unwrap();
+        let second_section_id = expected_sections.sections[1].0;
+        assert_eq!(
+            repair_log.actions,
+            vec![
+                RepairAction::TrailerRegenerated,
+                RepairAction::SectionLengthRecomputed {
+                    section_id: second_section_id,
+                    old_length: 0xffff,
+                    new_length: old_length.next_multiple_of(4),
+                },
+                RepairAction::SectionTableSorted,
+                RepairAction::FunctionNameDropped {
+                    full_name: "foo::orphan".to_owned(),
+                    function_internal_index: 7,
+                },
+            ]
+        );
+
+        let repaired_image = ModuleImage::read(&repaired_binary).unwrap();
+        let repaired_sections = ImageSections::from_module_image(&repaired_image);
+        assert_eq!(
+            repaired_sections.sections.len(),
+            expected_sections.sections.len()
+        );
+        for ((id, data), (expected_id, expected_data)) in repaired_sections
+            .sections
+            .iter()
+            .zip(expected_sections.sections.iter())
+        {
+            assert_eq!(id, expected_id);
+            assert!(data.starts_with(expected_data));
+        }
+
+        // refuse
+        assert_eq!(
+            repair(&[0u8; 16]).unwrap_err().to_string(),
+            "Repair error: Not a module image."
+        );
+
+        let mut unknown_section_binary = image_binary.clone();
+        let item_start = BASE_MODULE_HEADER_LENGTH + BASE_SECTION_HEADER_LENGTH;
+        unknown_section_binary[item_start..(item_start + 4)]
+            .copy_from_slice(&0xffffu32.to_le_bytes());
+        assert!(repair(&unknown_section_binary).is_err());
+    }
+}
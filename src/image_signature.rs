@@ -0,0 +1,248 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The signature of the images, it is used by the vendors and the package
+// repositories for proving the origin of the images.
+//
+// The signature is created and verified by the `SignatureProvider` supplied by
+// the caller, so that the keys never leave it, and this crate does not depend on
+// any cryptography library.
+//
+// The signature is stored in a custom section (e.g., `ModuleSectionId::Custom0`)
+// selected by the caller, and it covers the following data:
+//
+// - The image type (u16, little-endian).
+// - The length of the extra header (u32, little-endian) and the extra header,
+//   i.e., the compression metadata, so the signature of a compressed image
+//   can be verified without decompressing.
+// - The id (u32, little-endian), the length (u32, little-endian) and the data
+//   of each section except the signature section, in the order of the section table.
+
+use std::fmt::Display;
+
+use crate::{
+    image_pipeline::ImageSections, module_image::ModuleSectionId, ImageError, ImageErrorType,
+};
+
+// The reasons why the image can not be signed or verified.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SignatureError {
+    // The signature can only be stored in a custom section.
+    NonCustomSection(ModuleSectionId),
+
+    // The key is not available.
+    SigningFailed,
+
+    // The signature section is absent.
+    Unsigned(ModuleSectionId),
+
+    Mismatch(ModuleSectionId),
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureError::NonCustomSection(section_id) => write!(
+                f,
+                "The signature can only be stored in a custom section, found \"{}\".",
+                section_id.name()
+            ),
+            SignatureError::SigningFailed => write!(f, "Failed to sign the image."),
+            SignatureError::Unsigned(_) => write!(f, "The image is not signed."),
+            SignatureError::Mismatch(_) => write!(f, "The signature does not match."),
+        }
+    }
+}
+
+/// Creates and verifies the signatures.
+pub trait SignatureProvider {
+    /// Returns the signature of the data, or `None` if the key is not available.
+    fn sign(&self, data: &[u8]) -> Option<Vec<u8>>;
+
+    /// Returns `true` if the signature of the data is valid.
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Signs the sections and stores the signature in the specified custom section,
+/// the existing signature is replaced.
+pub fn sign_image_sections(
+    image_sections: &mut ImageSections,
+    section_id: ModuleSectionId,
+    signature_provider: &dyn SignatureProvider,
+) -> Result<(), ImageError> {
+    if !section_id.is_custom() {
+        return Err(ImageError::new(ImageErrorType::Signature(
+            SignatureError::NonCustomSection(section_id),
+        )));
+    }
+
+    let signature = signature_provider
+        .sign(&build_signed_data(image_sections, section_id))
+        .ok_or(ImageError::new(ImageErrorType::Signature(
+            SignatureError::SigningFailed,
+        )))?;
+
+    image_sections.set_section_data(section_id, signature);
+    Ok(())
+}
+
+/// Verifies the signature of the image, the image can be compressed.
+pub fn verify_image_signature(
+    image_binary: &[u8],
+    section_id: ModuleSectionId,
+    signature_provider: &dyn SignatureProvider,
+) -> Result<(), ImageError> {
+    let image_sections = ImageSections::read(image_binary)?;
+
+    let signature = image_sections
+        .get_section_data(section_id)
+        .ok_or(ImageError::new(ImageErrorType::Signature(
+            SignatureError::Unsigned(section_id),
+        )))?;
+
+    if signature_provider.verify(&build_signed_data(&image_sections, section_id), signature) {
+        Ok(())
+    } else {
+        Err(ImageError::new(ImageErrorType::Signature(
+            SignatureError::Mismatch(section_id),
+        )))
+    }
+}
+
+// Returns the data covered by the signature, see the list at the top of this file.
+fn build_signed_data(image_sections: &ImageSections, section_id: ModuleSectionId) -> Vec<u8> {
+    let extra_header = image_sections
+        .compression_info
+        .as_ref()
+        .map(|compression_info| compression_info.to_extra_header())
+        .unwrap_or_default();
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(&(image_sections.image_type as u16).to_le_bytes());
+    data.extend_from_slice(&(extra_header.len() as u32).to_le_bytes());
+    data.extend_from_slice(&extra_header);
+
+    for (id, section_data) in &image_sections.sections {
+        if *id == section_id {
+            continue;
+        }
+
+        data.extend_from_slice(&(*id as u32).to_le_bytes());
+        data.extend_from_slice(&(section_data.len() as u32).to_le_bytes());
+        data.extend_from_slice(section_data);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::TypeEntry,
+        entry_writer::build_shared_module_scaffold,
+        image_pipeline::ImageSections,
+        image_signature::{sign_image_sections, verify_image_signature, SignatureProvider},
+        module_image::{ModuleImage, ModuleSectionId},
+        ImageErrorType,
+    };
+
+    // A toy signature for testing: the checksum of the data with the key.
+    struct TestSignatureProvider {
+        key: u32,
+    }
+
+    impl SignatureProvider for TestSignatureProvider {
+        fn sign(&self, data: &[u8]) -> Option<Vec<u8>> {
+            let sum = data.iter().fold(self.key, |acc, byte| {
+                acc.wrapping_mul(31).wrapping_add(*byte as u32)
+            });
+            Some(sum.to_le_bytes().to_vec())
+        }
+
+        fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+            self.sign(data)
+                .is_some_and(|expected| expected == signature)
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_image() {
+        let image_binary =
+            build_shared_module_scaffold("foo", &[("bar", TypeEntry::new(vec![], vec![]))])
+                .unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+        let signature_provider = TestSignatureProvider { key: 7 };
+
+        let mut image_sections = ImageSections::from_module_image(&module_image);
+        sign_image_sections(
+            &mut image_sections,
+            ModuleSectionId::Custom0,
+            &signature_provider,
+        )
+        .unwrap();
+
+        let mut signed_binary: Vec<u8> = vec![];
+        image_sections.write(&mut signed_binary).unwrap();
+
+        // the signed image is still a plain image
+        assert!(ModuleImage::read(&signed_binary).is_ok());
+        assert!(verify_image_signature(
+            &signed_binary,
+            ModuleSectionId::Custom0,
+            &signature_provider
+        )
+        .is_ok());
+
+        // wrong key
+        assert!(verify_image_signature(
+            &signed_binary,
+            ModuleSectionId::Custom0,
+            &TestSignatureProvider { key: 11 }
+        )
+        .is_err());
+
+        // tampered section
+        let mut tampered_sections = ImageSections::read(&signed_binary).unwrap();
+        tampered_sections.set_section_data(ModuleSectionId::Custom1, vec![0, 0, 0, 0]);
+        let mut tampered_binary: Vec<u8> = vec![];
+        tampered_sections.write(&mut tampered_binary).unwrap();
+        assert_eq!(
+            verify_image_signature(
+                &tampered_binary,
+                ModuleSectionId::Custom0,
+                &signature_provider
+            )
+            .unwrap_err()
+            .to_string(),
+            "Signature error: The signature does not match."
+        );
+
+        // not signed
+        assert_eq!(
+            verify_image_signature(&image_binary, ModuleSectionId::Custom0, &signature_provider)
+                .unwrap_err()
+                .to_string(),
+            "Signature error: The image is not signed."
+        );
+
+        // the signature must be stored in a custom section
+        assert!(sign_image_sections(
+            &mut image_sections,
+            ModuleSectionId::Function,
+            &signature_provider
+        )
+        .is_err());
+
+        assert!(matches!(
+            ImageSections::read(&signed_binary[..10])
+                .unwrap_err()
+                .error_type,
+            ImageErrorType::TruncatedImage
+        ));
+    }
+}
@@ -0,0 +1,1020 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Transformations on the whole module (i.e., the `ImageCommonEntry` of an object file).
+//
+// Unlike the transformations in `bytecode_transform`, these transformations
+// may change the function list, the local variable lists and the function names,
+// so all of the related indices are updated as well.
+//
+// The relocate lists are required, because they are the only reliable
+// way to find out the indices within the bytecode.
+
+use std::collections::HashMap;
+
+use anc_isa::{opcode::Opcode, ModuleDependency, OperandDataType};
+
+use crate::{
+    bytecode_reader::InstructionParams,
+    bytecode_transform::{
+        decode_function_instructions, encode_function_instructions, InstructionItem,
+    },
+    entry::{FunctionEntry, FunctionNameEntry, ImageCommonEntry, LocalVariableListEntry},
+    index_remap::IndexRemap,
+    module_image::{RelocateType, Visibility},
+    opcode_info::get_opcode_info,
+    ImageError,
+};
+
+// Function inlining
+// -----------------
+//
+// A function is "trivially small" when:
+//
+// - It is straight-line code, i.e., it contains no `block`, `block_alt`, `block_nez`,
+//   `break`, `break_alt` or `recur`, and the only `end` is the last instruction.
+// - It does not contain `call`, so there is no recursion or inlining chain.
+// - The length of the code (the last `end` is excluded) does not exceed
+//   the specified `max_code_length`.
+//
+// A `call` to such a function is replaced with:
+//
+// 1. `local_store_*` instructions which pop the arguments from the operand stack
+//    into the new local variables (in reverse order).
+// 2. `imm_*` and `local_store_*` instructions which reset the other local
+//    variables of the callee to zero, because the call site may be executed
+//    multiple times (e.g., in a loop).
+// 3. The code of the callee (the last `end` is excluded), in which the `layers`
+//    and `index` of the local variable instructions are adjusted to the
+//    local variables which are appended to the caller.
+//
+// The local variable list of the caller is not modified in place because it may
+// be shared with other functions. Instead, a new list is appended.
+//
+// Finally, the inlined functions which are private and no longer referenced are
+// removed, and the function public indices, the relocate lists and the internal
+// indices of the "function name" entries are updated.
+
+/// Inlines trivially small functions into their callers within the module.
+///
+/// Returns the number of inlined call sites, or an error if the bytecode
+/// of a caller can not be encoded.
+pub fn inline_small_functions(
+    image_common_entry: &mut ImageCommonEntry,
+    max_code_length: usize,
+) -> Result<usize, ImageError> {
+    if image_common_entry.relocate_list_entries.len() != image_common_entry.function_entries.len() {
+        // The relocate lists are required.
+        return Ok(0);
+    }
+
+    let is_relocate_offsets_in_bounds = image_common_entry
+        .function_entries
+        .iter()
+        .zip(image_common_entry.relocate_list_entries.iter())
+        .all(|(function_entry, relocate_list)| {
+            relocate_list.relocate_entries.iter().all(|relocate_entry| {
+                relocate_entry
+                    .offset_in_function
+                    .checked_add(4)
+                    .is_some_and(|end| end <= function_entry.code.len())
+            })
+        });
+
+    if !is_relocate_offsets_in_bounds {
+        // The relocate lists do not match the bytecode.
+        return Ok(0);
+    }
+
+    let import_function_count = image_common_entry.import_function_entries.len();
+
+    // Find the candidates `function_internal_index -> body instructions`
+    let candidates = image_common_entry
+        .function_entries
+        .iter()
+        .zip(image_common_entry.relocate_list_entries.iter())
+        .enumerate()
+        .filter_map(
+            |(function_internal_index, (function_entry, relocate_list))| {
+                let mut items = decode_function_instructions(&function_entry.code, relocate_list);
+
+                let is_ends_with_end = items.last().is_some_and(|item| item.opcode == Opcode::end);
+                if !is_ends_with_end || function_entry.code.len() - 2 > max_code_length {
+                    return None;
+                }
+
+                items.pop(); // remove the last `end`
+
+                let is_straight_line = items.iter().all(|item| {
+                    !matches!(
+                        item.opcode,
+                        Opcode::end
+                            | Opcode::block
+                            | Opcode::block_alt
+                            | Opcode::block_nez
+                            | Opcode::break_
+                            | Opcode::break_alt
+                            | Opcode::recur
+                            | Opcode::call
+                    )
+                });
+
+                is_straight_line.then_some((function_internal_index, items))
+            },
+        )
+        .collect::<HashMap<usize, Vec<InstructionItem>>>();
+
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let mut inlined_count: usize = 0;
+    let mut inlined_functions: Vec<usize> = vec![];
+
+    for caller_internal_index in 0..image_common_entry.function_entries.len() {
+        let caller = &image_common_entry.function_entries[caller_internal_index];
+        let items = decode_function_instructions(
+            &caller.code,
+            &image_common_entry.relocate_list_entries[caller_internal_index],
+        );
+
+        let mut merged_local_variable_types = image_common_entry.local_variable_list_entries
+            [caller.local_variable_list_index]
+            .local_variable_types
+            .clone();
+
+        let mut items_new: Vec<InstructionItem> = Vec::with_capacity(items.len());
+        let mut is_changed = false;
+
+        // The number of blocks enclosing the current instruction,
+        // it is also the `layers` of the function frame.
+        let mut depth: u16 = 0;
+
+        for item in items {
+            match (item.opcode, item.params) {
+                (Opcode::block | Opcode::block_alt | Opcode::block_nez, _) => {
+                    depth += 1;
+                }
+                (Opcode::end, _) => {
+                    depth = depth.saturating_sub(1);
+                }
+                (Opcode::call, InstructionParams::Index(function_public_index))
+                    if function_public_index as usize >= import_function_count =>
+                {
+                    let callee_internal_index =
+                        function_public_index as usize - import_function_count;
+
+                    let opt_body = if callee_internal_index != caller_internal_index {
+                        candidates.get(&callee_internal_index)
+                    } else {
+                        None
+                    };
+
+                    if let Some(body) = opt_body {
+                        let callee = &image_common_entry.function_entries[callee_internal_index];
+                        let param_count = image_common_entry.type_entries[callee.type_index]
+                            .params
+                            .len();
+                        let callee_local_variable_types = &image_common_entry
+                            .local_variable_list_entries[callee.local_variable_list_index]
+                            .local_variable_types;
+
+                        let base_index = merged_local_variable_types.len();
+                        merged_local_variable_types.extend(callee_local_variable_types.iter());
+
+                        let mut inserted_items: Vec<InstructionItem> = vec![];
+
+                        // pop the arguments
+                        for idx in (0..param_count).rev() {
+                            inserted_items.push(build_local_store(
+                                callee_local_variable_types[idx],
+                                depth,
+                                base_index + idx,
+                            ));
+                        }
+
+                        // reset the other local variables
+                        for (idx, data_type) in callee_local_variable_types
+                            .iter()
+                            .enumerate()
+                            .skip(param_count)
+                        {
+                            inserted_items.push(build_imm_zero(*data_type));
+                            inserted_items.push(build_local_store(
+                                *data_type,
+                                depth,
+                                base_index + idx,
+                            ));
+                        }
+
+                        // the body of callee
+                        for body_item in body {
+                            let mut body_item_new = body_item.clone();
+                            body_item_new.origin_address = None;
+
+                            if let InstructionParams::Local { layers, index } = body_item.params {
+                                body_item_new.params = InstructionParams::Local {
+                                    layers: layers + depth,
+                                    index: index + base_index as u32,
+                                };
+                            }
+
+                            inserted_items.push(body_item_new);
+                        }
+
+                        // the jumps which target the `call` are redirected
+                        // to the first inserted instruction.
+                        if let Some(first_item) = inserted_items.first_mut() {
+                            first_item.origin_address = item.origin_address;
+                        }
+
+                        items_new.extend(inserted_items);
+                        is_changed = true;
+                        inlined_count += 1;
+
+                        if !inlined_functions.contains(&callee_internal_index) {
+                            inlined_functions.push(callee_internal_index);
+                        }
+
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            items_new.push(item);
+        }
+
+        if is_changed {
+            let (code, relocate_list) = encode_function_instructions(&items_new)?;
+
+            image_common_entry
+                .local_variable_list_entries
+                .push(LocalVariableListEntry::new(merged_local_variable_types));
+
+            let caller = &mut image_common_entry.function_entries[caller_internal_index];
+            caller.code = code;
+            caller.local_variable_list_index =
+                image_common_entry.local_variable_list_entries.len() - 1;
+            image_common_entry.relocate_list_entries[caller_internal_index] = relocate_list;
+        }
+    }
+
+    // Remove the inlined functions which are private and no longer referenced,
+    // in descending order so that the indices of the remaining ones are stable.
+    inlined_functions.sort();
+    for function_internal_index in inlined_functions.into_iter().rev() {
+        let is_private = image_common_entry
+            .function_name_entries
+            .iter()
+            .any(|entry| {
+                entry.internal_index == function_internal_index
+                    && entry.visibility == Visibility::Private
+            });
+
+        if is_private
+            && !is_function_referenced(
+                image_common_entry,
+                import_function_count + function_internal_index,
+            )
+        {
+            remove_function(image_common_entry, function_internal_index);
+        }
+    }
+
+    Ok(inlined_count)
+}
+
+fn build_local_store(data_type: OperandDataType, layers: u16, index: usize) -> InstructionItem {
+    let opcode = match data_type {
+        OperandDataType::I32 => Opcode::local_store_i32,
+        OperandDataType::I64 => Opcode::local_store_i64,
+        OperandDataType::F32 => Opcode::local_store_f32,
+        OperandDataType::F64 => Opcode::local_store_f64,
+    };
+
+    InstructionItem::new(
+        opcode,
+        InstructionParams::Local {
+            layers,
+            index: index as u32,
+        },
+    )
+}
+
+fn build_imm_zero(data_type: OperandDataType) -> InstructionItem {
+    match data_type {
+        OperandDataType::I32 => InstructionItem::new(Opcode::imm_i32, InstructionParams::ImmI32(0)),
+        OperandDataType::I64 => InstructionItem::new(
+            Opcode::imm_i64,
+            InstructionParams::ImmI64 { low: 0, high: 0 },
+        ),
+        OperandDataType::F32 => InstructionItem::new(Opcode::imm_f32, InstructionParams::ImmI32(0)),
+        OperandDataType::F64 => InstructionItem::new(
+            Opcode::imm_f64,
+            InstructionParams::ImmI64 { low: 0, high: 0 },
+        ),
+    }
+}
+
+// Function splitting
+// ------------------
+//
+// A function whose code exceeds the specified `max_code_length` is split by
+// moving its top-level `block`s (i.e., the blocks which are not nested in other
+// blocks) into new functions, and each moved block is replaced with a `call`.
+//
+// - The type and the local variable list of the block become the type and the
+//   local variable list of the new function, since the parameters of a block are
+//   also the first local variables of the block, the same as a function.
+// - The `layers` of the instructions within the block are unchanged, because
+//   the frame of the block becomes the frame of the new function. A `break` or
+//   `recur` whose target is the block now targets the function.
+//
+// A block can not be moved if it accesses the local variables of the function,
+// or if it contains a `break`, `recur` or `break_alt` whose target is outside
+// the block.
+//
+// The largest movable block is moved first, until the code fits the limit or
+// there is no more movable block. The new functions are appended to the
+// function list (so the existing function public indices are not changed),
+// and they are split as well if necessary. If the original function has a
+// name, the new function is named "{name}_part{N}" with private visibility.
+
+/// Splits the functions whose code exceeds `max_code_length` into multiple functions.
+///
+/// Returns the number of the new functions, or an error if the bytecode
+/// of a function can not be encoded.
+pub fn split_large_functions(
+    image_common_entry: &mut ImageCommonEntry,
+    max_code_length: usize,
+) -> Result<usize, ImageError> {
+    if image_common_entry.relocate_list_entries.len() != image_common_entry.function_entries.len() {
+        // The relocate lists are required.
+        return Ok(0);
+    }
+
+    let import_function_count = image_common_entry.import_function_entries.len();
+    let mut new_function_count: usize = 0;
+
+    // The new functions are appended, so the length of the list is checked on each iteration.
+    let mut function_internal_index: usize = 0;
+    while function_internal_index < image_common_entry.function_entries.len() {
+        while image_common_entry.function_entries[function_internal_index]
+            .code
+            .len()
+            > max_code_length
+        {
+            let items = decode_function_instructions(
+                &image_common_entry.function_entries[function_internal_index].code,
+                &image_common_entry.relocate_list_entries[function_internal_index],
+            );
+
+            let Some((block_pos, end_pos)) = find_largest_movable_block(&items) else {
+                break;
+            };
+
+            let InstructionParams::Block {
+                type_index,
+                local_variable_list_index,
+            } = items[block_pos].params
+            else {
+                unreachable!()
+            };
+
+            let new_function_internal_index = image_common_entry.function_entries.len();
+            let new_function_public_index = import_function_count + new_function_internal_index;
+
+            // The body of the new function, the `end` of the block becomes
+            // the `end` of the function.
+            let mut depth: usize = 0;
+            let body_items = items[block_pos + 1..=end_pos]
+                .iter()
+                .map(|item| {
+                    let mut item_new = item.clone();
+                    match (item.opcode, item.params) {
+                        (Opcode::block | Opcode::block_alt | Opcode::block_nez, _) => {
+                            depth += 1;
+                        }
+                        (Opcode::end, _) => {
+                            depth = depth.saturating_sub(1);
+                        }
+                        (
+                            Opcode::break_ | Opcode::recur,
+                            InstructionParams::Break { layers, .. },
+                        ) if layers as usize == depth => {
+                            // The target is the function, and the VM ignores the offset.
+                            item_new.params = InstructionParams::Break { layers, offset: 0 };
+                            item_new.origin_jump_target = None;
+                        }
+                        _ => {}
+                    }
+                    item_new
+                })
+                .collect::<Vec<InstructionItem>>();
+
+            let (body_code, body_relocate_list) = encode_function_instructions(&body_items)?;
+
+            // Replaces the block with a `call`, the jumps which target
+            // the block are redirected to the `call`.
+            let call_info = get_opcode_info(Opcode::call);
+            let mut call_item = InstructionItem::new(
+                Opcode::call,
+                InstructionParams::Index(new_function_public_index as u32),
+            );
+            call_item.relocates = vec![(
+                call_info.operand_offset(0),
+                RelocateType::FunctionPublicIndex,
+            )];
+            call_item.origin_address = items[block_pos].origin_address;
+
+            let mut items_new = items[..block_pos].to_vec();
+            items_new.push(call_item);
+            items_new.extend_from_slice(&items[end_pos + 1..]);
+
+            let (code, relocate_list) = encode_function_instructions(&items_new)?;
+
+            image_common_entry.function_entries[function_internal_index].code = code;
+            image_common_entry.relocate_list_entries[function_internal_index] = relocate_list;
+
+            image_common_entry.function_entries.push(FunctionEntry::new(
+                type_index as usize,
+                local_variable_list_index as usize,
+                body_code,
+            ));
+            image_common_entry
+                .relocate_list_entries
+                .push(body_relocate_list);
+
+            // The name of the new function.
+            let opt_full_name = image_common_entry
+                .function_name_entries
+                .iter()
+                .find(|entry| entry.internal_index == function_internal_index)
+                .map(|entry| entry.full_name.clone());
+
+            if let Some(full_name) = opt_full_name {
+                let mut part_number: usize = 1;
+                let new_full_name = loop {
+                    let candidate = format!("{}_part{}", full_name, part_number);
+                    if !image_common_entry
+                        .function_name_entries
+                        .iter()
+                        .any(|entry| entry.full_name == candidate)
+                    {
+                        break candidate;
+                    }
+                    part_number += 1;
+                };
+
+                image_common_entry
+                    .function_name_entries
+                    .push(FunctionNameEntry::new(
+                        new_full_name,
+                        Visibility::Private,
+                        new_function_internal_index,
+                    ));
+            }
+
+            new_function_count += 1;
+        }
+
+        function_internal_index += 1;
+    }
+
+    Ok(new_function_count)
+}
+
+// Returns the positions `(block, end)` of the largest top-level `block` which
+// can be moved into a new function, or `None` if there is no such block.
+fn find_largest_movable_block(items: &[InstructionItem]) -> Option<(usize, usize)> {
+    // A block which is not larger than a `call` is not worth moving.
+    let call_length = get_opcode_info(Opcode::call).length();
+
+    // `(block_length, block_pos, end_pos)`
+    let mut candidates: Vec<(usize, usize, usize)> = vec![];
+
+    // The number of blocks enclosing the current instruction.
+    let mut depth: usize = 0;
+
+    // `(block_pos, is_movable)` of the current top-level `block`.
+    let mut current: Option<(usize, bool)> = None;
+
+    for (pos, item) in items.iter().enumerate() {
+        // The depth within the current top-level block, `0` means the block itself.
+        let inner_depth = depth.saturating_sub(1);
+
+        match (item.opcode, item.params) {
+            (Opcode::block | Opcode::block_alt | Opcode::block_nez, _) => {
+                if depth == 0 && item.opcode == Opcode::block {
+                    current = Some((pos, true));
+                }
+                depth += 1;
+            }
+            (Opcode::end, _) => {
+                depth = depth.saturating_sub(1);
+
+                if depth == 0 {
+                    if let Some((block_pos, true)) = current.take() {
+                        let block_length =
+                            match (items[block_pos].origin_address, item.origin_address) {
+                                (Some(block_addr), Some(end_addr)) => end_addr + 2 - block_addr,
+                                _ => 0,
+                            };
+
+                        if block_length > call_length {
+                            candidates.push((block_length, block_pos, pos));
+                        }
+                    }
+                }
+            }
+            (_, InstructionParams::Local { layers, .. })
+            | (Opcode::break_ | Opcode::recur, InstructionParams::Break { layers, .. })
+                if layers as usize > inner_depth =>
+            {
+                if let Some((_, is_movable)) = current.as_mut() {
+                    *is_movable = false;
+                }
+            }
+            (Opcode::break_alt, _) if inner_depth == 0 => {
+                if let Some((_, is_movable)) = current.as_mut() {
+                    *is_movable = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    candidates
+        .into_iter()
+        .max_by_key(|(block_length, _, _)| *block_length)
+        .map(|(_, block_pos, end_pos)| (block_pos, end_pos))
+}
+
+// Import module pruning
+// ---------------------
+//
+// After modules are merged (i.e., statically linked), the imported functions
+// and data which refer to the merged modules are resolved and removed, so some
+// of the import module entries may no longer be referenced.
+//
+// The unreferenced entries are removed and the `import_module_index` of the
+// imported functions and data are updated. The self-reference entry (i.e.,
+// the current module of an object file) is always kept.
+
+/// Removes the import module entries which are not referenced by any
+/// imported function or imported data.
+///
+/// Returns the number of the removed entries.
+pub fn prune_import_modules(image_common_entry: &mut ImageCommonEntry) -> usize {
+    let import_module_count = image_common_entry.import_module_entries.len();
+
+    let removed_indices = (0..import_module_count)
+        .filter(|import_module_index| {
+            let is_self_reference = matches!(
+                *image_common_entry.import_module_entries[*import_module_index].module_dependency,
+                ModuleDependency::Current
+            );
+
+            let is_referenced = image_common_entry
+                .import_function_entries
+                .iter()
+                .any(|entry| entry.import_module_index == *import_module_index)
+                || image_common_entry
+                    .import_data_entries
+                    .iter()
+                    .any(|entry| entry.import_module_index == *import_module_index);
+
+            !is_self_reference && !is_referenced
+        })
+        .collect::<Vec<usize>>();
+
+    if removed_indices.is_empty() {
+        return 0;
+    }
+
+    // in descending order so that the indices of the remaining ones are stable.
+    for import_module_index in removed_indices.iter().rev() {
+        image_common_entry
+            .import_module_entries
+            .remove(*import_module_index);
+    }
+
+    let index_remap = IndexRemap {
+        import_module_indices: IndexRemap::build_removal_map(import_module_count, &removed_indices),
+        ..Default::default()
+    };
+
+    // The import module indices are not located in the bytecode,
+    // so the bytecode is not rewritten and it never fails.
+    index_remap
+        .apply_to_common_entry(image_common_entry)
+        .unwrap();
+
+    removed_indices.len()
+}
+
+// Calls the closure with `(function_internal_index, param_offset, value)` for
+// every relocatable parameter of the specified type.
+fn for_each_relocate_param(
+    image_common_entry: &ImageCommonEntry,
+    relocate_type: RelocateType,
+    mut f: impl FnMut(usize, usize, u32),
+) {
+    for (function_internal_index, (function_entry, relocate_list)) in image_common_entry
+        .function_entries
+        .iter()
+        .zip(image_common_entry.relocate_list_entries.iter())
+        .enumerate()
+    {
+        for relocate_entry in &relocate_list.relocate_entries {
+            if relocate_entry.relocate_type == relocate_type {
+                let offset = relocate_entry.offset_in_function;
+                let value =
+                    u32::from_le_bytes(function_entry.code[offset..offset + 4].try_into().unwrap());
+                f(function_internal_index, offset, value);
+            }
+        }
+    }
+}
+
+fn is_function_referenced(
+    image_common_entry: &ImageCommonEntry,
+    function_public_index: usize,
+) -> bool {
+    let mut is_referenced = false;
+    for_each_relocate_param(
+        image_common_entry,
+        RelocateType::FunctionPublicIndex,
+        |_, _, value| {
+            if value as usize == function_public_index {
+                is_referenced = true;
+            }
+        },
+    );
+    is_referenced
+}
+
+// Removes the function and updates the function public indices in the bytecode,
+// the internal indices of the "function name" entries and the initializers.
+fn remove_function(image_common_entry: &mut ImageCommonEntry, function_internal_index: usize) {
+    let import_function_count = image_common_entry.import_function_entries.len();
+    let function_count = image_common_entry.function_entries.len();
+
+    image_common_entry
+        .function_entries
+        .remove(function_internal_index);
+    image_common_entry
+        .relocate_list_entries
+        .remove(function_internal_index);
+
+    image_common_entry
+        .function_name_entries
+        .retain(|entry| entry.internal_index != function_internal_index);
+
+    let index_remap = IndexRemap {
+        function_public_indices: IndexRemap::build_removal_map(
+            import_function_count + function_count,
+            &[import_function_count + function_internal_index],
+        ),
+        ..Default::default()
+    };
+
+    // The relocate types are not changed, and the relocate offsets have been
+    // checked by `inline_small_functions`, so it never fails.
+    index_remap
+        .apply_to_common_entry(image_common_entry)
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{
+        opcode::Opcode, DataSectionType, DependencyCondition, DependencyLocal, EffectiveVersion,
+        MemoryDataType, ModuleDependency, OperandDataType,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            FunctionEntry, FunctionNameEntry, ImageCommonEntry, ImportDataEntry,
+            ImportFunctionEntry, ImportModuleEntry, LocalVariableListEntry, RelocateEntry,
+            RelocateListEntry, TypeEntry,
+        },
+        image_transform::{inline_small_functions, prune_import_modules, split_large_functions},
+        module_image::{ImageType, Visibility},
+    };
+
+    fn build_image_common_entry(
+        type_entries: Vec<TypeEntry>,
+        local_variable_list_entries: Vec<LocalVariableListEntry>,
+        function_entries: Vec<FunctionEntry>,
+        import_function_entries: Vec<ImportFunctionEntry>,
+        function_name_entries: Vec<FunctionNameEntry>,
+        relocate_list_entries: Vec<RelocateListEntry>,
+    ) -> ImageCommonEntry {
+        ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 0, 0),
+            image_type: ImageType::ObjectFile,
+            type_entries,
+            local_variable_list_entries,
+            function_entries,
+            read_only_data_entries: vec![],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![],
+            import_module_entries: vec![],
+            import_function_entries,
+            import_data_entries: vec![],
+            function_name_entries,
+            data_data_entries: vec![],
+            relocate_list_entries,
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+            initializer_entries: vec![],
+        }
+    }
+
+    #[test]
+    fn test_inline_small_functions() {
+        // function 0 (public index 1): `main`, calls the function 1 and 2.
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0000
+            .append_opcode_i32(Opcode::call, 2) // 0x0008
+            .append_opcode_i32(Opcode::call, 3) // 0x0010
+            .append_opcode(Opcode::end) // 0x0018
+            .to_bytes();
+
+        // function 1 (public index 2): `inc`, (i32) -> i32
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0)
+            .append_opcode_i16(Opcode::add_imm_i32, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // function 2 (public index 3): `neg`, (i32) -> i32, it is public
+        let code2 = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0)
+            .append_opcode(Opcode::neg_i32)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        // function 3 (public index 4): calls the function 0
+        let code3 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let mut image_common_entry = build_image_common_entry(
+            vec![
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+            ],
+            vec![
+                LocalVariableListEntry::new(vec![OperandDataType::I64]),
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+            ],
+            vec![
+                FunctionEntry::new(0, 0, code0),
+                FunctionEntry::new(1, 1, code1),
+                FunctionEntry::new(1, 1, code2),
+                FunctionEntry::new(0, 0, code3),
+            ],
+            vec![ImportFunctionEntry::new("bar::baz".to_owned(), 0, 0)],
+            vec![
+                FunctionNameEntry::new("foo::main".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::inc".to_owned(), Visibility::Private, 1),
+                FunctionNameEntry::new("foo::neg".to_owned(), Visibility::Public, 2),
+                FunctionNameEntry::new("foo::test".to_owned(), Visibility::Private, 3),
+            ],
+            vec![
+                RelocateListEntry::new(vec![
+                    RelocateEntry::from_function_public_index(0x08),
+                    RelocateEntry::from_function_public_index(0x10),
+                ]),
+                RelocateListEntry::new(vec![]),
+                RelocateListEntry::new(vec![]),
+                RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(0)]),
+            ],
+        );
+
+        assert_eq!(
+            inline_small_functions(&mut image_common_entry, 16).unwrap(),
+            2
+        );
+
+        // the function `inc` is removed, the function `neg` is public so it is kept.
+        assert_eq!(image_common_entry.function_entries.len(), 3);
+        assert_eq!(
+            image_common_entry.function_name_entries,
+            vec![
+                FunctionNameEntry::new("foo::main".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::neg".to_owned(), Visibility::Public, 1),
+                FunctionNameEntry::new("foo::test".to_owned(), Visibility::Private, 2),
+            ]
+        );
+
+        // a new local variable list is appended for `main`
+        assert_eq!(
+            image_common_entry.function_entries[0].local_variable_list_index,
+            2
+        );
+        assert_eq!(
+            image_common_entry.local_variable_list_entries[2],
+            LocalVariableListEntry::new(vec![
+                OperandDataType::I64,
+                OperandDataType::I32,
+                OperandDataType::I32
+            ])
+        );
+
+        let code0_expect = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0000
+            // inlined `inc`
+            .append_opcode_i16_i32(Opcode::local_store_i32, 0, 1) // 0x0008
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 1) // 0x0010
+            .append_opcode_i16(Opcode::add_imm_i32, 1) // 0x0018
+            // inlined `neg`
+            .append_opcode_i16_i32(Opcode::local_store_i32, 0, 2) // 0x001c
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 2) // 0x0024
+            .append_opcode(Opcode::neg_i32) // 0x002c
+            .append_opcode(Opcode::end) // 0x002e
+            .to_bytes();
+
+        assert_eq!(image_common_entry.function_entries[0].code, code0_expect);
+        assert_eq!(
+            image_common_entry.relocate_list_entries[0],
+            RelocateListEntry::new(vec![])
+        );
+
+        // the function `test` still calls `main` (public index 1)
+        let code3_expect = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::call, 1)
+            .append_opcode(Opcode::end)
+            .to_bytes();
+        assert_eq!(image_common_entry.function_entries[2].code, code3_expect);
+    }
+
+    #[test]
+    fn test_split_large_functions() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0000
+            // movable
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0008
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0) // 0x0014
+            .append_opcode_i16_i32(Opcode::break_, 0, 0x12) // 0x001c
+            .append_opcode_i32(Opcode::imm_i32, 13) // 0x0024
+            .append_opcode(Opcode::end) // 0x002c
+            // not movable, accesses the local variables of the function
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0030
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 1, 0) // 0x003c
+            .append_opcode(Opcode::end) // 0x0044
+            .append_opcode(Opcode::end) // 0x0046
+            .to_bytes();
+
+        let mut image_common_entry = build_image_common_entry(
+            vec![TypeEntry::new(vec![], vec![])],
+            vec![
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+                LocalVariableListEntry::new(vec![OperandDataType::I64]),
+            ],
+            vec![FunctionEntry::new(0, 0, code0)],
+            vec![ImportFunctionEntry::new("bar::baz".to_owned(), 0, 0)],
+            vec![FunctionNameEntry::new(
+                "foo::main".to_owned(),
+                Visibility::Public,
+                0,
+            )],
+            vec![RelocateListEntry::new(
+                [
+                    RelocateEntry::from_block_with_type_and_local_variables(0x08),
+                    RelocateEntry::from_block_with_type_and_local_variables(0x30),
+                ]
+                .concat(),
+            )],
+        );
+
+        assert_eq!(
+            split_large_functions(&mut image_common_entry, 40).unwrap(),
+            1
+        );
+
+        // the first block is replaced with a call to the new function (public index 2)
+        let code0_expect = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0000
+            .append_opcode_i32(Opcode::call, 2) // 0x0008
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0010
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 1, 0) // 0x001c
+            .append_opcode(Opcode::end) // 0x0024
+            .append_opcode(Opcode::end) // 0x0026
+            .to_bytes();
+
+        // the `break` targets the function now
+        let code1_expect = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0) // 0x0000
+            .append_opcode_i16_i32(Opcode::break_, 0, 0) // 0x0008
+            .append_opcode_i32(Opcode::imm_i32, 13) // 0x0010
+            .append_opcode(Opcode::end) // 0x0018
+            .to_bytes();
+
+        assert_eq!(
+            image_common_entry.function_entries,
+            vec![
+                FunctionEntry::new(0, 0, code0_expect),
+                FunctionEntry::new(0, 1, code1_expect),
+            ]
+        );
+
+        assert_eq!(
+            image_common_entry.relocate_list_entries,
+            vec![
+                RelocateListEntry::new(
+                    [
+                        vec![RelocateEntry::from_function_public_index(0x08)],
+                        RelocateEntry::from_block_with_type_and_local_variables(0x10),
+                    ]
+                    .concat()
+                ),
+                RelocateListEntry::new(vec![]),
+            ]
+        );
+
+        assert_eq!(
+            image_common_entry.function_name_entries,
+            vec![
+                FunctionNameEntry::new("foo::main".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::main_part1".to_owned(), Visibility::Private, 1),
+            ]
+        );
+
+        // the remaining block can not be moved
+        assert_eq!(
+            split_large_functions(&mut image_common_entry, 16).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_prune_import_modules() {
+        let build_import_module_entry = |name: &str| {
+            ImportModuleEntry::new(
+                name.to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: name.to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            )
+        };
+
+        let mut image_common_entry = build_image_common_entry(
+            vec![TypeEntry::new(vec![], vec![])],
+            vec![],
+            vec![],
+            vec![ImportFunctionEntry::new("baz::f".to_owned(), 3, 0)],
+            vec![],
+            vec![],
+        );
+        image_common_entry.import_module_entries = vec![
+            ImportModuleEntry::self_reference_entry(),
+            build_import_module_entry("bar"), // merged
+            build_import_module_entry("qux"), // merged
+            build_import_module_entry("baz"),
+            build_import_module_entry("quux"),
+        ];
+        image_common_entry.import_data_entries = vec![ImportDataEntry::new(
+            "quux::d".to_owned(),
+            4,
+            DataSectionType::ReadOnly,
+            MemoryDataType::I32,
+        )];
+
+        assert_eq!(prune_import_modules(&mut image_common_entry), 2);
+
+        assert_eq!(
+            image_common_entry.import_module_entries,
+            vec![
+                ImportModuleEntry::self_reference_entry(),
+                build_import_module_entry("baz"),
+                build_import_module_entry("quux"),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.import_function_entries[0].import_module_index,
+            1
+        );
+        assert_eq!(
+            image_common_entry.import_data_entries[0].import_module_index,
+            2
+        );
+
+        // nothing to prune
+        assert_eq!(prune_import_modules(&mut image_common_entry), 0);
+    }
+}
@@ -0,0 +1,376 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The index remapping of a module, it is shared by the transformations which
+// reorder, remove (e.g., strip and dedup) or merge the items of the sections.
+//
+// A transformation updates the item lists first, then builds an `IndexRemap`
+// with the `old index -> new index` maps, and applies it to the module,
+// so that all fields which reference the items are updated consistently:
+//
+// - type index: the functions, the imported functions, the external functions
+//   and the `block`/`block_alt` instructions.
+// - local variable list index: the functions and the `block*` instructions.
+// - function public index: the function names, the initializers and
+//   the `call`/`get_function`/`host_addr_function` instructions.
+// - data public index: the data names and the data instructions.
+// - external function index: the `extcall` instructions.
+// - import module index: the imported functions and the imported data.
+//
+// The indices which are absent from the maps are unchanged.
+//
+// The indices within the bytecode are located by the relocate lists.
+//
+// The function names and the initializers use the function internal index, and
+// the data names use the internal index within the data section, they are converted
+// to/from the public indices by `PublicIndexSpace` with the amounts of the imported items
+// and the data items of the module (i.e., after the item lists are updated).
+
+use std::collections::HashMap;
+
+use crate::{
+    bytecode_transform::retarget_indices, entry::ImageCommonEntry, module_image::RelocateType,
+    public_index::PublicIndexSpace, ImageError,
+};
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct IndexRemap {
+    pub type_indices: HashMap<usize, usize>,
+    pub local_variable_list_indices: HashMap<usize, usize>,
+    pub function_public_indices: HashMap<usize, usize>,
+    pub data_public_indices: HashMap<usize, usize>,
+    pub external_function_indices: HashMap<usize, usize>,
+    pub import_module_indices: HashMap<usize, usize>,
+}
+
+impl IndexRemap {
+    /// Builds the map `old index -> new index` for removing the items,
+    /// i.e., the following items are moved forward.
+    ///
+    /// The removed indices are not in the map.
+    pub fn build_removal_map(
+        item_count: usize,
+        removed_indices: &[usize],
+    ) -> HashMap<usize, usize> {
+        let mut map = HashMap::new();
+        let mut new_index = 0;
+
+        for old_index in 0..item_count {
+            if removed_indices.contains(&old_index) {
+                continue;
+            }
+
+            if old_index != new_index {
+                map.insert(old_index, new_index);
+            }
+            new_index += 1;
+        }
+
+        map
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.type_indices.is_empty()
+            && self.local_variable_list_indices.is_empty()
+            && self.function_public_indices.is_empty()
+            && self.data_public_indices.is_empty()
+            && self.external_function_indices.is_empty()
+            && self.import_module_indices.is_empty()
+    }
+
+    /// Rewrites all fields and the bytecode of the module which reference the items.
+    ///
+    /// Returns an error if the bytecode of a function can not be rewritten, see
+    /// `bytecode_transform::retarget_indices`, in which case the entry may be
+    /// partially modified.
+    pub fn apply_to_common_entry(
+        &self,
+        image_common_entry: &mut ImageCommonEntry,
+    ) -> Result<(), ImageError> {
+        let map_index = |map: &HashMap<usize, usize>, index: usize| -> usize {
+            map.get(&index).copied().unwrap_or(index)
+        };
+
+        // types and local variable lists
+        for function_entry in image_common_entry.function_entries.iter_mut() {
+            function_entry.type_index = map_index(&self.type_indices, function_entry.type_index);
+            function_entry.local_variable_list_index = map_index(
+                &self.local_variable_list_indices,
+                function_entry.local_variable_list_index,
+            );
+        }
+
+        for import_function_entry in image_common_entry.import_function_entries.iter_mut() {
+            import_function_entry.type_index =
+                map_index(&self.type_indices, import_function_entry.type_index);
+        }
+
+        for external_function_entry in image_common_entry.external_function_entries.iter_mut() {
+            external_function_entry.type_index =
+                map_index(&self.type_indices, external_function_entry.type_index);
+        }
+
+        // import modules
+        for import_function_entry in image_common_entry.import_function_entries.iter_mut() {
+            import_function_entry.import_module_index = map_index(
+                &self.import_module_indices,
+                import_function_entry.import_module_index,
+            );
+        }
+
+        for import_data_entry in image_common_entry.import_data_entries.iter_mut() {
+            import_data_entry.import_module_index = map_index(
+                &self.import_module_indices,
+                import_data_entry.import_module_index,
+            );
+        }
+
+        // functions
+        let public_index_space = PublicIndexSpace::from_common_entry(image_common_entry);
+        let map_function_internal_index = |function_internal_index: usize| -> usize {
+            let function_public_index = map_index(
+                &self.function_public_indices,
+                public_index_space.function_public_index(function_internal_index),
+            );
+            function_public_index - public_index_space.import_function_count
+        };
+
+        for function_name_entry in image_common_entry.function_name_entries.iter_mut() {
+            function_name_entry.internal_index =
+                map_function_internal_index(function_name_entry.internal_index);
+        }
+
+        for initializer_entry in image_common_entry.initializer_entries.iter_mut() {
+            initializer_entry.function_internal_index =
+                map_function_internal_index(initializer_entry.function_internal_index);
+        }
+
+        // data
+        for data_name_entry in image_common_entry.data_data_entries.iter_mut() {
+            let data_public_index = map_index(
+                &self.data_public_indices,
+                public_index_space.data_public_index(
+                    data_name_entry.section_type,
+                    data_name_entry.internal_index_in_section,
+                ),
+            );
+
+            (
+                data_name_entry.section_type,
+                data_name_entry.internal_index_in_section,
+            ) = public_index_space
+                .data_section_type_and_internal_index_from_public_index(data_public_index)
+                .unwrap();
+        }
+
+        // bytecode
+        let bytecode_map = [
+            (RelocateType::TypeIndex, &self.type_indices),
+            (
+                RelocateType::LocalVariableListIndex,
+                &self.local_variable_list_indices,
+            ),
+            (
+                RelocateType::FunctionPublicIndex,
+                &self.function_public_indices,
+            ),
+            (RelocateType::DataPublicIndex, &self.data_public_indices),
+            (
+                RelocateType::ExternalFunctionIndex,
+                &self.external_function_indices,
+            ),
+        ]
+        .iter()
+        .flat_map(|(relocate_type, map)| {
+            map.iter().map(|(old_index, new_index)| {
+                ((*relocate_type, *old_index), (*relocate_type, *new_index))
+            })
+        })
+        .collect::<HashMap<_, _>>();
+
+        if bytecode_map.is_empty() {
+            return Ok(());
+        }
+
+        for (function_entry, relocate_list) in image_common_entry
+            .function_entries
+            .iter_mut()
+            .zip(image_common_entry.relocate_list_entries.iter_mut())
+        {
+            retarget_indices(&mut function_entry.code, relocate_list, &bytecode_map)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{opcode::Opcode, DataSectionType, EffectiveVersion, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            DataNameEntry, ExternalFunctionEntry, FunctionEntry, FunctionNameEntry,
+            ImageCommonEntry, InitializerEntry, LocalVariableListEntry, ReadOnlyDataEntry,
+            ReadWriteDataEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+        },
+        index_remap::IndexRemap,
+        module_image::{ImageType, InitializerType, Visibility},
+    };
+
+    #[test]
+    fn test_build_removal_map() {
+        assert_eq!(
+            IndexRemap::build_removal_map(5, &[1, 3]),
+            HashMap::from([(2, 1), (4, 2)])
+        );
+        assert!(IndexRemap::build_removal_map(3, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_apply_to_common_entry() {
+        let build_code = |block_type_index: u32,
+                          block_local_variable_list_index: u32,
+                          function_public_index: u32,
+                          data_public_index: u32,
+                          external_function_index: u32| {
+            BytecodeWriterHelper::new()
+                .append_opcode_i32_i32(
+                    Opcode::block,
+                    block_type_index,
+                    block_local_variable_list_index,
+                ) // 0x0000
+                .append_opcode(Opcode::end) // 0x000c
+                .append_opcode_i32(Opcode::call, function_public_index) // 0x0010
+                .append_opcode_i32(Opcode::get_data, data_public_index) // 0x0018
+                .append_opcode_i32(Opcode::extcall, external_function_index) // 0x0020
+                .append_opcode(Opcode::end) // 0x0028
+                .to_bytes()
+        };
+
+        let relocate_list_entry = RelocateListEntry::new(
+            [
+                RelocateEntry::from_block_with_type_and_local_variables(0x0),
+                vec![
+                    RelocateEntry::from_function_public_index(0x10),
+                    RelocateEntry::from_data_public_index(0x18),
+                    RelocateEntry::from_external_function_index(0x20),
+                ],
+            ]
+            .concat(),
+        );
+
+        let mut image_common_entry = ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 0, 0),
+            image_type: ImageType::ObjectFile,
+            type_entries: vec![
+                TypeEntry::new(vec![], vec![]),
+                TypeEntry::new(vec![OperandDataType::I32], vec![]),
+            ],
+            local_variable_list_entries: vec![
+                LocalVariableListEntry::new(vec![]),
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+            ],
+            function_entries: vec![
+                FunctionEntry::new(0, 0, build_code(0, 1, 1, 0, 0)),
+                FunctionEntry::new(1, 1, build_code(1, 0, 0, 1, 1)),
+            ],
+            read_only_data_entries: vec![ReadOnlyDataEntry::from_i32(11)],
+            read_write_data_entries: vec![ReadWriteDataEntry::from_i32(13)],
+            uninit_data_entries: vec![],
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            function_name_entries: vec![
+                FunctionNameEntry::new("foo::a".to_owned(), Visibility::Public, 0),
+                FunctionNameEntry::new("foo::b".to_owned(), Visibility::Public, 1),
+            ],
+            data_data_entries: vec![
+                DataNameEntry::new(
+                    "foo::x".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::ReadOnly,
+                    0,
+                ),
+                DataNameEntry::new(
+                    "foo::y".to_owned(),
+                    Visibility::Public,
+                    DataSectionType::ReadWrite,
+                    0,
+                ),
+            ],
+            relocate_list_entries: vec![relocate_list_entry.clone(), relocate_list_entry.clone()],
+            external_library_entries: vec![],
+            external_function_entries: vec![
+                ExternalFunctionEntry::new("ext_a".to_owned(), 0, 0),
+                ExternalFunctionEntry::new("ext_b".to_owned(), 0, 1),
+            ],
+            initializer_entries: vec![InitializerEntry::new(InitializerType::Constructor, 1)],
+        };
+
+        // swap all items
+        let swap_map = HashMap::from([(0, 1), (1, 0)]);
+        let index_remap = IndexRemap {
+            type_indices: swap_map.clone(),
+            local_variable_list_indices: swap_map.clone(),
+            function_public_indices: swap_map.clone(),
+            data_public_indices: swap_map.clone(),
+            external_function_indices: swap_map,
+            ..Default::default()
+        };
+        index_remap
+            .apply_to_common_entry(&mut image_common_entry)
+            .unwrap();
+
+        assert_eq!(
+            image_common_entry.function_entries,
+            vec![
+                FunctionEntry::new(1, 1, build_code(1, 0, 0, 1, 1)),
+                FunctionEntry::new(0, 0, build_code(0, 1, 1, 0, 0)),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.relocate_list_entries,
+            vec![relocate_list_entry.clone(), relocate_list_entry]
+        );
+        assert_eq!(
+            image_common_entry
+                .function_name_entries
+                .iter()
+                .map(|entry| entry.internal_index)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert_eq!(
+            image_common_entry
+                .data_data_entries
+                .iter()
+                .map(|entry| (entry.section_type, entry.internal_index_in_section))
+                .collect::<Vec<_>>(),
+            vec![
+                (DataSectionType::ReadWrite, 0),
+                (DataSectionType::ReadOnly, 0),
+            ]
+        );
+        assert_eq!(
+            image_common_entry
+                .external_function_entries
+                .iter()
+                .map(|entry| entry.type_index)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert_eq!(
+            image_common_entry.initializer_entries,
+            vec![InitializerEntry::new(InitializerType::Constructor, 0)]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The global initialization order of the modules of an application.
+//
+// A module declares that an imported data item must be initialized before its own
+// constructors run by `ImportDataEntry::requires_initialization`. When linking,
+// the declarations are resolved (via the data index) into the dependency edges
+// between modules, i.e., `InitializationDependencyEntry`, and stored in the
+// "initialization dependency" section of the application image.
+//
+// The loader then runs the constructors of the modules in the order computed
+// by `compute_initialization_order`: a module is initialized after all modules
+// it depends on, the modules without dependencies between them keep the order of
+// the module indices. The finalizers are run in the reverse order.
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use crate::entry::{DataIndexListEntry, ImportDataEntry, InitializationDependencyEntry};
+
+#[derive(Debug, PartialEq)]
+pub struct InitializationOrderError {
+    // The modules which can not be initialized, i.e., the modules in the dependency
+    // cycles and the modules depending on them, in ascending order.
+    pub module_indices: Vec<usize>,
+}
+
+impl Display for InitializationOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Circular initialization dependency between modules: {:?}.",
+            self.module_indices
+        )
+    }
+}
+
+impl std::error::Error for InitializationOrderError {}
+
+/// Resolves the imported data items which require initialization into
+/// the dependency edges between modules.
+///
+/// `import_data_entries_list` contains the import data entries of each module,
+/// in the order of the module indices. Since the imported data items come first
+/// in the data public indices, the index of an import data entry is also
+/// its data public index in the data index list of the module.
+///
+/// The data items which are resolved to the module itself are skipped.
+pub fn build_initialization_dependency_entries(
+    import_data_entries_list: &[Vec<ImportDataEntry>],
+    data_index_list_entries: &[DataIndexListEntry],
+) -> Vec<InitializationDependencyEntry> {
+    let mut entries = vec![];
+
+    for (module_index, import_data_entries) in import_data_entries_list.iter().enumerate() {
+        for (data_public_index, _) in import_data_entries
+            .iter()
+            .enumerate()
+            .filter(|(_, import_data_entry)| import_data_entry.requires_initialization)
+        {
+            let data_index_entry =
+                &data_index_list_entries[module_index].index_entries[data_public_index];
+
+            if data_index_entry.target_module_index == module_index {
+                continue;
+            }
+
+            entries.push(InitializationDependencyEntry::new(
+                module_index,
+                data_index_entry.target_module_index,
+                data_index_entry.target_data_section_type,
+                data_index_entry.data_internal_index_in_section,
+            ));
+        }
+    }
+
+    entries
+}
+
+/// Computes the order in which the constructors of the modules run.
+pub fn compute_initialization_order(
+    module_count: usize,
+    initialization_dependency_entries: &[InitializationDependencyEntry],
+) -> Result<Vec<usize>, InitializationOrderError> {
+    // The modules which depend on each module, and the amount of
+    // the (distinct) dependencies of each module.
+    let mut dependents: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); module_count];
+    let mut dependency_counts = vec![0usize; module_count];
+
+    for entry in initialization_dependency_entries {
+        if dependents[entry.target_module_index].insert(entry.module_index) {
+            dependency_counts[entry.module_index] += 1;
+        }
+    }
+
+    let mut ready_module_indices = (0..module_count)
+        .filter(|module_index| dependency_counts[*module_index] == 0)
+        .collect::<BTreeSet<usize>>();
+
+    let mut order = Vec::with_capacity(module_count);
+    while let Some(module_index) = ready_module_indices.pop_first() {
+        order.push(module_index);
+
+        for dependent_module_index in &dependents[module_index] {
+            dependency_counts[*dependent_module_index] -= 1;
+            if dependency_counts[*dependent_module_index] == 0 {
+                ready_module_indices.insert(*dependent_module_index);
+            }
+        }
+    }
+
+    if order.len() == module_count {
+        Ok(order)
+    } else {
+        Err(InitializationOrderError {
+            module_indices: (0..module_count)
+                .filter(|module_index| dependency_counts[*module_index] > 0)
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{DataSectionType, MemoryDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            DataIndexEntry, DataIndexListEntry, ImportDataEntry, InitializationDependencyEntry,
+        },
+        initialization_order::{
+            build_initialization_dependency_entries, compute_initialization_order,
+            InitializationOrderError,
+        },
+    };
+
+    #[test]
+    fn test_build_initialization_dependency_entries() {
+        let build_import_data_entry = |full_name: &str, requires_initialization: bool| {
+            ImportDataEntry::new(
+                full_name.to_owned(),
+                1,
+                DataSectionType::ReadWrite,
+                MemoryDataType::I32,
+            )
+            .with_requires_initialization(requires_initialization)
+        };
+
+        let import_data_entries_list = vec![
+            vec![
+                build_import_data_entry("foo::config", true),
+                build_import_data_entry("foo::counter", false),
+            ],
+            vec![],
+            vec![build_import_data_entry("foo::config", true)],
+        ];
+
+        let data_index_list_entries = vec![
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 0),
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 1),
+                DataIndexEntry::new(0, DataSectionType::ReadOnly, 0),
+            ]),
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 0),
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 1),
+            ]),
+            DataIndexListEntry::new(vec![DataIndexEntry::new(1, DataSectionType::ReadWrite, 0)]),
+        ];
+
+        assert_eq!(
+            build_initialization_dependency_entries(
+                &import_data_entries_list,
+                &data_index_list_entries
+            ),
+            vec![
+                InitializationDependencyEntry::new(0, 1, DataSectionType::ReadWrite, 0),
+                InitializationDependencyEntry::new(2, 1, DataSectionType::ReadWrite, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_initialization_order() {
+        let build_entry = |module_index: usize, target_module_index: usize| {
+            InitializationDependencyEntry::new(
+                module_index,
+                target_module_index,
+                DataSectionType::ReadWrite,
+                0,
+            )
+        };
+
+        assert_eq!(compute_initialization_order(3, &[]), Ok(vec![0, 1, 2]));
+
+        // module 0 depends on 2, module 2 depends on 3 (twice)
+        assert_eq!(
+            compute_initialization_order(
+                4,
+                &[build_entry(0, 2), build_entry(2, 3), build_entry(2, 3)]
+            ),
+            Ok(vec![1, 3, 2, 0])
+        );
+
+        // cycle: 1 -> 2 -> 1
+        assert_eq!(
+            compute_initialization_order(
+                4,
+                &[build_entry(0, 1), build_entry(1, 2), build_entry(2, 1)]
+            ),
+            Err(InitializationOrderError {
+                module_indices: vec![0, 1, 2]
+            })
+        );
+    }
+}
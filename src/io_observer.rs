@@ -0,0 +1,255 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The observer of reading and writing images.
+//
+// The `*_with_observer` functions of the entry reader and the entry writer
+// notify the observer after each section is read (converted into entries)
+// or written (converted into bytes), with the time spent and the section length,
+// so that the build systems can report where the time goes when handling
+// thousands of modules. The writer also reports the overall progress
+// (sections completed and bytes written), so that the CLI front-ends can
+// render progress bars when emitting large images.
+//
+// This crate does not depend on any metrics library, the observer can
+// forward the records to whatever the build system uses.
+
+use std::time::Duration;
+
+use crate::module_image::ModuleSectionId;
+
+pub trait ImageIoObserver {
+    /// Called after a section is read, `length` is the length of the section data in bytes.
+    fn on_section_read(
+        &mut self,
+        _section_id: ModuleSectionId,
+        _length: usize,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// Called after a section is written, `length` is the length of the section data in bytes.
+    fn on_section_written(
+        &mut self,
+        _section_id: ModuleSectionId,
+        _length: usize,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// Called after a section is written, with the overall progress of writing.
+    fn on_write_progress(&mut self, _progress: WriteProgress) {}
+}
+
+// The observer which does nothing, it is used by the functions without observer.
+impl ImageIoObserver for () {}
+
+// The progress of writing an image.
+//
+// The total length is known before writing, since the length of
+// each section is estimated first to build the section table.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WriteProgress {
+    pub sections_completed: usize,
+    pub section_count: usize,
+    pub bytes_written: usize,
+    pub total_bytes: usize,
+}
+
+/// An observer which calls the given closure with the progress of writing,
+/// e.g., for rendering a progress bar.
+pub struct WriteProgressObserver<F: FnMut(WriteProgress)> {
+    callback: F,
+}
+
+impl<F: FnMut(WriteProgress)> WriteProgressObserver<F> {
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F: FnMut(WriteProgress)> ImageIoObserver for WriteProgressObserver<F> {
+    fn on_write_progress(&mut self, progress: WriteProgress) {
+        (self.callback)(progress)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SectionIoKind {
+    Read,
+    Write,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SectionIoRecord {
+    pub kind: SectionIoKind,
+    pub section_id: ModuleSectionId,
+    pub length: usize,
+    pub elapsed: Duration,
+}
+
+/// An observer which collects all records.
+#[derive(Debug, Default)]
+pub struct ImageIoRecorder {
+    pub records: Vec<SectionIoRecord>,
+}
+
+impl ImageIoRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the total time spent on the specified kind of operation.
+    pub fn total_elapsed(&self, kind: SectionIoKind) -> Duration {
+        self.records
+            .iter()
+            .filter(|record| record.kind == kind)
+            .map(|record| record.elapsed)
+            .sum()
+    }
+}
+
+impl ImageIoObserver for ImageIoRecorder {
+    fn on_section_read(&mut self, section_id: ModuleSectionId, length: usize, elapsed: Duration) {
+        self.records.push(SectionIoRecord {
+            kind: SectionIoKind::Read,
+            section_id,
+            length,
+            elapsed,
+        });
+    }
+
+    fn on_section_written(
+        &mut self,
+        section_id: ModuleSectionId,
+        length: usize,
+        elapsed: Duration,
+    ) {
+        self.records.push(SectionIoRecord {
+            kind: SectionIoKind::Write,
+            section_id,
+            length,
+            elapsed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry_reader::{read_object_file, read_object_file_with_observer},
+        entry_writer::{
+            build_minimal_module, write_object_file_with_observer, OptionalSectionPolicy,
+            WriteOptions,
+        },
+        io_observer::{ImageIoRecorder, SectionIoKind, WriteProgress, WriteProgressObserver},
+        module_image::{ModuleImage, ModuleSectionId},
+    };
+
+    #[test]
+    fn test_image_io_recorder() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+
+        let mut recorder = ImageIoRecorder::new();
+        let image_common_entry =
+            read_object_file_with_observer(&image_binary, &mut recorder).unwrap();
+
+        // the absent sections are not recorded
+        assert_eq!(
+            recorder
+                .records
+                .iter()
+                .map(|record| (record.kind, record.section_id))
+                .collect::<Vec<_>>(),
+            vec![
+                (SectionIoKind::Read, ModuleSectionId::Type),
+                (SectionIoKind::Read, ModuleSectionId::LocalVariable),
+                (SectionIoKind::Read, ModuleSectionId::Function),
+                (SectionIoKind::Read, ModuleSectionId::Property),
+            ]
+        );
+
+        let mut recorder = ImageIoRecorder::new();
+        let mut rewritten_binary: Vec<u8> = vec![];
+        write_object_file_with_observer(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                ..Default::default()
+            },
+            &mut recorder,
+            &mut rewritten_binary,
+        )
+        .unwrap();
+
+        let module_image = ModuleImage::read(&rewritten_binary).unwrap();
+        assert_eq!(
+            recorder
+                .records
+                .iter()
+                .map(|record| (record.kind, record.section_id, record.length))
+                .collect::<Vec<_>>(),
+            module_image
+                .items
+                .iter()
+                .map(|item| (SectionIoKind::Write, item.id, item.length as usize))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            recorder.total_elapsed(SectionIoKind::Read),
+            std::time::Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_write_progress_observer() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+
+        let mut progresses: Vec<WriteProgress> = vec![];
+        let mut observer = WriteProgressObserver::new(|progress| progresses.push(progress));
+
+        let mut rewritten_binary: Vec<u8> = vec![];
+        write_object_file_with_observer(
+            &image_common_entry,
+            false,
+            &WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                ..Default::default()
+            },
+            &mut observer,
+            &mut rewritten_binary,
+        )
+        .unwrap();
+
+        let module_image = ModuleImage::read(&rewritten_binary).unwrap();
+        let total_bytes = module_image.sections_data.len();
+
+        assert_eq!(progresses.len(), 4);
+        assert_eq!(
+            progresses[0],
+            WriteProgress {
+                sections_completed: 1,
+                section_count: 4,
+                bytes_written: module_image.items[0].length as usize,
+                total_bytes,
+            }
+        );
+        assert_eq!(
+            progresses[3],
+            WriteProgress {
+                sections_completed: 4,
+                section_count: 4,
+                bytes_written: total_bytes,
+                total_bytes,
+            }
+        );
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Lazy binding of the external functions.
+//
+// By default, the runtime resolves the symbols of all external functions
+// at load time. With lazy binding, the linker generates a thunk function for
+// each external function of a module, the thunk forwards the arguments to
+// the external function (see `StubTemplate::ForwardExternalCall`), and the
+// `extcall` instructions of the module are redirected to `call` the thunks.
+//
+// The thunks are recorded in the "lazy binding" section, so the runtime knows
+// which external functions are bound lazily, and resolves the symbol when
+// the thunk is called for the first time.
+
+use std::collections::HashMap;
+
+use crate::{
+    bytecode_template::{build_stub_function, StubTemplate},
+    bytecode_transform::retarget_indices,
+    entry::{ExternalFunctionIndexListEntry, FunctionEntry, ImageCommonEntry, LazyBindingEntry},
+    module_image::RelocateType,
+    ImageError,
+};
+
+/// Appends a thunk function for each external function of the module,
+/// and redirects the `extcall` instructions to the thunks.
+///
+/// `external_function_index_list_entry` is the external function index list of
+/// the module, it maps the external function indices to the unified indices.
+///
+/// Returns the lazy binding entries of the thunks. The relocate lists of
+/// the module are required, otherwise nothing is generated.
+///
+/// Returns an error if an `extcall` instruction can not be redirected, see
+/// `bytecode_transform::retarget_indices`, in which case the entry may be
+/// partially modified.
+pub fn generate_lazy_binding_thunks(
+    module_index: usize,
+    image_common_entry: &mut ImageCommonEntry,
+    external_function_index_list_entry: &ExternalFunctionIndexListEntry,
+) -> Result<Vec<LazyBindingEntry>, ImageError> {
+    if image_common_entry.relocate_list_entries.len() != image_common_entry.function_entries.len() {
+        return Ok(vec![]);
+    }
+
+    let import_function_count = image_common_entry.import_function_entries.len();
+    let original_function_count = image_common_entry.function_entries.len();
+
+    let mut lazy_binding_entries = vec![];
+    let mut map = HashMap::new();
+
+    for (external_function_index, external_function_entry) in image_common_entry
+        .external_function_entries
+        .iter()
+        .enumerate()
+    {
+        let type_index = external_function_entry.type_index;
+        let stub_function = build_stub_function(
+            &image_common_entry.type_entries[type_index],
+            &StubTemplate::ForwardExternalCall {
+                external_function_index,
+            },
+        );
+
+        let local_variable_list_index = match image_common_entry
+            .local_variable_list_entries
+            .iter()
+            .position(|entry| *entry == stub_function.local_variable_list_entry)
+        {
+            Some(idx) => idx,
+            None => {
+                image_common_entry
+                    .local_variable_list_entries
+                    .push(stub_function.local_variable_list_entry);
+                image_common_entry.local_variable_list_entries.len() - 1
+            }
+        };
+
+        let function_internal_index = image_common_entry.function_entries.len();
+        image_common_entry.function_entries.push(FunctionEntry::new(
+            type_index,
+            local_variable_list_index,
+            stub_function.code,
+        ));
+        image_common_entry
+            .relocate_list_entries
+            .push(stub_function.relocate_list_entry);
+
+        map.insert(
+            (RelocateType::ExternalFunctionIndex, external_function_index),
+            (
+                RelocateType::FunctionPublicIndex,
+                import_function_count + function_internal_index,
+            ),
+        );
+
+        lazy_binding_entries.push(LazyBindingEntry::new(
+            external_function_index_list_entry.index_entries[external_function_index]
+                .unified_external_function_index,
+            module_index,
+            function_internal_index,
+        ));
+    }
+
+    // Redirect the `extcall` instructions, the thunks are excluded.
+    for (function_entry, relocate_list) in image_common_entry
+        .function_entries
+        .iter_mut()
+        .zip(image_common_entry.relocate_list_entries.iter_mut())
+        .take(original_function_count)
+    {
+        retarget_indices(&mut function_entry.code, relocate_list, &map)?;
+    }
+
+    Ok(lazy_binding_entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, EffectiveVersion, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{
+            ExternalFunctionEntry, ExternalFunctionIndexEntry, ExternalFunctionIndexListEntry,
+            FunctionEntry, ImageCommonEntry, LazyBindingEntry, LocalVariableListEntry,
+            RelocateEntry, RelocateListEntry, TypeEntry,
+        },
+        lazy_binding::generate_lazy_binding_thunks,
+        module_image::ImageType,
+    };
+
+    #[test]
+    fn test_generate_lazy_binding_thunks() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::imm_i32, 11) // 0x0000
+            .append_opcode_i32(Opcode::extcall, 1) // 0x0008
+            .append_opcode_i32(Opcode::extcall, 0) // 0x0010
+            .append_opcode(Opcode::end) // 0x0018
+            .to_bytes();
+
+        let mut image_common_entry = ImageCommonEntry {
+            name: "foo".to_owned(),
+            version: EffectiveVersion::new(1, 0, 0),
+            image_type: ImageType::ObjectFile,
+            type_entries: vec![
+                TypeEntry::new(vec![], vec![]),
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+            ],
+            local_variable_list_entries: vec![LocalVariableListEntry::new(vec![])],
+            function_entries: vec![FunctionEntry::new(0, 0, code)],
+            read_only_data_entries: vec![],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![],
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            function_name_entries: vec![],
+            data_data_entries: vec![],
+            relocate_list_entries: vec![RelocateListEntry::new(vec![
+                RelocateEntry::from_external_function_index(0x8),
+                RelocateEntry::from_external_function_index(0x10),
+            ])],
+            external_library_entries: vec![],
+            external_function_entries: vec![
+                ExternalFunctionEntry::new("getuid".to_owned(), 0, 0),
+                ExternalFunctionEntry::new("abs".to_owned(), 0, 1),
+            ],
+            initializer_entries: vec![],
+        };
+
+        let lazy_binding_entries = generate_lazy_binding_thunks(
+            2,
+            &mut image_common_entry,
+            &ExternalFunctionIndexListEntry::new(vec![
+                ExternalFunctionIndexEntry::new(5),
+                ExternalFunctionIndexEntry::new(3),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            lazy_binding_entries,
+            vec![
+                LazyBindingEntry::new(5, 2, 1),
+                LazyBindingEntry::new(3, 2, 2)
+            ]
+        );
+
+        // the `extcall` instructions are redirected to the thunks
+        assert_eq!(
+            image_common_entry.function_entries[0].code,
+            BytecodeWriterHelper::new()
+                .append_opcode_i32(Opcode::imm_i32, 11)
+                .append_opcode_i32(Opcode::call, 2)
+                .append_opcode_i32(Opcode::call, 1)
+                .append_opcode(Opcode::end)
+                .to_bytes()
+        );
+        assert_eq!(
+            image_common_entry.relocate_list_entries[0],
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0x8),
+                RelocateEntry::from_function_public_index(0x10),
+            ])
+        );
+
+        // the thunk of "abs"
+        assert_eq!(
+            image_common_entry.function_entries[2],
+            FunctionEntry::new(
+                1,
+                1,
+                BytecodeWriterHelper::new()
+                    .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 0)
+                    .append_opcode_i32(Opcode::extcall, 1)
+                    .append_opcode(Opcode::end)
+                    .to_bytes()
+            )
+        );
+        assert_eq!(
+            image_common_entry.local_variable_list_entries,
+            vec![
+                LocalVariableListEntry::new(vec![]),
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.relocate_list_entries[2],
+            RelocateListEntry::new(vec![RelocateEntry::from_external_function_index(0x8)])
+        );
+    }
+}
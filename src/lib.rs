@@ -4,15 +4,64 @@
 // the Mozilla Public License version 2.0 and additional exceptions.
 // For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
 
+pub mod binding_generator;
+pub mod builder;
+pub mod bytecode_diff;
 pub mod bytecode_reader;
+pub mod bytecode_search;
+pub mod bytecode_template;
+pub mod bytecode_transform;
+pub mod bytecode_verifier;
 pub mod bytecode_writer;
 pub mod common_sections;
+pub mod compatibility;
+pub mod data_sharing;
 pub mod datatableaccess;
+pub mod debug_info;
+pub mod diagnostic;
 pub mod entry;
+pub mod entry_dump;
 pub mod entry_reader;
+pub mod entry_validator;
 pub mod entry_writer;
+pub mod export_surface;
+pub mod function_hash;
+pub mod function_report;
+pub mod image_compression;
+pub mod image_editor;
+pub mod image_encryption;
+pub mod image_manifest;
+pub mod image_pipeline;
+pub mod image_repair;
+pub mod image_signature;
+pub mod image_transform;
+pub mod index_remap;
+pub mod initialization_order;
+pub mod io_observer;
+pub mod lazy_binding;
+pub mod license_scan;
+pub mod link_hook;
 pub mod linking_sections;
+pub mod lint;
+pub mod memory_estimate;
 pub mod module_image;
+pub mod module_image_cache;
+pub mod module_interface;
+pub mod namespace_tree;
+pub mod native_container;
+pub mod opcode_info;
+pub mod patch_slot;
+pub mod program_statistics;
+pub mod public_index;
+pub mod relocate_coverage;
+pub mod roundtrip;
+pub mod section_registry;
+pub mod struct_data_builder;
+pub mod test_discovery;
+pub mod unified_external;
+pub mod validator;
+pub mod version_range;
+pub mod wasm_converter;
 
 // Conditional compilation for debug utilities.
 // See: https://doc.rust-lang.org/reference/conditional-compilation.html#debug_assertions
@@ -25,6 +74,8 @@ use std::{
     hash::{DefaultHasher, Hasher},
 };
 
+use module_image::ModuleSectionId;
+
 // Represents the hash of parameters and compile environment variables.
 // This is used in Local/Remote/Share dependencies.
 //
@@ -52,6 +103,88 @@ pub enum ImageErrorType {
     InvalidImage,
     // Indicates that the module image requires a newer runtime version.
     RequireNewVersionRuntime,
+    // Indicates that the image declares a trailer, but the trailer is missing
+    // or the total length does not match, e.g., a partially downloaded image.
+    TruncatedImage,
+    // Indicates that the checksum in the trailer does not match the content.
+    ChecksumMismatch,
+    // Indicates that the sections of the image are encrypted, the image
+    // must be decrypted by `image_encryption::decrypt_image` before reading.
+    EncryptedImage,
+    // Indicates that some sections of the image are compressed, the image
+    // must be decompressed by `image_compression::decompress_image` before reading.
+    CompressedImage,
+    // Indicates that a required section (e.g., the type section or the function section)
+    // is missing, see the `try_get_*_section` functions of `ModuleImage`.
+    MissingSection(ModuleSectionId),
+    // Indicates that a required section is present but its data is corrupted,
+    // e.g., the table exceeds the section or an offset exceeds the data area.
+    CorruptedSection(ModuleSectionId),
+    // Indicates that a field of the enum type has an unexpected value,
+    // e.g., an unknown section id or an unknown relocate type.
+    //
+    // `section_id` is the section containing the field, it is `None` if the field
+    // is in the image header or the section table.
+    InvalidEnumValue {
+        section_id: Option<ModuleSectionId>,
+        enum_name: &'static str,
+        value: u32,
+    },
+    // Indicates that the underlying writer (or reader) failed.
+    //
+    // `section_id` is the section being written when the failure occurred,
+    // it is `None` if the failure occurred outside of the sections
+    // (e.g., the image header or the section table).
+    // `bytes_written` is the amount of bytes written before the failed part.
+    Io {
+        section_id: Option<ModuleSectionId>,
+        bytes_written: usize,
+        error: std::io::Error,
+    },
+    // Indicates that the size of the data written by a section entry does not
+    // match its `SectionEntry::estimated_size`, e.g., a faulty pluggable section.
+    SectionSizeMismatch {
+        section_id: ModuleSectionId,
+        estimated_size: usize,
+        written_size: usize,
+    },
+    // Indicates that the alignment is neither `0` nor a power of two,
+    // e.g., `entry_writer::WriteOptions::min_data_align`.
+    InvalidAlign(u16),
+
+    // The following errors are reported by the tools built on top of the image,
+    // the details are described by the error type of each module.
+
+    // Indicates that an instruction can not be encoded.
+    Instruction(bytecode_writer::InstructionError),
+    // Indicates that the indices can not be retargeted.
+    Retarget(bytecode_transform::RetargetError),
+    // Indicates that a transform of the pipeline failed.
+    Transform(image_pipeline::TransformError),
+    // Indicates that a section can not be edited.
+    Edit(image_editor::EditError),
+    // Indicates that a patch slot can not be located or patched.
+    Patch(patch_slot::PatchError),
+    // Indicates that the builder can not build the image.
+    Build(builder::BuildError),
+    // Indicates that the image can not be repaired mechanically.
+    Repair(image_repair::RepairError),
+    // Indicates that the sections can not be compressed or decompressed.
+    Compression(image_compression::CompressionError),
+    // Indicates that the sections can not be encrypted or decrypted.
+    Encryption(image_encryption::EncryptionError),
+    // Indicates that the image can not be signed or verified.
+    Signature(image_signature::SignatureError),
+    // Indicates that the licenses can not be scanned.
+    License(license_scan::LicenseError),
+    // Indicates that the debug information can not be attached.
+    DebugInfo(debug_info::DebugInfoError),
+    // Indicates that a link hook rejected a symbol.
+    LinkHook(link_hook::LinkHookError),
+    // Indicates that a custom section codec rejected the data.
+    SectionRegistry(section_registry::SectionRegistryError),
+    // Indicates that the manifest can not be converted into an image.
+    Manifest(image_manifest::ManifestError),
 }
 
 impl ImageError {
@@ -59,11 +192,30 @@ impl ImageError {
     pub fn new(error_type: ImageErrorType) -> Self {
         Self { error_type }
     }
+
+    // Creates a new ImageError from an IO error, with the section and progress context.
+    pub fn from_io_error(
+        error: std::io::Error,
+        section_id: Option<ModuleSectionId>,
+        bytes_written: usize,
+    ) -> Self {
+        Self::new(ImageErrorType::Io {
+            section_id,
+            bytes_written,
+            error,
+        })
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> Self {
+        Self::from_io_error(error, None, 0)
+    }
 }
 
 impl Display for ImageError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.error_type {
+        match &self.error_type {
             ImageErrorType::InvalidImage => write!(f, "Not a valid module image."),
             ImageErrorType::RequireNewVersionRuntime => {
                 write!(
@@ -71,11 +223,132 @@ impl Display for ImageError {
                     "The version of the module image is newer than the runtime."
                 )
             }
+            ImageErrorType::TruncatedImage => write!(f, "The module image is truncated."),
+            ImageErrorType::ChecksumMismatch => {
+                write!(f, "The checksum of the module image does not match.")
+            }
+            ImageErrorType::EncryptedImage => write!(f, "The module image is encrypted."),
+            ImageErrorType::CompressedImage => write!(f, "The module image is compressed."),
+            ImageErrorType::MissingSection(section_id) => {
+                write!(f, "Cannot find the section \"{}\".", section_id.name())
+            }
+            ImageErrorType::CorruptedSection(section_id) => {
+                write!(f, "The section \"{}\" is corrupted.", section_id.name())
+            }
+            ImageErrorType::InvalidEnumValue {
+                section_id: Some(section_id),
+                enum_name,
+                value,
+            } => write!(
+                f,
+                "Invalid value {} of \"{}\" in the section \"{}\".",
+                value,
+                enum_name,
+                section_id.name()
+            ),
+            ImageErrorType::InvalidEnumValue {
+                section_id: None,
+                enum_name,
+                value,
+            } => write!(
+                f,
+                "Invalid value {} of \"{}\" in the header or the section table.",
+                value, enum_name
+            ),
+            ImageErrorType::Io {
+                section_id: Some(section_id),
+                bytes_written,
+                error,
+            } => write!(
+                f,
+                "Failed to write the section \"{}\" after {} bytes were written: {}",
+                section_id.name(),
+                bytes_written,
+                error
+            ),
+            ImageErrorType::Io {
+                section_id: None,
+                bytes_written,
+                error,
+            } => write!(
+                f,
+                "IO error after {} bytes were written: {}",
+                bytes_written, error
+            ),
+            ImageErrorType::SectionSizeMismatch {
+                section_id,
+                estimated_size,
+                written_size,
+            } => write!(
+                f,
+                "The section \"{}\" is estimated to be {} bytes, but {} bytes were written.",
+                section_id.name(),
+                estimated_size,
+                written_size
+            ),
+            ImageErrorType::InvalidAlign(align) => {
+                write!(f, "The alignment {} is not a power of two.", align)
+            }
+            ImageErrorType::Instruction(error) => write!(f, "Instruction error: {}", error),
+            ImageErrorType::Retarget(error) => write!(f, "Retarget error: {}", error),
+            ImageErrorType::Transform(error) => write!(f, "Transform failed: {}", error),
+            ImageErrorType::Edit(error) => write!(f, "Edit error: {}", error),
+            ImageErrorType::Patch(error) => write!(f, "Patch error: {}", error),
+            ImageErrorType::Build(error) => write!(f, "Build error: {}", error),
+            ImageErrorType::Repair(error) => write!(f, "Repair error: {}", error),
+            ImageErrorType::Compression(error) => write!(f, "Compression error: {}", error),
+            ImageErrorType::Encryption(error) => write!(f, "Encryption error: {}", error),
+            ImageErrorType::Signature(error) => write!(f, "Signature error: {}", error),
+            ImageErrorType::License(error) => write!(f, "License error: {}", error),
+            ImageErrorType::DebugInfo(error) => write!(f, "Debug info error: {}", error),
+            ImageErrorType::LinkHook(error) => write!(f, "Link hook rejected: {}", error),
+            ImageErrorType::SectionRegistry(error) => {
+                write!(f, "Section registry error: {}", error)
+            }
+            ImageErrorType::Manifest(error) => write!(f, "Manifest error: {}", error),
         }
     }
 }
 
-impl std::error::Error for ImageError {}
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.error_type {
+            ImageErrorType::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// Represents a corrupted optional section.
+//
+// Unlike `ImageError`, this error only affects the specified section,
+// the other sections of the image are still usable.
+#[derive(Debug, PartialEq)]
+pub struct SectionReadError {
+    pub section_id: ModuleSectionId,
+}
+
+impl SectionReadError {
+    pub fn new(section_id: ModuleSectionId) -> Self {
+        Self { section_id }
+    }
+}
+
+impl Display for SectionReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The section \"{}\" is corrupted.", self.section_id.name())
+    }
+}
+
+impl std::error::Error for SectionReadError {}
+
+// A corrupted optional section makes the whole image unusable for the readers
+// which require all sections, e.g., the entry reader.
+impl From<SectionReadError> for ImageError {
+    fn from(error: SectionReadError) -> Self {
+        Self::new(ImageErrorType::CorruptedSection(error.section_id))
+    }
+}
 
 // Computes a dependency hash from the given string input.
 // The hash is generated using Rust's default hasher (e.g. SipHash).
@@ -100,3 +373,18 @@ pub fn format_dependency_hash(hash: &DependencyHash) -> String {
         .collect::<Vec<String>>()
         .join("")
 }
+
+// Parses a hexadecimal string (e.g. the output of `format_dependency_hash`) into
+// a dependency hash, the remaining bytes are zero.
+// Returns `None` if the string is not an even-length hexadecimal string of up to 64 digits.
+pub fn parse_dependency_hash(text: &str) -> Option<DependencyHash> {
+    if text.len() % 2 != 0 || text.len() > 64 || !text.is_ascii() {
+        return None;
+    }
+
+    let mut hash = DEPENDENCY_HASH_ZERO;
+    for (idx, byte) in hash.iter_mut().take(text.len() / 2).enumerate() {
+        *byte = u8::from_str_radix(&text[idx * 2..idx * 2 + 2], 16).ok()?;
+    }
+    Some(hash)
+}
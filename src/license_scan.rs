@@ -0,0 +1,626 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Aggregates the licenses of the components of an application, i.e., the modules
+// in the linking module list and the external libraries of each module, and
+// checks them against a user-supplied policy.
+//
+// The licenses are recorded in the optional "license" section of each module
+// as SPDX license expressions, e.g. "MIT OR Apache-2.0", "GPL-2.0-only WITH
+// Classpath-exception-2.0". The expressions are expanded into the list of
+// choices, e.g. "MIT AND (Apache-2.0 OR BSD-3-Clause)" is expanded into
+// `[["MIT", "Apache-2.0"], ["MIT", "BSD-3-Clause"]]`, the exceptions (i.e.,
+// the "WITH" clauses) are dropped.
+//
+// A component "requires" a license when every choice contains the license,
+// the policy is checked against the required licenses, so the dual-licensed
+// components are accepted as long as one of the choices is acceptable.
+
+use std::fmt::Display;
+
+use crate::{
+    common_sections::license_section::LicenseSection,
+    entry::LicenseEntry,
+    image_pipeline::ImageSections,
+    module_image::{ImageType, ModuleImage, ModuleSectionId, SectionEntry},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why the licenses can not be scanned, or the license
+// expression can not be parsed.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LicenseError {
+    NotApplication(ImageType),
+    DependencyCountMismatch {
+        expected_count: usize,
+        actual_count: usize,
+    },
+
+    // `position` is the index of the token in the expression.
+    UnexpectedToken {
+        token: String,
+        position: usize,
+    },
+    IncompleteExpression,
+    MissingClosingParenthesis,
+    MissingException,
+}
+
+impl Display for LicenseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LicenseError::NotApplication(image_type) => {
+                write!(
+                    f,
+                    "The image is not an application, found {:?}.",
+                    image_type
+                )
+            }
+            LicenseError::DependencyCountMismatch {
+                expected_count,
+                actual_count,
+            } => write!(
+                f,
+                "Expect {} dependency module images, actual {}.",
+                expected_count, actual_count
+            ),
+            LicenseError::UnexpectedToken { token, position } => write!(
+                f,
+                "Unexpected token \"{}\" at position {} in license expression.",
+                token, position
+            ),
+            LicenseError::IncompleteExpression => write!(f, "Incomplete license expression."),
+            LicenseError::MissingClosingParenthesis => {
+                write!(f, "Missing \")\" in license expression.")
+            }
+            LicenseError::MissingException => write!(f, "Missing exception in license expression."),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LicenseComponent {
+    Module {
+        module_name: String,
+    },
+    ExternalLibrary {
+        // The name of the module which depends on the library.
+        module_name: String,
+        library_name: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ComponentLicense {
+    pub component: LicenseComponent,
+
+    // The SPDX license expression, it is `None` if the license is not recorded.
+    pub license: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LicensePolicy {
+    // The license identifiers which are not allowed in the application.
+    pub denied_licenses: Vec<String>,
+
+    // The pairs of license identifiers which can not be combined in the application.
+    pub incompatible_licenses: Vec<(String, String)>,
+
+    // Reports the components whose license is not recorded.
+    pub require_license: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LicenseViolationType {
+    // The license is not recorded.
+    Missing,
+
+    // The license expression can not be parsed.
+    InvalidExpression(String),
+
+    // Every choice of the license expression contains a denied license.
+    Denied(String),
+
+    // The component requires `license`, while the other component
+    // requires `other_license`, which is incompatible with it.
+    Incompatible {
+        license: String,
+        other_component: LicenseComponent,
+        other_license: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LicenseViolation {
+    pub component: LicenseComponent,
+    pub violation_type: LicenseViolationType,
+}
+
+/// Writes the image with the "license" section replaced by the specified entries.
+pub fn attach_licenses(
+    image: &ModuleImage,
+    license_entries: &[LicenseEntry],
+    writer: &mut dyn std::io::Write,
+) -> Result<(), ImageError> {
+    let (items, items_data) = LicenseSection::convert_from_entries(license_entries);
+    let license_section = LicenseSection {
+        items: &items,
+        items_data: &items_data,
+    };
+    let mut license_data: Vec<u8> = vec![];
+    license_section.write(&mut license_data)?;
+
+    let mut image_sections = ImageSections::from_module_image(image);
+    image_sections.set_section_data(ModuleSectionId::License, license_data);
+    image_sections.write(writer)
+}
+
+/// Collects the licenses of the module and its external libraries,
+/// the module comes first, followed by the libraries in the order
+/// of the "external library" section.
+pub fn collect_module_licenses(module_name: &str, image: &ModuleImage) -> Vec<ComponentLicense> {
+    let license_section = image.get_optional_license_section();
+
+    let mut component_licenses = vec![ComponentLicense {
+        component: LicenseComponent::Module {
+            module_name: module_name.to_owned(),
+        },
+        license: license_section
+            .as_ref()
+            .and_then(|section| section.get_module_license())
+            .map(|license| license.to_owned()),
+    }];
+
+    if let Some(external_library_section) = image.get_optional_external_library_section() {
+        for idx in 0..external_library_section.items.len() {
+            let (library_name, _, _) = external_library_section
+                .get_item_name_and_external_library_dependent_type_and_value(idx);
+            component_licenses.push(ComponentLicense {
+                component: LicenseComponent::ExternalLibrary {
+                    module_name: module_name.to_owned(),
+                    library_name: library_name.to_owned(),
+                },
+                license: license_section
+                    .as_ref()
+                    .and_then(|section| section.get_external_library_license(idx))
+                    .map(|license| license.to_owned()),
+            });
+        }
+    }
+
+    component_licenses
+}
+
+/// Collects the licenses of all modules of the application and their external libraries.
+///
+/// `dependency_module_images` are the images of the modules in the linking
+/// module list of the application, except the first one (i.e., the main module,
+/// which is the application image itself), in the order of the list.
+pub fn scan_application_licenses(
+    application_image: &ModuleImage,
+    dependency_module_images: &[ModuleImage],
+) -> Result<Vec<ComponentLicense>, ImageError> {
+    if application_image.image_type != ImageType::Application {
+        return Err(ImageError::new(ImageErrorType::License(
+            LicenseError::NotApplication(application_image.image_type),
+        )));
+    }
+
+    let linking_module_section = application_image.get_dynamic_link_module_list_section();
+
+    let module_count = linking_module_section.items.len();
+    if module_count != dependency_module_images.len() + 1 {
+        return Err(ImageError::new(ImageErrorType::License(
+            LicenseError::DependencyCountMismatch {
+                expected_count: module_count.saturating_sub(1),
+                actual_count: dependency_module_images.len(),
+            },
+        )));
+    }
+
+    let module_images = std::iter::once(application_image).chain(dependency_module_images);
+    let component_licenses = module_images
+        .enumerate()
+        .flat_map(|(module_index, image)| {
+            let (module_name, _) = linking_module_section.get_item_name_and_value(module_index);
+            collect_module_licenses(module_name, image)
+        })
+        .collect();
+
+    Ok(component_licenses)
+}
+
+/// Parses the SPDX license expression and expands it into the list of choices,
+/// each choice is a list of license identifiers which apply together.
+pub fn parse_license_expression(expression: &str) -> Result<Vec<Vec<String>>, ImageError> {
+    let tokens = expression
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(|token| token.to_owned())
+        .collect::<Vec<String>>();
+
+    let mut position = 0;
+    let choices = parse_or_expression(&tokens, &mut position)?;
+
+    if position != tokens.len() {
+        return Err(ImageError::new(ImageErrorType::License(
+            LicenseError::UnexpectedToken {
+                token: tokens[position].clone(),
+                position,
+            },
+        )));
+    }
+
+    Ok(choices)
+}
+
+// or_expression := and_expression ("OR" and_expression)*
+fn parse_or_expression(
+    tokens: &[String],
+    position: &mut usize,
+) -> Result<Vec<Vec<String>>, ImageError> {
+    let mut choices = parse_and_expression(tokens, position)?;
+
+    while tokens.get(*position).is_some_and(|token| token == "OR") {
+        *position += 1;
+        choices.extend(parse_and_expression(tokens, position)?);
+    }
+
+    Ok(choices)
+}
+
+// and_expression := primary ("AND" primary)*
+fn parse_and_expression(
+    tokens: &[String],
+    position: &mut usize,
+) -> Result<Vec<Vec<String>>, ImageError> {
+    let mut choices = parse_primary_expression(tokens, position)?;
+
+    while tokens.get(*position).is_some_and(|token| token == "AND") {
+        *position += 1;
+        let right_choices = parse_primary_expression(tokens, position)?;
+
+        // the cross product of the choices
+        choices = choices
+            .iter()
+            .flat_map(|left| {
+                right_choices.iter().map(move |right| {
+                    let mut combined = left.clone();
+                    for license in right {
+                        if !combined.contains(license) {
+                            combined.push(license.clone());
+                        }
+                    }
+                    combined
+                })
+            })
+            .collect();
+    }
+
+    Ok(choices)
+}
+
+// primary := "(" or_expression ")" | license_id ("WITH" exception_id)?
+fn parse_primary_expression(
+    tokens: &[String],
+    position: &mut usize,
+) -> Result<Vec<Vec<String>>, ImageError> {
+    let Some(token) = tokens.get(*position) else {
+        return Err(ImageError::new(ImageErrorType::License(
+            LicenseError::IncompleteExpression,
+        )));
+    };
+    *position += 1;
+
+    match token.as_str() {
+        "(" => {
+            let choices = parse_or_expression(tokens, position)?;
+            if !tokens.get(*position).is_some_and(|token| token == ")") {
+                return Err(ImageError::new(ImageErrorType::License(
+                    LicenseError::MissingClosingParenthesis,
+                )));
+            }
+            *position += 1;
+            Ok(choices)
+        }
+        ")" | "AND" | "OR" | "WITH" => Err(ImageError::new(ImageErrorType::License(
+            LicenseError::UnexpectedToken {
+                token: token.to_owned(),
+                position: *position - 1,
+            },
+        ))),
+        _ => {
+            if tokens.get(*position).is_some_and(|token| token == "WITH") {
+                // the exception is dropped
+                *position += 2;
+                if *position > tokens.len() {
+                    return Err(ImageError::new(ImageErrorType::License(
+                        LicenseError::MissingException,
+                    )));
+                }
+            }
+            Ok(vec![vec![token.to_owned()]])
+        }
+    }
+}
+
+// Returns the licenses which are contained in every choice.
+fn get_required_licenses(choices: &[Vec<String>]) -> Vec<String> {
+    match choices.split_first() {
+        Some((first, rest)) => first
+            .iter()
+            .filter(|license| rest.iter().all(|choice| choice.contains(license)))
+            .cloned()
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Checks the licenses of the components against the policy.
+pub fn check_license_policy(
+    component_licenses: &[ComponentLicense],
+    policy: &LicensePolicy,
+) -> Vec<LicenseViolation> {
+    let mut violations = vec![];
+
+    // The required licenses of each component, for checking the incompatible pairs.
+    let mut required_licenses_list: Vec<(&LicenseComponent, Vec<String>)> = vec![];
+
+    for component_license in component_licenses {
+        let component = &component_license.component;
+
+        let Some(license) = &component_license.license else {
+            if policy.require_license {
+                violations.push(LicenseViolation {
+                    component: component.clone(),
+                    violation_type: LicenseViolationType::Missing,
+                });
+            }
+            continue;
+        };
+
+        let choices = match parse_license_expression(license) {
+            Ok(choices) => choices,
+            Err(e) => {
+                violations.push(LicenseViolation {
+                    component: component.clone(),
+                    violation_type: LicenseViolationType::InvalidExpression(e.to_string()),
+                });
+                continue;
+            }
+        };
+
+        // only the choices without denied licenses are acceptable
+        let acceptable_choices = choices
+            .into_iter()
+            .filter(|choice| {
+                choice
+                    .iter()
+                    .all(|license| !policy.denied_licenses.contains(license))
+            })
+            .collect::<Vec<_>>();
+
+        if acceptable_choices.is_empty() {
+            violations.push(LicenseViolation {
+                component: component.clone(),
+                violation_type: LicenseViolationType::Denied(license.to_owned()),
+            });
+            continue;
+        }
+
+        required_licenses_list.push((component, get_required_licenses(&acceptable_choices)));
+    }
+
+    for (idx, (component, required_licenses)) in required_licenses_list.iter().enumerate() {
+        // the licenses of the same component are checked as well, e.g. "A AND B"
+        for (other_component, other_required_licenses) in &required_licenses_list[idx..] {
+            for (license, other_license) in &policy.incompatible_licenses {
+                let find_pair = |first: &String, second: &String| {
+                    required_licenses.contains(first) && other_required_licenses.contains(second)
+                };
+
+                let pair = if find_pair(license, other_license) {
+                    Some((license, other_license))
+                } else if find_pair(other_license, license) {
+                    Some((other_license, license))
+                } else {
+                    None
+                };
+
+                if let Some((license, other_license)) = pair {
+                    violations.push(LicenseViolation {
+                        component: (*component).clone(),
+                        violation_type: LicenseViolationType::Incompatible {
+                            license: license.to_owned(),
+                            other_component: (*other_component).clone(),
+                            other_license: other_license.to_owned(),
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{DependencyCondition, DependencyLocal, ExternalLibraryDependency};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{ExternalLibraryEntry, LicenseEntry},
+        license_scan::{
+            attach_licenses, check_license_policy, parse_license_expression,
+            scan_application_licenses, ComponentLicense, LicenseComponent, LicenseError,
+            LicensePolicy, LicenseViolation, LicenseViolationType,
+        },
+        module_image::ModuleImage,
+        utils::{
+            helper_build_application_fixture,
+            helper_build_module_binary_with_functions_and_data_and_external_functions,
+            HelperFunctionEntry,
+        },
+        ImageErrorType,
+    };
+
+    #[test]
+    fn test_parse_license_expression() {
+        assert_eq!(
+            parse_license_expression("MIT").unwrap(),
+            vec![vec!["MIT".to_owned()]]
+        );
+        assert_eq!(
+            parse_license_expression("MIT AND (Apache-2.0 OR BSD-3-Clause)").unwrap(),
+            vec![
+                vec!["MIT".to_owned(), "Apache-2.0".to_owned()],
+                vec!["MIT".to_owned(), "BSD-3-Clause".to_owned()],
+            ]
+        );
+        assert_eq!(
+            parse_license_expression("GPL-2.0-only WITH Classpath-exception-2.0 OR MIT").unwrap(),
+            vec![vec!["GPL-2.0-only".to_owned()], vec!["MIT".to_owned()]]
+        );
+
+        assert!(parse_license_expression("").is_err());
+        assert!(parse_license_expression("MIT AND").is_err());
+        assert!(parse_license_expression("(MIT OR Zlib").is_err());
+        assert!(matches!(
+            parse_license_expression("MIT Zlib").unwrap_err().error_type,
+            ImageErrorType::License(LicenseError::UnexpectedToken { token, position: 1 })
+                if token == "Zlib"
+        ));
+    }
+
+    #[test]
+    fn test_scan_and_check_licenses() {
+        let fixture = helper_build_application_fixture(1);
+
+        let dependency_binary =
+            helper_build_module_binary_with_functions_and_data_and_external_functions(
+                &[HelperFunctionEntry {
+                    params: vec![],
+                    results: vec![],
+                    local_variable_item_entries_without_args: vec![],
+                    code: vec![0u8],
+                }],
+                &[],
+                &[],
+                &[],
+                &[ExternalLibraryEntry::new(
+                    "libfoo".to_owned(),
+                    Box::new(ExternalLibraryDependency::Local(Box::new(
+                        DependencyLocal {
+                            path: "libfoo.so.1".to_owned(),
+                            condition: DependencyCondition::True,
+                            parameters: HashMap::default(),
+                        },
+                    ))),
+                )],
+                &[],
+            );
+
+        let mut application_binary: Vec<u8> = vec![];
+        attach_licenses(
+            &ModuleImage::read(&fixture.application_binary).unwrap(),
+            &[LicenseEntry::for_module("MIT OR GPL-3.0-only".to_owned())],
+            &mut application_binary,
+        )
+        .unwrap();
+
+        let mut dependency_binary_with_licenses: Vec<u8> = vec![];
+        attach_licenses(
+            &ModuleImage::read(&dependency_binary).unwrap(),
+            &[LicenseEntry::for_module(
+                "Apache-2.0 AND GPL-2.0-only".to_owned(),
+            )],
+            &mut dependency_binary_with_licenses,
+        )
+        .unwrap();
+
+        let application_image = ModuleImage::read(&application_binary).unwrap();
+        let dependency_images = [ModuleImage::read(&dependency_binary_with_licenses).unwrap()];
+
+        let component_licenses =
+            scan_application_licenses(&application_image, &dependency_images).unwrap();
+
+        let main = LicenseComponent::Module {
+            module_name: "main".to_owned(),
+        };
+        let dep0 = LicenseComponent::Module {
+            module_name: "dep0".to_owned(),
+        };
+        let libfoo = LicenseComponent::ExternalLibrary {
+            module_name: "dep0".to_owned(),
+            library_name: "libfoo".to_owned(),
+        };
+
+        assert_eq!(
+            component_licenses,
+            vec![
+                ComponentLicense {
+                    component: main.clone(),
+                    license: Some("MIT OR GPL-3.0-only".to_owned()),
+                },
+                ComponentLicense {
+                    component: dep0.clone(),
+                    license: Some("Apache-2.0 AND GPL-2.0-only".to_owned()),
+                },
+                ComponentLicense {
+                    component: libfoo.clone(),
+                    license: None,
+                },
+            ]
+        );
+
+        // the module "main" can choose "MIT"
+        let policy = LicensePolicy {
+            denied_licenses: vec!["GPL-3.0-only".to_owned()],
+            incompatible_licenses: vec![
+                ("GPL-2.0-only".to_owned(), "Apache-2.0".to_owned()),
+                ("GPL-2.0-only".to_owned(), "GPL-3.0-only".to_owned()),
+            ],
+            require_license: true,
+        };
+
+        assert_eq!(
+            check_license_policy(&component_licenses, &policy),
+            vec![
+                LicenseViolation {
+                    component: libfoo,
+                    violation_type: LicenseViolationType::Missing,
+                },
+                LicenseViolation {
+                    component: dep0.clone(),
+                    violation_type: LicenseViolationType::Incompatible {
+                        license: "GPL-2.0-only".to_owned(),
+                        other_component: dep0.clone(),
+                        other_license: "Apache-2.0".to_owned(),
+                    },
+                },
+            ]
+        );
+
+        let policy = LicensePolicy {
+            denied_licenses: vec!["MIT".to_owned(), "GPL-3.0-only".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            check_license_policy(&component_licenses, &policy),
+            vec![LicenseViolation {
+                component: main,
+                violation_type: LicenseViolationType::Denied("MIT OR GPL-3.0-only".to_owned()),
+            }]
+        );
+
+        // the amount of the dependency module images does not match
+        assert!(scan_application_licenses(&application_image, &[]).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The extension points of the linker.
+//
+// The linker (which is not part of this crate) resolves the imported functions and
+// data of each module, merges the modules into the application image, and assigns
+// the index entries (see `FunctionIndexEntry` and `DataIndexEntry`). It calls
+// the hook at each step, so that the build systems can implement their policies,
+// e.g., symbol allow-lists or telemetry, without forking the link algorithm.
+//
+// The events are:
+//
+// - `on_symbol_resolved`: an imported function or data is resolved to the module
+//   which exports it. The hook can reject the symbol by returning an error,
+//   and the linker aborts with the error. The package-private symbols are
+//   checked by `PackageVisibilityCheck`.
+// - `on_module_merged`: a module is merged into the application image,
+//   the main module is merged first (with index `0`).
+// - `on_index_assigned`: an index entry of a module is assigned.
+
+use std::fmt::Display;
+
+use crate::{
+    entry::{DataIndexEntry, FunctionIndexEntry},
+    module_image::{ExportType, Visibility},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why the built-in hooks reject a symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LinkHookError {
+    // See `SymbolAllowList`.
+    NotAllowed {
+        full_name: String,
+    },
+
+    // See `PackageVisibilityCheck`.
+    NotAccessible {
+        full_name: String,
+        visibility: Visibility,
+        importer_module_index: usize,
+        target_module_index: usize,
+    },
+}
+
+impl Display for LinkHookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkHookError::NotAllowed { full_name } => {
+                write!(f, "The symbol \"{}\" is not in the allow-list.", full_name)
+            }
+            LinkHookError::NotAccessible {
+                full_name,
+                importer_module_index,
+                ..
+            } => write!(
+                f,
+                "The symbol \"{}\" is not accessible by module {}.",
+                full_name, importer_module_index
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolvedSymbol<'a> {
+    // The index of the module which imports the symbol.
+    pub importer_module_index: usize,
+
+    // e.g. "foo::add".
+    pub full_name: &'a str,
+    pub export_type: ExportType,
+    pub visibility: Visibility,
+
+    // The index of the module which exports the symbol.
+    pub target_module_index: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AssignedIndex<'a> {
+    Function(&'a FunctionIndexEntry),
+    Data(&'a DataIndexEntry),
+}
+
+pub trait LinkHook {
+    /// Called after an imported symbol is resolved, returns an error to reject the symbol.
+    fn on_symbol_resolved(&mut self, _symbol: &ResolvedSymbol) -> Result<(), ImageError> {
+        Ok(())
+    }
+
+    /// Called after a module is merged into the application image.
+    fn on_module_merged(&mut self, _module_index: usize, _module_name: &str) {}
+
+    /// Called after an index entry is assigned, `public_index` is the function (or data)
+    /// public index within the module `module_index`.
+    fn on_index_assigned(
+        &mut self,
+        _module_index: usize,
+        _public_index: usize,
+        _assigned_index: AssignedIndex,
+    ) {
+    }
+}
+
+// The hook which does nothing, it is used by the linker when no hook is specified.
+impl LinkHook for () {}
+
+/// A hook which accepts only the listed symbols.
+///
+/// Each pattern is either a full name, e.g. "foo::add", or a prefix
+/// followed by `*`, e.g. "foo::math::*".
+pub struct SymbolAllowList {
+    patterns: Vec<String>,
+}
+
+impl SymbolAllowList {
+    pub fn new(patterns: &[&str]) -> Self {
+        Self {
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+        }
+    }
+
+    pub fn is_allowed(&self, full_name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => full_name.starts_with(prefix),
+                None => full_name == pattern,
+            })
+    }
+}
+
+impl LinkHook for SymbolAllowList {
+    fn on_symbol_resolved(&mut self, symbol: &ResolvedSymbol) -> Result<(), ImageError> {
+        if self.is_allowed(symbol.full_name) {
+            Ok(())
+        } else {
+            Err(ImageError::new(ImageErrorType::LinkHook(
+                LinkHookError::NotAllowed {
+                    full_name: symbol.full_name.to_owned(),
+                },
+            )))
+        }
+    }
+}
+
+/// A hook which rejects the symbols which are not accessible by the importing module,
+/// i.e., the private symbols, and the package-private symbols of the other packages.
+pub struct PackageVisibilityCheck {
+    // The package names indexed by the module index, `None` if the module
+    // does not belong to any package.
+    module_package_names: Vec<Option<String>>,
+}
+
+impl PackageVisibilityCheck {
+    pub fn new(module_package_names: Vec<Option<String>>) -> Self {
+        Self {
+            module_package_names,
+        }
+    }
+
+    /// Returns `true` if both modules belong to the same package.
+    pub fn is_same_package(&self, module_index: usize, other_module_index: usize) -> bool {
+        let get_package_name = |module_index: usize| {
+            self.module_package_names
+                .get(module_index)
+                .and_then(|name| name.as_ref())
+        };
+
+        match (
+            get_package_name(module_index),
+            get_package_name(other_module_index),
+        ) {
+            (Some(package_name), Some(other_package_name)) => package_name == other_package_name,
+            _ => false,
+        }
+    }
+}
+
+impl LinkHook for PackageVisibilityCheck {
+    fn on_symbol_resolved(&mut self, symbol: &ResolvedSymbol) -> Result<(), ImageError> {
+        let same_package =
+            self.is_same_package(symbol.importer_module_index, symbol.target_module_index);
+
+        if symbol.visibility.is_accessible(same_package) {
+            Ok(())
+        } else {
+            Err(ImageError::new(ImageErrorType::LinkHook(
+                LinkHookError::NotAccessible {
+                    full_name: symbol.full_name.to_owned(),
+                    visibility: symbol.visibility,
+                    importer_module_index: symbol.importer_module_index,
+                    target_module_index: symbol.target_module_index,
+                },
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        link_hook::{LinkHook, PackageVisibilityCheck, ResolvedSymbol, SymbolAllowList},
+        module_image::{ExportType, Visibility},
+    };
+
+    #[test]
+    fn test_symbol_allow_list() {
+        let mut allow_list = SymbolAllowList::new(&["foo::add", "bar::math::*"]);
+
+        let mut resolve = |full_name: &str| {
+            allow_list.on_symbol_resolved(&ResolvedSymbol {
+                importer_module_index: 0,
+                full_name,
+                export_type: ExportType::Function,
+                visibility: Visibility::Public,
+                target_module_index: 1,
+            })
+        };
+
+        assert!(resolve("foo::add").is_ok());
+        assert!(resolve("bar::math::sqrt").is_ok());
+        assert_eq!(
+            resolve("foo::sub").unwrap_err().to_string(),
+            "Link hook rejected: The symbol \"foo::sub\" is not in the allow-list."
+        );
+
+        // the default hook accepts all symbols
+        assert!(()
+            .on_symbol_resolved(&ResolvedSymbol {
+                importer_module_index: 0,
+                full_name: "foo::sub",
+                export_type: ExportType::Data,
+                visibility: Visibility::Public,
+                target_module_index: 1,
+            })
+            .is_ok());
+    }
+
+    #[test]
+    fn test_package_visibility_check() {
+        // module 0 and module 1 belong to the package "foo"
+        let mut check = PackageVisibilityCheck::new(vec![
+            Some("foo".to_owned()),
+            Some("foo".to_owned()),
+            Some("bar".to_owned()),
+            None,
+        ]);
+
+        assert!(check.is_same_package(0, 1));
+        assert!(!check.is_same_package(0, 2));
+        assert!(!check.is_same_package(3, 3));
+
+        let mut resolve = |importer_module_index: usize, visibility: Visibility| {
+            check.on_symbol_resolved(&ResolvedSymbol {
+                importer_module_index,
+                full_name: "foo::internal_add",
+                export_type: ExportType::Function,
+                visibility,
+                target_module_index: 1,
+            })
+        };
+
+        assert!(resolve(0, Visibility::Package).is_ok());
+        assert!(resolve(2, Visibility::Public).is_ok());
+        assert_eq!(
+            resolve(2, Visibility::Package).unwrap_err().to_string(),
+            "Link hook rejected: The symbol \"foo::internal_add\" is not accessible by module 2."
+        );
+        assert!(resolve(3, Visibility::Package).is_err());
+        assert!(resolve(0, Visibility::Private).is_err());
+    }
+}
@@ -8,6 +8,8 @@ pub mod data_index_section;
 pub mod entry_point_section;
 pub mod external_function_index_section;
 pub mod function_index_section;
+pub mod initialization_dependency_section;
+pub mod lazy_binding_section;
 pub mod linking_module_section;
 pub mod unified_external_function_section;
 pub mod unified_external_library_section;
@@ -11,20 +11,39 @@
 //              |-----------------------------------------------------|
 //  item 0 -->  | unit name offset 0 (u32) | unit name length 0 (u32) |
 //              | fn public index 0 (u32)                             | <-- table
+//              | app info offset 0 (u32)  | app info length 0 (u32)  |
+//              | run conf offset 0 (u32)  | run conf length 0 (u32)  |
 //  item 1 -->  | unit name offset 1       | unit name length 1       |
 //              | fn public index 1                                   |
+//              | app info offset 1        | app info length 1        |
+//              | run conf offset 1        | run conf length 1        |
 //              | ...                                                 |
 //              |-----------------------------------------------------|
-// offset 0 --> | unit name string 0 (UTF-8)                          | <-- data
-// offset 1 --> | unit name string 1                                  |
+// offset 0 --> | unit name 0 (UTF-8) | app info 0 | run conf 0       | <-- data
+// offset 1 --> | unit name 1         | app info 1 | run conf 1       |
 //              | ...                                                 |
 //              |-----------------------------------------------------|
+//
+// The "app info" is the metadata of a top-level application (see `ApplicationInfo`),
+// it is encoded as the description followed by a `\0`, e.g. "Copy files\0" (UTF-8).
+// The length is `0` if the entry point is not an application.
+//
+// The "run conf" is the run configuration (see `RunConfiguration`), it is encoded
+// as a list of "{key}={value}\0" (UTF-8), the keys are:
+//
+// - `env`: a required environment variable.
+// - `cwd`: the working directory.
+// - `arg`: a default argument.
+//
+// e.g. "env=HOME\0cwd=/tmp\0arg=-v\0arg=foo.txt\0".
+// The length is `0` if the run configuration is empty.
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
-    entry::EntryPointEntry,
+    entry::{ApplicationInfo, EntryPointEntry, RunConfiguration},
     module_image::{ModuleSectionId, SectionEntry},
 };
 
@@ -32,7 +51,8 @@ use crate::{
 pub struct EntryPointSection<'a> {
     /// A slice of entry point items representing the table.
     pub items: &'a [EntryPointItem],
-    /// A slice of UTF-8 encoded unit name strings representing the data area.
+    /// A slice of UTF-8 encoded unit name strings and application infos
+    /// representing the data area.
     pub unit_names_data: &'a [u8],
 }
 
@@ -64,15 +84,35 @@ pub struct EntryPointItem {
     ///
     /// The module index is omitted because entry points always exist in the main module.
     pub function_public_index: u32,
+    /// Offset of the encoded application info in the data area.
+    pub application_info_offset: u32,
+    /// Length of the encoded application info, `0` if the entry point is not an application.
+    pub application_info_length: u32,
+    /// Offset of the encoded run configuration in the data area.
+    pub run_configuration_offset: u32,
+    /// Length of the encoded run configuration, `0` if the run configuration is empty.
+    pub run_configuration_length: u32,
 }
 
 impl EntryPointItem {
     /// Creates a new `EntryPointItem`.
-    pub fn new(unit_name_offset: u32, unit_name_length: u32, function_public_index: u32) -> Self {
+    pub fn new(
+        unit_name_offset: u32,
+        unit_name_length: u32,
+        function_public_index: u32,
+        application_info_offset: u32,
+        application_info_length: u32,
+        run_configuration_offset: u32,
+        run_configuration_length: u32,
+    ) -> Self {
         Self {
             unit_name_offset,
             unit_name_length,
             function_public_index,
+            application_info_offset,
+            application_info_length,
+            run_configuration_offset,
+            run_configuration_length,
         }
     }
 }
@@ -93,6 +133,10 @@ impl<'a> SectionEntry<'a> for EntryPointSection<'a> {
         write_section_with_table_and_data_area(self.items, self.unit_names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.unit_names_data)
+    }
+
     /// Returns the section ID for the entry point section.
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::EntryPoint
@@ -100,6 +144,23 @@ impl<'a> SectionEntry<'a> for EntryPointSection<'a> {
 }
 
 impl<'a> EntryPointSection<'a> {
+    /// Checks that the unit names, the application information and the run
+    /// configurations of all items are located in the data area and are
+    /// valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            [
+                (item.unit_name_offset, item.unit_name_length),
+                (item.application_info_offset, item.application_info_length),
+                (item.run_configuration_offset, item.run_configuration_length),
+            ]
+            .iter()
+            .all(|(offset, length)| {
+                get_data_area_text(self.unit_names_data, *offset, *length).is_some()
+            })
+        })
+    }
+
     /// Retrieves the public index of the function corresponding to the given unit name.
     pub fn get_function_public_index(&'a self, expected_unit_name: &str) -> Option<usize> {
         let items = self.items;
@@ -118,51 +179,121 @@ impl<'a> EntryPointSection<'a> {
 
     /// Converts the section into a vector of `EntryPointEntry` objects.
     pub fn convert_to_entries(&self) -> Vec<EntryPointEntry> {
-        let items = self.items;
-        let unit_names_data = self.unit_names_data;
-
-        items
+        self.items
             .iter()
-            .map(|item| {
-                let unit_name_data = &unit_names_data[item.unit_name_offset as usize
-                    ..(item.unit_name_offset + item.unit_name_length) as usize];
+            .map(|item| self.convert_item_to_entry(item))
+            .collect()
+    }
 
-                let unit_name = std::str::from_utf8(unit_name_data).unwrap().to_owned();
-                EntryPointEntry::new(unit_name, item.function_public_index as usize)
-            })
+    /// Returns the entry points which are top-level applications,
+    /// in the order of the section.
+    pub fn get_application_entries(&self) -> Vec<EntryPointEntry> {
+        self.items
+            .iter()
+            .filter(|item| item.application_info_length > 0)
+            .map(|item| self.convert_item_to_entry(item))
             .collect()
     }
 
+    /// Retrieves the top-level application with the given name.
+    pub fn get_application_entry(&self, application_name: &str) -> Option<EntryPointEntry> {
+        self.get_application_entries()
+            .into_iter()
+            .find(|entry| entry.unit_name == application_name)
+    }
+
+    fn convert_item_to_entry(&self, item: &EntryPointItem) -> EntryPointEntry {
+        let read_text = |offset: u32, length: u32| {
+            let data = &self.unit_names_data[offset as usize..(offset + length) as usize];
+            std::str::from_utf8(data).unwrap()
+        };
+
+        let unit_name = read_text(item.unit_name_offset, item.unit_name_length).to_owned();
+        let mut entry = EntryPointEntry::new(unit_name, item.function_public_index as usize);
+
+        if item.application_info_length > 0 {
+            let application_info_text =
+                read_text(item.application_info_offset, item.application_info_length);
+            let description = application_info_text.trim_end_matches('\0');
+            entry = entry.with_application_info(ApplicationInfo::new(description.to_owned()));
+        }
+
+        let mut run_configuration = RunConfiguration::default();
+        for field in read_text(item.run_configuration_offset, item.run_configuration_length)
+            .split_terminator('\0')
+        {
+            match field.split_once('=') {
+                Some(("env", name)) => run_configuration
+                    .required_environment_variables
+                    .push(name.to_owned()),
+                Some(("cwd", path)) => run_configuration.working_directory = Some(path.to_owned()),
+                Some(("arg", argument)) => run_configuration
+                    .default_arguments
+                    .push(argument.to_owned()),
+                _ => {
+                    // ignore the unknown fields.
+                }
+            }
+        }
+
+        entry.with_run_configuration(run_configuration)
+    }
+
     /// Converts a vector of `EntryPointEntry` objects into section data.
     pub fn convert_from_entries(entries: &[EntryPointEntry]) -> (Vec<EntryPointItem>, Vec<u8>) {
-        let unit_name_bytes = entries
-            .iter()
-            .map(|entry| entry.unit_name.as_bytes())
-            .collect::<Vec<&[u8]>>();
-
-        let mut next_offset: u32 = 0;
+        let mut unit_names_data: Vec<u8> = vec![];
 
         let items = entries
             .iter()
-            .enumerate()
-            .map(|(idx, entry)| {
-                let unit_name_offset = next_offset;
-                let unit_name_length = unit_name_bytes[idx].len() as u32;
-                next_offset += unit_name_length; // Update offset for the next entry.
+            .map(|entry| {
+                let unit_name_offset = unit_names_data.len() as u32;
+                let unit_name_length = entry.unit_name.len() as u32;
+                unit_names_data.extend_from_slice(entry.unit_name.as_bytes());
+
+                let application_info_offset = unit_names_data.len() as u32;
+                if let Some(application_info) = &entry.application_info {
+                    unit_names_data.extend_from_slice(application_info.description.as_bytes());
+                    unit_names_data.push(0);
+                }
+                let application_info_length =
+                    unit_names_data.len() as u32 - application_info_offset;
+
+                let run_configuration = &entry.run_configuration;
+                let run_configuration_offset = unit_names_data.len() as u32;
+                let fields = run_configuration
+                    .required_environment_variables
+                    .iter()
+                    .map(|name| ("env", name))
+                    .chain(
+                        run_configuration
+                            .working_directory
+                            .iter()
+                            .map(|path| ("cwd", path)),
+                    )
+                    .chain(
+                        run_configuration
+                            .default_arguments
+                            .iter()
+                            .map(|argument| ("arg", argument)),
+                    );
+                for (key, value) in fields {
+                    unit_names_data.extend_from_slice(format!("{}={}\0", key, value).as_bytes());
+                }
+                let run_configuration_length =
+                    unit_names_data.len() as u32 - run_configuration_offset;
 
                 EntryPointItem::new(
                     unit_name_offset,
                     unit_name_length,
                     entry.function_public_index as u32,
+                    application_info_offset,
+                    application_info_length,
+                    run_configuration_offset,
+                    run_configuration_length,
                 )
             })
             .collect::<Vec<EntryPointItem>>();
 
-        let unit_names_data = unit_name_bytes
-            .iter()
-            .flat_map(|bytes| bytes.to_vec())
-            .collect::<Vec<u8>>();
-
         (items, unit_names_data)
     }
 }
@@ -170,7 +301,7 @@ impl<'a> EntryPointSection<'a> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        entry::EntryPointEntry,
+        entry::{ApplicationInfo, EntryPointEntry, RunConfiguration},
         linking_sections::entry_point_section::{EntryPointItem, EntryPointSection},
         module_image::SectionEntry,
     };
@@ -178,14 +309,14 @@ mod tests {
     #[test]
     fn test_write_section() {
         let items: Vec<EntryPointItem> = vec![
-            EntryPointItem::new(0, 6, 11),
-            EntryPointItem::new(6, 3, 13),
-            EntryPointItem::new(9, 5, 17),
+            EntryPointItem::new(0, 6, 11, 6, 0, 6, 0),
+            EntryPointItem::new(6, 3, 13, 9, 4, 13, 7),
+            EntryPointItem::new(20, 5, 17, 25, 0, 25, 0),
         ];
 
         let section = EntryPointSection {
             items: &items,
-            unit_names_data: "_startfoohello".as_bytes(),
+            unit_names_data: "_startfoobar\0arg=-v\0hello".as_bytes(),
         };
 
         let mut section_data: Vec<u8> = vec![];
@@ -198,20 +329,34 @@ mod tests {
             0, 0, 0, 0, // Name offset (item 0).
             6, 0, 0, 0, // Name length.
             11, 0, 0, 0, // Function public index.
+            6, 0, 0, 0, // Application info offset.
+            0, 0, 0, 0, // Application info length.
+            6, 0, 0, 0, // Run configuration offset.
+            0, 0, 0, 0, // Run configuration length.
             //
             6, 0, 0, 0, // Name offset (item 1).
             3, 0, 0, 0, // Name length.
             13, 0, 0, 0, // Function public index.
+            9, 0, 0, 0, // Application info offset.
+            4, 0, 0, 0, // Application info length.
+            13, 0, 0, 0, // Run configuration offset.
+            7, 0, 0, 0, // Run configuration length.
             //
-            9, 0, 0, 0, // Name offset (item 2).
+            20, 0, 0, 0, // Name offset (item 2).
             5, 0, 0, 0, // Name length.
             17, 0, 0, 0, // Function public index.
+            25, 0, 0, 0, // Application info offset.
+            0, 0, 0, 0, // Application info length.
+            25, 0, 0, 0, // Run configuration offset.
+            0, 0, 0, 0, // Run configuration length.
         ];
 
         expect_data.extend_from_slice(b"_start");
         expect_data.extend_from_slice(b"foo");
+        expect_data.extend_from_slice(b"bar\0"); // Application info.
+        expect_data.extend_from_slice(b"arg=-v\0"); // Run configuration.
         expect_data.extend_from_slice(b"hello");
-        expect_data.extend_from_slice(b"\0\0"); // Section 4-byte alignment.
+        expect_data.extend_from_slice(b"\0\0\0"); // Section 4-byte alignment.
 
         assert_eq!(section_data, expect_data);
     }
@@ -225,35 +370,68 @@ mod tests {
             0, 0, 0, 0, // Name offset (item 0).
             6, 0, 0, 0, // Name length.
             11, 0, 0, 0, // Function public index.
+            6, 0, 0, 0, // Application info offset.
+            0, 0, 0, 0, // Application info length.
+            6, 0, 0, 0, // Run configuration offset.
+            0, 0, 0, 0, // Run configuration length.
             //
             6, 0, 0, 0, // Name offset (item 1).
             3, 0, 0, 0, // Name length.
             13, 0, 0, 0, // Function public index.
+            9, 0, 0, 0, // Application info offset.
+            4, 0, 0, 0, // Application info length.
+            13, 0, 0, 0, // Run configuration offset.
+            7, 0, 0, 0, // Run configuration length.
             //
-            9, 0, 0, 0, // Name offset (item 2).
+            20, 0, 0, 0, // Name offset (item 2).
             5, 0, 0, 0, // Name length.
             17, 0, 0, 0, // Function public index.
+            25, 0, 0, 0, // Application info offset.
+            0, 0, 0, 0, // Application info length.
+            25, 0, 0, 0, // Run configuration offset.
+            0, 0, 0, 0, // Run configuration length.
         ];
 
         section_data.extend_from_slice("_start".as_bytes());
         section_data.extend_from_slice("foo".as_bytes());
+        section_data.extend_from_slice("bar\0".as_bytes());
+        section_data.extend_from_slice("arg=-v\0".as_bytes());
         section_data.extend_from_slice("hello".as_bytes());
 
         let section = EntryPointSection::read(&section_data);
 
         assert_eq!(section.items.len(), 3);
-        assert_eq!(section.items[0], EntryPointItem::new(0, 6, 11));
-        assert_eq!(section.items[1], EntryPointItem::new(6, 3, 13));
-        assert_eq!(section.items[2], EntryPointItem::new(9, 5, 17));
-        assert_eq!(section.unit_names_data, "_startfoohello".as_bytes())
+        assert_eq!(section.items[0], EntryPointItem::new(0, 6, 11, 6, 0, 6, 0));
+        assert_eq!(section.items[1], EntryPointItem::new(6, 3, 13, 9, 4, 13, 7));
+        assert_eq!(
+            section.items[2],
+            EntryPointItem::new(20, 5, 17, 25, 0, 25, 0)
+        );
+        assert_eq!(
+            section.unit_names_data,
+            "_startfoobar\0arg=-v\0hello".as_bytes()
+        )
     }
 
     #[test]
     fn test_convert() {
         let entries: Vec<EntryPointEntry> = vec![
             EntryPointEntry::new("_start".to_string(), 11),
-            EntryPointEntry::new("foo".to_string(), 13),
-            EntryPointEntry::new("hello".to_string(), 15),
+            EntryPointEntry::new("foo".to_string(), 13)
+                .with_application_info(ApplicationInfo::new("Foo tool".to_owned()))
+                .with_run_configuration(RunConfiguration {
+                    required_environment_variables: vec!["HOME".to_owned()],
+                    working_directory: Some("/tmp".to_owned()),
+                    default_arguments: vec!["-v".to_owned(), "a=b".to_owned(), "".to_owned()],
+                }),
+            EntryPointEntry::new("hello".to_string(), 15)
+                .with_application_info(ApplicationInfo::default()),
+            EntryPointEntry::new("world".to_string(), 17).with_run_configuration(
+                RunConfiguration {
+                    default_arguments: vec!["--all".to_owned()],
+                    ..Default::default()
+                },
+            ),
         ];
 
         let (items, names_data) = EntryPointSection::convert_from_entries(&entries);
@@ -268,7 +446,34 @@ mod tests {
 
         assert!(section.get_function_public_index("bar").is_none());
 
+        assert_eq!(
+            section
+                .get_application_entries()
+                .iter()
+                .map(|entry| entry.unit_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo", "hello"]
+        );
+        assert_eq!(
+            section
+                .get_application_entry("foo")
+                .unwrap()
+                .application_info,
+            Some(ApplicationInfo::new("Foo tool".to_owned()))
+        );
+        assert!(section.get_application_entry("world").is_none());
+
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries, entries_restore);
+
+        let run_configuration = &entries_restore[1].run_configuration;
+        assert_eq!(
+            run_configuration.get_missing_environment_variables(|name| name == "PATH"),
+            vec!["HOME"]
+        );
+        assert!(run_configuration
+            .get_missing_environment_variables(|name| name == "HOME")
+            .is_empty());
+        assert!(entries_restore[0].run_configuration.is_empty());
     }
 }
@@ -25,7 +25,10 @@
 //           |---------------------------------------|
 
 use crate::{
-    datatableaccess::{read_section_with_two_tables, write_section_with_two_tables},
+    datatableaccess::{
+        check_ranges, read_section_with_two_tables, section_with_two_tables_length,
+        write_section_with_two_tables,
+    },
     entry::{ExternalFunctionIndexEntry, ExternalFunctionIndexListEntry},
     module_image::{ModuleSectionId, RangeItem, SectionEntry},
 };
@@ -67,6 +70,10 @@ impl<'a> SectionEntry<'a> for ExternalFunctionIndexSection<'a> {
         write_section_with_two_tables(self.ranges, self.items, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_two_tables_length(self.ranges, self.items)
+    }
+
     /// Returns the section ID for the external function index section.
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::ExternalFunctionIndex
@@ -74,6 +81,11 @@ impl<'a> SectionEntry<'a> for ExternalFunctionIndexSection<'a> {
 }
 
 impl ExternalFunctionIndexSection<'_> {
+    /// Checks that the items of all ranges are located in the item table.
+    pub fn is_well_formed(&self) -> bool {
+        check_ranges(self.ranges, self.items.len())
+    }
+
     /// Returns the number of items in a specific range identified by `module_index`.
     pub fn get_items_count(&self, module_index: usize) -> usize {
         let range = &self.ranges[module_index];
@@ -29,7 +29,10 @@
 // `(target_module_index, function_internal_index)`.
 
 use crate::{
-    datatableaccess::{read_section_with_two_tables, write_section_with_two_tables},
+    datatableaccess::{
+        check_ranges, read_section_with_two_tables, section_with_two_tables_length,
+        write_section_with_two_tables,
+    },
     entry::{FunctionIndexEntry, FunctionIndexListEntry},
     module_image::{ModuleSectionId, RangeItem, SectionEntry},
 };
@@ -74,12 +77,21 @@ impl<'a> SectionEntry<'a> for FunctionIndexSection<'a> {
         write_section_with_two_tables(self.ranges, self.items, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_two_tables_length(self.ranges, self.items)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::FunctionIndex
     }
 }
 
 impl FunctionIndexSection<'_> {
+    /// Checks that the items of all ranges are located in the item table.
+    pub fn is_well_formed(&self) -> bool {
+        check_ranges(self.ranges, self.items.len())
+    }
+
     /// Returns the number of items in a specific range (module index).
     pub fn get_items_count(&self, module_index: usize) -> usize {
         let range = &self.ranges[module_index];
@@ -0,0 +1,208 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Initialization Dependency Section" lists the dependency edges between
+// the constructors of the modules at the data level, i.e., the constructors of
+// a module must run after the imported data item is initialized (the imported
+// data items are declared by `ImportDataEntry::requires_initialization`).
+//
+// The loader computes the global initialization order of the modules from
+// the edges, see `initialization_order::compute_initialization_order`.
+//
+// "Initialization Dependency Section" binary layout:
+//
+//              |--------------------------------------------------|
+//              | item count (u32) | extra header length (u32)     |
+//              |--------------------------------------------------|
+//  item 0 -->  | module idx 0 (u32) | target module idx 0 (u32)   | <-- table
+//              | target data section type 0 (u8) | pad 3 bytes    |
+//              | data internal idx in section 0 (u32)             |
+//  item 1 -->  | module idx 1       | target module idx 1         |
+//              | target data section type 1      | pad 3 bytes    |
+//              | data internal idx in section 1                   |
+//              | ...                                              |
+//              |--------------------------------------------------|
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
+    entry::InitializationDependencyEntry,
+    module_image::{ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct InitializationDependencySection<'a> {
+    pub items: &'a [InitializationDependencyItem],
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct InitializationDependencyItem {
+    pub module_index: u32,
+    pub target_module_index: u32,
+    pub target_data_section_type: DataSectionType,
+    _padding0: [u8; 3],
+    pub data_internal_index_in_section: u32,
+}
+
+impl InitializationDependencyItem {
+    pub fn new(
+        module_index: u32,
+        target_module_index: u32,
+        target_data_section_type: DataSectionType,
+        data_internal_index_in_section: u32,
+    ) -> Self {
+        Self {
+            module_index,
+            target_module_index,
+            target_data_section_type,
+            _padding0: [0; 3],
+            data_internal_index_in_section,
+        }
+    }
+}
+
+impl<'a> SectionEntry<'a> for InitializationDependencySection<'a> {
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::InitializationDependency
+    }
+
+    fn read(section_data: &'a [u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let items = read_section_with_one_table::<InitializationDependencyItem>(section_data);
+        InitializationDependencySection { items }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_one_table(self.items, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
+}
+
+impl InitializationDependencySection<'_> {
+    /// Returns the indices of the modules whose constructors must run
+    /// before the constructors of the specified module, without duplicates.
+    pub fn get_dependency_module_indices(&self, module_index: usize) -> Vec<usize> {
+        let mut target_module_indices = vec![];
+        for item in self
+            .items
+            .iter()
+            .filter(|item| item.module_index as usize == module_index)
+        {
+            let target_module_index = item.target_module_index as usize;
+            if !target_module_indices.contains(&target_module_index) {
+                target_module_indices.push(target_module_index);
+            }
+        }
+        target_module_indices
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<InitializationDependencyEntry> {
+        self.items
+            .iter()
+            .map(|item| {
+                InitializationDependencyEntry::new(
+                    item.module_index as usize,
+                    item.target_module_index as usize,
+                    item.target_data_section_type,
+                    item.data_internal_index_in_section as usize,
+                )
+            })
+            .collect()
+    }
+
+    pub fn convert_from_entries(
+        entries: &[InitializationDependencyEntry],
+    ) -> Vec<InitializationDependencyItem> {
+        entries
+            .iter()
+            .map(|entry| {
+                InitializationDependencyItem::new(
+                    entry.module_index as u32,
+                    entry.target_module_index as u32,
+                    entry.target_data_section_type,
+                    entry.data_internal_index_in_section as u32,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::DataSectionType;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::InitializationDependencyEntry,
+        linking_sections::initialization_dependency_section::{
+            InitializationDependencyItem, InitializationDependencySection,
+        },
+        module_image::SectionEntry,
+    };
+
+    #[test]
+    fn test_write_section() {
+        let items = vec![
+            InitializationDependencyItem::new(0, 1, DataSectionType::ReadWrite, 3),
+            InitializationDependencyItem::new(2, 1, DataSectionType::Uninit, 5),
+        ];
+
+        let section = InitializationDependencySection { items: &items };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![
+                2u8, 0, 0, 0, // item count
+                0, 0, 0, 0, // extra section header length
+                //
+                0, 0, 0, 0, // module index
+                1, 0, 0, 0, // target module index
+                1, // target data section type
+                0, 0, 0, // padding
+                3, 0, 0, 0, // data internal index in section
+                //
+                2, 0, 0, 0, // module index
+                1, 0, 0, 0, // target module index
+                2, // target data section type
+                0, 0, 0, // padding
+                5, 0, 0, 0, // data internal index in section
+            ]
+        );
+
+        let section_restore = InitializationDependencySection::read(&section_data);
+        assert_eq!(section_restore.items, &items);
+    }
+
+    #[test]
+    fn test_convert() {
+        let entries = vec![
+            InitializationDependencyEntry::new(0, 2, DataSectionType::ReadWrite, 0),
+            InitializationDependencyEntry::new(0, 1, DataSectionType::ReadOnly, 1),
+            InitializationDependencyEntry::new(0, 2, DataSectionType::ReadWrite, 4),
+            InitializationDependencyEntry::new(1, 2, DataSectionType::Uninit, 0),
+        ];
+
+        let items = InitializationDependencySection::convert_from_entries(&entries);
+        let section = InitializationDependencySection { items: &items };
+
+        assert_eq!(section.get_dependency_module_indices(0), vec![2, 1]);
+        assert_eq!(section.get_dependency_module_indices(1), vec![2]);
+        assert!(section.get_dependency_module_indices(2).is_empty());
+        assert_eq!(section.convert_to_entries(), entries);
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "Lazy Binding Section" lists the thunk functions of the lazily bound
+// external functions, see `lazy_binding::generate_lazy_binding_thunks`.
+//
+// The runtime does not resolve the symbols of these external functions at load time,
+// instead, the symbol is resolved when the thunk is called for the first time.
+//
+// "Lazy Binding Section" binary layout:
+//
+//              |----------------------------------------------------|
+//              | item count (u32) | extra header length (u32)       |
+//              |----------------------------------------------------|
+//  item 0 -->  | unified external function idx 0 (u32)              | <-- table
+//              | module idx 0 (u32) | function internal idx 0 (u32) |
+//  item 1 -->  | unified external function idx 1                    |
+//              | module idx 1       | function internal idx 1       |
+//              | ...                                                |
+//              |----------------------------------------------------|
+
+use crate::{
+    datatableaccess::{
+        read_section_with_one_table, section_with_one_table_length, write_section_with_one_table,
+    },
+    entry::LazyBindingEntry,
+    module_image::{ModuleSectionId, SectionEntry},
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct LazyBindingSection<'a> {
+    pub items: &'a [LazyBindingItem],
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct LazyBindingItem {
+    pub unified_external_function_index: u32,
+    pub module_index: u32,
+    pub function_internal_index: u32,
+}
+
+impl LazyBindingItem {
+    pub fn new(
+        unified_external_function_index: u32,
+        module_index: u32,
+        function_internal_index: u32,
+    ) -> Self {
+        Self {
+            unified_external_function_index,
+            module_index,
+            function_internal_index,
+        }
+    }
+}
+
+impl<'a> SectionEntry<'a> for LazyBindingSection<'a> {
+    fn id(&'a self) -> ModuleSectionId {
+        ModuleSectionId::LazyBinding
+    }
+
+    fn read(section_data: &'a [u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let items = read_section_with_one_table::<LazyBindingItem>(section_data);
+        LazyBindingSection { items }
+    }
+
+    fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write_section_with_one_table(self.items, writer)
+    }
+
+    fn estimated_size(&'a self) -> usize {
+        section_with_one_table_length(self.items)
+    }
+}
+
+impl LazyBindingSection<'_> {
+    /// Checks that the unified external functions and the modules of all items
+    /// are located in their sections, i.e., the indices are less than
+    /// `unified_external_function_count` and `module_count` respectively.
+    pub fn is_well_formed(
+        &self,
+        unified_external_function_count: usize,
+        module_count: usize,
+    ) -> bool {
+        self.items.iter().all(|item| {
+            (item.unified_external_function_index as usize) < unified_external_function_count
+                && (item.module_index as usize) < module_count
+        })
+    }
+
+    /// Returns the unified external function index which is bound by the thunk,
+    /// or `None` if the function is not a thunk.
+    pub fn get_unified_external_function_index(
+        &self,
+        module_index: usize,
+        function_internal_index: usize,
+    ) -> Option<usize> {
+        self.items
+            .iter()
+            .find(|item| {
+                item.module_index as usize == module_index
+                    && item.function_internal_index as usize == function_internal_index
+            })
+            .map(|item| item.unified_external_function_index as usize)
+    }
+
+    pub fn convert_to_entries(&self) -> Vec<LazyBindingEntry> {
+        self.items
+            .iter()
+            .map(|item| {
+                LazyBindingEntry::new(
+                    item.unified_external_function_index as usize,
+                    item.module_index as usize,
+                    item.function_internal_index as usize,
+                )
+            })
+            .collect()
+    }
+
+    pub fn convert_from_entries(entries: &[LazyBindingEntry]) -> Vec<LazyBindingItem> {
+        entries
+            .iter()
+            .map(|entry| {
+                LazyBindingItem::new(
+                    entry.unified_external_function_index as u32,
+                    entry.module_index as u32,
+                    entry.function_internal_index as u32,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::LazyBindingEntry,
+        linking_sections::lazy_binding_section::{LazyBindingItem, LazyBindingSection},
+        module_image::SectionEntry,
+    };
+
+    #[test]
+    fn test_write_section() {
+        let entries = vec![
+            LazyBindingEntry::new(3, 0, 5),
+            LazyBindingEntry::new(1, 2, 7),
+        ];
+        let items = LazyBindingSection::convert_from_entries(&entries);
+        let section = LazyBindingSection { items: &items };
+
+        let mut section_data: Vec<u8> = vec![];
+        section.write(&mut section_data).unwrap();
+
+        assert_eq!(
+            section_data,
+            vec![
+                2u8, 0, 0, 0, // item count
+                0, 0, 0, 0, // extra section header length
+                //
+                3, 0, 0, 0, // unified external function index
+                0, 0, 0, 0, // module index
+                5, 0, 0, 0, // function internal index
+                //
+                1, 0, 0, 0, // unified external function index
+                2, 0, 0, 0, // module index
+                7, 0, 0, 0, // function internal index
+            ]
+        );
+
+        let section_restore = LazyBindingSection::read(&section_data);
+        assert_eq!(
+            section_restore.items,
+            &[LazyBindingItem::new(3, 0, 5), LazyBindingItem::new(1, 2, 7)]
+        );
+        assert_eq!(
+            section_restore.get_unified_external_function_index(2, 7),
+            Some(1)
+        );
+        assert_eq!(
+            section_restore.get_unified_external_function_index(0, 7),
+            None
+        );
+        assert_eq!(section_restore.convert_to_entries(), entries);
+    }
+}
@@ -22,10 +22,12 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
-    entry::{LinkingModuleEntry, ModuleLocation},
+    entry::{LinkingModuleEntry, ModuleLocation, ModuleLocationKind},
     module_image::{ModuleSectionId, SectionEntry},
+    DependencyHash,
 };
 
 #[derive(Debug, PartialEq)]
@@ -75,6 +77,10 @@ impl<'a> SectionEntry<'a> for LinkingModuleSection<'a> {
         write_section_with_table_and_data_area(self.items, self.items_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
     /// Returns the section ID for the linking module.
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::LinkingModule
@@ -82,6 +88,17 @@ impl<'a> SectionEntry<'a> for LinkingModuleSection<'a> {
 }
 
 impl<'a> LinkingModuleSection<'a> {
+    /// Checks that the names and the values of all items are located in
+    /// the data area, and the values are valid module locations.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            let opt_value_text =
+                get_data_area_text(self.items_data, item.value_offset, item.value_length);
+            get_data_area_text(self.items_data, item.name_offset, item.name_length).is_some()
+                && opt_value_text.is_some_and(|text| ason::from_str::<ModuleLocation>(text).is_ok())
+        })
+    }
+
     pub fn get_item_name_and_value(&'a self, idx: usize) -> (&'a str, &'a [u8]) {
         let items = self.items;
         let items_data = self.items_data;
@@ -95,6 +112,25 @@ impl<'a> LinkingModuleSection<'a> {
         (std::str::from_utf8(name_data).unwrap(), value_data)
     }
 
+    /// Deserializes the location of the item at the specified index only,
+    /// the other items are left untouched.
+    pub fn get_item_module_location(&'a self, idx: usize) -> ModuleLocation {
+        let (_, value_data) = self.get_item_name_and_value(idx);
+        ason::from_reader(value_data).unwrap()
+    }
+
+    /// Returns the location kind and the parsed hash of the item at the specified index,
+    /// e.g., for comparing with the hash of the cached module file.
+    ///
+    /// The hash is `None` if the location has no hash or the hash string is malformed.
+    pub fn get_item_location_kind_and_hash(
+        &'a self,
+        idx: usize,
+    ) -> (ModuleLocationKind, Option<DependencyHash>) {
+        let module_location = self.get_item_module_location(idx);
+        (module_location.kind(), module_location.hash())
+    }
+
     /// Converts the section into a vector of `LinkingModuleEntry` objects.
     pub fn convert_to_entries(&self) -> Vec<LinkingModuleEntry> {
         let items = self.items;
@@ -163,9 +199,13 @@ impl<'a> LinkingModuleSection<'a> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        entry::{LinkingModuleEntry, ModuleLocation, ModuleLocationLocal, ModuleLocationShare},
+        entry::{
+            LinkingModuleEntry, ModuleLocation, ModuleLocationKind, ModuleLocationLocal,
+            ModuleLocationShare,
+        },
         linking_sections::linking_module_section::{LinkingModuleItem, LinkingModuleSection},
         module_image::SectionEntry,
+        DEPENDENCY_HASH_ZERO,
     };
 
     #[test]
@@ -278,4 +318,49 @@ mod tests {
         let entries_restore = section.convert_to_entries();
         assert_eq!(entries_restore, entries);
     }
+
+    #[test]
+    fn test_location_kind_and_hash() {
+        let entries = vec![
+            LinkingModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            LinkingModuleEntry::new(
+                "foo".to_owned(),
+                Box::new(ModuleLocation::Share(Box::new(ModuleLocationShare {
+                    version: "1.2.3".to_owned(),
+                    hash: "0123456789abcdef".to_owned(),
+                }))),
+            ),
+            LinkingModuleEntry::new(
+                "bar".to_owned(),
+                Box::new(ModuleLocation::Local(Box::new(ModuleLocationLocal {
+                    module_path: "/path/to/bar".to_owned(),
+                    hash: "xyz".to_owned(),
+                }))),
+            ),
+        ];
+
+        let (items, items_data) = LinkingModuleSection::convert_from_entries(&entries);
+        let section = LinkingModuleSection {
+            items: &items,
+            items_data: &items_data,
+        };
+
+        let mut expect_hash = DEPENDENCY_HASH_ZERO;
+        expect_hash[..8].copy_from_slice(&[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+
+        assert_eq!(
+            section.get_item_location_kind_and_hash(0),
+            (ModuleLocationKind::Embed, None)
+        );
+        assert_eq!(
+            section.get_item_location_kind_and_hash(1),
+            (ModuleLocationKind::Share, Some(expect_hash))
+        );
+
+        // the malformed hash
+        assert_eq!(
+            section.get_item_location_kind_and_hash(2),
+            (ModuleLocationKind::Local, None)
+        );
+    }
 }
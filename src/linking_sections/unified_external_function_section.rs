@@ -24,7 +24,8 @@
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ExternalFunctionEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -80,6 +81,10 @@ impl<'a> SectionEntry<'a> for UnifiedExternalFunctionSection<'a> {
         write_section_with_table_and_data_area(self.items, self.names_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.names_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         // Returns the section ID for the unified external function section.
         ModuleSectionId::UnifiedExternalFunction
@@ -87,6 +92,14 @@ impl<'a> SectionEntry<'a> for UnifiedExternalFunctionSection<'a> {
 }
 
 impl<'a> UnifiedExternalFunctionSection<'a> {
+    /// Checks that the names of all items are located in the data area
+    /// and are valid UTF-8 strings.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            get_data_area_text(self.names_data, item.name_offset, item.name_length).is_some()
+        })
+    }
+
     pub fn get_item_name_and_external_library_index_and_type_index(
         &'a self,
         idx: usize,
@@ -106,6 +119,26 @@ impl<'a> UnifiedExternalFunctionSection<'a> {
         )
     }
 
+    pub fn convert_to_entries(&self) -> Vec<ExternalFunctionEntry> {
+        let items = self.items;
+        let names_data = self.names_data;
+
+        items
+            .iter()
+            .map(|item| {
+                let name_data = &names_data
+                    [item.name_offset as usize..(item.name_offset + item.name_length) as usize];
+
+                let name = std::str::from_utf8(name_data).unwrap().to_owned();
+                ExternalFunctionEntry::new(
+                    name,
+                    item.external_library_index as usize,
+                    item.type_index as usize,
+                )
+            })
+            .collect()
+    }
+
     pub fn convert_from_entries(
         entries: &[ExternalFunctionEntry],
     ) -> (Vec<ExternalFunctionItem>, Vec<u8>) {
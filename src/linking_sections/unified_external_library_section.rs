@@ -28,7 +28,8 @@ use anc_isa::{ExternalLibraryDependency, ExternalLibraryDependencyType};
 
 use crate::{
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_text, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::ExternalLibraryEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -83,6 +84,10 @@ impl<'a> SectionEntry<'a> for UnifiedExternalLibrarySection<'a> {
         write_section_with_table_and_data_area(self.items, self.items_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.items_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         // Returns the section ID for UnifiedExternalLibrary.
         ModuleSectionId::UnifiedExternalLibrary
@@ -90,6 +95,18 @@ impl<'a> SectionEntry<'a> for UnifiedExternalLibrarySection<'a> {
 }
 
 impl<'a> UnifiedExternalLibrarySection<'a> {
+    /// Checks that the names and the values of all items are located in
+    /// the data area, and the values are valid dependency objects.
+    pub fn is_well_formed(&self) -> bool {
+        self.items.iter().all(|item| {
+            let opt_value_text =
+                get_data_area_text(self.items_data, item.value_offset, item.value_length);
+            get_data_area_text(self.items_data, item.name_offset, item.name_length).is_some()
+                && opt_value_text
+                    .is_some_and(|text| ason::from_str::<ExternalLibraryDependency>(text).is_ok())
+        })
+    }
+
     pub fn get_item_name_and_external_library_dependent_type_and_value(
         &'a self,
         idx: usize,
@@ -110,6 +127,25 @@ impl<'a> UnifiedExternalLibrarySection<'a> {
         )
     }
 
+    pub fn convert_to_entries(&self) -> Vec<ExternalLibraryEntry> {
+        let items = self.items;
+        let items_data = self.items_data;
+
+        items
+            .iter()
+            .map(|item| {
+                let name_data = &items_data
+                    [item.name_offset as usize..(item.name_offset + item.name_length) as usize];
+                let value_data = &items_data
+                    [item.value_offset as usize..(item.value_offset + item.value_length) as usize];
+
+                let name = std::str::from_utf8(name_data).unwrap().to_owned();
+                let dependency: ExternalLibraryDependency = ason::from_reader(value_data).unwrap();
+                ExternalLibraryEntry::new(name, Box::new(dependency))
+            })
+            .collect()
+    }
+
     pub fn convert_from_entries(
         entries: &[ExternalLibraryEntry],
     ) -> (Vec<ExternalLibraryItem>, Vec<u8>) {
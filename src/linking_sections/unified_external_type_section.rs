@@ -29,8 +29,10 @@ use std::ptr::slice_from_raw_parts;
 use anc_isa::OperandDataType;
 
 use crate::{
+    common_sections::type_section::is_valid_operand_data_type,
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        get_data_area_bytes, read_section_with_table_and_data_area,
+        section_with_table_and_data_area_length, write_section_with_table_and_data_area,
     },
     entry::TypeEntry,
     module_image::{ModuleSectionId, SectionEntry},
@@ -88,12 +90,30 @@ impl<'a> SectionEntry<'a> for UnifiedExternalTypeSection<'a> {
         write_section_with_table_and_data_area(self.items, self.types_data, writer)
     }
 
+    fn estimated_size(&'a self) -> usize {
+        section_with_table_and_data_area_length(self.items, self.types_data)
+    }
+
     fn id(&'a self) -> ModuleSectionId {
         ModuleSectionId::UnifiedExternalType
     }
 }
 
 impl<'a> UnifiedExternalTypeSection<'a> {
+    /// Checks that the parameter and result type lists of all items are located
+    /// in the data area and contain only valid operand data types.
+    pub fn is_well_formed(&self) -> bool {
+        let is_valid_list = |offset: u32, count: u16| {
+            get_data_area_bytes(self.types_data, offset, count as u32)
+                .is_some_and(|data| data.iter().all(|byte| is_valid_operand_data_type(*byte)))
+        };
+
+        self.items.iter().all(|item| {
+            is_valid_list(item.params_offset, item.params_count)
+                && is_valid_list(item.results_offset, item.results_count)
+        })
+    }
+
     pub fn get_item_params_and_results(
         &'a self,
         idx: usize,
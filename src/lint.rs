@@ -0,0 +1,433 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Runs a set of checks over a module image and reports the results
+// as a list of `Diagnostic`, so that the toolchains can run one call
+// and render the results.
+//
+// There are two levels of checks:
+//
+// - Warning: the image is valid but probably not what the author intended,
+//   e.g., unused imports, private functions that are never referenced,
+//   empty optional sections and missing name sections.
+// - Error: the image is invalid, e.g., index violations, they are
+//   reported by the `validator`.
+//
+// Each check has a code (e.g. "unused_import_function"), which can be
+// used to disable the check through the `LintConfig`.
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    bytecode_search::{find_callers, find_data_references},
+    common_sections::{
+        data_name_section::DataNameItem, function_name_section::FunctionNameItem,
+        function_section::FunctionItem, import_data_section::ImportDataItem,
+        import_function_section::ImportFunctionItem,
+    },
+    diagnostic::{table_item_byte_range, Diagnostic, Severity},
+    module_image::{ImageType, ModuleImage, ModuleSectionId, Visibility},
+    validator::{validate_strict, INVALID_INDEX_CODE},
+};
+
+pub const LINT_INVALID_INDEX: &str = INVALID_INDEX_CODE;
+pub const LINT_UNUSED_IMPORT_FUNCTION: &str = "unused_import_function";
+pub const LINT_UNUSED_IMPORT_DATA: &str = "unused_import_data";
+pub const LINT_UNUSED_PRIVATE_FUNCTION: &str = "unused_private_function";
+pub const LINT_UNUSED_PRIVATE_DATA: &str = "unused_private_data";
+pub const LINT_EMPTY_OPTIONAL_SECTION: &str = "empty_optional_section";
+pub const LINT_MISSING_NAME_SECTION: &str = "missing_name_section";
+
+#[derive(Debug, PartialEq, Default)]
+pub struct LintConfig {
+    // The codes of the disabled checks.
+    pub allowed_codes: Vec<&'static str>,
+
+    // Reports all warnings as errors.
+    pub warnings_as_errors: bool,
+}
+
+/// Runs all checks over the module image.
+///
+/// The errors are listed first, followed by the warnings.
+pub fn image_lint(image: &ModuleImage, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = validate_strict(image)
+        .iter()
+        .map(|error| error.to_diagnostic(image))
+        .collect();
+
+    check_unused_imports(image, &mut diagnostics);
+    check_unused_private_items(image, &mut diagnostics);
+    check_empty_optional_sections(image, &mut diagnostics);
+    check_missing_name_sections(image, &mut diagnostics);
+
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| !config.allowed_codes.contains(&diagnostic.code))
+        .map(|mut diagnostic| {
+            if config.warnings_as_errors {
+                diagnostic.severity = Severity::Error;
+            }
+            diagnostic
+        })
+        .collect()
+}
+
+// The items are not reported as unused if the references can not be searched,
+// e.g., the function section is corrupted, see `bytecode_search::find_callers`.
+fn check_unused_imports(image: &ModuleImage, diagnostics: &mut Vec<Diagnostic>) {
+    // The imported items come first in the public index.
+    if let Some(import_function_section) = image.get_optional_import_function_section() {
+        for (function_public_index, entry) in import_function_section
+            .convert_to_entries()
+            .iter()
+            .enumerate()
+        {
+            if find_callers(image, function_public_index).is_ok_and(|sites| sites.is_empty()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        LINT_UNUSED_IMPORT_FUNCTION,
+                        format!(
+                            "The imported function \"{}\" is never used.",
+                            entry.full_name
+                        ),
+                    )
+                    .with_span(
+                        ModuleSectionId::ImportFunction,
+                        table_item_byte_range(
+                            function_public_index,
+                            size_of::<ImportFunctionItem>(),
+                        ),
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(import_data_section) = image.get_optional_import_data_section() {
+        for (data_public_index, entry) in
+            import_data_section.convert_to_entries().iter().enumerate()
+        {
+            if find_data_references(image, data_public_index).is_ok_and(|sites| sites.is_empty()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        LINT_UNUSED_IMPORT_DATA,
+                        format!("The imported data \"{}\" is never used.", entry.full_name),
+                    )
+                    .with_span(
+                        ModuleSectionId::ImportData,
+                        table_item_byte_range(data_public_index, size_of::<ImportDataItem>()),
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_unused_private_items(image: &ModuleImage, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(function_name_section) = image.get_optional_export_function_section() {
+        let import_function_count = image
+            .get_optional_import_function_section()
+            .map_or(0, |section| section.items.len());
+
+        for (item_index, entry) in function_name_section
+            .convert_to_entries()
+            .iter()
+            .enumerate()
+        {
+            if entry.visibility == Visibility::Private
+                && find_callers(image, import_function_count + entry.internal_index)
+                    .is_ok_and(|sites| sites.is_empty())
+            {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        LINT_UNUSED_PRIVATE_FUNCTION,
+                        format!(
+                            "The private function \"{}\" is never referenced.",
+                            entry.full_name
+                        ),
+                    )
+                    .with_span(
+                        ModuleSectionId::FunctionName,
+                        table_item_byte_range(item_index, size_of::<FunctionNameItem>()),
+                    )
+                    .with_related(
+                        ModuleSectionId::Function,
+                        table_item_byte_range(entry.internal_index, size_of::<FunctionItem>()),
+                        "The function is defined here.".to_owned(),
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(data_name_section) = image.get_optional_export_data_section() {
+        // The data public index is ordered by:
+        // imported data, read-only data, read-write data and uninitialized data.
+        let import_data_count = image
+            .get_optional_import_data_section()
+            .map_or(0, |section| section.items.len());
+        let read_only_data_count = image
+            .get_optional_read_only_data_section()
+            .map_or(0, |section| section.items.len());
+        let read_write_data_count = image
+            .get_optional_read_write_data_section()
+            .map_or(0, |section| section.items.len());
+
+        for (item_index, entry) in data_name_section.convert_to_entries().iter().enumerate() {
+            let section_start = match entry.section_type {
+                DataSectionType::ReadOnly => import_data_count,
+                DataSectionType::ReadWrite => import_data_count + read_only_data_count,
+                DataSectionType::Uninit => {
+                    import_data_count + read_only_data_count + read_write_data_count
+                }
+            };
+
+            if entry.visibility == Visibility::Private
+                && find_data_references(image, section_start + entry.internal_index_in_section)
+                    .is_ok_and(|sites| sites.is_empty())
+            {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        LINT_UNUSED_PRIVATE_DATA,
+                        format!(
+                            "The private data \"{}\" is never referenced.",
+                            entry.full_name
+                        ),
+                    )
+                    .with_span(
+                        ModuleSectionId::DataName,
+                        table_item_byte_range(item_index, size_of::<DataNameItem>()),
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_empty_optional_sections(image: &ModuleImage, diagnostics: &mut Vec<Diagnostic>) {
+    for item in image.items {
+        if item.id.is_essential() {
+            continue;
+        }
+
+        // All optional sections start with the "item count (u32)".
+        let section_data =
+            &image.sections_data[item.offset as usize..(item.offset + item.length) as usize];
+        let is_empty = section_data.len() < 4
+            || u32::from_le_bytes(section_data[0..4].try_into().unwrap()) == 0;
+
+        if is_empty {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    LINT_EMPTY_OPTIONAL_SECTION,
+                    format!("The optional section {:?} is empty.", item.id),
+                )
+                .with_span(item.id, 0..item.length as usize),
+            );
+        }
+    }
+}
+
+fn check_missing_name_sections(image: &ModuleImage, diagnostics: &mut Vec<Diagnostic>) {
+    // The items of an application are never imported by other modules.
+    if image.image_type == ImageType::Application {
+        return;
+    }
+
+    let function_count = image.get_function_section().items.len();
+    if function_count > 0 && image.get_optional_export_function_section().is_none() {
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            LINT_MISSING_NAME_SECTION,
+            "The function name section is missing, no function can be imported by other modules."
+                .to_owned(),
+        ));
+    }
+
+    let data_count = image
+        .get_optional_read_only_data_section()
+        .map_or(0, |section| section.items.len())
+        + image
+            .get_optional_read_write_data_section()
+            .map_or(0, |section| section.items.len())
+        + image
+            .get_optional_uninit_data_section()
+            .map_or(0, |section| section.items.len());
+    if data_count > 0 && image.get_optional_export_data_section().is_none() {
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            LINT_MISSING_NAME_SECTION,
+            "The data name section is missing, no data can be imported by other modules."
+                .to_owned(),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        common_sections::{
+            function_name_section::FunctionNameSection, function_section::FunctionSection,
+            import_function_section::ImportFunctionSection,
+            local_variable_section::LocalVariableSection,
+            read_only_data_section::ReadOnlyDataSection, type_section::TypeSection,
+        },
+        diagnostic::{Diagnostic, Severity},
+        entry::{
+            FunctionEntry, FunctionNameEntry, ImportFunctionEntry, LocalVariableListEntry,
+            TypeEntry,
+        },
+        lint::{
+            image_lint, LintConfig, LINT_EMPTY_OPTIONAL_SECTION, LINT_INVALID_INDEX,
+            LINT_UNUSED_IMPORT_FUNCTION, LINT_UNUSED_PRIVATE_FUNCTION,
+        },
+        module_image::{ImageType, ModuleImage, ModuleSectionId, SectionEntry, Visibility},
+    };
+
+    #[test]
+    fn test_image_lint() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let (type_items, types_data) =
+            TypeSection::convert_from_entries(&[TypeEntry::new(vec![], vec![])]);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let (local_variable_lists, local_variable_list_data) =
+            LocalVariableSection::convert_from_entries(&[LocalVariableListEntry::new(vec![])]);
+        let local_variable_section = LocalVariableSection {
+            lists: &local_variable_lists,
+            list_data: &local_variable_list_data,
+        };
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, code.clone()),
+            FunctionEntry::new(5, 0, code), // type index out of range
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let (import_function_items, import_function_names_data) =
+            ImportFunctionSection::convert_from_entries(&[ImportFunctionEntry::new(
+                "std::io::print".to_owned(),
+                0,
+                0,
+            )]);
+        let import_function_section = ImportFunctionSection {
+            items: &import_function_items,
+            full_names_data: &import_function_names_data,
+        };
+
+        let (function_name_items, function_names_data) =
+            FunctionNameSection::convert_from_entries(&[
+                FunctionNameEntry::new("app::foo".to_owned(), Visibility::Private, 0),
+                FunctionNameEntry::new("app::_start".to_owned(), Visibility::Public, 1),
+            ]);
+        let function_name_section = FunctionNameSection {
+            items: &function_name_items,
+            full_names_data: &function_names_data,
+        };
+
+        let (read_only_data_items, read_only_datas_data) =
+            ReadOnlyDataSection::convert_from_entries(&[]);
+        let read_only_data_section = ReadOnlyDataSection {
+            items: &read_only_data_items,
+            datas_data: &read_only_datas_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![
+            &type_section,
+            &local_variable_section,
+            &function_section,
+            &import_function_section,
+            &function_name_section,
+            &read_only_data_section,
+        ];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let diagnostics = image_lint(&image, &LintConfig::default());
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    Severity::Error,
+                    LINT_INVALID_INDEX,
+                    "Function \"app::_start\" (index 1): The type index 5 is out of range, the type section has 1 items.".to_owned()
+                )
+                .with_span(ModuleSectionId::Function, 0x18..0x28),
+                Diagnostic::new(
+                    Severity::Warning,
+                    LINT_UNUSED_IMPORT_FUNCTION,
+                    "The imported function \"std::io::print\" is never used.".to_owned()
+                )
+                .with_span(ModuleSectionId::ImportFunction, 0x08..0x18),
+                Diagnostic::new(
+                    Severity::Warning,
+                    LINT_UNUSED_PRIVATE_FUNCTION,
+                    "The private function \"app::foo\" is never referenced.".to_owned()
+                )
+                .with_span(ModuleSectionId::FunctionName, 0x08..0x18)
+                .with_related(
+                    ModuleSectionId::Function,
+                    0x08..0x18,
+                    "The function is defined here.".to_owned()
+                ),
+                Diagnostic::new(
+                    Severity::Warning,
+                    LINT_EMPTY_OPTIONAL_SECTION,
+                    "The optional section ReadOnlyData is empty.".to_owned()
+                )
+                .with_span(ModuleSectionId::ReadOnlyData, 0..8),
+            ]
+        );
+
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "\
+warning[unused_private_function]: The private function \"app::foo\" is never referenced.
+  --> FunctionName 0x0008..0x0018
+  note: The function is defined here.
+  --> Function 0x0008..0x0018"
+        );
+
+        let config = LintConfig {
+            allowed_codes: vec![LINT_UNUSED_IMPORT_FUNCTION, LINT_EMPTY_OPTIONAL_SECTION],
+            warnings_as_errors: true,
+        };
+
+        let codes_and_severities = image_lint(&image, &config)
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.severity))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            codes_and_severities,
+            vec![
+                (LINT_INVALID_INDEX, Severity::Error),
+                (LINT_UNUSED_PRIVATE_FUNCTION, Severity::Error),
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Estimates the memory which the runtime allocates for a module, so that
+// the embedders on constrained devices can reject the images which won't fit
+// before loading them.
+//
+// The memory consists of:
+//
+// - the shared part: the code and the read-only data, they are loaded once
+//   and shared by all threads.
+// - the per-thread part: the read-write data is cloned for each thread, and
+//   the uninitialized data (".bss") is allocated for each thread.
+//
+// The stack is not included since its size depends on the call depth,
+// the largest "local variable area" of the functions and blocks is reported
+// instead, the runtime can multiply it by the expected call depth.
+
+use crate::module_image::ModuleImage;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MemoryEstimate {
+    // The length of the bytecode of all functions, in bytes.
+    pub code_bytes: usize,
+
+    // The size of the data area (the paddings are included) of the read-only data section.
+    pub read_only_data_bytes: usize,
+
+    // The size of the data area of the read-write data section,
+    // it is cloned for each thread.
+    pub read_write_data_bytes_per_thread: usize,
+
+    // The size of the uninitialized data (i.e., the end of the last data item),
+    // it is allocated for each thread.
+    pub uninit_data_bytes_per_thread: usize,
+
+    // The maximum `allocated_bytes` of the local variable lists, i.e., the size
+    // of the largest "local variable area" (the arguments are included) of a frame.
+    pub max_local_variable_bytes: usize,
+
+    // The maximum amount of local variables (the arguments are included) of a frame.
+    pub max_local_variable_count: usize,
+}
+
+impl MemoryEstimate {
+    /// Returns the total size of the code and the data when the module
+    /// runs with the specified amount of threads, the stack is not included.
+    pub fn total_bytes(&self, thread_count: usize) -> usize {
+        self.code_bytes
+            + self.read_only_data_bytes
+            + (self.read_write_data_bytes_per_thread + self.uninit_data_bytes_per_thread)
+                * thread_count
+    }
+}
+
+pub fn estimate_runtime_memory(image: &ModuleImage) -> MemoryEstimate {
+    let local_variable_section = image.get_local_variable_section();
+
+    MemoryEstimate {
+        code_bytes: image
+            .get_function_section()
+            .items
+            .iter()
+            .map(|item| item.code_length as usize)
+            .sum(),
+        read_only_data_bytes: image
+            .get_optional_read_only_data_section()
+            .map_or(0, |section| section.datas_data.len()),
+        read_write_data_bytes_per_thread: image
+            .get_optional_read_write_data_section()
+            .map_or(0, |section| section.datas_data.len()),
+        uninit_data_bytes_per_thread: image
+            .get_optional_uninit_data_section()
+            .and_then(|section| {
+                section
+                    .items
+                    .iter()
+                    .map(|item| (item.data_offset + item.data_length) as usize)
+                    .max()
+            })
+            .unwrap_or(0),
+        max_local_variable_bytes: local_variable_section
+            .lists
+            .iter()
+            .map(|list| list.allocated_bytes as usize)
+            .max()
+            .unwrap_or(0),
+        max_local_variable_count: local_variable_section
+            .lists
+            .iter()
+            .map(|list| list.list_item_count as usize)
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{ReadOnlyDataEntry, ReadWriteDataEntry, UninitDataEntry},
+        memory_estimate::{estimate_runtime_memory, MemoryEstimate},
+        module_image::ModuleImage,
+        utils::helper_build_module_binary_with_single_function_and_data,
+    };
+
+    #[test]
+    fn test_estimate_runtime_memory() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end)
+            .to_bytes();
+
+        let image_binary = helper_build_module_binary_with_single_function_and_data(
+            &[OperandDataType::I32],
+            &[],
+            &[OperandDataType::I64, OperandDataType::I32],
+            code.clone(),
+            &[ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1)],
+            &[
+                ReadWriteDataEntry::from_i32(11),
+                ReadWriteDataEntry::from_i64(13),
+            ],
+            &[
+                UninitDataEntry::from_i64(),
+                UninitDataEntry::from_bytes(12, 4),
+            ],
+        );
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let memory_estimate = estimate_runtime_memory(&module_image);
+        assert_eq!(
+            memory_estimate,
+            MemoryEstimate {
+                code_bytes: code.len(),
+                // the data area is padded to 4 bytes
+                read_only_data_bytes: 8,
+                // the i32 is padded to 8 bytes
+                read_write_data_bytes_per_thread: 16,
+                uninit_data_bytes_per_thread: 20,
+                max_local_variable_bytes: 24,
+                max_local_variable_count: 3,
+            }
+        );
+
+        assert_eq!(
+            memory_estimate.total_bytes(2),
+            code.len() + 8 + (16 + 20) * 2
+        );
+    }
+}
@@ -15,7 +15,13 @@
 //   - Uninitialized Data: Memory allocated but not initialized.
 // - Import/Export Sections: Define imported and exported functions and data.
 // - Relocation Section: Contains relocation information for linking.
+// - Export Hash Section: Contains the ABI hashes of the public functions and data.
+// - Function Hash Section: Contains the content hashes of the functions.
+// - Debug Link Section: References the companion debug file.
+// - License Section: Records the licenses of the module and its external libraries.
+// - Patch Slot Section: Reserves the regions which are filled after writing.
 // - External Library/Function Sections: Define external dependencies.
+// - Initializer Section: Declares the constructors and finalizers.
 // - Property Section: Contains metadata about the module.
 //
 // A minimal module requires only the following sections:
@@ -28,7 +34,14 @@
 // - Data Sections (Read-Only, Read-Write, Uninitialized)
 // - Import/Export Sections (for linking and debugging)
 // - Relocation Section (for linking)
+// - Export Hash Section (for checking the compatibility when loading)
+// - Function Hash Section (for the AOT/JIT code caches)
+// - Debug Link Section (for the images whose debug sections are split out)
+// - License Section (for the license compliance scanning)
+// - Patch Slot Section (for the signatures, hashes and timestamps, see `patch_slot`)
 // - External Library/Function Sections (for linking)
+// - Initializer Section
+// - Custom Sections (defined outside this crate, see `section_registry`)
 //
 // Applications consist of one or more modules. When linked, all imports are resolved, and additional sections are created:
 // - Function Index Section
@@ -37,8 +50,10 @@
 //
 // Optional sections for applications include:
 // - Data Index Section
+// - Initialization Dependency Section
 // - Unified External Library/Function/Type Sections
 // - External Function Index Section
+// - Lazy Binding Section
 
 // The binary layout of a module image file:
 //
@@ -66,32 +81,87 @@
 // | Section Data 1                                       |
 // | ...                                                  |
 // |------------------------------------------------------|
+//
+// Optional trailer:
+//
+// An image may end with a trailer, which is used to detect truncated
+// (e.g., partially downloaded) and corrupted images before any section is parsed.
+// The trailer is indicated by the extra header, so that the truncation can
+// be detected even when the trailer itself is lost:
+//
+// |-------------------------------------------------------------|
+// | Image Flags (u32)            | Reserved (u32)               | 8 bytes, the extra header, offset=16
+// |-------------------------------------------------------------|
+// | ... Body ...                                                |
+// |-------------------------------------------------------------|
+// | Trailer Magic Number (u64)                                  | 8 bytes
+// | Total Image Length (u32)     | Checksum (u32)               | 8 bytes
+// |-------------------------------------------------------------|
+//
+// - Bit 0 of the image flags indicates the existence of the trailer.
+// - The total image length includes the trailer itself.
+// - The checksum is the CRC-32 (IEEE 802.3) of all bytes before the trailer.
+//
+// The readers which do not recognize the extra header just skip it,
+// and the trailer is outside of the section data area.
+//
+// Bit 1 of the image flags indicates that some sections are encrypted,
+// the encryption metadata follows the image flags in the extra header,
+// see `image_encryption`.
 
-use anc_isa::{IMAGE_FORMAT_MAJOR_VERSION, IMAGE_FORMAT_MINOR_VERSION};
+use std::{io::Write, mem::offset_of, time::Instant};
+
+use anc_isa::{
+    DataSectionType, ExternalLibraryDependencyType, MemoryDataType, IMAGE_FORMAT_MAJOR_VERSION,
+    IMAGE_FORMAT_MINOR_VERSION,
+};
 
 use crate::{
     common_sections::{
-        data_name_section::DataNameSection, external_function_section::ExternalFunctionSection,
-        external_library_section::ExternalLibrarySection,
-        function_name_section::FunctionNameSection, function_section::FunctionSection,
-        import_data_section::ImportDataSection, import_function_section::ImportFunctionSection,
-        import_module_section::ImportModuleSection, local_variable_section::LocalVariableSection,
-        property_section::PropertySection, read_only_data_section::ReadOnlyDataSection,
-        read_write_data_section::ReadWriteDataSection, relocate_section::RelocateSection,
-        type_section::TypeSection, uninit_data_section::UninitDataSection,
+        data_name_section::{DataNameItem, DataNameSection},
+        debug_link_section::DebugLinkSection,
+        export_hash_section::{ExportHashItem, ExportHashSection},
+        external_function_section::{ExternalFunctionItem, ExternalFunctionSection},
+        external_library_section::{ExternalLibraryItem, ExternalLibrarySection},
+        function_hash_section::FunctionHashSection,
+        function_name_section::{FunctionNameItem, FunctionNameSection},
+        function_section::{FunctionItem, FunctionSection},
+        import_data_section::{ImportDataItem, ImportDataSection},
+        import_function_section::{ImportFunctionItem, ImportFunctionSection},
+        import_module_section::{ImportModuleItem, ImportModuleSection},
+        initializer_section::{InitializerItem, InitializerSection},
+        license_section::{LicenseItem, LicenseSection},
+        local_variable_section::{LocalVariableList, LocalVariableSection},
+        patch_slot_section::PatchSlotSection,
+        property_section::PropertySection,
+        read_only_data_section::{DataItem as ReadOnlyDataItem, ReadOnlyDataSection},
+        read_write_data_section::{DataItem as ReadWriteDataItem, ReadWriteDataSection},
+        relocate_section::{RelocateItem, RelocateList, RelocateSection},
+        type_section::{TypeItem, TypeSection},
+        uninit_data_section::{DataItem as UninitDataItem, UninitDataSection},
     },
     datatableaccess::{
-        read_section_with_table_and_data_area, write_section_with_table_and_data_area,
+        check_section_with_table, read_section_with_table_and_data_area,
+        write_section_with_table_and_data_area,
     },
+    io_observer::{ImageIoObserver, WriteProgress},
     linking_sections::{
-        data_index_section::DataIndexSection, entry_point_section::EntryPointSection,
+        data_index_section::{DataIndexItem, DataIndexSection},
+        entry_point_section::{EntryPointItem, EntryPointSection},
         external_function_index_section::ExternalFunctionIndexSection,
-        function_index_section::FunctionIndexSection, linking_module_section::LinkingModuleSection,
+        function_index_section::FunctionIndexSection,
+        initialization_dependency_section::{
+            InitializationDependencyItem, InitializationDependencySection,
+        },
+        lazy_binding_section::LazyBindingSection,
+        linking_module_section::{LinkingModuleItem, LinkingModuleSection},
         unified_external_function_section::UnifiedExternalFunctionSection,
-        unified_external_library_section::UnifiedExternalLibrarySection,
+        unified_external_library_section::{
+            ExternalLibraryItem as UnifiedLibraryItem, UnifiedExternalLibrarySection,
+        },
         unified_external_type_section::UnifiedExternalTypeSection,
     },
-    ImageError, ImageErrorType,
+    ImageError, ImageErrorType, SectionReadError,
 };
 
 // Each record in the table must be multiple of this value.
@@ -109,12 +179,32 @@ pub const IMAGE_FILE_MAGIC_NUMBER: &[u8; 8] = b"ancmod\0\0";
 pub const BASE_MODULE_HEADER_LENGTH: usize = 16;
 pub const BASE_SECTION_HEADER_LENGTH: usize = 8;
 
+// The magic number of the image trailer, "ancend" stands for the "end of module image".
+pub const IMAGE_TRAILER_MAGIC_NUMBER: &[u8; 8] = b"ancend\0\0";
+pub const IMAGE_TRAILER_LENGTH: usize = 16;
+
+// The flag in the extra header indicating that the image ends with a trailer.
+pub const IMAGE_FLAG_HAS_TRAILER: u32 = 1;
+
+// The flag in the extra header indicating that some sections are encrypted.
+pub const IMAGE_FLAG_ENCRYPTED: u32 = 2;
+
+// The flag in the extra header indicating that some sections are compressed.
+pub const IMAGE_FLAG_COMPRESSED: u32 = 4;
+
+// The length of the extra header which contains the image flags.
+const IMAGE_FLAGS_EXTRA_HEADER_LENGTH: u16 = 8;
+
 // Represents a module image, including its type, section items, and section data.
 #[derive(Debug, PartialEq)]
 pub struct ModuleImage<'a> {
     pub image_type: ImageType, // Type of the image (e.g., Application, SharedModule, ObjectFile).
     pub items: &'a [ModuleSectionItem], // Section metadata.
     pub sections_data: &'a [u8], // Raw section data.
+
+    // Whether the section table is in ascending order of the section ids,
+    // it is checked once when the image is created, see `get_section_index_by_id`.
+    is_table_sorted: bool,
 }
 
 // Represents a single section item in the module, including its ID, offset, and length.
@@ -151,6 +241,11 @@ pub enum ModuleSectionId {
     FunctionName = 0x0030, // Exported functions.
     DataName,              // Exported data.
     Relocate,              // Relocation information.
+    ExportHash,            // ABI hashes of the public functions and data.
+    FunctionHash,          // Content hashes of the functions.
+    DebugLink,             // Reference to the companion debug file.
+    License,               // Licenses of the module and its external libraries.
+    PatchSlot,             // Placeholder regions which are filled after writing.
 
     // Optional sections for linking
     ImportModule = 0x0040, // Imported modules.
@@ -159,6 +254,9 @@ pub enum ModuleSectionId {
     ExternalLibrary,       // External libraries.
     ExternalFunction,      // External functions.
 
+    // Optional sections for initialization
+    Initializer = 0x0050, // Constructors and finalizers.
+
     // Essential sections for applications
     EntryPoint = 0x0080, // Entry points.
     FunctionIndex,       // Function index mapping.
@@ -166,10 +264,160 @@ pub enum ModuleSectionId {
 
     // Optional sections for applications
     DataIndex = 0x0090,           // Data index mapping.
+    InitializationDependency,     // Initialization dependencies between modules.
     UnifiedExternalType = 0x00a0, // Unified external types.
     UnifiedExternalLibrary,       // Unified external libraries.
     UnifiedExternalFunction,      // Unified external functions.
     ExternalFunctionIndex,        // Mapping of external functions to unified external functions.
+    LazyBinding,                  // Thunks of the lazily bound external functions.
+
+    // Optional sections defined outside this crate, see `section_registry`.
+    Custom0 = 0x00f0,
+    Custom1,
+    Custom2,
+    Custom3,
+    Custom4,
+    Custom5,
+    Custom6,
+    Custom7,
+}
+
+impl ModuleSectionId {
+    /// Returns all section ids, in ascending order.
+    pub fn all() -> &'static [ModuleSectionId] {
+        &[
+            ModuleSectionId::Property,
+            ModuleSectionId::Type,
+            ModuleSectionId::LocalVariable,
+            ModuleSectionId::Function,
+            //
+            ModuleSectionId::ReadOnlyData,
+            ModuleSectionId::ReadWriteData,
+            ModuleSectionId::UninitData,
+            //
+            ModuleSectionId::FunctionName,
+            ModuleSectionId::DataName,
+            ModuleSectionId::Relocate,
+            ModuleSectionId::ExportHash,
+            ModuleSectionId::FunctionHash,
+            ModuleSectionId::DebugLink,
+            ModuleSectionId::License,
+            ModuleSectionId::PatchSlot,
+            //
+            ModuleSectionId::ImportModule,
+            ModuleSectionId::ImportFunction,
+            ModuleSectionId::ImportData,
+            ModuleSectionId::ExternalLibrary,
+            ModuleSectionId::ExternalFunction,
+            //
+            ModuleSectionId::Initializer,
+            //
+            ModuleSectionId::EntryPoint,
+            ModuleSectionId::FunctionIndex,
+            ModuleSectionId::LinkingModule,
+            //
+            ModuleSectionId::DataIndex,
+            ModuleSectionId::InitializationDependency,
+            ModuleSectionId::UnifiedExternalType,
+            ModuleSectionId::UnifiedExternalLibrary,
+            ModuleSectionId::UnifiedExternalFunction,
+            ModuleSectionId::ExternalFunctionIndex,
+            ModuleSectionId::LazyBinding,
+            //
+            ModuleSectionId::Custom0,
+            ModuleSectionId::Custom1,
+            ModuleSectionId::Custom2,
+            ModuleSectionId::Custom3,
+            ModuleSectionId::Custom4,
+            ModuleSectionId::Custom5,
+            ModuleSectionId::Custom6,
+            ModuleSectionId::Custom7,
+        ]
+    }
+
+    /// Returns the section id of the given number, or `None` if the number
+    /// is not a known section id (e.g., a section defined by a newer version of the format).
+    pub fn from_u32(value: u32) -> Option<ModuleSectionId> {
+        ModuleSectionId::all()
+            .iter()
+            .find(|section_id| **section_id as u32 == value)
+            .copied()
+    }
+
+    /// Returns `true` if the section is required by the module images, i.e., the
+    /// property, type, local variable and function sections, and the entry point,
+    /// function index and linking module sections for applications.
+    pub fn is_essential(&self) -> bool {
+        matches!(
+            self,
+            ModuleSectionId::Property
+                | ModuleSectionId::Type
+                | ModuleSectionId::LocalVariable
+                | ModuleSectionId::Function
+                | ModuleSectionId::EntryPoint
+                | ModuleSectionId::FunctionIndex
+                | ModuleSectionId::LinkingModule
+        )
+    }
+
+    /// Returns `true` if the section is generated by the linker,
+    /// i.e., it exists in the application images only.
+    pub fn is_linking(&self) -> bool {
+        let value = *self as u32;
+        value >= (ModuleSectionId::EntryPoint as u32)
+            && value <= (ModuleSectionId::LazyBinding as u32)
+    }
+
+    /// Returns `true` if the section is defined outside this crate.
+    pub fn is_custom(&self) -> bool {
+        (*self as u32) >= (ModuleSectionId::Custom0 as u32)
+    }
+
+    /// Returns the name of the section, e.g. "local_variable",
+    /// it is used by tools for labeling sections.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModuleSectionId::Property => "property",
+            ModuleSectionId::Type => "type",
+            ModuleSectionId::LocalVariable => "local_variable",
+            ModuleSectionId::Function => "function",
+            ModuleSectionId::ReadOnlyData => "read_only_data",
+            ModuleSectionId::ReadWriteData => "read_write_data",
+            ModuleSectionId::UninitData => "uninit_data",
+            ModuleSectionId::FunctionName => "function_name",
+            ModuleSectionId::DataName => "data_name",
+            ModuleSectionId::Relocate => "relocate",
+            ModuleSectionId::ExportHash => "export_hash",
+            ModuleSectionId::FunctionHash => "function_hash",
+            ModuleSectionId::DebugLink => "debug_link",
+            ModuleSectionId::License => "license",
+            ModuleSectionId::PatchSlot => "patch_slot",
+            ModuleSectionId::ImportModule => "import_module",
+            ModuleSectionId::ImportFunction => "import_function",
+            ModuleSectionId::ImportData => "import_data",
+            ModuleSectionId::ExternalLibrary => "external_library",
+            ModuleSectionId::ExternalFunction => "external_function",
+            ModuleSectionId::Initializer => "initializer",
+            ModuleSectionId::EntryPoint => "entry_point",
+            ModuleSectionId::FunctionIndex => "function_index",
+            ModuleSectionId::LinkingModule => "linking_module",
+            ModuleSectionId::DataIndex => "data_index",
+            ModuleSectionId::InitializationDependency => "initialization_dependency",
+            ModuleSectionId::UnifiedExternalType => "unified_external_type",
+            ModuleSectionId::UnifiedExternalLibrary => "unified_external_library",
+            ModuleSectionId::UnifiedExternalFunction => "unified_external_function",
+            ModuleSectionId::ExternalFunctionIndex => "external_function_index",
+            ModuleSectionId::LazyBinding => "lazy_binding",
+            ModuleSectionId::Custom0 => "custom0",
+            ModuleSectionId::Custom1 => "custom1",
+            ModuleSectionId::Custom2 => "custom2",
+            ModuleSectionId::Custom3 => "custom3",
+            ModuleSectionId::Custom4 => "custom4",
+            ModuleSectionId::Custom5 => "custom5",
+            ModuleSectionId::Custom6 => "custom6",
+            ModuleSectionId::Custom7 => "custom7",
+        }
+    }
 }
 
 // Represents the type of a module image (e.g., Application, SharedModule, ObjectFile).
@@ -179,6 +427,7 @@ pub enum ImageType {
     Application,  // `*.anca`
     SharedModule, // `*.ancm`
     ObjectFile,   // `*.anco`
+    DebugInfo,    // `*.ancd`, the companion debug file, see `debug_info`.
 }
 
 // Represents the visibility of functions and data between shared modules.
@@ -187,11 +436,59 @@ pub enum ImageType {
 pub enum Visibility {
     Private, // Accessible only within the same module.
     Public,  // Accessible across different modules.
+    Package, // Accessible only by the modules of the same package.
 }
 
-// Represents the type of relocation required for linking.
+// The package of a module is assigned by the build system (e.g., the modules
+// which are built from the same project), it is not recorded in the image,
+// so the linker checks the package-private symbols with the package names
+// supplied by the build system, see `link_hook::PackageVisibilityCheck`.
+impl Visibility {
+    /// Returns `true` if the function or data can be imported by other modules,
+    /// i.e., it is public or package-private.
+    pub fn is_exported(&self) -> bool {
+        matches!(self, Visibility::Public | Visibility::Package)
+    }
+
+    /// Returns `true` if the function or data can be imported by a module,
+    /// `same_package` indicates whether the importing module and the exporting
+    /// module belong to the same package.
+    pub fn is_accessible(&self, same_package: bool) -> bool {
+        match self {
+            Visibility::Private => false,
+            Visibility::Public => true,
+            Visibility::Package => same_package,
+        }
+    }
+}
+
+// Represents the type of the exported items, i.e., the items in the export hash section.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExportType {
+    Function,
+    Data,
+}
+
+// Represents the type of the functions in the initializer section.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InitializerType {
+    Constructor, // Runs before the entry point.
+    Finalizer,   // Runs at the shutdown.
+}
+
+// Represents the target of the items in the license section.
 #[repr(u8)]
 #[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LicenseTargetType {
+    Module,          // The module itself.
+    ExternalLibrary, // An external library of the module.
+}
+
+// Represents the type of relocation required for linking.
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum RelocateType {
     TypeIndex,              // Relocation for type indices.
     LocalVariableListIndex, // Relocation for local variable list indices.
@@ -200,6 +497,146 @@ pub enum RelocateType {
     DataPublicIndex,        // Relocation for public data indices.
 }
 
+// The following `TryFrom` implementations decode the raw values of the enum fields,
+// they are used to check the image before the sections are cast into the item
+// structs, since an unexpected value would be an invalid enum value (i.e.,
+// undefined behavior) otherwise. See `ModuleImage::read`.
+//
+// The `section_id` of the `ImageErrorType::InvalidEnumValue` is `None`,
+// it is filled by the caller.
+
+impl TryFrom<u32> for ModuleSectionId {
+    type Error = ImageError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        ModuleSectionId::from_u32(value)
+            .ok_or_else(|| invalid_enum_value_error("ModuleSectionId", value))
+    }
+}
+
+impl TryFrom<u16> for ImageType {
+    type Error = ImageError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ImageType::Application),
+            1 => Ok(ImageType::SharedModule),
+            2 => Ok(ImageType::ObjectFile),
+            3 => Ok(ImageType::DebugInfo),
+            _ => Err(invalid_enum_value_error("ImageType", value as u32)),
+        }
+    }
+}
+
+impl TryFrom<u8> for Visibility {
+    type Error = ImageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Visibility::Private),
+            1 => Ok(Visibility::Public),
+            2 => Ok(Visibility::Package),
+            _ => Err(invalid_enum_value_error("Visibility", value as u32)),
+        }
+    }
+}
+
+impl TryFrom<u8> for ExportType {
+    type Error = ImageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ExportType::Function),
+            1 => Ok(ExportType::Data),
+            _ => Err(invalid_enum_value_error("ExportType", value as u32)),
+        }
+    }
+}
+
+impl TryFrom<u8> for InitializerType {
+    type Error = ImageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(InitializerType::Constructor),
+            1 => Ok(InitializerType::Finalizer),
+            _ => Err(invalid_enum_value_error("InitializerType", value as u32)),
+        }
+    }
+}
+
+impl TryFrom<u8> for LicenseTargetType {
+    type Error = ImageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LicenseTargetType::Module),
+            1 => Ok(LicenseTargetType::ExternalLibrary),
+            _ => Err(invalid_enum_value_error("LicenseTargetType", value as u32)),
+        }
+    }
+}
+
+impl TryFrom<u8> for RelocateType {
+    type Error = ImageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RelocateType::TypeIndex),
+            1 => Ok(RelocateType::LocalVariableListIndex),
+            2 => Ok(RelocateType::FunctionPublicIndex),
+            3 => Ok(RelocateType::ExternalFunctionIndex),
+            4 => Ok(RelocateType::DataPublicIndex),
+            _ => Err(invalid_enum_value_error("RelocateType", value as u32)),
+        }
+    }
+}
+
+fn invalid_enum_value_error(enum_name: &'static str, value: u32) -> ImageError {
+    ImageError::new(ImageErrorType::InvalidEnumValue {
+        section_id: None,
+        enum_name,
+        value,
+    })
+}
+
+// The enums of the ISA crate cannot implement `TryFrom<u8>` in this crate,
+// their raw values are checked against the values of the variants instead.
+trait IsaEnumValues {
+    const NAME: &'static str;
+    const VALUES: &'static [u8];
+}
+
+impl IsaEnumValues for MemoryDataType {
+    const NAME: &'static str = "MemoryDataType";
+    const VALUES: &'static [u8] = &[
+        MemoryDataType::I32 as u8,
+        MemoryDataType::I64 as u8,
+        MemoryDataType::F32 as u8,
+        MemoryDataType::F64 as u8,
+        MemoryDataType::Bytes as u8,
+    ];
+}
+
+impl IsaEnumValues for DataSectionType {
+    const NAME: &'static str = "DataSectionType";
+    const VALUES: &'static [u8] = &[
+        DataSectionType::ReadOnly as u8,
+        DataSectionType::ReadWrite as u8,
+        DataSectionType::Uninit as u8,
+    ];
+}
+
+impl IsaEnumValues for ExternalLibraryDependencyType {
+    const NAME: &'static str = "ExternalLibraryDependencyType";
+    const VALUES: &'static [u8] = &[
+        ExternalLibraryDependencyType::Local as u8,
+        ExternalLibraryDependencyType::Remote as u8,
+        ExternalLibraryDependencyType::Share as u8,
+        ExternalLibraryDependencyType::Runtime as u8,
+    ];
+}
+
 // `RangeItem` is used for data index section and function index section.
 //
 // Note that one range item per module, e.g., consider the following items:
@@ -239,26 +676,90 @@ pub trait SectionEntry<'a> {
     where
         Self: Sized;
     fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()>;
+
+    /// Returns the length (in bytes) of the section data that `write` would produce.
+    ///
+    /// The default implementation performs a dry-run write which only counts
+    /// the bytes, sections with a cheaper way to calculate the length
+    /// can override it.
+    fn estimated_size(&'a self) -> usize {
+        let mut counter = ByteCounter::default();
+        self.write(&mut counter).unwrap();
+        counter.count
+    }
+}
+
+// A writer that forwards the data to the inner writer and counts
+// the number of bytes accepted by it.
+struct CountingWriter<'w> {
+    writer: &'w mut dyn std::io::Write,
+    count: usize,
+}
+
+impl std::io::Write for CountingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let length = self.writer.write(buf)?;
+        self.count += length;
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// A writer that discards the data and only counts the number of bytes written.
+#[derive(Default)]
+struct ByteCounter {
+    count: usize,
+}
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 impl<'a> ModuleImage<'a> {
+    pub fn new(
+        image_type: ImageType,
+        items: &'a [ModuleSectionItem],
+        sections_data: &'a [u8],
+    ) -> Self {
+        let is_table_sorted = items
+            .windows(2)
+            .all(|pair| (pair[0].id as u32) < (pair[1].id as u32));
+
+        Self {
+            image_type,
+            items,
+            sections_data,
+            is_table_sorted,
+        }
+    }
+
     pub fn read(image_binary: &'a [u8]) -> Result<Self, ImageError> {
+        if image_binary.len() < BASE_MODULE_HEADER_LENGTH {
+            return Err(ImageError::new(ImageErrorType::TruncatedImage));
+        }
+
         let magic_slice = &image_binary[0..8];
         if magic_slice != IMAGE_FILE_MAGIC_NUMBER {
             return Err(ImageError::new(ImageErrorType::InvalidImage));
         }
 
-        let ptr = image_binary.as_ptr();
-
-        let ptr_image_type = unsafe { ptr.offset(8) };
-        let image_type = unsafe { std::ptr::read(ptr_image_type as *const ImageType) };
+        let image_type =
+            ImageType::try_from(u16::from_le_bytes(image_binary[8..10].try_into().unwrap()))?;
 
-        let ptr_extra_header_length = unsafe { ptr.offset(10) };
-        let extra_header_length = unsafe { std::ptr::read(ptr_extra_header_length as *const u16) };
-
-        let ptr_declared_module_format_image_version = unsafe { ptr.offset(12) };
+        // The binary may be unaligned, so the numbers are not read by casting the pointer.
+        let extra_header_length = u16::from_le_bytes(image_binary[10..12].try_into().unwrap());
         let declared_module_image_version =
-            unsafe { std::ptr::read(ptr_declared_module_format_image_version as *const u32) };
+            u32::from_le_bytes(image_binary[12..16].try_into().unwrap());
 
         let supported_module_format_image_version =
             ((IMAGE_FORMAT_MAJOR_VERSION as u32) << 16) | (IMAGE_FORMAT_MINOR_VERSION as u32);
@@ -266,20 +767,221 @@ impl<'a> ModuleImage<'a> {
             return Err(ImageError::new(ImageErrorType::RequireNewVersionRuntime));
         }
 
-        let image_body =
-            &image_binary[(BASE_MODULE_HEADER_LENGTH + extra_header_length as usize)..];
+        let body_start = BASE_MODULE_HEADER_LENGTH + extra_header_length as usize;
+        let image_flags = if extra_header_length >= 4 {
+            image_binary
+                .get(BASE_MODULE_HEADER_LENGTH..(BASE_MODULE_HEADER_LENGTH + 4))
+                .map(|data| u32::from_le_bytes(data.try_into().unwrap()))
+                .ok_or(ImageError::new(ImageErrorType::TruncatedImage))?
+        } else {
+            0
+        };
+
+        if image_flags & IMAGE_FLAG_ENCRYPTED != 0 {
+            return Err(ImageError::new(ImageErrorType::EncryptedImage));
+        }
+
+        if image_flags & IMAGE_FLAG_COMPRESSED != 0 {
+            return Err(ImageError::new(ImageErrorType::CompressedImage));
+        }
+
+        let body_end = if image_flags & IMAGE_FLAG_HAS_TRAILER != 0 {
+            verify_trailer(image_binary, body_start)?
+        } else {
+            image_binary.len()
+        };
+
+        let image_body = image_binary
+            .get(body_start..body_end)
+            .ok_or(ImageError::new(ImageErrorType::TruncatedImage))?;
+
+        check_section_table(image_body)?;
 
         let (items, sections_data) =
             read_section_with_table_and_data_area::<ModuleSectionItem>(image_body);
 
-        Ok(Self {
-            image_type,
-            items,
-            sections_data,
+        let module_image = Self::new(image_type, items, sections_data);
+        module_image.check_section_enum_values()?;
+        Ok(module_image)
+    }
+
+    // Checks the raw values of the enum fields of the sections.
+    //
+    // The sections whose tables are malformed are skipped, they are
+    // reported by the `try_get_optional_*` functions.
+    fn check_section_enum_values(&'a self) -> Result<(), ImageError> {
+        self.check_table_enum_field::<FunctionNameItem, Visibility>(
+            ModuleSectionId::FunctionName,
+            offset_of!(FunctionNameItem, visibility),
+        )?;
+        self.check_table_enum_field::<DataNameItem, Visibility>(
+            ModuleSectionId::DataName,
+            offset_of!(DataNameItem, visibility),
+        )?;
+        self.check_table_enum_field::<ExportHashItem, ExportType>(
+            ModuleSectionId::ExportHash,
+            offset_of!(ExportHashItem, export_type),
+        )?;
+        self.check_table_enum_field::<InitializerItem, InitializerType>(
+            ModuleSectionId::Initializer,
+            offset_of!(InitializerItem, initializer_type),
+        )?;
+        self.check_table_enum_field::<LicenseItem, LicenseTargetType>(
+            ModuleSectionId::License,
+            offset_of!(LicenseItem, target_type),
+        )?;
+
+        self.check_table_isa_enum_field::<ReadOnlyDataItem, MemoryDataType>(
+            ModuleSectionId::ReadOnlyData,
+            offset_of!(ReadOnlyDataItem, memory_data_type),
+        )?;
+        self.check_table_isa_enum_field::<ReadWriteDataItem, MemoryDataType>(
+            ModuleSectionId::ReadWriteData,
+            offset_of!(ReadWriteDataItem, memory_data_type),
+        )?;
+        self.check_table_isa_enum_field::<UninitDataItem, MemoryDataType>(
+            ModuleSectionId::UninitData,
+            offset_of!(UninitDataItem, memory_data_type),
+        )?;
+        self.check_table_isa_enum_field::<ImportDataItem, MemoryDataType>(
+            ModuleSectionId::ImportData,
+            offset_of!(ImportDataItem, memory_data_type),
+        )?;
+        self.check_table_isa_enum_field::<ImportDataItem, DataSectionType>(
+            ModuleSectionId::ImportData,
+            offset_of!(ImportDataItem, data_section_type),
+        )?;
+        self.check_table_isa_enum_field::<DataNameItem, DataSectionType>(
+            ModuleSectionId::DataName,
+            offset_of!(DataNameItem, section_type),
+        )?;
+        self.check_table_isa_enum_field::<ExportHashItem, DataSectionType>(
+            ModuleSectionId::ExportHash,
+            offset_of!(ExportHashItem, section_type),
+        )?;
+        self.check_table_isa_enum_field::<InitializationDependencyItem, DataSectionType>(
+            ModuleSectionId::InitializationDependency,
+            offset_of!(InitializationDependencyItem, target_data_section_type),
+        )?;
+        self.check_table_isa_enum_field::<ExternalLibraryItem, ExternalLibraryDependencyType>(
+            ModuleSectionId::ExternalLibrary,
+            offset_of!(ExternalLibraryItem, external_library_dependent_type),
+        )?;
+        self.check_table_isa_enum_field::<UnifiedLibraryItem, ExternalLibraryDependencyType>(
+            ModuleSectionId::UnifiedExternalLibrary,
+            offset_of!(UnifiedLibraryItem, external_library_dependent_type),
+        )?;
+
+        self.check_data_index_section_types()?;
+        self.check_relocate_types()
+    }
+
+    // Checks the raw values of an `u8` enum field of the table items of a section,
+    // `I` is the type of the table item, `E` is the type of the field.
+    fn check_table_enum_field<I, E: TryFrom<u8, Error = ImageError>>(
+        &'a self,
+        section_id: ModuleSectionId,
+        field_offset: usize,
+    ) -> Result<(), ImageError> {
+        self.check_table_field::<I>(section_id, field_offset, check_enum_value::<E>)
+    }
+
+    // The same as `check_table_enum_field`, but for the enums of the ISA crate.
+    fn check_table_isa_enum_field<I, E: IsaEnumValues>(
+        &'a self,
+        section_id: ModuleSectionId,
+        field_offset: usize,
+    ) -> Result<(), ImageError> {
+        self.check_table_field::<I>(section_id, field_offset, check_isa_enum_value::<E>)
+    }
+
+    fn check_table_field<I>(
+        &'a self,
+        section_id: ModuleSectionId,
+        field_offset: usize,
+        check_value: fn(ModuleSectionId, u8) -> Result<(), ImageError>,
+    ) -> Result<(), ImageError> {
+        let Some(section_data) = self.get_section_data_by_id(section_id) else {
+            return Ok(());
+        };
+
+        if !check_section_with_table::<I>(section_data) {
+            return Ok(());
+        }
+
+        let item_count = u32::from_le_bytes(section_data[0..4].try_into().unwrap()) as usize;
+        for idx in 0..item_count {
+            let position = BASE_SECTION_HEADER_LENGTH + idx * size_of::<I>() + field_offset;
+            check_value(section_id, section_data[position])?;
+        }
+
+        Ok(())
+    }
+
+    // Checks the data section types of the data index items,
+    // which are located in the second table of the section.
+    fn check_data_index_section_types(&'a self) -> Result<(), ImageError> {
+        let Some(section_data) = self.get_section_data_by_id(ModuleSectionId::DataIndex) else {
+            return Ok(());
+        };
+
+        if !check_section_with_table::<RangeItem>(section_data) {
+            return Ok(());
+        }
+
+        let range_count = u32::from_le_bytes(section_data[0..4].try_into().unwrap()) as usize;
+        let items_start = BASE_SECTION_HEADER_LENGTH + range_count * size_of::<RangeItem>();
+        for item_data in section_data[items_start..].chunks_exact(size_of::<DataIndexItem>()) {
+            check_isa_enum_value::<DataSectionType>(
+                ModuleSectionId::DataIndex,
+                item_data[offset_of!(DataIndexItem, target_data_section_type)],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Checks the relocate types of the relocate items, which are located in the data area.
+    fn check_relocate_types(&'a self) -> Result<(), ImageError> {
+        let Some(section_data) = self.get_section_data_by_id(ModuleSectionId::Relocate) else {
+            return Ok(());
+        };
+
+        if !check_section_with_table::<RelocateList>(section_data) {
+            return Ok(());
+        }
+
+        // The relocate lists contain only `u32` fields, they can be read directly.
+        let section = RelocateSection::read(section_data);
+        for list in section.lists {
+            for idx in 0..list.list_item_count as usize {
+                let position = list.list_offset as usize
+                    + idx * size_of::<RelocateItem>()
+                    + offset_of!(RelocateItem, relocate_type);
+                let Some(value) = section.list_data.get(position) else {
+                    // The malformed list is reported by `try_get_optional_relocate_section`.
+                    return Ok(());
+                };
+                check_enum_value::<RelocateType>(ModuleSectionId::Relocate, *value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the image.
+    ///
+    /// If the writer fails, the error contains the section being written
+    /// and the amount of bytes written before, see `ImageErrorType::Io`.
+    pub fn write(&'a self, writer: &mut dyn std::io::Write) -> Result<(), ImageError> {
+        let mut counting_writer = CountingWriter { writer, count: 0 };
+
+        self.write_unchecked(&mut counting_writer).map_err(|e| {
+            self.convert_write_error(e, counting_writer.count, BASE_MODULE_HEADER_LENGTH)
         })
     }
 
-    pub fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+    fn write_unchecked(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
         const EXTRA_HEADER_LENGTH: u16 = 0;
 
         writer.write_all(IMAGE_FILE_MAGIC_NUMBER)?;
@@ -291,61 +993,264 @@ impl<'a> ModuleImage<'a> {
         write_section_with_table_and_data_area(self.items, self.sections_data, writer)
     }
 
+    /// The same as `write`, but the image ends with a trailer which contains
+    /// the total image length and the checksum, see the layout at the top of this file.
+    pub fn write_with_trailer(&'a self, writer: &mut dyn std::io::Write) -> Result<(), ImageError> {
+        // The checksum covers all bytes before the trailer, so the image
+        // is built in memory first.
+        let mut image_binary: Vec<u8> = vec![];
+        image_binary.extend_from_slice(IMAGE_FILE_MAGIC_NUMBER);
+        image_binary.extend_from_slice(&(self.image_type as u16).to_le_bytes());
+        image_binary.extend_from_slice(&IMAGE_FLAGS_EXTRA_HEADER_LENGTH.to_le_bytes());
+        image_binary.extend_from_slice(&IMAGE_FORMAT_MINOR_VERSION.to_le_bytes());
+        image_binary.extend_from_slice(&IMAGE_FORMAT_MAJOR_VERSION.to_le_bytes());
+        image_binary.extend_from_slice(&IMAGE_FLAG_HAS_TRAILER.to_le_bytes());
+        image_binary.extend_from_slice(&0u32.to_le_bytes()); // reserved
+
+        // Writing to a `Vec<u8>` never fails.
+        write_section_with_table_and_data_area(self.items, self.sections_data, &mut image_binary)
+            .unwrap();
+
+        let total_length = (image_binary.len() + IMAGE_TRAILER_LENGTH) as u32;
+        let checksum = compute_crc32(&image_binary);
+
+        image_binary.extend_from_slice(IMAGE_TRAILER_MAGIC_NUMBER);
+        image_binary.extend_from_slice(&total_length.to_le_bytes());
+        image_binary.extend_from_slice(&checksum.to_le_bytes());
+
+        let mut counting_writer = CountingWriter { writer, count: 0 };
+        counting_writer.write_all(&image_binary).map_err(|e| {
+            self.convert_write_error(
+                e,
+                counting_writer.count,
+                BASE_MODULE_HEADER_LENGTH + IMAGE_FLAGS_EXTRA_HEADER_LENGTH as usize,
+            )
+        })
+    }
+
+    /// Writes an image which consists of the given serialized sections, in the given order.
+    ///
+    /// The section data is written as it is, there is no conversion from or
+    /// to the entries, e.g., for the tools which move sections between images.
+    /// The data which is not a multiple of 4 bytes is followed by the padding,
+    /// so that the next section is still 4-byte aligned.
+    ///
+    /// Returns `ImageErrorType::InvalidImage` if a section id occurs more than once.
+    pub fn compose(
+        image_type: ImageType,
+        sections: &[(ModuleSectionId, &[u8])],
+        writer: &mut dyn std::io::Write,
+    ) -> Result<(), ImageError> {
+        let mut items: Vec<ModuleSectionItem> = Vec::with_capacity(sections.len());
+        let mut next_offset: u32 = 0;
+
+        for (section_id, section_data) in sections {
+            if items.iter().any(|item| item.id == *section_id) {
+                return Err(ImageError::new(ImageErrorType::InvalidImage));
+            }
+
+            items.push(ModuleSectionItem::new(
+                *section_id,
+                next_offset,
+                section_data.len() as u32,
+            ));
+            next_offset += section_data.len().next_multiple_of(4) as u32;
+        }
+
+        // The section table is written with an empty data area,
+        // and the sections are written one by one after it.
+        let module_image = ModuleImage::new(image_type, &items, &[]);
+
+        let mut counting_writer = CountingWriter { writer, count: 0 };
+        module_image
+            .write_composed_unchecked(sections, &mut counting_writer)
+            .map_err(|e| {
+                module_image.convert_write_error(
+                    e,
+                    counting_writer.count,
+                    BASE_MODULE_HEADER_LENGTH,
+                )
+            })
+    }
+
+    fn write_composed_unchecked(
+        &'a self,
+        sections: &[(ModuleSectionId, &[u8])],
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        self.write_unchecked(writer)?;
+
+        for (_, section_data) in sections {
+            writer.write_all(section_data)?;
+            let padding = section_data.len().next_multiple_of(4) - section_data.len();
+            writer.write_all(&[0u8; 4][..padding])?;
+        }
+
+        Ok(())
+    }
+
+    // Adds the context to the IO error, i.e., the section which contains
+    // the failed position and the amount of bytes written.
+    fn convert_write_error(
+        &'a self,
+        error: std::io::Error,
+        bytes_written: usize,
+        header_length: usize,
+    ) -> ImageError {
+        let data_area_start =
+            header_length + BASE_SECTION_HEADER_LENGTH + std::mem::size_of_val(self.items);
+
+        let section_id = bytes_written
+            .checked_sub(data_area_start)
+            .and_then(|position| {
+                self.items.iter().find(|item| {
+                    (item.offset as usize) <= position
+                        && position < (item.offset + item.length) as usize
+                })
+            })
+            .map(|item| item.id);
+
+        ImageError::from_io_error(error, section_id, bytes_written)
+    }
+
+    /// Assembles the section table and the section data area from the section entries.
+    ///
+    /// Returns an error if a section entry fails to write, or if the size of
+    /// the written data does not match its `SectionEntry::estimated_size`.
     pub fn convert_from_section_entries(
         entries: &[&'a dyn SectionEntry<'a>],
-    ) -> (Vec<ModuleSectionItem>, Vec<u8>) {
+    ) -> Result<(Vec<ModuleSectionItem>, Vec<u8>), ImageError> {
+        let mut items: Vec<ModuleSectionItem> = vec![];
         let mut image_binary: Vec<u8> = vec![];
+        Self::convert_from_section_entries_into(entries, &mut items, &mut image_binary)?;
+        Ok((items, image_binary))
+    }
+
+    /// Same as `convert_from_section_entries`, but writes the result into the given buffers.
+    ///
+    /// The buffers are cleared first, their capacity is kept, so the caller
+    /// can reuse them across many modules without reallocating.
+    pub fn convert_from_section_entries_into(
+        entries: &[&'a dyn SectionEntry<'a>],
+        items: &mut Vec<ModuleSectionItem>,
+        image_binary: &mut Vec<u8>,
+    ) -> Result<(), ImageError> {
+        Self::convert_from_section_entries_into_with_observer(entries, items, image_binary, &mut ())
+    }
 
-        let mut data_increment_lengths: Vec<usize> = vec![];
+    /// Same as `convert_from_section_entries_into`, but notifies the observer
+    /// after each section is written.
+    pub fn convert_from_section_entries_into_with_observer(
+        entries: &[&'a dyn SectionEntry<'a>],
+        items: &mut Vec<ModuleSectionItem>,
+        image_binary: &mut Vec<u8>,
+        observer: &mut dyn ImageIoObserver,
+    ) -> Result<(), ImageError> {
+        items.clear();
+        image_binary.clear();
+        items.reserve(entries.len());
+
+        // Build the section table from the size of each entry first,
+        // so that the data area can be allocated once with the exact capacity.
+        let mut next_offset: u32 = 0;
 
         for entry in entries {
-            entry.write(&mut image_binary).unwrap();
-            data_increment_lengths.push(image_binary.len());
+            let length = entry.estimated_size() as u32;
+            items.push(ModuleSectionItem::new(entry.id(), next_offset, length));
+            next_offset += length;
         }
 
-        let mut offsets: Vec<usize> = vec![0];
-        offsets.extend(data_increment_lengths.iter());
-        offsets.pop();
+        image_binary.reserve_exact(next_offset as usize);
+
+        for (section_index, (entry, item)) in entries.iter().zip(items.iter()).enumerate() {
+            let start = Instant::now();
+            entry.write(image_binary).map_err(|error| {
+                ImageError::from_io_error(error, Some(item.id), image_binary.len())
+            })?;
+            let elapsed = start.elapsed();
+
+            // A wrong `estimated_size` would produce a section table with wrong offsets.
+            let written_size = image_binary.len() - item.offset as usize;
+            if written_size != item.length as usize {
+                return Err(ImageError::new(ImageErrorType::SectionSizeMismatch {
+                    section_id: item.id,
+                    estimated_size: item.length as usize,
+                    written_size,
+                }));
+            }
 
-        let lengths = data_increment_lengths
-            .iter()
-            .zip(offsets.iter())
-            .map(|(next, current)| next - current)
-            .collect::<Vec<usize>>();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                section = ?item.id,
+                length = item.length,
+                elapsed_us = elapsed.as_micros() as u64,
+                "section written"
+            );
+
+            observer.on_section_written(item.id, item.length as usize, elapsed);
+            observer.on_write_progress(WriteProgress {
+                sections_completed: section_index + 1,
+                section_count: entries.len(),
+                bytes_written: image_binary.len(),
+                total_bytes: next_offset as usize,
+            });
+        }
 
-        let items = entries
-            .iter()
-            .zip(offsets.iter().zip(lengths.iter()))
-            .map(|(entry, (offset, length))| {
-                ModuleSectionItem::new(entry.id(), *offset as u32, *length as u32)
-            })
-            .collect::<Vec<ModuleSectionItem>>();
+        Ok(())
+    }
 
-        (items, image_binary)
+    /// Returns an iterator over `(section id, offset, length)` of all sections,
+    /// in the order of the section table. The offset is relative to the section data area.
+    pub fn sections(&'a self) -> impl Iterator<Item = (ModuleSectionId, u32, u32)> + 'a {
+        self.items
+            .iter()
+            .map(|item| (item.id, item.offset, item.length))
     }
 
     pub fn get_section_index_by_id(&'a self, section_id: ModuleSectionId) -> Option<usize> {
-        self.items.iter().enumerate().find_map(|(idx, item)| {
-            if item.id == section_id {
-                Some(idx)
-            } else {
-                None
-            }
-        })
+        // The writer emits the section table in ascending order of the section ids,
+        // so the binary search is used for the sorted tables. The linear search is
+        // only for the tables which are not sorted (e.g., the images written by
+        // the older writers, or the ones assembled by hand).
+        if self.is_table_sorted {
+            self.items
+                .binary_search_by_key(&(section_id as u32), |item| item.id as u32)
+                .ok()
+        } else {
+            self.items.iter().position(|item| item.id == section_id)
+        }
     }
 
     fn get_section_data_by_id(&'a self, section_id: ModuleSectionId) -> Option<&'a [u8]> {
-        self.items.iter().find_map(|item| {
-            if item.id == section_id {
-                let data =
-                    &self.sections_data[item.offset as usize..(item.offset + item.length) as usize];
-                Some(data)
-            } else {
-                None
-            }
+        self.get_section_index_by_id(section_id).map(|idx| {
+            let item = &self.items[idx];
+            &self.sections_data[item.offset as usize..(item.offset as usize + item.length as usize)]
         })
     }
 
+    fn get_required_section_data_by_id(
+        &'a self,
+        section_id: ModuleSectionId,
+    ) -> Result<&'a [u8], ImageError> {
+        self.get_section_data_by_id(section_id)
+            .ok_or(ImageError::new(ImageErrorType::MissingSection(section_id)))
+    }
+
+    /// Returns the data of the custom section, the custom sections are
+    /// decoded by the codecs outside this crate, see `section_registry`.
+    ///
+    /// Returns `None` if the section is absent or it is not a custom section.
+    pub fn get_optional_custom_section_data(
+        &'a self,
+        section_id: ModuleSectionId,
+    ) -> Option<&'a [u8]> {
+        if !section_id.is_custom() {
+            return None;
+        }
+
+        self.get_section_data_by_id(section_id)
+    }
+
     pub fn get_property_section(&'a self) -> PropertySection {
         self.get_section_data_by_id(ModuleSectionId::Property)
             .map_or_else(
@@ -402,39 +1307,160 @@ impl<'a> ModuleImage<'a> {
             )
     }
 
-    pub fn get_optional_read_only_data_section(&'a self) -> Option<ReadOnlyDataSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::ReadOnlyData)
-            .map(ReadOnlyDataSection::read)
-    }
+    // The following `try_get_*` functions are the fallible variants of the getters
+    // above, e.g., for the loaders processing untrusted images.
+    //
+    // A missing section results in an `ImageErrorType::MissingSection` error and
+    // a section whose table (or data area) is out of bounds results in an
+    // `ImageErrorType::CorruptedSection` error, rather than a panic (or undefined behavior).
 
-    pub fn get_optional_read_write_data_section(&'a self) -> Option<ReadWriteDataSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::ReadWriteData)
-            .map(ReadWriteDataSection::read)
-    }
+    pub fn try_get_property_section(&'a self) -> Result<PropertySection, ImageError> {
+        let section_data = self.get_required_section_data_by_id(ModuleSectionId::Property)?;
 
-    pub fn get_optional_uninit_data_section(&'a self) -> Option<UninitDataSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::UninitData)
-            .map(UninitDataSection::read)
+        // the property section is read by casting the pointer
+        let is_aligned = section_data.as_ptr() as usize % align_of::<PropertySection>() == 0;
+        let corrupted =
+            || ImageError::new(ImageErrorType::CorruptedSection(ModuleSectionId::Property));
+
+        if section_data.len() < size_of::<PropertySection>() || !is_aligned {
+            return Err(corrupted());
+        }
+
+        let section = PropertySection::read(section_data);
+        if section.is_well_formed() {
+            Ok(section)
+        } else {
+            Err(corrupted())
+        }
     }
 
-    pub fn get_optional_export_function_section(&'a self) -> Option<FunctionNameSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::FunctionName)
-            .map(FunctionNameSection::read)
+    pub fn try_get_type_section(&'a self) -> Result<TypeSection<'a>, ImageError> {
+        self.try_get_required_section::<TypeItem, TypeSection>(
+            ModuleSectionId::Type,
+            TypeSection::is_well_formed,
+        )
     }
 
-    pub fn get_optional_export_data_section(&'a self) -> Option<DataNameSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::DataName)
-            .map(DataNameSection::read)
+    pub fn try_get_local_variable_section(
+        &'a self,
+    ) -> Result<LocalVariableSection<'a>, ImageError> {
+        self.try_get_required_section::<LocalVariableList, LocalVariableSection>(
+            ModuleSectionId::LocalVariable,
+            LocalVariableSection::is_well_formed,
+        )
     }
 
-    pub fn get_optional_relocate_section(&'a self) -> Option<RelocateSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::Relocate)
-            .map(RelocateSection::read)
+    pub fn try_get_function_section(&'a self) -> Result<FunctionSection<'a>, ImageError> {
+        self.try_get_required_section::<FunctionItem, FunctionSection>(
+            ModuleSectionId::Function,
+            FunctionSection::is_well_formed,
+        )
     }
 
-    pub fn get_optional_import_module_section(&'a self) -> Option<ImportModuleSection<'a>> {
-        self.get_section_data_by_id(ModuleSectionId::ImportModule)
-            .map(ImportModuleSection::read)
+    pub fn try_get_entry_point_section(&'a self) -> Result<EntryPointSection<'a>, ImageError> {
+        self.try_get_required_section::<EntryPointItem, EntryPointSection>(
+            ModuleSectionId::EntryPoint,
+            EntryPointSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_dynamic_link_module_list_section(
+        &'a self,
+    ) -> Result<LinkingModuleSection<'a>, ImageError> {
+        self.try_get_required_section::<LinkingModuleItem, LinkingModuleSection>(
+            ModuleSectionId::LinkingModule,
+            LinkingModuleSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_function_index_section(
+        &'a self,
+    ) -> Result<FunctionIndexSection<'a>, ImageError> {
+        self.try_get_required_section::<RangeItem, FunctionIndexSection>(
+            ModuleSectionId::FunctionIndex,
+            FunctionIndexSection::is_well_formed,
+        )
+    }
+
+    // `I` is the type of the (first) table item of the section.
+    fn try_get_required_section<I, T: SectionEntry<'a>>(
+        &'a self,
+        section_id: ModuleSectionId,
+        is_well_formed: impl Fn(&T) -> bool,
+    ) -> Result<T, ImageError> {
+        let section_data = self.get_required_section_data_by_id(section_id)?;
+        let corrupted = || ImageError::new(ImageErrorType::CorruptedSection(section_id));
+
+        if !check_section_with_table::<I>(section_data) {
+            return Err(corrupted());
+        }
+
+        let section = T::read(section_data);
+        if is_well_formed(&section) {
+            Ok(section)
+        } else {
+            Err(corrupted())
+        }
+    }
+
+    pub fn get_optional_read_only_data_section(&'a self) -> Option<ReadOnlyDataSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::ReadOnlyData)
+            .map(ReadOnlyDataSection::read)
+    }
+
+    pub fn get_optional_read_write_data_section(&'a self) -> Option<ReadWriteDataSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::ReadWriteData)
+            .map(ReadWriteDataSection::read)
+    }
+
+    pub fn get_optional_uninit_data_section(&'a self) -> Option<UninitDataSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::UninitData)
+            .map(UninitDataSection::read)
+    }
+
+    pub fn get_optional_export_function_section(&'a self) -> Option<FunctionNameSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::FunctionName)
+            .map(FunctionNameSection::read)
+    }
+
+    pub fn get_optional_export_data_section(&'a self) -> Option<DataNameSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::DataName)
+            .map(DataNameSection::read)
+    }
+
+    pub fn get_optional_relocate_section(&'a self) -> Option<RelocateSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::Relocate)
+            .map(RelocateSection::read)
+    }
+
+    pub fn get_optional_export_hash_section(&'a self) -> Option<ExportHashSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::ExportHash)
+            .map(ExportHashSection::read)
+    }
+
+    pub fn get_optional_function_hash_section(&'a self) -> Option<FunctionHashSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::FunctionHash)
+            .map(FunctionHashSection::read)
+    }
+
+    pub fn get_optional_debug_link_section(&'a self) -> Option<DebugLinkSection> {
+        self.get_section_data_by_id(ModuleSectionId::DebugLink)
+            .map(DebugLinkSection::read)
+    }
+
+    pub fn get_optional_license_section(&'a self) -> Option<LicenseSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::License)
+            .map(LicenseSection::read)
+    }
+
+    pub fn get_optional_patch_slot_section(&'a self) -> Option<PatchSlotSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::PatchSlot)
+            .map(PatchSlotSection::read)
+    }
+
+    pub fn get_optional_import_module_section(&'a self) -> Option<ImportModuleSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::ImportModule)
+            .map(ImportModuleSection::read)
     }
 
     pub fn get_optional_import_function_section(&'a self) -> Option<ImportFunctionSection<'a>> {
@@ -457,11 +1483,23 @@ impl<'a> ModuleImage<'a> {
             .map(ExternalFunctionSection::read)
     }
 
+    pub fn get_optional_initializer_section(&'a self) -> Option<InitializerSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::Initializer)
+            .map(InitializerSection::read)
+    }
+
     pub fn get_optional_data_index_section(&'a self) -> Option<DataIndexSection<'a>> {
         self.get_section_data_by_id(ModuleSectionId::DataIndex)
             .map(DataIndexSection::read)
     }
 
+    pub fn get_optional_initialization_dependency_section(
+        &'a self,
+    ) -> Option<InitializationDependencySection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::InitializationDependency)
+            .map(InitializationDependencySection::read)
+    }
+
     pub fn get_optional_unified_external_type_section(
         &'a self,
     ) -> Option<UnifiedExternalTypeSection<'a>> {
@@ -489,23 +1527,355 @@ impl<'a> ModuleImage<'a> {
         self.get_section_data_by_id(ModuleSectionId::ExternalFunctionIndex)
             .map(ExternalFunctionIndexSection::read)
     }
+
+    pub fn get_optional_lazy_binding_section(&'a self) -> Option<LazyBindingSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::LazyBinding)
+            .map(LazyBindingSection::read)
+    }
+
+    pub fn get_optional_entry_point_section(&'a self) -> Option<EntryPointSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::EntryPoint)
+            .map(EntryPointSection::read)
+    }
+
+    pub fn get_optional_function_index_section(&'a self) -> Option<FunctionIndexSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::FunctionIndex)
+            .map(FunctionIndexSection::read)
+    }
+
+    pub fn get_optional_dynamic_link_module_list_section(
+        &'a self,
+    ) -> Option<LinkingModuleSection<'a>> {
+        self.get_section_data_by_id(ModuleSectionId::LinkingModule)
+            .map(LinkingModuleSection::read)
+    }
+
+    // The following `try_get_optional_*` functions check the section data
+    // before reading, a corrupted section results in a `SectionReadError`
+    // rather than a panic (or undefined behavior), and the other sections of
+    // the image are still usable.
+    //
+    // They are provided for all optional common sections, e.g., for the entry
+    // reader, which reads untrusted images.
+
+    pub fn try_get_optional_export_function_section(
+        &'a self,
+    ) -> Result<Option<FunctionNameSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<FunctionNameItem, FunctionNameSection>(
+            ModuleSectionId::FunctionName,
+            FunctionNameSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_export_data_section(
+        &'a self,
+    ) -> Result<Option<DataNameSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<DataNameItem, DataNameSection>(
+            ModuleSectionId::DataName,
+            DataNameSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_relocate_section(
+        &'a self,
+    ) -> Result<Option<RelocateSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<RelocateList, RelocateSection>(
+            ModuleSectionId::Relocate,
+            RelocateSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_read_only_data_section(
+        &'a self,
+    ) -> Result<Option<ReadOnlyDataSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ReadOnlyDataItem, ReadOnlyDataSection>(
+            ModuleSectionId::ReadOnlyData,
+            ReadOnlyDataSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_read_write_data_section(
+        &'a self,
+    ) -> Result<Option<ReadWriteDataSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ReadWriteDataItem, ReadWriteDataSection>(
+            ModuleSectionId::ReadWriteData,
+            ReadWriteDataSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_uninit_data_section(
+        &'a self,
+    ) -> Result<Option<UninitDataSection<'a>>, SectionReadError> {
+        // the uninit data section contains only the table.
+        self.try_get_optional_section::<UninitDataItem, UninitDataSection>(
+            ModuleSectionId::UninitData,
+            |_| true,
+        )
+    }
+
+    pub fn try_get_optional_import_module_section(
+        &'a self,
+    ) -> Result<Option<ImportModuleSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ImportModuleItem, ImportModuleSection>(
+            ModuleSectionId::ImportModule,
+            ImportModuleSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_import_function_section(
+        &'a self,
+    ) -> Result<Option<ImportFunctionSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ImportFunctionItem, ImportFunctionSection>(
+            ModuleSectionId::ImportFunction,
+            ImportFunctionSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_import_data_section(
+        &'a self,
+    ) -> Result<Option<ImportDataSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ImportDataItem, ImportDataSection>(
+            ModuleSectionId::ImportData,
+            ImportDataSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_external_library_section(
+        &'a self,
+    ) -> Result<Option<ExternalLibrarySection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ExternalLibraryItem, ExternalLibrarySection>(
+            ModuleSectionId::ExternalLibrary,
+            ExternalLibrarySection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_external_function_section(
+        &'a self,
+    ) -> Result<Option<ExternalFunctionSection<'a>>, SectionReadError> {
+        self.try_get_optional_section::<ExternalFunctionItem, ExternalFunctionSection>(
+            ModuleSectionId::ExternalFunction,
+            ExternalFunctionSection::is_well_formed,
+        )
+    }
+
+    pub fn try_get_optional_initializer_section(
+        &'a self,
+    ) -> Result<Option<InitializerSection<'a>>, SectionReadError> {
+        let function_count = self
+            .try_get_function_section()
+            .map_or(0, |section| section.items.len());
+
+        self.try_get_optional_section::<InitializerItem, InitializerSection>(
+            ModuleSectionId::Initializer,
+            |section| section.is_well_formed(function_count),
+        )
+    }
+
+    // `I` is the type of the (first) table item of the section.
+    //
+    // It is also used for checking the other sections, e.g., `roundtrip_check`
+    // checks all sections of an untrusted image before reading the entries.
+    pub(crate) fn try_get_optional_section<I, T: SectionEntry<'a>>(
+        &'a self,
+        section_id: ModuleSectionId,
+        is_well_formed: impl Fn(&T) -> bool,
+    ) -> Result<Option<T>, SectionReadError> {
+        let Some(section_data) = self.get_section_data_by_id(section_id) else {
+            return Ok(None);
+        };
+
+        if !check_section_with_table::<I>(section_data) {
+            return Err(SectionReadError::new(section_id));
+        }
+
+        let section = T::read(section_data);
+        if is_well_formed(&section) {
+            Ok(Some(section))
+        } else {
+            Err(SectionReadError::new(section_id))
+        }
+    }
+}
+
+/// Builds a new image which contains only the sections passing the predicate.
+///
+/// The section data are copied as they are, only the section table is recomputed,
+/// it is the primitive of stripping sections, extracting interfaces and
+/// splitting debug information.
+///
+/// Note: removing an essential section results in an image which
+/// cannot be loaded, it is the responsibility of the caller.
+pub fn filter_sections(
+    module_image: &ModuleImage,
+    predicate: impl Fn(ModuleSectionId) -> bool,
+) -> Result<Vec<u8>, ImageError> {
+    let mut items: Vec<ModuleSectionItem> = vec![];
+    let mut sections_data: Vec<u8> = vec![];
+
+    for item in module_image.items.iter().filter(|item| predicate(item.id)) {
+        let data =
+            &module_image.sections_data[item.offset as usize..(item.offset + item.length) as usize];
+        items.push(ModuleSectionItem::new(
+            item.id,
+            sections_data.len() as u32,
+            item.length,
+        ));
+        sections_data.extend_from_slice(data);
+    }
+
+    let filtered_module_image = ModuleImage::new(module_image.image_type, &items, &sections_data);
+
+    let mut image_binary: Vec<u8> = vec![];
+    filtered_module_image.write(&mut image_binary)?;
+    Ok(image_binary)
+}
+
+// Checks the section ids and the ranges of the section table before the table
+// is cast into `ModuleSectionItem`s, the range of each section must be located
+// in the sections data area.
+pub(crate) fn check_section_table(image_body: &[u8]) -> Result<(), ImageError> {
+    if !check_section_with_table::<ModuleSectionItem>(image_body) {
+        return Err(ImageError::new(ImageErrorType::InvalidImage));
+    }
+
+    let item_count = u32::from_le_bytes(image_body[0..4].try_into().unwrap()) as usize;
+    let table_end = BASE_SECTION_HEADER_LENGTH + item_count * size_of::<ModuleSectionItem>();
+    let sections_data_length = image_body.len() - table_end;
+
+    for idx in 0..item_count {
+        let read_field = |field_offset: usize| {
+            let position =
+                BASE_SECTION_HEADER_LENGTH + idx * size_of::<ModuleSectionItem>() + field_offset;
+            u32::from_le_bytes(image_body[position..(position + 4)].try_into().unwrap())
+        };
+
+        ModuleSectionId::try_from(read_field(offset_of!(ModuleSectionItem, id)))?;
+
+        let offset = read_field(offset_of!(ModuleSectionItem, offset)) as usize;
+        let length = read_field(offset_of!(ModuleSectionItem, length)) as usize;
+        if offset + length > sections_data_length {
+            return Err(ImageError::new(ImageErrorType::InvalidImage));
+        }
+    }
+
+    Ok(())
+}
+
+// Checks the raw value of an enum field, the section id is filled into the error.
+fn check_enum_value<E: TryFrom<u8, Error = ImageError>>(
+    section_id: ModuleSectionId,
+    value: u8,
+) -> Result<(), ImageError> {
+    E::try_from(value).map(|_| ()).map_err(|mut error| {
+        if let ImageErrorType::InvalidEnumValue {
+            section_id: field_section_id,
+            ..
+        } = &mut error.error_type
+        {
+            *field_section_id = Some(section_id);
+        }
+        error
+    })
+}
+
+// The same as `check_enum_value`, but for the enums of the ISA crate.
+fn check_isa_enum_value<E: IsaEnumValues>(
+    section_id: ModuleSectionId,
+    value: u8,
+) -> Result<(), ImageError> {
+    if E::VALUES.contains(&value) {
+        Ok(())
+    } else {
+        Err(ImageError::new(ImageErrorType::InvalidEnumValue {
+            section_id: Some(section_id),
+            enum_name: E::NAME,
+            value: value as u32,
+        }))
+    }
+}
+
+// Reads the image flags from the extra header without reading the image,
+// returns `None` if the image has no extra header.
+pub(crate) fn read_image_flags(image_binary: &[u8]) -> Option<u32> {
+    if image_binary.get(0..8)? != IMAGE_FILE_MAGIC_NUMBER {
+        return None;
+    }
+
+    let extra_header_length = u16::from_le_bytes(image_binary.get(10..12)?.try_into().unwrap());
+    if extra_header_length < 4 {
+        return None;
+    }
+
+    image_binary
+        .get(BASE_MODULE_HEADER_LENGTH..(BASE_MODULE_HEADER_LENGTH + 4))
+        .map(|data| u32::from_le_bytes(data.try_into().unwrap()))
+}
+
+// Verifies the trailer of the image, returns the end position of the image body.
+pub(crate) fn verify_trailer(image_binary: &[u8], body_start: usize) -> Result<usize, ImageError> {
+    if image_binary.len() < body_start + IMAGE_TRAILER_LENGTH {
+        return Err(ImageError::new(ImageErrorType::TruncatedImage));
+    }
+
+    let trailer_start = image_binary.len() - IMAGE_TRAILER_LENGTH;
+    let trailer = &image_binary[trailer_start..];
+
+    let total_length = u32::from_le_bytes(trailer[8..12].try_into().unwrap()) as usize;
+    let magic_slice = &trailer[0..8];
+    if magic_slice != IMAGE_TRAILER_MAGIC_NUMBER || total_length != image_binary.len() {
+        // The trailer is lost or moved, i.e., the image is truncated.
+        return Err(ImageError::new(ImageErrorType::TruncatedImage));
+    }
+
+    let checksum = u32::from_le_bytes(trailer[12..16].try_into().unwrap());
+    if checksum != compute_crc32(&image_binary[..trailer_start]) {
+        return Err(ImageError::new(ImageErrorType::ChecksumMismatch));
+    }
+
+    Ok(trailer_start)
+}
+
+// Computes the CRC-32 (IEEE 802.3, reflected, polynomial 0xedb88320) of the data.
+pub fn compute_crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xffff_ffff;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
 }
 
 #[cfg(test)]
 mod tests {
+    use std::mem::offset_of;
+
     use anc_isa::{OperandDataType, RUNTIME_EDITION};
 
     use crate::{
         common_sections::{
+            function_name_section::{FunctionNameItem, FunctionNameSection},
             local_variable_section::{LocalVariableItem, LocalVariableSection},
             property_section::PropertySection,
+            relocate_section::{RelocateItem, RelocateSection},
             type_section::TypeSection,
+            uninit_data_section::{DataItem, UninitDataSection},
         },
-        entry::{LocalVariableListEntry, TypeEntry},
+        entry::{
+            FunctionNameEntry, LocalVariableListEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+            UninitDataEntry,
+        },
+        entry_writer::build_minimal_module,
+        linking_sections::function_index_section::{FunctionIndexItem, FunctionIndexSection},
         module_image::{
-            ImageType, ModuleImage, SectionEntry, BASE_MODULE_HEADER_LENGTH,
-            IMAGE_FILE_MAGIC_NUMBER,
+            compute_crc32, filter_sections, ImageType, ModuleImage, ModuleSectionId,
+            ModuleSectionItem, RangeItem, SectionEntry, Visibility, BASE_MODULE_HEADER_LENGTH,
+            IMAGE_FILE_MAGIC_NUMBER, IMAGE_TRAILER_LENGTH,
         },
+        utils::helper_build_application_fixture,
+        ImageErrorType, SectionReadError,
     };
 
     #[test]
@@ -546,12 +1916,8 @@ mod tests {
             vec![&type_section, &local_variable_section, &property_section];
 
         let (section_items, sections_data) =
-            ModuleImage::convert_from_section_entries(&section_entries);
-        let module_image = ModuleImage {
-            image_type: ImageType::ObjectFile,
-            items: &section_items,
-            sections_data: &sections_data,
-        };
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let module_image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
 
         let mut image_binary: Vec<u8> = vec![];
         module_image.write(&mut image_binary).unwrap();
@@ -726,4 +2092,725 @@ mod tests {
         // assert_eq!(property_section_restore.import_function_count, 19);
         assert_eq!(property_section_restore.get_module_name(), "bar");
     }
+
+    #[test]
+    fn test_convert_from_section_entries_into_reused_buffers() {
+        let type_entries = vec![TypeEntry {
+            params: vec![OperandDataType::I32],
+            results: vec![OperandDataType::I64],
+        }];
+
+        let (type_items, types_data) = TypeSection::convert_from_entries(&type_entries);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let property_section = PropertySection::new("foo", *RUNTIME_EDITION, 1, 2, 3);
+
+        let mut section_items: Vec<ModuleSectionItem> = vec![];
+        let mut sections_data: Vec<u8> = vec![];
+
+        let section_entries0: Vec<&dyn SectionEntry> = vec![&type_section, &property_section];
+        ModuleImage::convert_from_section_entries_into(
+            &section_entries0,
+            &mut section_items,
+            &mut sections_data,
+        )
+        .unwrap();
+        let (expect_items, expect_data) =
+            ModuleImage::convert_from_section_entries(&section_entries0).unwrap();
+        assert_eq!(section_items, expect_items);
+        assert_eq!(sections_data, expect_data);
+
+        // the previous content should be discarded
+        let section_entries1: Vec<&dyn SectionEntry> = vec![&property_section];
+        ModuleImage::convert_from_section_entries_into(
+            &section_entries1,
+            &mut section_items,
+            &mut sections_data,
+        )
+        .unwrap();
+        let (expect_items, expect_data) =
+            ModuleImage::convert_from_section_entries(&section_entries1).unwrap();
+        assert_eq!(section_items, expect_items);
+        assert_eq!(sections_data, expect_data);
+    }
+
+    #[test]
+    fn test_convert_from_section_entries_with_wrong_estimated_size() {
+        // A section entry whose `estimated_size` does not match the written data.
+        struct FaultySection;
+
+        impl<'a> SectionEntry<'a> for FaultySection {
+            fn id(&'a self) -> ModuleSectionId {
+                ModuleSectionId::Type
+            }
+
+            fn read(_section_data: &'a [u8]) -> Self {
+                FaultySection
+            }
+
+            fn write(&'a self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+                writer.write_all(&[0u8; 8])
+            }
+
+            fn estimated_size(&'a self) -> usize {
+                4
+            }
+        }
+
+        let faulty_section = FaultySection;
+        let section_entries: Vec<&dyn SectionEntry> = vec![&faulty_section];
+        let error = ModuleImage::convert_from_section_entries(&section_entries).unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::SectionSizeMismatch {
+                section_id: ModuleSectionId::Type,
+                estimated_size: 4,
+                written_size: 8,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_section_entry_estimated_size() {
+        let type_entries = vec![
+            TypeEntry {
+                params: vec![OperandDataType::I32, OperandDataType::I64],
+                results: vec![OperandDataType::F32],
+            },
+            TypeEntry {
+                params: vec![],
+                results: vec![OperandDataType::F64],
+            },
+        ];
+
+        let (type_items, types_data) = TypeSection::convert_from_entries(&type_entries);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let property_section = PropertySection::new("foo", *RUNTIME_EDITION, 1, 2, 3);
+
+        let mut type_section_data: Vec<u8> = vec![];
+        type_section.write(&mut type_section_data).unwrap();
+        assert_eq!(type_section.estimated_size(), type_section_data.len());
+
+        let mut property_section_data: Vec<u8> = vec![];
+        property_section.write(&mut property_section_data).unwrap();
+        assert_eq!(
+            property_section.estimated_size(),
+            property_section_data.len()
+        );
+
+        // the sections with one table and two tables
+        let uninit_data_items = UninitDataSection::convert_from_entries(&[
+            UninitDataEntry::from_i32(),
+            UninitDataEntry::from_i64(),
+        ]);
+        let uninit_data_section = UninitDataSection {
+            items: &uninit_data_items,
+        };
+        let mut uninit_data_section_data: Vec<u8> = vec![];
+        uninit_data_section
+            .write(&mut uninit_data_section_data)
+            .unwrap();
+        assert_eq!(
+            uninit_data_section.estimated_size(),
+            uninit_data_section_data.len()
+        );
+
+        let function_index_items = [FunctionIndexItem::new(0, 1), FunctionIndexItem::new(1, 0)];
+        let function_index_section = FunctionIndexSection {
+            ranges: &[RangeItem::new(0, 2)],
+            items: &function_index_items,
+        };
+        let mut function_index_section_data: Vec<u8> = vec![];
+        function_index_section
+            .write(&mut function_index_section_data)
+            .unwrap();
+        assert_eq!(
+            function_index_section.estimated_size(),
+            function_index_section_data.len()
+        );
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&type_section, &property_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+
+        assert_eq!(sections_data.len(), sections_data.capacity());
+        assert_eq!(section_items[0].offset, 0);
+        assert_eq!(section_items[0].length as usize, type_section_data.len());
+        assert_eq!(section_items[1].offset as usize, type_section_data.len());
+        assert_eq!(
+            section_items[1].length as usize,
+            property_section_data.len()
+        );
+    }
+
+    #[test]
+    fn test_try_get_corrupted_optional_section() {
+        let (function_name_items, function_names_data) =
+            FunctionNameSection::convert_from_entries(&[FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Public,
+                0,
+            )]);
+        let function_name_section = FunctionNameSection {
+            items: &function_name_items,
+            full_names_data: &function_names_data,
+        };
+
+        let (relocate_lists, relocate_list_data) = RelocateSection::convert_from_entries(&[
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(0)]),
+        ]);
+        let relocate_section = RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> =
+            vec![&function_name_section, &relocate_section];
+        let (section_items, mut sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+
+        {
+            let module_image =
+                ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+            assert!(module_image
+                .try_get_optional_export_function_section()
+                .unwrap()
+                .is_some());
+            assert!(module_image
+                .try_get_optional_export_data_section()
+                .unwrap()
+                .is_none());
+        }
+
+        // corrupt the name length of the first item of the function name section
+        let name_length_offset = section_items[0].offset as usize + 8 + 4;
+        sections_data[name_length_offset] = 0xff;
+
+        let module_image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(
+            module_image.try_get_optional_export_function_section(),
+            Err(SectionReadError::new(ModuleSectionId::FunctionName))
+        );
+
+        // the other sections are still usable
+        let relocate_section_restore = module_image
+            .try_get_optional_relocate_section()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            relocate_section_restore.convert_to_entries(),
+            vec![RelocateListEntry::new(vec![
+                RelocateEntry::from_function_public_index(0)
+            ])]
+        );
+
+        // corrupt the item count of the relocate section
+        let item_count_offset = section_items[1].offset as usize;
+        sections_data[item_count_offset] = 0xff;
+
+        let module_image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(
+            module_image.try_get_optional_relocate_section(),
+            Err(SectionReadError::new(ModuleSectionId::Relocate))
+        );
+    }
+
+    #[test]
+    fn test_compute_crc32() {
+        assert_eq!(compute_crc32(b""), 0);
+        assert_eq!(compute_crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn test_module_image_with_trailer() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut image_binary_with_trailer: Vec<u8> = vec![];
+        module_image
+            .write_with_trailer(&mut image_binary_with_trailer)
+            .unwrap();
+
+        // extra header (8 bytes) and trailer (16 bytes)
+        assert_eq!(
+            image_binary_with_trailer.len(),
+            image_binary.len() + 8 + IMAGE_TRAILER_LENGTH
+        );
+
+        let module_image_restore = ModuleImage::read(&image_binary_with_trailer).unwrap();
+        assert_eq!(module_image_restore, module_image);
+
+        // truncated
+        let truncated_binary = &image_binary_with_trailer[..image_binary_with_trailer.len() - 20];
+        assert!(matches!(
+            ModuleImage::read(truncated_binary),
+            Err(e) if matches!(e.error_type, ImageErrorType::TruncatedImage)
+        ));
+
+        // lost the whole trailer
+        let truncated_binary =
+            &image_binary_with_trailer[..image_binary_with_trailer.len() - IMAGE_TRAILER_LENGTH];
+        assert!(matches!(
+            ModuleImage::read(truncated_binary),
+            Err(e) if matches!(e.error_type, ImageErrorType::TruncatedImage)
+        ));
+
+        // corrupted
+        let mut corrupted_binary = image_binary_with_trailer.clone();
+        corrupted_binary[BASE_MODULE_HEADER_LENGTH + 8 + 4] ^= 0xff;
+        assert!(matches!(
+            ModuleImage::read(&corrupted_binary),
+            Err(e) if matches!(e.error_type, ImageErrorType::ChecksumMismatch)
+        ));
+    }
+
+    // A writer which accepts the given amount of bytes, and then fails.
+    struct LimitedWriter {
+        remaining: usize,
+    }
+
+    impl std::io::Write for LimitedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(std::io::Error::other("disk full"));
+            }
+
+            let length = buf.len().min(self.remaining);
+            self.remaining -= length;
+            Ok(length)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_compose() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        // moves the sections into a new image, and adds an unaligned custom section
+        let mut sections = module_image
+            .sections()
+            .map(|(section_id, offset, length)| {
+                let data = &module_image.sections_data[offset as usize..(offset + length) as usize];
+                (section_id, data)
+            })
+            .collect::<Vec<_>>();
+        sections.push((ModuleSectionId::Custom0, b"hello".as_slice()));
+        sections.push((ModuleSectionId::Custom1, b"world!!!".as_slice()));
+
+        let mut composed_binary: Vec<u8> = vec![];
+        ModuleImage::compose(ImageType::ObjectFile, &sections, &mut composed_binary).unwrap();
+
+        let composed_image = ModuleImage::read(&composed_binary).unwrap();
+        assert_eq!(
+            composed_image.get_property_section().get_module_name(),
+            "foo"
+        );
+        assert_eq!(
+            composed_image.get_optional_custom_section_data(ModuleSectionId::Custom0),
+            Some(b"hello".as_slice())
+        );
+
+        // the section after the unaligned one is padded
+        let custom1_item = composed_image.items.last().unwrap();
+        assert_eq!(custom1_item.offset % 4, 0);
+        assert_eq!(
+            composed_image.get_optional_custom_section_data(ModuleSectionId::Custom1),
+            Some(b"world!!!".as_slice())
+        );
+
+        // duplicated sections
+        sections.push((ModuleSectionId::Custom0, b"".as_slice()));
+        let error = ModuleImage::compose(ImageType::ObjectFile, &sections, &mut Vec::<u8>::new())
+            .unwrap_err();
+        assert!(matches!(error.error_type, ImageErrorType::InvalidImage));
+    }
+
+    #[test]
+    fn test_read_invalid_enum_values() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        assert!(ModuleImage::read(&image_binary).is_ok());
+
+        // the image type
+        let mut binary = image_binary.clone();
+        binary[8] = 0xff;
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidEnumValue {
+                section_id: None,
+                enum_name: "ImageType",
+                value: 0xff
+            }
+        ));
+
+        // the id of the first section in the section table
+        let mut binary = image_binary.clone();
+        binary[BASE_MODULE_HEADER_LENGTH + 8] = 0x14;
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidEnumValue {
+                section_id: None,
+                enum_name: "ModuleSectionId",
+                value: 0x14
+            }
+        ));
+
+        // the visibility of a function name item
+        let (function_name_items, function_names_data) =
+            FunctionNameSection::convert_from_entries(&[FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Public,
+                0,
+            )]);
+        let mut function_name_section_data: Vec<u8> = vec![];
+        FunctionNameSection {
+            items: &function_name_items,
+            full_names_data: &function_names_data,
+        }
+        .write(&mut function_name_section_data)
+        .unwrap();
+        function_name_section_data[8 + offset_of!(FunctionNameItem, visibility)] = 3;
+
+        let mut binary: Vec<u8> = vec![];
+        ModuleImage::compose(
+            ImageType::ObjectFile,
+            &[(
+                ModuleSectionId::FunctionName,
+                function_name_section_data.as_slice(),
+            )],
+            &mut binary,
+        )
+        .unwrap();
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidEnumValue {
+                section_id: Some(ModuleSectionId::FunctionName),
+                enum_name: "Visibility",
+                value: 3
+            }
+        ));
+
+        // the relocate type of a relocate item
+        let (relocate_lists, relocate_list_data) = RelocateSection::convert_from_entries(&[
+            RelocateListEntry::new(vec![RelocateEntry::from_function_public_index(0)]),
+        ]);
+        let mut relocate_section_data: Vec<u8> = vec![];
+        RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        }
+        .write(&mut relocate_section_data)
+        .unwrap();
+
+        // header (8 bytes) + one relocate list (8 bytes) + the offset of the field
+        relocate_section_data[16 + offset_of!(RelocateItem, relocate_type)] = 9;
+
+        let mut binary: Vec<u8> = vec![];
+        ModuleImage::compose(
+            ImageType::ObjectFile,
+            &[(ModuleSectionId::Relocate, relocate_section_data.as_slice())],
+            &mut binary,
+        )
+        .unwrap();
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidEnumValue {
+                section_id: Some(ModuleSectionId::Relocate),
+                enum_name: "RelocateType",
+                value: 9
+            }
+        ));
+
+        // the memory data type (an enum of the ISA crate) of an uninit data item
+        let uninit_data_items =
+            UninitDataSection::convert_from_entries(&[UninitDataEntry::from_i32()]);
+        let mut uninit_data_section_data: Vec<u8> = vec![];
+        UninitDataSection {
+            items: &uninit_data_items,
+        }
+        .write(&mut uninit_data_section_data)
+        .unwrap();
+        uninit_data_section_data[8 + offset_of!(DataItem, memory_data_type)] = 0xff;
+
+        let mut binary: Vec<u8> = vec![];
+        ModuleImage::compose(
+            ImageType::ObjectFile,
+            &[(
+                ModuleSectionId::UninitData,
+                uninit_data_section_data.as_slice(),
+            )],
+            &mut binary,
+        )
+        .unwrap();
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidEnumValue {
+                section_id: Some(ModuleSectionId::UninitData),
+                enum_name: "MemoryDataType",
+                value: 0xff
+            }
+        ));
+    }
+
+    #[test]
+    fn test_read_malformed_section_table() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+
+        // truncated header
+        let error = ModuleImage::read(&image_binary[..10]).unwrap_err();
+        assert!(matches!(error.error_type, ImageErrorType::TruncatedImage));
+
+        // the length of the first section exceeds the sections data area
+        let mut binary = image_binary.clone();
+        let position = BASE_MODULE_HEADER_LENGTH + 8 + offset_of!(ModuleSectionItem, length);
+        binary[position..(position + 4)].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            ModuleImage::read(&binary).unwrap_err().error_type,
+            ImageErrorType::InvalidImage
+        ));
+    }
+
+    #[test]
+    fn test_write_error_context() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        // fails within the header
+        let error = module_image
+            .write(&mut LimitedWriter { remaining: 4 })
+            .unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::Io {
+                section_id: None,
+                bytes_written: 4,
+                ..
+            }
+        ));
+
+        // fails within the function section (the last section)
+        let function_item = module_image.items.last().unwrap();
+        assert_eq!(function_item.id, ModuleSectionId::Function);
+
+        let data_area_start = BASE_MODULE_HEADER_LENGTH
+            + 8 // section table header
+            + module_image.items.len() * size_of::<ModuleSectionItem>();
+        let limit = data_area_start + function_item.offset as usize + 2;
+
+        let error = module_image
+            .write(&mut LimitedWriter { remaining: limit })
+            .unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::Io {
+                section_id: Some(ModuleSectionId::Function),
+                bytes_written,
+                ..
+            } if bytes_written == limit
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Failed to write the section \"function\" after {} bytes were written: disk full",
+                limit
+            )
+        );
+
+        // with trailer
+        let error = module_image
+            .write_with_trailer(&mut LimitedWriter { remaining: 0 })
+            .unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::Io {
+                section_id: None,
+                bytes_written: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_section_table_sorted() {
+        let fixture = helper_build_application_fixture(1);
+        let module_image = ModuleImage::read(&fixture.application_binary).unwrap();
+
+        assert!(module_image
+            .items
+            .windows(2)
+            .all(|pair| (pair[0].id as u32) < (pair[1].id as u32)));
+        assert!(module_image.is_table_sorted);
+
+        for (idx, item) in module_image.items.iter().enumerate() {
+            assert_eq!(module_image.get_section_index_by_id(item.id), Some(idx));
+        }
+
+        // the absent section
+        assert_eq!(
+            module_image.get_section_index_by_id(ModuleSectionId::DebugLink),
+            None
+        );
+
+        // unsorted table
+        let type_section = TypeSection {
+            items: &[],
+            types_data: &[],
+        };
+        let property_section = PropertySection::new("foo", *RUNTIME_EDITION, 0, 0, 1);
+        let section_entries: Vec<&dyn SectionEntry> = vec![&type_section, &property_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let module_image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+        assert!(!module_image.is_table_sorted);
+
+        assert_eq!(
+            module_image.get_section_index_by_id(ModuleSectionId::Type),
+            Some(0)
+        );
+        assert_eq!(
+            module_image.get_section_index_by_id(ModuleSectionId::Property),
+            Some(1)
+        );
+        assert_eq!(
+            module_image.get_section_index_by_id(ModuleSectionId::Function),
+            None
+        );
+    }
+
+    #[test]
+    fn test_section_metadata() {
+        let all_ids = ModuleSectionId::all();
+        assert_eq!(all_ids.len(), 39);
+        assert!(all_ids
+            .windows(2)
+            .all(|pair| (pair[0] as u32) < (pair[1] as u32)));
+
+        assert_eq!(ModuleSectionId::Property.name(), "property");
+        assert_eq!(
+            ModuleSectionId::from_u32(0x0012),
+            Some(ModuleSectionId::LocalVariable)
+        );
+        assert_eq!(ModuleSectionId::from_u32(0x0014), None);
+
+        assert_eq!(
+            all_ids
+                .iter()
+                .filter(|section_id| section_id.is_essential())
+                .count(),
+            7
+        );
+        assert!(ModuleSectionId::DataIndex.is_linking());
+        assert!(!ModuleSectionId::Relocate.is_linking());
+        assert!(!ModuleSectionId::Custom0.is_linking());
+        assert!(ModuleSectionId::Custom0.is_custom());
+        assert_eq!(
+            ModuleSectionId::ExternalFunctionIndex.name(),
+            "external_function_index"
+        );
+
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(
+            module_image
+                .sections()
+                .map(|(id, _, _)| id.name())
+                .collect::<Vec<_>>(),
+            vec!["property", "type", "local_variable", "function"]
+        );
+
+        let (_, offset, length) = module_image.sections().last().unwrap();
+        assert_eq!((offset + length) as usize, module_image.sections_data.len());
+    }
+
+    #[test]
+    fn test_filter_sections() {
+        let fixture = helper_build_application_fixture(1);
+        let module_image = ModuleImage::read(&fixture.application_binary).unwrap();
+
+        let filtered_binary = filter_sections(&module_image, |section_id| {
+            !matches!(
+                section_id,
+                ModuleSectionId::FunctionName | ModuleSectionId::DataName
+            )
+        })
+        .unwrap();
+        let filtered_module_image = ModuleImage::read(&filtered_binary).unwrap();
+
+        assert_eq!(filtered_module_image.image_type, ImageType::Application);
+        assert_eq!(
+            filtered_module_image.items.len(),
+            module_image.items.len() - 2
+        );
+        assert!(filtered_module_image
+            .get_optional_export_function_section()
+            .is_none());
+        assert_eq!(
+            filtered_module_image.get_function_section(),
+            module_image.get_function_section()
+        );
+        assert_eq!(
+            filtered_module_image.get_optional_read_only_data_section(),
+            module_image.get_optional_read_only_data_section()
+        );
+
+        // keep all
+        assert_eq!(
+            filter_sections(&module_image, |_| true).unwrap(),
+            fixture.application_binary
+        );
+    }
+
+    #[test]
+    fn test_try_get_required_sections() {
+        let fixture = helper_build_application_fixture(1);
+        let module_image = ModuleImage::read(&fixture.application_binary).unwrap();
+
+        assert_eq!(
+            module_image.try_get_type_section().unwrap(),
+            module_image.get_type_section()
+        );
+        assert_eq!(
+            module_image.try_get_function_index_section().unwrap(),
+            module_image.get_function_index_section()
+        );
+
+        let filtered_binary = filter_sections(&module_image, |section_id| {
+            section_id != ModuleSectionId::Function
+        })
+        .unwrap();
+        let filtered_module_image = ModuleImage::read(&filtered_binary).unwrap();
+
+        let error = filtered_module_image
+            .try_get_function_section()
+            .unwrap_err();
+        assert!(matches!(
+            error.error_type,
+            ImageErrorType::MissingSection(ModuleSectionId::Function)
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Cannot find the section \"{}\".",
+                ModuleSectionId::Function.name()
+            )
+        );
+
+        // the other sections are still usable
+        assert!(filtered_module_image.try_get_property_section().is_ok());
+        assert!(filtered_module_image.try_get_entry_point_section().is_ok());
+    }
 }
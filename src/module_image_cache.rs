@@ -0,0 +1,212 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// A wrapper of `ModuleImage` which memoizes the section views.
+//
+// Each `ModuleImage::get_*_section` call looks up the section table and
+// reads the section header (i.e., `read_section_with_table_and_data_area`),
+// the runtime calls them frequently (e.g., getting the type of a function
+// on each function call), `ModuleImageCache` reads each section lazily
+// on the first access and returns the same view afterwards.
+//
+// The views are borrowed from the image binary, so the cache holds no copies
+// of the section data, it only stores the tables' addresses and lengths.
+//
+// Note: the cache is not `Sync` (it uses `OnceCell`), create one cache
+// per thread if necessary, they are cheap.
+
+use std::cell::OnceCell;
+
+use crate::{
+    common_sections::{
+        function_section::FunctionSection, import_function_section::ImportFunctionSection,
+        local_variable_section::LocalVariableSection, property_section::PropertySection,
+        read_only_data_section::ReadOnlyDataSection, read_write_data_section::ReadWriteDataSection,
+        type_section::TypeSection, uninit_data_section::UninitDataSection,
+    },
+    linking_sections::{
+        data_index_section::DataIndexSection, entry_point_section::EntryPointSection,
+        external_function_index_section::ExternalFunctionIndexSection,
+        function_index_section::FunctionIndexSection, linking_module_section::LinkingModuleSection,
+        unified_external_function_section::UnifiedExternalFunctionSection,
+    },
+    module_image::ModuleImage,
+};
+
+pub struct ModuleImageCache<'a> {
+    pub module_image: &'a ModuleImage<'a>,
+
+    property_section: OnceCell<PropertySection>,
+    type_section: OnceCell<TypeSection<'a>>,
+    local_variable_section: OnceCell<LocalVariableSection<'a>>,
+    function_section: OnceCell<FunctionSection<'a>>,
+
+    read_only_data_section: OnceCell<Option<ReadOnlyDataSection<'a>>>,
+    read_write_data_section: OnceCell<Option<ReadWriteDataSection<'a>>>,
+    uninit_data_section: OnceCell<Option<UninitDataSection<'a>>>,
+    import_function_section: OnceCell<Option<ImportFunctionSection<'a>>>,
+
+    entry_point_section: OnceCell<EntryPointSection<'a>>,
+    linking_module_section: OnceCell<LinkingModuleSection<'a>>,
+    function_index_section: OnceCell<FunctionIndexSection<'a>>,
+    data_index_section: OnceCell<Option<DataIndexSection<'a>>>,
+    unified_external_function_section: OnceCell<Option<UnifiedExternalFunctionSection<'a>>>,
+    external_function_index_section: OnceCell<Option<ExternalFunctionIndexSection<'a>>>,
+}
+
+impl<'a> ModuleImageCache<'a> {
+    pub fn new(module_image: &'a ModuleImage<'a>) -> Self {
+        Self {
+            module_image,
+            property_section: OnceCell::new(),
+            type_section: OnceCell::new(),
+            local_variable_section: OnceCell::new(),
+            function_section: OnceCell::new(),
+            read_only_data_section: OnceCell::new(),
+            read_write_data_section: OnceCell::new(),
+            uninit_data_section: OnceCell::new(),
+            import_function_section: OnceCell::new(),
+            entry_point_section: OnceCell::new(),
+            linking_module_section: OnceCell::new(),
+            function_index_section: OnceCell::new(),
+            data_index_section: OnceCell::new(),
+            unified_external_function_section: OnceCell::new(),
+            external_function_index_section: OnceCell::new(),
+        }
+    }
+
+    pub fn get_property_section(&self) -> &PropertySection {
+        self.property_section
+            .get_or_init(|| self.module_image.get_property_section())
+    }
+
+    pub fn get_type_section(&self) -> &TypeSection<'a> {
+        self.type_section
+            .get_or_init(|| self.module_image.get_type_section())
+    }
+
+    pub fn get_local_variable_section(&self) -> &LocalVariableSection<'a> {
+        self.local_variable_section
+            .get_or_init(|| self.module_image.get_local_variable_section())
+    }
+
+    pub fn get_function_section(&self) -> &FunctionSection<'a> {
+        self.function_section
+            .get_or_init(|| self.module_image.get_function_section())
+    }
+
+    pub fn get_optional_read_only_data_section(&self) -> Option<&ReadOnlyDataSection<'a>> {
+        self.read_only_data_section
+            .get_or_init(|| self.module_image.get_optional_read_only_data_section())
+            .as_ref()
+    }
+
+    pub fn get_optional_read_write_data_section(&self) -> Option<&ReadWriteDataSection<'a>> {
+        self.read_write_data_section
+            .get_or_init(|| self.module_image.get_optional_read_write_data_section())
+            .as_ref()
+    }
+
+    pub fn get_optional_uninit_data_section(&self) -> Option<&UninitDataSection<'a>> {
+        self.uninit_data_section
+            .get_or_init(|| self.module_image.get_optional_uninit_data_section())
+            .as_ref()
+    }
+
+    pub fn get_optional_import_function_section(&self) -> Option<&ImportFunctionSection<'a>> {
+        self.import_function_section
+            .get_or_init(|| self.module_image.get_optional_import_function_section())
+            .as_ref()
+    }
+
+    pub fn get_entry_point_section(&self) -> &EntryPointSection<'a> {
+        self.entry_point_section
+            .get_or_init(|| self.module_image.get_entry_point_section())
+    }
+
+    pub fn get_dynamic_link_module_list_section(&self) -> &LinkingModuleSection<'a> {
+        self.linking_module_section
+            .get_or_init(|| self.module_image.get_dynamic_link_module_list_section())
+    }
+
+    pub fn get_function_index_section(&self) -> &FunctionIndexSection<'a> {
+        self.function_index_section
+            .get_or_init(|| self.module_image.get_function_index_section())
+    }
+
+    pub fn get_optional_data_index_section(&self) -> Option<&DataIndexSection<'a>> {
+        self.data_index_section
+            .get_or_init(|| self.module_image.get_optional_data_index_section())
+            .as_ref()
+    }
+
+    pub fn get_optional_unified_external_function_section(
+        &self,
+    ) -> Option<&UnifiedExternalFunctionSection<'a>> {
+        self.unified_external_function_section
+            .get_or_init(|| {
+                self.module_image
+                    .get_optional_unified_external_function_section()
+            })
+            .as_ref()
+    }
+
+    pub fn get_optional_external_function_index_section(
+        &self,
+    ) -> Option<&ExternalFunctionIndexSection<'a>> {
+        self.external_function_index_section
+            .get_or_init(|| {
+                self.module_image
+                    .get_optional_external_function_index_section()
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        module_image::ModuleImage, module_image_cache::ModuleImageCache,
+        utils::helper_build_application_fixture,
+    };
+
+    #[test]
+    fn test_module_image_cache() {
+        let fixture = helper_build_application_fixture(1);
+        let module_image = ModuleImage::read(&fixture.application_binary).unwrap();
+        let cache = ModuleImageCache::new(&module_image);
+
+        assert_eq!(cache.get_type_section(), &module_image.get_type_section());
+        assert_eq!(
+            cache.get_function_section(),
+            &module_image.get_function_section()
+        );
+        assert_eq!(
+            cache.get_property_section().get_module_name(),
+            module_image.get_property_section().get_module_name()
+        );
+        assert_eq!(
+            cache.get_optional_read_only_data_section(),
+            module_image.get_optional_read_only_data_section().as_ref()
+        );
+        assert_eq!(
+            cache.get_optional_read_write_data_section(),
+            module_image.get_optional_read_write_data_section().as_ref()
+        );
+
+        // the same view is returned on subsequent calls
+        assert!(std::ptr::eq(
+            cache.get_type_section(),
+            cache.get_type_section()
+        ));
+        assert!(std::ptr::eq(
+            cache.get_function_index_section(),
+            cache.get_function_index_section()
+        ));
+    }
+}
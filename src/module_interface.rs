@@ -0,0 +1,695 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The "interface" of a module consists of the exported (i.e., public and
+// package-private) functions and data only, along with their types, without
+// any code or data content.
+//
+// It is used for generating documentation, and for compiling the dependent modules
+// without the complete module (i.e., compile against the interface), since the
+// compiler needs only the names, indices and types of the public items.
+//
+// The interface can be serialized into an ASON text (the interface artifact), e.g.:
+//
+// ```ason
+// {
+//     name: "foo"
+//     version: "1.0.0"
+//     functions: [
+//         {
+//             full_name: "foo::add"
+//             public_index: 0
+//             params: ["i32", "i32"]
+//             results: ["i32"]
+//             visibility: "public"
+//             ordinal: Option::None
+//             abi_hash: 1234
+//         }
+//     ]
+//     data: [
+//         {
+//             full_name: "foo::count"
+//             public_index: 0
+//             section_type: "read_write"
+//             memory_data_type: "i64"
+//             length: 8
+//             align: 8
+//             visibility: "package"
+//             abi_hash: 5678
+//         }
+//     ]
+// }
+// ```
+//
+// The public indices are computed in the same way as `binding_generator`.
+//
+// The interface can also be built into a "header module", i.e., a shared module
+// with stub functions, see `ModuleInterface::build_header_module`.
+
+use std::num::NonZeroU16;
+
+use anc_isa::{DataSectionType, EffectiveVersion, MemoryDataType, OperandDataType};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entry::{
+        DataNameEntry, FunctionNameEntry, ImageCommonEntry, ReadOnlyDataEntry, ReadWriteDataEntry,
+        TypeEntry, UninitDataEntry,
+    },
+    entry_dump::{format_data_section_type, format_memory_data_type, format_visibility},
+    entry_reader::read_object_file,
+    entry_writer::{
+        build_shared_module_scaffold, write_object_file_with_options, OptionalSectionPolicy,
+        WriteOptions,
+    },
+    export_surface::collect_export_signatures,
+    module_image::Visibility,
+    public_index::PublicIndexSpace,
+    ImageError,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ModuleInterface {
+    pub name: String,
+    pub version: EffectiveVersion,
+
+    // In the order of public indices.
+    pub functions: Vec<InterfaceFunction>,
+    pub data: Vec<InterfaceData>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InterfaceFunction {
+    pub full_name: String,
+    pub public_index: usize,
+    pub params: Vec<OperandDataType>,
+    pub results: Vec<OperandDataType>,
+
+    // `Public` or `Package`.
+    pub visibility: Visibility,
+    pub ordinal: Option<NonZeroU16>,
+
+    // See `export_surface::compute_abi_hash`.
+    pub abi_hash: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InterfaceData {
+    pub full_name: String,
+    pub public_index: usize,
+    pub section_type: DataSectionType,
+    pub memory_data_type: MemoryDataType,
+    pub length: u32,
+    pub align: u16,
+
+    // `Public` or `Package`.
+    pub visibility: Visibility,
+
+    // See `export_surface::compute_abi_hash`.
+    pub abi_hash: u64,
+}
+
+// The serialized form of `ModuleInterface`, the types are written as names,
+// e.g. "i32", "read_only", see `entry_dump::format_*`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct InterfaceDocument {
+    name: String,
+    version: String,
+    functions: Vec<InterfaceFunctionDocument>,
+    data: Vec<InterfaceDataDocument>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct InterfaceFunctionDocument {
+    full_name: String,
+    public_index: usize,
+    params: Vec<String>,
+    results: Vec<String>,
+    visibility: String,
+    ordinal: Option<NonZeroU16>,
+    abi_hash: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct InterfaceDataDocument {
+    full_name: String,
+    public_index: usize,
+    section_type: String,
+    memory_data_type: String,
+    length: u32,
+    align: u16,
+    visibility: String,
+    abi_hash: u64,
+}
+
+/// Extracts the interface (the public and package-private functions and data) of the module.
+pub fn extract_interface(image_common_entry: &ImageCommonEntry) -> ModuleInterface {
+    let signatures = collect_export_signatures(image_common_entry);
+    let get_abi_hash = |full_name: &str| {
+        signatures
+            .iter()
+            .find(|signature| signature.full_name == full_name)
+            .map_or(0, |signature| signature.hash)
+    };
+
+    let public_index_space = PublicIndexSpace::from_common_entry(image_common_entry);
+
+    let mut functions = image_common_entry
+        .function_name_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .map(|entry| {
+            let function_entry = &image_common_entry.function_entries[entry.internal_index];
+            let type_entry = &image_common_entry.type_entries[function_entry.type_index];
+            InterfaceFunction {
+                full_name: entry.full_name.clone(),
+                public_index: public_index_space.function_public_index(entry.internal_index),
+                params: type_entry.params.clone(),
+                results: type_entry.results.clone(),
+                visibility: entry.visibility,
+                ordinal: entry.ordinal,
+                abi_hash: get_abi_hash(&entry.full_name),
+            }
+        })
+        .collect::<Vec<_>>();
+    functions.sort_by_key(|function| function.public_index);
+
+    let mut data = image_common_entry
+        .data_data_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .map(|entry| {
+            let index = entry.internal_index_in_section;
+            let (memory_data_type, length, align) = match entry.section_type {
+                DataSectionType::ReadOnly => {
+                    let data_entry = &image_common_entry.read_only_data_entries[index];
+                    (
+                        data_entry.memory_data_type,
+                        data_entry.length,
+                        data_entry.align,
+                    )
+                }
+                DataSectionType::ReadWrite => {
+                    let data_entry = &image_common_entry.read_write_data_entries[index];
+                    (
+                        data_entry.memory_data_type,
+                        data_entry.length,
+                        data_entry.align,
+                    )
+                }
+                DataSectionType::Uninit => {
+                    let data_entry = &image_common_entry.uninit_data_entries[index];
+                    (
+                        data_entry.memory_data_type,
+                        data_entry.length,
+                        data_entry.align,
+                    )
+                }
+            };
+
+            InterfaceData {
+                full_name: entry.full_name.clone(),
+                public_index: public_index_space.data_public_index(entry.section_type, index),
+                section_type: entry.section_type,
+                memory_data_type,
+                length,
+                align,
+                visibility: entry.visibility,
+                abi_hash: get_abi_hash(&entry.full_name),
+            }
+        })
+        .collect::<Vec<_>>();
+    data.sort_by_key(|data_item| data_item.public_index);
+
+    let version = &image_common_entry.version;
+    ModuleInterface {
+        name: image_common_entry.name.clone(),
+        version: EffectiveVersion::new(version.major, version.minor, version.patch),
+        functions,
+        data,
+    }
+}
+
+impl ModuleInterface {
+    /// Serializes the interface into an ASON text.
+    pub fn to_ason_string(&self) -> String {
+        let document = InterfaceDocument {
+            name: self.name.clone(),
+            version: format!(
+                "{}.{}.{}",
+                self.version.major, self.version.minor, self.version.patch
+            ),
+            functions: self
+                .functions
+                .iter()
+                .map(|function| InterfaceFunctionDocument {
+                    full_name: function.full_name.clone(),
+                    public_index: function.public_index,
+                    params: format_operand_data_type_names(&function.params),
+                    results: format_operand_data_type_names(&function.results),
+                    visibility: format_visibility(function.visibility).to_owned(),
+                    ordinal: function.ordinal,
+                    abi_hash: function.abi_hash,
+                })
+                .collect(),
+            data: self
+                .data
+                .iter()
+                .map(|data_item| InterfaceDataDocument {
+                    full_name: data_item.full_name.clone(),
+                    public_index: data_item.public_index,
+                    section_type: format_data_section_type(data_item.section_type).to_owned(),
+                    memory_data_type: format_memory_data_type(data_item.memory_data_type)
+                        .to_owned(),
+                    length: data_item.length,
+                    align: data_item.align,
+                    visibility: format_visibility(data_item.visibility).to_owned(),
+                    abi_hash: data_item.abi_hash,
+                })
+                .collect(),
+        };
+
+        ason::to_string(&document).unwrap()
+    }
+
+    /// Deserializes the interface from an ASON text.
+    ///
+    /// Returns `None` if the text is malformed or contains unknown type names.
+    pub fn from_ason_str(text: &str) -> Option<Self> {
+        let document: InterfaceDocument = ason::from_str(text).ok()?;
+
+        let version_numbers = document
+            .version
+            .split('.')
+            .map(|number| number.parse::<u16>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let [major, minor, patch] = version_numbers[..] else {
+            return None;
+        };
+
+        let functions = document
+            .functions
+            .into_iter()
+            .map(|function| {
+                Some(InterfaceFunction {
+                    full_name: function.full_name,
+                    public_index: function.public_index,
+                    params: parse_operand_data_type_names(&function.params)?,
+                    results: parse_operand_data_type_names(&function.results)?,
+                    visibility: parse_export_visibility_name(&function.visibility)?,
+                    ordinal: function.ordinal,
+                    abi_hash: function.abi_hash,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let data = document
+            .data
+            .into_iter()
+            .map(|data_item| {
+                Some(InterfaceData {
+                    full_name: data_item.full_name,
+                    public_index: data_item.public_index,
+                    section_type: parse_data_section_type_name(&data_item.section_type)?,
+                    memory_data_type: parse_memory_data_type_name(&data_item.memory_data_type)?,
+                    length: data_item.length,
+                    align: data_item.align,
+                    visibility: parse_export_visibility_name(&data_item.visibility)?,
+                    abi_hash: data_item.abi_hash,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(ModuleInterface {
+            name: document.name,
+            version: EffectiveVersion::new(major, minor, patch),
+            functions,
+            data,
+        })
+    }
+
+    /// Builds a "header module" from the interface, i.e., a shared module which
+    /// contains only the exported functions (with stub bodies) and the exported data
+    /// (with zeroed content), so that the other modules can be compiled and linked
+    /// against it without the implementation code.
+    ///
+    /// The package-private items keep their visibility, so only the modules
+    /// of the same package can link against them.
+    ///
+    /// The body of each stub function is a `terminate` instruction with the code
+    /// `SCAFFOLD_STUB_TERMINATE_CODE`, see `entry_writer::build_shared_module_scaffold`.
+    ///
+    /// Note: the public indices of the items may differ from the original module
+    /// since the private items are removed, but the names, types and ABI hashes
+    /// are the same.
+    pub fn build_header_module(&self) -> Result<Vec<u8>, ImageError> {
+        let stubs = self
+            .functions
+            .iter()
+            .map(|function| {
+                let name_path = function
+                    .full_name
+                    .strip_prefix(&format!("{}::", self.name))
+                    .unwrap_or(&function.full_name);
+                (
+                    name_path,
+                    TypeEntry::new(function.params.clone(), function.results.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let scaffold_binary = build_shared_module_scaffold(&self.name, &stubs)?;
+        let mut image_common_entry = read_object_file(&scaffold_binary)?;
+
+        image_common_entry.version =
+            EffectiveVersion::new(self.version.major, self.version.minor, self.version.patch);
+
+        // Keeps the full names (which may not start with the module name) and the ordinals.
+        image_common_entry.function_name_entries = self
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(internal_index, function)| {
+                let entry = FunctionNameEntry::new(
+                    function.full_name.clone(),
+                    function.visibility,
+                    internal_index,
+                );
+                match function.ordinal {
+                    Some(ordinal) => entry.with_ordinal(ordinal),
+                    None => entry,
+                }
+            })
+            .collect();
+
+        for data_item in &self.data {
+            let internal_index_in_section = match data_item.section_type {
+                DataSectionType::ReadOnly => {
+                    image_common_entry
+                        .read_only_data_entries
+                        .push(ReadOnlyDataEntry {
+                            memory_data_type: data_item.memory_data_type,
+                            data: vec![0; data_item.length as usize],
+                            length: data_item.length,
+                            align: data_item.align,
+                        });
+                    image_common_entry.read_only_data_entries.len() - 1
+                }
+                DataSectionType::ReadWrite => {
+                    image_common_entry
+                        .read_write_data_entries
+                        .push(ReadWriteDataEntry {
+                            memory_data_type: data_item.memory_data_type,
+                            data: vec![0; data_item.length as usize],
+                            length: data_item.length,
+                            align: data_item.align,
+                        });
+                    image_common_entry.read_write_data_entries.len() - 1
+                }
+                DataSectionType::Uninit => {
+                    image_common_entry
+                        .uninit_data_entries
+                        .push(UninitDataEntry {
+                            memory_data_type: data_item.memory_data_type,
+                            length: data_item.length,
+                            align: data_item.align,
+                            may_be_undefined: false,
+                        });
+                    image_common_entry.uninit_data_entries.len() - 1
+                }
+            };
+
+            image_common_entry
+                .data_data_entries
+                .push(DataNameEntry::new(
+                    data_item.full_name.clone(),
+                    data_item.visibility,
+                    data_item.section_type,
+                    internal_index_in_section,
+                ));
+        }
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_object_file_with_options(
+            &image_common_entry,
+            true,
+            &WriteOptions {
+                optional_section_policy: OptionalSectionPolicy::OmitEmpty,
+                ..Default::default()
+            },
+            &mut image_binary,
+        )?;
+
+        Ok(image_binary)
+    }
+}
+
+fn format_operand_data_type_names(operand_data_types: &[OperandDataType]) -> Vec<String> {
+    operand_data_types
+        .iter()
+        .map(|operand_data_type| {
+            match operand_data_type {
+                OperandDataType::I32 => "i32",
+                OperandDataType::I64 => "i64",
+                OperandDataType::F32 => "f32",
+                OperandDataType::F64 => "f64",
+            }
+            .to_owned()
+        })
+        .collect()
+}
+
+pub fn parse_operand_data_type_names(names: &[String]) -> Option<Vec<OperandDataType>> {
+    names
+        .iter()
+        .map(|name| match name.as_str() {
+            "i32" => Some(OperandDataType::I32),
+            "i64" => Some(OperandDataType::I64),
+            "f32" => Some(OperandDataType::F32),
+            "f64" => Some(OperandDataType::F64),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn parse_data_section_type_name(name: &str) -> Option<DataSectionType> {
+    [
+        DataSectionType::ReadOnly,
+        DataSectionType::ReadWrite,
+        DataSectionType::Uninit,
+    ]
+    .into_iter()
+    .find(|section_type| format_data_section_type(*section_type) == name)
+}
+
+// Only the exported visibilities are accepted, since the interface
+// does not contain private items.
+fn parse_export_visibility_name(name: &str) -> Option<Visibility> {
+    [Visibility::Public, Visibility::Package]
+        .into_iter()
+        .find(|visibility| format_visibility(*visibility) == name)
+}
+
+pub fn parse_memory_data_type_name(name: &str) -> Option<MemoryDataType> {
+    [
+        MemoryDataType::I32,
+        MemoryDataType::I64,
+        MemoryDataType::F32,
+        MemoryDataType::F64,
+        MemoryDataType::Bytes,
+    ]
+    .into_iter()
+    .find(|memory_data_type| format_memory_data_type(*memory_data_type) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use anc_isa::{DataSectionType, EffectiveVersion, MemoryDataType, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            DataNameEntry, FunctionNameEntry, ReadOnlyDataEntry, ReadWriteDataEntry, TypeEntry,
+            UninitDataEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_object_file},
+        export_surface::{compare_export_surfaces, compute_abi_hash, ExportSurfaceDiff},
+        module_image::{ImageType, ModuleImage, Visibility},
+        module_interface::{extract_interface, InterfaceData, InterfaceFunction, ModuleInterface},
+    };
+
+    #[test]
+    fn test_extract_interface() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                (
+                    "add",
+                    TypeEntry::new(
+                        vec![OperandDataType::I32, OperandDataType::I32],
+                        vec![OperandDataType::I32],
+                    ),
+                ),
+                ("helper", TypeEntry::new(vec![], vec![])),
+                ("internal", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+
+        // make "helper" private and "internal" package-private
+        image_common_entry.function_name_entries[1] =
+            FunctionNameEntry::new("foo::helper".to_owned(), Visibility::Private, 1);
+        image_common_entry.function_name_entries[2] =
+            FunctionNameEntry::new("foo::internal".to_owned(), Visibility::Package, 2);
+
+        image_common_entry
+            .read_write_data_entries
+            .push(ReadWriteDataEntry::from_i64(0));
+        image_common_entry
+            .data_data_entries
+            .push(DataNameEntry::new(
+                "foo::count".to_owned(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                0,
+            ));
+
+        let interface = extract_interface(&image_common_entry);
+        assert_eq!(
+            interface,
+            ModuleInterface {
+                name: "foo".to_owned(),
+                version: EffectiveVersion::new(1, 0, 0),
+                functions: vec![
+                    InterfaceFunction {
+                        full_name: "foo::add".to_owned(),
+                        public_index: 0,
+                        params: vec![OperandDataType::I32, OperandDataType::I32],
+                        results: vec![OperandDataType::I32],
+                        visibility: Visibility::Public,
+                        ordinal: None,
+                        abi_hash: compute_abi_hash("function foo::add (i32, i32) -> (i32)"),
+                    },
+                    InterfaceFunction {
+                        full_name: "foo::internal".to_owned(),
+                        public_index: 2,
+                        params: vec![],
+                        results: vec![],
+                        visibility: Visibility::Package,
+                        ordinal: None,
+                        abi_hash: compute_abi_hash("function foo::internal () -> ()"),
+                    }
+                ],
+                data: vec![InterfaceData {
+                    full_name: "foo::count".to_owned(),
+                    public_index: 0,
+                    section_type: DataSectionType::ReadWrite,
+                    memory_data_type: MemoryDataType::I64,
+                    length: 8,
+                    align: 8,
+                    visibility: Visibility::Public,
+                    abi_hash: compute_abi_hash("data foo::count read_write i64"),
+                }],
+            }
+        );
+
+        // the serialized artifact
+        let text = interface.to_ason_string();
+        assert!(text.contains("\"foo::add\""));
+        assert!(!text.contains("\"foo::helper\""));
+        assert!(text.contains("\"package\""));
+        assert_eq!(ModuleInterface::from_ason_str(&text), Some(interface));
+
+        assert_eq!(ModuleInterface::from_ason_str("{}"), None);
+    }
+
+    #[test]
+    fn test_build_header_module() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("helper", TypeEntry::new(vec![], vec![])),
+                (
+                    "add",
+                    TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I64]),
+                ),
+            ],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry.function_name_entries = vec![
+            FunctionNameEntry::new("foo::helper".to_owned(), Visibility::Private, 0),
+            FunctionNameEntry::new("foo::add".to_owned(), Visibility::Public, 1)
+                .with_ordinal(NonZeroU16::new(3).unwrap()),
+        ];
+        image_common_entry
+            .read_only_data_entries
+            .push(ReadOnlyDataEntry::from_i32(0x11));
+        image_common_entry
+            .uninit_data_entries
+            .push(UninitDataEntry::from_i64());
+        image_common_entry.data_data_entries = vec![
+            DataNameEntry::new(
+                "foo::magic".to_owned(),
+                Visibility::Public,
+                DataSectionType::ReadOnly,
+                0,
+            ),
+            DataNameEntry::new(
+                "foo::buffer".to_owned(),
+                Visibility::Package,
+                DataSectionType::Uninit,
+                0,
+            ),
+        ];
+
+        let mut module_binary: Vec<u8> = vec![];
+        write_object_file(&image_common_entry, true, &mut module_binary).unwrap();
+
+        let interface = extract_interface(&image_common_entry);
+        let header_binary = interface.build_header_module().unwrap();
+
+        // the private function is removed, and the data content is zeroed
+        let header_entry = read_object_file(&header_binary).unwrap();
+        assert_eq!(header_entry.image_type, ImageType::SharedModule);
+        assert_eq!(header_entry.function_entries.len(), 1);
+        assert_eq!(
+            header_entry.read_only_data_entries[0].data,
+            vec![0, 0, 0, 0]
+        );
+        assert_eq!(
+            header_entry.function_name_entries[0].ordinal,
+            NonZeroU16::new(3)
+        );
+
+        // the package-private data is kept with its visibility
+        assert_eq!(
+            header_entry.data_data_entries[1].visibility,
+            Visibility::Package
+        );
+
+        // the interface and the export surface are unchanged
+        let header_interface = extract_interface(&header_entry);
+        assert_eq!(header_interface.functions[0].public_index, 0);
+        assert_eq!(
+            header_interface.functions[0].abi_hash,
+            interface.functions[0].abi_hash
+        );
+        assert_eq!(header_interface.data, interface.data);
+
+        let diff = compare_export_surfaces(
+            &ModuleImage::read(&module_binary).unwrap(),
+            &ModuleImage::read(&header_binary).unwrap(),
+        );
+        assert_eq!(diff, ExportSurfaceDiff::default());
+    }
+}
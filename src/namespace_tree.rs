@@ -0,0 +1,324 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Reconstructs the namespace tree (i.e., the submodules) of a compiled module
+// from the full names in the "function name", "data name", "import function"
+// and "import data" sections.
+//
+// A full name consists of the module name, the namespace path and the
+// identifier, e.g. "foo::math::sqrt". For example, the following full names:
+//
+// - "foo::add" (function)
+// - "foo::math::sqrt" (function)
+// - "foo::math::PI" (data)
+// - "bar::baz" (imported function)
+//
+// form the following tree:
+//
+// ```text
+// (root)
+// |-- bar
+// |   `-- baz
+// `-- foo
+//     |-- add
+//     `-- math
+//         |-- PI
+//         `-- sqrt
+// ```
+//
+// The children of the root node are the module names, i.e., the current module
+// and the imported modules. The child nodes and the items are sorted by name.
+
+use crate::{
+    module_image::{ModuleImage, Visibility},
+    public_index::PublicIndexSpace,
+    ImageError,
+};
+
+// The separator of the namespace path.
+const NAME_PATH_SEPARATOR: &str = "::";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NamespaceItemKind {
+    Function,
+    Data,
+    ImportFunction,
+    ImportData,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NamespaceItem {
+    // The identifier, i.e., the last segment of the full name, e.g. "sqrt".
+    pub name: String,
+
+    // e.g. "foo::math::sqrt".
+    pub full_name: String,
+    pub kind: NamespaceItemKind,
+
+    // The function public index or the data public index.
+    pub public_index: usize,
+
+    // `None` for the imported items.
+    pub visibility: Option<Visibility>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NamespaceNode {
+    // The last segment of the path, e.g. "math", it is empty for the root node.
+    pub name: String,
+
+    // e.g. "foo::math", it is empty for the root node.
+    pub path: String,
+
+    pub children: Vec<NamespaceNode>,
+    pub items: Vec<NamespaceItem>,
+}
+
+impl NamespaceNode {
+    fn new(name: &str, parent_path: &str) -> Self {
+        let path = if parent_path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}{}{}", parent_path, NAME_PATH_SEPARATOR, name)
+        };
+
+        Self {
+            name: name.to_owned(),
+            path,
+            children: vec![],
+            items: vec![],
+        }
+    }
+
+    /// Returns the descendant node of the specified relative path,
+    /// e.g. "foo::math" for the root node.
+    pub fn find_node(&self, path: &str) -> Option<&NamespaceNode> {
+        path.split(NAME_PATH_SEPARATOR)
+            .try_fold(self, |node, name| {
+                node.children.iter().find(|child| child.name == name)
+            })
+    }
+
+    /// Returns the number of the items of this node and all descendant nodes.
+    pub fn item_count(&self) -> usize {
+        self.items.len()
+            + self
+                .children
+                .iter()
+                .map(|child| child.item_count())
+                .sum::<usize>()
+    }
+
+    fn get_or_insert_child(&mut self, name: &str) -> &mut NamespaceNode {
+        let pos = match self
+            .children
+            .binary_search_by(|child| child.name.as_str().cmp(name))
+        {
+            Ok(pos) => pos,
+            Err(pos) => {
+                let child = NamespaceNode::new(name, &self.path);
+                self.children.insert(pos, child);
+                pos
+            }
+        };
+
+        &mut self.children[pos]
+    }
+
+    // Inserts the item into the node of its namespace path,
+    // the nodes are created if they do not exist.
+    fn insert_item(
+        &mut self,
+        full_name: &str,
+        kind: NamespaceItemKind,
+        public_index: usize,
+        visibility: Option<Visibility>,
+    ) {
+        let mut segments = full_name.split(NAME_PATH_SEPARATOR).collect::<Vec<&str>>();
+        let name = segments.pop().unwrap();
+
+        let node = segments
+            .into_iter()
+            .fold(self, |node, segment| node.get_or_insert_child(segment));
+
+        let pos = node
+            .items
+            .partition_point(|item| item.name.as_str() <= name);
+        node.items.insert(
+            pos,
+            NamespaceItem {
+                name: name.to_owned(),
+                full_name: full_name.to_owned(),
+                kind,
+                public_index,
+                visibility,
+            },
+        );
+    }
+}
+
+/// Builds the namespace tree of the module from the full names of
+/// the internal items and the imported items.
+///
+/// The children of the returned root node are the module names.
+pub fn build_namespace_tree(image: &ModuleImage) -> Result<NamespaceNode, ImageError> {
+    let public_index_space = PublicIndexSpace::from_module_image(image)?;
+    let mut root = NamespaceNode::default();
+
+    if let Some(import_function_section) = image.get_optional_import_function_section() {
+        for (function_public_index, entry) in import_function_section
+            .convert_to_entries()
+            .iter()
+            .enumerate()
+        {
+            root.insert_item(
+                &entry.full_name,
+                NamespaceItemKind::ImportFunction,
+                function_public_index,
+                None,
+            );
+        }
+    }
+
+    if let Some(import_data_section) = image.get_optional_import_data_section() {
+        for (data_public_index, entry) in
+            import_data_section.convert_to_entries().iter().enumerate()
+        {
+            root.insert_item(
+                &entry.full_name,
+                NamespaceItemKind::ImportData,
+                data_public_index,
+                None,
+            );
+        }
+    }
+
+    if let Some(function_name_section) = image.get_optional_export_function_section() {
+        for entry in function_name_section.convert_to_entries() {
+            root.insert_item(
+                &entry.full_name,
+                NamespaceItemKind::Function,
+                public_index_space.function_public_index(entry.internal_index),
+                Some(entry.visibility),
+            );
+        }
+    }
+
+    if let Some(data_name_section) = image.get_optional_export_data_section() {
+        for entry in data_name_section.convert_to_entries() {
+            root.insert_item(
+                &entry.full_name,
+                NamespaceItemKind::Data,
+                public_index_space
+                    .data_public_index(entry.section_type, entry.internal_index_in_section),
+                Some(entry.visibility),
+            );
+        }
+    }
+
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{DataSectionType, MemoryDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            DataNameEntry, ImportDataEntry, ImportFunctionEntry, ReadOnlyDataEntry, TypeEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_object_file},
+        module_image::{ModuleImage, Visibility},
+        namespace_tree::{build_namespace_tree, NamespaceItem, NamespaceItemKind},
+    };
+
+    #[test]
+    fn test_build_namespace_tree() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("math::sqrt", TypeEntry::new(vec![], vec![])),
+                ("add", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry
+            .import_function_entries
+            .push(ImportFunctionEntry::new("bar::baz".to_owned(), 0, 0));
+        image_common_entry
+            .import_data_entries
+            .push(ImportDataEntry::new(
+                "bar::config::level".to_owned(),
+                0,
+                DataSectionType::ReadOnly,
+                MemoryDataType::I32,
+            ));
+        image_common_entry
+            .read_only_data_entries
+            .push(ReadOnlyDataEntry::from_i32(3));
+        image_common_entry
+            .data_data_entries
+            .push(DataNameEntry::new(
+                "foo::math::PI".to_owned(),
+                Visibility::Private,
+                DataSectionType::ReadOnly,
+                0,
+            ));
+
+        let mut module_binary: Vec<u8> = vec![];
+        write_object_file(&image_common_entry, true, &mut module_binary).unwrap();
+        let image = ModuleImage::read(&module_binary).unwrap();
+
+        let root = build_namespace_tree(&image).unwrap();
+
+        assert_eq!(
+            root.children
+                .iter()
+                .map(|child| child.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["bar", "foo"]
+        );
+        assert_eq!(root.item_count(), 5);
+
+        let math = root.find_node("foo::math").unwrap();
+        assert_eq!(math.path, "foo::math");
+        assert_eq!(
+            math.items,
+            vec![
+                NamespaceItem {
+                    name: "PI".to_owned(),
+                    full_name: "foo::math::PI".to_owned(),
+                    kind: NamespaceItemKind::Data,
+                    public_index: 1,
+                    visibility: Some(Visibility::Private),
+                },
+                NamespaceItem {
+                    name: "sqrt".to_owned(),
+                    full_name: "foo::math::sqrt".to_owned(),
+                    kind: NamespaceItemKind::Function,
+                    public_index: 1,
+                    visibility: Some(Visibility::Public),
+                },
+            ]
+        );
+
+        let foo = root.find_node("foo").unwrap();
+        assert_eq!(foo.items.len(), 1);
+        assert_eq!(foo.items[0].full_name, "foo::add");
+        assert_eq!(foo.items[0].public_index, 2);
+
+        let config = root.find_node("bar::config").unwrap();
+        assert_eq!(config.items[0].kind, NamespaceItemKind::ImportData);
+        assert_eq!(config.items[0].public_index, 0);
+
+        assert!(root.find_node("foo::add").is_none());
+        assert!(root.find_node("baz").is_none());
+    }
+}
@@ -0,0 +1,396 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Embeds module images in the native object files, so that the images
+// can ride inside native executables (e.g., launchers), and be located by
+// the standard tools (e.g., `objdump -h`, `objcopy --dump-section`).
+//
+// Two containers are supported:
+//
+// - ELF: a relocatable object file (ELF64, little-endian) which contains
+//   the image in a non-allocated `PROGBITS` section and the section name table.
+// - COFF: an object file for the PE toolchains which contains the image
+//   in an initialized, read-only data section. Note that the section name
+//   of a COFF object file can not exceed 8 bytes (without a string table).
+//
+// The object files can be linked into executables by the native linkers.
+// The `extract_*` functions locate the section by name from the object files
+// as well as the linked executables (for PE, the DOS stub is skipped).
+//
+// Only the little-endian 64-bit ELF is supported for extracting.
+//
+// References:
+// - https://refspecs.linuxfoundation.org/elf/gabi4+/ch4.eheader.html
+// - https://learn.microsoft.com/en-us/windows/win32/debug/pe-format
+
+// The default name of the section which contains the module image.
+pub const MODULE_IMAGE_SECTION_NAME: &str = ".ancm";
+
+pub const ELF_MACHINE_X86_64: u16 = 62;
+pub const ELF_MACHINE_AARCH64: u16 = 183;
+pub const ELF_MACHINE_RISCV: u16 = 243;
+
+pub const COFF_MACHINE_AMD64: u16 = 0x8664;
+pub const COFF_MACHINE_ARM64: u16 = 0xaa64;
+
+const ELF_MAGIC_NUMBER: &[u8; 4] = b"\x7fELF";
+const ELF_HEADER_LENGTH: usize = 64;
+const ELF_SECTION_HEADER_LENGTH: usize = 64;
+const ELF_CLASS_64: u8 = 2;
+const ELF_DATA_LITTLE_ENDIAN: u8 = 1;
+const ELF_TYPE_RELOCATABLE: u16 = 1;
+const ELF_SECTION_TYPE_PROGBITS: u32 = 1;
+const ELF_SECTION_TYPE_STRTAB: u32 = 3;
+
+const COFF_FILE_HEADER_LENGTH: usize = 20;
+const COFF_SECTION_HEADER_LENGTH: usize = 40;
+const COFF_SECTION_NAME_LENGTH: usize = 8;
+
+// IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_ALIGN_8BYTES | IMAGE_SCN_MEM_READ
+const COFF_SECTION_CHARACTERISTICS: u32 = 0x0000_0040 | 0x0040_0000 | 0x4000_0000;
+
+/// Builds an ELF relocatable object file which contains the module image
+/// in the specified section.
+pub fn wrap_in_elf(image_binary: &[u8], section_name: &str, machine: u16) -> Vec<u8> {
+    // The section name table: "\0{section_name}\0.shstrtab\0"
+    let mut names_data: Vec<u8> = vec![0];
+    let section_name_offset = names_data.len() as u32;
+    names_data.extend_from_slice(section_name.as_bytes());
+    names_data.push(0);
+    let names_section_name_offset = names_data.len() as u32;
+    names_data.extend_from_slice(b".shstrtab\0");
+
+    let image_offset = ELF_HEADER_LENGTH;
+    let names_offset = image_offset + image_binary.len();
+    let section_headers_offset = (names_offset + names_data.len()).next_multiple_of(8);
+
+    let mut binary: Vec<u8> =
+        Vec::with_capacity(section_headers_offset + ELF_SECTION_HEADER_LENGTH * 3);
+
+    // ELF header
+    binary.extend_from_slice(ELF_MAGIC_NUMBER);
+    binary.push(ELF_CLASS_64);
+    binary.push(ELF_DATA_LITTLE_ENDIAN);
+    binary.push(1); // EI_VERSION
+    binary.resize(16, 0); // EI_OSABI, EI_ABIVERSION and padding
+    binary.extend_from_slice(&ELF_TYPE_RELOCATABLE.to_le_bytes());
+    binary.extend_from_slice(&machine.to_le_bytes());
+    binary.extend_from_slice(&1u32.to_le_bytes()); // e_version
+    binary.extend_from_slice(&0u64.to_le_bytes()); // e_entry
+    binary.extend_from_slice(&0u64.to_le_bytes()); // e_phoff
+    binary.extend_from_slice(&(section_headers_offset as u64).to_le_bytes()); // e_shoff
+    binary.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    binary.extend_from_slice(&(ELF_HEADER_LENGTH as u16).to_le_bytes()); // e_ehsize
+    binary.extend_from_slice(&0u16.to_le_bytes()); // e_phentsize
+    binary.extend_from_slice(&0u16.to_le_bytes()); // e_phnum
+    binary.extend_from_slice(&(ELF_SECTION_HEADER_LENGTH as u16).to_le_bytes()); // e_shentsize
+    binary.extend_from_slice(&3u16.to_le_bytes()); // e_shnum
+    binary.extend_from_slice(&2u16.to_le_bytes()); // e_shstrndx
+
+    // Section data
+    binary.extend_from_slice(image_binary);
+    binary.extend_from_slice(&names_data);
+    binary.resize(section_headers_offset, 0);
+
+    // Section headers: null, the image and the section name table.
+    binary.extend_from_slice(&[0u8; ELF_SECTION_HEADER_LENGTH]);
+    write_elf_section_header(
+        &mut binary,
+        section_name_offset,
+        ELF_SECTION_TYPE_PROGBITS,
+        image_offset,
+        image_binary.len(),
+        8,
+    );
+    write_elf_section_header(
+        &mut binary,
+        names_section_name_offset,
+        ELF_SECTION_TYPE_STRTAB,
+        names_offset,
+        names_data.len(),
+        1,
+    );
+
+    binary
+}
+
+fn write_elf_section_header(
+    binary: &mut Vec<u8>,
+    name_offset: u32,
+    section_type: u32,
+    offset: usize,
+    size: usize,
+    align: u64,
+) {
+    binary.extend_from_slice(&name_offset.to_le_bytes()); // sh_name
+    binary.extend_from_slice(&section_type.to_le_bytes()); // sh_type
+    binary.extend_from_slice(&0u64.to_le_bytes()); // sh_flags
+    binary.extend_from_slice(&0u64.to_le_bytes()); // sh_addr
+    binary.extend_from_slice(&(offset as u64).to_le_bytes()); // sh_offset
+    binary.extend_from_slice(&(size as u64).to_le_bytes()); // sh_size
+    binary.extend_from_slice(&0u32.to_le_bytes()); // sh_link
+    binary.extend_from_slice(&0u32.to_le_bytes()); // sh_info
+    binary.extend_from_slice(&align.to_le_bytes()); // sh_addralign
+    binary.extend_from_slice(&0u64.to_le_bytes()); // sh_entsize
+}
+
+/// Finds the section with the specified name in an ELF file (object file or executable),
+/// returns the section data.
+pub fn extract_from_elf<'a>(elf_binary: &'a [u8], section_name: &str) -> Option<&'a [u8]> {
+    let magic_slice = elf_binary.get(0..4)?;
+    if magic_slice != ELF_MAGIC_NUMBER
+        || elf_binary.get(4) != Some(&ELF_CLASS_64)
+        || elf_binary.get(5) != Some(&ELF_DATA_LITTLE_ENDIAN)
+    {
+        return None;
+    }
+
+    let section_headers_offset = read_u64(elf_binary, 0x28)? as usize;
+    let section_header_length = read_u16(elf_binary, 0x3a)? as usize;
+    let section_count = read_u16(elf_binary, 0x3c)? as usize;
+    let names_section_index = read_u16(elf_binary, 0x3e)? as usize;
+
+    // Returns `(name offset, data offset, data size)` of the section header.
+    //
+    // The offset of the section headers is read from the file,
+    // so the arithmetic is checked.
+    let read_section_header = |index: usize| {
+        let header_offset =
+            section_headers_offset.checked_add(index.checked_mul(section_header_length)?)?;
+        Some((
+            read_u32(elf_binary, header_offset)? as usize,
+            read_u64(elf_binary, header_offset.checked_add(0x18)?)? as usize,
+            read_u64(elf_binary, header_offset.checked_add(0x20)?)? as usize,
+        ))
+    };
+
+    let (_, names_offset, names_size) = read_section_header(names_section_index)?;
+    let names_data = elf_binary.get(names_offset..names_offset.checked_add(names_size)?)?;
+
+    (0..section_count).find_map(|index| {
+        let (name_offset, offset, size) = read_section_header(index)?;
+        let name_data = names_data.get(name_offset..)?;
+        let name_length = name_data.iter().position(|byte| *byte == 0)?;
+
+        if &name_data[..name_length] == section_name.as_bytes() {
+            elf_binary.get(offset..offset.checked_add(size)?)
+        } else {
+            None
+        }
+    })
+}
+
+/// Builds a COFF object file which contains the module image in the specified section.
+///
+/// Returns `None` if the section name is longer than 8 bytes.
+pub fn wrap_in_coff(image_binary: &[u8], section_name: &str, machine: u16) -> Option<Vec<u8>> {
+    if section_name.len() > COFF_SECTION_NAME_LENGTH {
+        return None;
+    }
+
+    let image_offset = COFF_FILE_HEADER_LENGTH + COFF_SECTION_HEADER_LENGTH;
+    let mut binary: Vec<u8> = Vec::with_capacity(image_offset + image_binary.len());
+
+    // File header
+    binary.extend_from_slice(&machine.to_le_bytes());
+    binary.extend_from_slice(&1u16.to_le_bytes()); // NumberOfSections
+    binary.extend_from_slice(&0u32.to_le_bytes()); // TimeDateStamp
+    binary.extend_from_slice(&0u32.to_le_bytes()); // PointerToSymbolTable
+    binary.extend_from_slice(&0u32.to_le_bytes()); // NumberOfSymbols
+    binary.extend_from_slice(&0u16.to_le_bytes()); // SizeOfOptionalHeader
+    binary.extend_from_slice(&0u16.to_le_bytes()); // Characteristics
+
+    // Section header
+    let mut name_data = [0u8; COFF_SECTION_NAME_LENGTH];
+    name_data[..section_name.len()].copy_from_slice(section_name.as_bytes());
+    binary.extend_from_slice(&name_data);
+    binary.extend_from_slice(&0u32.to_le_bytes()); // VirtualSize
+    binary.extend_from_slice(&0u32.to_le_bytes()); // VirtualAddress
+    binary.extend_from_slice(&(image_binary.len() as u32).to_le_bytes()); // SizeOfRawData
+    binary.extend_from_slice(&(image_offset as u32).to_le_bytes()); // PointerToRawData
+    binary.extend_from_slice(&0u32.to_le_bytes()); // PointerToRelocations
+    binary.extend_from_slice(&0u32.to_le_bytes()); // PointerToLinenumbers
+    binary.extend_from_slice(&0u16.to_le_bytes()); // NumberOfRelocations
+    binary.extend_from_slice(&0u16.to_le_bytes()); // NumberOfLinenumbers
+    binary.extend_from_slice(&COFF_SECTION_CHARACTERISTICS.to_le_bytes());
+
+    // Section data
+    binary.extend_from_slice(image_binary);
+
+    Some(binary)
+}
+
+/// Finds the section with the specified name in a COFF object file or a PE executable,
+/// returns the section data.
+///
+/// Note: the section data of a PE executable may be padded with zeros
+/// to the file alignment if the virtual size is absent.
+pub fn extract_from_coff<'a>(coff_binary: &'a [u8], section_name: &str) -> Option<&'a [u8]> {
+    // The PE executable starts with the DOS stub, the offset of
+    // the signature "PE\0\0" is at the offset 0x3c.
+    let file_header_offset = if coff_binary.get(0..2)? == b"MZ" {
+        // The offset of the signature is read from the file,
+        // so the arithmetic is checked.
+        let signature_offset = read_u32(coff_binary, 0x3c)? as usize;
+        let file_header_offset = signature_offset.checked_add(4)?;
+        let signature_slice = coff_binary.get(signature_offset..file_header_offset)?;
+        if signature_slice != b"PE\0\0" {
+            return None;
+        }
+        file_header_offset
+    } else {
+        0
+    };
+
+    let section_count = read_u16(coff_binary, file_header_offset + 2)? as usize;
+    let optional_header_length = read_u16(coff_binary, file_header_offset + 16)? as usize;
+    let section_headers_offset =
+        file_header_offset + COFF_FILE_HEADER_LENGTH + optional_header_length;
+
+    (0..section_count).find_map(|index| {
+        let header_offset = section_headers_offset + index * COFF_SECTION_HEADER_LENGTH;
+        let name_data = coff_binary.get(header_offset..header_offset + COFF_SECTION_NAME_LENGTH)?;
+        let name_length = name_data
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(COFF_SECTION_NAME_LENGTH);
+
+        if &name_data[..name_length] != section_name.as_bytes() {
+            return None;
+        }
+
+        let virtual_size = read_u32(coff_binary, header_offset + 8)? as usize;
+        let raw_data_size = read_u32(coff_binary, header_offset + 16)? as usize;
+        let raw_data_offset = read_u32(coff_binary, header_offset + 20)? as usize;
+
+        // The virtual size is the actual size of the section data in the executables,
+        // and it is zero in the object files.
+        let size = if virtual_size != 0 && virtual_size < raw_data_size {
+            virtual_size
+        } else {
+            raw_data_size
+        };
+
+        coff_binary.get(raw_data_offset..raw_data_offset.checked_add(size)?)
+    })
+}
+
+fn read_u16(binary: &[u8], offset: usize) -> Option<u16> {
+    binary
+        .get(offset..offset.checked_add(2)?)
+        .map(|data| u16::from_le_bytes(data.try_into().unwrap()))
+}
+
+fn read_u32(binary: &[u8], offset: usize) -> Option<u32> {
+    binary
+        .get(offset..offset.checked_add(4)?)
+        .map(|data| u32::from_le_bytes(data.try_into().unwrap()))
+}
+
+fn read_u64(binary: &[u8], offset: usize) -> Option<u64> {
+    binary
+        .get(offset..offset.checked_add(8)?)
+        .map(|data| u64::from_le_bytes(data.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry_writer::build_minimal_module,
+        module_image::ModuleImage,
+        native_container::{
+            extract_from_coff, extract_from_elf, wrap_in_coff, wrap_in_elf, COFF_MACHINE_AMD64,
+            ELF_MACHINE_X86_64, MODULE_IMAGE_SECTION_NAME,
+        },
+    };
+
+    #[test]
+    fn test_elf_container() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let elf_binary = wrap_in_elf(&image_binary, MODULE_IMAGE_SECTION_NAME, ELF_MACHINE_X86_64);
+
+        assert_eq!(&elf_binary[0..4], b"\x7fELF");
+
+        let extracted = extract_from_elf(&elf_binary, MODULE_IMAGE_SECTION_NAME).unwrap();
+        assert_eq!(extracted, image_binary.as_slice());
+        assert!(ModuleImage::read(extracted).is_ok());
+
+        assert_eq!(
+            extract_from_elf(&elf_binary, ".shstrtab"),
+            Some(b"\0.ancm\0.shstrtab\0".as_slice())
+        );
+        assert_eq!(extract_from_elf(&elf_binary, ".text"), None);
+        assert_eq!(
+            extract_from_elf(&image_binary, MODULE_IMAGE_SECTION_NAME),
+            None
+        );
+        assert_eq!(
+            extract_from_elf(&elf_binary[..100], MODULE_IMAGE_SECTION_NAME),
+            None
+        );
+
+        // crafted section header offset and length which overflow
+        let mut crafted_binary = elf_binary.clone();
+        crafted_binary[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            extract_from_elf(&crafted_binary, MODULE_IMAGE_SECTION_NAME),
+            None
+        );
+
+        let mut crafted_binary = elf_binary.clone();
+        crafted_binary[0x28..0x30].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+        crafted_binary[0x3a..0x3c].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert_eq!(
+            extract_from_elf(&crafted_binary, MODULE_IMAGE_SECTION_NAME),
+            None
+        );
+    }
+
+    #[test]
+    fn test_coff_container() {
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let coff_binary =
+            wrap_in_coff(&image_binary, MODULE_IMAGE_SECTION_NAME, COFF_MACHINE_AMD64).unwrap();
+
+        let extracted = extract_from_coff(&coff_binary, MODULE_IMAGE_SECTION_NAME).unwrap();
+        assert_eq!(extracted, image_binary.as_slice());
+        assert_eq!(extract_from_coff(&coff_binary, ".text"), None);
+
+        // the section name is too long
+        assert_eq!(
+            wrap_in_coff(&image_binary, ".xiaoxuan_module", COFF_MACHINE_AMD64),
+            None
+        );
+
+        // an executable, i.e., the DOS stub and the PE signature precede the file header
+        let stub_length = 0x40 + 4;
+        let mut pe_binary = vec![0u8; 0x40];
+        pe_binary[0..2].copy_from_slice(b"MZ");
+        pe_binary[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        pe_binary.extend_from_slice(b"PE\0\0");
+        pe_binary.extend_from_slice(&coff_binary);
+
+        // patch the `PointerToRawData` and set the `VirtualSize`
+        let section_header_offset = stub_length + 20;
+        let raw_data_offset = (stub_length + 20 + 40) as u32;
+        pe_binary[(section_header_offset + 20)..(section_header_offset + 24)]
+            .copy_from_slice(&raw_data_offset.to_le_bytes());
+        pe_binary[(section_header_offset + 8)..(section_header_offset + 12)]
+            .copy_from_slice(&((image_binary.len() - 8) as u32).to_le_bytes());
+
+        let extracted = extract_from_coff(&pe_binary, MODULE_IMAGE_SECTION_NAME).unwrap();
+        assert_eq!(extracted, &image_binary[..image_binary.len() - 8]);
+
+        // the offset of the PE signature is out of bounds
+        pe_binary[0x3c..0x40].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            extract_from_coff(&pe_binary, MODULE_IMAGE_SECTION_NAME),
+            None
+        );
+    }
+}
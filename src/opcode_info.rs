@@ -0,0 +1,857 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The metadata of the opcodes, i.e., the category, the operands (the parameters
+// which are encoded in the instruction) and the encoded length of each opcode.
+//
+// The instructions are encoded as the following layouts:
+//
+// - `[opcode]`, 16 bits.
+// - `[opcode + i16]`, 32 bits.
+// - `[opcode + padding + i32]`, 64 bits.
+// - `[opcode + i16 + i32]`, 64 bits.
+// - `[opcode + padding + i32 + i32]`, 96 bits.
+// - `[opcode + padding + i32 + i32 + i32]`, 128 bits.
+//
+// i.e., an `i16` operand (if there is one) immediately follows the opcode,
+// otherwise the `i32` operands are preceded by 16 bits of padding, so that
+// they are 4-byte aligned.
+//
+// The decoder (`bytecode_reader::decode_instruction`) and the relocate coverage
+// checker (`relocate_coverage`) are built on top of this table.
+//
+// `ALL_OPCODES` lists the same opcodes as the table, it is used for looking up
+// the opcodes by name, e.g. by the assembler of `image_manifest`, and for building
+// the lookup table of the opcodes by the encoded value, which is used by the decoder.
+
+use anc_isa::opcode::Opcode;
+
+use crate::module_image::RelocateType;
+
+// The length of the opcode, in bytes.
+pub const OPCODE_LENGTH: usize = 2;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OpcodeCategory {
+    Fundamental,
+    LocalVariable,
+    Data,
+    Arithmetic,
+    Bitwise,
+    Math,
+    Conversion,
+    Comparison,
+    ControlFlow,
+    Memory,
+    Machine,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OperandKind {
+    // i32, the immediate number of `imm_i32` and `imm_f32`,
+    // or the low/high 32 bits of `imm_i64` and `imm_f64`.
+    Immediate,
+
+    // i16, the layers of the local variable or the target block.
+    Layers,
+
+    // i32
+    LocalVariableIndex,
+
+    // i16, the offset in bytes within the data.
+    DataOffset,
+
+    // i32, relocatable.
+    DataPublicIndex,
+
+    // i16, the amount of `add_imm_*` and `sub_imm_*`.
+    Amount,
+
+    // i32, relocatable.
+    TypeIndex,
+
+    // i32, relocatable.
+    LocalVariableListIndex,
+
+    // i32, the offset (in bytes) from the instruction to the jump target,
+    // e.g. the instruction next to the block or the alternative branch.
+    JumpOffset,
+
+    // i32, relocatable.
+    FunctionPublicIndex,
+
+    // i32, relocatable.
+    ExternalFunctionIndex,
+
+    // i32, the number of the environment call.
+    EnvCallNumber,
+
+    // i32, the code of `terminate`.
+    TerminateCode,
+}
+
+impl OperandKind {
+    /// Returns the size of the operand in bytes, i.e., `2` or `4`.
+    pub fn size(&self) -> usize {
+        match self {
+            OperandKind::Layers | OperandKind::DataOffset | OperandKind::Amount => 2,
+            _ => 4,
+        }
+    }
+
+    /// Returns the relocate type if the operand is relocatable, see `RelocateEntry`.
+    pub fn relocate_type(&self) -> Option<RelocateType> {
+        match self {
+            OperandKind::TypeIndex => Some(RelocateType::TypeIndex),
+            OperandKind::LocalVariableListIndex => Some(RelocateType::LocalVariableListIndex),
+            OperandKind::FunctionPublicIndex => Some(RelocateType::FunctionPublicIndex),
+            OperandKind::ExternalFunctionIndex => Some(RelocateType::ExternalFunctionIndex),
+            OperandKind::DataPublicIndex => Some(RelocateType::DataPublicIndex),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OpcodeInfo {
+    pub category: OpcodeCategory,
+    pub operands: &'static [OperandKind],
+}
+
+impl OpcodeInfo {
+    const fn new(category: OpcodeCategory, operands: &'static [OperandKind]) -> Self {
+        Self { category, operands }
+    }
+
+    /// Returns the encoded length of the instruction in bytes,
+    /// the opcode and the padding are included.
+    pub fn length(&self) -> usize {
+        match self.operands.last() {
+            Some(operand) => self.operand_offset(self.operands.len() - 1) + operand.size(),
+            None => OPCODE_LENGTH,
+        }
+    }
+
+    /// Returns the offset of the specified operand within the instruction.
+    pub fn operand_offset(&self, operand_index: usize) -> usize {
+        let mut offset = OPCODE_LENGTH;
+        for operand in &self.operands[..operand_index] {
+            offset += operand.size();
+        }
+
+        // the `i32` operands are 4-byte aligned.
+        if self.operands[operand_index].size() == 4 {
+            offset = offset.next_multiple_of(4);
+        }
+        offset
+    }
+}
+
+// The operand lists.
+const NO_OPERANDS: &[OperandKind] = &[];
+const IMM_32_OPERANDS: &[OperandKind] = &[OperandKind::Immediate];
+const IMM_64_OPERANDS: &[OperandKind] = &[OperandKind::Immediate, OperandKind::Immediate];
+const LOCAL_OPERANDS: &[OperandKind] = &[OperandKind::Layers, OperandKind::LocalVariableIndex];
+const DATA_OPERANDS: &[OperandKind] = &[OperandKind::DataOffset, OperandKind::DataPublicIndex];
+const DATA_EXTEND_OPERANDS: &[OperandKind] = &[OperandKind::DataPublicIndex];
+const AMOUNT_OPERANDS: &[OperandKind] = &[OperandKind::Amount];
+const BLOCK_OPERANDS: &[OperandKind] =
+    &[OperandKind::TypeIndex, OperandKind::LocalVariableListIndex];
+const BREAK_OPERANDS: &[OperandKind] = &[OperandKind::Layers, OperandKind::JumpOffset];
+const BLOCK_ALT_OPERANDS: &[OperandKind] = &[
+    OperandKind::TypeIndex,
+    OperandKind::LocalVariableListIndex,
+    OperandKind::JumpOffset,
+];
+const BREAK_ALT_OPERANDS: &[OperandKind] = &[OperandKind::JumpOffset];
+const BLOCK_NEZ_OPERANDS: &[OperandKind] =
+    &[OperandKind::LocalVariableListIndex, OperandKind::JumpOffset];
+const FUNCTION_OPERANDS: &[OperandKind] = &[OperandKind::FunctionPublicIndex];
+const EXTERNAL_FUNCTION_OPERANDS: &[OperandKind] = &[OperandKind::ExternalFunctionIndex];
+const ENVCALL_OPERANDS: &[OperandKind] = &[OperandKind::EnvCallNumber];
+const TERMINATE_OPERANDS: &[OperandKind] = &[OperandKind::TerminateCode];
+
+// The table entries.
+static FUNDAMENTAL: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Fundamental, NO_OPERANDS);
+static FUNDAMENTAL_IMM_32: OpcodeInfo =
+    OpcodeInfo::new(OpcodeCategory::Fundamental, IMM_32_OPERANDS);
+static FUNDAMENTAL_IMM_64: OpcodeInfo =
+    OpcodeInfo::new(OpcodeCategory::Fundamental, IMM_64_OPERANDS);
+static LOCAL_VARIABLE: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::LocalVariable, LOCAL_OPERANDS);
+static DATA: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Data, DATA_OPERANDS);
+static DATA_EXTEND: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Data, DATA_EXTEND_OPERANDS);
+static DATA_DYNAMIC: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Data, NO_OPERANDS);
+static ARITHMETIC: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Arithmetic, NO_OPERANDS);
+static ARITHMETIC_IMM: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Arithmetic, AMOUNT_OPERANDS);
+static BITWISE: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Bitwise, NO_OPERANDS);
+static MATH: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Math, NO_OPERANDS);
+static CONVERSION: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Conversion, NO_OPERANDS);
+static COMPARISON: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Comparison, NO_OPERANDS);
+static CONTROL_FLOW: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, NO_OPERANDS);
+static BLOCK: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, BLOCK_OPERANDS);
+static BREAK: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, BREAK_OPERANDS);
+static BLOCK_ALT: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, BLOCK_ALT_OPERANDS);
+static BREAK_ALT: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, BREAK_ALT_OPERANDS);
+static BLOCK_NEZ: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, BLOCK_NEZ_OPERANDS);
+static CALL: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, FUNCTION_OPERANDS);
+static ENVCALL: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::ControlFlow, ENVCALL_OPERANDS);
+static EXTCALL: OpcodeInfo =
+    OpcodeInfo::new(OpcodeCategory::ControlFlow, EXTERNAL_FUNCTION_OPERANDS);
+static MEMORY: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Memory, NO_OPERANDS);
+static MACHINE: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Machine, NO_OPERANDS);
+static TERMINATE: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Machine, TERMINATE_OPERANDS);
+static MACHINE_FUNCTION: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Machine, FUNCTION_OPERANDS);
+static MACHINE_DATA: OpcodeInfo = OpcodeInfo::new(OpcodeCategory::Machine, DATA_OPERANDS);
+static MACHINE_DATA_EXTEND: OpcodeInfo =
+    OpcodeInfo::new(OpcodeCategory::Machine, DATA_EXTEND_OPERANDS);
+
+/// Returns the metadata of the opcode.
+pub fn get_opcode_info(opcode: Opcode) -> &'static OpcodeInfo {
+    match opcode {
+        // Category: Fundamental
+        Opcode::nop => &FUNDAMENTAL,
+        Opcode::imm_i32 | Opcode::imm_f32 => &FUNDAMENTAL_IMM_32,
+        Opcode::imm_i64 | Opcode::imm_f64 => &FUNDAMENTAL_IMM_64,
+        // Category: Local Variables
+        Opcode::local_load_i64
+        | Opcode::local_load_i32_s
+        | Opcode::local_load_i32_u
+        | Opcode::local_load_i16_s
+        | Opcode::local_load_i16_u
+        | Opcode::local_load_i8_s
+        | Opcode::local_load_i8_u
+        | Opcode::local_load_f64
+        | Opcode::local_load_f32
+        | Opcode::local_store_i64
+        | Opcode::local_store_i32
+        | Opcode::local_store_i16
+        | Opcode::local_store_i8
+        | Opcode::local_store_f64
+        | Opcode::local_store_f32 => &LOCAL_VARIABLE,
+        // Category: Data
+        Opcode::data_load_i64
+        | Opcode::data_load_i32_s
+        | Opcode::data_load_i32_u
+        | Opcode::data_load_i16_s
+        | Opcode::data_load_i16_u
+        | Opcode::data_load_i8_s
+        | Opcode::data_load_i8_u
+        | Opcode::data_load_f64
+        | Opcode::data_load_f32
+        | Opcode::data_store_i64
+        | Opcode::data_store_i32
+        | Opcode::data_store_i16
+        | Opcode::data_store_i8
+        | Opcode::data_store_f64
+        | Opcode::data_store_f32 => &DATA,
+        Opcode::data_load_extend_i64
+        | Opcode::data_load_extend_i32_s
+        | Opcode::data_load_extend_i32_u
+        | Opcode::data_load_extend_i16_s
+        | Opcode::data_load_extend_i16_u
+        | Opcode::data_load_extend_i8_s
+        | Opcode::data_load_extend_i8_u
+        | Opcode::data_load_extend_f64
+        | Opcode::data_load_extend_f32
+        | Opcode::data_store_extend_i64
+        | Opcode::data_store_extend_i32
+        | Opcode::data_store_extend_i16
+        | Opcode::data_store_extend_i8
+        | Opcode::data_store_extend_f64
+        | Opcode::data_store_extend_f32 => &DATA_EXTEND,
+        Opcode::data_load_dynamic_i64
+        | Opcode::data_load_dynamic_i32_s
+        | Opcode::data_load_dynamic_i32_u
+        | Opcode::data_load_dynamic_i16_s
+        | Opcode::data_load_dynamic_i16_u
+        | Opcode::data_load_dynamic_i8_s
+        | Opcode::data_load_dynamic_i8_u
+        | Opcode::data_load_dynamic_f64
+        | Opcode::data_load_dynamic_f32
+        | Opcode::data_store_dynamic_i64
+        | Opcode::data_store_dynamic_i32
+        | Opcode::data_store_dynamic_i16
+        | Opcode::data_store_dynamic_i8
+        | Opcode::data_store_dynamic_f64
+        | Opcode::data_store_dynamic_f32 => &DATA_DYNAMIC,
+        // Category: Arithmetic
+        Opcode::add_i32
+        | Opcode::sub_i32
+        | Opcode::mul_i32
+        | Opcode::div_i32_s
+        | Opcode::div_i32_u
+        | Opcode::rem_i32_s
+        | Opcode::rem_i32_u
+        | Opcode::add_i64
+        | Opcode::sub_i64
+        | Opcode::mul_i64
+        | Opcode::div_i64_s
+        | Opcode::div_i64_u
+        | Opcode::rem_i64_s
+        | Opcode::rem_i64_u
+        | Opcode::add_f32
+        | Opcode::sub_f32
+        | Opcode::mul_f32
+        | Opcode::div_f32
+        | Opcode::add_f64
+        | Opcode::sub_f64
+        | Opcode::mul_f64
+        | Opcode::div_f64 => &ARITHMETIC,
+        Opcode::add_imm_i32 | Opcode::sub_imm_i32 | Opcode::add_imm_i64 | Opcode::sub_imm_i64 => {
+            &ARITHMETIC_IMM
+        }
+        // Category: Bitwise
+        Opcode::and
+        | Opcode::or
+        | Opcode::xor
+        | Opcode::not
+        | Opcode::count_leading_zeros_i32
+        | Opcode::count_leading_ones_i32
+        | Opcode::count_trailing_zeros_i32
+        | Opcode::count_ones_i32
+        | Opcode::shift_left_i32
+        | Opcode::shift_right_i32_s
+        | Opcode::shift_right_i32_u
+        | Opcode::rotate_left_i32
+        | Opcode::rotate_right_i32
+        | Opcode::count_leading_zeros_i64
+        | Opcode::count_leading_ones_i64
+        | Opcode::count_trailing_zeros_i64
+        | Opcode::count_ones_i64
+        | Opcode::shift_left_i64
+        | Opcode::shift_right_i64_s
+        | Opcode::shift_right_i64_u
+        | Opcode::rotate_left_i64
+        | Opcode::rotate_right_i64 => &BITWISE,
+        // Category: Math
+        Opcode::abs_i32
+        | Opcode::neg_i32
+        | Opcode::abs_i64
+        | Opcode::neg_i64
+        | Opcode::abs_f32
+        | Opcode::neg_f32
+        | Opcode::copysign_f32
+        | Opcode::sqrt_f32
+        | Opcode::min_f32
+        | Opcode::max_f32
+        | Opcode::ceil_f32
+        | Opcode::floor_f32
+        | Opcode::round_half_away_from_zero_f32
+        | Opcode::round_half_to_even_f32
+        | Opcode::trunc_f32
+        | Opcode::fract_f32
+        | Opcode::cbrt_f32
+        | Opcode::exp_f32
+        | Opcode::exp2_f32
+        | Opcode::ln_f32
+        | Opcode::log2_f32
+        | Opcode::log10_f32
+        | Opcode::sin_f32
+        | Opcode::cos_f32
+        | Opcode::tan_f32
+        | Opcode::asin_f32
+        | Opcode::acos_f32
+        | Opcode::atan_f32
+        | Opcode::pow_f32
+        | Opcode::log_f32
+        | Opcode::abs_f64
+        | Opcode::neg_f64
+        | Opcode::copysign_f64
+        | Opcode::sqrt_f64
+        | Opcode::min_f64
+        | Opcode::max_f64
+        | Opcode::ceil_f64
+        | Opcode::floor_f64
+        | Opcode::round_half_away_from_zero_f64
+        | Opcode::round_half_to_even_f64
+        | Opcode::trunc_f64
+        | Opcode::fract_f64
+        | Opcode::cbrt_f64
+        | Opcode::exp_f64
+        | Opcode::exp2_f64
+        | Opcode::ln_f64
+        | Opcode::log2_f64
+        | Opcode::log10_f64
+        | Opcode::sin_f64
+        | Opcode::cos_f64
+        | Opcode::tan_f64
+        | Opcode::asin_f64
+        | Opcode::acos_f64
+        | Opcode::atan_f64
+        | Opcode::pow_f64
+        | Opcode::log_f64 => &MATH,
+        // Category: Conversion
+        Opcode::truncate_i64_to_i32
+        | Opcode::extend_i32_s_to_i64
+        | Opcode::extend_i32_u_to_i64
+        | Opcode::demote_f64_to_f32
+        | Opcode::promote_f32_to_f64
+        | Opcode::convert_f32_to_i32_s
+        | Opcode::convert_f32_to_i32_u
+        | Opcode::convert_f64_to_i32_s
+        | Opcode::convert_f64_to_i32_u
+        | Opcode::convert_f32_to_i64_s
+        | Opcode::convert_f32_to_i64_u
+        | Opcode::convert_f64_to_i64_s
+        | Opcode::convert_f64_to_i64_u
+        | Opcode::convert_i32_s_to_f32
+        | Opcode::convert_i32_u_to_f32
+        | Opcode::convert_i64_s_to_f32
+        | Opcode::convert_i64_u_to_f32
+        | Opcode::convert_i32_s_to_f64
+        | Opcode::convert_i32_u_to_f64
+        | Opcode::convert_i64_s_to_f64
+        | Opcode::convert_i64_u_to_f64 => &CONVERSION,
+        // Category: Comparison
+        Opcode::eqz_i32
+        | Opcode::nez_i32
+        | Opcode::eq_i32
+        | Opcode::ne_i32
+        | Opcode::lt_i32_s
+        | Opcode::lt_i32_u
+        | Opcode::gt_i32_s
+        | Opcode::gt_i32_u
+        | Opcode::le_i32_s
+        | Opcode::le_i32_u
+        | Opcode::ge_i32_s
+        | Opcode::ge_i32_u
+        | Opcode::eqz_i64
+        | Opcode::nez_i64
+        | Opcode::eq_i64
+        | Opcode::ne_i64
+        | Opcode::lt_i64_s
+        | Opcode::lt_i64_u
+        | Opcode::gt_i64_s
+        | Opcode::gt_i64_u
+        | Opcode::le_i64_s
+        | Opcode::le_i64_u
+        | Opcode::ge_i64_s
+        | Opcode::ge_i64_u
+        | Opcode::eq_f32
+        | Opcode::ne_f32
+        | Opcode::lt_f32
+        | Opcode::gt_f32
+        | Opcode::le_f32
+        | Opcode::ge_f32
+        | Opcode::eq_f64
+        | Opcode::ne_f64
+        | Opcode::lt_f64
+        | Opcode::gt_f64
+        | Opcode::le_f64
+        | Opcode::ge_f64 => &COMPARISON,
+        // Category: Control flow
+        Opcode::end => &CONTROL_FLOW,
+        Opcode::block => &BLOCK,
+        Opcode::break_ | Opcode::recur => &BREAK,
+        Opcode::block_alt => &BLOCK_ALT,
+        Opcode::break_alt => &BREAK_ALT,
+        Opcode::block_nez => &BLOCK_NEZ,
+        Opcode::call => &CALL,
+        Opcode::envcall => &ENVCALL,
+        Opcode::extcall => &EXTCALL,
+        Opcode::call_dynamic | Opcode::syscall => &CONTROL_FLOW,
+        // Category: Memory
+        Opcode::memory_allocate
+        | Opcode::memory_reallocate
+        | Opcode::memory_free
+        | Opcode::memory_fill
+        | Opcode::memory_copy => &MEMORY,
+        // Category: Machine
+        Opcode::terminate => &TERMINATE,
+        Opcode::get_function | Opcode::host_addr_function => &MACHINE_FUNCTION,
+        Opcode::get_data | Opcode::host_addr_data_extend => &MACHINE_DATA_EXTEND,
+        Opcode::host_addr_data => &MACHINE_DATA,
+        Opcode::host_addr_function_dynamic | Opcode::host_addr_data_dynamic => &MACHINE,
+    }
+}
+
+/// All opcodes, in the order of the categories.
+pub const ALL_OPCODES: &[Opcode] = &[
+    // Category: Fundamental
+    Opcode::nop,
+    Opcode::imm_i32,
+    Opcode::imm_f32,
+    Opcode::imm_i64,
+    Opcode::imm_f64,
+    // Category: Local Variables
+    Opcode::local_load_i64,
+    Opcode::local_load_i32_s,
+    Opcode::local_load_i32_u,
+    Opcode::local_load_i16_s,
+    Opcode::local_load_i16_u,
+    Opcode::local_load_i8_s,
+    Opcode::local_load_i8_u,
+    Opcode::local_load_f64,
+    Opcode::local_load_f32,
+    Opcode::local_store_i64,
+    Opcode::local_store_i32,
+    Opcode::local_store_i16,
+    Opcode::local_store_i8,
+    Opcode::local_store_f64,
+    Opcode::local_store_f32,
+    // Category: Data
+    Opcode::data_load_i64,
+    Opcode::data_load_i32_s,
+    Opcode::data_load_i32_u,
+    Opcode::data_load_i16_s,
+    Opcode::data_load_i16_u,
+    Opcode::data_load_i8_s,
+    Opcode::data_load_i8_u,
+    Opcode::data_load_f64,
+    Opcode::data_load_f32,
+    Opcode::data_store_i64,
+    Opcode::data_store_i32,
+    Opcode::data_store_i16,
+    Opcode::data_store_i8,
+    Opcode::data_store_f64,
+    Opcode::data_store_f32,
+    Opcode::data_load_extend_i64,
+    Opcode::data_load_extend_i32_s,
+    Opcode::data_load_extend_i32_u,
+    Opcode::data_load_extend_i16_s,
+    Opcode::data_load_extend_i16_u,
+    Opcode::data_load_extend_i8_s,
+    Opcode::data_load_extend_i8_u,
+    Opcode::data_load_extend_f64,
+    Opcode::data_load_extend_f32,
+    Opcode::data_store_extend_i64,
+    Opcode::data_store_extend_i32,
+    Opcode::data_store_extend_i16,
+    Opcode::data_store_extend_i8,
+    Opcode::data_store_extend_f64,
+    Opcode::data_store_extend_f32,
+    Opcode::data_load_dynamic_i64,
+    Opcode::data_load_dynamic_i32_s,
+    Opcode::data_load_dynamic_i32_u,
+    Opcode::data_load_dynamic_i16_s,
+    Opcode::data_load_dynamic_i16_u,
+    Opcode::data_load_dynamic_i8_s,
+    Opcode::data_load_dynamic_i8_u,
+    Opcode::data_load_dynamic_f64,
+    Opcode::data_load_dynamic_f32,
+    Opcode::data_store_dynamic_i64,
+    Opcode::data_store_dynamic_i32,
+    Opcode::data_store_dynamic_i16,
+    Opcode::data_store_dynamic_i8,
+    Opcode::data_store_dynamic_f64,
+    Opcode::data_store_dynamic_f32,
+    // Category: Arithmetic
+    Opcode::add_i32,
+    Opcode::sub_i32,
+    Opcode::mul_i32,
+    Opcode::div_i32_s,
+    Opcode::div_i32_u,
+    Opcode::rem_i32_s,
+    Opcode::rem_i32_u,
+    Opcode::add_i64,
+    Opcode::sub_i64,
+    Opcode::mul_i64,
+    Opcode::div_i64_s,
+    Opcode::div_i64_u,
+    Opcode::rem_i64_s,
+    Opcode::rem_i64_u,
+    Opcode::add_f32,
+    Opcode::sub_f32,
+    Opcode::mul_f32,
+    Opcode::div_f32,
+    Opcode::add_f64,
+    Opcode::sub_f64,
+    Opcode::mul_f64,
+    Opcode::div_f64,
+    Opcode::add_imm_i32,
+    Opcode::sub_imm_i32,
+    Opcode::add_imm_i64,
+    Opcode::sub_imm_i64,
+    // Category: Bitwise
+    Opcode::and,
+    Opcode::or,
+    Opcode::xor,
+    Opcode::not,
+    Opcode::count_leading_zeros_i32,
+    Opcode::count_leading_ones_i32,
+    Opcode::count_trailing_zeros_i32,
+    Opcode::count_ones_i32,
+    Opcode::shift_left_i32,
+    Opcode::shift_right_i32_s,
+    Opcode::shift_right_i32_u,
+    Opcode::rotate_left_i32,
+    Opcode::rotate_right_i32,
+    Opcode::count_leading_zeros_i64,
+    Opcode::count_leading_ones_i64,
+    Opcode::count_trailing_zeros_i64,
+    Opcode::count_ones_i64,
+    Opcode::shift_left_i64,
+    Opcode::shift_right_i64_s,
+    Opcode::shift_right_i64_u,
+    Opcode::rotate_left_i64,
+    Opcode::rotate_right_i64,
+    // Category: Math
+    Opcode::abs_i32,
+    Opcode::neg_i32,
+    Opcode::abs_i64,
+    Opcode::neg_i64,
+    Opcode::abs_f32,
+    Opcode::neg_f32,
+    Opcode::copysign_f32,
+    Opcode::sqrt_f32,
+    Opcode::min_f32,
+    Opcode::max_f32,
+    Opcode::ceil_f32,
+    Opcode::floor_f32,
+    Opcode::round_half_away_from_zero_f32,
+    Opcode::round_half_to_even_f32,
+    Opcode::trunc_f32,
+    Opcode::fract_f32,
+    Opcode::cbrt_f32,
+    Opcode::exp_f32,
+    Opcode::exp2_f32,
+    Opcode::ln_f32,
+    Opcode::log2_f32,
+    Opcode::log10_f32,
+    Opcode::sin_f32,
+    Opcode::cos_f32,
+    Opcode::tan_f32,
+    Opcode::asin_f32,
+    Opcode::acos_f32,
+    Opcode::atan_f32,
+    Opcode::pow_f32,
+    Opcode::log_f32,
+    Opcode::abs_f64,
+    Opcode::neg_f64,
+    Opcode::copysign_f64,
+    Opcode::sqrt_f64,
+    Opcode::min_f64,
+    Opcode::max_f64,
+    Opcode::ceil_f64,
+    Opcode::floor_f64,
+    Opcode::round_half_away_from_zero_f64,
+    Opcode::round_half_to_even_f64,
+    Opcode::trunc_f64,
+    Opcode::fract_f64,
+    Opcode::cbrt_f64,
+    Opcode::exp_f64,
+    Opcode::exp2_f64,
+    Opcode::ln_f64,
+    Opcode::log2_f64,
+    Opcode::log10_f64,
+    Opcode::sin_f64,
+    Opcode::cos_f64,
+    Opcode::tan_f64,
+    Opcode::asin_f64,
+    Opcode::acos_f64,
+    Opcode::atan_f64,
+    Opcode::pow_f64,
+    Opcode::log_f64,
+    // Category: Conversion
+    Opcode::truncate_i64_to_i32,
+    Opcode::extend_i32_s_to_i64,
+    Opcode::extend_i32_u_to_i64,
+    Opcode::demote_f64_to_f32,
+    Opcode::promote_f32_to_f64,
+    Opcode::convert_f32_to_i32_s,
+    Opcode::convert_f32_to_i32_u,
+    Opcode::convert_f64_to_i32_s,
+    Opcode::convert_f64_to_i32_u,
+    Opcode::convert_f32_to_i64_s,
+    Opcode::convert_f32_to_i64_u,
+    Opcode::convert_f64_to_i64_s,
+    Opcode::convert_f64_to_i64_u,
+    Opcode::convert_i32_s_to_f32,
+    Opcode::convert_i32_u_to_f32,
+    Opcode::convert_i64_s_to_f32,
+    Opcode::convert_i64_u_to_f32,
+    Opcode::convert_i32_s_to_f64,
+    Opcode::convert_i32_u_to_f64,
+    Opcode::convert_i64_s_to_f64,
+    Opcode::convert_i64_u_to_f64,
+    // Category: Comparison
+    Opcode::eqz_i32,
+    Opcode::nez_i32,
+    Opcode::eq_i32,
+    Opcode::ne_i32,
+    Opcode::lt_i32_s,
+    Opcode::lt_i32_u,
+    Opcode::gt_i32_s,
+    Opcode::gt_i32_u,
+    Opcode::le_i32_s,
+    Opcode::le_i32_u,
+    Opcode::ge_i32_s,
+    Opcode::ge_i32_u,
+    Opcode::eqz_i64,
+    Opcode::nez_i64,
+    Opcode::eq_i64,
+    Opcode::ne_i64,
+    Opcode::lt_i64_s,
+    Opcode::lt_i64_u,
+    Opcode::gt_i64_s,
+    Opcode::gt_i64_u,
+    Opcode::le_i64_s,
+    Opcode::le_i64_u,
+    Opcode::ge_i64_s,
+    Opcode::ge_i64_u,
+    Opcode::eq_f32,
+    Opcode::ne_f32,
+    Opcode::lt_f32,
+    Opcode::gt_f32,
+    Opcode::le_f32,
+    Opcode::ge_f32,
+    Opcode::eq_f64,
+    Opcode::ne_f64,
+    Opcode::lt_f64,
+    Opcode::gt_f64,
+    Opcode::le_f64,
+    Opcode::ge_f64,
+    // Category: Control flow
+    Opcode::end,
+    Opcode::block,
+    Opcode::break_,
+    Opcode::recur,
+    Opcode::block_alt,
+    Opcode::break_alt,
+    Opcode::block_nez,
+    Opcode::call,
+    Opcode::envcall,
+    Opcode::extcall,
+    Opcode::call_dynamic,
+    Opcode::syscall,
+    // Category: Memory
+    Opcode::memory_allocate,
+    Opcode::memory_reallocate,
+    Opcode::memory_free,
+    Opcode::memory_fill,
+    Opcode::memory_copy,
+    // Category: Machine
+    Opcode::terminate,
+    Opcode::get_function,
+    Opcode::host_addr_function,
+    Opcode::get_data,
+    Opcode::host_addr_data_extend,
+    Opcode::host_addr_data,
+    Opcode::host_addr_function_dynamic,
+    Opcode::host_addr_data_dynamic,
+];
+
+/// Returns the opcode of the specified name, e.g. "imm_i32".
+pub fn find_opcode_by_name(name: &str) -> Option<Opcode> {
+    ALL_OPCODES
+        .iter()
+        .copied()
+        .find(|opcode| opcode.get_name() == name)
+}
+
+// The lookup table of the opcodes by the encoded value, it is built at compile
+// time from `ALL_OPCODES`, and indexed by the high byte and then the low byte
+// of the value, so that the decoder does not scan the opcode list.
+const OPCODE_TABLE_ROW_COUNT: usize = {
+    let mut max_high_byte: usize = 0;
+    let mut idx: usize = 0;
+    while idx < ALL_OPCODES.len() {
+        let high_byte = (ALL_OPCODES[idx] as u16 >> 8) as usize;
+        if high_byte > max_high_byte {
+            max_high_byte = high_byte;
+        }
+        idx += 1;
+    }
+    max_high_byte + 1
+};
+
+static OPCODE_TABLE: [[Option<Opcode>; 256]; OPCODE_TABLE_ROW_COUNT] = {
+    let mut table = [[None; 256]; OPCODE_TABLE_ROW_COUNT];
+    let mut idx: usize = 0;
+    while idx < ALL_OPCODES.len() {
+        let opcode = ALL_OPCODES[idx];
+        let value = opcode as u16;
+        table[(value >> 8) as usize][(value & 0xff) as usize] = Some(opcode);
+        idx += 1;
+    }
+    table
+};
+
+/// Returns the opcode of the specified encoded value, or `None` if the value
+/// is not a known opcode, e.g. the bytecode is corrupted.
+pub fn find_opcode_by_value(value: u16) -> Option<Opcode> {
+    OPCODE_TABLE
+        .get((value >> 8) as usize)
+        .and_then(|row| row[(value & 0xff) as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        module_image::RelocateType,
+        opcode_info::{
+            find_opcode_by_name, find_opcode_by_value, get_opcode_info, OpcodeCategory,
+            OperandKind, ALL_OPCODES,
+        },
+    };
+
+    #[test]
+    fn test_opcode_info() {
+        let info = get_opcode_info(Opcode::end);
+        assert_eq!(info.category, OpcodeCategory::ControlFlow);
+        assert_eq!(info.length(), 2);
+
+        let info = get_opcode_info(Opcode::add_imm_i32);
+        assert_eq!(info.category, OpcodeCategory::Arithmetic);
+        assert_eq!(info.operands, &[OperandKind::Amount]);
+        assert_eq!(info.operand_offset(0), 2);
+        assert_eq!(info.length(), 4);
+
+        let info = get_opcode_info(Opcode::data_load_i32_u);
+        assert_eq!(info.operand_offset(0), 2);
+        assert_eq!(info.operand_offset(1), 4);
+        assert_eq!(info.length(), 8);
+
+        let info = get_opcode_info(Opcode::call);
+        assert_eq!(info.operand_offset(0), 4);
+        assert_eq!(info.length(), 8);
+        assert_eq!(
+            info.operands[0].relocate_type(),
+            Some(RelocateType::FunctionPublicIndex)
+        );
+
+        let info = get_opcode_info(Opcode::imm_i64);
+        assert_eq!(info.category, OpcodeCategory::Fundamental);
+        assert_eq!(info.length(), 12);
+
+        let info = get_opcode_info(Opcode::block_alt);
+        assert_eq!(info.operand_offset(2), 12);
+        assert_eq!(info.length(), 16);
+        assert_eq!(info.operands[2].relocate_type(), None);
+
+        let info = get_opcode_info(Opcode::host_addr_data_extend);
+        assert_eq!(info.category, OpcodeCategory::Machine);
+        assert_eq!(info.operands, &[OperandKind::DataPublicIndex]);
+    }
+
+    #[test]
+    fn test_find_opcode_by_name() {
+        assert_eq!(find_opcode_by_name("imm_i32"), Some(Opcode::imm_i32));
+        assert_eq!(find_opcode_by_name("end"), Some(Opcode::end));
+        assert_eq!(find_opcode_by_name("foo"), None);
+
+        // the names are unique
+        for opcode in ALL_OPCODES {
+            assert_eq!(find_opcode_by_name(opcode.get_name()), Some(*opcode));
+        }
+    }
+
+    #[test]
+    fn test_find_opcode_by_value() {
+        assert_eq!(
+            find_opcode_by_value(Opcode::imm_i32 as u16),
+            Some(Opcode::imm_i32)
+        );
+        assert_eq!(find_opcode_by_value(0xffff), None);
+
+        for opcode in ALL_OPCODES {
+            assert_eq!(find_opcode_by_value(*opcode as u16), Some(*opcode));
+        }
+    }
+}
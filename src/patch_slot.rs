@@ -0,0 +1,310 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Two-phase writing of the images.
+//
+// 1. The placeholder regions (i.e., the "patch slots", see `patch_slot_section`)
+//    are reserved when writing the image, they are zero-filled.
+// 2. The tools (e.g., signing and fingerprinting) run over the final bytes of
+//    the image, and fill the results into the slots by offset.
+//
+// so the image is not serialized a second time after signing.
+//
+// The slots are zero when the signature (or the hash) is computed, so the
+// verifiers clear the slots (see `clear_patch_slots`) before computing again.
+//
+// If the image ends with a trailer, its checksum is updated after patching.
+
+use std::{fmt::Display, ops::Range};
+
+use crate::{
+    common_sections::patch_slot_section::PatchSlotSection,
+    entry::PatchSlotEntry,
+    image_pipeline::ImageSections,
+    module_image::{
+        compute_crc32, ModuleImage, ModuleSectionId, SectionEntry, BASE_MODULE_HEADER_LENGTH,
+        IMAGE_FLAG_HAS_TRAILER, IMAGE_TRAILER_LENGTH,
+    },
+    ImageError, ImageErrorType,
+};
+
+// The reasons why a patch slot can not be located or patched.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatchError {
+    MissingSlot(String),
+
+    // The region (offsets from the start of the image binary) is not within a slot.
+    RegionOutOfSlot(Range<usize>),
+
+    DataLengthMismatch {
+        name: String,
+        slot_length: usize,
+        data_length: usize,
+    },
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::MissingSlot(name) => write!(f, "Cannot find the patch slot \"{}\".", name),
+            PatchError::RegionOutOfSlot(region) => write!(
+                f,
+                "The region {}..{} is not within a patch slot.",
+                region.start, region.end
+            ),
+            PatchError::DataLengthMismatch {
+                name,
+                slot_length,
+                data_length,
+            } => write!(
+                f,
+                "The length of the data ({}) does not match the patch slot \"{}\" ({}).",
+                data_length, name, slot_length
+            ),
+        }
+    }
+}
+
+// The location of a slot within the image binary.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PatchSlotLocation {
+    pub name: String,
+
+    // The offset (in bytes) from the start of the image binary.
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Writes the image with the "patch slot" section replaced by the specified entries,
+/// the slots are zero-filled.
+pub fn reserve_patch_slots(
+    image: &ModuleImage,
+    patch_slot_entries: &[PatchSlotEntry],
+    writer: &mut dyn std::io::Write,
+) -> Result<(), ImageError> {
+    let (items, items_data) = PatchSlotSection::convert_from_entries(patch_slot_entries);
+    let patch_slot_section = PatchSlotSection {
+        items: &items,
+        items_data: &items_data,
+    };
+    let mut patch_slot_data: Vec<u8> = vec![];
+    patch_slot_section.write(&mut patch_slot_data)?;
+
+    let mut image_sections = ImageSections::from_module_image(image);
+    image_sections.set_section_data(ModuleSectionId::PatchSlot, patch_slot_data);
+    image_sections.write(writer)
+}
+
+/// Returns the locations of the slots, in the order of the "patch slot" section.
+pub fn locate_patch_slots(image_binary: &[u8]) -> Result<Vec<PatchSlotLocation>, ImageError> {
+    let image = ModuleImage::read(image_binary)?;
+
+    let Some(patch_slot_section) = image.get_optional_patch_slot_section() else {
+        return Ok(vec![]);
+    };
+
+    // The data area of the section is a sub-slice of the image binary.
+    let data_area_offset =
+        patch_slot_section.items_data.as_ptr() as usize - image_binary.as_ptr() as usize;
+
+    let locations = patch_slot_section
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| PatchSlotLocation {
+            name: patch_slot_section.get_item_name(idx).to_owned(),
+            offset: data_area_offset + item.slot_offset as usize,
+            length: item.slot_length as usize,
+        })
+        .collect();
+
+    Ok(locations)
+}
+
+/// Returns the location of the slot with the specified name.
+pub fn find_patch_slot(image_binary: &[u8], name: &str) -> Result<PatchSlotLocation, ImageError> {
+    locate_patch_slots(image_binary)?
+        .into_iter()
+        .find(|location| location.name == name)
+        .ok_or_else(|| {
+            ImageError::new(ImageErrorType::Patch(PatchError::MissingSlot(
+                name.to_owned(),
+            )))
+        })
+}
+
+/// Writes the data into the image at the specified offset.
+///
+/// The region `offset..(offset + data.len())` must be within a slot,
+/// the trailer checksum (if present) is updated.
+pub fn patch_image(image_binary: &mut [u8], offset: usize, data: &[u8]) -> Result<(), ImageError> {
+    let end = offset + data.len();
+    let is_within_slot = locate_patch_slots(image_binary)?
+        .iter()
+        .any(|location| location.offset <= offset && end <= location.offset + location.length);
+
+    if !is_within_slot {
+        return Err(ImageError::new(ImageErrorType::Patch(
+            PatchError::RegionOutOfSlot(offset..end),
+        )));
+    }
+
+    image_binary[offset..end].copy_from_slice(data);
+    update_trailer_checksum(image_binary);
+    Ok(())
+}
+
+/// Fills the slot with the specified name, the length of the data
+/// must be equal to the length of the slot.
+pub fn patch_slot(image_binary: &mut [u8], name: &str, data: &[u8]) -> Result<(), ImageError> {
+    let location = find_patch_slot(image_binary, name)?;
+    if data.len() != location.length {
+        return Err(ImageError::new(ImageErrorType::Patch(
+            PatchError::DataLengthMismatch {
+                name: name.to_owned(),
+                slot_length: location.length,
+                data_length: data.len(),
+            },
+        )));
+    }
+
+    patch_image(image_binary, location.offset, data)
+}
+
+/// Zero-fills all slots, i.e., restores the bytes which were signed (or hashed).
+pub fn clear_patch_slots(image_binary: &mut [u8]) -> Result<(), ImageError> {
+    for location in locate_patch_slots(image_binary)? {
+        image_binary[location.offset..(location.offset + location.length)].fill(0);
+    }
+
+    update_trailer_checksum(image_binary);
+    Ok(())
+}
+
+// Recomputes the checksum of the trailer if the image ends with one,
+// see the layout at the top of `module_image`.
+fn update_trailer_checksum(image_binary: &mut [u8]) {
+    let extra_header_length = u16::from_le_bytes(image_binary[10..12].try_into().unwrap());
+    if extra_header_length < 4 {
+        return;
+    }
+
+    let image_flags = u32::from_le_bytes(
+        image_binary[BASE_MODULE_HEADER_LENGTH..(BASE_MODULE_HEADER_LENGTH + 4)]
+            .try_into()
+            .unwrap(),
+    );
+    if image_flags & IMAGE_FLAG_HAS_TRAILER == 0 {
+        return;
+    }
+
+    let trailer_start = image_binary.len() - IMAGE_TRAILER_LENGTH;
+    let checksum = compute_crc32(&image_binary[..trailer_start]);
+    image_binary[(trailer_start + 12)..(trailer_start + 16)]
+        .copy_from_slice(&checksum.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::PatchSlotEntry,
+        module_image::ModuleImage,
+        patch_slot::{
+            clear_patch_slots, find_patch_slot, locate_patch_slots, patch_image, patch_slot,
+            reserve_patch_slots,
+        },
+        utils::helper_build_module_binary_with_single_function,
+    };
+
+    #[test]
+    fn test_patch_slots() {
+        let image_binary = helper_build_module_binary_with_single_function(&[], &[], &[], vec![0]);
+        let image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut reserved_binary: Vec<u8> = vec![];
+        reserve_patch_slots(
+            &image,
+            &[
+                PatchSlotEntry::new("signature".to_owned(), 6),
+                PatchSlotEntry::new("timestamp".to_owned(), 8),
+            ],
+            &mut reserved_binary,
+        )
+        .unwrap();
+
+        let locations = locate_patch_slots(&reserved_binary).unwrap();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[0].length, 6);
+        assert_eq!(locations[1].offset, locations[0].offset + 8);
+        assert!(
+            reserved_binary[locations[0].offset..(locations[0].offset + 6)]
+                .iter()
+                .all(|byte| *byte == 0)
+        );
+
+        // fill the slots
+        let mut patched_binary = reserved_binary.clone();
+        patch_slot(&mut patched_binary, "signature", &[1, 2, 3, 4, 5, 6]).unwrap();
+
+        let timestamp = find_patch_slot(&patched_binary, "timestamp").unwrap();
+        patch_image(&mut patched_binary, timestamp.offset + 4, &[7, 8]).unwrap();
+
+        assert_eq!(
+            &patched_binary[locations[0].offset..(locations[0].offset + 6)],
+            &[1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            &patched_binary[timestamp.offset..(timestamp.offset + 8)],
+            &[0, 0, 0, 0, 7, 8, 0, 0]
+        );
+
+        // the other sections are untouched
+        let patched_image = ModuleImage::read(&patched_binary).unwrap();
+        assert_eq!(
+            patched_image.get_function_section().items.len(),
+            image.get_function_section().items.len()
+        );
+
+        // errors
+        assert!(patch_slot(&mut patched_binary, "signature", &[1, 2]).is_err());
+        assert!(patch_slot(&mut patched_binary, "hash", &[1]).is_err());
+        assert!(patch_image(&mut patched_binary, timestamp.offset + 6, &[1, 2, 3]).is_err());
+        assert!(patch_image(&mut patched_binary, 0, &[1]).is_err());
+
+        // restores the signed bytes
+        clear_patch_slots(&mut patched_binary).unwrap();
+        assert_eq!(patched_binary, reserved_binary);
+    }
+
+    #[test]
+    fn test_patch_slots_with_trailer() {
+        let image_binary = helper_build_module_binary_with_single_function(&[], &[], &[], vec![0]);
+        let image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut reserved_binary: Vec<u8> = vec![];
+        reserve_patch_slots(
+            &image,
+            &[PatchSlotEntry::new("hash".to_owned(), 8)],
+            &mut reserved_binary,
+        )
+        .unwrap();
+
+        // rewrite the image with the trailer
+        let reserved_image = ModuleImage::read(&reserved_binary).unwrap();
+        let mut trailer_binary: Vec<u8> = vec![];
+        reserved_image
+            .write_with_trailer(&mut trailer_binary)
+            .unwrap();
+
+        patch_slot(&mut trailer_binary, "hash", &[0xff; 8]).unwrap();
+
+        // the checksum is updated
+        assert!(ModuleImage::read(&trailer_binary).is_ok());
+        assert_eq!(locate_patch_slots(&trailer_binary).unwrap()[0].length, 8);
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The aggregate statistics of a program, i.e., the main image and its
+// dependent modules, e.g., for the build dashboards.
+//
+// The modules are given in the order of the "linking module" section,
+// i.e., the main module first, and the module index in the report
+// is the position in that list.
+//
+// The symbols are the exported (i.e., non-private) function and data names,
+// a symbol is "duplicated" if its full name is exported by more than one module.
+//
+// The external libraries are counted by name, a library referenced by
+// several modules is counted once.
+
+use crate::module_image::{ModuleImage, Visibility};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DuplicatedSymbol {
+    pub full_name: String,
+
+    // The indices of the modules which export the symbol, in ascending order.
+    pub module_indices: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ProgramStatistics {
+    pub module_count: usize,
+
+    // The internal functions, the imported functions are not included.
+    pub function_count: usize,
+
+    // The length of the bytecode of all functions, in bytes.
+    pub code_bytes: usize,
+
+    // The size of the data areas (the paddings are included).
+    pub read_only_data_bytes: usize,
+    pub read_write_data_bytes: usize,
+
+    // The end of the last data item of the uninitialized data sections.
+    pub uninit_data_bytes: usize,
+
+    // The amount of the distinct external libraries (by name).
+    pub external_library_count: usize,
+
+    pub duplicated_symbols: Vec<DuplicatedSymbol>,
+}
+
+impl ProgramStatistics {
+    /// Returns the total size of the data of all section types.
+    pub fn data_bytes(&self) -> usize {
+        self.read_only_data_bytes + self.read_write_data_bytes + self.uninit_data_bytes
+    }
+}
+
+/// Collects the statistics of the specified modules,
+/// the main module should be the first one.
+pub fn compute_program_statistics(module_images: &[&ModuleImage]) -> ProgramStatistics {
+    let mut statistics = ProgramStatistics {
+        module_count: module_images.len(),
+        ..ProgramStatistics::default()
+    };
+
+    let mut external_library_names: Vec<String> = vec![];
+
+    // `(full_name, module_index)`
+    let mut symbols: Vec<(String, usize)> = vec![];
+
+    for (module_index, image) in module_images.iter().enumerate() {
+        let function_section = image.get_function_section();
+        statistics.function_count += function_section.items.len();
+        statistics.code_bytes += function_section
+            .items
+            .iter()
+            .map(|item| item.code_length as usize)
+            .sum::<usize>();
+
+        statistics.read_only_data_bytes += image
+            .get_optional_read_only_data_section()
+            .map_or(0, |section| section.datas_data.len());
+        statistics.read_write_data_bytes += image
+            .get_optional_read_write_data_section()
+            .map_or(0, |section| section.datas_data.len());
+        statistics.uninit_data_bytes += image
+            .get_optional_uninit_data_section()
+            .and_then(|section| {
+                section
+                    .items
+                    .iter()
+                    .map(|item| (item.data_offset + item.data_length) as usize)
+                    .max()
+            })
+            .unwrap_or(0);
+
+        if let Some(external_library_section) = image.get_optional_external_library_section() {
+            for entry in external_library_section.convert_to_entries() {
+                if !external_library_names.contains(&entry.name) {
+                    external_library_names.push(entry.name);
+                }
+            }
+        }
+
+        if let Some(function_name_section) = image.get_optional_export_function_section() {
+            symbols.extend(
+                function_name_section
+                    .convert_to_entries()
+                    .into_iter()
+                    .filter(|entry| entry.visibility != Visibility::Private)
+                    .map(|entry| (entry.full_name, module_index)),
+            );
+        }
+
+        if let Some(data_name_section) = image.get_optional_export_data_section() {
+            symbols.extend(
+                data_name_section
+                    .convert_to_entries()
+                    .into_iter()
+                    .filter(|entry| entry.visibility != Visibility::Private)
+                    .map(|entry| (entry.full_name, module_index)),
+            );
+        }
+    }
+
+    statistics.external_library_count = external_library_names.len();
+
+    // group the symbols by name, the sort is stable so the module indices
+    // in each group keep ascending.
+    symbols.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let mut groups: Vec<DuplicatedSymbol> = vec![];
+    for (full_name, module_index) in symbols {
+        match groups.last_mut() {
+            Some(group) if group.full_name == full_name => {
+                if !group.module_indices.contains(&module_index) {
+                    group.module_indices.push(module_index);
+                }
+            }
+            _ => groups.push(DuplicatedSymbol {
+                full_name,
+                module_indices: vec![module_index],
+            }),
+        }
+    }
+
+    statistics.duplicated_symbols = groups
+        .into_iter()
+        .filter(|group| group.module_indices.len() > 1)
+        .collect();
+
+    statistics
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::OperandDataType;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::TypeEntry,
+        entry_writer::build_shared_module_scaffold,
+        module_image::ModuleImage,
+        program_statistics::{compute_program_statistics, DuplicatedSymbol},
+        utils::helper_build_application_fixture,
+    };
+
+    #[test]
+    fn test_compute_program_statistics() {
+        let fixture = helper_build_application_fixture(2);
+
+        // a module which exports the same function as "dep1"
+        let scaffold_binary = build_shared_module_scaffold(
+            "dep1",
+            &[
+                (
+                    "get_number",
+                    TypeEntry::new(vec![], vec![OperandDataType::I32]),
+                ),
+                ("get_name", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let mut module_binaries = vec![&fixture.application_binary];
+        module_binaries.extend(fixture.dependency_module_binaries.iter());
+        module_binaries.push(&scaffold_binary);
+
+        let module_images = module_binaries
+            .iter()
+            .map(|binary| ModuleImage::read(binary).unwrap())
+            .collect::<Vec<_>>();
+        let statistics = compute_program_statistics(&module_images.iter().collect::<Vec<_>>());
+
+        let expected_code_bytes = module_images
+            .iter()
+            .map(|image| {
+                image
+                    .get_function_section()
+                    .items
+                    .iter()
+                    .map(|item| item.code_length as usize)
+                    .sum::<usize>()
+            })
+            .sum::<usize>();
+
+        assert_eq!(statistics.module_count, 4);
+        assert_eq!(statistics.function_count, 5);
+        assert_eq!(statistics.code_bytes, expected_code_bytes);
+
+        // each "depN" has one i32 read-only data
+        assert_eq!(statistics.read_only_data_bytes, 8);
+        assert_eq!(statistics.read_write_data_bytes, 0);
+        assert_eq!(statistics.uninit_data_bytes, 0);
+        assert_eq!(statistics.data_bytes(), 8);
+        assert_eq!(statistics.external_library_count, 0);
+
+        assert_eq!(
+            statistics.duplicated_symbols,
+            vec![DuplicatedSymbol {
+                full_name: "dep1::get_number".to_owned(),
+                module_indices: vec![2, 3],
+            }]
+        );
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Converts between the internal indices, the mixed internal indices and
+// the public indices of the functions and data of a module.
+//
+// The public indices of a module start with the imported items, followed by
+// the internal items:
+//
+// - `function_public_index = import_function_count + function_internal_index`
+// - `data_public_index = import_data_count + mixed_data_internal_index`
+//
+// The mixed data internal index combines the internal read-only data, the internal
+// read-write data and the internal uninitialized data in order, see the diagram
+// in `data_name_section`.
+
+use anc_isa::DataSectionType;
+
+use crate::{entry::ImageCommonEntry, module_image::ModuleImage, ImageError};
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct PublicIndexSpace {
+    pub import_function_count: usize,
+    pub function_count: usize,
+
+    // The imported data of all data sections.
+    pub import_data_count: usize,
+    pub read_only_data_count: usize,
+    pub read_write_data_count: usize,
+    pub uninit_data_count: usize,
+}
+
+impl PublicIndexSpace {
+    pub fn from_common_entry(image_common_entry: &ImageCommonEntry) -> Self {
+        Self {
+            import_function_count: image_common_entry.import_function_entries.len(),
+            function_count: image_common_entry.function_entries.len(),
+            import_data_count: image_common_entry.import_data_entries.len(),
+            read_only_data_count: image_common_entry.read_only_data_entries.len(),
+            read_write_data_count: image_common_entry.read_write_data_entries.len(),
+            uninit_data_count: image_common_entry.uninit_data_entries.len(),
+        }
+    }
+
+    /// Returns `ImageErrorType::MissingSection` or `ImageErrorType::CorruptedSection`
+    /// if the function section is missing, or a section is corrupted.
+    pub fn from_module_image(image: &ModuleImage) -> Result<Self, ImageError> {
+        Ok(Self {
+            import_function_count: image
+                .try_get_optional_import_function_section()?
+                .map_or(0, |section| section.items.len()),
+            function_count: image.try_get_function_section()?.items.len(),
+            import_data_count: image
+                .try_get_optional_import_data_section()?
+                .map_or(0, |section| section.items.len()),
+            read_only_data_count: image
+                .try_get_optional_read_only_data_section()?
+                .map_or(0, |section| section.items.len()),
+            read_write_data_count: image
+                .try_get_optional_read_write_data_section()?
+                .map_or(0, |section| section.items.len()),
+            uninit_data_count: image
+                .try_get_optional_uninit_data_section()?
+                .map_or(0, |section| section.items.len()),
+        })
+    }
+
+    /// Returns the amount of the function public indices,
+    /// i.e., the imported functions and the internal functions.
+    pub fn function_public_index_count(&self) -> usize {
+        self.import_function_count + self.function_count
+    }
+
+    pub fn function_public_index(&self, function_internal_index: usize) -> usize {
+        self.import_function_count + function_internal_index
+    }
+
+    /// Returns the internal index of the function, or `None` if the
+    /// public index refers to an imported function or is out of range.
+    pub fn function_internal_index(&self, function_public_index: usize) -> Option<usize> {
+        function_public_index
+            .checked_sub(self.import_function_count)
+            .filter(|function_internal_index| *function_internal_index < self.function_count)
+    }
+
+    pub fn is_imported_function(&self, function_public_index: usize) -> bool {
+        function_public_index < self.import_function_count
+    }
+
+    /// Returns the amount of the internal data of all data sections.
+    pub fn mixed_data_count(&self) -> usize {
+        self.read_only_data_count + self.read_write_data_count + self.uninit_data_count
+    }
+
+    /// Returns the amount of the data public indices,
+    /// i.e., the imported data and the internal data.
+    pub fn data_public_index_count(&self) -> usize {
+        self.import_data_count + self.mixed_data_count()
+    }
+
+    pub fn mixed_data_internal_index(
+        &self,
+        section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+    ) -> usize {
+        let section_offset = match section_type {
+            DataSectionType::ReadOnly => 0,
+            DataSectionType::ReadWrite => self.read_only_data_count,
+            DataSectionType::Uninit => self.read_only_data_count + self.read_write_data_count,
+        };
+        section_offset + data_internal_index_in_section
+    }
+
+    /// Returns the section type and the internal index in the section,
+    /// or `None` if the mixed internal index is out of range.
+    pub fn data_section_type_and_internal_index(
+        &self,
+        mixed_data_internal_index: usize,
+    ) -> Option<(DataSectionType, usize)> {
+        let read_write_data_offset = self.read_only_data_count;
+        let uninit_data_offset = read_write_data_offset + self.read_write_data_count;
+
+        if mixed_data_internal_index < read_write_data_offset {
+            Some((DataSectionType::ReadOnly, mixed_data_internal_index))
+        } else if mixed_data_internal_index < uninit_data_offset {
+            Some((
+                DataSectionType::ReadWrite,
+                mixed_data_internal_index - read_write_data_offset,
+            ))
+        } else if mixed_data_internal_index < self.mixed_data_count() {
+            Some((
+                DataSectionType::Uninit,
+                mixed_data_internal_index - uninit_data_offset,
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn data_public_index(
+        &self,
+        section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+    ) -> usize {
+        self.import_data_count
+            + self.mixed_data_internal_index(section_type, data_internal_index_in_section)
+    }
+
+    /// Returns the mixed internal index of the data, or `None` if the
+    /// public index refers to an imported data or is out of range.
+    pub fn mixed_data_internal_index_from_public_index(
+        &self,
+        data_public_index: usize,
+    ) -> Option<usize> {
+        data_public_index
+            .checked_sub(self.import_data_count)
+            .filter(|mixed_data_internal_index| {
+                *mixed_data_internal_index < self.mixed_data_count()
+            })
+    }
+
+    /// Returns the section type and the internal index in the section of the data,
+    /// or `None` if the public index refers to an imported data or is out of range.
+    pub fn data_section_type_and_internal_index_from_public_index(
+        &self,
+        data_public_index: usize,
+    ) -> Option<(DataSectionType, usize)> {
+        self.mixed_data_internal_index_from_public_index(data_public_index)
+            .and_then(|mixed_data_internal_index| {
+                self.data_section_type_and_internal_index(mixed_data_internal_index)
+            })
+    }
+
+    pub fn is_imported_data(&self, data_public_index: usize) -> bool {
+        data_public_index < self.import_data_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::DataSectionType;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{ReadOnlyDataEntry, ReadWriteDataEntry, UninitDataEntry},
+        module_image::ModuleImage,
+        public_index::PublicIndexSpace,
+        utils::helper_build_module_binary_with_single_function_and_data,
+    };
+
+    #[test]
+    fn test_public_index_space() {
+        let space = PublicIndexSpace {
+            import_function_count: 2,
+            function_count: 3,
+            import_data_count: 3,
+            read_only_data_count: 2,
+            read_write_data_count: 1,
+            uninit_data_count: 2,
+        };
+
+        // functions
+        assert_eq!(space.function_public_index_count(), 5);
+        assert_eq!(space.function_public_index(1), 3);
+        assert_eq!(space.function_internal_index(3), Some(1));
+        assert_eq!(space.function_internal_index(1), None);
+        assert_eq!(space.function_internal_index(5), None);
+        assert!(space.is_imported_function(1));
+        assert!(!space.is_imported_function(2));
+
+        // data
+        assert_eq!(space.mixed_data_count(), 5);
+        assert_eq!(space.data_public_index_count(), 8);
+        assert_eq!(
+            space.mixed_data_internal_index(DataSectionType::ReadWrite, 0),
+            2
+        );
+        assert_eq!(space.data_public_index(DataSectionType::Uninit, 1), 7);
+        assert_eq!(
+            space.data_section_type_and_internal_index(1),
+            Some((DataSectionType::ReadOnly, 1))
+        );
+        assert_eq!(
+            space.data_section_type_and_internal_index(2),
+            Some((DataSectionType::ReadWrite, 0))
+        );
+        assert_eq!(
+            space.data_section_type_and_internal_index(4),
+            Some((DataSectionType::Uninit, 1))
+        );
+        assert_eq!(space.data_section_type_and_internal_index(5), None);
+        assert_eq!(
+            space.data_section_type_and_internal_index_from_public_index(6),
+            Some((DataSectionType::Uninit, 0))
+        );
+        assert_eq!(
+            space.data_section_type_and_internal_index_from_public_index(2),
+            None
+        );
+        assert!(space.is_imported_data(2));
+        assert!(!space.is_imported_data(3));
+
+        // round trip
+        for data_public_index in 3..8 {
+            let (section_type, data_internal_index_in_section) = space
+                .data_section_type_and_internal_index_from_public_index(data_public_index)
+                .unwrap();
+            assert_eq!(
+                space.data_public_index(section_type, data_internal_index_in_section),
+                data_public_index
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_index_space_from_module_image() {
+        let image_binary = helper_build_module_binary_with_single_function_and_data(
+            &[],
+            &[],
+            &[],
+            vec![0u8],
+            &[ReadOnlyDataEntry::from_i32(11)],
+            &[
+                ReadWriteDataEntry::from_i32(13),
+                ReadWriteDataEntry::from_i64(17),
+            ],
+            &[UninitDataEntry::from_i32()],
+        );
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(
+            PublicIndexSpace::from_module_image(&module_image).unwrap(),
+            PublicIndexSpace {
+                import_function_count: 0,
+                function_count: 1,
+                import_data_count: 0,
+                read_only_data_count: 1,
+                read_write_data_count: 2,
+                uninit_data_count: 1,
+            }
+        );
+    }
+}
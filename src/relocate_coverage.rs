@@ -0,0 +1,250 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Checks that the relocate list of each function covers exactly the relocatable
+// parameters of its instructions, it is used to catch the compilers which forget
+// to emit relocations (or emit them at the wrong locations).
+//
+// The relocatable parameters are derived from the operands of the opcodes
+// (see `opcode_info`), and the "Relocate" section of `entry` for the list of
+// instructions:
+//
+// - `block`, `block_alt`: the type index and the local variable list index.
+// - `block_nez`: the local variable list index.
+// - `call`, `get_function`, `host_addr_function`: the function public index.
+// - `extcall`: the external function index.
+// - `get_data`, `data_load_*`, `data_store_*`, `host_addr_data`, and their
+//   `*_extend` variants: the data public index.
+//
+// A relocate entry with a wrong relocate type is reported as
+// a missing entry (the expected type) and an extra entry (the actual type).
+
+use crate::{
+    bytecode_reader::InstructionIterator,
+    entry::RelocateEntry,
+    module_image::{ModuleImage, RelocateType},
+    opcode_info::get_opcode_info,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RelocateCoverageIssueType {
+    // The parameter is relocatable but there is no relocate entry for it.
+    Missing,
+
+    // The relocate entry does not point to a relocatable parameter.
+    Extra,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RelocateCoverageIssue {
+    pub function_internal_index: usize,
+    pub offset_in_function: usize,
+    pub relocate_type: RelocateType,
+    pub issue_type: RelocateCoverageIssueType,
+}
+
+/// Returns the relocate entries which the bytecode requires,
+/// in the order of the offsets.
+pub fn get_expected_relocate_entries(code: &[u8]) -> Vec<RelocateEntry> {
+    let mut relocate_entries = vec![];
+
+    for record in InstructionIterator::new(code) {
+        let info = get_opcode_info(record.opcode);
+
+        for (idx, operand) in info.operands.iter().enumerate() {
+            if let Some(relocate_type) = operand.relocate_type() {
+                relocate_entries.push(RelocateEntry::new(
+                    record.offset + info.operand_offset(idx),
+                    relocate_type,
+                ));
+            }
+        }
+    }
+
+    relocate_entries
+}
+
+/// Compares the relocate entries of a function with the entries which
+/// its bytecode requires, the issues are in the order of the offsets.
+pub fn check_function_relocate_coverage(
+    function_internal_index: usize,
+    code: &[u8],
+    relocate_entries: &[RelocateEntry],
+) -> Vec<RelocateCoverageIssue> {
+    let expected_relocate_entries = get_expected_relocate_entries(code);
+
+    let build_issue =
+        |entry: &RelocateEntry, issue_type: RelocateCoverageIssueType| RelocateCoverageIssue {
+            function_internal_index,
+            offset_in_function: entry.offset_in_function,
+            relocate_type: entry.relocate_type,
+            issue_type,
+        };
+
+    let mut issues = expected_relocate_entries
+        .iter()
+        .filter(|entry| !relocate_entries.contains(entry))
+        .map(|entry| build_issue(entry, RelocateCoverageIssueType::Missing))
+        .chain(
+            relocate_entries
+                .iter()
+                .filter(|entry| !expected_relocate_entries.contains(entry))
+                .map(|entry| build_issue(entry, RelocateCoverageIssueType::Extra)),
+        )
+        .collect::<Vec<_>>();
+
+    issues.sort_by_key(|issue| issue.offset_in_function);
+    issues
+}
+
+/// Checks the relocate lists of all functions of the image.
+///
+/// If the image has no relocate section (e.g., the relocations are stripped),
+/// all relocatable parameters are reported as missing.
+pub fn analyze_relocate_coverage(image: &ModuleImage) -> Vec<RelocateCoverageIssue> {
+    let function_section = image.get_function_section();
+    let opt_relocate_section = image.get_optional_relocate_section();
+
+    let mut issues = vec![];
+
+    for (function_internal_index, item) in function_section.items.iter().enumerate() {
+        let code = &function_section.codes_data
+            [item.code_offset as usize..(item.code_offset + item.code_length) as usize];
+
+        let relocate_entries = match &opt_relocate_section {
+            Some(relocate_section) if function_internal_index < relocate_section.lists.len() => {
+                relocate_section
+                    .get_relocate_list(function_internal_index)
+                    .iter()
+                    .map(|relocate_item| {
+                        RelocateEntry::new(
+                            relocate_item.offset_in_function as usize,
+                            relocate_item.relocate_type,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => vec![],
+        };
+
+        issues.extend(check_function_relocate_coverage(
+            function_internal_index,
+            code,
+            &relocate_entries,
+        ));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::opcode::Opcode;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        common_sections::{function_section::FunctionSection, relocate_section::RelocateSection},
+        entry::{FunctionEntry, RelocateEntry, RelocateListEntry},
+        module_image::{ImageType, ModuleImage, RelocateType, SectionEntry},
+        relocate_coverage::{
+            analyze_relocate_coverage, get_expected_relocate_entries, RelocateCoverageIssue,
+            RelocateCoverageIssueType,
+        },
+    };
+
+    #[test]
+    fn test_relocate_coverage() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 1, 2) // 0x0000
+            .append_opcode_i32(Opcode::call, 3) // 0x000c
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 5) // 0x0014
+            .append_opcode(Opcode::end) // 0x001c
+            .append_opcode_i32(Opcode::extcall, 7) // 0x0020
+            .append_opcode(Opcode::end) // 0x0028
+            .to_bytes();
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode_i32(Opcode::get_data, 1) // 0x0000
+            .append_opcode(Opcode::end) // 0x0008
+            .to_bytes();
+
+        assert_eq!(
+            get_expected_relocate_entries(&code0),
+            vec![
+                RelocateEntry::new(0x4, RelocateType::TypeIndex),
+                RelocateEntry::new(0x8, RelocateType::LocalVariableListIndex),
+                RelocateEntry::from_function_public_index(0xc),
+                RelocateEntry::from_data_public_index(0x14),
+                RelocateEntry::from_external_function_index(0x20),
+            ]
+        );
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, code0),
+            FunctionEntry::new(0, 0, code1),
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let (relocate_lists, relocate_list_data) = RelocateSection::convert_from_entries(&[
+            RelocateListEntry::new(vec![
+                RelocateEntry::new(0x4, RelocateType::TypeIndex),
+                RelocateEntry::new(0x8, RelocateType::LocalVariableListIndex),
+                // the `call` is missing
+                RelocateEntry::from_data_public_index(0x14),
+                // the wrong type
+                RelocateEntry::from_function_public_index(0x20),
+            ]),
+            RelocateListEntry::new(vec![
+                RelocateEntry::from_data_public_index(0x0),
+                // the extra entry
+                RelocateEntry::from_data_public_index(0x8),
+            ]),
+        ]);
+        let relocate_section = RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&function_section, &relocate_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        assert_eq!(
+            analyze_relocate_coverage(&image),
+            vec![
+                RelocateCoverageIssue {
+                    function_internal_index: 0,
+                    offset_in_function: 0x10,
+                    relocate_type: RelocateType::FunctionPublicIndex,
+                    issue_type: RelocateCoverageIssueType::Missing,
+                },
+                RelocateCoverageIssue {
+                    function_internal_index: 0,
+                    offset_in_function: 0x24,
+                    relocate_type: RelocateType::ExternalFunctionIndex,
+                    issue_type: RelocateCoverageIssueType::Missing,
+                },
+                RelocateCoverageIssue {
+                    function_internal_index: 0,
+                    offset_in_function: 0x24,
+                    relocate_type: RelocateType::FunctionPublicIndex,
+                    issue_type: RelocateCoverageIssueType::Extra,
+                },
+                RelocateCoverageIssue {
+                    function_internal_index: 1,
+                    offset_in_function: 0xc,
+                    relocate_type: RelocateType::DataPublicIndex,
+                    issue_type: RelocateCoverageIssueType::Extra,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,655 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The entry point for the fuzz harnesses.
+//
+// `roundtrip_check` takes arbitrary bytes and performs the following steps:
+//
+// 1. Reads the module header and the section table, and checks the tables and
+//    the data areas of all sections, the bytes which are not a well-formed module
+//    image are rejected, so that the reader never reads out of the bounds of
+//    the bytes (or the sections).
+// 2. Reads the bytes as entries (`ImageCommonEntry` and `ImageLinkingEntry`),
+//    the bytes are rejected if reading fails.
+// 3. Writes the entries as a new image and reads it again,
+//    the entries must be equal to the ones of step 2, and all sections of
+//    the source image must exist in the new image.
+//
+// Only the failures in step 3 indicate bugs of this crate.
+//
+// Note: the panics of step 3 are caught by `std::panic::catch_unwind` and
+// reported as inconsistencies, but the panic hook still prints the messages,
+// the harness can set a silent hook if needed.
+//
+// `sanitize` performs the steps 1 and 2, and then writes the entries as a new
+// image, i.e., the sections are written in the order of the writer, the padding
+// bytes and the unused bytes (e.g., the rest of the module name buffer) are zeroed,
+// and the strings are re-encoded. It neutralizes the hand-tampered images
+// before they are processed by the other tools.
+//
+// The sections which are not covered by the entries (i.e., the license, patch slot,
+// debug link and custom sections) are checked in step 1, and then copied to the
+// new image as they are.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use anc_isa::DataSectionType;
+
+use crate::{
+    common_sections::{
+        export_hash_section::{ExportHashItem, ExportHashSection},
+        external_function_section::{ExternalFunctionItem, ExternalFunctionSection},
+        external_library_section::{ExternalLibraryItem, ExternalLibrarySection},
+        function_hash_section::{FunctionHashItem, FunctionHashSection},
+        function_section::{FunctionItem, FunctionSection},
+        import_data_section::{ImportDataItem, ImportDataSection},
+        import_function_section::{ImportFunctionItem, ImportFunctionSection},
+        import_module_section::{ImportModuleItem, ImportModuleSection},
+        license_section::{LicenseItem, LicenseSection},
+        patch_slot_section::{PatchSlotItem, PatchSlotSection},
+        read_only_data_section::{self, ReadOnlyDataSection},
+        read_write_data_section::{self, ReadWriteDataSection},
+        uninit_data_section::{self, UninitDataSection},
+    },
+    entry::{ImageCommonEntry, ImageLinkingEntry},
+    entry_reader::{read_image_file, read_object_file},
+    entry_writer::{write_image_file_with_options, write_object_file_with_options, WriteOptions},
+    image_pipeline::ImageSections,
+    linking_sections::{
+        data_index_section::DataIndexSection,
+        external_function_index_section::ExternalFunctionIndexSection,
+        initialization_dependency_section::{
+            InitializationDependencyItem, InitializationDependencySection,
+        },
+        lazy_binding_section::{LazyBindingItem, LazyBindingSection},
+        unified_external_function_section::{self, UnifiedExternalFunctionSection},
+        unified_external_library_section::{self, UnifiedExternalLibrarySection},
+        unified_external_type_section::{self, UnifiedExternalTypeSection},
+    },
+    module_image::{ImageType, ModuleImage, ModuleSectionId, RangeItem, SectionEntry},
+    ImageError, ImageErrorType,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum RoundTripOutcome {
+    // The bytes are not a valid module image.
+    Rejected,
+
+    // The image is read, written and read again, and the entries are equal.
+    Consistent,
+
+    // The entries are changed after writing and reading, or writing fails.
+    Inconsistent(String),
+}
+
+/// Reads the bytes as a module image, writes it and reads it again,
+/// checks whether the entries are equal. This function never panics.
+pub fn roundtrip_check(image_binary: &[u8]) -> RoundTripOutcome {
+    let Some(module_image) = check_image(image_binary) else {
+        return RoundTripOutcome::Rejected;
+    };
+
+    let Some(entries) = read_entries(image_binary) else {
+        return RoundTripOutcome::Rejected;
+    };
+
+    let rewritten_binary = match catch_unwind(AssertUnwindSafe(|| {
+        rewrite_image(&module_image, &entries)
+    })) {
+        Ok(Ok(binary)) => binary,
+        Ok(Err(e)) => {
+            return RoundTripOutcome::Inconsistent(format!("Failed to write the image: {}", e))
+        }
+        Err(_) => {
+            return RoundTripOutcome::Inconsistent("Panicked when writing the image.".to_owned())
+        }
+    };
+
+    let reread_entries = match catch_unwind(|| read_entries(&rewritten_binary)) {
+        Ok(Some(entries)) => entries,
+        Ok(None) => {
+            return RoundTripOutcome::Inconsistent("Failed to read the rewritten image.".to_owned())
+        }
+        Err(_) => {
+            return RoundTripOutcome::Inconsistent(
+                "Panicked when reading the rewritten image.".to_owned(),
+            )
+        }
+    };
+
+    if reread_entries.0 != entries.0 {
+        RoundTripOutcome::Inconsistent("The common entries are changed.".to_owned())
+    } else if reread_entries.1 != entries.1 {
+        RoundTripOutcome::Inconsistent("The linking entries are changed.".to_owned())
+    } else if let Err(message) = compare_sections(&module_image, &rewritten_binary) {
+        RoundTripOutcome::Inconsistent(message)
+    } else {
+        RoundTripOutcome::Consistent
+    }
+}
+
+/// Re-writes the image in the normalized form.
+///
+/// Returns `ImageErrorType::InvalidImage` if the bytes cannot be read as a module image.
+pub fn sanitize(image_binary: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let Some(module_image) = check_image(image_binary) else {
+        return Err(ImageError::new(ImageErrorType::InvalidImage));
+    };
+
+    let Some(entries) = read_entries(image_binary) else {
+        return Err(ImageError::new(ImageErrorType::InvalidImage));
+    };
+
+    rewrite_image(&module_image, &entries)
+}
+
+// Reads the module header and the section table (`ModuleImage::read` checks
+// the bounds of the sections and the raw values of the enum fields), and then
+// checks the tables and the data areas of all sections.
+fn check_image(image_binary: &[u8]) -> Option<ModuleImage<'_>> {
+    ModuleImage::read(image_binary)
+        .ok()
+        .filter(|module_image| check_sections(module_image))
+}
+
+fn check_sections(module_image: &ModuleImage) -> bool {
+    // The numbers of the items which are referenced by the other sections.
+    let function_count = get_item_count::<FunctionItem, FunctionSection>(
+        module_image,
+        ModuleSectionId::Function,
+        |section| section.items.len(),
+    );
+    let get_data_count = |section_type: DataSectionType| match section_type {
+        DataSectionType::ReadOnly => {
+            get_item_count::<read_only_data_section::DataItem, ReadOnlyDataSection>(
+                module_image,
+                ModuleSectionId::ReadOnlyData,
+                |section| section.items.len(),
+            )
+        }
+        DataSectionType::ReadWrite => {
+            get_item_count::<read_write_data_section::DataItem, ReadWriteDataSection>(
+                module_image,
+                ModuleSectionId::ReadWriteData,
+                |section| section.items.len(),
+            )
+        }
+        DataSectionType::Uninit => {
+            get_item_count::<uninit_data_section::DataItem, UninitDataSection>(
+                module_image,
+                ModuleSectionId::UninitData,
+                |section| section.items.len(),
+            )
+        }
+    };
+    let unified_external_function_count = get_item_count::<
+        unified_external_function_section::ExternalFunctionItem,
+        UnifiedExternalFunctionSection,
+    >(
+        module_image,
+        ModuleSectionId::UnifiedExternalFunction,
+        |section| section.items.len(),
+    );
+    let module_count = get_item_count::<RangeItem, ExternalFunctionIndexSection>(
+        module_image,
+        ModuleSectionId::ExternalFunctionIndex,
+        |section| section.ranges.len(),
+    );
+
+    module_image.items.iter().all(|item| match item.id {
+        ModuleSectionId::Property => module_image.try_get_property_section().is_ok(),
+        ModuleSectionId::Type => module_image.try_get_type_section().is_ok(),
+        ModuleSectionId::LocalVariable => module_image.try_get_local_variable_section().is_ok(),
+        ModuleSectionId::Function => module_image.try_get_function_section().is_ok(),
+        ModuleSectionId::ReadOnlyData => {
+            check_section::<read_only_data_section::DataItem, ReadOnlyDataSection>(
+                module_image,
+                item.id,
+                ReadOnlyDataSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::ReadWriteData => {
+            check_section::<read_write_data_section::DataItem, ReadWriteDataSection>(
+                module_image,
+                item.id,
+                ReadWriteDataSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::UninitData => check_section::<
+            uninit_data_section::DataItem,
+            UninitDataSection,
+        >(module_image, item.id, |_| true),
+        ModuleSectionId::FunctionName => module_image
+            .try_get_optional_export_function_section()
+            .is_ok(),
+        ModuleSectionId::DataName => module_image.try_get_optional_export_data_section().is_ok(),
+        ModuleSectionId::Relocate => module_image.try_get_optional_relocate_section().is_ok(),
+        ModuleSectionId::ExportHash => {
+            check_section::<ExportHashItem, ExportHashSection>(module_image, item.id, |section| {
+                section.is_well_formed(function_count, &get_data_count)
+            })
+        }
+        ModuleSectionId::FunctionHash => check_section::<FunctionHashItem, FunctionHashSection>(
+            module_image,
+            item.id,
+            |section| section.is_well_formed(function_count),
+        ),
+        // The debug link section contains only the hash of the debug file (u64).
+        ModuleSectionId::DebugLink => item.length as usize >= size_of::<u64>(),
+        ModuleSectionId::License => check_section::<LicenseItem, LicenseSection>(
+            module_image,
+            item.id,
+            LicenseSection::is_well_formed,
+        ),
+        ModuleSectionId::PatchSlot => check_section::<PatchSlotItem, PatchSlotSection>(
+            module_image,
+            item.id,
+            PatchSlotSection::is_well_formed,
+        ),
+        ModuleSectionId::ImportModule => check_section::<ImportModuleItem, ImportModuleSection>(
+            module_image,
+            item.id,
+            ImportModuleSection::is_well_formed,
+        ),
+        ModuleSectionId::ImportFunction => {
+            check_section::<ImportFunctionItem, ImportFunctionSection>(
+                module_image,
+                item.id,
+                ImportFunctionSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::ImportData => check_section::<ImportDataItem, ImportDataSection>(
+            module_image,
+            item.id,
+            ImportDataSection::is_well_formed,
+        ),
+        ModuleSectionId::ExternalLibrary => {
+            check_section::<ExternalLibraryItem, ExternalLibrarySection>(
+                module_image,
+                item.id,
+                ExternalLibrarySection::is_well_formed,
+            )
+        }
+        ModuleSectionId::ExternalFunction => {
+            check_section::<ExternalFunctionItem, ExternalFunctionSection>(
+                module_image,
+                item.id,
+                ExternalFunctionSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::Initializer => module_image.try_get_optional_initializer_section().is_ok(),
+        ModuleSectionId::EntryPoint => module_image.try_get_entry_point_section().is_ok(),
+        ModuleSectionId::FunctionIndex => module_image.try_get_function_index_section().is_ok(),
+        ModuleSectionId::LinkingModule => module_image
+            .try_get_dynamic_link_module_list_section()
+            .is_ok(),
+        ModuleSectionId::DataIndex => check_section::<RangeItem, DataIndexSection>(
+            module_image,
+            item.id,
+            DataIndexSection::is_well_formed,
+        ),
+        ModuleSectionId::InitializationDependency => check_section::<
+            InitializationDependencyItem,
+            InitializationDependencySection,
+        >(module_image, item.id, |_| true),
+        ModuleSectionId::UnifiedExternalType => {
+            check_section::<unified_external_type_section::TypeItem, UnifiedExternalTypeSection>(
+                module_image,
+                item.id,
+                UnifiedExternalTypeSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::UnifiedExternalLibrary => check_section::<
+            unified_external_library_section::ExternalLibraryItem,
+            UnifiedExternalLibrarySection,
+        >(
+            module_image,
+            item.id,
+            UnifiedExternalLibrarySection::is_well_formed,
+        ),
+        ModuleSectionId::UnifiedExternalFunction => check_section::<
+            unified_external_function_section::ExternalFunctionItem,
+            UnifiedExternalFunctionSection,
+        >(
+            module_image,
+            item.id,
+            UnifiedExternalFunctionSection::is_well_formed,
+        ),
+        ModuleSectionId::ExternalFunctionIndex => {
+            check_section::<RangeItem, ExternalFunctionIndexSection>(
+                module_image,
+                item.id,
+                ExternalFunctionIndexSection::is_well_formed,
+            )
+        }
+        ModuleSectionId::LazyBinding => {
+            check_section::<LazyBindingItem, LazyBindingSection>(module_image, item.id, |section| {
+                section.is_well_formed(unified_external_function_count, module_count)
+            })
+        }
+        // The custom sections are opaque bytes.
+        ModuleSectionId::Custom0
+        | ModuleSectionId::Custom1
+        | ModuleSectionId::Custom2
+        | ModuleSectionId::Custom3
+        | ModuleSectionId::Custom4
+        | ModuleSectionId::Custom5
+        | ModuleSectionId::Custom6
+        | ModuleSectionId::Custom7 => true,
+    })
+}
+
+// `I` is the type of the (first) table item of the section.
+fn check_section<'a, I, T: SectionEntry<'a>>(
+    module_image: &'a ModuleImage<'a>,
+    section_id: ModuleSectionId,
+    is_well_formed: impl Fn(&T) -> bool,
+) -> bool {
+    module_image
+        .try_get_optional_section::<I, T>(section_id, is_well_formed)
+        .is_ok()
+}
+
+// Returns the number of the items of the section, or `0` if the section does
+// not exist or its table is malformed (it is reported by the check of the section).
+fn get_item_count<'a, I, T: SectionEntry<'a>>(
+    module_image: &'a ModuleImage<'a>,
+    section_id: ModuleSectionId,
+    get_count: impl Fn(&T) -> usize,
+) -> usize {
+    module_image
+        .try_get_optional_section::<I, T>(section_id, |_| true)
+        .ok()
+        .flatten()
+        .map_or(0, |section| get_count(&section))
+}
+
+fn read_entries(image_binary: &[u8]) -> Option<(ImageCommonEntry, Option<ImageLinkingEntry>)> {
+    // The image type is at the offset 8 of the header.
+    let image_type = u16::from_le_bytes(image_binary[8..10].try_into().unwrap());
+
+    if image_type == ImageType::Application as u16 {
+        read_image_file(image_binary)
+            .ok()
+            .map(|(image_common_entry, image_linking_entry)| {
+                (image_common_entry, Some(image_linking_entry))
+            })
+    } else {
+        read_object_file(image_binary)
+            .ok()
+            .map(|image_common_entry| (image_common_entry, None))
+    }
+}
+
+fn write_entries(
+    (image_common_entry, image_linking_entry): &(ImageCommonEntry, Option<ImageLinkingEntry>),
+    options: &WriteOptions,
+) -> Result<Vec<u8>, ImageError> {
+    let mut image_binary: Vec<u8> = vec![];
+
+    match image_linking_entry {
+        Some(image_linking_entry) => write_image_file_with_options(
+            image_common_entry,
+            image_linking_entry,
+            options,
+            &mut image_binary,
+        )?,
+        None => write_object_file_with_options(
+            image_common_entry,
+            image_common_entry.image_type == ImageType::SharedModule,
+            options,
+            &mut image_binary,
+        )?,
+    }
+
+    Ok(image_binary)
+}
+
+// Writes the entries as a new image, and copies the sections which are not
+// covered by the entries from the source image.
+fn rewrite_image(
+    module_image: &ModuleImage,
+    entries: &(ImageCommonEntry, Option<ImageLinkingEntry>),
+) -> Result<Vec<u8>, ImageError> {
+    // The function hashes are derived from the functions, they are computed
+    // again if the source image contains them.
+    let options = WriteOptions {
+        emit_function_hashes: get_item_count::<FunctionHashItem, FunctionHashSection>(
+            module_image,
+            ModuleSectionId::FunctionHash,
+            |section| section.items.len(),
+        ) > 0,
+        ..WriteOptions::default()
+    };
+    let rewritten_binary = write_entries(entries, &options)?;
+
+    let opaque_sections = ImageSections::from_module_image(module_image)
+        .sections
+        .into_iter()
+        .filter(|(section_id, _)| is_opaque_section(*section_id))
+        .collect::<Vec<_>>();
+
+    if opaque_sections.is_empty() {
+        return Ok(rewritten_binary);
+    }
+
+    let mut image_sections =
+        ImageSections::from_module_image(&ModuleImage::read(&rewritten_binary)?);
+    for (section_id, section_data) in opaque_sections {
+        image_sections.set_section_data(section_id, section_data);
+    }
+
+    let mut image_binary: Vec<u8> = vec![];
+    image_sections.write(&mut image_binary)?;
+    Ok(image_binary)
+}
+
+// Checks that all sections of the source image exist in the rewritten image,
+// and the sections which are not covered by the entries are unchanged.
+fn compare_sections(module_image: &ModuleImage, rewritten_binary: &[u8]) -> Result<(), String> {
+    let rewritten_image = ModuleImage::read(rewritten_binary)
+        .map_err(|_| "Failed to read the rewritten image.".to_owned())?;
+    let rewritten_sections = ImageSections::from_module_image(&rewritten_image);
+
+    for (section_id, section_data) in ImageSections::from_module_image(module_image).sections {
+        match rewritten_sections.get_section_data(section_id) {
+            None => {
+                return Err(format!("The section \"{}\" is lost.", section_id.name()));
+            }
+            Some(data) if is_opaque_section(section_id) && data != section_data => {
+                return Err(format!("The section \"{}\" is changed.", section_id.name()));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// The sections which are not read into the entries.
+fn is_opaque_section(section_id: ModuleSectionId) -> bool {
+    section_id.is_custom()
+        || matches!(
+            section_id,
+            ModuleSectionId::License | ModuleSectionId::PatchSlot | ModuleSectionId::DebugLink
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::offset_of;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        common_sections::{function_section::FunctionItem, license_section::LicenseItem},
+        entry::LicenseEntry,
+        entry_writer::build_minimal_module,
+        license_scan::attach_licenses,
+        module_image::ModuleImage,
+        roundtrip::{roundtrip_check, sanitize, RoundTripOutcome},
+        utils::helper_build_application_fixture,
+    };
+
+    #[test]
+    fn test_roundtrip_check() {
+        let fixture = helper_build_application_fixture(2);
+        assert_eq!(
+            roundtrip_check(&fixture.application_binary),
+            RoundTripOutcome::Consistent
+        );
+        assert_eq!(
+            roundtrip_check(&fixture.dependency_module_binaries[0]),
+            RoundTripOutcome::Consistent
+        );
+
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+        assert_eq!(
+            roundtrip_check(&object_binary),
+            RoundTripOutcome::Consistent
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_check_rejected() {
+        assert_eq!(roundtrip_check(&[]), RoundTripOutcome::Rejected);
+        assert_eq!(roundtrip_check(b"ancmod\0\0"), RoundTripOutcome::Rejected);
+
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+
+        // truncated
+        assert_eq!(
+            roundtrip_check(&object_binary[..object_binary.len() - 4]),
+            RoundTripOutcome::Rejected
+        );
+
+        // invalid image type
+        let mut binary = object_binary.clone();
+        binary[8] = 0xff;
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+
+        // invalid section id, the first item of the section table
+        // is at `module header (16 bytes) + table header (8 bytes)`.
+        let mut binary = object_binary.clone();
+        binary[24] = 0xff;
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_roundtrip_check_corrupted_sections() {
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+        let name_position = object_binary
+            .windows(3)
+            .position(|window| window == b"foo")
+            .unwrap();
+
+        // the module name length exceeds the name buffer,
+        // the length (u32) is located before the name buffer.
+        let mut binary = object_binary.clone();
+        binary[(name_position - 4)..name_position].copy_from_slice(&0x1000_u32.to_le_bytes());
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+
+        // the module name is not a valid UTF-8 string
+        let mut binary = object_binary.clone();
+        binary[name_position] = 0xff;
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+        assert!(sanitize(&binary).is_err());
+
+        // the code of a function is out of the bounds of the function section
+        let fixture = helper_build_application_fixture(1);
+        let module_image = ModuleImage::read(&fixture.application_binary).unwrap();
+        let function_section = module_image.try_get_function_section().unwrap();
+        let code_length_position = function_section.items.as_ptr() as usize
+            - fixture.application_binary.as_ptr() as usize
+            + offset_of!(FunctionItem, code_length);
+
+        let mut binary = fixture.application_binary.clone();
+        binary[code_length_position..(code_length_position + 4)]
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_roundtrip_check_with_license_section() {
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&object_binary).unwrap();
+
+        let mut licensed_binary: Vec<u8> = vec![];
+        attach_licenses(
+            &module_image,
+            &[LicenseEntry::for_module("MIT".to_owned())],
+            &mut licensed_binary,
+        )
+        .unwrap();
+
+        assert_eq!(
+            roundtrip_check(&licensed_binary),
+            RoundTripOutcome::Consistent
+        );
+
+        // the license exceeds the data area of the license section
+        let licensed_image = ModuleImage::read(&licensed_binary).unwrap();
+        let license_section = licensed_image.get_optional_license_section().unwrap();
+        let license_length_position = license_section.items.as_ptr() as usize
+            - licensed_binary.as_ptr() as usize
+            + offset_of!(LicenseItem, license_length);
+
+        let mut binary = licensed_binary.clone();
+        binary[license_length_position..(license_length_position + 4)]
+            .copy_from_slice(&0x1000_u32.to_le_bytes());
+        assert_eq!(roundtrip_check(&binary), RoundTripOutcome::Rejected);
+        assert!(sanitize(&binary).is_err());
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+        let sanitized_binary = sanitize(&object_binary).unwrap();
+
+        // tamper the unused bytes of the module name buffer
+        let name_position = object_binary
+            .windows(3)
+            .position(|window| window == b"foo")
+            .unwrap();
+        let mut tampered_binary = object_binary.clone();
+        tampered_binary[(name_position + 3)..(name_position + 11)].fill(0xaa);
+
+        assert_eq!(sanitize(&tampered_binary).unwrap(), sanitized_binary);
+
+        // idempotent
+        assert_eq!(sanitize(&sanitized_binary).unwrap(), sanitized_binary);
+
+        assert!(sanitize(b"ancmod\0\0").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_with_license_section() {
+        let object_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&object_binary).unwrap();
+
+        let mut licensed_binary: Vec<u8> = vec![];
+        attach_licenses(
+            &module_image,
+            &[LicenseEntry::for_module("MIT".to_owned())],
+            &mut licensed_binary,
+        )
+        .unwrap();
+
+        // the license section is kept
+        let sanitized_binary = sanitize(&licensed_binary).unwrap();
+        let sanitized_image = ModuleImage::read(&sanitized_binary).unwrap();
+        assert_eq!(
+            sanitized_image
+                .get_optional_license_section()
+                .unwrap()
+                .get_module_license(),
+            Some("MIT")
+        );
+
+        // idempotent
+        assert_eq!(sanitize(&sanitized_binary).unwrap(), sanitized_binary);
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The registry of the custom section codecs.
+//
+// The section ids `Custom0` to `Custom7` (0x00f0 to 0x00f7) are reserved
+// for the sections defined outside this crate, e.g., the experimental sections
+// which are prototyped by other crates before being adopted by the image format.
+//
+// The other crates implement `SectionCodec` for their sections and register
+// the codecs with the custom section ids, then the registry is used for:
+//
+// - reading: the custom sections of an image are validated by the codecs,
+//   see `SectionCodecRegistry::read_custom_sections`.
+// - writing: the custom sections are validated and added to an image,
+//   see `SectionCodecRegistry::write_custom_sections`.
+// - dumping: the custom sections are formatted as text by the codecs,
+//   see `SectionCodecRegistry::dump_custom_sections`.
+//
+// The other tools of this crate treat the custom sections as opaque bytes,
+// e.g., the `image_pipeline` copies them as they are.
+
+use std::fmt::Display;
+
+use crate::{
+    image_pipeline::ImageSections,
+    module_image::{ModuleImage, ModuleSectionId},
+    ImageError, ImageErrorType,
+};
+
+// The reasons why the registry rejects a section or a codec.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SectionRegistryError {
+    NonCustomSection(ModuleSectionId),
+    AlreadyRegistered {
+        section_id: ModuleSectionId,
+        codec_name: String,
+    },
+    Unregistered(ModuleSectionId),
+
+    // `message` is the message returned by `SectionCodec::validate`.
+    InvalidSection {
+        section_id: ModuleSectionId,
+        codec_name: String,
+        message: String,
+    },
+}
+
+impl Display for SectionRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionRegistryError::NonCustomSection(section_id) => write!(
+                f,
+                "The section id {:?} is not a custom section id.",
+                section_id
+            ),
+            SectionRegistryError::AlreadyRegistered {
+                section_id,
+                codec_name,
+            } => write!(
+                f,
+                "The section id {:?} is already registered by \"{}\".",
+                section_id, codec_name
+            ),
+            SectionRegistryError::Unregistered(section_id) => {
+                write!(f, "The section id {:?} is not registered.", section_id)
+            }
+            SectionRegistryError::InvalidSection {
+                section_id,
+                codec_name,
+                message,
+            } => write!(
+                f,
+                "Invalid section {} ({}): {}",
+                section_id.name(),
+                codec_name,
+                message
+            ),
+        }
+    }
+}
+
+pub trait SectionCodec {
+    /// Returns the name of the section, e.g. "source_map", for labeling the section.
+    fn name(&self) -> &str;
+
+    /// Checks the section data, returns the error message if it is malformed.
+    fn validate(&self, _section_data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Formats the section data as text.
+    fn dump(&self, section_data: &[u8]) -> String;
+}
+
+#[derive(Default)]
+pub struct SectionCodecRegistry {
+    codecs: Vec<(ModuleSectionId, Box<dyn SectionCodec>)>,
+}
+
+impl SectionCodecRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the codec with the custom section id.
+    ///
+    /// Returns an error if the id is not a custom section id, or it is already registered.
+    pub fn register(
+        &mut self,
+        section_id: ModuleSectionId,
+        codec: impl SectionCodec + 'static,
+    ) -> Result<(), ImageError> {
+        if !section_id.is_custom() {
+            return Err(ImageError::new(ImageErrorType::SectionRegistry(
+                SectionRegistryError::NonCustomSection(section_id),
+            )));
+        }
+
+        if let Some(existing_codec) = self.get_codec(section_id) {
+            return Err(ImageError::new(ImageErrorType::SectionRegistry(
+                SectionRegistryError::AlreadyRegistered {
+                    section_id,
+                    codec_name: existing_codec.name().to_owned(),
+                },
+            )));
+        }
+
+        self.codecs.push((section_id, Box::new(codec)));
+        Ok(())
+    }
+
+    pub fn get_codec(&self, section_id: ModuleSectionId) -> Option<&dyn SectionCodec> {
+        self.codecs
+            .iter()
+            .find(|(id, _)| *id == section_id)
+            .map(|(_, codec)| codec.as_ref())
+    }
+
+    /// Returns the registered custom sections of the image, in the order
+    /// of the section table, each section is validated by its codec.
+    ///
+    /// The custom sections without codecs are skipped.
+    pub fn read_custom_sections<'a>(
+        &self,
+        image: &'a ModuleImage<'a>,
+    ) -> Result<Vec<(ModuleSectionId, &'a [u8])>, ImageError> {
+        let mut sections = vec![];
+
+        for (section_id, _, _) in image.sections() {
+            let Some(codec) = self.get_codec(section_id) else {
+                continue;
+            };
+
+            let section_data = image.get_optional_custom_section_data(section_id).unwrap();
+            codec
+                .validate(section_data)
+                .map_err(|message| invalid_section_error(section_id, codec, message))?;
+            sections.push((section_id, section_data));
+        }
+
+        Ok(sections)
+    }
+
+    /// Writes the image with the specified custom sections added (or replaced),
+    /// each section must have a registered codec and pass the validation.
+    pub fn write_custom_sections(
+        &self,
+        image: &ModuleImage,
+        custom_sections: &[(ModuleSectionId, Vec<u8>)],
+        writer: &mut dyn std::io::Write,
+    ) -> Result<(), ImageError> {
+        let mut image_sections = ImageSections::from_module_image(image);
+
+        for (section_id, section_data) in custom_sections {
+            let Some(codec) = self.get_codec(*section_id) else {
+                return Err(ImageError::new(ImageErrorType::SectionRegistry(
+                    SectionRegistryError::Unregistered(*section_id),
+                )));
+            };
+
+            codec
+                .validate(section_data)
+                .map_err(|message| invalid_section_error(*section_id, codec, message))?;
+            image_sections.set_section_data(*section_id, section_data.clone());
+        }
+
+        image_sections.write(writer)
+    }
+
+    /// Formats all custom sections of the image, e.g.:
+    ///
+    /// ```text
+    /// custom0 (source_map):
+    /// ...the text of the codec...
+    ///
+    /// custom1: 16 bytes
+    /// ```
+    ///
+    /// The custom sections without codecs are listed with their lengths only.
+    pub fn dump_custom_sections(&self, image: &ModuleImage) -> String {
+        image
+            .sections()
+            .filter(|(section_id, _, _)| section_id.is_custom())
+            .map(|(section_id, _, length)| match self.get_codec(section_id) {
+                Some(codec) => {
+                    let section_data = image.get_optional_custom_section_data(section_id).unwrap();
+                    format!(
+                        "{} ({}):\n{}",
+                        section_id.name(),
+                        codec.name(),
+                        codec.dump(section_data)
+                    )
+                }
+                None => format!("{}: {} bytes", section_id.name(), length),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn invalid_section_error(
+    section_id: ModuleSectionId,
+    codec: &dyn SectionCodec,
+    message: String,
+) -> ImageError {
+    ImageError::new(ImageErrorType::SectionRegistry(
+        SectionRegistryError::InvalidSection {
+            section_id,
+            codec_name: codec.name().to_owned(),
+            message,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry_writer::build_minimal_module,
+        module_image::{ModuleImage, ModuleSectionId},
+        section_registry::{SectionCodec, SectionCodecRegistry},
+    };
+
+    // A section which contains a list of u32 numbers.
+    struct NumberListCodec;
+
+    impl SectionCodec for NumberListCodec {
+        fn name(&self) -> &str {
+            "number_list"
+        }
+
+        fn validate(&self, section_data: &[u8]) -> Result<(), String> {
+            if section_data.len() % 4 == 0 {
+                Ok(())
+            } else {
+                Err("The length is not a multiple of 4.".to_owned())
+            }
+        }
+
+        fn dump(&self, section_data: &[u8]) -> String {
+            section_data
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = SectionCodecRegistry::new();
+        assert!(registry
+            .register(ModuleSectionId::Custom0, NumberListCodec)
+            .is_ok());
+        assert_eq!(
+            registry
+                .register(ModuleSectionId::Custom0, NumberListCodec)
+                .unwrap_err()
+                .to_string(),
+            "Section registry error: The section id Custom0 is already registered \
+            by \"number_list\"."
+        );
+        assert_eq!(
+            registry
+                .register(ModuleSectionId::Relocate, NumberListCodec)
+                .unwrap_err()
+                .to_string(),
+            "Section registry error: The section id Relocate is not a custom section id."
+        );
+    }
+
+    #[test]
+    fn test_read_write_and_dump_custom_sections() {
+        let mut registry = SectionCodecRegistry::new();
+        registry
+            .register(ModuleSectionId::Custom1, NumberListCodec)
+            .unwrap();
+
+        let image_binary = build_minimal_module("foo", &[]).unwrap();
+        let module_image = ModuleImage::read(&image_binary).unwrap();
+
+        let mut output_binary: Vec<u8> = vec![];
+        registry
+            .write_custom_sections(
+                &module_image,
+                &[(ModuleSectionId::Custom1, vec![11, 0, 0, 0, 13, 0, 0, 0])],
+                &mut output_binary,
+            )
+            .unwrap();
+
+        // the malformed section is rejected
+        assert_eq!(
+            registry
+                .write_custom_sections(
+                    &module_image,
+                    &[(ModuleSectionId::Custom1, vec![11, 0])],
+                    &mut Vec::<u8>::new(),
+                )
+                .unwrap_err()
+                .to_string(),
+            "Section registry error: Invalid section custom1 (number_list): \
+            The length is not a multiple of 4."
+        );
+
+        let output_module_image = ModuleImage::read(&output_binary).unwrap();
+        assert_eq!(
+            registry.read_custom_sections(&output_module_image).unwrap(),
+            vec![(
+                ModuleSectionId::Custom1,
+                [11u8, 0, 0, 0, 13, 0, 0, 0].as_slice()
+            )]
+        );
+        assert_eq!(
+            registry.dump_custom_sections(&output_module_image),
+            "custom1 (number_list):\n11, 13"
+        );
+
+        // the custom sections without codecs are opaque
+        let registry = SectionCodecRegistry::new();
+        assert_eq!(
+            registry.read_custom_sections(&output_module_image).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            registry.dump_custom_sections(&output_module_image),
+            "custom1: 8 bytes"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Builds the content of a "struct" data item.
+//
+// The fields are laid out in the order of appending, following the C rules:
+//
+// - Each field is placed at the next offset which is a multiple of its alignment,
+//   the gap is filled with zero (the padding).
+// - The alignment of the struct is the maximum alignment of the fields.
+// - The total size is rounded up to a multiple of the struct alignment.
+//
+// e.g., the struct `{a: i8, b: i32, c: i16}` is laid out as:
+//
+// | offset | content     |
+// |--------|-------------|
+// | 0      | a           |
+// | 1..4   | padding     |
+// | 4..8   | b           |
+// | 8..10  | c           |
+// | 10..12 | padding     |
+//
+// size 12, align 4.
+//
+// The result is a data entry of type "bytes" with the struct alignment,
+// see `ReadOnlyDataEntry::from_bytes` and `ReadWriteDataEntry::from_bytes`.
+
+use crate::entry::{ReadOnlyDataEntry, ReadWriteDataEntry};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructField {
+    pub name: String,
+    pub offset: usize, // The offset of the field within the struct, in bytes.
+    pub length: usize, // The length of the field (padding excluded), in bytes.
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructDataBuilder {
+    data: Vec<u8>,
+    align: u16,
+    fields: Vec<StructField>,
+}
+
+impl Default for StructDataBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Chain calling style for appending fields.
+///
+/// Note that 'i32' in method name means a 32-bit integer, which is equivalent to
+/// the 'uint32_t' in C or 'u32' in Rust, the same applies to the i8, i16, and i64.
+impl StructDataBuilder {
+    pub fn new() -> Self {
+        Self {
+            data: vec![],
+            align: 1,
+            fields: vec![],
+        }
+    }
+
+    pub fn append_i8(self, name: &str, value: u8) -> Self {
+        self.append_bytes(name, &[value], 1)
+    }
+
+    pub fn append_i16(self, name: &str, value: u16) -> Self {
+        self.append_bytes(name, &value.to_le_bytes(), 2)
+    }
+
+    pub fn append_i32(self, name: &str, value: u32) -> Self {
+        self.append_bytes(name, &value.to_le_bytes(), 4)
+    }
+
+    pub fn append_i64(self, name: &str, value: u64) -> Self {
+        self.append_bytes(name, &value.to_le_bytes(), 8)
+    }
+
+    pub fn append_f32(self, name: &str, value: f32) -> Self {
+        self.append_bytes(name, &value.to_le_bytes(), 4)
+    }
+
+    pub fn append_f64(self, name: &str, value: f64) -> Self {
+        self.append_bytes(name, &value.to_le_bytes(), 8)
+    }
+
+    /// Appends a field of raw bytes, e.g., a fixed-length array or a nested struct,
+    /// the `align` should be the alignment of the element (or the nested struct).
+    ///
+    /// The value `0` of `align` is treated as `1`.
+    pub fn append_bytes(mut self, name: &str, value: &[u8], align: u16) -> Self {
+        let align = align.max(1);
+        let offset = self.data.len().next_multiple_of(align as usize);
+
+        self.data.resize(offset, 0);
+        self.data.extend_from_slice(value);
+        self.align = self.align.max(align);
+        self.fields.push(StructField {
+            name: name.to_owned(),
+            offset,
+            length: value.len(),
+        });
+        self
+    }
+
+    /// Returns the current struct alignment, i.e., the maximum alignment of the fields.
+    pub fn align(&self) -> u16 {
+        self.align
+    }
+
+    /// Returns the current struct size, the tail padding is included.
+    pub fn size(&self) -> usize {
+        self.data.len().next_multiple_of(self.align as usize)
+    }
+
+    pub fn fields(&self) -> &[StructField] {
+        &self.fields
+    }
+
+    /// Returns the offset of the specified field, or `None` if the field does not exist.
+    pub fn get_field_offset(&self, name: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.offset)
+    }
+
+    /// Returns the struct content, the tail padding is included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = self.data.clone();
+        data.resize(self.size(), 0);
+        data
+    }
+
+    /// Builds the read-write data entry and returns it along with the fields.
+    pub fn build(self) -> (ReadWriteDataEntry, Vec<StructField>) {
+        let entry = ReadWriteDataEntry::from_bytes(self.to_bytes(), self.align);
+        (entry, self.fields)
+    }
+
+    /// Builds the read-only data entry and returns it along with the fields.
+    pub fn build_read_only(self) -> (ReadOnlyDataEntry, Vec<StructField>) {
+        let entry = ReadOnlyDataEntry::from_bytes(self.to_bytes(), self.align);
+        (entry, self.fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::MemoryDataType;
+    use pretty_assertions::assert_eq;
+
+    use crate::struct_data_builder::{StructDataBuilder, StructField};
+
+    #[test]
+    fn test_struct_data_builder() {
+        let builder = StructDataBuilder::new()
+            .append_i8("a", 0x11)
+            .append_i32("b", 0x22)
+            .append_i16("c", 0x33);
+
+        assert_eq!(builder.align(), 4);
+        assert_eq!(builder.size(), 12);
+        assert_eq!(builder.get_field_offset("b"), Some(4));
+        assert_eq!(builder.get_field_offset("d"), None);
+
+        let (entry, fields) = builder.build();
+        assert_eq!(entry.memory_data_type, MemoryDataType::Bytes);
+        assert_eq!(entry.length, 12);
+        assert_eq!(entry.align, 4);
+        assert_eq!(
+            entry.data,
+            vec![
+                0x11, 0, 0, 0, // a, padding
+                0x22, 0, 0, 0, // b
+                0x33, 0, // c
+                0, 0, // padding
+            ]
+        );
+        assert_eq!(
+            fields,
+            vec![
+                StructField {
+                    name: "a".to_owned(),
+                    offset: 0,
+                    length: 1
+                },
+                StructField {
+                    name: "b".to_owned(),
+                    offset: 4,
+                    length: 4
+                },
+                StructField {
+                    name: "c".to_owned(),
+                    offset: 8,
+                    length: 2
+                },
+            ]
+        );
+
+        // nested struct and floating-point fields
+        let inner = StructDataBuilder::new()
+            .append_i16("x", 1)
+            .append_i16("y", 2);
+        let (entry, fields) = StructDataBuilder::new()
+            .append_bytes("point", &inner.to_bytes(), inner.align())
+            .append_f64("scale", 1.0)
+            .build_read_only();
+
+        assert_eq!(entry.length, 16);
+        assert_eq!(entry.align, 8);
+        assert_eq!(fields[1].offset, 8);
+        assert_eq!(&entry.data[0..4], &[1, 0, 2, 0]);
+        assert_eq!(&entry.data[8..16], &1.0f64.to_le_bytes());
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Discovers the unit tests of a module, see the "Internal Entry Point Names"
+// in `entry` for the naming convention:
+//
+// - Executes Function: `{app_module_name}::tests::{submodule_name}::test_*`
+// - Internal Name (i.e., the unit name): `{submodule_name}::test_*`
+//
+// The tests are collected from:
+//
+// - the "function name" section, the functions whose full names follow the
+//   convention above, this works for both the object files and the images.
+// - the "entry point" section (if present), the entry points whose unit names
+//   follow the convention above.
+//
+// The submodule name can be a namespace path, e.g. "math::float", the functions
+// directly under the "tests" namespace (i.e., without a submodule) are not tests.
+//
+// The tests are grouped by the submodule name, the groups and the tests in
+// each group are sorted by name, and the unit names can be passed to the
+// test runner of the runtime directly.
+
+use crate::{module_image::ModuleImage, public_index::PublicIndexSpace, ImageError};
+
+const NAME_PATH_SEPARATOR: &str = "::";
+const TESTS_NAMESPACE: &str = "tests";
+const TEST_FUNCTION_NAME_PREFIX: &str = "test_";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TestUnit {
+    // e.g. "math::test_add".
+    pub unit_name: String,
+
+    // The last segment of the unit name, e.g. "test_add".
+    pub name: String,
+
+    pub function_public_index: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TestGroup {
+    // e.g. "math".
+    pub submodule_name: String,
+    pub tests: Vec<TestUnit>,
+}
+
+/// Returns the unit name of the test function, or `None` if the full name of
+/// the function does not follow the `{app_module_name}::tests::{submodule_name}::test_*`
+/// convention.
+pub fn get_test_unit_name<'a>(app_module_name: &str, full_name: &'a str) -> Option<&'a str> {
+    let unit_name = full_name
+        .strip_prefix(app_module_name)?
+        .strip_prefix(NAME_PATH_SEPARATOR)?
+        .strip_prefix(TESTS_NAMESPACE)?
+        .strip_prefix(NAME_PATH_SEPARATOR)?;
+
+    is_test_unit_name(unit_name).then_some(unit_name)
+}
+
+/// Returns `true` if the unit name follows the `{submodule_name}::test_*` convention.
+pub fn is_test_unit_name(unit_name: &str) -> bool {
+    match unit_name.rsplit_once(NAME_PATH_SEPARATOR) {
+        Some((submodule_name, name)) => {
+            !submodule_name.is_empty()
+                && name.len() > TEST_FUNCTION_NAME_PREFIX.len()
+                && name.starts_with(TEST_FUNCTION_NAME_PREFIX)
+        }
+        None => false,
+    }
+}
+
+/// Enumerates the unit tests of the module, grouped by the submodule name.
+pub fn discover_tests(image: &ModuleImage) -> Result<Vec<TestGroup>, ImageError> {
+    let property_section = image.get_property_section();
+    let app_module_name = property_section.get_module_name();
+    let public_index_space = PublicIndexSpace::from_module_image(image)?;
+
+    // `(unit_name, function_public_index)`
+    let mut unit_entries: Vec<(String, usize)> = vec![];
+
+    if let Some(function_name_section) = image.get_optional_export_function_section() {
+        for entry in function_name_section.convert_to_entries() {
+            if let Some(unit_name) = get_test_unit_name(app_module_name, &entry.full_name) {
+                unit_entries.push((
+                    unit_name.to_owned(),
+                    public_index_space.function_public_index(entry.internal_index),
+                ));
+            }
+        }
+    }
+
+    if let Some(entry_point_section) = image.get_optional_entry_point_section() {
+        for entry in entry_point_section.convert_to_entries() {
+            if is_test_unit_name(&entry.unit_name)
+                && !unit_entries
+                    .iter()
+                    .any(|(unit_name, _)| *unit_name == entry.unit_name)
+            {
+                unit_entries.push((entry.unit_name, entry.function_public_index));
+            }
+        }
+    }
+
+    unit_entries.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let mut groups: Vec<TestGroup> = vec![];
+    for (unit_name, function_public_index) in unit_entries {
+        let (submodule_name, name) = unit_name.rsplit_once(NAME_PATH_SEPARATOR).unwrap();
+        let test_unit = TestUnit {
+            unit_name: unit_name.clone(),
+            name: name.to_owned(),
+            function_public_index,
+        };
+
+        match groups.last_mut() {
+            Some(group) if group.submodule_name == submodule_name => group.tests.push(test_unit),
+            _ => groups.push(TestGroup {
+                submodule_name: submodule_name.to_owned(),
+                tests: vec![test_unit],
+            }),
+        }
+    }
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{EntryPointEntry, ImageLinkingEntry, TypeEntry},
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_image_file},
+        module_image::ModuleImage,
+        test_discovery::{discover_tests, get_test_unit_name, is_test_unit_name},
+    };
+
+    #[test]
+    fn test_get_test_unit_name() {
+        assert_eq!(
+            get_test_unit_name("foo", "foo::tests::math::test_add"),
+            Some("math::test_add")
+        );
+        assert_eq!(
+            get_test_unit_name("foo", "foo::tests::math::float::test_add"),
+            Some("math::float::test_add")
+        );
+        assert_eq!(get_test_unit_name("foo", "foo::tests::test_add"), None);
+        assert_eq!(get_test_unit_name("foo", "foo::tests::math::add"), None);
+        assert_eq!(get_test_unit_name("foo", "foo::math::test_add"), None);
+        assert_eq!(
+            get_test_unit_name("foo", "foobar::tests::math::test_add"),
+            None
+        );
+        assert_eq!(
+            get_test_unit_name("bar", "foo::tests::math::test_add"),
+            None
+        );
+
+        assert!(is_test_unit_name("math::test_add"));
+        assert!(!is_test_unit_name("math::test_"));
+        assert!(!is_test_unit_name("test_add"));
+        assert!(!is_test_unit_name("_start"));
+    }
+
+    #[test]
+    fn test_discover_tests() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("add", TypeEntry::new(vec![], vec![])),
+                ("tests::math::test_sub", TypeEntry::new(vec![], vec![])),
+                ("tests::io::test_read", TypeEntry::new(vec![], vec![])),
+                ("tests::math::test_add", TypeEntry::new(vec![], vec![])),
+                ("tests::math::helper", TypeEntry::new(vec![], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let image_common_entry = read_object_file(&image_binary).unwrap();
+        let image_linking_entry = ImageLinkingEntry {
+            function_index_list_entries: vec![],
+            data_index_list_entries: vec![],
+            initialization_dependency_entries: vec![],
+            external_function_index_entries: vec![],
+            unified_external_library_entries: vec![],
+            unified_external_type_entries: vec![],
+            unified_external_function_entries: vec![],
+            lazy_binding_entries: vec![],
+            linking_module_entries: vec![],
+            entry_point_entries: vec![
+                EntryPointEntry::new("_start".to_owned(), 0),
+                EntryPointEntry::new("math::test_add".to_owned(), 3),
+                EntryPointEntry::new("string::test_concat".to_owned(), 0),
+            ],
+        };
+
+        let mut module_binary: Vec<u8> = vec![];
+        write_image_file(
+            &image_common_entry,
+            &image_linking_entry,
+            &mut module_binary,
+        )
+        .unwrap();
+        let image = ModuleImage::read(&module_binary).unwrap();
+
+        let groups = discover_tests(&image).unwrap();
+
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group.submodule_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["io", "math", "string"]
+        );
+
+        let math_tests = &groups[1].tests;
+        assert_eq!(
+            math_tests
+                .iter()
+                .map(|test| (
+                    test.unit_name.as_str(),
+                    test.name.as_str(),
+                    test.function_public_index
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("math::test_add", "test_add", 3),
+                ("math::test_sub", "test_sub", 1)
+            ]
+        );
+
+        assert_eq!(groups[0].tests[0].unit_name, "io::test_read");
+        assert_eq!(groups[2].tests[0].unit_name, "string::test_concat");
+
+        // object file, without the entry point section
+        let image = ModuleImage::read(&image_binary).unwrap();
+        assert_eq!(
+            discover_tests(&image)
+                .unwrap()
+                .iter()
+                .map(|group| group.tests.len())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Builds the "unified external library", "unified external type" and
+// "unified external function" sections of an application image, as well as
+// the "external function index" section, from the modules being linked.
+//
+// The entries are deduplicated across the modules:
+//
+// - Libraries are merged if they are equal (both the name and the dependency).
+// - Types are merged by structural equivalence, i.e., the same params and results,
+//   regardless of the type index in each module.
+// - Functions are merged if they have the same name, unified library and unified type.
+//
+// Only the types referenced by the external functions are added to the unified
+// type table, so the table does not grow linearly with the number of modules.
+//
+// Each module gets a type remap (module type index -> unified type index),
+// which is applied to the external function records before merging.
+
+use crate::{
+    entry::{
+        ExternalFunctionEntry, ExternalFunctionIndexEntry, ExternalFunctionIndexListEntry,
+        ExternalLibraryEntry, ImageCommonEntry, TypeEntry,
+    },
+    entry_writer::find_or_append,
+};
+
+#[derive(Debug, PartialEq, Default)]
+pub struct UnifiedExternalEntries {
+    pub unified_external_library_entries: Vec<ExternalLibraryEntry>,
+    pub unified_external_type_entries: Vec<TypeEntry>,
+    pub unified_external_function_entries: Vec<ExternalFunctionEntry>,
+
+    // One list per module, in the order of the modules,
+    // maps the module external function index to the unified external function index.
+    pub external_function_index_list_entries: Vec<ExternalFunctionIndexListEntry>,
+}
+
+/// Builds the unified external entries of the specified modules,
+/// the modules should be in the order of the "linking module" section.
+pub fn build_unified_external_entries(
+    image_common_entries: &[ImageCommonEntry],
+) -> UnifiedExternalEntries {
+    let mut unified_external_entries = UnifiedExternalEntries::default();
+
+    for image_common_entry in image_common_entries {
+        // `module library index -> unified library index`
+        let library_remap = image_common_entry
+            .external_library_entries
+            .iter()
+            .map(|entry| {
+                find_or_append(
+                    &mut unified_external_entries.unified_external_library_entries,
+                    entry.clone(),
+                )
+            })
+            .collect::<Vec<usize>>();
+
+        // `module type index -> unified type index`, only the types of the
+        // external functions are remapped.
+        let mut type_remap: Vec<Option<usize>> = vec![None; image_common_entry.type_entries.len()];
+
+        let mut index_entries: Vec<ExternalFunctionIndexEntry> = vec![];
+        for entry in &image_common_entry.external_function_entries {
+            let unified_type_index = *type_remap[entry.type_index].get_or_insert_with(|| {
+                find_or_append(
+                    &mut unified_external_entries.unified_external_type_entries,
+                    image_common_entry.type_entries[entry.type_index].clone(),
+                )
+            });
+
+            let unified_function_index = find_or_append(
+                &mut unified_external_entries.unified_external_function_entries,
+                ExternalFunctionEntry::new(
+                    entry.name.clone(),
+                    library_remap[entry.external_library_index],
+                    unified_type_index,
+                ),
+            );
+
+            index_entries.push(ExternalFunctionIndexEntry::new(unified_function_index));
+        }
+
+        unified_external_entries
+            .external_function_index_list_entries
+            .push(ExternalFunctionIndexListEntry::new(index_entries));
+    }
+
+    unified_external_entries
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{
+        DependencyCondition, DependencyLocal, ExternalLibraryDependency, OperandDataType,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{
+            ExternalFunctionEntry, ExternalFunctionIndexEntry, ExternalFunctionIndexListEntry,
+            ExternalLibraryEntry, ImageCommonEntry, TypeEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::build_shared_module_scaffold,
+        unified_external::build_unified_external_entries,
+    };
+
+    fn build_external_library_entry(name: &str) -> ExternalLibraryEntry {
+        ExternalLibraryEntry::new(
+            name.to_owned(),
+            Box::new(ExternalLibraryDependency::Local(Box::new(
+                DependencyLocal {
+                    path: format!("{}.so.1", name),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                },
+            ))),
+        )
+    }
+
+    fn build_image_common_entry(
+        name: &str,
+        type_entries: Vec<TypeEntry>,
+        external_library_entries: Vec<ExternalLibraryEntry>,
+        external_function_entries: Vec<ExternalFunctionEntry>,
+    ) -> ImageCommonEntry {
+        let image_binary = build_shared_module_scaffold(name, &[]).unwrap();
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry.type_entries = type_entries;
+        image_common_entry.external_library_entries = external_library_entries;
+        image_common_entry.external_function_entries = external_function_entries;
+        image_common_entry
+    }
+
+    #[test]
+    fn test_build_unified_external_entries() {
+        let type_i32_i32 = TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]);
+        let type_none = TypeEntry::new(vec![], vec![]);
+        let type_i64 = TypeEntry::new(vec![OperandDataType::I64], vec![]);
+
+        let module_foo = build_image_common_entry(
+            "foo",
+            vec![type_none.clone(), type_i32_i32.clone()],
+            vec![build_external_library_entry("libc")],
+            vec![
+                ExternalFunctionEntry::new("abs".to_owned(), 0, 1),
+                ExternalFunctionEntry::new("getpid".to_owned(), 0, 0),
+            ],
+        );
+
+        // the type tables are in different order, and the type `(i64)->()` is
+        // not used by the external functions.
+        let module_bar = build_image_common_entry(
+            "bar",
+            vec![type_i64, type_i32_i32.clone(), type_none.clone()],
+            vec![
+                build_external_library_entry("libm"),
+                build_external_library_entry("libc"),
+            ],
+            vec![
+                ExternalFunctionEntry::new("abs".to_owned(), 1, 1),
+                ExternalFunctionEntry::new("round".to_owned(), 0, 1),
+            ],
+        );
+
+        let unified_external_entries = build_unified_external_entries(&[module_foo, module_bar]);
+
+        assert_eq!(
+            unified_external_entries.unified_external_library_entries,
+            vec![
+                build_external_library_entry("libc"),
+                build_external_library_entry("libm")
+            ]
+        );
+
+        assert_eq!(
+            unified_external_entries.unified_external_type_entries,
+            vec![type_i32_i32, type_none]
+        );
+
+        assert_eq!(
+            unified_external_entries.unified_external_function_entries,
+            vec![
+                ExternalFunctionEntry::new("abs".to_owned(), 0, 0),
+                ExternalFunctionEntry::new("getpid".to_owned(), 0, 1),
+                ExternalFunctionEntry::new("round".to_owned(), 1, 0),
+            ]
+        );
+
+        assert_eq!(
+            unified_external_entries.external_function_index_list_entries,
+            vec![
+                ExternalFunctionIndexListEntry::new(vec![
+                    ExternalFunctionIndexEntry::new(0),
+                    ExternalFunctionIndexEntry::new(1),
+                ]),
+                ExternalFunctionIndexListEntry::new(vec![
+                    ExternalFunctionIndexEntry::new(0),
+                    ExternalFunctionIndexEntry::new(2),
+                ]),
+            ]
+        );
+    }
+}
@@ -28,13 +28,24 @@ use crate::linking_sections::unified_external_library_section::UnifiedExternalLi
 use crate::linking_sections::unified_external_type_section::UnifiedExternalTypeSection;
 use crate::ImageError;
 
-use anc_isa::{DataSectionType, OperandDataType, RUNTIME_EDITION};
+use std::collections::HashMap;
 
+use anc_isa::opcode::Opcode;
+use anc_isa::{
+    DataSectionType, DependencyCondition, DependencyLocal, EffectiveVersion, MemoryDataType,
+    ModuleDependency, OperandDataType, RUNTIME_EDITION,
+};
+
+use crate::bytecode_writer::BytecodeWriterHelper;
 use crate::entry::{
-    DataNameEntry, EntryPointEntry, ExternalFunctionEntry, ExternalLibraryEntry, FunctionEntry,
-    FunctionNameEntry, LinkingModuleEntry, LocalVariableListEntry, ModuleLocation,
-    ReadOnlyDataEntry, ReadWriteDataEntry, TypeEntry, UninitDataEntry,
+    DataIndexEntry, DataIndexListEntry, DataNameEntry, EntryPointEntry, ExternalFunctionEntry,
+    ExternalFunctionIndexListEntry, ExternalLibraryEntry, FunctionEntry, FunctionIndexEntry,
+    FunctionIndexListEntry, FunctionNameEntry, ImageCommonEntry, ImageLinkingEntry,
+    ImportDataEntry, ImportFunctionEntry, ImportModuleEntry, LinkingModuleEntry,
+    LocalVariableListEntry, ModuleLocation, ModuleLocationLocal, ReadOnlyDataEntry,
+    ReadWriteDataEntry, RelocateListEntry, TypeEntry, UninitDataEntry,
 };
+use crate::entry_writer::{write_image_file, write_object_file};
 
 use crate::module_image::{ImageType, ModuleImage, RangeItem, SectionEntry, Visibility};
 
@@ -61,6 +72,14 @@ pub struct HelperExternalFunctionEntry {
     pub result: Option<OperandDataType>, // Result type of the external function, if any.
 }
 
+/// A helper object representing an imported function entry for unit tests.
+pub struct HelperImportFunctionEntry {
+    pub full_name: String,             // Full name of the imported function.
+    pub import_module_index: usize,    // Index of the import module.
+    pub params: Vec<OperandDataType>,  // Parameters of the imported function.
+    pub results: Vec<OperandDataType>, // Results of the imported function.
+}
+
 /// Builds a module binary with a single function and no data sections.
 /// This is a simplified helper function for unit tests.
 pub fn helper_build_module_binary_with_single_function(
@@ -323,6 +342,368 @@ pub fn helper_build_module_binary_with_functions_and_data_and_external_functions
     )
 }
 
+/// Builds an object file binary with functions, data, imports and relocations.
+/// This helper function is used for unit tests of linking.
+///
+/// Unlike the other helpers which build linked application images, this helper
+/// builds an object file, i.e., the import module, import function, import data
+/// and relocate sections are populated, and there are no index sections.
+///
+/// Note:
+/// - The first entry of `import_module_entries` should be the self-reference entry,
+///   i.e., `ImportModuleEntry::self_reference_entry()`.
+/// - The relocate list entries should correspond to the functions one by one.
+/// - All internal functions and data are public, and they are named
+///   "main::func{N}", "main::ro_data{N}", "main::rw_data{N}" and "main::uninit_data{N}".
+#[allow(clippy::too_many_arguments)]
+pub fn helper_build_module_binary_with_imports(
+    helper_function_entries: &[HelperFunctionEntry],
+    read_only_data_entries: &[ReadOnlyDataEntry],
+    read_write_data_entries: &[ReadWriteDataEntry],
+    uninit_data_entries: &[UninitDataEntry],
+    import_module_entries: &[ImportModuleEntry],
+    helper_import_function_entries: &[HelperImportFunctionEntry],
+    import_data_entries: &[ImportDataEntry],
+    relocate_list_entries: &[RelocateListEntry],
+) -> Vec<u8> {
+    // Build type entries.
+    // Note: For simplicity, duplicate items are not merged.
+
+    let function_type_entries = helper_function_entries
+        .iter()
+        .map(|entry| TypeEntry {
+            params: entry.params.clone(),
+            results: entry.results.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let import_function_type_entries = helper_import_function_entries
+        .iter()
+        .map(|entry| TypeEntry {
+            params: entry.params.clone(),
+            results: entry.results.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut type_entries = vec![];
+    type_entries.extend_from_slice(&function_type_entries);
+    type_entries.extend_from_slice(&import_function_type_entries);
+
+    // Build local variable list entries.
+    // Note: For simplicity, duplicate items are not merged.
+
+    let local_variable_list_entries = helper_function_entries
+        .iter()
+        .map(|entry| {
+            let mut local_variable_types = vec![];
+            local_variable_types.extend_from_slice(&entry.params);
+            local_variable_types.extend_from_slice(&entry.local_variable_item_entries_without_args);
+
+            LocalVariableListEntry {
+                local_variable_types,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Build function entries.
+    let function_entries = helper_function_entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| FunctionEntry {
+            type_index: idx,
+            local_variable_list_index: idx,
+            code: entry.code.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let import_function_entries = helper_import_function_entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| ImportFunctionEntry {
+            full_name: entry.full_name.clone(),
+            import_module_index: entry.import_module_index,
+            type_index: idx + function_entries.len(),
+        })
+        .collect::<Vec<_>>();
+
+    // Build name entries.
+    let function_name_entries = (0..function_entries.len())
+        .map(|idx| FunctionNameEntry::new(format!("main::func{}", idx), Visibility::Public, idx))
+        .collect::<Vec<_>>();
+
+    let mut data_name_entries = vec![];
+    for (prefix, data_section_type, count) in [
+        (
+            "ro_data",
+            DataSectionType::ReadOnly,
+            read_only_data_entries.len(),
+        ),
+        (
+            "rw_data",
+            DataSectionType::ReadWrite,
+            read_write_data_entries.len(),
+        ),
+        (
+            "uninit_data",
+            DataSectionType::Uninit,
+            uninit_data_entries.len(),
+        ),
+    ] {
+        for idx in 0..count {
+            data_name_entries.push(DataNameEntry::new(
+                format!("main::{}{}", prefix, idx),
+                Visibility::Public,
+                data_section_type,
+                idx,
+            ));
+        }
+    }
+
+    let image_common_entry = ImageCommonEntry {
+        name: "main".to_owned(),
+        version: EffectiveVersion::new(1, 0, 0),
+        image_type: ImageType::ObjectFile,
+        type_entries,
+        local_variable_list_entries,
+        function_entries,
+        read_only_data_entries: read_only_data_entries.to_vec(),
+        read_write_data_entries: read_write_data_entries.to_vec(),
+        uninit_data_entries: uninit_data_entries.to_vec(),
+        import_module_entries: import_module_entries.to_vec(),
+        import_function_entries,
+        import_data_entries: import_data_entries.to_vec(),
+        function_name_entries,
+        data_data_entries: data_name_entries,
+        relocate_list_entries: relocate_list_entries.to_vec(),
+        external_library_entries: vec![],
+        external_function_entries: vec![],
+        initializer_entries: vec![],
+    };
+
+    // Build object file binary.
+    let mut image_binary: Vec<u8> = vec![];
+    write_object_file(&image_common_entry, false, &mut image_binary).unwrap();
+    image_binary
+}
+
+/// A helper object representing a set of modules of an application for unit tests.
+pub struct HelperApplicationFixture {
+    // The application image, i.e., the main module and the index sections.
+    pub application_binary: Vec<u8>,
+
+    // The shared modules "dep0", "dep1", ..., which the main module depends on.
+    pub dependency_module_binaries: Vec<Vec<u8>>,
+
+    // The expected index entries, the first entry is the main module.
+    pub function_index_list_entries: Vec<FunctionIndexListEntry>,
+    pub data_index_list_entries: Vec<DataIndexListEntry>,
+}
+
+/// Builds an application image and the given number of shared modules.
+/// This helper function is used as the fixture of the runtime and linker tests.
+///
+/// Each shared module "depN" exports:
+/// - a function "depN::get_number", which has no parameters and returns `N` (i32).
+/// - a read-only data "depN::number", whose value is `N` (i32).
+///
+/// The main module "main" imports all functions and data above, and
+/// the function "main::main" (the default entry point) calls each imported
+/// function and loads each imported data in order, i.e., the results are
+/// `(0, 0, 1, 1, ..., N-1, N-1)`.
+pub fn helper_build_application_fixture(dependency_count: usize) -> HelperApplicationFixture {
+    let dependency_module_binaries = (0..dependency_count)
+        .map(|module_index| {
+            let module_name = format!("dep{}", module_index);
+            let code = BytecodeWriterHelper::new()
+                .append_opcode_i32(Opcode::imm_i32, module_index as u32)
+                .append_opcode(Opcode::end)
+                .to_bytes();
+
+            let image_common_entry = ImageCommonEntry {
+                name: module_name.clone(),
+                version: EffectiveVersion::new(1, 0, 0),
+                image_type: ImageType::SharedModule,
+                type_entries: vec![TypeEntry::new(vec![], vec![OperandDataType::I32])],
+                local_variable_list_entries: vec![LocalVariableListEntry::new(vec![])],
+                function_entries: vec![FunctionEntry::new(0, 0, code)],
+                read_only_data_entries: vec![ReadOnlyDataEntry::from_i32(module_index as u32)],
+                read_write_data_entries: vec![],
+                uninit_data_entries: vec![],
+                import_module_entries: vec![],
+                import_function_entries: vec![],
+                import_data_entries: vec![],
+                function_name_entries: vec![FunctionNameEntry::new(
+                    format!("{}::get_number", module_name),
+                    Visibility::Public,
+                    0,
+                )],
+                data_data_entries: vec![DataNameEntry::new(
+                    format!("{}::number", module_name),
+                    Visibility::Public,
+                    DataSectionType::ReadOnly,
+                    0,
+                )],
+                relocate_list_entries: vec![],
+                external_library_entries: vec![],
+                external_function_entries: vec![],
+                initializer_entries: vec![],
+            };
+
+            let mut module_binary: Vec<u8> = vec![];
+            write_object_file(&image_common_entry, true, &mut module_binary).unwrap();
+            module_binary
+        })
+        .collect::<Vec<_>>();
+
+    // The function "main::main".
+    //
+    // The public indices of the imported functions and data are `0..dependency_count`,
+    // and the public index of the function "main::main" is `dependency_count`.
+    let mut bytecode_writer = BytecodeWriterHelper::new();
+    for idx in 0..dependency_count {
+        bytecode_writer = bytecode_writer
+            .append_opcode_i32(Opcode::call, idx as u32)
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, idx as u32);
+    }
+    let code = bytecode_writer.append_opcode(Opcode::end).to_bytes();
+
+    let image_common_entry = ImageCommonEntry {
+        name: "main".to_owned(),
+        version: EffectiveVersion::new(1, 0, 0),
+        image_type: ImageType::Application,
+        type_entries: vec![
+            TypeEntry::new(vec![], vec![OperandDataType::I32; dependency_count * 2]),
+            TypeEntry::new(vec![], vec![OperandDataType::I32]),
+        ],
+        local_variable_list_entries: vec![LocalVariableListEntry::new(vec![])],
+        function_entries: vec![FunctionEntry::new(0, 0, code)],
+        read_only_data_entries: vec![],
+        read_write_data_entries: vec![],
+        uninit_data_entries: vec![],
+        import_module_entries: (0..dependency_count)
+            .map(|module_index| {
+                ImportModuleEntry::new(
+                    format!("dep{}", module_index),
+                    Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                        path: format!("dep{}", module_index),
+                        condition: DependencyCondition::True,
+                        parameters: HashMap::default(),
+                    }))),
+                )
+            })
+            .collect(),
+        import_function_entries: (0..dependency_count)
+            .map(|module_index| {
+                ImportFunctionEntry::new(
+                    format!("dep{}::get_number", module_index),
+                    module_index,
+                    1,
+                )
+            })
+            .collect(),
+        import_data_entries: (0..dependency_count)
+            .map(|module_index| {
+                ImportDataEntry::new(
+                    format!("dep{}::number", module_index),
+                    module_index,
+                    DataSectionType::ReadOnly,
+                    MemoryDataType::I32,
+                )
+            })
+            .collect(),
+        function_name_entries: vec![FunctionNameEntry::new(
+            "main::main".to_owned(),
+            Visibility::Public,
+            0,
+        )],
+        data_data_entries: vec![],
+        relocate_list_entries: vec![],
+        external_library_entries: vec![],
+        external_function_entries: vec![],
+        initializer_entries: vec![],
+    };
+
+    // Build index entries.
+    //
+    // The module index of the main module is 0, and the module index of
+    // the shared module "depN" is `N + 1`.
+    let build_function_index_list_entries = || {
+        let mut main_index_entries = (0..dependency_count)
+            .map(|idx| FunctionIndexEntry::new(idx + 1, 0))
+            .collect::<Vec<_>>();
+        main_index_entries.push(FunctionIndexEntry::new(0, 0));
+
+        let mut list_entries = vec![FunctionIndexListEntry::new(main_index_entries)];
+        for idx in 0..dependency_count {
+            list_entries.push(FunctionIndexListEntry::new(vec![FunctionIndexEntry::new(
+                idx + 1,
+                0,
+            )]));
+        }
+        list_entries
+    };
+
+    let build_data_index_list_entries = || {
+        let main_index_entries = (0..dependency_count)
+            .map(|idx| DataIndexEntry::new(idx + 1, DataSectionType::ReadOnly, 0))
+            .collect::<Vec<_>>();
+
+        let mut list_entries = vec![DataIndexListEntry::new(main_index_entries)];
+        for idx in 0..dependency_count {
+            list_entries.push(DataIndexListEntry::new(vec![DataIndexEntry::new(
+                idx + 1,
+                DataSectionType::ReadOnly,
+                0,
+            )]));
+        }
+        list_entries
+    };
+
+    let mut linking_module_entries = vec![LinkingModuleEntry::new(
+        "main".to_owned(),
+        Box::new(ModuleLocation::Embed),
+    )];
+    for idx in 0..dependency_count {
+        linking_module_entries.push(LinkingModuleEntry::new(
+            format!("dep{}", idx),
+            Box::new(ModuleLocation::Local(Box::new(ModuleLocationLocal {
+                module_path: format!("dep{}", idx),
+                hash: String::new(),
+            }))),
+        ));
+    }
+
+    let image_linking_entry = ImageLinkingEntry {
+        function_index_list_entries: build_function_index_list_entries(),
+        data_index_list_entries: build_data_index_list_entries(),
+        initialization_dependency_entries: vec![],
+        external_function_index_entries: (0..=dependency_count)
+            .map(|_| ExternalFunctionIndexListEntry::new(vec![]))
+            .collect(),
+        unified_external_library_entries: vec![],
+        unified_external_type_entries: vec![],
+        unified_external_function_entries: vec![],
+        lazy_binding_entries: vec![],
+        linking_module_entries,
+        entry_point_entries: vec![EntryPointEntry::new("_start".to_owned(), dependency_count)],
+    };
+
+    let mut application_binary: Vec<u8> = vec![];
+    write_image_file(
+        &image_common_entry,
+        &image_linking_entry,
+        &mut application_binary,
+    )
+    .unwrap();
+
+    HelperApplicationFixture {
+        application_binary,
+        dependency_module_binaries,
+        function_index_list_entries: build_function_index_list_entries(),
+        data_index_list_entries: build_data_index_list_entries(),
+    }
+}
+
 /// Builds a complete module binary with all sections.
 /// This is a low-level helper function for unit tests.
 #[allow(clippy::too_many_arguments)]
@@ -583,8 +964,8 @@ pub fn helper_build_module_binary(
         &external_function_section,
         /* The following are index sections. */
         &entry_point_section,
-        &module_list_section,
         &function_index_section,
+        &module_list_section,
         &data_index_section,
         &unified_external_type_section,
         &unified_external_library_section,
@@ -593,12 +974,8 @@ pub fn helper_build_module_binary(
     ];
 
     let (section_items, sections_data) =
-        ModuleImage::convert_from_section_entries(&section_entries);
-    let module_image = ModuleImage {
-        image_type: ImageType::Application,
-        items: &section_items,
-        sections_data: &sections_data,
-    };
+        ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+    let module_image = ModuleImage::new(ImageType::Application, &section_items, &sections_data);
 
     // Build module image binary.
     let mut image_binary: Vec<u8> = vec![];
@@ -628,25 +1005,35 @@ mod tests {
 
     use anc_isa::{
         DataSectionType, DependencyCondition, DependencyLocal, DependencyShare,
-        ExternalLibraryDependency, ExternalLibraryDependencyType, MemoryDataType, OperandDataType,
+        ExternalLibraryDependency, ExternalLibraryDependencyType, MemoryDataType, ModuleDependency,
+        OperandDataType,
     };
 
     use crate::{
         common_sections::{
             self, local_variable_section::LocalVariableItem, read_only_data_section::DataItem,
         },
-        entry::{ExternalLibraryEntry, ReadOnlyDataEntry, ReadWriteDataEntry, UninitDataEntry},
+        entry::{
+            DataNameEntry, ExternalLibraryEntry, FunctionNameEntry, ImportDataEntry,
+            ImportFunctionEntry, ImportModuleEntry, ReadOnlyDataEntry, ReadWriteDataEntry,
+            RelocateEntry, RelocateListEntry, TypeEntry, UninitDataEntry,
+        },
+        entry_reader::{read_image_file, read_object_file},
         linking_sections::{
             data_index_section::DataIndexItem,
             external_function_index_section::ExternalFunctionIndexItem,
             function_index_section::FunctionIndexItem,
         },
-        module_image::RangeItem,
+        module_image::{ImageType, RangeItem, Visibility},
         utils::{
+            helper_build_application_fixture,
             helper_build_module_binary_with_functions_and_data_and_external_functions,
+            helper_build_module_binary_with_imports,
             helper_build_module_binary_with_single_function_and_data,
             helper_load_modules_from_binaries, HelperExternalFunctionEntry, HelperFunctionEntry,
+            HelperImportFunctionEntry,
         },
+        validator::{validate_data_public_indices, validate_entry_points},
     };
 
     #[test]
@@ -1106,4 +1493,139 @@ mod tests {
             ("magic_file", 1, 6)
         );
     }
+
+    #[test]
+    fn test_build_module_binary_with_imports() {
+        let import_module_entries = vec![
+            ImportModuleEntry::self_reference_entry(),
+            ImportModuleEntry::new(
+                "math".to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: "math".to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            ),
+        ];
+
+        let import_data_entries = vec![ImportDataEntry::new(
+            "math::pi".to_owned(),
+            1,
+            DataSectionType::ReadOnly,
+            MemoryDataType::F64,
+        )];
+
+        let relocate_list_entries = vec![RelocateListEntry::new(vec![
+            RelocateEntry::from_function_public_index(0),
+            RelocateEntry::from_data_public_index(8),
+        ])];
+
+        let binary = helper_build_module_binary_with_imports(
+            &[HelperFunctionEntry {
+                params: vec![OperandDataType::I32],
+                results: vec![OperandDataType::I32],
+                local_variable_item_entries_without_args: vec![],
+                code: vec![0u8; 16],
+            }],
+            &[],
+            &[ReadWriteDataEntry::from_i32(11)],
+            &[],
+            &import_module_entries,
+            &[HelperImportFunctionEntry {
+                full_name: "math::add".to_owned(),
+                import_module_index: 1,
+                params: vec![OperandDataType::I32, OperandDataType::I32],
+                results: vec![OperandDataType::I32],
+            }],
+            &import_data_entries,
+            &relocate_list_entries,
+        );
+
+        let image_common_entry = read_object_file(&binary).unwrap();
+
+        assert_eq!(image_common_entry.image_type, ImageType::ObjectFile);
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+                TypeEntry::new(
+                    vec![OperandDataType::I32, OperandDataType::I32],
+                    vec![OperandDataType::I32]
+                ),
+            ]
+        );
+        assert_eq!(
+            image_common_entry.import_module_entries,
+            import_module_entries
+        );
+        assert_eq!(
+            image_common_entry.import_function_entries,
+            vec![ImportFunctionEntry::new("math::add".to_owned(), 1, 1)]
+        );
+        assert_eq!(image_common_entry.import_data_entries, import_data_entries);
+        assert_eq!(
+            image_common_entry.relocate_list_entries,
+            relocate_list_entries
+        );
+        assert_eq!(
+            image_common_entry.function_name_entries,
+            vec![FunctionNameEntry::new(
+                "main::func0".to_owned(),
+                Visibility::Public,
+                0
+            )]
+        );
+        assert_eq!(
+            image_common_entry.data_data_entries,
+            vec![DataNameEntry::new(
+                "main::rw_data0".to_owned(),
+                Visibility::Public,
+                DataSectionType::ReadWrite,
+                0
+            )]
+        );
+    }
+
+    #[test]
+    fn test_build_application_fixture() {
+        let fixture = helper_build_application_fixture(2);
+        assert_eq!(fixture.dependency_module_binaries.len(), 2);
+
+        let (image_common_entry, image_linking_entry) =
+            read_image_file(&fixture.application_binary).unwrap();
+        assert_eq!(image_common_entry.import_function_entries.len(), 2);
+        assert_eq!(image_common_entry.import_data_entries.len(), 2);
+        assert_eq!(
+            image_linking_entry.function_index_list_entries,
+            fixture.function_index_list_entries
+        );
+        assert_eq!(
+            image_linking_entry.data_index_list_entries,
+            fixture.data_index_list_entries
+        );
+        assert_eq!(image_linking_entry.linking_module_entries.len(), 3);
+
+        let dependency_common_entry =
+            read_object_file(&fixture.dependency_module_binaries[1]).unwrap();
+        assert_eq!(dependency_common_entry.name, "dep1");
+        assert_eq!(dependency_common_entry.image_type, ImageType::SharedModule);
+        assert_eq!(
+            dependency_common_entry.function_name_entries,
+            vec![FunctionNameEntry::new(
+                "dep1::get_number".to_owned(),
+                Visibility::Public,
+                0
+            )]
+        );
+
+        // The binaries are consistent.
+        let mut module_binaries: Vec<&[u8]> = vec![&fixture.application_binary];
+        for module_binary in &fixture.dependency_module_binaries {
+            module_binaries.push(module_binary);
+        }
+
+        let module_images = helper_load_modules_from_binaries(&module_binaries).unwrap();
+        assert!(validate_data_public_indices(&module_images).is_empty());
+        assert!(validate_entry_points(&module_images).is_empty());
+    }
 }
@@ -0,0 +1,2545 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Validates the module image before it is loaded by the runtime.
+//
+// The runtime trusts the indices in the bytecode and accesses the
+// memory directly, for example, an out-of-range local variable index
+// leads to memory corruption rather than an error. So the module images
+// produced by third-party tools should be validated first.
+//
+// Each check returns a list of `ValidationError`, an empty list means
+// the image passes the check.
+
+use std::fmt::Display;
+
+use anc_isa::{opcode::Opcode, DataSectionType};
+
+use crate::{
+    bytecode_reader::{decode_instruction, InstructionIterator, InstructionParams},
+    bytecode_verifier::{verify_bytecode, BytecodeVerificationErrorType},
+    common_sections::function_section::{FunctionItem, FunctionSection},
+    diagnostic::{data_area_byte_range, table_item_byte_range, Diagnostic, Severity},
+    linking_sections::data_index_section::DataIndexItem,
+    module_image::{
+        InitializerType, ModuleImage, ModuleSectionId, RangeItem, RelocateType, Visibility,
+        DATA_ITEM_ALIGN_BYTES,
+    },
+    opcode_info::OPCODE_LENGTH,
+};
+
+// The code of the diagnostics converted from `ValidationError`.
+pub const INVALID_INDEX_CODE: &str = "invalid_index";
+
+// All instructions that contain a relocatable `i32` parameter place it
+// at the offset `instruction_address + 4`, see `RelocateEntry::from_*`.
+const RELOCATABLE_PARAM_OFFSET_IN_INSTRUCTION: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub struct ValidationError {
+    // `None` if the error is not related to a specific function.
+    pub function_internal_index: Option<usize>,
+
+    // The full name of the function, it is only available when
+    // the "function name section" is present, see `validate_strict`.
+    pub function_name: Option<String>,
+
+    // The offset of the instruction within the function code,
+    // `None` if the error is not related to a specific instruction.
+    pub instruction_offset: Option<usize>,
+
+    pub error_type: ValidationErrorType,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ValidationErrorType {
+    // The required section is missing, or its table (or data area) is
+    // out of bounds, see the `try_get_*` functions of `ModuleImage`.
+    InvalidSection {
+        section_id: ModuleSectionId,
+    },
+
+    // The code of the function fails the bytecode verification, e.g., unknown
+    // opcodes or truncated instructions, see `bytecode_verifier`.
+    //
+    // The function is skipped by the other checks on the instructions.
+    InvalidBytecode {
+        error_type: BytecodeVerificationErrorType,
+    },
+
+    // The type index of the function, `block` or `block_alt`
+    // does not exist in the type section.
+    TypeIndexOutOfRange {
+        type_index: u32,
+        type_count: usize,
+    },
+
+    // The local variable list index of the function, `block`, `block_alt`
+    // or `block_nez` does not exist in the local variable section.
+    LocalVariableListIndexOutOfRange {
+        local_variable_list_index: u32,
+        local_variable_list_count: usize,
+    },
+
+    // The `layers` of `local_load_*` or `local_store_*` exceeds
+    // the number of the enclosing blocks (including the function).
+    LocalVariableLayersOutOfRange {
+        layers: u16,
+    },
+
+    // The local variable index exceeds the local variable list
+    // of the target block (or function).
+    LocalVariableIndexOutOfRange {
+        layers: u16,
+        index: u32,
+        local_variable_count: usize,
+    },
+
+    // The `layers` of `break` or `recur` exceeds the number of
+    // the enclosing blocks (including the function).
+    BreakLayersOutOfRange {
+        layers: u16,
+    },
+
+    // The `end` instruction follows the `end` of the function,
+    // i.e., there is no block to close.
+    UnmatchedEnd,
+
+    // The `block`, `block_alt` or `block_nez` is not closed by an `end` instruction.
+    UnclosedBlock,
+
+    // The function code is not closed by an `end` instruction.
+    MissingFunctionEnd,
+
+    // The relocate section does not contain the relocate list of the function,
+    // i.e., the relocate section has fewer lists than the functions.
+    RelocateListNotFound {
+        module_index: usize,
+    },
+
+    // The relocate item refers to a parameter which is not located in the
+    // code of the function, i.e., `offset_in_function` is less than the offset
+    // of the parameter within the instruction, or the parameter exceeds the code.
+    RelocateOffsetOutOfBounds {
+        module_index: usize,
+        offset_in_function: u32,
+        code_length: usize,
+    },
+
+    // The `data_public_index` operand of module `module_index` is not
+    // covered by the range of that module in the data index section.
+    DataPublicIndexOutOfRange {
+        module_index: usize,
+        data_public_index: u32,
+        data_count: usize,
+    },
+
+    // The target of the data index item does not exist in the data
+    // section of the target module.
+    DataIndexTargetNotFound {
+        module_index: usize,
+        data_public_index: usize,
+        target_module_index: usize,
+        target_data_section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+    },
+
+    // The function public index of the entry point is not covered by
+    // the range of the main module in the function index section.
+    EntryPointFunctionIndexOutOfRange {
+        unit_name: String,
+        function_public_index: usize,
+        function_count: usize,
+    },
+
+    // The function index item of the entry point refers to a
+    // non-existent function.
+    EntryPointTargetNotFound {
+        unit_name: String,
+        function_public_index: usize,
+        target_module_index: usize,
+        function_internal_index: usize,
+    },
+
+    DuplicateEntryPointUnitName {
+        unit_name: String,
+    },
+
+    // The number of the ranges of the function index section, the data index
+    // section or the external function index section does not match the number
+    // of the modules of the application.
+    IndexRangeCountMismatch {
+        section_id: ModuleSectionId,
+        range_count: usize,
+        module_count: usize,
+    },
+
+    // The target of the function index item does not exist, i.e., the target
+    // module does not exist, or the target function does not exist in the main module.
+    FunctionIndexTargetNotFound {
+        module_index: usize,
+        function_public_index: usize,
+        target_module_index: usize,
+        function_internal_index: usize,
+    },
+
+    // The ranges of the index section are not in the order of the "linking module"
+    // section, i.e., the range of a module does not start right after the range of
+    // the previous module, or it exceeds the items of the section.
+    IndexRangeOutOfOrder {
+        section_id: ModuleSectionId,
+        module_index: usize,
+        range_offset: usize,
+        expected_offset: usize,
+    },
+
+    // The unified external function index of the external function index item
+    // is out of range of the unified external function section.
+    UnifiedExternalFunctionIndexOutOfRange {
+        module_index: usize,
+        external_function_index: usize,
+        unified_external_function_index: usize,
+        unified_external_function_count: usize,
+    },
+
+    // The range `data_offset..(data_offset + data_length)` of the data item
+    // exceeds the data area of the section.
+    DataItemOutOfBounds {
+        data_section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+        data_offset: u32,
+        data_length: u32,
+        data_area_length: usize,
+    },
+
+    // The `data_offset` is not a multiple of the alignment of the data item,
+    // the alignment is `data_align` rounded up to `DATA_ITEM_ALIGN_BYTES`.
+    DataItemMisaligned {
+        data_section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+        data_offset: u32,
+        required_align: u32,
+    },
+
+    // The data item overlaps with another item of the same section.
+    DataItemOverlapped {
+        data_section_type: DataSectionType,
+        data_internal_index_in_section: usize,
+        other_data_internal_index_in_section: usize,
+    },
+
+    // The function of the initializer item does not exist.
+    InitializerTargetNotFound {
+        initializer_type: InitializerType,
+        function_internal_index: usize,
+        function_count: usize,
+    },
+
+    // The function of the initializer item is not public.
+    InitializerNotPublic {
+        initializer_type: InitializerType,
+    },
+
+    // The signature of the function of the initializer item is not `() -> ()`.
+    InitializerSignatureMismatch {
+        initializer_type: InitializerType,
+    },
+}
+
+impl ValidationError {
+    pub fn new(
+        function_internal_index: usize,
+        instruction_offset: Option<usize>,
+        error_type: ValidationErrorType,
+    ) -> Self {
+        Self {
+            function_internal_index: Some(function_internal_index),
+            function_name: None,
+            instruction_offset,
+            error_type,
+        }
+    }
+
+    /// Converts the error into an error-level `Diagnostic`.
+    ///
+    /// The span is located in the specified image, i.e., the image which
+    /// is validated (for the errors of the linked application, it is the
+    /// image of the module where the error occurs, or the application image
+    /// for the errors of the index sections).
+    /// The span of the errors about entry points is not available.
+    pub fn to_diagnostic(&self, image: &ModuleImage) -> Diagnostic {
+        let diagnostic = Diagnostic::new(Severity::Error, INVALID_INDEX_CODE, self.to_string());
+
+        if let Some(function_internal_index) = self.function_internal_index {
+            let function_section = image.get_function_section();
+            let item_count = function_section.items.len();
+            let item_size = size_of::<FunctionItem>();
+
+            let function_item_range = table_item_byte_range(function_internal_index, item_size);
+
+            match self.instruction_offset {
+                Some(instruction_offset) => {
+                    let item = &function_section.items[function_internal_index];
+                    let (_, _, code) = function_section
+                        .get_item_type_index_and_local_variable_list_index_and_code(
+                            function_internal_index,
+                        );
+                    // The invalid code can not be decoded, the span covers the opcode only.
+                    let offset_next = match self.error_type {
+                        ValidationErrorType::InvalidBytecode { .. } => {
+                            code.len().min(instruction_offset + OPCODE_LENGTH)
+                        }
+                        _ => decode_instruction(code, instruction_offset).0,
+                    };
+
+                    diagnostic
+                        .with_span(
+                            ModuleSectionId::Function,
+                            data_area_byte_range(
+                                item_count,
+                                item_size,
+                                item.code_offset as usize + instruction_offset,
+                                offset_next - instruction_offset,
+                            ),
+                        )
+                        .with_related(
+                            ModuleSectionId::Function,
+                            function_item_range,
+                            "The function is defined here.".to_owned(),
+                        )
+                }
+                None => diagnostic.with_span(ModuleSectionId::Function, function_item_range),
+            }
+        } else if let ValidationErrorType::DataIndexTargetNotFound {
+            module_index,
+            data_public_index,
+            ..
+        } = self.error_type
+        {
+            // The data index section consists of two tables.
+            let data_index_section = image.get_optional_data_index_section().unwrap_or_default();
+            let item_index =
+                data_index_section.ranges[module_index].offset as usize + data_public_index;
+
+            diagnostic.with_span(
+                ModuleSectionId::DataIndex,
+                data_area_byte_range(
+                    data_index_section.ranges.len(),
+                    size_of::<RangeItem>(),
+                    item_index * size_of::<DataIndexItem>(),
+                    size_of::<DataIndexItem>(),
+                ),
+            )
+        } else {
+            diagnostic
+        }
+    }
+
+    /// Creates an error which is not related to a specific function.
+    pub fn from_error_type(error_type: ValidationErrorType) -> Self {
+        Self {
+            function_internal_index: None,
+            function_name: None,
+            instruction_offset: None,
+            error_type,
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(function_internal_index) = self.function_internal_index {
+            match &self.function_name {
+                Some(function_name) => write!(
+                    f,
+                    "Function \"{}\" (index {})",
+                    function_name, function_internal_index
+                )?,
+                None => write!(f, "Function {}", function_internal_index)?,
+            }
+
+            if let Some(offset) = self.instruction_offset {
+                write!(f, ", instruction 0x{:04x}", offset)?;
+            }
+
+            write!(f, ": ")?;
+        }
+
+        match &self.error_type {
+            ValidationErrorType::InvalidSection { section_id } => write!(
+                f,
+                "The section \"{}\" is missing or corrupted.",
+                section_id.name()
+            ),
+            ValidationErrorType::InvalidBytecode { error_type } => {
+                write!(f, "Invalid bytecode: {}", error_type)
+            }
+            ValidationErrorType::TypeIndexOutOfRange {
+                type_index,
+                type_count,
+            } => write!(
+                f,
+                "The type index {} is out of range, the type section has {} items.",
+                type_index, type_count
+            ),
+            ValidationErrorType::LocalVariableListIndexOutOfRange {
+                local_variable_list_index,
+                local_variable_list_count,
+            } => write!(
+                f,
+                "The local variable list index {} is out of range, the local variable section has {} lists.",
+                local_variable_list_index, local_variable_list_count
+            ),
+            ValidationErrorType::LocalVariableLayersOutOfRange { layers } => {
+                write!(f, "The local variable layers {} is out of range.", layers)
+            }
+            ValidationErrorType::LocalVariableIndexOutOfRange {
+                layers,
+                index,
+                local_variable_count,
+            } => write!(
+                f,
+                "The local variable index {} (layers {}) is out of range, the list has {} variables.",
+                index, layers, local_variable_count
+            ),
+            ValidationErrorType::BreakLayersOutOfRange { layers } => write!(
+                f,
+                "The break layers {} exceeds the number of the enclosing blocks.",
+                layers
+            ),
+            ValidationErrorType::UnmatchedEnd => {
+                write!(f, "The \"end\" instruction does not match any block.")
+            }
+            ValidationErrorType::UnclosedBlock => {
+                write!(f, "The block is not closed by an \"end\" instruction.")
+            }
+            ValidationErrorType::MissingFunctionEnd => {
+                write!(f, "The function is not closed by an \"end\" instruction.")
+            }
+            ValidationErrorType::RelocateListNotFound { module_index } => write!(
+                f,
+                "The relocate list of the function does not exist in module {}.",
+                module_index
+            ),
+            ValidationErrorType::RelocateOffsetOutOfBounds {
+                module_index,
+                offset_in_function,
+                code_length,
+            } => write!(
+                f,
+                "The relocate offset {} of module {} is out of bounds, the code has {} bytes.",
+                offset_in_function, module_index, code_length
+            ),
+            ValidationErrorType::DataPublicIndexOutOfRange {
+                module_index,
+                data_public_index,
+                data_count,
+            } => write!(
+                f,
+                "The data public index {} of module {} is out of range, the module has {} data index items.",
+                data_public_index, module_index, data_count
+            ),
+            ValidationErrorType::DataIndexTargetNotFound {
+                module_index,
+                data_public_index,
+                target_module_index,
+                target_data_section_type,
+                data_internal_index_in_section,
+            } => write!(
+                f,
+                "The target of data public index {} of module {} does not exist, module: {}, section: {:?}, index: {}.",
+                data_public_index,
+                module_index,
+                target_module_index,
+                target_data_section_type,
+                data_internal_index_in_section
+            ),
+            ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                unit_name,
+                function_public_index,
+                function_count,
+            } => write!(
+                f,
+                "The function public index {} of entry point \"{}\" is out of range, the main module has {} function index items.",
+                function_public_index, unit_name, function_count
+            ),
+            ValidationErrorType::EntryPointTargetNotFound {
+                unit_name,
+                function_public_index,
+                target_module_index,
+                function_internal_index,
+            } => write!(
+                f,
+                "The target function of entry point \"{}\" (function public index {}) does not exist, module: {}, index: {}.",
+                unit_name, function_public_index, target_module_index, function_internal_index
+            ),
+            ValidationErrorType::DuplicateEntryPointUnitName { unit_name } => {
+                write!(f, "Duplicate entry point unit name \"{}\".", unit_name)
+            }
+            ValidationErrorType::IndexRangeCountMismatch {
+                section_id,
+                range_count,
+                module_count,
+            } => write!(
+                f,
+                "The {} section has {} ranges, but the application has {} modules.",
+                section_id.name(),
+                range_count,
+                module_count
+            ),
+            ValidationErrorType::FunctionIndexTargetNotFound {
+                module_index,
+                function_public_index,
+                target_module_index,
+                function_internal_index,
+            } => write!(
+                f,
+                "The target of function public index {} of module {} does not exist, module: {}, index: {}.",
+                function_public_index, module_index, target_module_index, function_internal_index
+            ),
+            ValidationErrorType::IndexRangeOutOfOrder {
+                section_id,
+                module_index,
+                range_offset,
+                expected_offset,
+            } => write!(
+                f,
+                "The range of module {} in the {} section starts at {}, expected {}.",
+                module_index,
+                section_id.name(),
+                range_offset,
+                expected_offset
+            ),
+            ValidationErrorType::UnifiedExternalFunctionIndexOutOfRange {
+                module_index,
+                external_function_index,
+                unified_external_function_index,
+                unified_external_function_count,
+            } => write!(
+                f,
+                "The unified external function index {} of external function {} of module {} is out of range, the unified external function section has {} items.",
+                unified_external_function_index,
+                external_function_index,
+                module_index,
+                unified_external_function_count
+            ),
+            ValidationErrorType::DataItemOutOfBounds {
+                data_section_type,
+                data_internal_index_in_section,
+                data_offset,
+                data_length,
+                data_area_length,
+            } => write!(
+                f,
+                "The data item {} of section {:?} (offset {}, length {}) exceeds the data area, the data area has {} bytes.",
+                data_internal_index_in_section,
+                data_section_type,
+                data_offset,
+                data_length,
+                data_area_length
+            ),
+            ValidationErrorType::DataItemMisaligned {
+                data_section_type,
+                data_internal_index_in_section,
+                data_offset,
+                required_align,
+            } => write!(
+                f,
+                "The offset {} of data item {} of section {:?} is not aligned to {} bytes.",
+                data_offset, data_internal_index_in_section, data_section_type, required_align
+            ),
+            ValidationErrorType::InitializerTargetNotFound {
+                initializer_type,
+                function_internal_index,
+                function_count,
+            } => write!(
+                f,
+                "The function {} of the {:?} does not exist, the module has {} functions.",
+                function_internal_index, initializer_type, function_count
+            ),
+            ValidationErrorType::InitializerNotPublic { initializer_type } => {
+                write!(f, "The {:?} function is not public.", initializer_type)
+            }
+            ValidationErrorType::InitializerSignatureMismatch { initializer_type } => write!(
+                f,
+                "The signature of the {:?} function is not \"() -> ()\".",
+                initializer_type
+            ),
+            ValidationErrorType::DataItemOverlapped {
+                data_section_type,
+                data_internal_index_in_section,
+                other_data_internal_index_in_section,
+            } => write!(
+                f,
+                "The data item {} of section {:?} overlaps with the data item {}.",
+                data_internal_index_in_section,
+                data_section_type,
+                other_data_internal_index_in_section
+            ),
+        }
+    }
+}
+
+/// Runs all checks in strict mode.
+///
+/// If the "function name section" is present, the errors are
+/// labeled with the full names of the functions.
+pub fn validate_strict(image: &ModuleImage) -> Vec<ValidationError> {
+    let mut errors = validate_function_codes(image);
+    errors.extend(validate_type_and_local_variable_list_indices(image));
+    errors.extend(validate_block_structure(image));
+    errors.extend(validate_local_variable_access(image));
+    errors.extend(validate_data_layout(image));
+    errors.extend(validate_initializers(image));
+
+    // The names are only used for labeling, so a corrupted name section is ignored.
+    if let Ok(Some(function_name_section)) = image.try_get_optional_export_function_section() {
+        for error in errors.iter_mut() {
+            error.function_name = error.function_internal_index.and_then(|idx| {
+                function_name_section
+                    .get_item_full_name_and_visibility(idx)
+                    .map(|(full_name, _)| full_name.to_owned())
+            });
+        }
+    }
+
+    errors
+}
+
+/// Runs the checks which apply to a single image, i.e., `validate_strict`
+/// and `validate_index_sections`.
+///
+/// Returns all errors if the image does not pass the checks.
+pub fn validate_module_image(image: &ModuleImage) -> Result<(), Vec<ValidationError>> {
+    let mut errors = validate_strict(image);
+    errors.extend(validate_index_sections(image));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that the function section, the type section and the local variable
+/// section are present and well-formed, and that the code of every function
+/// passes the bytecode verification.
+///
+/// The other checks on the instructions decode the code, so they skip
+/// the functions (or the whole image) which fail this check.
+pub fn validate_function_codes(image: &ModuleImage) -> Vec<ValidationError> {
+    let mut errors: Vec<ValidationError> = vec![];
+
+    let mut check_section = |section_id: ModuleSectionId, is_valid: bool| {
+        if !is_valid {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::InvalidSection { section_id },
+            ));
+        }
+    };
+
+    check_section(ModuleSectionId::Type, image.try_get_type_section().is_ok());
+    check_section(
+        ModuleSectionId::LocalVariable,
+        image.try_get_local_variable_section().is_ok(),
+    );
+
+    match image.try_get_function_section() {
+        Ok(function_section) => {
+            for function_internal_index in 0..function_section.items.len() {
+                let (_, _, code) = function_section
+                    .get_item_type_index_and_local_variable_list_index_and_code(
+                        function_internal_index,
+                    );
+
+                if let Err(error) = verify_bytecode(code) {
+                    errors.push(ValidationError::new(
+                        function_internal_index,
+                        Some(error.instruction_offset),
+                        ValidationErrorType::InvalidBytecode {
+                            error_type: error.error_type,
+                        },
+                    ));
+                }
+            }
+        }
+        Err(_) => {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::InvalidSection {
+                    section_id: ModuleSectionId::Function,
+                },
+            ));
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks that the `type_index` and `local_variable_list_index` of every
+/// function, and the operands of every `block`, `block_alt` and `block_nez`
+/// instruction, refer to existing items in the type section and the
+/// local variable section.
+pub fn validate_type_and_local_variable_list_indices(image: &ModuleImage) -> Vec<ValidationError> {
+    // The missing or corrupted sections are reported by `validate_function_codes`.
+    let (Ok(function_section), Ok(type_section), Ok(local_variable_section)) = (
+        image.try_get_function_section(),
+        image.try_get_type_section(),
+        image.try_get_local_variable_section(),
+    ) else {
+        return vec![];
+    };
+
+    let type_count = type_section.items.len();
+    let local_variable_list_count = local_variable_section.lists.len();
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    let mut check = |function_internal_index: usize,
+                     instruction_offset: Option<usize>,
+                     opt_type_index: Option<u32>,
+                     local_variable_list_index: u32| {
+        if let Some(type_index) = opt_type_index {
+            if type_index as usize >= type_count {
+                errors.push(ValidationError::new(
+                    function_internal_index,
+                    instruction_offset,
+                    ValidationErrorType::TypeIndexOutOfRange {
+                        type_index,
+                        type_count,
+                    },
+                ));
+            }
+        }
+
+        if local_variable_list_index as usize >= local_variable_list_count {
+            errors.push(ValidationError::new(
+                function_internal_index,
+                instruction_offset,
+                ValidationErrorType::LocalVariableListIndexOutOfRange {
+                    local_variable_list_index,
+                    local_variable_list_count,
+                },
+            ));
+        }
+    };
+
+    for (function_internal_index, item) in function_section.items.iter().enumerate() {
+        check(
+            function_internal_index,
+            None,
+            Some(item.type_index),
+            item.local_variable_list_index,
+        );
+
+        let (_, _, code) = function_section
+            .get_item_type_index_and_local_variable_list_index_and_code(function_internal_index);
+
+        // The invalid code can not be decoded, it is reported by `validate_function_codes`.
+        if verify_bytecode(code).is_err() {
+            continue;
+        }
+
+        for record in InstructionIterator::new(code) {
+            match (record.opcode, record.params) {
+                (
+                    Opcode::block,
+                    InstructionParams::Block {
+                        type_index,
+                        local_variable_list_index,
+                    },
+                )
+                | (
+                    Opcode::block_alt,
+                    InstructionParams::BlockAlt {
+                        type_index,
+                        local_variable_list_index,
+                        ..
+                    },
+                ) => check(
+                    function_internal_index,
+                    Some(record.offset),
+                    Some(type_index),
+                    local_variable_list_index,
+                ),
+                (
+                    Opcode::block_nez,
+                    InstructionParams::BlockNez {
+                        local_variable_list_index,
+                        ..
+                    },
+                ) => check(
+                    function_internal_index,
+                    Some(record.offset),
+                    None,
+                    local_variable_list_index,
+                ),
+                _ => {
+                    // not a block instruction
+                }
+            }
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks that the `block`, `block_alt` and `block_nez` instructions are balanced
+/// with the `end` instructions in every function (the last `end` closes the
+/// function itself), and that the `layers` of every `break` and `recur`
+/// instruction does not exceed the nesting depth.
+///
+/// It is a cheap structural check, the operands and the jump offsets are not verified.
+pub fn validate_block_structure(image: &ModuleImage) -> Vec<ValidationError> {
+    // The missing or corrupted section is reported by `validate_function_codes`.
+    let Ok(function_section) = image.try_get_function_section() else {
+        return vec![];
+    };
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    for (function_internal_index, code) in get_verified_function_codes(&function_section) {
+        // The offsets of the enclosing blocks, the first one is the
+        // function (`None`), the last one is the innermost block.
+        let mut frames: Vec<Option<usize>> = vec![None];
+
+        for record in InstructionIterator::new(code) {
+            match (record.opcode, record.params) {
+                (Opcode::block | Opcode::block_alt | Opcode::block_nez, _) => {
+                    frames.push(Some(record.offset));
+                }
+                (Opcode::end, _) => {
+                    if frames.pop().is_none() {
+                        errors.push(ValidationError::new(
+                            function_internal_index,
+                            Some(record.offset),
+                            ValidationErrorType::UnmatchedEnd,
+                        ));
+                    }
+                }
+                (Opcode::break_ | Opcode::recur, InstructionParams::Break { layers, .. }) => {
+                    if layers as usize >= frames.len() {
+                        errors.push(ValidationError::new(
+                            function_internal_index,
+                            Some(record.offset),
+                            ValidationErrorType::BreakLayersOutOfRange { layers },
+                        ));
+                    }
+                }
+                _ => {
+                    // not a control flow instruction with layers
+                }
+            }
+        }
+
+        for frame in frames {
+            let error_type = match frame {
+                Some(_) => ValidationErrorType::UnclosedBlock,
+                None => ValidationErrorType::MissingFunctionEnd,
+            };
+            errors.push(ValidationError::new(
+                function_internal_index,
+                frame,
+                error_type,
+            ));
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks that the `(layers, local_variable_index)` of every `local_load_*`
+/// and `local_store_*` instruction refers to an existing local variable of
+/// the enclosing function or blocks.
+///
+/// Blocks whose local variable list does not exist are skipped silently,
+/// since they are reported by the index validation.
+pub fn validate_local_variable_access(image: &ModuleImage) -> Vec<ValidationError> {
+    // The missing or corrupted sections are reported by `validate_function_codes`.
+    let (Ok(function_section), Ok(local_variable_section)) = (
+        image.try_get_function_section(),
+        image.try_get_local_variable_section(),
+    ) else {
+        return vec![];
+    };
+
+    // Returns the number of local variables of the specified list,
+    // or `None` if the list does not exist.
+    let get_local_variable_count = |local_variable_list_index: usize| -> Option<usize> {
+        local_variable_section
+            .lists
+            .get(local_variable_list_index)
+            .map(|list| list.list_item_count as usize)
+    };
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    for (function_internal_index, code) in get_verified_function_codes(&function_section) {
+        let local_variable_list_index =
+            function_section.items[function_internal_index].local_variable_list_index as usize;
+
+        // The local variable counts of the function and the enclosing blocks,
+        // the last one is the innermost block.
+        let mut frames: Vec<Option<usize>> =
+            vec![get_local_variable_count(local_variable_list_index)];
+
+        for record in InstructionIterator::new(code) {
+            match (record.opcode, record.params) {
+                (
+                    Opcode::block,
+                    InstructionParams::Block {
+                        local_variable_list_index,
+                        ..
+                    },
+                )
+                | (
+                    Opcode::block_alt,
+                    InstructionParams::BlockAlt {
+                        local_variable_list_index,
+                        ..
+                    },
+                )
+                | (
+                    Opcode::block_nez,
+                    InstructionParams::BlockNez {
+                        local_variable_list_index,
+                        ..
+                    },
+                ) => {
+                    frames.push(get_local_variable_count(local_variable_list_index as usize));
+                }
+                (Opcode::end, _) => {
+                    frames.pop();
+                }
+                (_, InstructionParams::Local { layers, index }) => {
+                    let Some(frame) = (layers as usize)
+                        .checked_add(1)
+                        .and_then(|depth| frames.len().checked_sub(depth))
+                        .map(|pos| frames[pos])
+                    else {
+                        errors.push(ValidationError::new(
+                            function_internal_index,
+                            Some(record.offset),
+                            ValidationErrorType::LocalVariableLayersOutOfRange { layers },
+                        ));
+                        continue;
+                    };
+
+                    if let Some(local_variable_count) = frame {
+                        if index as usize >= local_variable_count {
+                            errors.push(ValidationError::new(
+                                function_internal_index,
+                                Some(record.offset),
+                                ValidationErrorType::LocalVariableIndexOutOfRange {
+                                    layers,
+                                    index,
+                                    local_variable_count,
+                                },
+                            ));
+                        }
+                    }
+                }
+                _ => {
+                    // no local variable access
+                }
+            }
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks the data public indices of a linked application.
+///
+/// `module_images[0]` is the application image (i.e., the main module)
+/// which contains the "data index section", and `module_images[i]` is
+/// the module whose `module_index` is `i`.
+///
+/// - Every `data_public_index` operand (located by the relocate list) of
+///   module `i` must be covered by the range `i` of the data index section.
+/// - The target of every data index item must exist in the data section
+///   of the target module.
+pub fn validate_data_public_indices(module_images: &[ModuleImage]) -> Vec<ValidationError> {
+    let Some(application_image) = module_images.first() else {
+        return vec![];
+    };
+
+    let data_index_section = application_image
+        .get_optional_data_index_section()
+        .unwrap_or_default();
+
+    let get_data_count = |module_index: usize| -> usize {
+        data_index_section
+            .ranges
+            .get(module_index)
+            .map_or(0, |range| range.count as usize)
+    };
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    // check the operands
+    for (module_index, module_image) in module_images.iter().enumerate() {
+        let relocate_section = match module_image.try_get_optional_relocate_section() {
+            Ok(Some(relocate_section)) => relocate_section,
+            Ok(None) => continue,
+            Err(_) => {
+                errors.push(ValidationError::from_error_type(
+                    ValidationErrorType::InvalidSection {
+                        section_id: ModuleSectionId::Relocate,
+                    },
+                ));
+                continue;
+            }
+        };
+
+        let Ok(function_section) = module_image.try_get_function_section() else {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::InvalidSection {
+                    section_id: ModuleSectionId::Function,
+                },
+            ));
+            continue;
+        };
+
+        let data_count = get_data_count(module_index);
+
+        for function_internal_index in 0..function_section.items.len() {
+            let (_, _, code) = function_section
+                .get_item_type_index_and_local_variable_list_index_and_code(
+                    function_internal_index,
+                );
+
+            if relocate_section
+                .lists
+                .get(function_internal_index)
+                .is_none()
+            {
+                errors.push(ValidationError::new(
+                    function_internal_index,
+                    None,
+                    ValidationErrorType::RelocateListNotFound { module_index },
+                ));
+                continue;
+            }
+
+            for relocate_item in relocate_section.get_relocate_list(function_internal_index) {
+                if relocate_item.relocate_type != RelocateType::DataPublicIndex {
+                    continue;
+                }
+
+                let param_offset = relocate_item.offset_in_function as usize;
+                let opt_instruction_offset =
+                    param_offset.checked_sub(RELOCATABLE_PARAM_OFFSET_IN_INSTRUCTION);
+                let opt_param_data = param_offset
+                    .checked_add(4)
+                    .and_then(|end| code.get(param_offset..end));
+
+                let (Some(instruction_offset), Some(param_data)) =
+                    (opt_instruction_offset, opt_param_data)
+                else {
+                    errors.push(ValidationError::new(
+                        function_internal_index,
+                        None,
+                        ValidationErrorType::RelocateOffsetOutOfBounds {
+                            module_index,
+                            offset_in_function: relocate_item.offset_in_function,
+                            code_length: code.len(),
+                        },
+                    ));
+                    continue;
+                };
+
+                let data_public_index = u32::from_le_bytes(param_data.try_into().unwrap());
+
+                if data_public_index as usize >= data_count {
+                    errors.push(ValidationError::new(
+                        function_internal_index,
+                        Some(instruction_offset),
+                        ValidationErrorType::DataPublicIndexOutOfRange {
+                            module_index,
+                            data_public_index,
+                            data_count,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    // check the targets
+    for (module_index, range) in data_index_section.ranges.iter().enumerate() {
+        // The ranges exceeding the items are reported by `validate_index_sections`.
+        let is_range_in_bounds = (range.offset as usize)
+            .checked_add(range.count as usize)
+            .is_some_and(|end| end <= data_index_section.items.len());
+        if !is_range_in_bounds {
+            continue;
+        }
+
+        for data_public_index in 0..range.count as usize {
+            let (target_module_index, target_data_section_type, data_internal_index_in_section) =
+                data_index_section
+                    .get_item_target_module_index_and_data_section_type_and_data_internal_index_in_section(
+                        module_index,
+                        data_public_index,
+                    );
+
+            let target_data_count = module_images.get(target_module_index).map_or(0, |image| {
+                get_data_item_count(image, target_data_section_type)
+            });
+
+            if data_internal_index_in_section >= target_data_count {
+                errors.push(ValidationError::from_error_type(
+                    ValidationErrorType::DataIndexTargetNotFound {
+                        module_index,
+                        data_public_index,
+                        target_module_index,
+                        target_data_section_type,
+                        data_internal_index_in_section,
+                    },
+                ));
+            }
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks the entry points of a linked application.
+///
+/// `module_images` is the same as the `validate_data_public_indices`.
+///
+/// - The `function_public_index` of every entry point must be resolved
+///   through the range of the main module in the function index section
+///   to an existing function.
+/// - The unit names must be unique.
+pub fn validate_entry_points(module_images: &[ModuleImage]) -> Vec<ValidationError> {
+    let Some(application_image) = module_images.first() else {
+        return vec![];
+    };
+
+    let Some(entry_point_section) = application_image.get_optional_entry_point_section() else {
+        return vec![];
+    };
+
+    // entry points always exist in the main module.
+    let main_module_function_index_items = application_image
+        .get_optional_function_index_section()
+        .and_then(|section| {
+            // The range exceeding the items is reported by `validate_index_sections`.
+            let range = section.ranges.first()?;
+            let start = range.offset as usize;
+            let end = start.checked_add(range.count as usize)?;
+            section.items.get(start..end)
+        })
+        .unwrap_or_default();
+
+    let function_count = main_module_function_index_items.len();
+
+    let mut errors: Vec<ValidationError> = vec![];
+    let mut unit_names: Vec<String> = vec![];
+
+    for entry in entry_point_section.convert_to_entries() {
+        if unit_names.contains(&entry.unit_name) {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::DuplicateEntryPointUnitName {
+                    unit_name: entry.unit_name.clone(),
+                },
+            ));
+        }
+
+        if entry.function_public_index >= function_count {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                    unit_name: entry.unit_name.clone(),
+                    function_public_index: entry.function_public_index,
+                    function_count,
+                },
+            ));
+        } else {
+            let item = &main_module_function_index_items[entry.function_public_index];
+            let target_module_index = item.target_module_index as usize;
+            let function_internal_index = item.function_internal_index as usize;
+
+            let is_found = module_images
+                .get(target_module_index)
+                .is_some_and(|image| function_internal_index < get_function_count(image));
+
+            if !is_found {
+                errors.push(ValidationError::from_error_type(
+                    ValidationErrorType::EntryPointTargetNotFound {
+                        unit_name: entry.unit_name.clone(),
+                        function_public_index: entry.function_public_index,
+                        target_module_index,
+                        function_internal_index,
+                    },
+                ));
+            }
+        }
+
+        unit_names.push(entry.unit_name);
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks the index sections of a linked application image.
+///
+/// Only the application image is required, so the targets within the other
+/// modules are not checked, see `validate_data_public_indices` and
+/// `validate_entry_points` for checking with all modules.
+///
+/// - The function index section, the data index section and the external function
+///   index section must have a range for each module of the "linking module" section,
+///   and the ranges must be in the order of the modules, i.e., contiguous.
+/// - The target module of every function index item and data index item must exist,
+///   and the target within the main module (i.e., the module `0`) must exist.
+/// - The `function_public_index` of every entry point must be covered by
+///   the range of the main module in the function index section.
+/// - The unified external function index of every external function index item
+///   must be covered by the unified external function section.
+///
+/// Stale index sections (e.g., the modules are re-linked but the index sections
+/// are not regenerated) result in wrong functions being called at runtime,
+/// so they should be caught here.
+///
+/// The images without the "linking module" section (i.e., the object files
+/// and the shared modules) are not checked.
+pub fn validate_index_sections(image: &ModuleImage) -> Vec<ValidationError> {
+    let Some(linking_module_section) = image.get_optional_dynamic_link_module_list_section() else {
+        return vec![];
+    };
+
+    let module_count = linking_module_section.items.len();
+    let function_count = get_function_count(image);
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    let mut check_range_count = |section_id: ModuleSectionId, range_count: usize| {
+        if range_count != module_count {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::IndexRangeCountMismatch {
+                    section_id,
+                    range_count,
+                    module_count,
+                },
+            ));
+        }
+    };
+
+    let function_index_section = image.get_optional_function_index_section();
+    let data_index_section = image.get_optional_data_index_section();
+    let external_function_index_section = image.get_optional_external_function_index_section();
+
+    if let Some(section) = &function_index_section {
+        check_range_count(ModuleSectionId::FunctionIndex, section.ranges.len());
+    }
+
+    if let Some(section) = &data_index_section {
+        check_range_count(ModuleSectionId::DataIndex, section.ranges.len());
+    }
+
+    if let Some(section) = &external_function_index_section {
+        check_range_count(ModuleSectionId::ExternalFunctionIndex, section.ranges.len());
+    }
+
+    let mut check_range_order = |section_id: ModuleSectionId,
+                                 ranges: &[RangeItem],
+                                 item_count: usize| {
+        let mut expected_offset: usize = 0;
+        for (module_index, range) in ranges.iter().enumerate() {
+            let range_offset = range.offset as usize;
+            if range_offset != expected_offset || range_offset + range.count as usize > item_count {
+                errors.push(ValidationError::from_error_type(
+                    ValidationErrorType::IndexRangeOutOfOrder {
+                        section_id,
+                        module_index,
+                        range_offset,
+                        expected_offset,
+                    },
+                ));
+            }
+            expected_offset = range_offset + range.count as usize;
+        }
+    };
+
+    if let Some(section) = &function_index_section {
+        check_range_order(
+            ModuleSectionId::FunctionIndex,
+            section.ranges,
+            section.items.len(),
+        );
+    }
+
+    if let Some(section) = &data_index_section {
+        check_range_order(
+            ModuleSectionId::DataIndex,
+            section.ranges,
+            section.items.len(),
+        );
+    }
+
+    if let Some(section) = &external_function_index_section {
+        check_range_order(
+            ModuleSectionId::ExternalFunctionIndex,
+            section.ranges,
+            section.items.len(),
+        );
+    }
+
+    if let Some(section) = &function_index_section {
+        for (module_index, range) in section.ranges.iter().enumerate() {
+            for function_public_index in 0..range.count as usize {
+                let Some(item) = section
+                    .items
+                    .get(range.offset as usize + function_public_index)
+                else {
+                    break;
+                };
+
+                let target_module_index = item.target_module_index as usize;
+                let function_internal_index = item.function_internal_index as usize;
+
+                let is_found = target_module_index < module_count
+                    && (target_module_index != 0 || function_internal_index < function_count);
+
+                if !is_found {
+                    errors.push(ValidationError::from_error_type(
+                        ValidationErrorType::FunctionIndexTargetNotFound {
+                            module_index,
+                            function_public_index,
+                            target_module_index,
+                            function_internal_index,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(section) = &data_index_section {
+        for (module_index, range) in section.ranges.iter().enumerate() {
+            for data_public_index in 0..range.count as usize {
+                let Some(item) = section.items.get(range.offset as usize + data_public_index)
+                else {
+                    break;
+                };
+
+                let target_module_index = item.target_module_index as usize;
+                let target_data_section_type = item.target_data_section_type;
+                let data_internal_index_in_section = item.data_internal_index_in_section as usize;
+
+                let is_found = target_module_index < module_count
+                    && (target_module_index != 0
+                        || data_internal_index_in_section
+                            < get_data_item_count(image, target_data_section_type));
+
+                if !is_found {
+                    errors.push(ValidationError::from_error_type(
+                        ValidationErrorType::DataIndexTargetNotFound {
+                            module_index,
+                            data_public_index,
+                            target_module_index,
+                            target_data_section_type,
+                            data_internal_index_in_section,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(section) = &external_function_index_section {
+        let unified_external_function_count = image
+            .get_optional_unified_external_function_section()
+            .map_or(0, |section| section.items.len());
+
+        for (module_index, range) in section.ranges.iter().enumerate() {
+            for external_function_index in 0..range.count as usize {
+                let Some(item) = section
+                    .items
+                    .get(range.offset as usize + external_function_index)
+                else {
+                    break;
+                };
+
+                let unified_external_function_index = item.unified_external_function_index as usize;
+                if unified_external_function_index >= unified_external_function_count {
+                    errors.push(ValidationError::from_error_type(
+                        ValidationErrorType::UnifiedExternalFunctionIndexOutOfRange {
+                            module_index,
+                            external_function_index,
+                            unified_external_function_index,
+                            unified_external_function_count,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(entry_point_section) = image.get_optional_entry_point_section() {
+        let main_function_count = function_index_section
+            .as_ref()
+            .and_then(|section| section.ranges.first())
+            .map_or(0, |range| range.count as usize);
+
+        for entry in entry_point_section.convert_to_entries() {
+            if entry.function_public_index >= main_function_count {
+                errors.push(ValidationError::from_error_type(
+                    ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                        unit_name: entry.unit_name,
+                        function_public_index: entry.function_public_index,
+                        function_count: main_function_count,
+                    },
+                ));
+            }
+        }
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks the layout of the items in the read-only and read-write data sections:
+///
+/// - `data_offset + data_length` must not exceed the data area.
+/// - `data_offset` must be a multiple of `data_align` rounded up to
+///   `DATA_ITEM_ALIGN_BYTES` (8 bytes), see `ReadOnlyDataSection::convert_from_entries`.
+/// - The items must not overlap with each other.
+///
+/// The uninitialized data section has no data area, so it is not checked.
+pub fn validate_data_layout(image: &ModuleImage) -> Vec<ValidationError> {
+    let mut errors: Vec<ValidationError> = vec![];
+
+    if let Some(section) = image.get_optional_read_only_data_section() {
+        let ranges = section
+            .items
+            .iter()
+            .map(|item| (item.data_offset, item.data_length, item.data_align))
+            .collect::<Vec<_>>();
+        check_data_layout(
+            DataSectionType::ReadOnly,
+            &ranges,
+            section.datas_data.len(),
+            &mut errors,
+        );
+    }
+
+    if let Some(section) = image.get_optional_read_write_data_section() {
+        let ranges = section
+            .items
+            .iter()
+            .map(|item| (item.data_offset, item.data_length, item.data_align))
+            .collect::<Vec<_>>();
+        check_data_layout(
+            DataSectionType::ReadWrite,
+            &ranges,
+            section.datas_data.len(),
+            &mut errors,
+        );
+    }
+
+    trace_errors(errors)
+}
+
+/// Checks that the functions of the initializer section (constructors and finalizers)
+/// exist, are public, and have the signature `() -> ()`.
+///
+/// The visibility is checked only if the "function name section" is present,
+/// since the names may be stripped from the application images.
+pub fn validate_initializers(image: &ModuleImage) -> Vec<ValidationError> {
+    let Some(initializer_section) = image.get_optional_initializer_section() else {
+        return vec![];
+    };
+
+    let type_section = image.get_type_section();
+    let function_section = image.get_function_section();
+    let function_name_section = image.get_optional_export_function_section();
+    let function_count = function_section.items.len();
+
+    let mut errors: Vec<ValidationError> = vec![];
+
+    for item in initializer_section.items {
+        let function_internal_index = item.function_internal_index as usize;
+        let initializer_type = item.initializer_type;
+
+        let Some(function_item) = function_section.items.get(function_internal_index) else {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::InitializerTargetNotFound {
+                    initializer_type,
+                    function_internal_index,
+                    function_count,
+                },
+            ));
+            continue;
+        };
+
+        if let Some(function_name_section) = &function_name_section {
+            let is_public = function_name_section
+                .get_item_full_name_and_visibility(function_internal_index)
+                .is_some_and(|(_, visibility)| visibility == Visibility::Public);
+
+            if !is_public {
+                errors.push(ValidationError::new(
+                    function_internal_index,
+                    None,
+                    ValidationErrorType::InitializerNotPublic { initializer_type },
+                ));
+            }
+        }
+
+        let type_index = function_item.type_index as usize;
+        let is_signature_matched = type_index < type_section.items.len() && {
+            let (params, results) = type_section.get_item_params_and_results(type_index);
+            params.is_empty() && results.is_empty()
+        };
+
+        if !is_signature_matched {
+            errors.push(ValidationError::new(
+                function_internal_index,
+                None,
+                ValidationErrorType::InitializerSignatureMismatch { initializer_type },
+            ));
+        }
+    }
+
+    trace_errors(errors)
+}
+
+// `ranges` is a list of `(data_offset, data_length, data_align)`.
+fn check_data_layout(
+    data_section_type: DataSectionType,
+    ranges: &[(u32, u32, u16)],
+    data_area_length: usize,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (idx, (data_offset, data_length, data_align)) in ranges.iter().enumerate() {
+        if *data_offset as usize + *data_length as usize > data_area_length {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::DataItemOutOfBounds {
+                    data_section_type,
+                    data_internal_index_in_section: idx,
+                    data_offset: *data_offset,
+                    data_length: *data_length,
+                    data_area_length,
+                },
+            ));
+        }
+
+        // The value `0` of `data_align` is invalid, it is treated as `1` here
+        // so that the alignment is still rounded up to `DATA_ITEM_ALIGN_BYTES`.
+        let head_align = DATA_ITEM_ALIGN_BYTES as u32;
+        let required_align = (*data_align as u32).max(1).div_ceil(head_align) * head_align;
+
+        if data_offset % required_align != 0 {
+            errors.push(ValidationError::from_error_type(
+                ValidationErrorType::DataItemMisaligned {
+                    data_section_type,
+                    data_internal_index_in_section: idx,
+                    data_offset: *data_offset,
+                    required_align,
+                },
+            ));
+        }
+    }
+
+    // Checks the overlapping in the order of the offsets, the empty items are skipped.
+    let mut sorted_indices = (0..ranges.len())
+        .filter(|idx| ranges[*idx].1 > 0)
+        .collect::<Vec<_>>();
+    sorted_indices.sort_by_key(|idx| ranges[*idx].0);
+
+    // The index and the end offset of the item which ends the farthest so far.
+    let mut farthest: Option<(usize, u64)> = None;
+
+    for idx in sorted_indices {
+        let (data_offset, data_length, _) = ranges[idx];
+        let data_end = data_offset as u64 + data_length as u64;
+
+        match farthest {
+            Some((other_idx, other_end)) => {
+                if (data_offset as u64) < other_end {
+                    errors.push(ValidationError::from_error_type(
+                        ValidationErrorType::DataItemOverlapped {
+                            data_section_type,
+                            data_internal_index_in_section: idx,
+                            other_data_internal_index_in_section: other_idx,
+                        },
+                    ));
+                }
+
+                if data_end > other_end {
+                    farthest = Some((idx, data_end));
+                }
+            }
+            None => farthest = Some((idx, data_end)),
+        }
+    }
+}
+
+// Returns the number of the items of the specified data section.
+fn get_data_item_count(image: &ModuleImage, data_section_type: DataSectionType) -> usize {
+    match data_section_type {
+        DataSectionType::ReadOnly => image
+            .get_optional_read_only_data_section()
+            .map_or(0, |section| section.items.len()),
+        DataSectionType::ReadWrite => image
+            .get_optional_read_write_data_section()
+            .map_or(0, |section| section.items.len()),
+        DataSectionType::Uninit => image
+            .get_optional_uninit_data_section()
+            .map_or(0, |section| section.items.len()),
+    }
+}
+
+// Returns the number of functions of the image, the missing or corrupted
+// function section is reported by `validate_function_codes`.
+fn get_function_count(image: &ModuleImage) -> usize {
+    image
+        .try_get_function_section()
+        .map_or(0, |section| section.items.len())
+}
+
+// Returns the `(function_internal_index, code)` of the functions which pass
+// the bytecode verification, the instructions of the other functions can not
+// be decoded, they are reported by `validate_function_codes`.
+fn get_verified_function_codes<'a>(
+    function_section: &'a FunctionSection<'a>,
+) -> Vec<(usize, &'a [u8])> {
+    (0..function_section.items.len())
+        .map(|function_internal_index| {
+            let (_, _, code) = function_section
+                .get_item_type_index_and_local_variable_list_index_and_code(
+                    function_internal_index,
+                );
+            (function_internal_index, code)
+        })
+        .filter(|(_, code)| verify_bytecode(code).is_ok())
+        .collect()
+}
+
+// Emits an event for each error if the feature "tracing" is enabled.
+fn trace_errors(errors: Vec<ValidationError>) -> Vec<ValidationError> {
+    #[cfg(feature = "tracing")]
+    for error in &errors {
+        tracing::warn!(target: "anc_image::validator", "{}", error);
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, DataSectionType, MemoryDataType, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_verifier::BytecodeVerificationErrorType,
+        bytecode_writer::BytecodeWriterHelper,
+        common_sections::{
+            function_name_section::FunctionNameSection,
+            function_section::FunctionSection,
+            local_variable_section::LocalVariableSection,
+            read_only_data_section::{DataItem, ReadOnlyDataSection},
+            relocate_section::RelocateSection,
+            type_section::TypeSection,
+        },
+        entry::{
+            DataIndexEntry, DataIndexListEntry, EntryPointEntry, FunctionEntry, FunctionIndexEntry,
+            FunctionIndexListEntry, FunctionNameEntry, InitializerEntry, LocalVariableListEntry,
+            ReadOnlyDataEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+        },
+        entry_reader::read_object_file,
+        entry_writer::{build_shared_module_scaffold, write_object_file},
+        image_pipeline::ImageSections,
+        linking_sections::{
+            data_index_section::DataIndexSection,
+            entry_point_section::EntryPointSection,
+            external_function_index_section::{
+                ExternalFunctionIndexItem, ExternalFunctionIndexSection,
+            },
+            function_index_section::{FunctionIndexItem, FunctionIndexSection},
+        },
+        module_image::{
+            ImageType, InitializerType, ModuleImage, ModuleSectionId, RangeItem, SectionEntry,
+            Visibility,
+        },
+        utils::helper_build_application_fixture,
+        validator::{
+            validate_block_structure, validate_data_layout, validate_data_public_indices,
+            validate_entry_points, validate_function_codes, validate_index_sections,
+            validate_initializers, validate_local_variable_access, validate_module_image,
+            validate_strict, validate_type_and_local_variable_list_indices, ValidationError,
+            ValidationErrorType,
+        },
+    };
+
+    #[test]
+    fn test_validate_local_variable_access() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 1) // 0x0000
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 2) // 0x0008, out of range
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0010
+            .append_opcode_i16_i32(Opcode::local_store_i32, 0, 0) // 0x001c
+            .append_opcode_i16_i32(Opcode::local_store_i32, 0, 1) // 0x0024, out of range
+            .append_opcode_i16_i32(Opcode::local_store_i32, 1, 1) // 0x002c
+            .append_opcode_i16_i32(Opcode::local_store_i32, 2, 0) // 0x0034, out of range
+            .append_opcode(Opcode::end) // 0x003c
+            .append_opcode(Opcode::end) // 0x003e
+            .to_bytes();
+
+        let (type_items, types_data) =
+            TypeSection::convert_from_entries(&[TypeEntry::new(vec![], vec![])]);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let (local_variable_lists, local_variable_list_data) =
+            LocalVariableSection::convert_from_entries(&[
+                LocalVariableListEntry::new(vec![OperandDataType::I32, OperandDataType::I32]),
+                LocalVariableListEntry::new(vec![OperandDataType::I32]),
+            ]);
+        let local_variable_section = LocalVariableSection {
+            lists: &local_variable_lists,
+            list_data: &local_variable_list_data,
+        };
+
+        let (function_items, codes_data) =
+            FunctionSection::convert_from_entries(&[FunctionEntry::new(0, 0, code)]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> =
+            vec![&type_section, &local_variable_section, &function_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let errors = validate_local_variable_access(&image);
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    0,
+                    Some(0x08),
+                    ValidationErrorType::LocalVariableIndexOutOfRange {
+                        layers: 0,
+                        index: 2,
+                        local_variable_count: 2
+                    }
+                ),
+                ValidationError::new(
+                    0,
+                    Some(0x24),
+                    ValidationErrorType::LocalVariableIndexOutOfRange {
+                        layers: 0,
+                        index: 1,
+                        local_variable_count: 1
+                    }
+                ),
+                ValidationError::new(
+                    0,
+                    Some(0x34),
+                    ValidationErrorType::LocalVariableLayersOutOfRange { layers: 2 }
+                ),
+            ]
+        );
+
+        assert_eq!(
+            errors[0].to_string(),
+            "Function 0, instruction 0x0008: The local variable index 2 (layers 0) is out of range, the list has 2 variables."
+        );
+    }
+
+    #[test]
+    fn test_validate_block_structure() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 0, 0) // 0x0000
+            .append_opcode_i16_i32(Opcode::break_, 1, 0) // 0x000c
+            .append_opcode_i16_i32(Opcode::break_, 2, 0) // 0x0014, out of range
+            .append_opcode(Opcode::end) // 0x001c
+            .append_opcode_i16_i32(Opcode::recur, 1, 0) // 0x0020, out of range
+            .append_opcode(Opcode::end) // 0x0028
+            .append_opcode(Opcode::end) // 0x002a, unmatched
+            .to_bytes();
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block_nez, 0, 0x14) // 0x0000, unclosed
+            .append_opcode_i16_i32(Opcode::break_, 1, 0) // 0x000c
+            .to_bytes();
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, code0),
+            FunctionEntry::new(0, 0, code1),
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![&function_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let errors = validate_block_structure(&image);
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    0,
+                    Some(0x14),
+                    ValidationErrorType::BreakLayersOutOfRange { layers: 2 }
+                ),
+                ValidationError::new(
+                    0,
+                    Some(0x20),
+                    ValidationErrorType::BreakLayersOutOfRange { layers: 1 }
+                ),
+                ValidationError::new(0, Some(0x2a), ValidationErrorType::UnmatchedEnd),
+                ValidationError::new(1, None, ValidationErrorType::MissingFunctionEnd),
+                ValidationError::new(1, Some(0x00), ValidationErrorType::UnclosedBlock),
+            ]
+        );
+
+        assert_eq!(
+            errors[0].to_string(),
+            "Function 0, instruction 0x0014: The break layers 2 exceeds the number of the enclosing blocks."
+        );
+    }
+
+    #[test]
+    fn test_validate_function_codes() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end) // 0x0000
+            .to_bytes();
+
+        let mut code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::nop) // 0x0000
+            .to_bytes();
+        code1.extend_from_slice(&[0xff, 0xff]); // 0x0002, unknown opcode
+
+        let (local_variable_lists, local_variable_list_data) =
+            LocalVariableSection::convert_from_entries(&[LocalVariableListEntry::new(vec![])]);
+        let local_variable_section = LocalVariableSection {
+            lists: &local_variable_lists,
+            list_data: &local_variable_list_data,
+        };
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, code0),
+            FunctionEntry::new(0, 0, code1),
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        // the type section is missing
+        let section_entries: Vec<&dyn SectionEntry> =
+            vec![&local_variable_section, &function_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let errors = validate_function_codes(&image);
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::from_error_type(ValidationErrorType::InvalidSection {
+                    section_id: ModuleSectionId::Type
+                }),
+                ValidationError::new(
+                    1,
+                    Some(0x02),
+                    ValidationErrorType::InvalidBytecode {
+                        error_type: BytecodeVerificationErrorType::UnknownOpcode(0xffff)
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(
+            errors[1].to_string(),
+            "Function 1, instruction 0x0002: Invalid bytecode: Unknown opcode 0xffff."
+        );
+
+        // the invalid code is skipped by the other checks
+        assert_eq!(validate_block_structure(&image), vec![]);
+        assert_eq!(validate_local_variable_access(&image), vec![]);
+        assert_eq!(
+            validate_type_and_local_variable_list_indices(&image),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_validate_type_and_local_variable_list_indices() {
+        let code0 = BytecodeWriterHelper::new()
+            .append_opcode_i32_i32(Opcode::block, 0, 1) // 0x0000
+            .append_opcode(Opcode::end) // 0x000c
+            .append_opcode_i32_i32(Opcode::block, 3, 0) // 0x0010, type index out of range
+            .append_opcode(Opcode::end) // 0x001c
+            .append_opcode_i32_i32(Opcode::block_nez, 2, 0x0e) // 0x0020, local list out of range
+            .append_opcode(Opcode::end) // 0x002c
+            .append_opcode(Opcode::end) // 0x002e
+            .to_bytes();
+
+        let code1 = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::end) // 0x0000
+            .to_bytes();
+
+        let (type_items, types_data) =
+            TypeSection::convert_from_entries(&[TypeEntry::new(vec![], vec![])]);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let (local_variable_lists, local_variable_list_data) =
+            LocalVariableSection::convert_from_entries(&[
+                LocalVariableListEntry::new(vec![]),
+                LocalVariableListEntry::new(vec![]),
+            ]);
+        let local_variable_section = LocalVariableSection {
+            lists: &local_variable_lists,
+            list_data: &local_variable_list_data,
+        };
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, code0),
+            FunctionEntry::new(1, 0, code1), // type index out of range
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let (function_name_items, function_names_data) =
+            FunctionNameSection::convert_from_entries(&[FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Public,
+                1,
+            )]);
+        let function_name_section = FunctionNameSection {
+            items: &function_name_items,
+            full_names_data: &function_names_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![
+            &type_section,
+            &local_variable_section,
+            &function_section,
+            &function_name_section,
+        ];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let errors = validate_type_and_local_variable_list_indices(&image);
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    0,
+                    Some(0x10),
+                    ValidationErrorType::TypeIndexOutOfRange {
+                        type_index: 3,
+                        type_count: 1
+                    }
+                ),
+                ValidationError::new(
+                    0,
+                    Some(0x20),
+                    ValidationErrorType::LocalVariableListIndexOutOfRange {
+                        local_variable_list_index: 2,
+                        local_variable_list_count: 2
+                    }
+                ),
+                ValidationError::new(
+                    1,
+                    None,
+                    ValidationErrorType::TypeIndexOutOfRange {
+                        type_index: 1,
+                        type_count: 1
+                    }
+                ),
+            ]
+        );
+
+        let errors_strict = validate_strict(&image);
+
+        assert_eq!(errors_strict.len(), 3);
+        assert_eq!(
+            errors_strict[1].to_string(),
+            "Function 0, instruction 0x0020: The local variable list index 2 is out of range, the local variable section has 2 lists."
+        );
+        assert_eq!(
+            errors_strict[2].to_string(),
+            "Function \"foo::bar\" (index 1): The type index 1 is out of range, the type section has 1 items."
+        );
+    }
+
+    #[test]
+    fn test_validate_data_public_indices() {
+        // module 0 (the application image)
+
+        let code = BytecodeWriterHelper::new()
+            .append_opcode_i16_i32(Opcode::data_load_i32_u, 0, 1) // 0x0000
+            .append_opcode_i32(Opcode::get_data, 2) // 0x0008, out of range
+            .append_opcode(Opcode::end) // 0x0010
+            .to_bytes();
+
+        let (function_items0, codes_data0) =
+            FunctionSection::convert_from_entries(&[FunctionEntry::new(0, 0, code)]);
+        let function_section0 = FunctionSection {
+            items: &function_items0,
+            codes_data: &codes_data0,
+        };
+
+        let (relocate_lists, relocate_list_data) =
+            RelocateSection::convert_from_entries(&[RelocateListEntry::new(vec![
+                RelocateEntry::from_data_public_index(0),
+                RelocateEntry::from_data_public_index(8),
+                RelocateEntry::from_data_public_index(0x10), // out of bounds
+            ])]);
+        let relocate_section = RelocateSection {
+            lists: &relocate_lists,
+            list_data: &relocate_list_data,
+        };
+
+        let (read_only_data_items, read_only_datas_data) =
+            ReadOnlyDataSection::convert_from_entries(&[ReadOnlyDataEntry::from_i32(11)]);
+        let read_only_data_section = ReadOnlyDataSection {
+            items: &read_only_data_items,
+            datas_data: &read_only_datas_data,
+        };
+
+        let (data_index_ranges, data_index_items) =
+            DataIndexSection::convert_from_entries(&[DataIndexListEntry::new(vec![
+                DataIndexEntry::new(0, DataSectionType::ReadOnly, 0),
+                DataIndexEntry::new(1, DataSectionType::ReadWrite, 0), // target not found
+            ])]);
+        let data_index_section = DataIndexSection {
+            ranges: &data_index_ranges,
+            items: &data_index_items,
+        };
+
+        let section_entries0: Vec<&dyn SectionEntry> = vec![
+            &function_section0,
+            &relocate_section,
+            &read_only_data_section,
+            &data_index_section,
+        ];
+        let (section_items0, sections_data0) =
+            ModuleImage::convert_from_section_entries(&section_entries0).unwrap();
+        let image0 = ModuleImage::new(ImageType::Application, &section_items0, &sections_data0);
+
+        // module 1
+
+        let (function_items1, codes_data1) = FunctionSection::convert_from_entries(&[]);
+        let function_section1 = FunctionSection {
+            items: &function_items1,
+            codes_data: &codes_data1,
+        };
+
+        let section_entries1: Vec<&dyn SectionEntry> = vec![&function_section1];
+        let (section_items1, sections_data1) =
+            ModuleImage::convert_from_section_entries(&section_entries1).unwrap();
+        let image1 = ModuleImage::new(ImageType::SharedModule, &section_items1, &sections_data1);
+
+        let errors = validate_data_public_indices(&[image0, image1]);
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::new(
+                    0,
+                    Some(0x08),
+                    ValidationErrorType::DataPublicIndexOutOfRange {
+                        module_index: 0,
+                        data_public_index: 2,
+                        data_count: 2
+                    }
+                ),
+                ValidationError::new(
+                    0,
+                    None,
+                    ValidationErrorType::RelocateOffsetOutOfBounds {
+                        module_index: 0,
+                        offset_in_function: 0x14,
+                        code_length: 0x12
+                    }
+                ),
+                ValidationError::from_error_type(ValidationErrorType::DataIndexTargetNotFound {
+                    module_index: 0,
+                    data_public_index: 1,
+                    target_module_index: 1,
+                    target_data_section_type: DataSectionType::ReadWrite,
+                    data_internal_index_in_section: 0
+                }),
+            ]
+        );
+
+        assert_eq!(
+            errors[1].to_string(),
+            "Function 0: The relocate offset 20 of module 0 is out of bounds, the code has 18 bytes."
+        );
+        assert_eq!(
+            errors[2].to_string(),
+            "The target of data public index 1 of module 0 does not exist, module: 1, section: ReadWrite, index: 0."
+        );
+    }
+
+    #[test]
+    fn test_validate_entry_points() {
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(0, 0, vec![]),
+            FunctionEntry::new(0, 0, vec![]),
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let (function_index_ranges, function_index_items) =
+            FunctionIndexSection::convert_from_entries(&[FunctionIndexListEntry::new(vec![
+                FunctionIndexEntry::new(0, 0),
+                FunctionIndexEntry::new(0, 1),
+                FunctionIndexEntry::new(0, 2), // target not found
+            ])]);
+        let function_index_section = FunctionIndexSection {
+            ranges: &function_index_ranges,
+            items: &function_index_items,
+        };
+
+        let (entry_point_items, unit_names_data) = EntryPointSection::convert_from_entries(&[
+            EntryPointEntry::new("_start".to_owned(), 0),
+            EntryPointEntry::new("foo".to_owned(), 2),
+            EntryPointEntry::new("bar".to_owned(), 3),
+            EntryPointEntry::new("foo".to_owned(), 1),
+        ]);
+        let entry_point_section = EntryPointSection {
+            items: &entry_point_items,
+            unit_names_data: &unit_names_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> = vec![
+            &function_section,
+            &function_index_section,
+            &entry_point_section,
+        ];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::Application, &section_items, &sections_data);
+
+        assert_eq!(
+            validate_entry_points(&[image]),
+            vec![
+                ValidationError::from_error_type(ValidationErrorType::EntryPointTargetNotFound {
+                    unit_name: "foo".to_owned(),
+                    function_public_index: 2,
+                    target_module_index: 0,
+                    function_internal_index: 2
+                }),
+                ValidationError::from_error_type(
+                    ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                        unit_name: "bar".to_owned(),
+                        function_public_index: 3,
+                        function_count: 3
+                    }
+                ),
+                ValidationError::from_error_type(
+                    ValidationErrorType::DuplicateEntryPointUnitName {
+                        unit_name: "foo".to_owned()
+                    }
+                ),
+            ]
+        );
+
+        // the function index section is missing
+        let section_entries: Vec<&dyn SectionEntry> = vec![&entry_point_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::Application, &section_items, &sections_data);
+
+        let errors = validate_entry_points(&[image]);
+        assert_eq!(errors.len(), 5);
+        assert_eq!(
+            errors[0],
+            ValidationError::from_error_type(
+                ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                    unit_name: "_start".to_owned(),
+                    function_public_index: 0,
+                    function_count: 0
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_module_image() {
+        let fixture = helper_build_application_fixture(2);
+        let image = ModuleImage::read(&fixture.application_binary).unwrap();
+        assert_eq!(validate_module_image(&image), Ok(()));
+
+        // replace the index sections
+        let (function_index_ranges, function_index_items) =
+            FunctionIndexSection::convert_from_entries(&[
+                FunctionIndexListEntry::new(vec![
+                    FunctionIndexEntry::new(1, 0),
+                    FunctionIndexEntry::new(3, 0), // module not found
+                ]),
+                FunctionIndexListEntry::new(vec![
+                    FunctionIndexEntry::new(0, 1), // function not found
+                ]),
+            ]);
+        let function_index_section = FunctionIndexSection {
+            ranges: &function_index_ranges,
+            items: &function_index_items,
+        };
+
+        let (data_index_ranges, data_index_items) = DataIndexSection::convert_from_entries(&[
+            DataIndexListEntry::new(vec![
+                DataIndexEntry::new(1, DataSectionType::ReadOnly, 0),
+                DataIndexEntry::new(0, DataSectionType::ReadOnly, 0), // data not found
+            ]),
+            DataIndexListEntry::new(vec![]),
+            DataIndexListEntry::new(vec![]),
+        ]);
+        let data_index_section = DataIndexSection {
+            ranges: &data_index_ranges,
+            items: &data_index_items,
+        };
+
+        let mut function_index_section_data: Vec<u8> = vec![];
+        function_index_section
+            .write(&mut function_index_section_data)
+            .unwrap();
+        let mut data_index_section_data: Vec<u8> = vec![];
+        data_index_section
+            .write(&mut data_index_section_data)
+            .unwrap();
+
+        let mut image_sections = ImageSections::from_module_image(&image);
+        image_sections
+            .set_section_data(ModuleSectionId::FunctionIndex, function_index_section_data);
+        image_sections.set_section_data(ModuleSectionId::DataIndex, data_index_section_data);
+
+        let mut image_binary: Vec<u8> = vec![];
+        image_sections.write(&mut image_binary).unwrap();
+        let image = ModuleImage::read(&image_binary).unwrap();
+
+        let errors = validate_module_image(&image).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::from_error_type(ValidationErrorType::IndexRangeCountMismatch {
+                    section_id: ModuleSectionId::FunctionIndex,
+                    range_count: 2,
+                    module_count: 3
+                }),
+                ValidationError::from_error_type(
+                    ValidationErrorType::FunctionIndexTargetNotFound {
+                        module_index: 0,
+                        function_public_index: 1,
+                        target_module_index: 3,
+                        function_internal_index: 0
+                    }
+                ),
+                ValidationError::from_error_type(
+                    ValidationErrorType::FunctionIndexTargetNotFound {
+                        module_index: 1,
+                        function_public_index: 0,
+                        target_module_index: 0,
+                        function_internal_index: 1
+                    }
+                ),
+                ValidationError::from_error_type(ValidationErrorType::DataIndexTargetNotFound {
+                    module_index: 0,
+                    data_public_index: 1,
+                    target_module_index: 0,
+                    target_data_section_type: DataSectionType::ReadOnly,
+                    data_internal_index_in_section: 0
+                }),
+                ValidationError::from_error_type(
+                    ValidationErrorType::EntryPointFunctionIndexOutOfRange {
+                        unit_name: "_start".to_owned(),
+                        function_public_index: 2,
+                        function_count: 2
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(
+            errors[0].to_string(),
+            "The function_index section has 2 ranges, but the application has 3 modules."
+        );
+    }
+
+    #[test]
+    fn test_validate_stale_index_sections() {
+        let fixture = helper_build_application_fixture(1);
+        let image = ModuleImage::read(&fixture.application_binary).unwrap();
+        assert_eq!(validate_index_sections(&image), vec![]);
+
+        // the ranges are swapped, i.e., the items are
+        // `[(1, 0), (0, 0), (1, 0)]`, the correct ranges are `[(0, 2), (2, 1)]`.
+        let function_index_ranges = [RangeItem::new(1, 2), RangeItem::new(0, 1)];
+        let function_index_items = [
+            FunctionIndexItem::new(1, 0),
+            FunctionIndexItem::new(0, 0),
+            FunctionIndexItem::new(1, 0),
+        ];
+        let function_index_section = FunctionIndexSection {
+            ranges: &function_index_ranges,
+            items: &function_index_items,
+        };
+
+        // there is no unified external function
+        let external_function_index_ranges = [RangeItem::new(0, 1), RangeItem::new(1, 0)];
+        let external_function_index_items = [ExternalFunctionIndexItem::new(5)];
+        let external_function_index_section = ExternalFunctionIndexSection {
+            ranges: &external_function_index_ranges,
+            items: &external_function_index_items,
+        };
+
+        let mut function_index_section_data: Vec<u8> = vec![];
+        function_index_section
+            .write(&mut function_index_section_data)
+            .unwrap();
+        let mut external_function_index_section_data: Vec<u8> = vec![];
+        external_function_index_section
+            .write(&mut external_function_index_section_data)
+            .unwrap();
+
+        let mut image_sections = ImageSections::from_module_image(&image);
+        image_sections
+            .set_section_data(ModuleSectionId::FunctionIndex, function_index_section_data);
+        image_sections.set_section_data(
+            ModuleSectionId::ExternalFunctionIndex,
+            external_function_index_section_data,
+        );
+
+        let mut image_binary: Vec<u8> = vec![];
+        image_sections.write(&mut image_binary).unwrap();
+        let image = ModuleImage::read(&image_binary).unwrap();
+
+        let errors = validate_index_sections(&image);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::from_error_type(ValidationErrorType::IndexRangeOutOfOrder {
+                    section_id: ModuleSectionId::FunctionIndex,
+                    module_index: 0,
+                    range_offset: 1,
+                    expected_offset: 0
+                }),
+                ValidationError::from_error_type(ValidationErrorType::IndexRangeOutOfOrder {
+                    section_id: ModuleSectionId::FunctionIndex,
+                    module_index: 1,
+                    range_offset: 0,
+                    expected_offset: 3
+                }),
+                ValidationError::from_error_type(
+                    ValidationErrorType::UnifiedExternalFunctionIndexOutOfRange {
+                        module_index: 0,
+                        external_function_index: 0,
+                        unified_external_function_index: 5,
+                        unified_external_function_count: 0
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(
+            errors[0].to_string(),
+            "The range of module 0 in the function_index section starts at 1, expected 0."
+        );
+    }
+
+    #[test]
+    fn test_validate_data_layout() {
+        let (items, datas_data) = ReadOnlyDataSection::convert_from_entries(&[
+            ReadOnlyDataEntry::from_i32(11),
+            ReadOnlyDataEntry::from_i64(13),
+            ReadOnlyDataEntry::from_bytes(b"hello".to_vec(), 1),
+        ]);
+
+        let build_image_binary = |items: &[DataItem], datas_data: &[u8]| {
+            let read_only_data_section = ReadOnlyDataSection { items, datas_data };
+            let section_entries: Vec<&dyn SectionEntry> = vec![&read_only_data_section];
+            let (section_items, sections_data) =
+                ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+            let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+            let mut image_binary: Vec<u8> = vec![];
+            image.write(&mut image_binary).unwrap();
+            image_binary
+        };
+
+        let image_binary = build_image_binary(&items, &datas_data);
+        assert!(validate_data_layout(&ModuleImage::read(&image_binary).unwrap()).is_empty());
+
+        // corrupt the items
+        let corrupted_items = vec![
+            DataItem::new(0, 4, MemoryDataType::I32, 4),
+            DataItem::new(2, 8, MemoryDataType::I64, 8), // misaligned and overlapped
+            DataItem::new(16, 16, MemoryDataType::Bytes, 1), // out of bounds
+        ];
+        let image_binary = build_image_binary(&corrupted_items, &datas_data);
+
+        assert_eq!(
+            validate_data_layout(&ModuleImage::read(&image_binary).unwrap()),
+            vec![
+                ValidationError::from_error_type(ValidationErrorType::DataItemMisaligned {
+                    data_section_type: DataSectionType::ReadOnly,
+                    data_internal_index_in_section: 1,
+                    data_offset: 2,
+                    required_align: 8
+                }),
+                ValidationError::from_error_type(ValidationErrorType::DataItemOutOfBounds {
+                    data_section_type: DataSectionType::ReadOnly,
+                    data_internal_index_in_section: 2,
+                    data_offset: 16,
+                    data_length: 16,
+                    data_area_length: 21
+                }),
+                ValidationError::from_error_type(ValidationErrorType::DataItemOverlapped {
+                    data_section_type: DataSectionType::ReadOnly,
+                    data_internal_index_in_section: 1,
+                    other_data_internal_index_in_section: 0
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_initializers() {
+        let image_binary = build_shared_module_scaffold(
+            "foo",
+            &[
+                ("init", TypeEntry::new(vec![], vec![])),
+                ("fini", TypeEntry::new(vec![OperandDataType::I32], vec![])),
+            ],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry.function_name_entries[1].visibility = Visibility::Private;
+        image_common_entry.initializer_entries = vec![
+            InitializerEntry::new(InitializerType::Constructor, 0),
+            InitializerEntry::new(InitializerType::Finalizer, 1),
+            InitializerEntry::new(InitializerType::Constructor, 2),
+        ];
+
+        let mut image_binary: Vec<u8> = vec![];
+        write_object_file(&image_common_entry, true, &mut image_binary).unwrap();
+        let image = ModuleImage::read(&image_binary).unwrap();
+
+        assert_eq!(
+            read_object_file(&image_binary).unwrap().initializer_entries,
+            image_common_entry.initializer_entries
+        );
+
+        assert_eq!(
+            validate_initializers(&image),
+            vec![
+                ValidationError::new(
+                    1,
+                    None,
+                    ValidationErrorType::InitializerNotPublic {
+                        initializer_type: InitializerType::Finalizer
+                    }
+                ),
+                ValidationError::new(
+                    1,
+                    None,
+                    ValidationErrorType::InitializerSignatureMismatch {
+                        initializer_type: InitializerType::Finalizer
+                    }
+                ),
+                ValidationError::from_error_type(ValidationErrorType::InitializerTargetNotFound {
+                    initializer_type: InitializerType::Constructor,
+                    function_internal_index: 2,
+                    function_count: 2
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validation_error_to_diagnostic() {
+        let code = BytecodeWriterHelper::new()
+            .append_opcode(Opcode::nop) // 0x0000
+            .append_opcode_i16_i32(Opcode::local_load_i32_u, 0, 3) // 0x0004, out of range
+            .append_opcode(Opcode::end) // 0x000c
+            .to_bytes();
+
+        let (type_items, types_data) =
+            TypeSection::convert_from_entries(&[TypeEntry::new(vec![], vec![])]);
+        let type_section = TypeSection {
+            items: &type_items,
+            types_data: &types_data,
+        };
+
+        let (local_variable_lists, local_variable_list_data) =
+            LocalVariableSection::convert_from_entries(&[LocalVariableListEntry::new(vec![])]);
+        let local_variable_section = LocalVariableSection {
+            lists: &local_variable_lists,
+            list_data: &local_variable_list_data,
+        };
+
+        let (function_items, codes_data) = FunctionSection::convert_from_entries(&[
+            FunctionEntry::new(
+                0,
+                0,
+                BytecodeWriterHelper::new()
+                    .append_opcode(Opcode::nop)
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+            ),
+            FunctionEntry::new(0, 0, code),
+        ]);
+        let function_section = FunctionSection {
+            items: &function_items,
+            codes_data: &codes_data,
+        };
+
+        let section_entries: Vec<&dyn SectionEntry> =
+            vec![&type_section, &local_variable_section, &function_section];
+        let (section_items, sections_data) =
+            ModuleImage::convert_from_section_entries(&section_entries).unwrap();
+        let image = ModuleImage::new(ImageType::ObjectFile, &section_items, &sections_data);
+
+        let errors = validate_strict(&image);
+        assert_eq!(errors.len(), 1);
+
+        // section header: 8 bytes
+        // table: 2 * 16 bytes
+        // data: function 0 (4 bytes), function 1
+        let diagnostic = errors[0].to_diagnostic(&image);
+        assert_eq!(diagnostic.section_id, Some(ModuleSectionId::Function));
+        assert_eq!(diagnostic.byte_range, Some(0x30..0x38));
+        assert_eq!(diagnostic.related[0].byte_range, 0x18..0x28);
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "\
+error[invalid_index]: Function 1, instruction 0x0004: The local variable index 3 (layers 0) is out of range, the list has 0 variables.
+  --> Function 0x0030..0x0038
+  note: The function is defined here.
+  --> Function 0x0018..0x0028"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// The version ranges of the imported modules.
+//
+// An import module entry of an object file can request a version range
+// (see `ImportModuleEntry::version_range`), and the index generator checks that
+// the version of the resolved module (see `ModuleLocationShare::version`)
+// satisfies it, see `check_import_version_ranges`.
+//
+// A version range is a comma-separated list of comparators, all comparators
+// must be satisfied, e.g. ">=1.2, <2.0". Each comparator is an operator followed
+// by a version "major[.minor[.patch]]", the omitted parts are `0`. The operators are:
+//
+// - `=`: equals the version, e.g. "=1.2.3".
+// - `>`, `>=`, `<`, `<=`: compares with the version.
+// - `^`: compatible with the version, i.e., the leftmost non-zero part is unchanged,
+//   e.g. "^1.2" means ">=1.2.0, <2.0.0", and "^0.2" means ">=0.2.0, <0.3.0".
+// - `~`: the major and minor versions are unchanged, e.g. "~1.2" means ">=1.2.0, <1.3.0".
+//
+// The operator `^` is used when the operator is omitted, e.g. "1.2" equals to "^1.2".
+
+use crate::entry::{ImportModuleEntry, LinkingModuleEntry, ModuleLocation};
+
+// The versions are `(major, minor, patch)`.
+type Version = (u16, u16, u16);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Comparator {
+    Equal(Version),
+    Greater(Version),
+    GreaterOrEqual(Version),
+    Less(Version),
+    LessOrEqual(Version),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct VersionRange {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionRange {
+    /// Parses the version range text, returns `None` if it is malformed.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut comparators = vec![];
+
+        for part in text.split(',') {
+            let part = part.trim();
+            let (operator, version_text) = match part.find(|c: char| c.is_ascii_digit()) {
+                Some(pos) => part.split_at(pos),
+                None => return None,
+            };
+
+            let version = parse_version(version_text)?;
+            match operator.trim() {
+                "=" => comparators.push(Comparator::Equal(version)),
+                ">" => comparators.push(Comparator::Greater(version)),
+                ">=" => comparators.push(Comparator::GreaterOrEqual(version)),
+                "<" => comparators.push(Comparator::Less(version)),
+                "<=" => comparators.push(Comparator::LessOrEqual(version)),
+                "^" | "" => {
+                    let upper = match version {
+                        (0, 0, patch) => (0, 0, patch.checked_add(1)?),
+                        (0, minor, _) => (0, minor.checked_add(1)?, 0),
+                        (major, _, _) => (major.checked_add(1)?, 0, 0),
+                    };
+                    comparators.push(Comparator::GreaterOrEqual(version));
+                    comparators.push(Comparator::Less(upper));
+                }
+                "~" => {
+                    comparators.push(Comparator::GreaterOrEqual(version));
+                    comparators.push(Comparator::Less((version.0, version.1.checked_add(1)?, 0)));
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self { comparators })
+    }
+
+    /// Returns `true` if the version satisfies all comparators.
+    pub fn matches(&self, version: (u16, u16, u16)) -> bool {
+        self.comparators.iter().all(|comparator| match *comparator {
+            Comparator::Equal(v) => version == v,
+            Comparator::Greater(v) => version > v,
+            Comparator::GreaterOrEqual(v) => version >= v,
+            Comparator::Less(v) => version < v,
+            Comparator::LessOrEqual(v) => version <= v,
+        })
+    }
+}
+
+/// Parses the version text "major[.minor[.patch]]", the omitted parts are `0`.
+pub fn parse_version(text: &str) -> Option<(u16, u16, u16)> {
+    let parts = text
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u16>().ok())
+        .collect::<Option<Vec<u16>>>()?;
+
+    match parts.as_slice() {
+        [major] => Some((*major, 0, 0)),
+        [major, minor] => Some((*major, *minor, 0)),
+        [major, minor, patch] => Some((*major, *minor, *patch)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportVersionViolationType {
+    // The version range text is malformed.
+    InvalidRange,
+
+    // There is no linking module with the name of the import.
+    Unresolved,
+
+    // The version of the resolved module is malformed or does not satisfy the range,
+    // the data is the version of the resolved module.
+    Mismatch(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImportVersionViolation {
+    // The index of the import module entry within the importing module.
+    pub import_module_index: usize,
+    pub module_name: String,
+    pub version_range: String,
+    pub violation_type: ImportVersionViolationType,
+}
+
+/// Checks the version ranges of the import module entries of a module
+/// against the resolved linking modules, returns the violations per import module entry.
+///
+/// Only the shared modules have versions, the imports resolved to other
+/// locations (e.g., local and remote modules, which are pinned by hashes) are skipped.
+pub fn check_import_version_ranges(
+    import_module_entries: &[ImportModuleEntry],
+    linking_module_entries: &[LinkingModuleEntry],
+) -> Vec<ImportVersionViolation> {
+    let mut violations = vec![];
+
+    for (import_module_index, import_module_entry) in import_module_entries.iter().enumerate() {
+        let Some(version_range_text) = &import_module_entry.version_range else {
+            continue;
+        };
+
+        let violation_type = check_version_range(
+            &import_module_entry.name,
+            version_range_text,
+            linking_module_entries,
+        );
+
+        if let Some(violation_type) = violation_type {
+            violations.push(ImportVersionViolation {
+                import_module_index,
+                module_name: import_module_entry.name.clone(),
+                version_range: version_range_text.clone(),
+                violation_type,
+            });
+        }
+    }
+
+    violations
+}
+
+fn check_version_range(
+    module_name: &str,
+    version_range_text: &str,
+    linking_module_entries: &[LinkingModuleEntry],
+) -> Option<ImportVersionViolationType> {
+    let Some(version_range) = VersionRange::parse(version_range_text) else {
+        return Some(ImportVersionViolationType::InvalidRange);
+    };
+
+    let Some(linking_module_entry) = linking_module_entries
+        .iter()
+        .find(|entry| entry.name == module_name)
+    else {
+        return Some(ImportVersionViolationType::Unresolved);
+    };
+
+    let ModuleLocation::Share(share) = linking_module_entry.module_location.as_ref() else {
+        return None;
+    };
+
+    match parse_version(&share.version) {
+        Some(version) if version_range.matches(version) => None,
+        _ => Some(ImportVersionViolationType::Mismatch(share.version.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_isa::{DependencyCondition, DependencyLocal, ModuleDependency};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        entry::{ImportModuleEntry, LinkingModuleEntry, ModuleLocation, ModuleLocationShare},
+        version_range::{
+            check_import_version_ranges, parse_version, ImportVersionViolation,
+            ImportVersionViolationType, VersionRange,
+        },
+    };
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1"), Some((1, 0, 0)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("1.x"), None);
+    }
+
+    #[test]
+    fn test_version_range() {
+        let matches = |text: &str, version: (u16, u16, u16)| {
+            VersionRange::parse(text).unwrap().matches(version)
+        };
+
+        assert!(matches("=1.2.3", (1, 2, 3)));
+        assert!(!matches("=1.2.3", (1, 2, 4)));
+
+        assert!(matches(">=1.2, <2", (1, 9, 0)));
+        assert!(!matches(">=1.2, <2", (2, 0, 0)));
+        assert!(!matches(">1.2", (1, 2, 0)));
+        assert!(matches("<=1.2", (1, 2, 0)));
+
+        assert!(matches("^1.2", (1, 3, 0)));
+        assert!(!matches("^1.2", (1, 1, 0)));
+        assert!(!matches("^1.2", (2, 0, 0)));
+        assert!(matches("^0.2", (0, 2, 5)));
+        assert!(!matches("^0.2", (0, 3, 0)));
+        assert!(matches("1.2", (1, 5, 0)));
+
+        assert!(matches("~1.2", (1, 2, 9)));
+        assert!(!matches("~1.2", (1, 3, 0)));
+
+        assert_eq!(VersionRange::parse("!1.0"), None);
+        assert_eq!(VersionRange::parse(">=1.0,"), None);
+        assert_eq!(VersionRange::parse("latest"), None);
+    }
+
+    #[test]
+    fn test_check_import_version_ranges() {
+        let build_import_module_entry = |name: &str, version_range: &str| {
+            ImportModuleEntry::new(
+                name.to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: name.to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            )
+            .with_version_range(version_range.to_owned())
+        };
+
+        let build_linking_module_entry = |name: &str, version: &str| {
+            LinkingModuleEntry::new(
+                name.to_owned(),
+                Box::new(ModuleLocation::Share(Box::new(ModuleLocationShare {
+                    version: version.to_owned(),
+                    hash: "01234567".to_owned(),
+                }))),
+            )
+        };
+
+        let import_module_entries = vec![
+            ImportModuleEntry::self_reference_entry(),
+            build_import_module_entry("math", "^1.2"),
+            build_import_module_entry("http", ">=2.0, <3.0"),
+            build_import_module_entry("json", "latest"),
+            build_import_module_entry("xml", "^1.0"),
+        ];
+
+        let linking_module_entries = vec![
+            LinkingModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            build_linking_module_entry("math", "1.4.0"),
+            build_linking_module_entry("http", "3.1.0"),
+            build_linking_module_entry("json", "1.0.0"),
+        ];
+
+        assert_eq!(
+            check_import_version_ranges(&import_module_entries, &linking_module_entries),
+            vec![
+                ImportVersionViolation {
+                    import_module_index: 2,
+                    module_name: "http".to_owned(),
+                    version_range: ">=2.0, <3.0".to_owned(),
+                    violation_type: ImportVersionViolationType::Mismatch("3.1.0".to_owned()),
+                },
+                ImportVersionViolation {
+                    import_module_index: 3,
+                    module_name: "json".to_owned(),
+                    version_range: "latest".to_owned(),
+                    violation_type: ImportVersionViolationType::InvalidRange,
+                },
+                ImportVersionViolation {
+                    import_module_index: 4,
+                    module_name: "xml".to_owned(),
+                    version_range: "^1.0".to_owned(),
+                    violation_type: ImportVersionViolationType::Unresolved,
+                },
+            ]
+        );
+    }
+}
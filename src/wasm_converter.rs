@@ -0,0 +1,456 @@
+// Copyright (c) 2025 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions.
+// For more details, see the LICENSE, LICENSE.additional, and CONTRIBUTING files.
+
+// Converts a module into a WebAssembly module, so that the existing
+// WebAssembly tools can be used to inspect the structure of the module.
+//
+// Only the overlapping subset is converted:
+//
+// | XiaoXuan Core                | WebAssembly                                |
+// |------------------------------|--------------------------------------------|
+// | Type                         | Type (`func`)                              |
+// | Function                     | Function and Code (locals only, see below) |
+// | Import function              | Import (`func`), module = import module    |
+// | External function            | Import (`func`), module = external library |
+// | Read-only/read-write data    | Memory and active Data segments            |
+// | Uninitialized data           | Memory (the space is reserved)             |
+// | Exported function name       | Export (`func`)                            |
+//
+// The function public index is the same as the WebAssembly function index
+// when there is no external function, because both of them count
+// the imported functions first.
+//
+// The following constructs are not translatable, they are reported in
+// `WasmConversion::untranslated`:
+//
+// - The bytecode, since the instruction sets are different, the body of each
+//   function is replaced with `unreachable`, the local variables are kept.
+// - Imported data, WebAssembly can not import an individual data item.
+// - Data names, data items have no counterpart in WebAssembly exports.
+//
+// The data items are placed in the linear memory one after another (read-only,
+// read-write and then uninitialized), with their alignments respected.
+//
+// Reference: https://webassembly.github.io/spec/core/binary/index.html
+
+use anc_isa::OperandDataType;
+
+use crate::entry::ImageCommonEntry;
+
+const WASM_MAGIC_NUMBER: &[u8; 4] = b"\0asm";
+const WASM_VERSION: u32 = 1;
+const WASM_PAGE_SIZE: usize = 65536;
+
+const WASM_SECTION_ID_TYPE: u8 = 1;
+const WASM_SECTION_ID_IMPORT: u8 = 2;
+const WASM_SECTION_ID_FUNCTION: u8 = 3;
+const WASM_SECTION_ID_MEMORY: u8 = 5;
+const WASM_SECTION_ID_EXPORT: u8 = 7;
+const WASM_SECTION_ID_CODE: u8 = 10;
+const WASM_SECTION_ID_DATA: u8 = 11;
+
+const WASM_FUNCTION_TYPE: u8 = 0x60;
+const WASM_EXTERNAL_KIND_FUNCTION: u8 = 0x00;
+
+const WASM_OPCODE_UNREACHABLE: u8 = 0x00;
+const WASM_OPCODE_END: u8 = 0x0b;
+const WASM_OPCODE_I32_CONST: u8 = 0x41;
+
+#[derive(Debug, PartialEq)]
+pub struct WasmConversion {
+    pub wasm_binary: Vec<u8>,
+
+    // The descriptions of the constructs which are not translated.
+    pub untranslated: Vec<String>,
+}
+
+/// Converts the module into a WebAssembly module (best-effort).
+pub fn convert_to_wasm(image_common_entry: &ImageCommonEntry) -> WasmConversion {
+    let mut untranslated: Vec<String> = vec![];
+    let mut wasm_binary: Vec<u8> = vec![];
+
+    wasm_binary.extend_from_slice(WASM_MAGIC_NUMBER);
+    wasm_binary.extend_from_slice(&WASM_VERSION.to_le_bytes());
+
+    // Type section
+    let mut type_section: Vec<u8> = vec![];
+    write_uleb128(
+        &mut type_section,
+        image_common_entry.type_entries.len() as u64,
+    );
+    for type_entry in &image_common_entry.type_entries {
+        type_section.push(WASM_FUNCTION_TYPE);
+        write_value_types(&mut type_section, &type_entry.params);
+        write_value_types(&mut type_section, &type_entry.results);
+    }
+    write_section(&mut wasm_binary, WASM_SECTION_ID_TYPE, &type_section);
+
+    // Import section
+    let import_count = image_common_entry.import_function_entries.len()
+        + image_common_entry.external_function_entries.len();
+    if import_count > 0 {
+        let mut import_section: Vec<u8> = vec![];
+        write_uleb128(&mut import_section, import_count as u64);
+
+        for import_function_entry in &image_common_entry.import_function_entries {
+            let import_module_entry = &image_common_entry.import_module_entries
+                [import_function_entry.import_module_index];
+            write_name(&mut import_section, &import_module_entry.name);
+            write_name(&mut import_section, &import_function_entry.full_name);
+            import_section.push(WASM_EXTERNAL_KIND_FUNCTION);
+            write_uleb128(&mut import_section, import_function_entry.type_index as u64);
+        }
+
+        for external_function_entry in &image_common_entry.external_function_entries {
+            let external_library_entry = &image_common_entry.external_library_entries
+                [external_function_entry.external_library_index];
+            write_name(&mut import_section, &external_library_entry.name);
+            write_name(&mut import_section, &external_function_entry.name);
+            import_section.push(WASM_EXTERNAL_KIND_FUNCTION);
+            write_uleb128(
+                &mut import_section,
+                external_function_entry.type_index as u64,
+            );
+        }
+
+        write_section(&mut wasm_binary, WASM_SECTION_ID_IMPORT, &import_section);
+    }
+
+    for import_data_entry in &image_common_entry.import_data_entries {
+        untranslated.push(format!(
+            "Imported data \"{}\" is not translated.",
+            import_data_entry.full_name
+        ));
+    }
+
+    // Function section
+    let mut function_section: Vec<u8> = vec![];
+    write_uleb128(
+        &mut function_section,
+        image_common_entry.function_entries.len() as u64,
+    );
+    for function_entry in &image_common_entry.function_entries {
+        write_uleb128(&mut function_section, function_entry.type_index as u64);
+    }
+    write_section(
+        &mut wasm_binary,
+        WASM_SECTION_ID_FUNCTION,
+        &function_section,
+    );
+
+    // Memory and data segments
+    let (data_segments, memory_length) = layout_data(image_common_entry);
+    if memory_length > 0 {
+        let page_count = memory_length.div_ceil(WASM_PAGE_SIZE);
+        let mut memory_section: Vec<u8> = vec![];
+        write_uleb128(&mut memory_section, 1);
+        memory_section.push(0x00); // limits without maximum
+        write_uleb128(&mut memory_section, page_count as u64);
+        write_section(&mut wasm_binary, WASM_SECTION_ID_MEMORY, &memory_section);
+    }
+
+    // Export section
+    // The WebAssembly function index counts all imports first.
+    let imported_function_count = import_count;
+    let export_function_entries = image_common_entry
+        .function_name_entries
+        .iter()
+        .filter(|entry| entry.visibility.is_exported())
+        .collect::<Vec<_>>();
+    if !export_function_entries.is_empty() {
+        let mut export_section: Vec<u8> = vec![];
+        write_uleb128(&mut export_section, export_function_entries.len() as u64);
+        for function_name_entry in export_function_entries {
+            write_name(&mut export_section, &function_name_entry.full_name);
+            export_section.push(WASM_EXTERNAL_KIND_FUNCTION);
+            let function_index = imported_function_count + function_name_entry.internal_index;
+            write_uleb128(&mut export_section, function_index as u64);
+        }
+        write_section(&mut wasm_binary, WASM_SECTION_ID_EXPORT, &export_section);
+    }
+
+    for data_name_entry in &image_common_entry.data_data_entries {
+        untranslated.push(format!(
+            "Data name \"{}\" is not translated.",
+            data_name_entry.full_name
+        ));
+    }
+
+    // Code section
+    let mut code_section: Vec<u8> = vec![];
+    write_uleb128(
+        &mut code_section,
+        image_common_entry.function_entries.len() as u64,
+    );
+    for (function_internal_index, function_entry) in
+        image_common_entry.function_entries.iter().enumerate()
+    {
+        // The local variable list includes the arguments,
+        // but the WebAssembly locals do not.
+        let param_count = image_common_entry.type_entries[function_entry.type_index]
+            .params
+            .len();
+        let local_variable_types = &image_common_entry.local_variable_list_entries
+            [function_entry.local_variable_list_index]
+            .local_variable_types;
+        let local_types = local_variable_types.get(param_count..).unwrap_or_default();
+
+        let mut body: Vec<u8> = vec![];
+        write_uleb128(&mut body, local_types.len() as u64);
+        for local_type in local_types {
+            write_uleb128(&mut body, 1);
+            body.push(get_value_type(*local_type));
+        }
+        body.push(WASM_OPCODE_UNREACHABLE);
+        body.push(WASM_OPCODE_END);
+
+        write_uleb128(&mut code_section, body.len() as u64);
+        code_section.extend_from_slice(&body);
+
+        untranslated.push(format!(
+            "The bytecode of function {} is not translated.",
+            function_internal_index
+        ));
+    }
+    write_section(&mut wasm_binary, WASM_SECTION_ID_CODE, &code_section);
+
+    // Data section
+    if !data_segments.is_empty() {
+        let mut data_section: Vec<u8> = vec![];
+        write_uleb128(&mut data_section, data_segments.len() as u64);
+        for (offset, data) in data_segments {
+            write_uleb128(&mut data_section, 0); // active segment of memory 0
+            data_section.push(WASM_OPCODE_I32_CONST);
+            write_sleb128(&mut data_section, offset as i64);
+            data_section.push(WASM_OPCODE_END);
+            write_uleb128(&mut data_section, data.len() as u64);
+            data_section.extend_from_slice(data);
+        }
+        write_section(&mut wasm_binary, WASM_SECTION_ID_DATA, &data_section);
+    }
+
+    WasmConversion {
+        wasm_binary,
+        untranslated,
+    }
+}
+
+// Returns the data segments `(offset, data)` and the total length of the memory.
+fn layout_data(image_common_entry: &ImageCommonEntry) -> (Vec<(usize, &[u8])>, usize) {
+    let mut data_segments: Vec<(usize, &[u8])> = vec![];
+    let mut next_offset: usize = 0;
+
+    let mut place = |length: u32, align: u16| {
+        let align = (align as usize).max(1);
+        let offset = next_offset.div_ceil(align) * align;
+        next_offset = offset + length as usize;
+        offset
+    };
+
+    for entry in &image_common_entry.read_only_data_entries {
+        let offset = place(entry.length, entry.align);
+        data_segments.push((offset, entry.data.as_slice()));
+    }
+
+    for entry in &image_common_entry.read_write_data_entries {
+        let offset = place(entry.length, entry.align);
+        data_segments.push((offset, entry.data.as_slice()));
+    }
+
+    for entry in &image_common_entry.uninit_data_entries {
+        place(entry.length, entry.align);
+    }
+
+    (data_segments, next_offset)
+}
+
+fn get_value_type(operand_data_type: OperandDataType) -> u8 {
+    match operand_data_type {
+        OperandDataType::I32 => 0x7f,
+        OperandDataType::I64 => 0x7e,
+        OperandDataType::F32 => 0x7d,
+        OperandDataType::F64 => 0x7c,
+    }
+}
+
+fn write_value_types(buffer: &mut Vec<u8>, operand_data_types: &[OperandDataType]) {
+    write_uleb128(buffer, operand_data_types.len() as u64);
+    buffer.extend(operand_data_types.iter().map(|t| get_value_type(*t)));
+}
+
+fn write_name(buffer: &mut Vec<u8>, name: &str) {
+    write_uleb128(buffer, name.len() as u64);
+    buffer.extend_from_slice(name.as_bytes());
+}
+
+fn write_section(buffer: &mut Vec<u8>, section_id: u8, section_data: &[u8]) {
+    buffer.push(section_id);
+    write_uleb128(buffer, section_data.len() as u64);
+    buffer.extend_from_slice(section_data);
+}
+
+fn write_uleb128(buffer: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            break;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(buffer: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let is_done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if is_done {
+            buffer.push(byte);
+            break;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_isa::{opcode::Opcode, OperandDataType};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        bytecode_writer::BytecodeWriterHelper,
+        entry::{FunctionNameEntry, ReadOnlyDataEntry},
+        entry_reader::read_object_file,
+        entry_writer::{build_minimal_module, MinimalFunctionEntry},
+        module_image::Visibility,
+        wasm_converter::{convert_to_wasm, write_sleb128, write_uleb128},
+    };
+
+    #[test]
+    fn test_leb128() {
+        let mut buffer: Vec<u8> = vec![];
+        write_uleb128(&mut buffer, 624485);
+        assert_eq!(buffer, vec![0xe5, 0x8e, 0x26]);
+
+        let mut buffer: Vec<u8> = vec![];
+        write_sleb128(&mut buffer, -123456);
+        assert_eq!(buffer, vec![0xc0, 0xbb, 0x78]);
+
+        let mut buffer: Vec<u8> = vec![];
+        write_sleb128(&mut buffer, 64);
+        assert_eq!(buffer, vec![0xc0, 0x00]);
+    }
+
+    #[test]
+    fn test_convert_to_wasm() {
+        let image_binary = build_minimal_module(
+            "foo",
+            &[MinimalFunctionEntry {
+                params: vec![OperandDataType::I32],
+                results: vec![OperandDataType::I64],
+                local_variable_types_without_args: vec![OperandDataType::F32],
+                code: BytecodeWriterHelper::new()
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+            }],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry
+            .function_name_entries
+            .push(FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Public,
+                0,
+            ));
+        image_common_entry
+            .read_only_data_entries
+            .push(ReadOnlyDataEntry::from_i32(0x11));
+
+        let conversion = convert_to_wasm(&image_common_entry);
+
+        assert_eq!(
+            conversion.wasm_binary,
+            vec![
+                0x00, 0x61, 0x73, 0x6d, // magic
+                0x01, 0x00, 0x00, 0x00, // version
+                //
+                0x01, 0x06, // type section
+                0x01, // type count
+                0x60, 0x01, 0x7f, 0x01, 0x7e, // (i32) -> (i64)
+                //
+                0x03, 0x02, // function section
+                0x01, 0x00, // function 0 -> type 0
+                //
+                0x05, 0x03, // memory section
+                0x01, 0x00, 0x01, // 1 memory, min 1 page
+                //
+                0x07, 0x0c, // export section
+                0x01, // export count
+                0x08, b'f', b'o', b'o', b':', b':', b'b', b'a', b'r', // name
+                0x00, 0x00, // function 0
+                //
+                0x0a, 0x07, // code section
+                0x01, // function count
+                0x05, // body size
+                0x01, 0x01, 0x7d, // 1 local f32
+                0x00, 0x0b, // unreachable, end
+                //
+                0x0b, 0x0a, // data section
+                0x01, // segment count
+                0x00, 0x41, 0x00, 0x0b, // memory 0, offset (i32.const 0)
+                0x04, 0x11, 0x00, 0x00, 0x00, // data
+            ]
+        );
+
+        assert_eq!(
+            conversion.untranslated,
+            vec!["The bytecode of function 0 is not translated.".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_convert_to_wasm_with_package_function() {
+        let image_binary = build_minimal_module(
+            "foo",
+            &[MinimalFunctionEntry {
+                params: vec![],
+                results: vec![],
+                local_variable_types_without_args: vec![],
+                code: BytecodeWriterHelper::new()
+                    .append_opcode(Opcode::end)
+                    .to_bytes(),
+            }],
+        )
+        .unwrap();
+
+        let mut image_common_entry = read_object_file(&image_binary).unwrap();
+        image_common_entry
+            .function_name_entries
+            .push(FunctionNameEntry::new(
+                "foo::bar".to_owned(),
+                Visibility::Package,
+                0,
+            ));
+
+        let conversion = convert_to_wasm(&image_common_entry);
+
+        // the package-private function is exported as well
+        let export_section = [
+            0x07, 0x0c, // export section
+            0x01, // export count
+            0x08, b'f', b'o', b'o', b':', b':', b'b', b'a', b'r', // name
+            0x00, 0x00, // function 0
+        ];
+        assert!(conversion
+            .wasm_binary
+            .windows(export_section.len())
+            .any(|window| window == export_section));
+    }
+}